@@ -0,0 +1,421 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+
+use crate::error::ContractError;
+use crate::state::{
+    next_outbound_nonce, read_config, read_inbound_transfer, read_inbound_window,
+    read_outbound_window, store_config, store_inbound_transfer, store_inbound_window,
+    store_outbound_window, Config, InboundClaim, InboundTransfer, RateWindow,
+};
+
+use cosmwasm_std::{
+    from_binary, to_binary, Binary, CanonicalAddr, CosmosMsg, Deps, DepsMut, Env, MessageInfo,
+    Response, StdError, StdResult, Uint128, WasmMsg,
+};
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use shard_token::bridge::{
+    ConfigResponse, Cw20HookMsg, ExecuteMsg, InboundClaimResponse, InboundTransferResponse,
+    InstantiateMsg, MigrateMsg, QueryMsg, RateLimit, RateLimitUsage, RateLimitsResponse,
+};
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    let relayers = canonicalize_relayers(deps.as_ref(), &msg.relayers)?;
+    validate_relayers(&relayers, msg.threshold)?;
+    store_config(
+        deps.storage,
+        &Config {
+            shard_token: deps.api.addr_canonicalize(&msg.shard_token)?,
+            admin: deps.api.addr_canonicalize(&msg.admin)?,
+            relayers,
+            threshold: msg.threshold,
+            inbound_limit: msg.inbound_limit,
+            outbound_limit: msg.outbound_limit,
+            paused: false,
+        },
+    )?;
+
+    Ok(Response::default())
+}
+
+fn canonicalize_relayers(deps: Deps, relayers: &[String]) -> StdResult<Vec<CanonicalAddr>> {
+    relayers
+        .iter()
+        .map(|relayer| deps.api.addr_canonicalize(relayer))
+        .collect()
+}
+
+fn validate_relayers(relayers: &[CanonicalAddr], threshold: u32) -> Result<(), ContractError> {
+    let duplicate = relayers
+        .iter()
+        .enumerate()
+        .any(|(i, relayer)| relayers[i + 1..].contains(relayer));
+    if duplicate || threshold == 0 || threshold as usize > relayers.len() {
+        return Err(ContractError::InvalidRelayers {});
+    }
+    Ok(())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
+        ExecuteMsg::AttestInbound {
+            source_chain,
+            transfer_id,
+            recipient,
+            amount,
+        } => attest_inbound(
+            deps,
+            env,
+            info,
+            source_chain,
+            transfer_id,
+            recipient,
+            amount,
+        ),
+        ExecuteMsg::SetPaused { paused } => set_paused(deps, info, paused),
+        ExecuteMsg::UpdateConfig {
+            admin,
+            relayers,
+            threshold,
+            inbound_limit,
+            outbound_limit,
+        } => update_config(
+            deps,
+            info,
+            admin,
+            relayers,
+            threshold,
+            inbound_limit,
+            outbound_limit,
+        ),
+    }
+}
+
+pub fn receive_cw20(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    cw20_msg: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    let config: Config = read_config(deps.storage)?;
+    // only the shard token contract can execute this message
+    if config.shard_token != deps.api.addr_canonicalize(info.sender.as_str())? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    match from_binary(&cw20_msg.msg)? {
+        Cw20HookMsg::BridgeOut {
+            destination_chain,
+            destination_address,
+        } => bridge_out(
+            deps,
+            env,
+            config,
+            cw20_msg.sender,
+            cw20_msg.amount,
+            destination_chain,
+            destination_address,
+        ),
+    }
+}
+
+pub fn bridge_out(
+    deps: DepsMut,
+    env: Env,
+    config: Config,
+    sender: String,
+    amount: Uint128,
+    destination_chain: String,
+    destination_address: String,
+) -> Result<Response, ContractError> {
+    if config.paused {
+        return Err(ContractError::Paused {});
+    }
+    if amount.is_zero() {
+        return Err(ContractError::NoFunds {});
+    }
+    if destination_chain.is_empty() || destination_address.is_empty() {
+        return Err(ContractError::InvalidDestination {});
+    }
+    let window = consume_rate_limit(
+        read_outbound_window(deps.storage)?,
+        &config.outbound_limit,
+        env.block.time.seconds(),
+        amount,
+    )?;
+    store_outbound_window(deps.storage, &window)?;
+    let nonce = next_outbound_nonce(deps.storage)?;
+
+    Ok(Response::new()
+        .add_message(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: deps.api.addr_humanize(&config.shard_token)?.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Burn { amount })?,
+            funds: vec![],
+        }))
+        .add_attributes(vec![
+            ("action", "bridge_out"),
+            ("nonce", &nonce.to_string()),
+            ("sender", &sender),
+            ("destination_chain", &destination_chain),
+            ("destination_address", &destination_address),
+            ("amount", &amount.to_string()),
+        ]))
+}
+
+pub fn attest_inbound(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    source_chain: String,
+    transfer_id: String,
+    recipient: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let config: Config = read_config(deps.storage)?;
+    let relayer = deps.api.addr_canonicalize(info.sender.as_str())?;
+    if !config.relayers.contains(&relayer) {
+        return Err(ContractError::Unauthorized {});
+    }
+    if config.paused {
+        return Err(ContractError::Paused {});
+    }
+    if amount.is_zero() {
+        return Err(ContractError::NoFunds {});
+    }
+
+    let recipient_raw = deps.api.addr_canonicalize(&recipient)?;
+    let mut transfer = read_inbound_transfer(deps.storage, &source_chain, &transfer_id)?.unwrap_or(
+        InboundTransfer {
+            claims: vec![],
+            minted: false,
+        },
+    );
+    if transfer.minted {
+        return Err(ContractError::AlreadyMinted {});
+    }
+    // each relayer backs a single claim, and a compromised relayer attesting a
+    // wrong one first cannot keep the others from agreeing on the right one
+    if transfer
+        .claims
+        .iter()
+        .any(|claim| claim.attestations.contains(&relayer))
+    {
+        return Err(ContractError::AlreadyAttested {});
+    }
+    let hash = InboundClaim::hash(&recipient_raw, amount);
+    let index = match transfer.claims.iter().position(|claim| claim.hash == hash) {
+        Some(index) => index,
+        None => {
+            transfer.claims.push(InboundClaim {
+                hash: hash.clone(),
+                recipient: recipient_raw,
+                amount,
+                attestations: vec![],
+            });
+            transfer.claims.len() - 1
+        }
+    };
+    let claim = &mut transfer.claims[index];
+    // attestations of relayers removed since then no longer count
+    claim
+        .attestations
+        .retain(|attestation| config.relayers.contains(attestation));
+    claim.attestations.push(relayer);
+
+    let mut res = Response::new();
+    if claim.attestations.len() >= config.threshold as usize {
+        let window = consume_rate_limit(
+            read_inbound_window(deps.storage)?,
+            &config.inbound_limit,
+            env.block.time.seconds(),
+            amount,
+        )?;
+        store_inbound_window(deps.storage, &window)?;
+        transfer.minted = true;
+        res = res.add_message(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: deps.api.addr_humanize(&config.shard_token)?.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Mint {
+                recipient: recipient.clone(),
+                amount,
+            })?,
+            funds: vec![],
+        }));
+    }
+    store_inbound_transfer(deps.storage, &source_chain, &transfer_id, &transfer)?;
+
+    Ok(res.add_attributes(vec![
+        ("action", "attest_inbound"),
+        ("relayer", info.sender.as_str()),
+        ("source_chain", &source_chain),
+        ("transfer_id", &transfer_id),
+        ("recipient", &recipient),
+        ("amount", &amount.to_string()),
+        ("claim", &hash.to_base64()),
+        ("minted", &transfer.minted.to_string()),
+    ]))
+}
+
+/// Adds `amount` to the current window of `limit`, failing past its maximum
+fn consume_rate_limit(
+    window: RateWindow,
+    limit: &RateLimit,
+    now: u64,
+    amount: Uint128,
+) -> Result<RateWindow, ContractError> {
+    let mut window = window.current(limit, now);
+    let remaining = window.remaining(limit);
+    if amount > remaining {
+        return Err(ContractError::RateLimitExceeded {
+            remaining: remaining.to_string(),
+        });
+    }
+    window.used += amount;
+    Ok(window)
+}
+
+pub fn set_paused(
+    deps: DepsMut,
+    info: MessageInfo,
+    paused: bool,
+) -> Result<Response, ContractError> {
+    let mut config: Config = read_config(deps.storage)?;
+    if config.admin != deps.api.addr_canonicalize(info.sender.as_str())? {
+        return Err(ContractError::Unauthorized {});
+    }
+    config.paused = paused;
+    store_config(deps.storage, &config)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "set_paused"),
+        ("paused", &paused.to_string()),
+    ]))
+}
+
+pub fn update_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    admin: Option<String>,
+    relayers: Option<Vec<String>>,
+    threshold: Option<u32>,
+    inbound_limit: Option<RateLimit>,
+    outbound_limit: Option<RateLimit>,
+) -> Result<Response, ContractError> {
+    let mut config: Config = read_config(deps.storage)?;
+    if config.admin != deps.api.addr_canonicalize(info.sender.as_str())? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if let Some(admin) = admin {
+        config.admin = deps.api.addr_canonicalize(&admin)?;
+    }
+    if let Some(relayers) = relayers {
+        config.relayers = canonicalize_relayers(deps.as_ref(), &relayers)?;
+    }
+    if let Some(threshold) = threshold {
+        config.threshold = threshold;
+    }
+    validate_relayers(&config.relayers, config.threshold)?;
+    if let Some(inbound_limit) = inbound_limit {
+        config.inbound_limit = inbound_limit;
+    }
+    if let Some(outbound_limit) = outbound_limit {
+        config.outbound_limit = outbound_limit;
+    }
+    store_config(deps.storage, &config)?;
+
+    Ok(Response::new().add_attribute("action", "update_config"))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_binary(&query_config(deps)?),
+        QueryMsg::InboundTransfer {
+            source_chain,
+            transfer_id,
+        } => to_binary(&query_inbound_transfer(deps, source_chain, transfer_id)?),
+        QueryMsg::RateLimits {} => to_binary(&query_rate_limits(deps, env)?),
+    }
+}
+
+pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
+    let config: Config = read_config(deps.storage)?;
+    Ok(ConfigResponse {
+        shard_token: deps.api.addr_humanize(&config.shard_token)?.to_string(),
+        admin: deps.api.addr_humanize(&config.admin)?.to_string(),
+        relayers: config
+            .relayers
+            .iter()
+            .map(|relayer| Ok(deps.api.addr_humanize(relayer)?.to_string()))
+            .collect::<StdResult<Vec<String>>>()?,
+        threshold: config.threshold,
+        inbound_limit: config.inbound_limit,
+        outbound_limit: config.outbound_limit,
+        paused: config.paused,
+    })
+}
+
+pub fn query_inbound_transfer(
+    deps: Deps,
+    source_chain: String,
+    transfer_id: String,
+) -> StdResult<InboundTransferResponse> {
+    let transfer = match read_inbound_transfer(deps.storage, &source_chain, &transfer_id)? {
+        Some(transfer) => transfer,
+        None => return Err(StdError::not_found("InboundTransfer")),
+    };
+    let claims = transfer
+        .claims
+        .into_iter()
+        .map(|claim| {
+            Ok(InboundClaimResponse {
+                hash: claim.hash,
+                recipient: deps.api.addr_humanize(&claim.recipient)?.to_string(),
+                amount: claim.amount,
+                attestations: claim
+                    .attestations
+                    .iter()
+                    .map(|relayer| Ok(deps.api.addr_humanize(relayer)?.to_string()))
+                    .collect::<StdResult<Vec<String>>>()?,
+            })
+        })
+        .collect::<StdResult<Vec<InboundClaimResponse>>>()?;
+    Ok(InboundTransferResponse {
+        claims,
+        minted: transfer.minted,
+    })
+}
+
+pub fn query_rate_limits(deps: Deps, env: Env) -> StdResult<RateLimitsResponse> {
+    let config: Config = read_config(deps.storage)?;
+    let now = env.block.time.seconds();
+    let usage = |window: RateWindow, limit: &RateLimit| {
+        let window = window.current(limit, now);
+        RateLimitUsage {
+            window_start: window.start,
+            used: window.used,
+            remaining: window.remaining(limit),
+        }
+    };
+    Ok(RateLimitsResponse {
+        inbound: usage(read_inbound_window(deps.storage)?, &config.inbound_limit),
+        outbound: usage(read_outbound_window(deps.storage)?, &config.outbound_limit),
+    })
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    Ok(Response::default())
+}
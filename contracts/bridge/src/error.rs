@@ -0,0 +1,32 @@
+use cosmwasm_std::StdError;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Relayers must be unique and the threshold between 1 and their number")]
+    InvalidRelayers {},
+
+    #[error("Bridge is paused")]
+    Paused {},
+
+    #[error("Amount must be greater than zero")]
+    NoFunds {},
+
+    #[error("Destination chain and address must not be empty")]
+    InvalidDestination {},
+
+    #[error("Transfer already attested by this relayer")]
+    AlreadyAttested {},
+
+    #[error("Transfer already minted")]
+    AlreadyMinted {},
+
+    #[error("Rate limit exceeded, {remaining} left in the current window")]
+    RateLimitExceeded { remaining: String },
+}
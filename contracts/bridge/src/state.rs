@@ -0,0 +1,135 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::{Binary, CanonicalAddr, StdResult, Storage, Uint128};
+use cosmwasm_storage::{singleton, singleton_read, Bucket, ReadonlyBucket};
+use sha2::{Digest, Sha256};
+use shard_token::bridge::RateLimit;
+
+static KEY_CONFIG: &[u8] = b"config";
+static KEY_INBOUND_WINDOW: &[u8] = b"inbound_window";
+static KEY_OUTBOUND_WINDOW: &[u8] = b"outbound_window";
+static KEY_OUTBOUND_NONCE: &[u8] = b"outbound_nonce";
+
+static PREFIX_INBOUND: &[u8] = b"inbound";
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Config {
+    pub shard_token: CanonicalAddr,
+    pub admin: CanonicalAddr,
+    pub relayers: Vec<CanonicalAddr>,
+    pub threshold: u32,
+    pub inbound_limit: RateLimit,
+    pub outbound_limit: RateLimit,
+    pub paused: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct RateWindow {
+    pub start: u64,
+    pub used: Uint128,
+}
+
+impl RateWindow {
+    /// The window in effect at `now`, a new one if this one is over or unused
+    pub fn current(self, limit: &RateLimit, now: u64) -> RateWindow {
+        if self.used.is_zero() || now >= self.start + limit.period {
+            RateWindow {
+                start: now,
+                used: Uint128::zero(),
+            }
+        } else {
+            self
+        }
+    }
+
+    pub fn remaining(&self, limit: &RateLimit) -> Uint128 {
+        limit.max_amount.checked_sub(self.used).unwrap_or_default()
+    }
+}
+
+/// Relayers may disagree on a transfer, each claimed recipient and amount
+/// gathers its own attestations
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InboundTransfer {
+    pub claims: Vec<InboundClaim>,
+    pub minted: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InboundClaim {
+    /// See `InboundClaim::hash`
+    pub hash: Binary,
+    pub recipient: CanonicalAddr,
+    pub amount: Uint128,
+    pub attestations: Vec<CanonicalAddr>,
+}
+
+impl InboundClaim {
+    /// Sha256 of the canonical recipient followed by the big endian amount
+    pub fn hash(recipient: &CanonicalAddr, amount: Uint128) -> Binary {
+        let mut hasher = Sha256::new();
+        hasher.update(recipient.as_slice());
+        hasher.update(amount.u128().to_be_bytes());
+        Binary::from(hasher.finalize().to_vec())
+    }
+}
+
+pub fn store_config(storage: &mut dyn Storage, config: &Config) -> StdResult<()> {
+    singleton(storage, KEY_CONFIG).save(config)
+}
+
+pub fn read_config(storage: &dyn Storage) -> StdResult<Config> {
+    singleton_read(storage, KEY_CONFIG).load()
+}
+
+pub fn store_inbound_window(storage: &mut dyn Storage, window: &RateWindow) -> StdResult<()> {
+    singleton(storage, KEY_INBOUND_WINDOW).save(window)
+}
+
+pub fn read_inbound_window(storage: &dyn Storage) -> StdResult<RateWindow> {
+    Ok(singleton_read(storage, KEY_INBOUND_WINDOW)
+        .may_load()?
+        .unwrap_or_default())
+}
+
+pub fn store_outbound_window(storage: &mut dyn Storage, window: &RateWindow) -> StdResult<()> {
+    singleton(storage, KEY_OUTBOUND_WINDOW).save(window)
+}
+
+pub fn read_outbound_window(storage: &dyn Storage) -> StdResult<RateWindow> {
+    Ok(singleton_read(storage, KEY_OUTBOUND_WINDOW)
+        .may_load()?
+        .unwrap_or_default())
+}
+
+/// Nonce for the next outbound transfer, nonces start at 1
+pub fn next_outbound_nonce(storage: &mut dyn Storage) -> StdResult<u64> {
+    let nonce = singleton_read(storage, KEY_OUTBOUND_NONCE)
+        .may_load()?
+        .unwrap_or(0u64)
+        + 1;
+    singleton(storage, KEY_OUTBOUND_NONCE).save(&nonce)?;
+    Ok(nonce)
+}
+
+pub fn store_inbound_transfer(
+    storage: &mut dyn Storage,
+    source_chain: &str,
+    transfer_id: &str,
+    transfer: &InboundTransfer,
+) -> StdResult<()> {
+    let mut inbound_bucket: Bucket<InboundTransfer> =
+        Bucket::multilevel(storage, &[PREFIX_INBOUND, source_chain.as_bytes()]);
+    inbound_bucket.save(transfer_id.as_bytes(), transfer)
+}
+
+pub fn read_inbound_transfer(
+    storage: &dyn Storage,
+    source_chain: &str,
+    transfer_id: &str,
+) -> StdResult<Option<InboundTransfer>> {
+    let inbound_bucket: ReadonlyBucket<InboundTransfer> =
+        ReadonlyBucket::multilevel(storage, &[PREFIX_INBOUND, source_chain.as_bytes()]);
+    inbound_bucket.may_load(transfer_id.as_bytes())
+}
@@ -0,0 +1,306 @@
+use crate::contract::{execute, instantiate, query};
+use crate::error::ContractError;
+use cosmwasm_std::testing::{
+    mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
+};
+use cosmwasm_std::{
+    from_binary, to_binary, CosmosMsg, Env, OwnedDeps, Response, SubMsg, Timestamp, Uint128,
+    WasmMsg,
+};
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use shard_token::bridge::{
+    ConfigResponse, Cw20HookMsg, ExecuteMsg, InboundTransferResponse, InstantiateMsg, QueryMsg,
+    RateLimit, RateLimitsResponse,
+};
+
+fn env_at(time: u64) -> Env {
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(time);
+    env
+}
+
+fn limit(max_amount: u128) -> RateLimit {
+    RateLimit {
+        period: 100,
+        max_amount: Uint128::from(max_amount),
+    }
+}
+
+fn setup() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+    let mut deps = mock_dependencies(&[]);
+    let msg = InstantiateMsg {
+        shard_token: "shard0000".to_string(),
+        admin: "admin0000".to_string(),
+        relayers: vec![
+            "relayer0000".to_string(),
+            "relayer0001".to_string(),
+            "relayer0002".to_string(),
+        ],
+        threshold: 2,
+        inbound_limit: limit(1000),
+        outbound_limit: limit(500),
+    };
+    instantiate(deps.as_mut(), env_at(0), mock_info("addr0000", &[]), msg).unwrap();
+    deps
+}
+
+fn attest(
+    deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+    relayer: &str,
+    transfer_id: &str,
+    amount: u128,
+    time: u64,
+) -> Result<Response, ContractError> {
+    execute(
+        deps.as_mut(),
+        env_at(time),
+        mock_info(relayer, &[]),
+        ExecuteMsg::AttestInbound {
+            source_chain: "ethereum".to_string(),
+            transfer_id: transfer_id.to_string(),
+            recipient: "user0000".to_string(),
+            amount: Uint128::from(amount),
+        },
+    )
+}
+
+fn bridge_out(
+    deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+    amount: u128,
+    time: u64,
+) -> Result<Response, ContractError> {
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "user0000".to_string(),
+        amount: Uint128::from(amount),
+        msg: to_binary(&Cw20HookMsg::BridgeOut {
+            destination_chain: "ethereum".to_string(),
+            destination_address: "0xabc".to_string(),
+        })
+        .unwrap(),
+    });
+    execute(
+        deps.as_mut(),
+        env_at(time),
+        mock_info("shard0000", &[]),
+        msg,
+    )
+}
+
+fn token_msg(msg: Cw20ExecuteMsg) -> SubMsg {
+    SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: "shard0000".to_string(),
+        msg: to_binary(&msg).unwrap(),
+        funds: vec![],
+    }))
+}
+
+#[test]
+fn instantiate_validates_relayers() {
+    let mut deps = mock_dependencies(&[]);
+    let msg = InstantiateMsg {
+        shard_token: "shard0000".to_string(),
+        admin: "admin0000".to_string(),
+        relayers: vec!["relayer0000".to_string()],
+        threshold: 2,
+        inbound_limit: limit(1000),
+        outbound_limit: limit(500),
+    };
+    let err = instantiate(deps.as_mut(), env_at(0), mock_info("addr0000", &[]), msg);
+    assert_eq!(err, Err(ContractError::InvalidRelayers {}));
+}
+
+#[test]
+fn inbound_transfer_mints_at_threshold() {
+    let mut deps = setup();
+    assert_eq!(
+        attest(&mut deps, "user0000", "0x01", 100, 10),
+        Err(ContractError::Unauthorized {})
+    );
+
+    let res = attest(&mut deps, "relayer0000", "0x01", 100, 10).unwrap();
+    assert!(res.messages.is_empty());
+    assert_eq!(
+        attest(&mut deps, "relayer0000", "0x01", 100, 10),
+        Err(ContractError::AlreadyAttested {})
+    );
+    let res = attest(&mut deps, "relayer0001", "0x01", 100, 10).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![token_msg(Cw20ExecuteMsg::Mint {
+            recipient: "user0000".to_string(),
+            amount: Uint128::from(100u128),
+        })]
+    );
+    assert_eq!(
+        attest(&mut deps, "relayer0002", "0x01", 100, 10),
+        Err(ContractError::AlreadyMinted {})
+    );
+
+    let res = query(
+        deps.as_ref(),
+        env_at(10),
+        QueryMsg::InboundTransfer {
+            source_chain: "ethereum".to_string(),
+            transfer_id: "0x01".to_string(),
+        },
+    )
+    .unwrap();
+    let transfer: InboundTransferResponse = from_binary(&res).unwrap();
+    assert_eq!(transfer.claims.len(), 1);
+    assert_eq!(
+        transfer.claims[0].attestations,
+        vec!["relayer0000".to_string(), "relayer0001".to_string()]
+    );
+    assert!(transfer.minted);
+}
+
+#[test]
+fn wrong_attestation_does_not_block_the_transfer() {
+    let mut deps = setup();
+    // a compromised relayer attests first with a wrong amount
+    let res = attest(&mut deps, "relayer0000", "0x01", 999, 10).unwrap();
+    assert!(res.messages.is_empty());
+    assert_eq!(
+        attest(&mut deps, "relayer0000", "0x01", 100, 10),
+        Err(ContractError::AlreadyAttested {})
+    );
+
+    let res = attest(&mut deps, "relayer0001", "0x01", 100, 10).unwrap();
+    assert!(res.messages.is_empty());
+    let res = attest(&mut deps, "relayer0002", "0x01", 100, 10).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![token_msg(Cw20ExecuteMsg::Mint {
+            recipient: "user0000".to_string(),
+            amount: Uint128::from(100u128),
+        })]
+    );
+
+    let res = query(
+        deps.as_ref(),
+        env_at(10),
+        QueryMsg::InboundTransfer {
+            source_chain: "ethereum".to_string(),
+            transfer_id: "0x01".to_string(),
+        },
+    )
+    .unwrap();
+    let transfer: InboundTransferResponse = from_binary(&res).unwrap();
+    let amounts: Vec<(u128, usize)> = transfer
+        .claims
+        .iter()
+        .map(|claim| (claim.amount.u128(), claim.attestations.len()))
+        .collect();
+    assert_eq!(amounts, vec![(999, 1), (100, 2)]);
+    assert!(transfer.minted);
+}
+
+#[test]
+fn inbound_mints_are_rate_limited() {
+    let mut deps = setup();
+    attest(&mut deps, "relayer0000", "0x01", 800, 10).unwrap();
+    attest(&mut deps, "relayer0001", "0x01", 800, 10).unwrap();
+
+    attest(&mut deps, "relayer0000", "0x02", 300, 20).unwrap();
+    assert_eq!(
+        attest(&mut deps, "relayer0001", "0x02", 300, 20),
+        Err(ContractError::RateLimitExceeded {
+            remaining: "200".to_string()
+        })
+    );
+
+    let res = query(deps.as_ref(), env_at(20), QueryMsg::RateLimits {}).unwrap();
+    let limits: RateLimitsResponse = from_binary(&res).unwrap();
+    assert_eq!(limits.inbound.window_start, 10);
+    assert_eq!(limits.inbound.used, Uint128::from(800u128));
+    assert_eq!(limits.outbound.remaining, Uint128::from(500u128));
+
+    // the attestation can be submitted again once the window is over
+    let res = attest(&mut deps, "relayer0001", "0x02", 300, 110).unwrap();
+    assert_eq!(res.messages.len(), 1);
+}
+
+#[test]
+fn bridge_out_burns_and_emits_destination() {
+    let mut deps = setup();
+    let res = bridge_out(&mut deps, 200, 10).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![token_msg(Cw20ExecuteMsg::Burn {
+            amount: Uint128::from(200u128)
+        })]
+    );
+    let attribute = |key: &str| {
+        res.attributes
+            .iter()
+            .find(|attr| attr.key == key)
+            .map(|attr| attr.value.clone())
+    };
+    assert_eq!(attribute("nonce"), Some("1".to_string()));
+    assert_eq!(attribute("destination_chain"), Some("ethereum".to_string()));
+    assert_eq!(attribute("destination_address"), Some("0xabc".to_string()));
+
+    assert_eq!(
+        bridge_out(&mut deps, 301, 20),
+        Err(ContractError::RateLimitExceeded {
+            remaining: "300".to_string()
+        })
+    );
+}
+
+#[test]
+fn pause_stops_both_directions() {
+    let mut deps = setup();
+    let err = execute(
+        deps.as_mut(),
+        env_at(0),
+        mock_info("relayer0000", &[]),
+        ExecuteMsg::SetPaused { paused: true },
+    );
+    assert_eq!(err, Err(ContractError::Unauthorized {}));
+
+    execute(
+        deps.as_mut(),
+        env_at(0),
+        mock_info("admin0000", &[]),
+        ExecuteMsg::SetPaused { paused: true },
+    )
+    .unwrap();
+    assert_eq!(
+        attest(&mut deps, "relayer0000", "0x01", 100, 10),
+        Err(ContractError::Paused {})
+    );
+    assert_eq!(
+        bridge_out(&mut deps, 100, 10),
+        Err(ContractError::Paused {})
+    );
+
+    let res = query(deps.as_ref(), env_at(0), QueryMsg::Config {}).unwrap();
+    let config: ConfigResponse = from_binary(&res).unwrap();
+    assert!(config.paused);
+}
+
+#[test]
+fn update_config_revalidates_threshold() {
+    let mut deps = setup();
+    let msg = ExecuteMsg::UpdateConfig {
+        admin: None,
+        relayers: Some(vec!["relayer0000".to_string()]),
+        threshold: None,
+        inbound_limit: None,
+        outbound_limit: None,
+    };
+    let err = execute(deps.as_mut(), env_at(0), mock_info("admin0000", &[]), msg);
+    assert_eq!(err, Err(ContractError::InvalidRelayers {}));
+
+    let msg = ExecuteMsg::UpdateConfig {
+        admin: None,
+        relayers: Some(vec!["relayer0000".to_string()]),
+        threshold: Some(1),
+        inbound_limit: None,
+        outbound_limit: None,
+    };
+    execute(deps.as_mut(), env_at(0), mock_info("admin0000", &[]), msg).unwrap();
+    let res = attest(&mut deps, "relayer0000", "0x01", 100, 10).unwrap();
+    assert_eq!(res.messages.len(), 1);
+}
@@ -0,0 +1,302 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+
+use crate::error::ContractError;
+use crate::state::{
+    is_claimed, next_round_id, read_bitmap_words, read_config, read_round, read_rounds,
+    set_claimed, store_config, store_round, Config, Round,
+};
+
+use cosmwasm_std::{
+    from_binary, to_binary, Addr, Binary, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Response,
+    StdError, StdResult, Uint128, WasmMsg,
+};
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use sha3::Digest;
+use shard_token::distributor::{
+    ClaimedBitmapResponse, ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg,
+    IsClaimedResponse, MigrateMsg, QueryMsg, RoundResponse, RoundsResponse,
+};
+use std::convert::TryInto;
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    store_config(
+        deps.storage,
+        &Config {
+            shard_token: deps.api.addr_canonicalize(&msg.shard_token)?,
+        },
+    )?;
+
+    Ok(Response::default())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
+        ExecuteMsg::Claim {
+            round,
+            index,
+            amount,
+            proof,
+        } => claim(deps, env, info, round, index, amount, proof),
+        ExecuteMsg::Clawback { round } => clawback(deps, env, info, round),
+    }
+}
+
+pub fn receive_cw20(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    cw20_msg: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    let config: Config = read_config(deps.storage)?;
+    // only the shard token contract can execute this message
+    if config.shard_token != deps.api.addr_canonicalize(info.sender.as_str())? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    match from_binary(&cw20_msg.msg)? {
+        Cw20HookMsg::CreateRound {
+            merkle_root,
+            expires_at,
+        } => create_round(
+            deps,
+            env,
+            cw20_msg.sender,
+            cw20_msg.amount,
+            merkle_root,
+            expires_at,
+        ),
+    }
+}
+
+pub fn create_round(
+    deps: DepsMut,
+    env: Env,
+    creator: String,
+    amount: Uint128,
+    merkle_root: String,
+    expires_at: Option<u64>,
+) -> Result<Response, ContractError> {
+    if amount.is_zero() {
+        return Err(ContractError::NoFunds {});
+    }
+    decode_hash(&merkle_root).map_err(|_| ContractError::InvalidHexMerkle {})?;
+    let round = Round {
+        creator: deps.api.addr_canonicalize(&creator)?,
+        merkle_root,
+        expires_at,
+        total_amount: amount,
+        claimed_amount: Uint128::zero(),
+        clawed_back: false,
+    };
+    if round.is_expired(env.block.time.seconds()) {
+        return Err(ContractError::Expired {});
+    }
+    let id = next_round_id(deps.storage)?;
+    store_round(deps.storage, id, &round)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "create_round"),
+        ("round", &id.to_string()),
+        ("creator", &creator),
+        ("merkle_root", &round.merkle_root),
+        ("amount", &amount.to_string()),
+    ]))
+}
+
+pub fn claim(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+    index: u32,
+    amount: Uint128,
+    proof: Vec<String>,
+) -> Result<Response, ContractError> {
+    let mut round = load_round(deps.as_ref(), id)?;
+    if round.clawed_back {
+        return Err(ContractError::ClawedBack {});
+    }
+    if round.is_expired(env.block.time.seconds()) {
+        return Err(ContractError::Expired {});
+    }
+    if is_claimed(deps.storage, id, index)? {
+        return Err(ContractError::AlreadyClaimed {});
+    }
+
+    let leaf = format!("{},{},{}", index, info.sender, amount);
+    let mut hash = keccak256(leaf.as_bytes());
+    for p in proof {
+        let proof_buf = decode_hash(&p).map_err(|_| ContractError::InvalidHexProof {})?;
+        hash = if hash < proof_buf {
+            keccak256(&[hash, proof_buf].concat())
+        } else {
+            keccak256(&[proof_buf, hash].concat())
+        };
+    }
+    if decode_hash(&round.merkle_root)? != hash {
+        return Err(ContractError::MerkleVerification {});
+    }
+
+    // a wrong tree must not pay out the funds of other rounds
+    round.claimed_amount = round
+        .claimed_amount
+        .checked_add(amount)
+        .map_err(StdError::from)?;
+    if round.claimed_amount > round.total_amount {
+        return Err(ContractError::InsufficientFunds {});
+    }
+    store_round(deps.storage, id, &round)?;
+    set_claimed(deps.storage, id, index)?;
+
+    Ok(Response::new()
+        .add_message(send_tokens(deps.as_ref(), &info.sender, amount)?)
+        .add_attributes(vec![
+            ("action", "claim"),
+            ("round", &id.to_string()),
+            ("index", &index.to_string()),
+            ("address", info.sender.as_str()),
+            ("amount", &amount.to_string()),
+        ]))
+}
+
+pub fn clawback(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    let mut round = load_round(deps.as_ref(), id)?;
+    if deps.api.addr_canonicalize(info.sender.as_str())? != round.creator {
+        return Err(ContractError::Unauthorized {});
+    }
+    if round.clawed_back {
+        return Err(ContractError::ClawedBack {});
+    }
+    if round.expires_at.is_none() || !round.is_expired(env.block.time.seconds()) {
+        return Err(ContractError::NotExpired {});
+    }
+
+    let amount = round.total_amount - round.claimed_amount;
+    round.clawed_back = true;
+    store_round(deps.storage, id, &round)?;
+
+    let mut res = Response::new();
+    if !amount.is_zero() {
+        res = res.add_message(send_tokens(deps.as_ref(), &info.sender, amount)?);
+    }
+    Ok(res.add_attributes(vec![
+        ("action", "clawback"),
+        ("round", &id.to_string()),
+        ("amount", &amount.to_string()),
+    ]))
+}
+
+fn keccak256(data: &[u8]) -> [u8; 32] {
+    sha3::Keccak256::digest(data)
+        .as_slice()
+        .try_into()
+        .expect("Wrong length")
+}
+
+fn decode_hash(hex_hash: &str) -> StdResult<[u8; 32]> {
+    let mut buf: [u8; 32] = [0; 32];
+    hex::decode_to_slice(hex_hash, &mut buf)
+        .map_err(|_| StdError::generic_err("Invalid hex encoded hash"))?;
+    Ok(buf)
+}
+
+fn load_round(deps: Deps, id: u64) -> StdResult<Round> {
+    match read_round(deps.storage, id)? {
+        Some(round) => Ok(round),
+        None => Err(StdError::not_found("Round")),
+    }
+}
+
+fn send_tokens(deps: Deps, recipient: &Addr, amount: Uint128) -> StdResult<CosmosMsg> {
+    let config: Config = read_config(deps.storage)?;
+    Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: deps.api.addr_humanize(&config.shard_token)?.to_string(),
+        msg: to_binary(&Cw20ExecuteMsg::Transfer {
+            recipient: recipient.to_string(),
+            amount,
+        })?,
+        funds: vec![],
+    }))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_binary(&query_config(deps)?),
+        QueryMsg::Round { round } => to_binary(&query_round(deps, round)?),
+        QueryMsg::Rounds { start_after, limit } => {
+            to_binary(&query_rounds(deps, start_after, limit)?)
+        }
+        QueryMsg::IsClaimed { round, index } => to_binary(&IsClaimedResponse {
+            is_claimed: is_claimed(deps.storage, round, index)?,
+        }),
+        QueryMsg::ClaimedBitmap {
+            round,
+            start_word,
+            limit,
+        } => to_binary(&ClaimedBitmapResponse {
+            start_word,
+            words: read_bitmap_words(deps.storage, round, start_word, limit)?,
+        }),
+    }
+}
+
+pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
+    let config: Config = read_config(deps.storage)?;
+    Ok(ConfigResponse {
+        shard_token: deps.api.addr_humanize(&config.shard_token)?.to_string(),
+    })
+}
+
+pub fn query_round(deps: Deps, id: u64) -> StdResult<RoundResponse> {
+    let round = load_round(deps, id)?;
+    round_response(deps, id, round)
+}
+
+pub fn query_rounds(
+    deps: Deps,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<RoundsResponse> {
+    let rounds = read_rounds(deps.storage, start_after, limit)?
+        .into_iter()
+        .map(|(id, round)| round_response(deps, id, round))
+        .collect::<StdResult<Vec<RoundResponse>>>()?;
+    Ok(RoundsResponse { rounds })
+}
+
+fn round_response(deps: Deps, id: u64, round: Round) -> StdResult<RoundResponse> {
+    Ok(RoundResponse {
+        round: id,
+        creator: deps.api.addr_humanize(&round.creator)?.to_string(),
+        merkle_root: round.merkle_root,
+        expires_at: round.expires_at,
+        total_amount: round.total_amount,
+        claimed_amount: round.claimed_amount,
+        clawed_back: round.clawed_back,
+    })
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    Ok(Response::default())
+}
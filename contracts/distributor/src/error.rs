@@ -0,0 +1,38 @@
+use cosmwasm_std::StdError;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Round amount must be greater than zero")]
+    NoFunds {},
+
+    #[error("Already claimed")]
+    AlreadyClaimed {},
+
+    #[error("Invalid hex encoded proof")]
+    InvalidHexProof {},
+
+    #[error("Invalid hex encoded merkle root")]
+    InvalidHexMerkle {},
+
+    #[error("Merkle verification failed")]
+    MerkleVerification {},
+
+    #[error("Round expired")]
+    Expired {},
+
+    #[error("Round not expired")]
+    NotExpired {},
+
+    #[error("Round was clawed back")]
+    ClawedBack {},
+
+    #[error("Claims exceed the funds of the round")]
+    InsufficientFunds {},
+}
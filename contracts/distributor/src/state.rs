@@ -0,0 +1,129 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::{CanonicalAddr, Order, StdResult, Storage, Uint128};
+use cosmwasm_storage::{singleton, singleton_read, Bucket, ReadonlyBucket};
+
+static KEY_CONFIG: &[u8] = b"config";
+static KEY_ROUND_COUNT: &[u8] = b"round_count";
+
+static PREFIX_ROUND: &[u8] = b"round";
+static PREFIX_CLAIMED_BITMAP: &[u8] = b"claimed_bitmap";
+
+// settings for pagination
+const MAX_LIMIT: u32 = 30;
+const DEFAULT_LIMIT: u32 = 10;
+
+/// Number of leaves tracked by one word of a claimed bitmap
+pub const BITMAP_WORD_BITS: u32 = 128;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Config {
+    pub shard_token: CanonicalAddr,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Round {
+    pub creator: CanonicalAddr,
+    pub merkle_root: String,
+    pub expires_at: Option<u64>,
+    pub total_amount: Uint128,
+    pub claimed_amount: Uint128,
+    pub clawed_back: bool,
+}
+
+impl Round {
+    pub fn is_expired(&self, now: u64) -> bool {
+        match self.expires_at {
+            Some(expires_at) => now > expires_at,
+            None => false,
+        }
+    }
+}
+
+pub fn store_config(storage: &mut dyn Storage, config: &Config) -> StdResult<()> {
+    singleton(storage, KEY_CONFIG).save(config)
+}
+
+pub fn read_config(storage: &dyn Storage) -> StdResult<Config> {
+    singleton_read(storage, KEY_CONFIG).load()
+}
+
+/// Id for the next round, ids start at 1
+pub fn next_round_id(storage: &mut dyn Storage) -> StdResult<u64> {
+    let id = singleton_read(storage, KEY_ROUND_COUNT)
+        .may_load()?
+        .unwrap_or(0u64)
+        + 1;
+    singleton(storage, KEY_ROUND_COUNT).save(&id)?;
+    Ok(id)
+}
+
+pub fn store_round(storage: &mut dyn Storage, id: u64, round: &Round) -> StdResult<()> {
+    let mut round_bucket: Bucket<Round> = Bucket::new(storage, PREFIX_ROUND);
+    round_bucket.save(&id.to_be_bytes(), round)
+}
+
+pub fn read_round(storage: &dyn Storage, id: u64) -> StdResult<Option<Round>> {
+    let round_bucket: ReadonlyBucket<Round> = ReadonlyBucket::new(storage, PREFIX_ROUND);
+    round_bucket.may_load(&id.to_be_bytes())
+}
+
+pub fn read_rounds(
+    storage: &dyn Storage,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Vec<(u64, Round)>> {
+    let round_bucket: ReadonlyBucket<Round> = ReadonlyBucket::new(storage, PREFIX_ROUND);
+
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    // ids are stored big endian, so the first key after `start_after` is its successor
+    let start = start_after.map(|id| (id + 1).to_be_bytes().to_vec());
+
+    round_bucket
+        .range(start.as_deref(), None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (k, v) = item?;
+            let mut id = [0u8; 8];
+            id.copy_from_slice(&k);
+            Ok((u64::from_be_bytes(id), v))
+        })
+        .collect()
+}
+
+pub fn is_claimed(storage: &dyn Storage, round: u64, index: u32) -> StdResult<bool> {
+    let word = read_bitmap_word(storage, round, index / BITMAP_WORD_BITS)?;
+    Ok(word.u128() & (1 << (index % BITMAP_WORD_BITS)) != 0)
+}
+
+pub fn set_claimed(storage: &mut dyn Storage, round: u64, index: u32) -> StdResult<()> {
+    let word_index = index / BITMAP_WORD_BITS;
+    let word =
+        read_bitmap_word(storage, round, word_index)?.u128() | (1 << (index % BITMAP_WORD_BITS));
+    let mut bitmap_bucket: Bucket<Uint128> =
+        Bucket::multilevel(storage, &[PREFIX_CLAIMED_BITMAP, &round.to_be_bytes()]);
+    bitmap_bucket.save(&word_index.to_be_bytes(), &Uint128::from(word))
+}
+
+/// Words `start_word..start_word + limit` of the claimed bitmap of a round,
+/// words without claims being zero
+pub fn read_bitmap_words(
+    storage: &dyn Storage,
+    round: u64,
+    start_word: u32,
+    limit: Option<u32>,
+) -> StdResult<Vec<Uint128>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT);
+    (start_word..start_word.saturating_add(limit))
+        .map(|word_index| read_bitmap_word(storage, round, word_index))
+        .collect()
+}
+
+fn read_bitmap_word(storage: &dyn Storage, round: u64, word_index: u32) -> StdResult<Uint128> {
+    let bitmap_bucket: ReadonlyBucket<Uint128> =
+        ReadonlyBucket::multilevel(storage, &[PREFIX_CLAIMED_BITMAP, &round.to_be_bytes()]);
+    Ok(bitmap_bucket
+        .may_load(&word_index.to_be_bytes())?
+        .unwrap_or_default())
+}
@@ -0,0 +1,216 @@
+use crate::contract::{execute, instantiate, query};
+use crate::error::ContractError;
+use cosmwasm_std::testing::{
+    mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
+};
+use cosmwasm_std::{
+    from_binary, to_binary, CosmosMsg, DepsMut, Env, OwnedDeps, Response, SubMsg, Timestamp,
+    Uint128, WasmMsg,
+};
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use sha3::Digest;
+use shard_token::distributor::{
+    ClaimedBitmapResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, IsClaimedResponse, QueryMsg,
+    RoundResponse,
+};
+
+fn env_at(time: u64) -> Env {
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(time);
+    env
+}
+
+fn setup() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+    let mut deps = mock_dependencies(&[]);
+    let msg = InstantiateMsg {
+        shard_token: "shard0000".to_string(),
+    };
+    instantiate(deps.as_mut(), env_at(0), mock_info("addr0000", &[]), msg).unwrap();
+    deps
+}
+
+fn hash(data: &[u8]) -> [u8; 32] {
+    let mut buf = [0u8; 32];
+    buf.copy_from_slice(sha3::Keccak256::digest(data).as_slice());
+    buf
+}
+
+fn leaf(index: u32, address: &str, amount: u128) -> [u8; 32] {
+    hash(format!("{},{},{}", index, address, amount).as_bytes())
+}
+
+// Tree of two claims: 100 for addr0000 at index 0, 200 for addr1111 at index 1
+fn tree() -> (String, Vec<String>, Vec<String>) {
+    let (a, b) = (leaf(0, "addr0000", 100), leaf(1, "addr1111", 200));
+    let root = if a < b {
+        hash(&[a, b].concat())
+    } else {
+        hash(&[b, a].concat())
+    };
+    (
+        hex::encode(root),
+        vec![hex::encode(b)],
+        vec![hex::encode(a)],
+    )
+}
+
+fn create_round(deps: DepsMut, amount: u128, expires_at: Option<u64>) -> Response {
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "creator0000".to_string(),
+        amount: Uint128::from(amount),
+        msg: to_binary(&Cw20HookMsg::CreateRound {
+            merkle_root: tree().0,
+            expires_at,
+        })
+        .unwrap(),
+    });
+    execute(deps, env_at(0), mock_info("shard0000", &[]), msg).unwrap()
+}
+
+fn claim(
+    deps: DepsMut,
+    time: u64,
+    claimer: &str,
+    index: u32,
+    amount: u128,
+    proof: Vec<String>,
+) -> Result<Response, ContractError> {
+    let msg = ExecuteMsg::Claim {
+        round: 1,
+        index,
+        amount: Uint128::from(amount),
+        proof,
+    };
+    execute(deps, env_at(time), mock_info(claimer, &[]), msg)
+}
+
+fn transfer_msg(recipient: &str, amount: u128) -> SubMsg {
+    SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: "shard0000".to_string(),
+        msg: to_binary(&Cw20ExecuteMsg::Transfer {
+            recipient: recipient.to_string(),
+            amount: Uint128::from(amount),
+        })
+        .unwrap(),
+        funds: vec![],
+    }))
+}
+
+#[test]
+fn claim_with_proof() {
+    let mut deps = setup();
+    create_round(deps.as_mut(), 300, None);
+    let (_, proof0, proof1) = tree();
+
+    match claim(deps.as_mut(), 10, "addr0000", 0, 200, proof0.clone()).unwrap_err() {
+        ContractError::MerkleVerification {} => {}
+        e => panic!("unexpected error: {:?}", e),
+    }
+    match claim(deps.as_mut(), 10, "addr1111", 0, 100, proof0.clone()).unwrap_err() {
+        ContractError::MerkleVerification {} => {}
+        e => panic!("unexpected error: {:?}", e),
+    }
+
+    let res = claim(deps.as_mut(), 10, "addr0000", 0, 100, proof0.clone()).unwrap();
+    assert_eq!(res.messages, vec![transfer_msg("addr0000", 100)]);
+    match claim(deps.as_mut(), 10, "addr0000", 0, 100, proof0).unwrap_err() {
+        ContractError::AlreadyClaimed {} => {}
+        e => panic!("unexpected error: {:?}", e),
+    }
+    let res = claim(deps.as_mut(), 10, "addr1111", 1, 200, proof1).unwrap();
+    assert_eq!(res.messages, vec![transfer_msg("addr1111", 200)]);
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Round { round: 1 }).unwrap();
+    let round: RoundResponse = from_binary(&res).unwrap();
+    assert_eq!(round.creator, "creator0000");
+    assert_eq!(round.claimed_amount, Uint128::from(300u128));
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::IsClaimed { round: 1, index: 1 },
+    )
+    .unwrap();
+    let claimed: IsClaimedResponse = from_binary(&res).unwrap();
+    assert!(claimed.is_claimed);
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::ClaimedBitmap {
+            round: 1,
+            start_word: 0,
+            limit: Some(2),
+        },
+    )
+    .unwrap();
+    let bitmap: ClaimedBitmapResponse = from_binary(&res).unwrap();
+    assert_eq!(bitmap.words, vec![Uint128::from(0b11u128), Uint128::zero()]);
+}
+
+#[test]
+fn claims_cannot_exceed_round_funds() {
+    let mut deps = setup();
+    create_round(deps.as_mut(), 250, None);
+    let (_, proof0, proof1) = tree();
+    claim(deps.as_mut(), 10, "addr0000", 0, 100, proof0).unwrap();
+    match claim(deps.as_mut(), 10, "addr1111", 1, 200, proof1).unwrap_err() {
+        ContractError::InsufficientFunds {} => {}
+        e => panic!("unexpected error: {:?}", e),
+    }
+}
+
+#[test]
+fn clawback_after_expiration() {
+    let mut deps = setup();
+    create_round(deps.as_mut(), 300, Some(1000));
+    let (_, proof0, proof1) = tree();
+    claim(deps.as_mut(), 10, "addr0000", 0, 100, proof0).unwrap();
+
+    let clawback = ExecuteMsg::Clawback { round: 1 };
+    match execute(
+        deps.as_mut(),
+        env_at(1000),
+        mock_info("creator0000", &[]),
+        clawback.clone(),
+    )
+    .unwrap_err()
+    {
+        ContractError::NotExpired {} => {}
+        e => panic!("unexpected error: {:?}", e),
+    }
+    match execute(
+        deps.as_mut(),
+        env_at(1001),
+        mock_info("addr0000", &[]),
+        clawback.clone(),
+    )
+    .unwrap_err()
+    {
+        ContractError::Unauthorized {} => {}
+        e => panic!("unexpected error: {:?}", e),
+    }
+    match claim(deps.as_mut(), 1001, "addr1111", 1, 200, proof1).unwrap_err() {
+        ContractError::Expired {} => {}
+        e => panic!("unexpected error: {:?}", e),
+    }
+
+    let res = execute(
+        deps.as_mut(),
+        env_at(1001),
+        mock_info("creator0000", &[]),
+        clawback.clone(),
+    )
+    .unwrap();
+    assert_eq!(res.messages, vec![transfer_msg("creator0000", 200)]);
+    match execute(
+        deps.as_mut(),
+        env_at(1002),
+        mock_info("creator0000", &[]),
+        clawback,
+    )
+    .unwrap_err()
+    {
+        ContractError::ClawedBack {} => {}
+        e => panic!("unexpected error: {:?}", e),
+    }
+}
@@ -0,0 +1,240 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+
+use crate::error::ContractError;
+use crate::state::{
+    read_config, read_escrow, read_escrows, remove_escrow, store_config, store_escrow, Config,
+    Escrow,
+};
+
+use cosmwasm_std::{
+    from_binary, to_binary, Addr, Binary, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Response,
+    StdError, StdResult, Uint128, WasmMsg,
+};
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use shard_token::escrow::{
+    ConfigResponse, Cw20HookMsg, EscrowResponse, EscrowsResponse, ExecuteMsg, InstantiateMsg,
+    MigrateMsg, QueryMsg,
+};
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    store_config(
+        deps.storage,
+        &Config {
+            shard_token: deps.api.addr_canonicalize(&msg.shard_token)?,
+        },
+    )?;
+
+    Ok(Response::default())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
+        ExecuteMsg::Release { id } => release(deps, env, info, id),
+        ExecuteMsg::Refund { id } => refund(deps, env, info, id),
+    }
+}
+
+pub fn receive_cw20(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    cw20_msg: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    let config: Config = read_config(deps.storage)?;
+    // only the shard token contract can execute this message
+    if config.shard_token != deps.api.addr_canonicalize(info.sender.as_str())? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    match from_binary(&cw20_msg.msg)? {
+        Cw20HookMsg::Create {
+            id,
+            payee,
+            arbiter,
+            end_time,
+        } => create(
+            deps,
+            env,
+            cw20_msg.sender,
+            cw20_msg.amount,
+            id,
+            payee,
+            arbiter,
+            end_time,
+        ),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn create(
+    deps: DepsMut,
+    env: Env,
+    payer: String,
+    amount: Uint128,
+    id: String,
+    payee: String,
+    arbiter: String,
+    end_time: Option<u64>,
+) -> Result<Response, ContractError> {
+    if amount.is_zero() {
+        return Err(ContractError::NoFunds {});
+    }
+    if read_escrow(deps.storage, &id)?.is_some() {
+        return Err(ContractError::AlreadyInUse {});
+    }
+    let escrow = Escrow {
+        payer: deps.api.addr_canonicalize(&payer)?,
+        payee: deps.api.addr_canonicalize(&payee)?,
+        arbiter: deps.api.addr_canonicalize(&arbiter)?,
+        amount,
+        end_time,
+    };
+    if escrow.is_expired(env.block.time.seconds()) {
+        return Err(ContractError::Expired {});
+    }
+    store_escrow(deps.storage, &id, &escrow)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "create"),
+        ("id", &id),
+        ("payer", &payer),
+        ("payee", &payee),
+        ("arbiter", &arbiter),
+        ("amount", &amount.to_string()),
+    ]))
+}
+
+pub fn release(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: String,
+) -> Result<Response, ContractError> {
+    let escrow = load_escrow(deps.as_ref(), &id)?;
+    if deps.api.addr_canonicalize(info.sender.as_str())? != escrow.arbiter {
+        return Err(ContractError::Unauthorized {});
+    }
+    if escrow.is_expired(env.block.time.seconds()) {
+        return Err(ContractError::Expired {});
+    }
+
+    remove_escrow(deps.storage, &id);
+    let payee = deps.api.addr_humanize(&escrow.payee)?;
+    Ok(Response::new()
+        .add_message(send_tokens(deps.as_ref(), &payee, escrow.amount)?)
+        .add_attributes(vec![
+            ("action", "release"),
+            ("id", &id),
+            ("payee", payee.as_str()),
+            ("amount", &escrow.amount.to_string()),
+        ]))
+}
+
+pub fn refund(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: String,
+) -> Result<Response, ContractError> {
+    let escrow = load_escrow(deps.as_ref(), &id)?;
+    if !escrow.is_expired(env.block.time.seconds())
+        && deps.api.addr_canonicalize(info.sender.as_str())? != escrow.arbiter
+    {
+        return Err(ContractError::NotExpired {});
+    }
+
+    remove_escrow(deps.storage, &id);
+    let payer = deps.api.addr_humanize(&escrow.payer)?;
+    Ok(Response::new()
+        .add_message(send_tokens(deps.as_ref(), &payer, escrow.amount)?)
+        .add_attributes(vec![
+            ("action", "refund"),
+            ("id", &id),
+            ("payer", payer.as_str()),
+            ("amount", &escrow.amount.to_string()),
+        ]))
+}
+
+fn load_escrow(deps: Deps, id: &str) -> StdResult<Escrow> {
+    match read_escrow(deps.storage, id)? {
+        Some(escrow) => Ok(escrow),
+        None => Err(StdError::not_found("Escrow")),
+    }
+}
+
+fn send_tokens(deps: Deps, recipient: &Addr, amount: Uint128) -> StdResult<CosmosMsg> {
+    let config: Config = read_config(deps.storage)?;
+    Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: deps.api.addr_humanize(&config.shard_token)?.to_string(),
+        msg: to_binary(&Cw20ExecuteMsg::Transfer {
+            recipient: recipient.to_string(),
+            amount,
+        })?,
+        funds: vec![],
+    }))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_binary(&query_config(deps)?),
+        QueryMsg::Escrow { id } => to_binary(&query_escrow(deps, id)?),
+        QueryMsg::ListEscrows { start_after, limit } => {
+            to_binary(&query_escrows(deps, start_after, limit)?)
+        }
+    }
+}
+
+pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
+    let config: Config = read_config(deps.storage)?;
+    Ok(ConfigResponse {
+        shard_token: deps.api.addr_humanize(&config.shard_token)?.to_string(),
+    })
+}
+
+pub fn query_escrow(deps: Deps, id: String) -> StdResult<EscrowResponse> {
+    let escrow = load_escrow(deps, &id)?;
+    escrow_response(deps, id, escrow)
+}
+
+pub fn query_escrows(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<EscrowsResponse> {
+    let escrows = read_escrows(deps.storage, start_after, limit)?
+        .into_iter()
+        .map(|(id, escrow)| escrow_response(deps, id, escrow))
+        .collect::<StdResult<Vec<EscrowResponse>>>()?;
+    Ok(EscrowsResponse { escrows })
+}
+
+fn escrow_response(deps: Deps, id: String, escrow: Escrow) -> StdResult<EscrowResponse> {
+    Ok(EscrowResponse {
+        id,
+        payer: deps.api.addr_humanize(&escrow.payer)?.to_string(),
+        payee: deps.api.addr_humanize(&escrow.payee)?.to_string(),
+        arbiter: deps.api.addr_humanize(&escrow.arbiter)?.to_string(),
+        amount: escrow.amount,
+        end_time: escrow.end_time,
+    })
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    Ok(Response::default())
+}
@@ -0,0 +1,23 @@
+use cosmwasm_std::StdError;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Escrow id already in use")]
+    AlreadyInUse {},
+
+    #[error("Escrow amount must be greater than zero")]
+    NoFunds {},
+
+    #[error("Escrow expired")]
+    Expired {},
+
+    #[error("Escrow not expired")]
+    NotExpired {},
+}
@@ -0,0 +1,88 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::{CanonicalAddr, Order, StdResult, Storage, Uint128};
+use cosmwasm_storage::{singleton, singleton_read, Bucket, ReadonlyBucket};
+
+static KEY_CONFIG: &[u8] = b"config";
+
+static PREFIX_ESCROW: &[u8] = b"escrow";
+
+// settings for pagination
+const MAX_LIMIT: u32 = 30;
+const DEFAULT_LIMIT: u32 = 10;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Config {
+    pub shard_token: CanonicalAddr,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Escrow {
+    pub payer: CanonicalAddr,
+    pub payee: CanonicalAddr,
+    pub arbiter: CanonicalAddr,
+    pub amount: Uint128,
+    pub end_time: Option<u64>,
+}
+
+impl Escrow {
+    pub fn is_expired(&self, now: u64) -> bool {
+        match self.end_time {
+            Some(end_time) => now > end_time,
+            None => false,
+        }
+    }
+}
+
+pub fn store_config(storage: &mut dyn Storage, config: &Config) -> StdResult<()> {
+    singleton(storage, KEY_CONFIG).save(config)
+}
+
+pub fn read_config(storage: &dyn Storage) -> StdResult<Config> {
+    singleton_read(storage, KEY_CONFIG).load()
+}
+
+pub fn store_escrow(storage: &mut dyn Storage, id: &str, escrow: &Escrow) -> StdResult<()> {
+    let mut escrow_bucket: Bucket<Escrow> = Bucket::new(storage, PREFIX_ESCROW);
+    escrow_bucket.save(id.as_bytes(), escrow)
+}
+
+pub fn remove_escrow(storage: &mut dyn Storage, id: &str) {
+    let mut escrow_bucket: Bucket<Escrow> = Bucket::new(storage, PREFIX_ESCROW);
+    escrow_bucket.remove(id.as_bytes())
+}
+
+pub fn read_escrow(storage: &dyn Storage, id: &str) -> StdResult<Option<Escrow>> {
+    let escrow_bucket: ReadonlyBucket<Escrow> = ReadonlyBucket::new(storage, PREFIX_ESCROW);
+    escrow_bucket.may_load(id.as_bytes())
+}
+
+pub fn read_escrows(
+    storage: &dyn Storage,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Vec<(String, Escrow)>> {
+    let escrow_bucket: ReadonlyBucket<Escrow> = ReadonlyBucket::new(storage, PREFIX_ESCROW);
+
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = calc_range_start(start_after);
+
+    escrow_bucket
+        .range(start.as_deref(), None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (k, v) = item?;
+            Ok((String::from_utf8_lossy(&k).to_string(), v))
+        })
+        .collect()
+}
+
+// the first key after the provided key is the key with a 0 byte appended
+fn calc_range_start(start_after: Option<String>) -> Option<Vec<u8>> {
+    start_after.map(|id| {
+        let mut v = id.as_bytes().to_vec();
+        v.push(0);
+        v
+    })
+}
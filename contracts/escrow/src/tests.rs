@@ -0,0 +1,229 @@
+use crate::contract::{execute, instantiate, query};
+use crate::error::ContractError;
+use cosmwasm_std::testing::{
+    mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
+};
+use cosmwasm_std::{
+    from_binary, to_binary, CosmosMsg, DepsMut, Env, OwnedDeps, Response, StdError, SubMsg,
+    Timestamp, Uint128, WasmMsg,
+};
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use shard_token::escrow::{
+    ConfigResponse, Cw20HookMsg, EscrowResponse, EscrowsResponse, ExecuteMsg, InstantiateMsg,
+    QueryMsg,
+};
+
+fn env_at(time: u64) -> Env {
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(time);
+    env
+}
+
+fn setup() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+    let mut deps = mock_dependencies(&[]);
+    let msg = InstantiateMsg {
+        shard_token: "shard0000".to_string(),
+    };
+    instantiate(deps.as_mut(), env_at(0), mock_info("addr0000", &[]), msg).unwrap();
+    deps
+}
+
+fn create(deps: DepsMut, token: &str, id: &str, amount: u128) -> Result<Response, ContractError> {
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "payer0000".to_string(),
+        amount: Uint128::from(amount),
+        msg: to_binary(&Cw20HookMsg::Create {
+            id: id.to_string(),
+            payee: "payee0000".to_string(),
+            arbiter: "arbiter0000".to_string(),
+            end_time: Some(1000),
+        })
+        .unwrap(),
+    });
+    execute(deps, env_at(100), mock_info(token, &[]), msg)
+}
+
+fn transfer_msg(recipient: &str, amount: u128) -> SubMsg {
+    SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: "shard0000".to_string(),
+        msg: to_binary(&Cw20ExecuteMsg::Transfer {
+            recipient: recipient.to_string(),
+            amount: Uint128::from(amount),
+        })
+        .unwrap(),
+        funds: vec![],
+    }))
+}
+
+#[test]
+fn proper_initialization() {
+    let deps = setup();
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+    let config: ConfigResponse = from_binary(&res).unwrap();
+    assert_eq!("shard0000", config.shard_token.as_str());
+}
+
+#[test]
+fn create_escrow() {
+    let mut deps = setup();
+    create(deps.as_mut(), "shard0000", "invoice-1", 100).unwrap();
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Escrow {
+            id: "invoice-1".to_string(),
+        },
+    )
+    .unwrap();
+    let escrow: EscrowResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        escrow,
+        EscrowResponse {
+            id: "invoice-1".to_string(),
+            payer: "payer0000".to_string(),
+            payee: "payee0000".to_string(),
+            arbiter: "arbiter0000".to_string(),
+            amount: Uint128::from(100u128),
+            end_time: Some(1000),
+        }
+    );
+
+    match create(deps.as_mut(), "shard0000", "invoice-1", 100).unwrap_err() {
+        ContractError::AlreadyInUse {} => {}
+        e => panic!("unexpected error: {:?}", e),
+    }
+    match create(deps.as_mut(), "other0000", "invoice-2", 100).unwrap_err() {
+        ContractError::Unauthorized {} => {}
+        e => panic!("unexpected error: {:?}", e),
+    }
+    match create(deps.as_mut(), "shard0000", "invoice-2", 0).unwrap_err() {
+        ContractError::NoFunds {} => {}
+        e => panic!("unexpected error: {:?}", e),
+    }
+}
+
+#[test]
+fn release_by_arbiter() {
+    let mut deps = setup();
+    create(deps.as_mut(), "shard0000", "invoice-1", 100).unwrap();
+
+    let msg = ExecuteMsg::Release {
+        id: "invoice-1".to_string(),
+    };
+    match execute(
+        deps.as_mut(),
+        env_at(200),
+        mock_info("payee0000", &[]),
+        msg.clone(),
+    )
+    .unwrap_err()
+    {
+        ContractError::Unauthorized {} => {}
+        e => panic!("unexpected error: {:?}", e),
+    }
+    match execute(
+        deps.as_mut(),
+        env_at(1001),
+        mock_info("arbiter0000", &[]),
+        msg.clone(),
+    )
+    .unwrap_err()
+    {
+        ContractError::Expired {} => {}
+        e => panic!("unexpected error: {:?}", e),
+    }
+
+    let res = execute(
+        deps.as_mut(),
+        env_at(200),
+        mock_info("arbiter0000", &[]),
+        msg,
+    )
+    .unwrap();
+    assert_eq!(res.messages, vec![transfer_msg("payee0000", 100)]);
+
+    // the escrow is closed
+    let err = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Escrow {
+            id: "invoice-1".to_string(),
+        },
+    )
+    .unwrap_err();
+    match err {
+        StdError::NotFound { .. } => {}
+        e => panic!("unexpected error: {:?}", e),
+    }
+}
+
+#[test]
+fn refund_after_expiration_or_by_arbiter() {
+    let mut deps = setup();
+    create(deps.as_mut(), "shard0000", "invoice-1", 100).unwrap();
+    create(deps.as_mut(), "shard0000", "invoice-2", 200).unwrap();
+
+    let msg = ExecuteMsg::Refund {
+        id: "invoice-1".to_string(),
+    };
+    match execute(
+        deps.as_mut(),
+        env_at(1000),
+        mock_info("payer0000", &[]),
+        msg.clone(),
+    )
+    .unwrap_err()
+    {
+        ContractError::NotExpired {} => {}
+        e => panic!("unexpected error: {:?}", e),
+    }
+    let res = execute(deps.as_mut(), env_at(1001), mock_info("anyone", &[]), msg).unwrap();
+    assert_eq!(res.messages, vec![transfer_msg("payer0000", 100)]);
+
+    let msg = ExecuteMsg::Refund {
+        id: "invoice-2".to_string(),
+    };
+    let res = execute(
+        deps.as_mut(),
+        env_at(200),
+        mock_info("arbiter0000", &[]),
+        msg,
+    )
+    .unwrap();
+    assert_eq!(res.messages, vec![transfer_msg("payer0000", 200)]);
+}
+
+#[test]
+fn list_escrows() {
+    let mut deps = setup();
+    for id in &["c", "a", "b"] {
+        create(deps.as_mut(), "shard0000", id, 100).unwrap();
+    }
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::ListEscrows {
+            start_after: None,
+            limit: Some(2),
+        },
+    )
+    .unwrap();
+    let list: EscrowsResponse = from_binary(&res).unwrap();
+    let ids: Vec<String> = list.escrows.into_iter().map(|escrow| escrow.id).collect();
+    assert_eq!(ids, vec!["a", "b"]);
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::ListEscrows {
+            start_after: Some("b".to_string()),
+            limit: None,
+        },
+    )
+    .unwrap();
+    let list: EscrowsResponse = from_binary(&res).unwrap();
+    let ids: Vec<String> = list.escrows.into_iter().map(|escrow| escrow.id).collect();
+    assert_eq!(ids, vec!["c"]);
+}
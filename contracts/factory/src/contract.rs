@@ -0,0 +1,295 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+
+use crate::error::ContractError;
+use crate::state::{
+    read_config, read_token, read_tokens, read_tokens_by_creator, store_config, store_token,
+    Config, Token,
+};
+
+use bech32::{decode, encode, FromBase32, ToBase32};
+use cosmwasm_std::{
+    instantiate2_address, to_binary, Addr, BankMsg, Binary, Coin, Deps, DepsMut, Env, MessageInfo,
+    Response, StdError, StdResult, Uint128, WasmMsg,
+};
+use shard_token::factory::{
+    ConfigResponse, ExecuteMsg, InitialBalance, InstantiateMsg, MigrateMsg, PredictAddressResponse,
+    QueryMsg, TokenInstantiateMsg, TokenMinterInfo, TokenResponse, TokensResponse,
+};
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    let admin = match msg.admin {
+        Some(admin) => deps.api.addr_validate(&admin)?,
+        None => info.sender,
+    };
+    store_config(
+        deps.storage,
+        &Config {
+            token_code_id: msg.token_code_id,
+            creation_fee: msg.creation_fee,
+            admin,
+        },
+    )?;
+
+    Ok(Response::default())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::CreateToken {
+            name,
+            symbol,
+            decimals,
+            initial_balances,
+            max_supply,
+            minter,
+        } => create_token(
+            deps,
+            env,
+            info,
+            name,
+            symbol,
+            decimals,
+            initial_balances,
+            max_supply,
+            minter,
+        ),
+        ExecuteMsg::ExecuteOnToken { symbol, msg } => execute_on_token(deps, info, symbol, msg),
+        ExecuteMsg::UpdateConfig {
+            token_code_id,
+            creation_fee,
+            admin,
+        } => update_config(deps, info, token_code_id, creation_fee, admin),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn create_token(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    name: String,
+    symbol: String,
+    decimals: u8,
+    initial_balances: Vec<InitialBalance>,
+    max_supply: Option<Uint128>,
+    minter: Option<String>,
+) -> Result<Response, ContractError> {
+    let config = read_config(deps.storage)?;
+    if read_token(deps.storage, &symbol)?.is_some() {
+        return Err(ContractError::SymbolTaken { symbol });
+    }
+    let mut res = Response::new();
+    match &config.creation_fee {
+        Some(fee) => {
+            if info.funds != [fee.clone()] {
+                return Err(ContractError::WrongCreationFee { fee: fee.clone() });
+            }
+            res = res.add_message(BankMsg::Send {
+                to_address: config.admin.to_string(),
+                amount: info.funds.clone(),
+            });
+        }
+        None => {
+            if !info.funds.is_empty() {
+                return Err(ContractError::UnexpectedFunds {});
+            }
+        }
+    }
+
+    let address = predict_address(deps.as_ref(), &env, config.token_code_id, &symbol)?;
+    let token_msg = TokenInstantiateMsg {
+        name,
+        symbol: symbol.clone(),
+        decimals,
+        initial_balances,
+        max_supply,
+        mint: minter.map(|minter| TokenMinterInfo { minter }),
+    };
+    store_token(
+        deps.storage,
+        &symbol,
+        &Token {
+            address: address.clone(),
+            creator: info.sender.clone(),
+            code_id: config.token_code_id,
+            created_at: env.block.time.seconds(),
+        },
+    )?;
+
+    Ok(res
+        .add_message(WasmMsg::Instantiate2 {
+            admin: Some(config.admin.to_string()),
+            code_id: config.token_code_id,
+            label: format!("shard-token {}", symbol),
+            msg: to_binary(&token_msg)?,
+            funds: vec![],
+            salt: Binary::from(symbol.as_bytes()),
+        })
+        .add_attributes(vec![
+            ("action", "create_token"),
+            ("symbol", &symbol),
+            ("address", address.as_str()),
+            ("creator", info.sender.as_str()),
+        ]))
+}
+
+pub fn execute_on_token(
+    deps: DepsMut,
+    info: MessageInfo,
+    symbol: String,
+    msg: Binary,
+) -> Result<Response, ContractError> {
+    let token = load_token(deps.as_ref(), &symbol)?;
+    if info.sender != token.creator {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    Ok(Response::new()
+        .add_message(WasmMsg::Execute {
+            contract_addr: token.address.to_string(),
+            msg,
+            funds: info.funds,
+        })
+        .add_attributes(vec![("action", "execute_on_token"), ("symbol", &symbol)]))
+}
+
+pub fn update_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    token_code_id: u64,
+    creation_fee: Option<Coin>,
+    admin: String,
+) -> Result<Response, ContractError> {
+    let config = read_config(deps.storage)?;
+    if info.sender != config.admin {
+        return Err(ContractError::Unauthorized {});
+    }
+    store_config(
+        deps.storage,
+        &Config {
+            token_code_id,
+            creation_fee,
+            admin: deps.api.addr_validate(&admin)?,
+        },
+    )?;
+
+    Ok(Response::new().add_attribute("action", "update_config"))
+}
+
+// Tokens are instantiated by the factory with their symbol as salt, so their
+// address only depends on the code checksum and the symbol. The chain encodes
+// it like the factory's own address, which gives the bech32 prefix.
+fn predict_address(deps: Deps, env: &Env, code_id: u64, symbol: &str) -> StdResult<Addr> {
+    let checksum = deps.querier.query_wasm_code_info(code_id)?.checksum;
+    let (prefix, data, variant) = decode(env.contract.address.as_str())
+        .map_err(|err| StdError::generic_err(err.to_string()))?;
+    let creator =
+        Vec::<u8>::from_base32(&data).map_err(|err| StdError::generic_err(err.to_string()))?;
+    let address = instantiate2_address(checksum.as_slice(), &creator.into(), symbol.as_bytes())
+        .map_err(|err| StdError::generic_err(err.to_string()))?;
+    encode(&prefix, address.as_slice().to_base32(), variant)
+        .map(Addr::unchecked)
+        .map_err(|err| StdError::generic_err(err.to_string()))
+}
+
+fn load_token(deps: Deps, symbol: &str) -> Result<Token, ContractError> {
+    match read_token(deps.storage, symbol)? {
+        Some(token) => Ok(token),
+        None => Err(ContractError::TokenNotFound {
+            symbol: symbol.to_string(),
+        }),
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_binary(&query_config(deps)?),
+        QueryMsg::Token { symbol } => to_binary(&query_token(deps, symbol)?),
+        QueryMsg::ListTokens { start_after, limit } => {
+            to_binary(&query_tokens(deps, start_after, limit)?)
+        }
+        QueryMsg::TokensByCreator {
+            creator,
+            start_after,
+            limit,
+        } => to_binary(&query_tokens_by_creator(deps, creator, start_after, limit)?),
+        QueryMsg::PredictAddress { symbol } => {
+            let config = read_config(deps.storage)?;
+            let address = predict_address(deps, &env, config.token_code_id, &symbol)?;
+            to_binary(&PredictAddressResponse {
+                address: address.to_string(),
+            })
+        }
+    }
+}
+
+pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
+    let config = read_config(deps.storage)?;
+    Ok(ConfigResponse {
+        token_code_id: config.token_code_id,
+        creation_fee: config.creation_fee,
+        admin: config.admin.to_string(),
+    })
+}
+
+pub fn query_token(deps: Deps, symbol: String) -> StdResult<TokenResponse> {
+    match read_token(deps.storage, &symbol)? {
+        Some(token) => Ok(token_response(symbol, token)),
+        None => Err(StdError::not_found("Token")),
+    }
+}
+
+pub fn query_tokens(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<TokensResponse> {
+    let tokens = read_tokens(deps.storage, start_after, limit)?
+        .into_iter()
+        .map(|(symbol, token)| token_response(symbol, token))
+        .collect();
+    Ok(TokensResponse { tokens })
+}
+
+pub fn query_tokens_by_creator(
+    deps: Deps,
+    creator: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<TokensResponse> {
+    let creator = deps.api.addr_validate(&creator)?;
+    let tokens = read_tokens_by_creator(deps.storage, &creator, start_after, limit)?
+        .into_iter()
+        .map(|(symbol, token)| token_response(symbol, token))
+        .collect();
+    Ok(TokensResponse { tokens })
+}
+
+fn token_response(symbol: String, token: Token) -> TokenResponse {
+    TokenResponse {
+        symbol,
+        address: token.address.to_string(),
+        creator: token.creator.to_string(),
+        code_id: token.code_id,
+        created_at: token.created_at,
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    Ok(Response::default())
+}
@@ -0,0 +1,23 @@
+use cosmwasm_std::{Coin, StdError};
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("A token with symbol {symbol} was already launched")]
+    SymbolTaken { symbol: String },
+
+    #[error("No token with symbol {symbol} was launched")]
+    TokenNotFound { symbol: String },
+
+    #[error("Exactly the creation fee of {fee} must be attached")]
+    WrongCreationFee { fee: Coin },
+
+    #[error("No funds may be attached, there is no creation fee")]
+    UnexpectedFunds {},
+}
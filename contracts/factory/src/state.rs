@@ -0,0 +1,106 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::{Addr, Coin, Order, StdResult, Storage};
+use cosmwasm_storage::{singleton, singleton_read, Bucket, ReadonlyBucket};
+
+static KEY_CONFIG: &[u8] = b"config";
+
+static PREFIX_TOKEN: &[u8] = b"token";
+static PREFIX_TOKENS_BY_CREATOR: &[u8] = b"tokens_by_creator";
+
+// settings for pagination
+const MAX_LIMIT: u32 = 30;
+const DEFAULT_LIMIT: u32 = 10;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Config {
+    pub token_code_id: u64,
+    pub creation_fee: Option<Coin>,
+    pub admin: Addr,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Token {
+    pub address: Addr,
+    pub creator: Addr,
+    pub code_id: u64,
+    pub created_at: u64,
+}
+
+pub fn store_config(storage: &mut dyn Storage, config: &Config) -> StdResult<()> {
+    singleton(storage, KEY_CONFIG).save(config)
+}
+
+pub fn read_config(storage: &dyn Storage) -> StdResult<Config> {
+    singleton_read(storage, KEY_CONFIG).load()
+}
+
+/// Registers a launched token under its symbol and in the index of its creator
+pub fn store_token(storage: &mut dyn Storage, symbol: &str, token: &Token) -> StdResult<()> {
+    let mut token_bucket: Bucket<Token> = Bucket::new(storage, PREFIX_TOKEN);
+    token_bucket.save(symbol.as_bytes(), token)?;
+    let mut creator_bucket: Bucket<bool> = Bucket::multilevel(
+        storage,
+        &[PREFIX_TOKENS_BY_CREATOR, token.creator.as_bytes()],
+    );
+    creator_bucket.save(symbol.as_bytes(), &true)
+}
+
+pub fn read_token(storage: &dyn Storage, symbol: &str) -> StdResult<Option<Token>> {
+    let token_bucket: ReadonlyBucket<Token> = ReadonlyBucket::new(storage, PREFIX_TOKEN);
+    token_bucket.may_load(symbol.as_bytes())
+}
+
+pub fn read_tokens(
+    storage: &dyn Storage,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Vec<(String, Token)>> {
+    let token_bucket: ReadonlyBucket<Token> = ReadonlyBucket::new(storage, PREFIX_TOKEN);
+
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = calc_range_start(start_after);
+
+    token_bucket
+        .range(start.as_deref(), None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (k, v) = item?;
+            Ok((String::from_utf8_lossy(&k).to_string(), v))
+        })
+        .collect()
+}
+
+pub fn read_tokens_by_creator(
+    storage: &dyn Storage,
+    creator: &Addr,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Vec<(String, Token)>> {
+    let creator_bucket: ReadonlyBucket<bool> =
+        ReadonlyBucket::multilevel(storage, &[PREFIX_TOKENS_BY_CREATOR, creator.as_bytes()]);
+
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = calc_range_start(start_after);
+
+    creator_bucket
+        .range(start.as_deref(), None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (k, _) = item?;
+            let symbol = String::from_utf8_lossy(&k).to_string();
+            let token = ReadonlyBucket::new(storage, PREFIX_TOKEN).load(&k)?;
+            Ok((symbol, token))
+        })
+        .collect()
+}
+
+// the first key after the provided key is the key with a 0 byte appended
+fn calc_range_start(start_after: Option<String>) -> Option<Vec<u8>> {
+    start_after.map(|symbol| {
+        let mut v = symbol.as_bytes().to_vec();
+        v.push(0);
+        v
+    })
+}
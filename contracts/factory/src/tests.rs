@@ -0,0 +1,289 @@
+use crate::contract::{execute, instantiate, query};
+use crate::error::ContractError;
+use cosmwasm_std::testing::{
+    mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
+};
+use cosmwasm_std::{
+    coin, from_binary, to_binary, BankMsg, Binary, CodeInfoResponse, ContractResult, CosmosMsg,
+    Deps, DepsMut, Env, HexBinary, OwnedDeps, Response, StdError, SystemError, SystemResult,
+    Uint128, WasmMsg, WasmQuery,
+};
+use shard_token::factory::{
+    ConfigResponse, ExecuteMsg, InitialBalance, InstantiateMsg, PredictAddressResponse, QueryMsg,
+    TokenInstantiateMsg, TokenResponse, TokensResponse,
+};
+
+const TOKEN_CODE_ID: u64 = 7;
+
+fn setup(creation_fee: Option<u128>) -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+    let mut deps = mock_dependencies();
+    deps.querier.update_wasm(|query| match query {
+        WasmQuery::CodeInfo { code_id } => SystemResult::Ok(ContractResult::Ok(
+            to_binary(&CodeInfoResponse::new(
+                *code_id,
+                "uploader".to_string(),
+                HexBinary::from(vec![*code_id as u8; 32]),
+            ))
+            .unwrap(),
+        )),
+        _ => SystemResult::Err(SystemError::UnsupportedRequest {
+            kind: "wasm".to_string(),
+        }),
+    });
+    let msg = InstantiateMsg {
+        token_code_id: TOKEN_CODE_ID,
+        creation_fee: creation_fee.map(|amount| coin(amount, "uluna")),
+        admin: None,
+    };
+    let info = mock_info(deps.api.addr_make("admin").as_str(), &[]);
+    let env = env(&deps.api);
+    instantiate(deps.as_mut(), env, info, msg).unwrap();
+    deps
+}
+
+// Addresses are predicted from the bech32 address of the factory
+fn env(api: &MockApi) -> Env {
+    let mut env = mock_env();
+    env.contract.address = api.addr_make("factory");
+    env
+}
+
+fn create_token_msg(symbol: &str) -> ExecuteMsg {
+    ExecuteMsg::CreateToken {
+        name: "Cash Token".to_string(),
+        symbol: symbol.to_string(),
+        decimals: 9,
+        initial_balances: vec![InitialBalance {
+            address: "holder0000".to_string(),
+            amount: Uint128::from(1000u128),
+        }],
+        max_supply: None,
+        minter: None,
+    }
+}
+
+fn create_token(
+    deps: DepsMut,
+    env: Env,
+    creator: &str,
+    symbol: &str,
+    funds: u128,
+) -> Result<Response, ContractError> {
+    let funds = if funds == 0 {
+        vec![]
+    } else {
+        vec![coin(funds, "uluna")]
+    };
+    execute(
+        deps,
+        env,
+        mock_info(creator, &funds),
+        create_token_msg(symbol),
+    )
+}
+
+fn predicted_address(deps: Deps, env: Env, symbol: &str) -> String {
+    let msg = QueryMsg::PredictAddress {
+        symbol: symbol.to_string(),
+    };
+    let res: PredictAddressResponse = from_binary(&query(deps, env, msg).unwrap()).unwrap();
+    res.address
+}
+
+#[test]
+fn proper_initialization() {
+    let deps = setup(Some(100));
+    let res = query(deps.as_ref(), env(&deps.api), QueryMsg::Config {}).unwrap();
+    let config: ConfigResponse = from_binary(&res).unwrap();
+    assert_eq!(config.token_code_id, TOKEN_CODE_ID);
+    assert_eq!(config.creation_fee, Some(coin(100, "uluna")));
+    assert_eq!(config.admin, deps.api.addr_make("admin").to_string());
+}
+
+#[test]
+fn create_token_instantiates_at_predicted_address() {
+    let mut deps = setup(None);
+    let env = env(&deps.api);
+    let creator = deps.api.addr_make("creator");
+    let predicted = predicted_address(deps.as_ref(), env.clone(), "CASH");
+
+    let res = create_token(deps.as_mut(), env.clone(), creator.as_str(), "CASH", 0).unwrap();
+    assert_eq!(res.messages.len(), 1);
+    match &res.messages[0].msg {
+        CosmosMsg::Wasm(WasmMsg::Instantiate2 {
+            code_id, msg, salt, ..
+        }) => {
+            assert_eq!(*code_id, TOKEN_CODE_ID);
+            assert_eq!(salt, &Binary::from(b"CASH"));
+            let token_msg: TokenInstantiateMsg = from_binary(msg).unwrap();
+            assert_eq!(token_msg.symbol, "CASH");
+            assert_eq!(token_msg.mint, None);
+        }
+        msg => panic!("unexpected message: {:?}", msg),
+    }
+
+    let res = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::Token {
+            symbol: "CASH".to_string(),
+        },
+    )
+    .unwrap();
+    let token: TokenResponse = from_binary(&res).unwrap();
+    assert_eq!(token.address, predicted);
+    assert_eq!(token.creator, creator.to_string());
+    assert_eq!(token.created_at, env.block.time.seconds());
+
+    // symbols are unique
+    match create_token(deps.as_mut(), env, creator.as_str(), "CASH", 0).unwrap_err() {
+        ContractError::SymbolTaken { symbol } => assert_eq!(symbol, "CASH"),
+        e => panic!("unexpected error: {:?}", e),
+    }
+}
+
+#[test]
+fn creation_fee_is_paid_to_admin() {
+    let mut deps = setup(Some(100));
+    let env = env(&deps.api);
+    let creator = deps.api.addr_make("creator");
+
+    match create_token(deps.as_mut(), env.clone(), creator.as_str(), "CASH", 99).unwrap_err() {
+        ContractError::WrongCreationFee { fee } => assert_eq!(fee, coin(100, "uluna")),
+        e => panic!("unexpected error: {:?}", e),
+    }
+
+    let res = create_token(deps.as_mut(), env, creator.as_str(), "CASH", 100).unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Bank(BankMsg::Send {
+            to_address: deps.api.addr_make("admin").to_string(),
+            amount: vec![coin(100, "uluna")],
+        })
+    );
+}
+
+#[test]
+fn funds_are_rejected_without_creation_fee() {
+    let mut deps = setup(None);
+    let env = env(&deps.api);
+    let creator = deps.api.addr_make("creator");
+    match create_token(deps.as_mut(), env, creator.as_str(), "CASH", 1).unwrap_err() {
+        ContractError::UnexpectedFunds {} => {}
+        e => panic!("unexpected error: {:?}", e),
+    }
+}
+
+#[test]
+fn list_tokens_paginates() {
+    let mut deps = setup(None);
+    let env = env(&deps.api);
+    let alice = deps.api.addr_make("alice");
+    let bob = deps.api.addr_make("bob");
+    create_token(deps.as_mut(), env.clone(), alice.as_str(), "AAA", 0).unwrap();
+    create_token(deps.as_mut(), env.clone(), bob.as_str(), "BBB", 0).unwrap();
+    create_token(deps.as_mut(), env.clone(), alice.as_str(), "CCC", 0).unwrap();
+
+    let list = |start_after: Option<&str>, limit: u32| -> Vec<String> {
+        let msg = QueryMsg::ListTokens {
+            start_after: start_after.map(|symbol| symbol.to_string()),
+            limit: Some(limit),
+        };
+        let res: TokensResponse =
+            from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+        res.tokens.into_iter().map(|token| token.symbol).collect()
+    };
+    assert_eq!(list(None, 2), vec!["AAA", "BBB"]);
+    assert_eq!(list(Some("BBB"), 2), vec!["CCC"]);
+
+    let msg = QueryMsg::TokensByCreator {
+        creator: alice.to_string(),
+        start_after: None,
+        limit: None,
+    };
+    let res: TokensResponse =
+        from_binary(&query(deps.as_ref(), env.clone(), msg).unwrap()).unwrap();
+    let symbols: Vec<String> = res.tokens.into_iter().map(|token| token.symbol).collect();
+    assert_eq!(symbols, vec!["AAA", "CCC"]);
+
+    let res = query(
+        deps.as_ref(),
+        env,
+        QueryMsg::Token {
+            symbol: "DDD".to_string(),
+        },
+    );
+    assert_eq!(res, Err(StdError::not_found("Token")));
+}
+
+#[test]
+fn only_creator_can_execute_on_token() {
+    let mut deps = setup(None);
+    let env = env(&deps.api);
+    let creator = deps.api.addr_make("creator");
+    create_token(deps.as_mut(), env.clone(), creator.as_str(), "CASH", 0).unwrap();
+    let address = predicted_address(deps.as_ref(), env.clone(), "CASH");
+    let msg = ExecuteMsg::ExecuteOnToken {
+        symbol: "CASH".to_string(),
+        msg: Binary::from(br#"{"pause":{}}"#),
+    };
+
+    let other = deps.api.addr_make("other");
+    match execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(other.as_str(), &[]),
+        msg.clone(),
+    )
+    .unwrap_err()
+    {
+        ContractError::Unauthorized {} => {}
+        e => panic!("unexpected error: {:?}", e),
+    }
+
+    let res = execute(deps.as_mut(), env, mock_info(creator.as_str(), &[]), msg).unwrap();
+    assert_eq!(
+        res.messages[0].msg,
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: address,
+            msg: Binary::from(br#"{"pause":{}}"#),
+            funds: vec![],
+        })
+    );
+}
+
+#[test]
+fn only_admin_can_update_config() {
+    let mut deps = setup(None);
+    let env = env(&deps.api);
+    let msg = ExecuteMsg::UpdateConfig {
+        token_code_id: 8,
+        creation_fee: Some(coin(5, "uluna")),
+        admin: deps.api.addr_make("new_admin").to_string(),
+    };
+    let other = deps.api.addr_make("other");
+    match execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(other.as_str(), &[]),
+        msg.clone(),
+    )
+    .unwrap_err()
+    {
+        ContractError::Unauthorized {} => {}
+        e => panic!("unexpected error: {:?}", e),
+    }
+
+    let admin = deps.api.addr_make("admin");
+    execute(
+        deps.as_mut(),
+        env.clone(),
+        mock_info(admin.as_str(), &[]),
+        msg,
+    )
+    .unwrap();
+    let config: ConfigResponse =
+        from_binary(&query(deps.as_ref(), env, QueryMsg::Config {}).unwrap()).unwrap();
+    assert_eq!(config.token_code_id, 8);
+    assert_eq!(config.admin, deps.api.addr_make("new_admin").to_string());
+}
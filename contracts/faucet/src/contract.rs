@@ -0,0 +1,180 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+
+use crate::error::ContractError;
+use crate::state::{
+    read_block_claims, read_config, read_last_claim, store_block_claims, store_config,
+    store_last_claim, BlockClaims, Config,
+};
+
+use cosmwasm_std::{
+    to_binary, Binary, CanonicalAddr, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Response,
+    StdResult, Uint128, WasmMsg,
+};
+use cw20::Cw20ExecuteMsg;
+use shard_token::faucet::{
+    ConfigResponse, ExecuteMsg, InstantiateMsg, MigrateMsg, NextClaimAtResponse, QueryMsg,
+};
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    if msg.amount.is_zero() {
+        return Err(ContractError::InvalidAmount {});
+    }
+    store_config(
+        deps.storage,
+        &Config {
+            shard_token: deps.api.addr_canonicalize(&msg.shard_token)?,
+            admin: deps.api.addr_canonicalize(&msg.admin)?,
+            amount: msg.amount,
+            cooldown: msg.cooldown,
+            max_claims_per_block: msg.max_claims_per_block,
+        },
+    )?;
+
+    Ok(Response::default())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Claim {} => claim(deps, env, info),
+        ExecuteMsg::UpdateConfig {
+            admin,
+            amount,
+            cooldown,
+            max_claims_per_block,
+        } => update_config(deps, info, admin, amount, cooldown, max_claims_per_block),
+    }
+}
+
+pub fn claim(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let config: Config = read_config(deps.storage)?;
+    let claimer = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let now = env.block.time.seconds();
+
+    if let Some(next_claim_at) = next_claim_at(deps.as_ref(), &config, &claimer)? {
+        if now < next_claim_at {
+            return Err(ContractError::CooldownActive { next_claim_at });
+        }
+    }
+
+    // throttles claims from freshly created addresses
+    let mut block_claims = read_block_claims(deps.storage)?;
+    if block_claims.height != env.block.height {
+        block_claims = BlockClaims {
+            height: env.block.height,
+            count: 0,
+        };
+    }
+    if block_claims.count >= config.max_claims_per_block {
+        return Err(ContractError::BlockLimitReached {});
+    }
+    block_claims.count += 1;
+    store_block_claims(deps.storage, &block_claims)?;
+    store_last_claim(deps.storage, &claimer, now)?;
+
+    Ok(Response::new()
+        .add_message(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: deps.api.addr_humanize(&config.shard_token)?.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: info.sender.to_string(),
+                amount: config.amount,
+            })?,
+            funds: vec![],
+        }))
+        .add_attributes(vec![
+            ("action", "claim"),
+            ("recipient", info.sender.as_str()),
+            ("amount", &config.amount.to_string()),
+        ]))
+}
+
+pub fn update_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    admin: Option<String>,
+    amount: Option<Uint128>,
+    cooldown: Option<u64>,
+    max_claims_per_block: Option<u32>,
+) -> Result<Response, ContractError> {
+    let mut config: Config = read_config(deps.storage)?;
+    if config.admin != deps.api.addr_canonicalize(info.sender.as_str())? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if let Some(admin) = admin {
+        config.admin = deps.api.addr_canonicalize(&admin)?;
+    }
+    if let Some(amount) = amount {
+        if amount.is_zero() {
+            return Err(ContractError::InvalidAmount {});
+        }
+        config.amount = amount;
+    }
+    if let Some(cooldown) = cooldown {
+        config.cooldown = cooldown;
+    }
+    if let Some(max_claims_per_block) = max_claims_per_block {
+        config.max_claims_per_block = max_claims_per_block;
+    }
+    store_config(deps.storage, &config)?;
+
+    Ok(Response::new().add_attribute("action", "update_config"))
+}
+
+/// Earliest claim time of `address` with the current cooldown, `None` if it
+/// never claimed
+fn next_claim_at(deps: Deps, config: &Config, address: &CanonicalAddr) -> StdResult<Option<u64>> {
+    Ok(read_last_claim(deps.storage, address)?.map(|last_claim| last_claim + config.cooldown))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_binary(&query_config(deps)?),
+        QueryMsg::NextClaimAt { address } => to_binary(&query_next_claim_at(deps, env, address)?),
+    }
+}
+
+pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
+    let config: Config = read_config(deps.storage)?;
+    Ok(ConfigResponse {
+        shard_token: deps.api.addr_humanize(&config.shard_token)?.to_string(),
+        admin: deps.api.addr_humanize(&config.admin)?.to_string(),
+        amount: config.amount,
+        cooldown: config.cooldown,
+        max_claims_per_block: config.max_claims_per_block,
+    })
+}
+
+/// Only the cooldown is considered, a claim may still hit the block limit
+pub fn query_next_claim_at(
+    deps: Deps,
+    env: Env,
+    address: String,
+) -> StdResult<NextClaimAtResponse> {
+    let config: Config = read_config(deps.storage)?;
+    let address = deps.api.addr_canonicalize(&address)?;
+    let now = env.block.time.seconds();
+    let next_claim_at = next_claim_at(deps, &config, &address)?.map_or(now, |time| time.max(now));
+    Ok(NextClaimAtResponse {
+        next_claim_at,
+        can_claim: next_claim_at == now,
+    })
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    Ok(Response::default())
+}
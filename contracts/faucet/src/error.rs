@@ -0,0 +1,20 @@
+use cosmwasm_std::StdError;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Claim amount must be greater than zero")]
+    InvalidAmount {},
+
+    #[error("Cannot claim before {next_claim_at}")]
+    CooldownActive { next_claim_at: u64 },
+
+    #[error("Too many claims in this block, try again in the next one")]
+    BlockLimitReached {},
+}
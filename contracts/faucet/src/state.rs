@@ -0,0 +1,59 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::{CanonicalAddr, StdResult, Storage, Uint128};
+use cosmwasm_storage::{singleton, singleton_read, Bucket, ReadonlyBucket};
+
+static KEY_CONFIG: &[u8] = b"config";
+static KEY_BLOCK_CLAIMS: &[u8] = b"block_claims";
+
+static PREFIX_LAST_CLAIM: &[u8] = b"last_claim";
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Config {
+    pub shard_token: CanonicalAddr,
+    pub admin: CanonicalAddr,
+    pub amount: Uint128,
+    pub cooldown: u64,
+    pub max_claims_per_block: u32,
+}
+
+/// Claims counted in the last block with a claim
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct BlockClaims {
+    pub height: u64,
+    pub count: u32,
+}
+
+pub fn store_config(storage: &mut dyn Storage, config: &Config) -> StdResult<()> {
+    singleton(storage, KEY_CONFIG).save(config)
+}
+
+pub fn read_config(storage: &dyn Storage) -> StdResult<Config> {
+    singleton_read(storage, KEY_CONFIG).load()
+}
+
+pub fn store_block_claims(storage: &mut dyn Storage, block_claims: &BlockClaims) -> StdResult<()> {
+    singleton(storage, KEY_BLOCK_CLAIMS).save(block_claims)
+}
+
+pub fn read_block_claims(storage: &dyn Storage) -> StdResult<BlockClaims> {
+    Ok(singleton_read(storage, KEY_BLOCK_CLAIMS)
+        .may_load()?
+        .unwrap_or_default())
+}
+
+pub fn store_last_claim(
+    storage: &mut dyn Storage,
+    address: &CanonicalAddr,
+    time: u64,
+) -> StdResult<()> {
+    let mut last_claim_bucket: Bucket<u64> = Bucket::new(storage, PREFIX_LAST_CLAIM);
+    last_claim_bucket.save(address.as_slice(), &time)
+}
+
+/// Block time in seconds of the last claim of `address`
+pub fn read_last_claim(storage: &dyn Storage, address: &CanonicalAddr) -> StdResult<Option<u64>> {
+    let last_claim_bucket: ReadonlyBucket<u64> = ReadonlyBucket::new(storage, PREFIX_LAST_CLAIM);
+    last_claim_bucket.may_load(address.as_slice())
+}
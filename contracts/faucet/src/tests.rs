@@ -0,0 +1,154 @@
+use crate::contract::{execute, instantiate, query};
+use crate::error::ContractError;
+use cosmwasm_std::testing::{
+    mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
+};
+use cosmwasm_std::{
+    from_binary, to_binary, CosmosMsg, Env, OwnedDeps, Response, SubMsg, Timestamp, Uint128,
+    WasmMsg,
+};
+use cw20::Cw20ExecuteMsg;
+use shard_token::faucet::{
+    ConfigResponse, ExecuteMsg, InstantiateMsg, NextClaimAtResponse, QueryMsg,
+};
+
+fn env_at(height: u64, time: u64) -> Env {
+    let mut env = mock_env();
+    env.block.height = height;
+    env.block.time = Timestamp::from_seconds(time);
+    env
+}
+
+fn setup() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+    let mut deps = mock_dependencies(&[]);
+    let msg = InstantiateMsg {
+        shard_token: "shard0000".to_string(),
+        admin: "admin0000".to_string(),
+        amount: Uint128::from(100u128),
+        cooldown: 3600,
+        max_claims_per_block: 2,
+    };
+    instantiate(deps.as_mut(), env_at(1, 0), mock_info("addr0000", &[]), msg).unwrap();
+    deps
+}
+
+fn claim(
+    deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+    claimer: &str,
+    height: u64,
+    time: u64,
+) -> Result<Response, ContractError> {
+    execute(
+        deps.as_mut(),
+        env_at(height, time),
+        mock_info(claimer, &[]),
+        ExecuteMsg::Claim {},
+    )
+}
+
+fn next_claim_at(
+    deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>,
+    address: &str,
+    time: u64,
+) -> NextClaimAtResponse {
+    let res = query(
+        deps.as_ref(),
+        env_at(1, time),
+        QueryMsg::NextClaimAt {
+            address: address.to_string(),
+        },
+    )
+    .unwrap();
+    from_binary(&res).unwrap()
+}
+
+#[test]
+fn claim_dispenses_amount_once_per_cooldown() {
+    let mut deps = setup();
+    assert_eq!(
+        next_claim_at(&deps, "user0000", 10),
+        NextClaimAtResponse {
+            next_claim_at: 10,
+            can_claim: true,
+        }
+    );
+
+    let res = claim(&mut deps, "user0000", 10, 10).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "shard0000".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "user0000".to_string(),
+                amount: Uint128::from(100u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    );
+
+    assert_eq!(
+        claim(&mut deps, "user0000", 11, 3609),
+        Err(ContractError::CooldownActive {
+            next_claim_at: 3610
+        })
+    );
+    assert_eq!(
+        next_claim_at(&deps, "user0000", 3609),
+        NextClaimAtResponse {
+            next_claim_at: 3610,
+            can_claim: false,
+        }
+    );
+    claim(&mut deps, "user0000", 12, 3610).unwrap();
+}
+
+#[test]
+fn claims_are_limited_per_block() {
+    let mut deps = setup();
+    claim(&mut deps, "user0000", 10, 10).unwrap();
+    claim(&mut deps, "user0001", 10, 10).unwrap();
+    assert_eq!(
+        claim(&mut deps, "user0002", 10, 10),
+        Err(ContractError::BlockLimitReached {})
+    );
+    claim(&mut deps, "user0002", 11, 15).unwrap();
+}
+
+#[test]
+fn update_config_by_admin_only() {
+    let mut deps = setup();
+    let msg = ExecuteMsg::UpdateConfig {
+        admin: None,
+        amount: Some(Uint128::from(5u128)),
+        cooldown: Some(60),
+        max_claims_per_block: None,
+    };
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("user0000", &[]),
+        msg.clone(),
+    );
+    assert_eq!(err, Err(ContractError::Unauthorized {}));
+
+    execute(deps.as_mut(), mock_env(), mock_info("admin0000", &[]), msg).unwrap();
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+    let config: ConfigResponse = from_binary(&res).unwrap();
+    assert_eq!(config.amount, Uint128::from(5u128));
+    assert_eq!(config.cooldown, 60);
+    assert_eq!(config.max_claims_per_block, 2);
+
+    // the new cooldown applies to past claims as well
+    claim(&mut deps, "user0000", 10, 10).unwrap();
+    assert_eq!(next_claim_at(&deps, "user0000", 20).next_claim_at, 70);
+
+    let msg = ExecuteMsg::UpdateConfig {
+        admin: None,
+        amount: Some(Uint128::zero()),
+        cooldown: None,
+        max_claims_per_block: None,
+    };
+    let err = execute(deps.as_mut(), mock_env(), mock_info("admin0000", &[]), msg);
+    assert_eq!(err, Err(ContractError::InvalidAmount {}));
+}
@@ -0,0 +1,411 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+
+use crate::error::ContractError;
+use crate::querier::{query_total_supply_at, query_voting_power_at};
+use crate::state::{
+    next_proposal_id, read_ballot, read_ballots, read_config, read_proposal, read_proposals,
+    store_ballot, store_config, store_proposal, Ballot, Config, Proposal,
+};
+
+use cosmwasm_std::{
+    from_binary, to_binary, Binary, CosmosMsg, Decimal, Deps, DepsMut, Env, MessageInfo, Response,
+    StdError, StdResult, Uint128, WasmMsg,
+};
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use shard_token::gov::{
+    ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, ProposalResponse,
+    ProposalStatus, ProposalsResponse, QueryMsg, VoteOption, VoteResponse, VotesResponse,
+};
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    validate_config(msg.quorum, msg.threshold)?;
+    store_config(
+        deps.storage,
+        &Config {
+            shard_token: deps.api.addr_canonicalize(&msg.shard_token)?,
+            quorum: msg.quorum,
+            threshold: msg.threshold,
+            voting_period: msg.voting_period,
+            proposal_deposit: msg.proposal_deposit,
+        },
+    )?;
+
+    Ok(Response::default())
+}
+
+fn validate_config(quorum: Decimal, threshold: Decimal) -> Result<(), ContractError> {
+    // a threshold of 1 could never be exceeded
+    if quorum > Decimal::one() || threshold >= Decimal::one() {
+        return Err(ContractError::InvalidConfig {});
+    }
+    Ok(())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
+        ExecuteMsg::CastVote { proposal_id, vote } => cast_vote(deps, env, info, proposal_id, vote),
+        ExecuteMsg::ExecuteProposal { proposal_id } => execute_proposal(deps, env, proposal_id),
+        ExecuteMsg::CloseProposal { proposal_id } => close_proposal(deps, env, proposal_id),
+        ExecuteMsg::UpdateConfig {
+            quorum,
+            threshold,
+            voting_period,
+            proposal_deposit,
+        } => update_config(
+            deps,
+            env,
+            info,
+            quorum,
+            threshold,
+            voting_period,
+            proposal_deposit,
+        ),
+    }
+}
+
+pub fn receive_cw20(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    cw20_msg: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    let config: Config = read_config(deps.storage)?;
+    // only the shard token contract can execute this message
+    if config.shard_token != deps.api.addr_canonicalize(info.sender.as_str())? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    match from_binary(&cw20_msg.msg)? {
+        Cw20HookMsg::CreateProposal {
+            title,
+            description,
+            msgs,
+        } => create_proposal(
+            deps,
+            env,
+            cw20_msg.sender,
+            cw20_msg.amount,
+            title,
+            description,
+            msgs,
+        ),
+    }
+}
+
+pub fn create_proposal(
+    deps: DepsMut,
+    env: Env,
+    proposer: String,
+    deposit: Uint128,
+    title: String,
+    description: String,
+    msgs: Vec<CosmosMsg>,
+) -> Result<Response, ContractError> {
+    let config: Config = read_config(deps.storage)?;
+    if deposit < config.proposal_deposit {
+        return Err(ContractError::InsufficientDeposit {
+            required: config.proposal_deposit.to_string(),
+        });
+    }
+
+    // voting power is snapshotted at the end of the previous block, so it
+    // cannot be moved around within the block the proposal is created in
+    let snapshot_height = env.block.height.saturating_sub(1);
+    let shard_token = deps.api.addr_humanize(&config.shard_token)?;
+    let total_power = query_total_supply_at(&deps.querier, shard_token, snapshot_height)?;
+
+    let id = next_proposal_id(deps.storage)?;
+    store_proposal(
+        deps.storage,
+        id,
+        &Proposal {
+            proposer: deps.api.addr_canonicalize(&proposer)?,
+            title,
+            description,
+            msgs,
+            deposit,
+            snapshot_height,
+            end_height: env.block.height + config.voting_period,
+            quorum: config.quorum,
+            threshold: config.threshold,
+            total_power,
+            status: ProposalStatus::Open,
+            yes_votes: Uint128::zero(),
+            no_votes: Uint128::zero(),
+            abstain_votes: Uint128::zero(),
+        },
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "create_proposal"),
+        ("proposal_id", &id.to_string()),
+        ("proposer", &proposer),
+        ("deposit", &deposit.to_string()),
+    ]))
+}
+
+pub fn cast_vote(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    proposal_id: u64,
+    vote: VoteOption,
+) -> Result<Response, ContractError> {
+    let mut proposal = load_proposal(deps.as_ref(), proposal_id)?;
+    if proposal.status != ProposalStatus::Open || env.block.height > proposal.end_height {
+        return Err(ContractError::VotingEnded {});
+    }
+    let voter = deps.api.addr_canonicalize(info.sender.as_str())?;
+    if read_ballot(deps.storage, proposal_id, &voter)?.is_some() {
+        return Err(ContractError::AlreadyVoted {});
+    }
+
+    let config: Config = read_config(deps.storage)?;
+    let weight = query_voting_power_at(
+        &deps.querier,
+        deps.api.addr_humanize(&config.shard_token)?,
+        info.sender.to_string(),
+        proposal.snapshot_height,
+    )?;
+    if weight.is_zero() {
+        return Err(ContractError::NoVotingPower {});
+    }
+
+    match vote {
+        VoteOption::Yes => proposal.yes_votes += weight,
+        VoteOption::No => proposal.no_votes += weight,
+        VoteOption::Abstain => proposal.abstain_votes += weight,
+    }
+    store_proposal(deps.storage, proposal_id, &proposal)?;
+    store_ballot(deps.storage, proposal_id, &voter, &Ballot { vote, weight })?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "cast_vote"),
+        ("proposal_id", &proposal_id.to_string()),
+        ("voter", info.sender.as_str()),
+        ("vote", vote_str(vote)),
+        ("weight", &weight.to_string()),
+    ]))
+}
+
+fn vote_str(vote: VoteOption) -> &'static str {
+    match vote {
+        VoteOption::Yes => "yes",
+        VoteOption::No => "no",
+        VoteOption::Abstain => "abstain",
+    }
+}
+
+pub fn execute_proposal(
+    deps: DepsMut,
+    env: Env,
+    proposal_id: u64,
+) -> Result<Response, ContractError> {
+    let mut proposal = load_ended_proposal(deps.as_ref(), &env, proposal_id)?;
+    if !proposal.passed() {
+        return Err(ContractError::NotPassed {});
+    }
+    proposal.status = ProposalStatus::Executed;
+    store_proposal(deps.storage, proposal_id, &proposal)?;
+
+    // a passed proposal always reached quorum, so the deposit goes back
+    let refund = refund_deposit(deps.as_ref(), &proposal)?;
+    Ok(Response::new()
+        .add_messages(proposal.msgs)
+        .add_message(refund)
+        .add_attributes(vec![
+            ("action", "execute_proposal"),
+            ("proposal_id", &proposal_id.to_string()),
+        ]))
+}
+
+pub fn close_proposal(
+    deps: DepsMut,
+    env: Env,
+    proposal_id: u64,
+) -> Result<Response, ContractError> {
+    let mut proposal = load_ended_proposal(deps.as_ref(), &env, proposal_id)?;
+    if proposal.passed() {
+        return Err(ContractError::Passed {});
+    }
+    proposal.status = ProposalStatus::Closed;
+    store_proposal(deps.storage, proposal_id, &proposal)?;
+
+    // deposits of proposals that did not even reach quorum stay with the contract
+    let quorum_reached = proposal.quorum_reached();
+    let mut res = Response::new();
+    if quorum_reached {
+        res = res.add_message(refund_deposit(deps.as_ref(), &proposal)?);
+    }
+    Ok(res.add_attributes(vec![
+        ("action", "close_proposal"),
+        ("proposal_id", &proposal_id.to_string()),
+        ("deposit_refunded", &quorum_reached.to_string()),
+    ]))
+}
+
+pub fn update_config(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    quorum: Decimal,
+    threshold: Decimal,
+    voting_period: u64,
+    proposal_deposit: Uint128,
+) -> Result<Response, ContractError> {
+    // configuration changes go through proposals
+    if info.sender != env.contract.address {
+        return Err(ContractError::Unauthorized {});
+    }
+    validate_config(quorum, threshold)?;
+
+    let mut config: Config = read_config(deps.storage)?;
+    config.quorum = quorum;
+    config.threshold = threshold;
+    config.voting_period = voting_period;
+    config.proposal_deposit = proposal_deposit;
+    store_config(deps.storage, &config)?;
+
+    Ok(Response::new().add_attribute("action", "update_config"))
+}
+
+fn load_proposal(deps: Deps, id: u64) -> StdResult<Proposal> {
+    match read_proposal(deps.storage, id)? {
+        Some(proposal) => Ok(proposal),
+        None => Err(StdError::not_found("Proposal")),
+    }
+}
+
+/// Loads a proposal that can be settled: voting ended and it is still open
+fn load_ended_proposal(deps: Deps, env: &Env, id: u64) -> Result<Proposal, ContractError> {
+    let proposal = load_proposal(deps, id)?;
+    if proposal.status != ProposalStatus::Open {
+        return Err(ContractError::AlreadySettled {});
+    }
+    if env.block.height <= proposal.end_height {
+        return Err(ContractError::VotingNotEnded {});
+    }
+    Ok(proposal)
+}
+
+fn refund_deposit(deps: Deps, proposal: &Proposal) -> StdResult<CosmosMsg> {
+    let config: Config = read_config(deps.storage)?;
+    Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: deps.api.addr_humanize(&config.shard_token)?.to_string(),
+        msg: to_binary(&Cw20ExecuteMsg::Transfer {
+            recipient: deps.api.addr_humanize(&proposal.proposer)?.to_string(),
+            amount: proposal.deposit,
+        })?,
+        funds: vec![],
+    }))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_binary(&query_config(deps)?),
+        QueryMsg::Proposal { proposal_id } => to_binary(&query_proposal(deps, env, proposal_id)?),
+        QueryMsg::Proposals { start_after, limit } => {
+            to_binary(&query_proposals(deps, env, start_after, limit)?)
+        }
+        QueryMsg::Votes {
+            proposal_id,
+            start_after,
+            limit,
+        } => to_binary(&query_votes(deps, proposal_id, start_after, limit)?),
+    }
+}
+
+pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
+    let config: Config = read_config(deps.storage)?;
+    Ok(ConfigResponse {
+        shard_token: deps.api.addr_humanize(&config.shard_token)?.to_string(),
+        quorum: config.quorum,
+        threshold: config.threshold,
+        voting_period: config.voting_period,
+        proposal_deposit: config.proposal_deposit,
+    })
+}
+
+pub fn query_proposal(deps: Deps, env: Env, proposal_id: u64) -> StdResult<ProposalResponse> {
+    let proposal = load_proposal(deps, proposal_id)?;
+    proposal_response(deps, &env, proposal_id, proposal)
+}
+
+pub fn query_proposals(
+    deps: Deps,
+    env: Env,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<ProposalsResponse> {
+    let proposals = read_proposals(deps.storage, start_after, limit)?
+        .into_iter()
+        .map(|(id, proposal)| proposal_response(deps, &env, id, proposal))
+        .collect::<StdResult<Vec<ProposalResponse>>>()?;
+    Ok(ProposalsResponse { proposals })
+}
+
+fn proposal_response(
+    deps: Deps,
+    env: &Env,
+    id: u64,
+    proposal: Proposal,
+) -> StdResult<ProposalResponse> {
+    Ok(ProposalResponse {
+        id,
+        proposer: deps.api.addr_humanize(&proposal.proposer)?.to_string(),
+        status: proposal.current_status(env.block.height),
+        title: proposal.title,
+        description: proposal.description,
+        msgs: proposal.msgs,
+        deposit: proposal.deposit,
+        snapshot_height: proposal.snapshot_height,
+        end_height: proposal.end_height,
+        yes_votes: proposal.yes_votes,
+        no_votes: proposal.no_votes,
+        abstain_votes: proposal.abstain_votes,
+        total_power: proposal.total_power,
+    })
+}
+
+pub fn query_votes(
+    deps: Deps,
+    proposal_id: u64,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<VotesResponse> {
+    let start_after = match start_after {
+        Some(voter) => Some(deps.api.addr_canonicalize(&voter)?),
+        None => None,
+    };
+    let votes = read_ballots(deps.storage, proposal_id, start_after, limit)?
+        .into_iter()
+        .map(|(voter, ballot)| {
+            Ok(VoteResponse {
+                voter: deps.api.addr_humanize(&voter)?.to_string(),
+                vote: ballot.vote,
+                weight: ballot.weight,
+            })
+        })
+        .collect::<StdResult<Vec<VoteResponse>>>()?;
+    Ok(VotesResponse { votes })
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    Ok(Response::default())
+}
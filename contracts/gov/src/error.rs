@@ -0,0 +1,38 @@
+use cosmwasm_std::StdError;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Quorum and threshold must be between 0 and 1")]
+    InvalidConfig {},
+
+    #[error("Deposit must be at least {required}")]
+    InsufficientDeposit { required: String },
+
+    #[error("No voting power at the proposal snapshot")]
+    NoVotingPower {},
+
+    #[error("Already voted on this proposal")]
+    AlreadyVoted {},
+
+    #[error("Voting period ended")]
+    VotingEnded {},
+
+    #[error("Voting period not ended")]
+    VotingNotEnded {},
+
+    #[error("Proposal did not pass")]
+    NotPassed {},
+
+    #[error("Proposal passed, it must be executed")]
+    Passed {},
+
+    #[error("Proposal was already settled")]
+    AlreadySettled {},
+}
@@ -0,0 +1,110 @@
+use cosmwasm_std::testing::{MockApi, MockQuerier, MockStorage, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{
+    from_binary, from_slice, to_binary, Coin, ContractResult, Empty, OwnedDeps, Querier,
+    QuerierResult, QueryRequest, SystemError, SystemResult, Uint128, WasmQuery,
+};
+
+use crate::querier::{TokenQueryMsg, TotalSupplyResponse, VotingPowerResponse};
+
+/// mock_dependencies is a drop-in replacement for cosmwasm_std::testing::mock_dependencies
+/// this uses our CustomQuerier.
+pub fn mock_dependencies(
+    contract_balance: &[Coin],
+) -> OwnedDeps<MockStorage, MockApi, WasmMockQuerier> {
+    let custom_querier: WasmMockQuerier =
+        WasmMockQuerier::new(MockQuerier::new(&[(MOCK_CONTRACT_ADDR, contract_balance)]));
+
+    OwnedDeps {
+        api: MockApi::default(),
+        storage: MockStorage::default(),
+        querier: custom_querier,
+    }
+}
+
+pub struct WasmMockQuerier {
+    base: MockQuerier<Empty>,
+    token_querier: TokenQuerier,
+}
+
+// Snapshots of the shard token: voting power per address and the total supply,
+// both as of the end of `height`
+#[derive(Clone, Default)]
+pub struct TokenQuerier {
+    height: u64,
+    voting_powers: Vec<(String, u128)>,
+    total_supply: u128,
+}
+
+impl Querier for WasmMockQuerier {
+    fn raw_query(&self, bin_request: &[u8]) -> QuerierResult {
+        // MockQuerier doesn't support Custom, so we ignore it completely here
+        let request: QueryRequest<Empty> = match from_slice(bin_request) {
+            Ok(v) => v,
+            Err(e) => {
+                return SystemResult::Err(SystemError::InvalidRequest {
+                    error: format!("Parsing query request: {}", e),
+                    request: bin_request.into(),
+                })
+            }
+        };
+        self.handle_query(&request)
+    }
+}
+
+impl WasmMockQuerier {
+    pub fn handle_query(&self, request: &QueryRequest<Empty>) -> QuerierResult {
+        match &request {
+            QueryRequest::Wasm(WasmQuery::Smart {
+                contract_addr: _,
+                msg,
+            }) => match from_binary(msg) {
+                Ok(TokenQueryMsg::VotingPowerAt { address, height }) => {
+                    let voting_power = match height == self.token_querier.height {
+                        true => self
+                            .token_querier
+                            .voting_powers
+                            .iter()
+                            .find(|(voter, _)| voter == &address)
+                            .map_or(0, |(_, voting_power)| *voting_power),
+                        false => 0,
+                    };
+                    SystemResult::Ok(ContractResult::from(to_binary(&VotingPowerResponse {
+                        voting_power: Uint128::from(voting_power),
+                    })))
+                }
+                Ok(TokenQueryMsg::TotalSupplyAt { .. }) => {
+                    SystemResult::Ok(ContractResult::from(to_binary(&TotalSupplyResponse {
+                        total_supply: Uint128::from(self.token_querier.total_supply),
+                    })))
+                }
+                _ => panic!("query not mocked"),
+            },
+            _ => self.base.handle_query(request),
+        }
+    }
+}
+
+impl WasmMockQuerier {
+    pub fn new(base: MockQuerier<Empty>) -> Self {
+        WasmMockQuerier {
+            base,
+            token_querier: TokenQuerier::default(),
+        }
+    }
+
+    pub fn with_token_snapshot(
+        &mut self,
+        height: u64,
+        voting_powers: &[(&str, u128)],
+        total_supply: u128,
+    ) {
+        self.token_querier = TokenQuerier {
+            height,
+            voting_powers: voting_powers
+                .iter()
+                .map(|(voter, voting_power)| (voter.to_string(), *voting_power))
+                .collect(),
+            total_supply,
+        };
+    }
+}
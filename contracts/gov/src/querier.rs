@@ -0,0 +1,51 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::{to_binary, Addr, QuerierWrapper, QueryRequest, StdResult, Uint128, WasmQuery};
+
+/// The snapshot queries of the shard token used to weigh votes
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TokenQueryMsg {
+    VotingPowerAt { address: String, height: u64 },
+    TotalSupplyAt { height: u64 },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VotingPowerResponse {
+    pub voting_power: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TotalSupplyResponse {
+    pub total_supply: Uint128,
+}
+
+/// Voting power delegated to `address` at the end of block `height`
+pub fn query_voting_power_at(
+    querier: &QuerierWrapper,
+    shard_token: Addr,
+    address: String,
+    height: u64,
+) -> StdResult<Uint128> {
+    let res: VotingPowerResponse = querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: shard_token.to_string(),
+        msg: to_binary(&TokenQueryMsg::VotingPowerAt { address, height })?,
+    }))?;
+
+    Ok(res.voting_power)
+}
+
+/// Total supply of the token at the end of block `height`
+pub fn query_total_supply_at(
+    querier: &QuerierWrapper,
+    shard_token: Addr,
+    height: u64,
+) -> StdResult<Uint128> {
+    let res: TotalSupplyResponse = querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: shard_token.to_string(),
+        msg: to_binary(&TokenQueryMsg::TotalSupplyAt { height })?,
+    }))?;
+
+    Ok(res.total_supply)
+}
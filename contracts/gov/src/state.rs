@@ -0,0 +1,186 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::{CanonicalAddr, CosmosMsg, Decimal, Order, StdResult, Storage, Uint128};
+use cosmwasm_storage::{singleton, singleton_read, Bucket, ReadonlyBucket};
+use shard_token::gov::{ProposalStatus, VoteOption};
+
+static KEY_CONFIG: &[u8] = b"config";
+static KEY_PROPOSAL_COUNT: &[u8] = b"proposal_count";
+
+static PREFIX_PROPOSAL: &[u8] = b"proposal";
+static PREFIX_VOTE: &[u8] = b"vote";
+
+// settings for pagination
+const MAX_LIMIT: u32 = 30;
+const DEFAULT_LIMIT: u32 = 10;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Config {
+    pub shard_token: CanonicalAddr,
+    pub quorum: Decimal,
+    pub threshold: Decimal,
+    pub voting_period: u64,
+    pub proposal_deposit: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Proposal {
+    pub proposer: CanonicalAddr,
+    pub title: String,
+    pub description: String,
+    pub msgs: Vec<CosmosMsg>,
+    pub deposit: Uint128,
+    pub snapshot_height: u64,
+    pub end_height: u64,
+    /// Quorum and threshold are fixed when the proposal is created
+    pub quorum: Decimal,
+    pub threshold: Decimal,
+    pub total_power: Uint128,
+    /// Only `Open`, `Executed` and `Closed` are stored, the outcome of an
+    /// ended proposal is derived from its tally
+    pub status: ProposalStatus,
+    pub yes_votes: Uint128,
+    pub no_votes: Uint128,
+    pub abstain_votes: Uint128,
+}
+
+impl Proposal {
+    pub fn quorum_reached(&self) -> bool {
+        let votes = self.yes_votes + self.no_votes + self.abstain_votes;
+        !self.total_power.is_zero() && Decimal::from_ratio(votes, self.total_power) >= self.quorum
+    }
+
+    /// Abstentions count towards the quorum only, the threshold is the share
+    /// of yes votes among yes and no votes
+    pub fn passed(&self) -> bool {
+        let decisive = self.yes_votes + self.no_votes;
+        self.quorum_reached()
+            && !decisive.is_zero()
+            && Decimal::from_ratio(self.yes_votes, decisive) > self.threshold
+    }
+
+    /// Status as seen at block `height`
+    pub fn current_status(&self, height: u64) -> ProposalStatus {
+        match self.status {
+            ProposalStatus::Open if height > self.end_height => {
+                if self.passed() {
+                    ProposalStatus::Passed
+                } else {
+                    ProposalStatus::Rejected
+                }
+            }
+            status => status,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Ballot {
+    pub vote: VoteOption,
+    pub weight: Uint128,
+}
+
+pub fn store_config(storage: &mut dyn Storage, config: &Config) -> StdResult<()> {
+    singleton(storage, KEY_CONFIG).save(config)
+}
+
+pub fn read_config(storage: &dyn Storage) -> StdResult<Config> {
+    singleton_read(storage, KEY_CONFIG).load()
+}
+
+/// Id for the next proposal, ids start at 1
+pub fn next_proposal_id(storage: &mut dyn Storage) -> StdResult<u64> {
+    let id = singleton_read(storage, KEY_PROPOSAL_COUNT)
+        .may_load()?
+        .unwrap_or(0u64)
+        + 1;
+    singleton(storage, KEY_PROPOSAL_COUNT).save(&id)?;
+    Ok(id)
+}
+
+pub fn store_proposal(storage: &mut dyn Storage, id: u64, proposal: &Proposal) -> StdResult<()> {
+    let mut proposal_bucket: Bucket<Proposal> = Bucket::new(storage, PREFIX_PROPOSAL);
+    proposal_bucket.save(&id.to_be_bytes(), proposal)
+}
+
+pub fn read_proposal(storage: &dyn Storage, id: u64) -> StdResult<Option<Proposal>> {
+    let proposal_bucket: ReadonlyBucket<Proposal> = ReadonlyBucket::new(storage, PREFIX_PROPOSAL);
+    proposal_bucket.may_load(&id.to_be_bytes())
+}
+
+pub fn read_proposals(
+    storage: &dyn Storage,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Vec<(u64, Proposal)>> {
+    let proposal_bucket: ReadonlyBucket<Proposal> = ReadonlyBucket::new(storage, PREFIX_PROPOSAL);
+
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    // ids are stored big endian, so the first key after `start_after` is its successor
+    let start = start_after.map(|id| (id + 1).to_be_bytes().to_vec());
+
+    proposal_bucket
+        .range(start.as_deref(), None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (k, v) = item?;
+            let mut id = [0u8; 8];
+            id.copy_from_slice(&k);
+            Ok((u64::from_be_bytes(id), v))
+        })
+        .collect()
+}
+
+pub fn store_ballot(
+    storage: &mut dyn Storage,
+    proposal_id: u64,
+    voter: &CanonicalAddr,
+    ballot: &Ballot,
+) -> StdResult<()> {
+    let mut vote_bucket: Bucket<Ballot> =
+        Bucket::multilevel(storage, &[PREFIX_VOTE, &proposal_id.to_be_bytes()]);
+    vote_bucket.save(voter.as_slice(), ballot)
+}
+
+pub fn read_ballot(
+    storage: &dyn Storage,
+    proposal_id: u64,
+    voter: &CanonicalAddr,
+) -> StdResult<Option<Ballot>> {
+    let vote_bucket: ReadonlyBucket<Ballot> =
+        ReadonlyBucket::multilevel(storage, &[PREFIX_VOTE, &proposal_id.to_be_bytes()]);
+    vote_bucket.may_load(voter.as_slice())
+}
+
+/// Ballots cast on a proposal ordered by canonical voter address
+pub fn read_ballots(
+    storage: &dyn Storage,
+    proposal_id: u64,
+    start_after: Option<CanonicalAddr>,
+    limit: Option<u32>,
+) -> StdResult<Vec<(CanonicalAddr, Ballot)>> {
+    let vote_bucket: ReadonlyBucket<Ballot> =
+        ReadonlyBucket::multilevel(storage, &[PREFIX_VOTE, &proposal_id.to_be_bytes()]);
+
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = calc_range_start(start_after);
+
+    vote_bucket
+        .range(start.as_deref(), None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (k, v) = item?;
+            Ok((CanonicalAddr::from(k), v))
+        })
+        .collect()
+}
+
+// the first key after the provided key is the key with a 0 byte appended
+fn calc_range_start(start_after: Option<CanonicalAddr>) -> Option<Vec<u8>> {
+    start_after.map(|addr| {
+        let mut v = addr.as_slice().to_vec();
+        v.push(0);
+        v
+    })
+}
@@ -0,0 +1,360 @@
+use crate::contract::{execute, instantiate, query};
+use crate::error::ContractError;
+use crate::mock_querier::{mock_dependencies, WasmMockQuerier};
+use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{
+    from_binary, to_binary, BankMsg, CosmosMsg, Decimal, DepsMut, Env, OwnedDeps, Response,
+    StdError, SubMsg, Uint128, WasmMsg,
+};
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use shard_token::gov::{
+    ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, ProposalResponse, ProposalStatus,
+    ProposalsResponse, QueryMsg, VoteOption, VotesResponse,
+};
+
+fn env_at(height: u64) -> Env {
+    let mut env = mock_env();
+    env.block.height = height;
+    env
+}
+
+fn setup() -> OwnedDeps<MockStorage, MockApi, WasmMockQuerier> {
+    let mut deps = mock_dependencies(&[]);
+    // voting power is only known at the end of block 99
+    deps.querier.with_token_snapshot(
+        99,
+        &[("voter0000", 600), ("voter0001", 300), ("voter0002", 100)],
+        1000,
+    );
+    let msg = InstantiateMsg {
+        shard_token: "shard0000".to_string(),
+        quorum: Decimal::percent(40),
+        threshold: Decimal::percent(50),
+        voting_period: 100,
+        proposal_deposit: Uint128::from(50u128),
+    };
+    instantiate(deps.as_mut(), env_at(1), mock_info("addr0000", &[]), msg).unwrap();
+    deps
+}
+
+fn bank_msg() -> CosmosMsg {
+    CosmosMsg::Bank(BankMsg::Send {
+        to_address: "grantee0000".to_string(),
+        amount: vec![],
+    })
+}
+
+fn propose(deps: DepsMut, deposit: u128) -> Result<Response, ContractError> {
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "proposer0000".to_string(),
+        amount: Uint128::from(deposit),
+        msg: to_binary(&Cw20HookMsg::CreateProposal {
+            title: "Grant".to_string(),
+            description: "Pay a grant".to_string(),
+            msgs: vec![bank_msg()],
+        })
+        .unwrap(),
+    });
+    execute(deps, env_at(100), mock_info("shard0000", &[]), msg)
+}
+
+fn vote(deps: DepsMut, voter: &str, vote: VoteOption) -> Result<Response, ContractError> {
+    execute(
+        deps,
+        env_at(150),
+        mock_info(voter, &[]),
+        ExecuteMsg::CastVote {
+            proposal_id: 1,
+            vote,
+        },
+    )
+}
+
+fn refund_msg(amount: u128) -> SubMsg {
+    SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: "shard0000".to_string(),
+        msg: to_binary(&Cw20ExecuteMsg::Transfer {
+            recipient: "proposer0000".to_string(),
+            amount: Uint128::from(amount),
+        })
+        .unwrap(),
+        funds: vec![],
+    }))
+}
+
+fn proposal(
+    deps: &OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+    height: u64,
+) -> ProposalResponse {
+    let res = query(
+        deps.as_ref(),
+        env_at(height),
+        QueryMsg::Proposal { proposal_id: 1 },
+    )
+    .unwrap();
+    from_binary(&res).unwrap()
+}
+
+#[test]
+fn proper_initialization() {
+    let deps = setup();
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+    let config: ConfigResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        config,
+        ConfigResponse {
+            shard_token: "shard0000".to_string(),
+            quorum: Decimal::percent(40),
+            threshold: Decimal::percent(50),
+            voting_period: 100,
+            proposal_deposit: Uint128::from(50u128),
+        }
+    );
+
+    let mut deps = mock_dependencies(&[]);
+    let msg = InstantiateMsg {
+        shard_token: "shard0000".to_string(),
+        quorum: Decimal::percent(40),
+        threshold: Decimal::one(),
+        voting_period: 100,
+        proposal_deposit: Uint128::from(50u128),
+    };
+    let err = instantiate(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg);
+    assert_eq!(err, Err(ContractError::InvalidConfig {}));
+}
+
+#[test]
+fn create_proposal_requires_deposit() {
+    let mut deps = setup();
+    let err = propose(deps.as_mut(), 49).unwrap_err();
+    assert_eq!(
+        err,
+        ContractError::InsufficientDeposit {
+            required: "50".to_string()
+        }
+    );
+
+    // only deposits in the shard token are accepted
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "proposer0000".to_string(),
+        amount: Uint128::from(50u128),
+        msg: to_binary(&Cw20HookMsg::CreateProposal {
+            title: "Grant".to_string(),
+            description: "Pay a grant".to_string(),
+            msgs: vec![],
+        })
+        .unwrap(),
+    });
+    let err = execute(deps.as_mut(), env_at(100), mock_info("other0000", &[]), msg);
+    assert_eq!(err, Err(ContractError::Unauthorized {}));
+
+    propose(deps.as_mut(), 50).unwrap();
+    let proposal = proposal(&deps, 100);
+    assert_eq!(proposal.proposer, "proposer0000");
+    assert_eq!(proposal.msgs, vec![bank_msg()]);
+    assert_eq!(proposal.snapshot_height, 99);
+    assert_eq!(proposal.end_height, 200);
+    assert_eq!(proposal.total_power, Uint128::from(1000u128));
+    assert_eq!(proposal.status, ProposalStatus::Open);
+}
+
+#[test]
+fn votes_are_weighted_by_snapshot_voting_power() {
+    let mut deps = setup();
+    propose(deps.as_mut(), 50).unwrap();
+
+    vote(deps.as_mut(), "voter0000", VoteOption::Yes).unwrap();
+    vote(deps.as_mut(), "voter0002", VoteOption::Abstain).unwrap();
+    assert_eq!(
+        vote(deps.as_mut(), "voter0000", VoteOption::No),
+        Err(ContractError::AlreadyVoted {})
+    );
+    assert_eq!(
+        vote(deps.as_mut(), "nobody0000", VoteOption::Yes),
+        Err(ContractError::NoVotingPower {})
+    );
+
+    let proposal = proposal(&deps, 150);
+    assert_eq!(proposal.yes_votes, Uint128::from(600u128));
+    assert_eq!(proposal.no_votes, Uint128::zero());
+    assert_eq!(proposal.abstain_votes, Uint128::from(100u128));
+
+    let err = execute(
+        deps.as_mut(),
+        env_at(201),
+        mock_info("voter0001", &[]),
+        ExecuteMsg::CastVote {
+            proposal_id: 1,
+            vote: VoteOption::No,
+        },
+    );
+    assert_eq!(err, Err(ContractError::VotingEnded {}));
+}
+
+#[test]
+fn passed_proposal_executes_its_messages() {
+    let mut deps = setup();
+    propose(deps.as_mut(), 50).unwrap();
+    vote(deps.as_mut(), "voter0000", VoteOption::Yes).unwrap();
+    vote(deps.as_mut(), "voter0001", VoteOption::No).unwrap();
+
+    let msg = ExecuteMsg::ExecuteProposal { proposal_id: 1 };
+    let err = execute(
+        deps.as_mut(),
+        env_at(200),
+        mock_info("anyone0000", &[]),
+        msg.clone(),
+    );
+    assert_eq!(err, Err(ContractError::VotingNotEnded {}));
+    assert_eq!(proposal(&deps, 201).status, ProposalStatus::Passed);
+
+    let res = execute(
+        deps.as_mut(),
+        env_at(201),
+        mock_info("anyone0000", &[]),
+        msg.clone(),
+    )
+    .unwrap();
+    assert_eq!(res.messages, vec![SubMsg::new(bank_msg()), refund_msg(50)]);
+    assert_eq!(proposal(&deps, 201).status, ProposalStatus::Executed);
+
+    let err = execute(
+        deps.as_mut(),
+        env_at(202),
+        mock_info("anyone0000", &[]),
+        msg,
+    );
+    assert_eq!(err, Err(ContractError::AlreadySettled {}));
+}
+
+#[test]
+fn rejected_proposal_keeps_deposit_without_quorum() {
+    let mut deps = setup();
+    propose(deps.as_mut(), 50).unwrap();
+    // 300 out of 1000 is below the 40% quorum
+    vote(deps.as_mut(), "voter0001", VoteOption::Yes).unwrap();
+    assert_eq!(proposal(&deps, 201).status, ProposalStatus::Rejected);
+
+    let err = execute(
+        deps.as_mut(),
+        env_at(201),
+        mock_info("anyone0000", &[]),
+        ExecuteMsg::ExecuteProposal { proposal_id: 1 },
+    );
+    assert_eq!(err, Err(ContractError::NotPassed {}));
+
+    let res = execute(
+        deps.as_mut(),
+        env_at(201),
+        mock_info("anyone0000", &[]),
+        ExecuteMsg::CloseProposal { proposal_id: 1 },
+    )
+    .unwrap();
+    assert!(res.messages.is_empty());
+    assert_eq!(proposal(&deps, 201).status, ProposalStatus::Closed);
+}
+
+#[test]
+fn rejected_proposal_refunds_deposit_with_quorum() {
+    let mut deps = setup();
+    propose(deps.as_mut(), 50).unwrap();
+    vote(deps.as_mut(), "voter0000", VoteOption::No).unwrap();
+
+    let res = execute(
+        deps.as_mut(),
+        env_at(201),
+        mock_info("anyone0000", &[]),
+        ExecuteMsg::CloseProposal { proposal_id: 1 },
+    )
+    .unwrap();
+    assert_eq!(res.messages, vec![refund_msg(50)]);
+}
+
+#[test]
+fn update_config_only_through_proposals() {
+    let mut deps = setup();
+    let msg = ExecuteMsg::UpdateConfig {
+        quorum: Decimal::percent(20),
+        threshold: Decimal::percent(60),
+        voting_period: 10,
+        proposal_deposit: Uint128::from(5u128),
+    };
+    let err = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("addr0000", &[]),
+        msg.clone(),
+    );
+    assert_eq!(err, Err(ContractError::Unauthorized {}));
+
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(MOCK_CONTRACT_ADDR, &[]),
+        msg,
+    )
+    .unwrap();
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+    let config: ConfigResponse = from_binary(&res).unwrap();
+    assert_eq!(config.quorum, Decimal::percent(20));
+    assert_eq!(config.voting_period, 10);
+}
+
+#[test]
+fn proposals_and_votes_are_paginated() {
+    let mut deps = setup();
+    for _ in 0..3 {
+        propose(deps.as_mut(), 50).unwrap();
+    }
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Proposals {
+            start_after: Some(1),
+            limit: Some(1),
+        },
+    )
+    .unwrap();
+    let proposals: ProposalsResponse = from_binary(&res).unwrap();
+    assert_eq!(proposals.proposals.len(), 1);
+    assert_eq!(proposals.proposals[0].id, 2);
+
+    vote(deps.as_mut(), "voter0000", VoteOption::Yes).unwrap();
+    vote(deps.as_mut(), "voter0001", VoteOption::No).unwrap();
+    vote(deps.as_mut(), "voter0002", VoteOption::Abstain).unwrap();
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Votes {
+            proposal_id: 1,
+            start_after: None,
+            limit: Some(2),
+        },
+    )
+    .unwrap();
+    let first: VotesResponse = from_binary(&res).unwrap();
+    assert_eq!(first.votes.len(), 2);
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Votes {
+            proposal_id: 1,
+            start_after: Some(first.votes[1].voter.clone()),
+            limit: None,
+        },
+    )
+    .unwrap();
+    let rest: VotesResponse = from_binary(&res).unwrap();
+    assert_eq!(rest.votes.len(), 1);
+    assert!(!first
+        .votes
+        .iter()
+        .any(|vote| vote.voter == rest.votes[0].voter));
+
+    let err = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Proposal { proposal_id: 9 },
+    );
+    assert_eq!(err, Err(StdError::not_found("Proposal")));
+}
@@ -0,0 +1,27 @@
+use std::env::current_dir;
+use std::fs::create_dir_all;
+
+use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
+
+use shard_ics20::ibc::{Ics20Ack, Ics20Packet};
+use shard_token::ics20::{
+    ChannelResponse, ConfigResponse, ExecuteMsg, InstantiateMsg, ListChannelsResponse, QueryMsg,
+    TransferMsg,
+};
+
+fn main() {
+    let mut out_dir = current_dir().unwrap();
+    out_dir.push("schema");
+    create_dir_all(&out_dir).unwrap();
+    remove_schemas(&out_dir).unwrap();
+
+    export_schema(&schema_for!(InstantiateMsg), &out_dir);
+    export_schema(&schema_for!(ExecuteMsg), &out_dir);
+    export_schema(&schema_for!(QueryMsg), &out_dir);
+    export_schema(&schema_for!(TransferMsg), &out_dir);
+    export_schema(&schema_for!(Ics20Packet), &out_dir);
+    export_schema(&schema_for!(Ics20Ack), &out_dir);
+    export_schema(&schema_for!(ConfigResponse), &out_dir);
+    export_schema(&schema_for!(ListChannelsResponse), &out_dir);
+    export_schema(&schema_for!(ChannelResponse), &out_dir);
+}
@@ -0,0 +1,162 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+
+use crate::error::ContractError;
+use crate::ibc::Ics20Packet;
+use crate::state::{
+    read_channel_info, read_channel_infos, read_channel_state, read_config, store_channel_state,
+    store_config, Config,
+};
+
+use cosmwasm_std::{
+    from_binary, to_binary, Binary, Deps, DepsMut, Env, IbcMsg, MessageInfo, Response, StdError,
+    StdResult, Uint128,
+};
+use cw20::Cw20ReceiveMsg;
+use shard_token::ics20::{
+    ChannelResponse, ConfigResponse, ExecuteMsg, InstantiateMsg, ListChannelsResponse, MigrateMsg,
+    QueryMsg, TransferMsg,
+};
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    store_config(
+        deps.storage,
+        &Config {
+            shard_token: deps.api.addr_canonicalize(&msg.shard_token)?,
+            default_timeout: msg.default_timeout,
+        },
+    )?;
+
+    Ok(Response::default())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
+    }
+}
+
+pub fn receive_cw20(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    cw20_msg: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    let config: Config = read_config(deps.storage)?;
+    // only the shard token contract can execute this message
+    if config.shard_token != deps.api.addr_canonicalize(info.sender.as_str())? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let msg: TransferMsg = from_binary(&cw20_msg.msg)?;
+    transfer(
+        deps,
+        env,
+        info,
+        config,
+        msg,
+        cw20_msg.sender,
+        cw20_msg.amount,
+    )
+}
+
+/// Escrows `amount` received from `sender` and sends it over the channel
+pub fn transfer(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    config: Config,
+    msg: TransferMsg,
+    sender: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    if amount.is_zero() {
+        return Err(ContractError::NoFunds {});
+    }
+    if read_channel_info(deps.storage, &msg.channel)?.is_none() {
+        return Err(ContractError::NoSuchChannel { id: msg.channel });
+    }
+
+    // the tokens stay escrowed here until they return over the same channel
+    let mut state = read_channel_state(deps.storage, &msg.channel)?;
+    state.outstanding += amount;
+    state.total_sent += amount;
+    store_channel_state(deps.storage, &msg.channel, &state)?;
+
+    let packet = Ics20Packet::new(
+        amount,
+        format!("cw20:{}", info.sender),
+        &sender,
+        &msg.remote_address,
+    );
+    let timeout = msg.timeout.unwrap_or(config.default_timeout);
+    let send_packet = IbcMsg::SendPacket {
+        channel_id: msg.channel.clone(),
+        data: to_binary(&packet)?,
+        timeout: env.block.time.plus_seconds(timeout).into(),
+    };
+
+    Ok(Response::new()
+        .add_message(send_packet)
+        .add_attributes(vec![
+            ("action", "transfer"),
+            ("channel", &msg.channel),
+            ("sender", &sender),
+            ("receiver", &msg.remote_address),
+            ("denom", &packet.denom),
+            ("amount", &amount.to_string()),
+        ]))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_binary(&query_config(deps)?),
+        QueryMsg::ListChannels {} => to_binary(&query_list_channels(deps)?),
+        QueryMsg::Channel { id } => to_binary(&query_channel(deps, id)?),
+    }
+}
+
+pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
+    let config: Config = read_config(deps.storage)?;
+    Ok(ConfigResponse {
+        shard_token: deps.api.addr_humanize(&config.shard_token)?.to_string(),
+        default_timeout: config.default_timeout,
+    })
+}
+
+pub fn query_list_channels(deps: Deps) -> StdResult<ListChannelsResponse> {
+    Ok(ListChannelsResponse {
+        channels: read_channel_infos(deps.storage)?,
+    })
+}
+
+pub fn query_channel(deps: Deps, id: String) -> StdResult<ChannelResponse> {
+    let info = match read_channel_info(deps.storage, &id)? {
+        Some(info) => info,
+        None => return Err(StdError::not_found("ChannelInfo")),
+    };
+    let state = read_channel_state(deps.storage, &id)?;
+    Ok(ChannelResponse {
+        info,
+        outstanding: state.outstanding,
+        total_sent: state.total_sent,
+    })
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    Ok(Response::default())
+}
@@ -0,0 +1,35 @@
+use cosmwasm_std::StdError;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Channel doesn't exist: {id}")]
+    NoSuchChannel { id: String },
+
+    #[error("Amount must be greater than zero")]
+    NoFunds {},
+
+    #[error("Only supports unordered channels")]
+    OnlyUnorderedChannel {},
+
+    #[error("Invalid IBC channel version, expected ics20-1: {version}")]
+    InvalidIbcVersion { version: String },
+
+    #[error("Only the shard token can be transferred, got denom {denom}")]
+    InvalidDenom { denom: String },
+
+    #[error("Insufficient funds to return to channel {id}: outstanding {outstanding}")]
+    InsufficientOutstanding { id: String, outstanding: u128 },
+
+    #[error("ICS20 channels cannot be closed")]
+    CannotClose {},
+
+    #[error("Unknown reply id {id}")]
+    UnknownReply { id: u64 },
+}
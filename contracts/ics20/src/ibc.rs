@@ -0,0 +1,287 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::{
+    from_binary, to_binary, Binary, DepsMut, Env, IbcBasicResponse, IbcChannelCloseMsg,
+    IbcChannelConnectMsg, IbcChannelOpenMsg, IbcEndpoint, IbcOrder, IbcPacket, IbcPacketAckMsg,
+    IbcPacketReceiveMsg, IbcPacketTimeoutMsg, IbcReceiveResponse, Reply, Response, StdResult,
+    SubMsg, Uint128, WasmMsg,
+};
+use cw20::Cw20ExecuteMsg;
+use shard_token::ics20::{ChannelInfo, ICS20_VERSION};
+
+use crate::error::ContractError;
+use crate::state::{
+    read_channel_state, read_config, read_pending_release, store_channel_info, store_channel_state,
+    store_pending_release, ChannelState, PendingRelease,
+};
+
+pub const RELEASE_REPLY_ID: u64 = 1;
+
+/// The format of the packet data sent over ICS20 channels
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Ics20Packet {
+    /// amount of tokens to transfer
+    pub amount: Uint128,
+    /// the token denomination to be transferred
+    pub denom: String,
+    /// the recipient address on the destination chain
+    pub receiver: String,
+    /// the sender address
+    pub sender: String,
+}
+
+impl Ics20Packet {
+    pub fn new<T: Into<String>>(amount: Uint128, denom: T, sender: &str, receiver: &str) -> Self {
+        Ics20Packet {
+            denom: denom.into(),
+            amount,
+            sender: sender.to_string(),
+            receiver: receiver.to_string(),
+        }
+    }
+}
+
+/// The standard ICS20 acknowledgement
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Ics20Ack {
+    Result(Binary),
+    Error(String),
+}
+
+fn ack_success() -> StdResult<Binary> {
+    to_binary(&Ics20Ack::Result(Binary::from(vec![1])))
+}
+
+fn ack_fail(err: String) -> StdResult<Binary> {
+    to_binary(&Ics20Ack::Error(err))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_channel_open(
+    _deps: DepsMut,
+    _env: Env,
+    msg: IbcChannelOpenMsg,
+) -> Result<(), ContractError> {
+    let channel = msg.channel();
+    if channel.order != IbcOrder::Unordered {
+        return Err(ContractError::OnlyUnorderedChannel {});
+    }
+    if channel.version != ICS20_VERSION {
+        return Err(ContractError::InvalidIbcVersion {
+            version: channel.version.clone(),
+        });
+    }
+    if let Some(version) = msg.counterparty_version() {
+        if version != ICS20_VERSION {
+            return Err(ContractError::InvalidIbcVersion {
+                version: version.to_string(),
+            });
+        }
+    }
+    Ok(())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_channel_connect(
+    deps: DepsMut,
+    _env: Env,
+    msg: IbcChannelConnectMsg,
+) -> Result<IbcBasicResponse, ContractError> {
+    let channel = msg.channel().clone();
+    let info = ChannelInfo {
+        id: channel.endpoint.channel_id,
+        counterparty_port_id: channel.counterparty_endpoint.port_id,
+        counterparty_channel_id: channel.counterparty_endpoint.channel_id,
+        connection_id: channel.connection_id,
+    };
+    store_channel_info(deps.storage, &info)?;
+
+    Ok(IbcBasicResponse::new()
+        .add_attribute("action", "ibc_connect")
+        .add_attribute("channel_id", info.id))
+}
+
+// Closing would strand the outstanding balance on the other chain
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_channel_close(
+    _deps: DepsMut,
+    _env: Env,
+    _msg: IbcChannelCloseMsg,
+) -> Result<IbcBasicResponse, ContractError> {
+    Err(ContractError::CannotClose {})
+}
+
+/// Releases tokens returning from the other chain. Failures, including a
+/// failed payout, are reported in the acknowledgement instead of aborting, so
+/// the sender gets its refund.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_packet_receive(
+    deps: DepsMut,
+    _env: Env,
+    msg: IbcPacketReceiveMsg,
+) -> Result<IbcReceiveResponse, ContractError> {
+    match receive(deps, &msg.packet) {
+        Ok(res) => Ok(res),
+        Err(err) => Ok(IbcReceiveResponse::new()
+            .set_ack(ack_fail(err.to_string())?)
+            .add_attribute("action", "receive")
+            .add_attribute("success", "false")
+            .add_attribute("error", err.to_string())),
+    }
+}
+
+fn receive(mut deps: DepsMut, packet: &IbcPacket) -> Result<IbcReceiveResponse, ContractError> {
+    let msg: Ics20Packet = from_binary(&packet.data)?;
+    let config = read_config(deps.storage)?;
+    let token = deps.api.addr_humanize(&config.shard_token)?;
+
+    if msg.denom != voucher_denom(&packet.src, token.as_str()) {
+        return Err(ContractError::InvalidDenom { denom: msg.denom });
+    }
+    let channel = &packet.dest.channel_id;
+    reduce_outstanding(deps.branch(), channel, msg.amount)?;
+    store_pending_release(
+        deps.storage,
+        &PendingRelease {
+            channel: channel.clone(),
+            amount: msg.amount,
+        },
+    )?;
+
+    let release = Cw20ExecuteMsg::Transfer {
+        recipient: msg.receiver.clone(),
+        amount: msg.amount,
+    };
+    Ok(IbcReceiveResponse::new()
+        .set_ack(ack_success()?)
+        .add_submessage(SubMsg::reply_on_error(
+            WasmMsg::Execute {
+                contract_addr: token.to_string(),
+                msg: to_binary(&release)?,
+                funds: vec![],
+            },
+            RELEASE_REPLY_ID,
+        ))
+        .add_attribute("action", "receive")
+        .add_attribute("sender", msg.sender)
+        .add_attribute("receiver", msg.receiver)
+        .add_attribute("amount", msg.amount.to_string())
+        .add_attribute("success", "true"))
+}
+
+/// Undoes a release whose payout failed: the tokens stay escrowed for the
+/// channel and the packet is acknowledged with an error so the sender is
+/// refunded on the other chain
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, _env: Env, reply: Reply) -> Result<Response, ContractError> {
+    if reply.id != RELEASE_REPLY_ID {
+        return Err(ContractError::UnknownReply { id: reply.id });
+    }
+    let err = match reply.result.into_result() {
+        Ok(_) => return Ok(Response::new()),
+        Err(err) => err,
+    };
+    let pending = read_pending_release(deps.storage)?;
+    let mut state = read_channel_state(deps.storage, &pending.channel)?;
+    state.outstanding += pending.amount;
+    store_channel_state(deps.storage, &pending.channel, &state)?;
+
+    Ok(Response::new()
+        .set_data(ack_fail(err.clone())?)
+        .add_attribute("action", "receive")
+        .add_attribute("success", "false")
+        .add_attribute("error", err))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_packet_ack(
+    deps: DepsMut,
+    _env: Env,
+    ack: IbcPacketAckMsg,
+) -> Result<IbcBasicResponse, ContractError> {
+    let ics20_ack: Ics20Ack = from_binary(&ack.acknowledgement.data)?;
+    match ics20_ack {
+        Ics20Ack::Result(_) => on_packet_success(ack.original_packet),
+        Ics20Ack::Error(err) => on_packet_failure(deps, ack.original_packet, err),
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_packet_timeout(
+    deps: DepsMut,
+    _env: Env,
+    msg: IbcPacketTimeoutMsg,
+) -> Result<IbcBasicResponse, ContractError> {
+    on_packet_failure(deps, msg.packet, "timeout".to_string())
+}
+
+fn on_packet_success(packet: IbcPacket) -> Result<IbcBasicResponse, ContractError> {
+    let msg: Ics20Packet = from_binary(&packet.data)?;
+    Ok(IbcBasicResponse::new()
+        .add_attribute("action", "acknowledge")
+        .add_attribute("sender", msg.sender)
+        .add_attribute("receiver", msg.receiver)
+        .add_attribute("denom", msg.denom)
+        .add_attribute("amount", msg.amount.to_string())
+        .add_attribute("success", "true"))
+}
+
+/// Refunds the escrowed tokens of a packet the other chain rejected or that
+/// timed out
+pub fn on_packet_failure(
+    deps: DepsMut,
+    packet: IbcPacket,
+    err: String,
+) -> Result<IbcBasicResponse, ContractError> {
+    let msg: Ics20Packet = from_binary(&packet.data)?;
+    let config = read_config(deps.storage)?;
+    let token = deps.api.addr_humanize(&config.shard_token)?;
+    reduce_outstanding(deps, &packet.src.channel_id, msg.amount)?;
+
+    let refund = Cw20ExecuteMsg::Transfer {
+        recipient: msg.sender.clone(),
+        amount: msg.amount,
+    };
+    Ok(IbcBasicResponse::new()
+        .add_submessage(SubMsg::new(WasmMsg::Execute {
+            contract_addr: token.to_string(),
+            msg: to_binary(&refund)?,
+            funds: vec![],
+        }))
+        .add_attribute("action", "acknowledge")
+        .add_attribute("sender", msg.sender)
+        .add_attribute("receiver", msg.receiver)
+        .add_attribute("denom", msg.denom)
+        .add_attribute("amount", msg.amount.to_string())
+        .add_attribute("success", "false")
+        .add_attribute("error", err))
+}
+
+// Returning vouchers are prefixed with the port and channel they were sent
+// back over, i.e. the source endpoint of the packet
+fn voucher_denom(endpoint: &IbcEndpoint, token: &str) -> String {
+    format!(
+        "{}/{}/cw20:{}",
+        endpoint.port_id, endpoint.channel_id, token
+    )
+}
+
+fn reduce_outstanding(deps: DepsMut, channel: &str, amount: Uint128) -> Result<(), ContractError> {
+    let mut state: ChannelState = read_channel_state(deps.storage, channel)?;
+    state.outstanding = match state.outstanding.checked_sub(amount) {
+        Ok(outstanding) => outstanding,
+        Err(_) => {
+            return Err(ContractError::InsufficientOutstanding {
+                id: channel.to_string(),
+                outstanding: state.outstanding.u128(),
+            })
+        }
+    };
+    store_channel_state(deps.storage, channel, &state)?;
+    Ok(())
+}
@@ -0,0 +1,82 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::{CanonicalAddr, Order, StdResult, Storage, Uint128};
+use cosmwasm_storage::{singleton, singleton_read, Bucket, ReadonlyBucket};
+use shard_token::ics20::ChannelInfo;
+
+static KEY_CONFIG: &[u8] = b"config";
+static KEY_PENDING_RELEASE: &[u8] = b"pending_release";
+
+static PREFIX_CHANNEL_INFO: &[u8] = b"channel_info";
+static PREFIX_CHANNEL_STATE: &[u8] = b"channel_state";
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Config {
+    pub shard_token: CanonicalAddr,
+    pub default_timeout: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq, JsonSchema)]
+pub struct ChannelState {
+    pub outstanding: Uint128,
+    pub total_sent: Uint128,
+}
+
+/// A release of returning tokens, kept so a failed payout can be undone
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingRelease {
+    pub channel: String,
+    pub amount: Uint128,
+}
+
+pub fn store_config(storage: &mut dyn Storage, config: &Config) -> StdResult<()> {
+    singleton(storage, KEY_CONFIG).save(config)
+}
+
+pub fn read_config(storage: &dyn Storage) -> StdResult<Config> {
+    singleton_read(storage, KEY_CONFIG).load()
+}
+
+pub fn store_pending_release(storage: &mut dyn Storage, pending: &PendingRelease) -> StdResult<()> {
+    singleton(storage, KEY_PENDING_RELEASE).save(pending)
+}
+
+pub fn read_pending_release(storage: &dyn Storage) -> StdResult<PendingRelease> {
+    singleton_read(storage, KEY_PENDING_RELEASE).load()
+}
+
+pub fn store_channel_info(storage: &mut dyn Storage, info: &ChannelInfo) -> StdResult<()> {
+    let mut channel_bucket: Bucket<ChannelInfo> = Bucket::new(storage, PREFIX_CHANNEL_INFO);
+    channel_bucket.save(info.id.as_bytes(), info)
+}
+
+pub fn read_channel_info(storage: &dyn Storage, id: &str) -> StdResult<Option<ChannelInfo>> {
+    let channel_bucket: ReadonlyBucket<ChannelInfo> =
+        ReadonlyBucket::new(storage, PREFIX_CHANNEL_INFO);
+    channel_bucket.may_load(id.as_bytes())
+}
+
+pub fn read_channel_infos(storage: &dyn Storage) -> StdResult<Vec<ChannelInfo>> {
+    let channel_bucket: ReadonlyBucket<ChannelInfo> =
+        ReadonlyBucket::new(storage, PREFIX_CHANNEL_INFO);
+    channel_bucket
+        .range(None, None, Order::Ascending)
+        .map(|item| Ok(item?.1))
+        .collect()
+}
+
+pub fn store_channel_state(
+    storage: &mut dyn Storage,
+    id: &str,
+    state: &ChannelState,
+) -> StdResult<()> {
+    let mut state_bucket: Bucket<ChannelState> = Bucket::new(storage, PREFIX_CHANNEL_STATE);
+    state_bucket.save(id.as_bytes(), state)
+}
+
+pub fn read_channel_state(storage: &dyn Storage, id: &str) -> StdResult<ChannelState> {
+    let state_bucket: ReadonlyBucket<ChannelState> =
+        ReadonlyBucket::new(storage, PREFIX_CHANNEL_STATE);
+    Ok(state_bucket.may_load(id.as_bytes())?.unwrap_or_default())
+}
@@ -0,0 +1,301 @@
+use crate::contract::{execute, instantiate, query};
+use crate::error::ContractError;
+use crate::ibc::{
+    ibc_channel_connect, ibc_channel_open, ibc_packet_receive, ibc_packet_timeout,
+    on_packet_failure, reply, Ics20Ack, Ics20Packet, RELEASE_REPLY_ID,
+};
+use cosmwasm_std::testing::{
+    mock_dependencies, mock_env, mock_ibc_channel, mock_info, MockApi, MockQuerier, MockStorage,
+};
+use cosmwasm_std::{
+    from_binary, to_binary, ContractResult, CosmosMsg, Deps, DepsMut, IbcChannelConnectMsg,
+    IbcChannelOpenMsg, IbcEndpoint, IbcMsg, IbcOrder, IbcPacket, IbcPacketReceiveMsg,
+    IbcPacketTimeoutMsg, OwnedDeps, Reply, ReplyOn, Response, SubMsg, Timestamp, Uint128, WasmMsg,
+};
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use shard_token::ics20::{
+    ChannelResponse, ExecuteMsg, InstantiateMsg, ListChannelsResponse, QueryMsg, TransferMsg,
+    ICS20_VERSION,
+};
+
+fn setup() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+    let mut deps = mock_dependencies(&[]);
+    let msg = InstantiateMsg {
+        shard_token: "shard0000".to_string(),
+        default_timeout: 600,
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+
+    let channel = mock_ibc_channel("channel-0", IbcOrder::Unordered, ICS20_VERSION);
+    ibc_channel_open(
+        deps.as_mut(),
+        mock_env(),
+        IbcChannelOpenMsg::new_init(channel.clone()),
+    )
+    .unwrap();
+    ibc_channel_connect(
+        deps.as_mut(),
+        mock_env(),
+        IbcChannelConnectMsg::new_ack(channel, ICS20_VERSION),
+    )
+    .unwrap();
+    deps
+}
+
+fn send(deps: DepsMut, token: &str, amount: u128) -> Result<Response, ContractError> {
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(amount),
+        msg: to_binary(&TransferMsg {
+            channel: "channel-0".to_string(),
+            remote_address: "cosmos1remote".to_string(),
+            timeout: None,
+        })
+        .unwrap(),
+    });
+    execute(deps, mock_env(), mock_info(token, &[]), msg)
+}
+
+fn outstanding(deps: Deps) -> Uint128 {
+    let res = query(
+        deps,
+        mock_env(),
+        QueryMsg::Channel {
+            id: "channel-0".to_string(),
+        },
+    )
+    .unwrap();
+    let channel: ChannelResponse = from_binary(&res).unwrap();
+    channel.outstanding
+}
+
+fn sent_packet(amount: u128) -> IbcPacket {
+    let data = Ics20Packet::new(
+        Uint128::from(amount),
+        "cw20:shard0000",
+        "addr0000",
+        "cosmos1remote",
+    );
+    IbcPacket::new(
+        to_binary(&data).unwrap(),
+        IbcEndpoint {
+            port_id: "wasm.ics20".to_string(),
+            channel_id: "channel-0".to_string(),
+        },
+        IbcEndpoint {
+            port_id: "transfer".to_string(),
+            channel_id: "channel-1234".to_string(),
+        },
+        1,
+        Timestamp::from_seconds(1665321069).into(),
+    )
+}
+
+fn returning_packet(denom: &str, amount: u128) -> IbcPacket {
+    let data = Ics20Packet::new(Uint128::from(amount), denom, "cosmos1remote", "addr1111");
+    IbcPacket::new(
+        to_binary(&data).unwrap(),
+        IbcEndpoint {
+            port_id: "transfer".to_string(),
+            channel_id: "channel-1234".to_string(),
+        },
+        IbcEndpoint {
+            port_id: "wasm.ics20".to_string(),
+            channel_id: "channel-0".to_string(),
+        },
+        2,
+        Timestamp::from_seconds(1665321069).into(),
+    )
+}
+
+fn cw20_transfer(recipient: &str, amount: u128) -> SubMsg {
+    SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: "shard0000".to_string(),
+        msg: to_binary(&Cw20ExecuteMsg::Transfer {
+            recipient: recipient.to_string(),
+            amount: Uint128::from(amount),
+        })
+        .unwrap(),
+        funds: vec![],
+    }))
+}
+
+#[test]
+fn enforces_version_and_ordering() {
+    let mut deps = mock_dependencies(&[]);
+    let channel = mock_ibc_channel("channel-0", IbcOrder::Ordered, ICS20_VERSION);
+    match ibc_channel_open(
+        deps.as_mut(),
+        mock_env(),
+        IbcChannelOpenMsg::new_init(channel),
+    )
+    .unwrap_err()
+    {
+        ContractError::OnlyUnorderedChannel {} => {}
+        e => panic!("unexpected error: {:?}", e),
+    }
+
+    let channel = mock_ibc_channel("channel-0", IbcOrder::Unordered, "ics20-2");
+    match ibc_channel_open(
+        deps.as_mut(),
+        mock_env(),
+        IbcChannelOpenMsg::new_init(channel),
+    )
+    .unwrap_err()
+    {
+        ContractError::InvalidIbcVersion { version } => assert_eq!(version, "ics20-2"),
+        e => panic!("unexpected error: {:?}", e),
+    }
+}
+
+#[test]
+fn lists_connected_channels() {
+    let deps = setup();
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::ListChannels {}).unwrap();
+    let list: ListChannelsResponse = from_binary(&res).unwrap();
+    assert_eq!(list.channels.len(), 1);
+    assert_eq!(list.channels[0].id, "channel-0");
+    assert_eq!(outstanding(deps.as_ref()), Uint128::zero());
+}
+
+#[test]
+fn send_emits_packet_and_escrows() {
+    let mut deps = setup();
+    let res = send(deps.as_mut(), "shard0000", 1000).unwrap();
+    assert_eq!(res.messages.len(), 1);
+    match &res.messages[0].msg {
+        CosmosMsg::Ibc(IbcMsg::SendPacket {
+            channel_id,
+            data,
+            timeout,
+        }) => {
+            assert_eq!(channel_id, "channel-0");
+            let packet: Ics20Packet = from_binary(data).unwrap();
+            assert_eq!(
+                packet,
+                Ics20Packet::new(
+                    Uint128::from(1000u128),
+                    "cw20:shard0000",
+                    "addr0000",
+                    "cosmos1remote"
+                )
+            );
+            let expected = mock_env().block.time.plus_seconds(600);
+            assert_eq!(timeout, &expected.into());
+        }
+        msg => panic!("unexpected message: {:?}", msg),
+    }
+    assert_eq!(outstanding(deps.as_ref()), Uint128::from(1000u128));
+}
+
+#[test]
+fn send_rejects_other_tokens_and_channels() {
+    let mut deps = setup();
+    match send(deps.as_mut(), "other0000", 1000).unwrap_err() {
+        ContractError::Unauthorized {} => {}
+        e => panic!("unexpected error: {:?}", e),
+    }
+    match send(deps.as_mut(), "shard0000", 0).unwrap_err() {
+        ContractError::NoFunds {} => {}
+        e => panic!("unexpected error: {:?}", e),
+    }
+
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(10u128),
+        msg: to_binary(&TransferMsg {
+            channel: "channel-9".to_string(),
+            remote_address: "cosmos1remote".to_string(),
+            timeout: None,
+        })
+        .unwrap(),
+    });
+    match execute(deps.as_mut(), mock_env(), mock_info("shard0000", &[]), msg).unwrap_err() {
+        ContractError::NoSuchChannel { id } => assert_eq!(id, "channel-9"),
+        e => panic!("unexpected error: {:?}", e),
+    }
+}
+
+#[test]
+fn refunds_on_timeout_and_error_ack() {
+    let mut deps = setup();
+    send(deps.as_mut(), "shard0000", 1000).unwrap();
+
+    let res = ibc_packet_timeout(
+        deps.as_mut(),
+        mock_env(),
+        IbcPacketTimeoutMsg::new(sent_packet(600)),
+    )
+    .unwrap();
+    assert_eq!(res.messages, vec![cw20_transfer("addr0000", 600)]);
+    assert_eq!(outstanding(deps.as_ref()), Uint128::from(400u128));
+
+    let res = on_packet_failure(deps.as_mut(), sent_packet(400), "denied".to_string()).unwrap();
+    assert_eq!(res.messages, vec![cw20_transfer("addr0000", 400)]);
+    assert_eq!(outstanding(deps.as_ref()), Uint128::zero());
+}
+
+#[test]
+fn returning_tokens_are_released() {
+    let mut deps = setup();
+    send(deps.as_mut(), "shard0000", 1000).unwrap();
+
+    let packet = returning_packet("transfer/channel-1234/cw20:shard0000", 300);
+    let res =
+        ibc_packet_receive(deps.as_mut(), mock_env(), IbcPacketReceiveMsg::new(packet)).unwrap();
+    let ack: Ics20Ack = from_binary(&res.acknowledgement).unwrap();
+    match ack {
+        Ics20Ack::Result(_) => {}
+        ack => panic!("unexpected ack: {:?}", ack),
+    }
+    let mut release = cw20_transfer("addr1111", 300);
+    release.id = RELEASE_REPLY_ID;
+    release.reply_on = ReplyOn::Error;
+    assert_eq!(res.messages, vec![release]);
+    assert_eq!(outstanding(deps.as_ref()), Uint128::from(700u128));
+
+    // cannot return more than was sent over the channel
+    let packet = returning_packet("transfer/channel-1234/cw20:shard0000", 701);
+    let res =
+        ibc_packet_receive(deps.as_mut(), mock_env(), IbcPacketReceiveMsg::new(packet)).unwrap();
+    let ack: Ics20Ack = from_binary(&res.acknowledgement).unwrap();
+    match ack {
+        Ics20Ack::Error(_) => {}
+        ack => panic!("unexpected ack: {:?}", ack),
+    }
+    assert!(res.messages.is_empty());
+    assert_eq!(outstanding(deps.as_ref()), Uint128::from(700u128));
+
+    // foreign denoms are rejected
+    let packet = returning_packet("transfer/channel-1234/uatom", 1);
+    let res =
+        ibc_packet_receive(deps.as_mut(), mock_env(), IbcPacketReceiveMsg::new(packet)).unwrap();
+    let ack: Ics20Ack = from_binary(&res.acknowledgement).unwrap();
+    match ack {
+        Ics20Ack::Error(_) => {}
+        ack => panic!("unexpected ack: {:?}", ack),
+    }
+}
+
+#[test]
+fn failed_release_is_undone_with_an_error_ack() {
+    let mut deps = setup();
+    send(deps.as_mut(), "shard0000", 1000).unwrap();
+
+    let packet = returning_packet("transfer/channel-1234/cw20:shard0000", 300);
+    ibc_packet_receive(deps.as_mut(), mock_env(), IbcPacketReceiveMsg::new(packet)).unwrap();
+    assert_eq!(outstanding(deps.as_ref()), Uint128::from(700u128));
+
+    let res = reply(
+        deps.as_mut(),
+        mock_env(),
+        Reply {
+            id: RELEASE_REPLY_ID,
+            result: ContractResult::Err("recipient is frozen".to_string()),
+        },
+    )
+    .unwrap();
+    let ack: Ics20Ack = from_binary(&res.data.unwrap()).unwrap();
+    assert_eq!(ack, Ics20Ack::Error("recipient is frozen".to_string()));
+    assert_eq!(outstanding(deps.as_ref()), Uint128::from(1000u128));
+}
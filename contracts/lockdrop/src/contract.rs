@@ -0,0 +1,283 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+
+use crate::error::ContractError;
+use crate::state::{
+    read_config, read_position, read_positions, read_state, read_user_info, remove_position,
+    store_config, store_position, store_state, store_user_info, Config, State,
+};
+
+use cosmwasm_std::{
+    from_binary, to_binary, Addr, Binary, CosmosMsg, Decimal, Deps, DepsMut, Env, MessageInfo,
+    Response, StdError, StdResult, Uint128, WasmMsg,
+};
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use shard_token::lockdrop::{
+    ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, LockOption, MigrateMsg,
+    PositionResponse, QueryMsg, StateResponse, UserInfoResponse,
+};
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    validate_config(msg.window_start, msg.window_end, &msg.lock_options)?;
+    store_config(
+        deps.storage,
+        &Config {
+            shard_token: deps.api.addr_canonicalize(&msg.shard_token)?,
+            window_start: msg.window_start,
+            window_end: msg.window_end,
+            lock_options: msg.lock_options,
+        },
+    )?;
+    store_state(deps.storage, &State::default())?;
+
+    Ok(Response::default())
+}
+
+fn validate_config(
+    window_start: u64,
+    window_end: u64,
+    lock_options: &[LockOption],
+) -> Result<(), ContractError> {
+    let duplicate = lock_options.iter().enumerate().any(|(i, option)| {
+        lock_options[i + 1..]
+            .iter()
+            .any(|other| other.duration == option.duration)
+    });
+    if window_start >= window_end
+        || lock_options.is_empty()
+        || duplicate
+        || lock_options.iter().any(|option| option.boost.is_zero())
+    {
+        return Err(ContractError::InvalidConfig {});
+    }
+    Ok(())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
+        ExecuteMsg::Unlock { duration } => unlock(deps, env, info, duration),
+        ExecuteMsg::ClaimRewards {} => claim_rewards(deps, env, info),
+    }
+}
+
+pub fn receive_cw20(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    cw20_msg: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    let config: Config = read_config(deps.storage)?;
+    // only the shard token contract can execute this message
+    if config.shard_token != deps.api.addr_canonicalize(info.sender.as_str())? {
+        return Err(ContractError::Unauthorized {});
+    }
+    if cw20_msg.amount.is_zero() {
+        return Err(ContractError::NoFunds {});
+    }
+
+    match from_binary(&cw20_msg.msg)? {
+        Cw20HookMsg::Lock { duration } => lock(
+            deps,
+            env,
+            config,
+            cw20_msg.sender,
+            cw20_msg.amount,
+            duration,
+        ),
+        Cw20HookMsg::FundRewards {} => fund_rewards(deps, cw20_msg.amount),
+    }
+}
+
+pub fn lock(
+    deps: DepsMut,
+    env: Env,
+    config: Config,
+    user: String,
+    amount: Uint128,
+    duration: u64,
+) -> Result<Response, ContractError> {
+    if !config.is_window_open(env.block.time.seconds()) {
+        return Err(ContractError::WindowClosed {});
+    }
+    let boost = match config.lock_option(duration) {
+        Some(option) => option.boost,
+        None => return Err(ContractError::InvalidDuration {}),
+    };
+    let weight = amount * boost;
+
+    let user_raw = deps.api.addr_canonicalize(&user)?;
+    let locked = read_position(deps.storage, &user_raw, duration)?.unwrap_or_default();
+    store_position(deps.storage, &user_raw, duration, locked + amount)?;
+
+    let mut user_info = read_user_info(deps.storage, &user_raw)?;
+    user_info.weight += weight;
+    store_user_info(deps.storage, &user_raw, &user_info)?;
+
+    let mut state = read_state(deps.storage)?;
+    state.total_locked += amount;
+    state.total_weight += weight;
+    store_state(deps.storage, &state)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "lock"),
+        ("user", &user),
+        ("duration", &duration.to_string()),
+        ("amount", &amount.to_string()),
+        ("weight", &weight.to_string()),
+    ]))
+}
+
+pub fn fund_rewards(deps: DepsMut, amount: Uint128) -> Result<Response, ContractError> {
+    let mut state = read_state(deps.storage)?;
+    state.total_rewards += amount;
+    store_state(deps.storage, &state)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "fund_rewards"),
+        ("amount", &amount.to_string()),
+    ]))
+}
+
+pub fn unlock(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    duration: u64,
+) -> Result<Response, ContractError> {
+    let config: Config = read_config(deps.storage)?;
+    let user_raw = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let amount = match read_position(deps.storage, &user_raw, duration)? {
+        Some(amount) => amount,
+        None => return Err(StdError::not_found("Position").into()),
+    };
+    if env.block.time.seconds() < config.window_end + duration {
+        return Err(ContractError::StillLocked {});
+    }
+    remove_position(deps.storage, &user_raw, duration);
+
+    let mut state = read_state(deps.storage)?;
+    state.total_locked -= amount;
+    store_state(deps.storage, &state)?;
+
+    Ok(Response::new()
+        .add_message(send_tokens(deps.as_ref(), &info.sender, amount)?)
+        .add_attributes(vec![
+            ("action", "unlock"),
+            ("user", info.sender.as_str()),
+            ("duration", &duration.to_string()),
+            ("amount", &amount.to_string()),
+        ]))
+}
+
+pub fn claim_rewards(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let config: Config = read_config(deps.storage)?;
+    // allocations are only final once no more tokens can be locked
+    if env.block.time.seconds() < config.window_end {
+        return Err(ContractError::WindowNotEnded {});
+    }
+
+    let user_raw = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let mut user_info = read_user_info(deps.storage, &user_raw)?;
+    let state = read_state(deps.storage)?;
+    let amount = state.reward_allocation(user_info.weight) - user_info.rewards_claimed;
+    if amount.is_zero() {
+        return Err(ContractError::NothingToClaim {});
+    }
+    user_info.rewards_claimed += amount;
+    store_user_info(deps.storage, &user_raw, &user_info)?;
+
+    Ok(Response::new()
+        .add_message(send_tokens(deps.as_ref(), &info.sender, amount)?)
+        .add_attributes(vec![
+            ("action", "claim_rewards"),
+            ("user", info.sender.as_str()),
+            ("amount", &amount.to_string()),
+        ]))
+}
+
+fn send_tokens(deps: Deps, recipient: &Addr, amount: Uint128) -> StdResult<CosmosMsg> {
+    let config: Config = read_config(deps.storage)?;
+    Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: deps.api.addr_humanize(&config.shard_token)?.to_string(),
+        msg: to_binary(&Cw20ExecuteMsg::Transfer {
+            recipient: recipient.to_string(),
+            amount,
+        })?,
+        funds: vec![],
+    }))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_binary(&query_config(deps)?),
+        QueryMsg::State {} => to_binary(&query_state(deps)?),
+        QueryMsg::UserInfo { address } => to_binary(&query_user_info(deps, address)?),
+    }
+}
+
+pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
+    let config: Config = read_config(deps.storage)?;
+    Ok(ConfigResponse {
+        shard_token: deps.api.addr_humanize(&config.shard_token)?.to_string(),
+        window_start: config.window_start,
+        window_end: config.window_end,
+        lock_options: config.lock_options,
+    })
+}
+
+pub fn query_state(deps: Deps) -> StdResult<StateResponse> {
+    let state = read_state(deps.storage)?;
+    Ok(StateResponse {
+        total_locked: state.total_locked,
+        total_weight: state.total_weight,
+        total_rewards: state.total_rewards,
+    })
+}
+
+pub fn query_user_info(deps: Deps, address: String) -> StdResult<UserInfoResponse> {
+    let config: Config = read_config(deps.storage)?;
+    let user_raw = deps.api.addr_canonicalize(&address)?;
+    let positions = read_positions(deps.storage, &user_raw)?
+        .into_iter()
+        .map(|(duration, amount)| PositionResponse {
+            duration,
+            amount,
+            boost: config
+                .lock_option(duration)
+                .map_or(Decimal::zero(), |option| option.boost),
+            unlock_time: config.window_end + duration,
+        })
+        .collect();
+    let user_info = read_user_info(deps.storage, &user_raw)?;
+    let state = read_state(deps.storage)?;
+
+    Ok(UserInfoResponse {
+        positions,
+        weight: user_info.weight,
+        reward_allocation: state.reward_allocation(user_info.weight),
+        rewards_claimed: user_info.rewards_claimed,
+    })
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    Ok(Response::default())
+}
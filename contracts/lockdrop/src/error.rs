@@ -0,0 +1,32 @@
+use cosmwasm_std::StdError;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Invalid lock window or lock options")]
+    InvalidConfig {},
+
+    #[error("Amount must be greater than zero")]
+    NoFunds {},
+
+    #[error("No lock option for this duration")]
+    InvalidDuration {},
+
+    #[error("Lock window is not open")]
+    WindowClosed {},
+
+    #[error("Lock window not ended")]
+    WindowNotEnded {},
+
+    #[error("Position is still locked")]
+    StillLocked {},
+
+    #[error("Nothing to claim")]
+    NothingToClaim {},
+}
@@ -0,0 +1,133 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::{CanonicalAddr, Order, StdResult, Storage, Uint128};
+use cosmwasm_storage::{singleton, singleton_read, Bucket, ReadonlyBucket};
+use shard_token::lockdrop::LockOption;
+
+static KEY_CONFIG: &[u8] = b"config";
+static KEY_STATE: &[u8] = b"state";
+
+static PREFIX_POSITION: &[u8] = b"position";
+static PREFIX_USER: &[u8] = b"user";
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Config {
+    pub shard_token: CanonicalAddr,
+    pub window_start: u64,
+    pub window_end: u64,
+    pub lock_options: Vec<LockOption>,
+}
+
+impl Config {
+    pub fn lock_option(&self, duration: u64) -> Option<&LockOption> {
+        self.lock_options
+            .iter()
+            .find(|option| option.duration == duration)
+    }
+
+    pub fn is_window_open(&self, now: u64) -> bool {
+        self.window_start <= now && now < self.window_end
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct State {
+    pub total_locked: Uint128,
+    pub total_weight: Uint128,
+    pub total_rewards: Uint128,
+}
+
+impl State {
+    /// Share of the rewards for `weight`
+    pub fn reward_allocation(&self, weight: Uint128) -> Uint128 {
+        if self.total_weight.is_zero() {
+            return Uint128::zero();
+        }
+        self.total_rewards.multiply_ratio(weight, self.total_weight)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct UserInfo {
+    /// Weights are kept when positions unlock, so rewards can be claimed
+    /// after withdrawing
+    pub weight: Uint128,
+    pub rewards_claimed: Uint128,
+}
+
+pub fn store_config(storage: &mut dyn Storage, config: &Config) -> StdResult<()> {
+    singleton(storage, KEY_CONFIG).save(config)
+}
+
+pub fn read_config(storage: &dyn Storage) -> StdResult<Config> {
+    singleton_read(storage, KEY_CONFIG).load()
+}
+
+pub fn store_state(storage: &mut dyn Storage, state: &State) -> StdResult<()> {
+    singleton(storage, KEY_STATE).save(state)
+}
+
+pub fn read_state(storage: &dyn Storage) -> StdResult<State> {
+    singleton_read(storage, KEY_STATE).load()
+}
+
+pub fn store_user_info(
+    storage: &mut dyn Storage,
+    user: &CanonicalAddr,
+    user_info: &UserInfo,
+) -> StdResult<()> {
+    let mut user_bucket: Bucket<UserInfo> = Bucket::new(storage, PREFIX_USER);
+    user_bucket.save(user.as_slice(), user_info)
+}
+
+pub fn read_user_info(storage: &dyn Storage, user: &CanonicalAddr) -> StdResult<UserInfo> {
+    let user_bucket: ReadonlyBucket<UserInfo> = ReadonlyBucket::new(storage, PREFIX_USER);
+    Ok(user_bucket.may_load(user.as_slice())?.unwrap_or_default())
+}
+
+pub fn store_position(
+    storage: &mut dyn Storage,
+    user: &CanonicalAddr,
+    duration: u64,
+    amount: Uint128,
+) -> StdResult<()> {
+    let mut position_bucket: Bucket<Uint128> =
+        Bucket::multilevel(storage, &[PREFIX_POSITION, user.as_slice()]);
+    position_bucket.save(&duration.to_be_bytes(), &amount)
+}
+
+pub fn remove_position(storage: &mut dyn Storage, user: &CanonicalAddr, duration: u64) {
+    let mut position_bucket: Bucket<Uint128> =
+        Bucket::multilevel(storage, &[PREFIX_POSITION, user.as_slice()]);
+    position_bucket.remove(&duration.to_be_bytes())
+}
+
+pub fn read_position(
+    storage: &dyn Storage,
+    user: &CanonicalAddr,
+    duration: u64,
+) -> StdResult<Option<Uint128>> {
+    let position_bucket: ReadonlyBucket<Uint128> =
+        ReadonlyBucket::multilevel(storage, &[PREFIX_POSITION, user.as_slice()]);
+    position_bucket.may_load(&duration.to_be_bytes())
+}
+
+/// Locked amounts of `user` by duration, ordered by duration. There is at
+/// most one position per lock option, so no pagination is needed.
+pub fn read_positions(
+    storage: &dyn Storage,
+    user: &CanonicalAddr,
+) -> StdResult<Vec<(u64, Uint128)>> {
+    let position_bucket: ReadonlyBucket<Uint128> =
+        ReadonlyBucket::multilevel(storage, &[PREFIX_POSITION, user.as_slice()]);
+    position_bucket
+        .range(None, None, Order::Ascending)
+        .map(|item| {
+            let (k, v) = item?;
+            let mut duration = [0u8; 8];
+            duration.copy_from_slice(&k);
+            Ok((u64::from_be_bytes(duration), v))
+        })
+        .collect()
+}
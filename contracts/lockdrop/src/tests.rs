@@ -0,0 +1,303 @@
+use crate::contract::{execute, instantiate, query};
+use crate::error::ContractError;
+use cosmwasm_std::testing::{
+    mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
+};
+use cosmwasm_std::{
+    from_binary, to_binary, CosmosMsg, Decimal, DepsMut, Env, OwnedDeps, Response, StdError,
+    SubMsg, Timestamp, Uint128, WasmMsg,
+};
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use shard_token::lockdrop::{
+    Cw20HookMsg, ExecuteMsg, InstantiateMsg, LockOption, PositionResponse, QueryMsg, StateResponse,
+    UserInfoResponse,
+};
+
+fn env_at(time: u64) -> Env {
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(time);
+    env
+}
+
+fn lock_options() -> Vec<LockOption> {
+    vec![
+        LockOption {
+            duration: 100,
+            boost: Decimal::one(),
+        },
+        LockOption {
+            duration: 400,
+            boost: Decimal::percent(300),
+        },
+    ]
+}
+
+fn setup() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+    let mut deps = mock_dependencies(&[]);
+    let msg = InstantiateMsg {
+        shard_token: "shard0000".to_string(),
+        window_start: 1000,
+        window_end: 2000,
+        lock_options: lock_options(),
+    };
+    instantiate(deps.as_mut(), env_at(0), mock_info("addr0000", &[]), msg).unwrap();
+    deps
+}
+
+fn receive(
+    deps: DepsMut,
+    time: u64,
+    sender: &str,
+    amount: u128,
+    hook: Cw20HookMsg,
+) -> Result<Response, ContractError> {
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: sender.to_string(),
+        amount: Uint128::from(amount),
+        msg: to_binary(&hook).unwrap(),
+    });
+    execute(deps, env_at(time), mock_info("shard0000", &[]), msg)
+}
+
+fn user_info(deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>, user: &str) -> UserInfoResponse {
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::UserInfo {
+            address: user.to_string(),
+        },
+    )
+    .unwrap();
+    from_binary(&res).unwrap()
+}
+
+fn transfer_msg(recipient: &str, amount: u128) -> SubMsg {
+    SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: "shard0000".to_string(),
+        msg: to_binary(&Cw20ExecuteMsg::Transfer {
+            recipient: recipient.to_string(),
+            amount: Uint128::from(amount),
+        })
+        .unwrap(),
+        funds: vec![],
+    }))
+}
+
+#[test]
+fn instantiate_validates_options() {
+    let mut deps = mock_dependencies(&[]);
+    let mut duplicated = lock_options();
+    duplicated[1].duration = 100;
+    let msg = InstantiateMsg {
+        shard_token: "shard0000".to_string(),
+        window_start: 1000,
+        window_end: 2000,
+        lock_options: duplicated,
+    };
+    let err = instantiate(deps.as_mut(), env_at(0), mock_info("addr0000", &[]), msg);
+    assert_eq!(err, Err(ContractError::InvalidConfig {}));
+
+    let msg = InstantiateMsg {
+        shard_token: "shard0000".to_string(),
+        window_start: 2000,
+        window_end: 2000,
+        lock_options: lock_options(),
+    };
+    let err = instantiate(deps.as_mut(), env_at(0), mock_info("addr0000", &[]), msg);
+    assert_eq!(err, Err(ContractError::InvalidConfig {}));
+}
+
+#[test]
+fn lock_only_during_window_and_for_known_durations() {
+    let mut deps = setup();
+    let lock = || Cw20HookMsg::Lock { duration: 100 };
+    assert_eq!(
+        receive(deps.as_mut(), 999, "user0000", 10, lock()),
+        Err(ContractError::WindowClosed {})
+    );
+    assert_eq!(
+        receive(deps.as_mut(), 2000, "user0000", 10, lock()),
+        Err(ContractError::WindowClosed {})
+    );
+    assert_eq!(
+        receive(
+            deps.as_mut(),
+            1500,
+            "user0000",
+            10,
+            Cw20HookMsg::Lock { duration: 200 }
+        ),
+        Err(ContractError::InvalidDuration {})
+    );
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "user0000".to_string(),
+        amount: Uint128::from(10u128),
+        msg: to_binary(&lock()).unwrap(),
+    });
+    let err = execute(
+        deps.as_mut(),
+        env_at(1500),
+        mock_info("other0000", &[]),
+        msg,
+    );
+    assert_eq!(err, Err(ContractError::Unauthorized {}));
+
+    receive(deps.as_mut(), 1500, "user0000", 10, lock()).unwrap();
+    receive(deps.as_mut(), 1600, "user0000", 5, lock()).unwrap();
+    receive(
+        deps.as_mut(),
+        1700,
+        "user0000",
+        20,
+        Cw20HookMsg::Lock { duration: 400 },
+    )
+    .unwrap();
+
+    let info = user_info(&deps, "user0000");
+    assert_eq!(
+        info.positions,
+        vec![
+            PositionResponse {
+                duration: 100,
+                amount: Uint128::from(15u128),
+                boost: Decimal::one(),
+                unlock_time: 2100,
+            },
+            PositionResponse {
+                duration: 400,
+                amount: Uint128::from(20u128),
+                boost: Decimal::percent(300),
+                unlock_time: 2400,
+            },
+        ]
+    );
+    assert_eq!(info.weight, Uint128::from(75u128));
+}
+
+#[test]
+fn rewards_are_allocated_by_boosted_weight() {
+    let mut deps = setup();
+    receive(
+        deps.as_mut(),
+        1500,
+        "user0000",
+        100,
+        Cw20HookMsg::Lock { duration: 100 },
+    )
+    .unwrap();
+    receive(
+        deps.as_mut(),
+        1500,
+        "user0001",
+        100,
+        Cw20HookMsg::Lock { duration: 400 },
+    )
+    .unwrap();
+    receive(
+        deps.as_mut(),
+        1500,
+        "funder0000",
+        1000,
+        Cw20HookMsg::FundRewards {},
+    )
+    .unwrap();
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::State {}).unwrap();
+    let state: StateResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        state,
+        StateResponse {
+            total_locked: Uint128::from(200u128),
+            total_weight: Uint128::from(400u128),
+            total_rewards: Uint128::from(1000u128),
+        }
+    );
+
+    let claim = ExecuteMsg::ClaimRewards {};
+    let err = execute(
+        deps.as_mut(),
+        env_at(1999),
+        mock_info("user0001", &[]),
+        claim.clone(),
+    );
+    assert_eq!(err, Err(ContractError::WindowNotEnded {}));
+
+    let res = execute(
+        deps.as_mut(),
+        env_at(2000),
+        mock_info("user0001", &[]),
+        claim.clone(),
+    )
+    .unwrap();
+    assert_eq!(res.messages, vec![transfer_msg("user0001", 750)]);
+    let err = execute(
+        deps.as_mut(),
+        env_at(2000),
+        mock_info("user0001", &[]),
+        claim.clone(),
+    );
+    assert_eq!(err, Err(ContractError::NothingToClaim {}));
+
+    // rewards funded later are shared with the same weights
+    receive(
+        deps.as_mut(),
+        3000,
+        "funder0000",
+        400,
+        Cw20HookMsg::FundRewards {},
+    )
+    .unwrap();
+    let res = execute(
+        deps.as_mut(),
+        env_at(3000),
+        mock_info("user0001", &[]),
+        claim,
+    )
+    .unwrap();
+    assert_eq!(res.messages, vec![transfer_msg("user0001", 300)]);
+    let info = user_info(&deps, "user0000");
+    assert_eq!(info.reward_allocation, Uint128::from(350u128));
+    assert_eq!(info.rewards_claimed, Uint128::zero());
+}
+
+#[test]
+fn unlock_after_duration() {
+    let mut deps = setup();
+    receive(
+        deps.as_mut(),
+        1500,
+        "user0000",
+        100,
+        Cw20HookMsg::Lock { duration: 400 },
+    )
+    .unwrap();
+
+    let msg = ExecuteMsg::Unlock { duration: 400 };
+    let err = execute(
+        deps.as_mut(),
+        env_at(2399),
+        mock_info("user0000", &[]),
+        msg.clone(),
+    );
+    assert_eq!(err, Err(ContractError::StillLocked {}));
+
+    let res = execute(
+        deps.as_mut(),
+        env_at(2400),
+        mock_info("user0000", &[]),
+        msg.clone(),
+    )
+    .unwrap();
+    assert_eq!(res.messages, vec![transfer_msg("user0000", 100)]);
+
+    // the weight stays for claiming rewards
+    let info = user_info(&deps, "user0000");
+    assert!(info.positions.is_empty());
+    assert_eq!(info.weight, Uint128::from(300u128));
+
+    let err = execute(deps.as_mut(), env_at(2400), mock_info("user0000", &[]), msg);
+    assert_eq!(
+        err,
+        Err(ContractError::Std(StdError::not_found("Position")))
+    );
+}
@@ -0,0 +1,429 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+
+use crate::error::ContractError;
+use crate::querier::query_token_balance;
+use crate::state::{
+    read_config, read_pending_provide, read_pool, read_shares, remove_pending_provide,
+    store_config, store_pending_provide, store_pool, store_shares, Config, PendingProvide, Pool,
+};
+
+use cosmwasm_std::{
+    coins, from_binary, to_binary, Addr, BankMsg, Binary, CosmosMsg, Deps, DepsMut, Env,
+    MessageInfo, Reply, Response, StdError, StdResult, SubMsg, Uint128, WasmMsg,
+};
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use shard_token::pair::{
+    ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, PoolResponse, QueryMsg,
+    ShareResponse,
+};
+
+const MAX_COMMISSION_BPS: u16 = 10_000;
+const PROVIDE_REPLY_ID: u64 = 1;
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    if msg.commission_bps > MAX_COMMISSION_BPS {
+        return Err(ContractError::InvalidCommission {});
+    }
+    store_config(
+        deps.storage,
+        &Config {
+            shard_token: deps.api.addr_canonicalize(&msg.shard_token)?,
+            native_denom: msg.native_denom,
+            commission_bps: msg.commission_bps,
+        },
+    )?;
+    store_pool(deps.storage, &Pool::default())?;
+
+    Ok(Response::default())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Receive(msg) => receive_cw20(deps, env, info, msg),
+        ExecuteMsg::ProvideLiquidity {
+            token_amount,
+            min_shares,
+        } => provide_liquidity(deps, env, info, token_amount, min_shares),
+        ExecuteMsg::WithdrawLiquidity { shares } => withdraw_liquidity(deps, info, shares),
+        ExecuteMsg::Swap { min_return } => swap_native(deps, info, min_return),
+    }
+}
+
+pub fn receive_cw20(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    cw20_msg: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    let config: Config = read_config(deps.storage)?;
+    // only the shard token contract can execute this message
+    if config.shard_token != deps.api.addr_canonicalize(info.sender.as_str())? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    match from_binary(&cw20_msg.msg)? {
+        Cw20HookMsg::Swap { min_return } => {
+            let trader = deps.api.addr_validate(&cw20_msg.sender)?;
+            swap_token(deps, env, trader, cw20_msg.amount, min_return)
+        }
+    }
+}
+
+// The tokens are pulled first and the shares issued in the reply for what the
+// pair actually received, which is less than `token_amount` when the token
+// charges a fee on the transfer
+pub fn provide_liquidity(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    token_amount: Uint128,
+    min_shares: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    let config: Config = read_config(deps.storage)?;
+    let native_amount = native_funds(&info, &config)?;
+    if token_amount.is_zero() {
+        return Err(ContractError::ZeroAmount {});
+    }
+
+    let shard_token = deps.api.addr_humanize(&config.shard_token)?;
+    let token_balance = query_token_balance(
+        &deps.querier,
+        shard_token.clone(),
+        env.contract.address.clone(),
+    )?;
+    store_pending_provide(
+        deps.storage,
+        &PendingProvide {
+            provider: deps.api.addr_canonicalize(info.sender.as_str())?,
+            native_amount,
+            min_shares,
+            token_balance,
+        },
+    )?;
+
+    Ok(Response::new().add_submessage(SubMsg::reply_on_success(
+        CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: shard_token.to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::TransferFrom {
+                owner: info.sender.to_string(),
+                recipient: env.contract.address.to_string(),
+                amount: token_amount,
+            })?,
+            funds: vec![],
+        }),
+        PROVIDE_REPLY_ID,
+    )))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
+    match msg.id {
+        PROVIDE_REPLY_ID => issue_shares(deps, env),
+        id => Err(ContractError::UnknownReply { id }),
+    }
+}
+
+fn issue_shares(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let config: Config = read_config(deps.storage)?;
+    let pending = read_pending_provide(deps.storage)?;
+    remove_pending_provide(deps.storage);
+    let token_balance = query_token_balance(
+        &deps.querier,
+        deps.api.addr_humanize(&config.shard_token)?,
+        env.contract.address,
+    )?;
+    let token_amount = token_balance
+        .checked_sub(pending.token_balance)
+        .map_err(StdError::from)?;
+    if token_amount.is_zero() {
+        return Err(ContractError::ZeroAmount {});
+    }
+    let native_amount = pending.native_amount;
+
+    let mut pool = read_pool(deps.storage)?;
+    let shares = if pool.total_shares.is_zero() {
+        Uint128::from(isqrt(
+            token_amount
+                .checked_mul(native_amount)
+                .map_err(StdError::from)?
+                .u128(),
+        ))
+    } else {
+        std::cmp::min(
+            token_amount.multiply_ratio(pool.total_shares, pool.token_reserve),
+            native_amount.multiply_ratio(pool.total_shares, pool.native_reserve),
+        )
+    };
+    if shares.is_zero() || shares < pending.min_shares.unwrap_or_default() {
+        return Err(ContractError::TooFewShares { shares });
+    }
+
+    pool.token_reserve += token_amount;
+    pool.native_reserve += native_amount;
+    pool.total_shares += shares;
+    store_pool(deps.storage, &pool)?;
+    let provider_shares = read_shares(deps.storage, &pending.provider)? + shares;
+    store_shares(deps.storage, &pending.provider, provider_shares)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "provide_liquidity"),
+        (
+            "provider",
+            deps.api.addr_humanize(&pending.provider)?.as_str(),
+        ),
+        ("token_amount", &token_amount.to_string()),
+        ("native_amount", &native_amount.to_string()),
+        ("shares", &shares.to_string()),
+    ]))
+}
+
+pub fn withdraw_liquidity(
+    deps: DepsMut,
+    info: MessageInfo,
+    shares: Uint128,
+) -> Result<Response, ContractError> {
+    if shares.is_zero() {
+        return Err(ContractError::ZeroAmount {});
+    }
+    let provider = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let provider_shares = read_shares(deps.storage, &provider)?;
+    if provider_shares < shares {
+        return Err(ContractError::InsufficientShares {});
+    }
+
+    let mut pool = read_pool(deps.storage)?;
+    let token_amount = pool.token_reserve.multiply_ratio(shares, pool.total_shares);
+    let native_amount = pool
+        .native_reserve
+        .multiply_ratio(shares, pool.total_shares);
+    pool.token_reserve -= token_amount;
+    pool.native_reserve -= native_amount;
+    pool.total_shares -= shares;
+    store_pool(deps.storage, &pool)?;
+    store_shares(deps.storage, &provider, provider_shares - shares)?;
+
+    let mut res = Response::new();
+    if !token_amount.is_zero() {
+        res = res.add_message(send_tokens(deps.as_ref(), &info.sender, token_amount)?);
+    }
+    if !native_amount.is_zero() {
+        res = res.add_message(send_native(deps.as_ref(), &info.sender, native_amount)?);
+    }
+    Ok(res.add_attributes(vec![
+        ("action", "withdraw_liquidity"),
+        ("provider", info.sender.as_str()),
+        ("shares", &shares.to_string()),
+        ("token_amount", &token_amount.to_string()),
+        ("native_amount", &native_amount.to_string()),
+    ]))
+}
+
+pub fn swap_native(
+    deps: DepsMut,
+    info: MessageInfo,
+    min_return: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    let config: Config = read_config(deps.storage)?;
+    let offer_amount = native_funds(&info, &config)?;
+    let mut pool = read_pool(deps.storage)?;
+    let return_amount = compute_swap(
+        &config,
+        pool.native_reserve,
+        pool.token_reserve,
+        offer_amount,
+        min_return,
+    )?;
+    pool.native_reserve += offer_amount;
+    pool.token_reserve -= return_amount;
+    store_pool(deps.storage, &pool)?;
+
+    Ok(Response::new()
+        .add_message(send_tokens(deps.as_ref(), &info.sender, return_amount)?)
+        .add_attributes(vec![
+            ("action", "swap"),
+            ("trader", info.sender.as_str()),
+            ("offer_asset", &config.native_denom),
+            ("offer_amount", &offer_amount.to_string()),
+            ("return_amount", &return_amount.to_string()),
+        ]))
+}
+
+// The hook amount is what the token says it sent. Only what the pair's balance
+// shows beyond the reserve is counted, so a token charging a fee on the
+// transfer cannot leave the reserve above what the pair holds.
+pub fn swap_token(
+    deps: DepsMut,
+    env: Env,
+    trader: Addr,
+    amount: Uint128,
+    min_return: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    let config: Config = read_config(deps.storage)?;
+    let mut pool = read_pool(deps.storage)?;
+    let token_balance = query_token_balance(
+        &deps.querier,
+        deps.api.addr_humanize(&config.shard_token)?,
+        env.contract.address,
+    )?;
+    let received = token_balance
+        .checked_sub(pool.token_reserve)
+        .unwrap_or_default();
+    let offer_amount = std::cmp::min(amount, received);
+    if offer_amount.is_zero() {
+        return Err(ContractError::ZeroAmount {});
+    }
+    let return_amount = compute_swap(
+        &config,
+        pool.token_reserve,
+        pool.native_reserve,
+        offer_amount,
+        min_return,
+    )?;
+    pool.token_reserve += offer_amount;
+    pool.native_reserve -= return_amount;
+    store_pool(deps.storage, &pool)?;
+
+    Ok(Response::new()
+        .add_message(send_native(deps.as_ref(), &trader, return_amount)?)
+        .add_attributes(vec![
+            ("action", "swap"),
+            ("trader", trader.as_str()),
+            ("offer_asset", "shard_token"),
+            ("offer_amount", &offer_amount.to_string()),
+            ("return_amount", &return_amount.to_string()),
+        ]))
+}
+
+// Constant product: the return keeps offer_reserve * ask_reserve unchanged,
+// then the commission is taken out of it and stays in the pool
+fn compute_swap(
+    config: &Config,
+    offer_reserve: Uint128,
+    ask_reserve: Uint128,
+    offer_amount: Uint128,
+    min_return: Option<Uint128>,
+) -> Result<Uint128, ContractError> {
+    if offer_reserve.is_zero() || ask_reserve.is_zero() {
+        return Err(ContractError::NoLiquidity {});
+    }
+    let return_amount = ask_reserve.multiply_ratio(
+        offer_amount,
+        offer_reserve
+            .checked_add(offer_amount)
+            .map_err(StdError::from)?,
+    );
+    let commission = return_amount.multiply_ratio(config.commission_bps, MAX_COMMISSION_BPS);
+    let return_amount = return_amount - commission;
+    if return_amount.is_zero() || return_amount < min_return.unwrap_or_default() {
+        return Err(ContractError::MaxSlippage {
+            amount: return_amount,
+        });
+    }
+    Ok(return_amount)
+}
+
+fn native_funds(info: &MessageInfo, config: &Config) -> Result<Uint128, ContractError> {
+    match info.funds.as_slice() {
+        [coin] if coin.denom == config.native_denom && !coin.amount.is_zero() => Ok(coin.amount),
+        _ => Err(ContractError::InvalidFunds {
+            denom: config.native_denom.clone(),
+        }),
+    }
+}
+
+fn isqrt(n: u128) -> u128 {
+    if n < 2 {
+        return n;
+    }
+    let mut x = n;
+    let mut y = n / 2 + n % 2;
+    while y < x {
+        x = y;
+        y = (x + n / x) / 2;
+    }
+    x
+}
+
+fn send_tokens(deps: Deps, recipient: &Addr, amount: Uint128) -> StdResult<CosmosMsg> {
+    let config: Config = read_config(deps.storage)?;
+    Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: deps.api.addr_humanize(&config.shard_token)?.to_string(),
+        msg: to_binary(&Cw20ExecuteMsg::Transfer {
+            recipient: recipient.to_string(),
+            amount,
+        })?,
+        funds: vec![],
+    }))
+}
+
+fn send_native(deps: Deps, recipient: &Addr, amount: Uint128) -> StdResult<CosmosMsg> {
+    let config: Config = read_config(deps.storage)?;
+    Ok(CosmosMsg::Bank(BankMsg::Send {
+        to_address: recipient.to_string(),
+        amount: coins(amount.u128(), config.native_denom),
+    }))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_binary(&query_config(deps)?),
+        QueryMsg::Pool {} => to_binary(&query_pool(deps)?),
+        QueryMsg::Share { address } => to_binary(&query_share(deps, address)?),
+    }
+}
+
+pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
+    let config: Config = read_config(deps.storage)?;
+    Ok(ConfigResponse {
+        shard_token: deps.api.addr_humanize(&config.shard_token)?.to_string(),
+        native_denom: config.native_denom,
+        commission_bps: config.commission_bps,
+    })
+}
+
+pub fn query_pool(deps: Deps) -> StdResult<PoolResponse> {
+    let pool = read_pool(deps.storage)?;
+    Ok(PoolResponse {
+        token_reserve: pool.token_reserve,
+        native_reserve: pool.native_reserve,
+        total_shares: pool.total_shares,
+    })
+}
+
+pub fn query_share(deps: Deps, address: String) -> StdResult<ShareResponse> {
+    let shares = read_shares(deps.storage, &deps.api.addr_canonicalize(&address)?)?;
+    let pool = read_pool(deps.storage)?;
+    let (token_amount, native_amount) = if shares.is_zero() {
+        (Uint128::zero(), Uint128::zero())
+    } else {
+        (
+            pool.token_reserve.multiply_ratio(shares, pool.total_shares),
+            pool.native_reserve
+                .multiply_ratio(shares, pool.total_shares),
+        )
+    };
+    Ok(ShareResponse {
+        shares,
+        token_amount,
+        native_amount,
+    })
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    Ok(Response::default())
+}
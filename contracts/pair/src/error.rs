@@ -0,0 +1,35 @@
+use cosmwasm_std::{StdError, Uint128};
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Commission must be at most 10000 basis points")]
+    InvalidCommission {},
+
+    #[error("Exactly one coin of {denom} must be attached")]
+    InvalidFunds { denom: String },
+
+    #[error("Amount must be greater than zero")]
+    ZeroAmount {},
+
+    #[error("Only {shares} shares would be issued")]
+    TooFewShares { shares: Uint128 },
+
+    #[error("Swap would only return {amount}")]
+    MaxSlippage { amount: Uint128 },
+
+    #[error("Pool has no liquidity")]
+    NoLiquidity {},
+
+    #[error("Insufficient shares")]
+    InsufficientShares {},
+
+    #[error("Unknown reply id {id}")]
+    UnknownReply { id: u64 },
+}
@@ -0,0 +1,18 @@
+use cosmwasm_std::{to_binary, Addr, QuerierWrapper, QueryRequest, StdResult, Uint128, WasmQuery};
+use cw20::{BalanceResponse, Cw20QueryMsg};
+
+/// Token balance of `account`
+pub fn query_token_balance(
+    querier: &QuerierWrapper,
+    shard_token: Addr,
+    account: Addr,
+) -> StdResult<Uint128> {
+    let res: BalanceResponse = querier.query(&QueryRequest::Wasm(WasmQuery::Smart {
+        contract_addr: shard_token.to_string(),
+        msg: to_binary(&Cw20QueryMsg::Balance {
+            address: account.to_string(),
+        })?,
+    }))?;
+
+    Ok(res.balance)
+}
@@ -0,0 +1,85 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::{CanonicalAddr, StdResult, Storage, Uint128};
+use cosmwasm_storage::{singleton, singleton_read, Bucket, ReadonlyBucket};
+
+static KEY_CONFIG: &[u8] = b"config";
+static KEY_POOL: &[u8] = b"pool";
+static KEY_PENDING_PROVIDE: &[u8] = b"pending_provide";
+
+static PREFIX_SHARES: &[u8] = b"shares";
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Config {
+    pub shard_token: CanonicalAddr,
+    pub native_denom: String,
+    pub commission_bps: u16,
+}
+
+/// Reserves are tracked rather than read from balances, so tokens sent to the
+/// contract outside of its messages do not move the price
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct Pool {
+    pub token_reserve: Uint128,
+    pub native_reserve: Uint128,
+    pub total_shares: Uint128,
+}
+
+/// A deposit waiting for its TransferFrom, after which the tokens received are
+/// measured against `token_balance`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingProvide {
+    pub provider: CanonicalAddr,
+    pub native_amount: Uint128,
+    pub min_shares: Option<Uint128>,
+    pub token_balance: Uint128,
+}
+
+pub fn store_config(storage: &mut dyn Storage, config: &Config) -> StdResult<()> {
+    singleton(storage, KEY_CONFIG).save(config)
+}
+
+pub fn read_config(storage: &dyn Storage) -> StdResult<Config> {
+    singleton_read(storage, KEY_CONFIG).load()
+}
+
+pub fn store_pool(storage: &mut dyn Storage, pool: &Pool) -> StdResult<()> {
+    singleton(storage, KEY_POOL).save(pool)
+}
+
+pub fn read_pool(storage: &dyn Storage) -> StdResult<Pool> {
+    singleton_read(storage, KEY_POOL).load()
+}
+
+pub fn store_pending_provide(storage: &mut dyn Storage, pending: &PendingProvide) -> StdResult<()> {
+    singleton(storage, KEY_PENDING_PROVIDE).save(pending)
+}
+
+pub fn read_pending_provide(storage: &dyn Storage) -> StdResult<PendingProvide> {
+    singleton_read(storage, KEY_PENDING_PROVIDE).load()
+}
+
+pub fn remove_pending_provide(storage: &mut dyn Storage) {
+    singleton::<PendingProvide>(storage, KEY_PENDING_PROVIDE).remove()
+}
+
+pub fn store_shares(
+    storage: &mut dyn Storage,
+    owner: &CanonicalAddr,
+    shares: Uint128,
+) -> StdResult<()> {
+    let mut shares_bucket: Bucket<Uint128> = Bucket::new(storage, PREFIX_SHARES);
+    if shares.is_zero() {
+        shares_bucket.remove(owner.as_slice());
+        return Ok(());
+    }
+    shares_bucket.save(owner.as_slice(), &shares)
+}
+
+pub fn read_shares(storage: &dyn Storage, owner: &CanonicalAddr) -> StdResult<Uint128> {
+    let shares_bucket: ReadonlyBucket<Uint128> = ReadonlyBucket::new(storage, PREFIX_SHARES);
+    Ok(shares_bucket
+        .may_load(owner.as_slice())?
+        .unwrap_or_default())
+}
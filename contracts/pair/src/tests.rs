@@ -0,0 +1,296 @@
+use crate::contract::{execute, instantiate, query, reply};
+use crate::error::ContractError;
+use crate::mock_querier::{mock_dependencies, WasmMockQuerier};
+use cosmwasm_std::testing::{mock_env, mock_info, MockApi, MockStorage, MOCK_CONTRACT_ADDR};
+use cosmwasm_std::{
+    coins, from_binary, to_binary, BankMsg, ContractResult, CosmosMsg, OwnedDeps, Reply, Response,
+    SubMsg, SubMsgExecutionResponse, Uint128, WasmMsg,
+};
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use shard_token::pair::{
+    Cw20HookMsg, ExecuteMsg, InstantiateMsg, PoolResponse, QueryMsg, ShareResponse,
+};
+
+fn setup() -> OwnedDeps<MockStorage, MockApi, WasmMockQuerier> {
+    let mut deps = mock_dependencies(&[]);
+    let msg = InstantiateMsg {
+        shard_token: "shard0000".to_string(),
+        native_denom: "uluna".to_string(),
+        commission_bps: 30,
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+    deps
+}
+
+// Provides liquidity and completes the TransferFrom, of which the pair
+// receives `received` tokens
+fn provide(
+    deps: &mut OwnedDeps<MockStorage, MockApi, WasmMockQuerier>,
+    provider: &str,
+    token_amount: u128,
+    native_amount: u128,
+    received: u128,
+) -> Result<Response, ContractError> {
+    let msg = ExecuteMsg::ProvideLiquidity {
+        token_amount: Uint128::from(token_amount),
+        min_shares: None,
+    };
+    execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info(provider, &coins(native_amount, "uluna")),
+        msg,
+    )?;
+    let balance = deps.querier.token_balance() + Uint128::from(received);
+    deps.querier.with_token_balance(balance);
+    let msg = Reply {
+        id: 1,
+        result: ContractResult::Ok(SubMsgExecutionResponse {
+            events: vec![],
+            data: None,
+        }),
+    };
+    reply(deps.as_mut(), mock_env(), msg)
+}
+
+fn query_pool(deps: &OwnedDeps<MockStorage, MockApi, WasmMockQuerier>) -> PoolResponse {
+    from_binary(&query(deps.as_ref(), mock_env(), QueryMsg::Pool {}).unwrap()).unwrap()
+}
+
+fn transfer_msg(recipient: &str, amount: u128) -> SubMsg {
+    SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: "shard0000".to_string(),
+        msg: to_binary(&Cw20ExecuteMsg::Transfer {
+            recipient: recipient.to_string(),
+            amount: Uint128::from(amount),
+        })
+        .unwrap(),
+        funds: vec![],
+    }))
+}
+
+fn bank_msg(recipient: &str, amount: u128) -> SubMsg {
+    SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+        to_address: recipient.to_string(),
+        amount: coins(amount, "uluna"),
+    }))
+}
+
+#[test]
+fn provide_and_withdraw_liquidity() {
+    let mut deps = setup();
+    let msg = ExecuteMsg::ProvideLiquidity {
+        token_amount: Uint128::from(4000u128),
+        min_shares: None,
+    };
+    let info = mock_info("lp0000", &coins(1000, "uluna"));
+    let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::reply_on_success(
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "shard0000".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::TransferFrom {
+                    owner: "lp0000".to_string(),
+                    recipient: MOCK_CONTRACT_ADDR.to_string(),
+                    amount: Uint128::from(4000u128),
+                })
+                .unwrap(),
+                funds: vec![],
+            }),
+            1
+        )]
+    );
+    // nothing is issued before the tokens arrive
+    assert_eq!(query_pool(&deps).total_shares, Uint128::zero());
+    deps.querier.with_token_balance(Uint128::from(4000u128));
+    let msg = Reply {
+        id: 1,
+        result: ContractResult::Ok(SubMsgExecutionResponse {
+            events: vec![],
+            data: None,
+        }),
+    };
+    reply(deps.as_mut(), mock_env(), msg).unwrap();
+    assert_eq!(
+        query_pool(&deps),
+        PoolResponse {
+            token_reserve: Uint128::from(4000u128),
+            native_reserve: Uint128::from(1000u128),
+            total_shares: Uint128::from(2000u128),
+        }
+    );
+
+    // later providers get shares at the pool ratio
+    provide(&mut deps, "lp1111", 400, 100, 400).unwrap();
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Share {
+            address: "lp1111".to_string(),
+        },
+    )
+    .unwrap();
+    let share: ShareResponse = from_binary(&res).unwrap();
+    assert_eq!(share.shares, Uint128::from(200u128));
+    assert_eq!(share.token_amount, Uint128::from(400u128));
+
+    let msg = ExecuteMsg::WithdrawLiquidity {
+        shares: Uint128::from(201u128),
+    };
+    match execute(deps.as_mut(), mock_env(), mock_info("lp1111", &[]), msg).unwrap_err() {
+        ContractError::InsufficientShares {} => {}
+        e => panic!("unexpected error: {:?}", e),
+    }
+    let msg = ExecuteMsg::WithdrawLiquidity {
+        shares: Uint128::from(200u128),
+    };
+    let res = execute(deps.as_mut(), mock_env(), mock_info("lp1111", &[]), msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![transfer_msg("lp1111", 400), bank_msg("lp1111", 100)]
+    );
+    assert_eq!(query_pool(&deps).total_shares, Uint128::from(2000u128));
+}
+
+#[test]
+fn provide_requires_native_denom() {
+    let mut deps = setup();
+    let msg = ExecuteMsg::ProvideLiquidity {
+        token_amount: Uint128::from(100u128),
+        min_shares: None,
+    };
+    let info = mock_info("lp0000", &coins(100, "uusd"));
+    match execute(deps.as_mut(), mock_env(), info, msg).unwrap_err() {
+        ContractError::InvalidFunds { denom } => assert_eq!(denom, "uluna"),
+        e => panic!("unexpected error: {:?}", e),
+    }
+}
+
+#[test]
+fn swap_both_ways() {
+    let mut deps = setup();
+    match execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("trader0000", &coins(100, "uluna")),
+        ExecuteMsg::Swap { min_return: None },
+    )
+    .unwrap_err()
+    {
+        ContractError::NoLiquidity {} => {}
+        e => panic!("unexpected error: {:?}", e),
+    }
+    provide(&mut deps, "lp0000", 10000, 10000, 10000).unwrap();
+
+    // 10000 * 1000 / 11000 = 909, less 0.3% commission
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("trader0000", &coins(1000, "uluna")),
+        ExecuteMsg::Swap { min_return: None },
+    )
+    .unwrap();
+    assert_eq!(res.messages, vec![transfer_msg("trader0000", 907)]);
+    let pool = query_pool(&deps);
+    assert_eq!(pool.token_reserve, Uint128::from(9093u128));
+    assert_eq!(pool.native_reserve, Uint128::from(11000u128));
+    // the tokens were sent out, then the trader sends them back
+    deps.querier.with_token_balance(Uint128::from(10000u128));
+
+    let swap = |min_return: Option<u128>| {
+        ExecuteMsg::Receive(Cw20ReceiveMsg {
+            sender: "trader0000".to_string(),
+            amount: Uint128::from(907u128),
+            msg: to_binary(&Cw20HookMsg::Swap {
+                min_return: min_return.map(Uint128::from),
+            })
+            .unwrap(),
+        })
+    };
+    // 11000 * 907 / 10000 = 997, less 0.3% commission
+    match execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("shard0000", &[]),
+        swap(Some(1000)),
+    )
+    .unwrap_err()
+    {
+        ContractError::MaxSlippage { amount } => assert_eq!(amount, Uint128::from(995u128)),
+        e => panic!("unexpected error: {:?}", e),
+    }
+    match execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("other0000", &[]),
+        swap(None),
+    )
+    .unwrap_err()
+    {
+        ContractError::Unauthorized {} => {}
+        e => panic!("unexpected error: {:?}", e),
+    }
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("shard0000", &[]),
+        swap(None),
+    )
+    .unwrap();
+    assert_eq!(res.messages, vec![bank_msg("trader0000", 995)]);
+}
+
+#[test]
+fn provide_counts_tokens_received() {
+    let mut deps = setup();
+    // a 1% transfer fee leaves the pair with less than was pulled
+    provide(&mut deps, "lp0000", 4000, 1000, 3960).unwrap();
+    assert_eq!(query_pool(&deps).token_reserve, Uint128::from(3960u128));
+
+    // later shares are issued for the tokens received
+    let res = provide(&mut deps, "lp1111", 400, 100, 396).unwrap();
+    assert!(res
+        .attributes
+        .iter()
+        .any(|attr| attr.key == "token_amount" && attr.value == "396"));
+    let pool = query_pool(&deps);
+    assert_eq!(pool.token_reserve, Uint128::from(4356u128));
+    assert_eq!(pool.native_reserve, Uint128::from(1100u128));
+
+    match provide(&mut deps, "lp2222", 400, 100, 0).unwrap_err() {
+        ContractError::ZeroAmount {} => {}
+        e => panic!("unexpected error: {:?}", e),
+    }
+}
+
+#[test]
+fn swap_counts_tokens_received() {
+    let mut deps = setup();
+    provide(&mut deps, "lp0000", 10000, 10000, 10000).unwrap();
+
+    // a 1% transfer fee leaves the pair with 990 of the 1000 sent
+    deps.querier.with_token_balance(Uint128::from(10990u128));
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "trader0000".to_string(),
+        amount: Uint128::from(1000u128),
+        msg: to_binary(&Cw20HookMsg::Swap { min_return: None }).unwrap(),
+    });
+    let res = execute(deps.as_mut(), mock_env(), mock_info("shard0000", &[]), msg).unwrap();
+    // 10000 * 990 / 10990 = 900, less 0.3% commission
+    assert_eq!(res.messages, vec![bank_msg("trader0000", 898)]);
+    let pool = query_pool(&deps);
+    assert_eq!(pool.token_reserve, Uint128::from(10990u128));
+    assert_eq!(pool.native_reserve, Uint128::from(9102u128));
+
+    // nothing beyond the reserve arrived
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "trader0000".to_string(),
+        amount: Uint128::from(1000u128),
+        msg: to_binary(&Cw20HookMsg::Swap { min_return: None }).unwrap(),
+    });
+    match execute(deps.as_mut(), mock_env(), mock_info("shard0000", &[]), msg).unwrap_err() {
+        ContractError::ZeroAmount {} => {}
+        e => panic!("unexpected error: {:?}", e),
+    }
+}
@@ -0,0 +1,252 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+
+use crate::error::ContractError;
+use crate::state::{
+    read_all_cw20_received, read_all_released, read_config, read_cw20_received, read_released,
+    read_total_released, store_config, store_cw20_received, store_released, store_total_released,
+    AssetRaw, Config, PayeeRaw,
+};
+
+use cosmwasm_std::{
+    to_binary, BankMsg, Binary, Coin, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Response,
+    StdError, StdResult, Uint128, WasmMsg,
+};
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use shard_token::splitter::{
+    Asset, AssetAmount, ConfigResponse, ExecuteMsg, InstantiateMsg, MigrateMsg, Payee,
+    PayeeResponse, QueryMsg,
+};
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    let mut payees: Vec<PayeeRaw> = vec![];
+    for payee in msg.payees {
+        let address = deps.api.addr_canonicalize(&payee.address)?;
+        if payee.weight == 0 || payees.iter().any(|other| other.address == address) {
+            return Err(ContractError::InvalidPayees {});
+        }
+        payees.push(PayeeRaw {
+            address,
+            weight: payee.weight,
+        });
+    }
+    if payees.is_empty() {
+        return Err(ContractError::InvalidPayees {});
+    }
+    store_config(deps.storage, &Config { payees })?;
+
+    Ok(Response::default())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Receive(msg) => receive_cw20(deps, info, msg),
+        ExecuteMsg::Release { asset } => release(deps, env, asset),
+    }
+}
+
+/// Any cw20 token can be split, the sender of the hook is the token contract
+pub fn receive_cw20(
+    deps: DepsMut,
+    info: MessageInfo,
+    cw20_msg: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    if cw20_msg.amount.is_zero() {
+        return Err(ContractError::NoFunds {});
+    }
+    let token = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let received = read_cw20_received(deps.storage, &token)?;
+    store_cw20_received(deps.storage, &token, received + cw20_msg.amount)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "receive"),
+        ("token", info.sender.as_str()),
+        ("from", &cw20_msg.sender),
+        ("amount", &cw20_msg.amount.to_string()),
+    ]))
+}
+
+/// Releases a single asset, so a token that fails to transfer cannot hold up
+/// the others
+pub fn release(deps: DepsMut, env: Env, asset: Asset) -> Result<Response, ContractError> {
+    let config: Config = read_config(deps.storage)?;
+    let asset = asset_raw(deps.as_ref(), asset)?;
+    let received = read_asset_received(deps.as_ref(), &env, &asset)?;
+    let total_weight = config.total_weight();
+
+    let mut messages: Vec<CosmosMsg> = vec![];
+    for payee in config.payees.iter() {
+        let released = read_released(deps.storage, &payee.address, &asset)?;
+        let amount = received.multiply_ratio(payee.weight, total_weight) - released;
+        if amount.is_zero() {
+            continue;
+        }
+        store_released(deps.storage, &payee.address, &asset, released + amount)?;
+        let total_released = read_total_released(deps.storage, &asset)?;
+        store_total_released(deps.storage, &asset, total_released + amount)?;
+
+        let recipient = deps.api.addr_humanize(&payee.address)?.to_string();
+        messages.push(match &asset {
+            AssetRaw::Native(denom) => CosmosMsg::Bank(BankMsg::Send {
+                to_address: recipient,
+                amount: vec![Coin {
+                    denom: denom.clone(),
+                    amount,
+                }],
+            }),
+            AssetRaw::Cw20(token) => CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: deps.api.addr_humanize(token)?.to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer { recipient, amount })?,
+                funds: vec![],
+            }),
+        });
+    }
+    if messages.is_empty() {
+        return Err(ContractError::NothingToRelease {});
+    }
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attribute("action", "release"))
+}
+
+/// Total amount ever received of `asset`, see `read_received`
+fn read_asset_received(deps: Deps, env: &Env, asset: &AssetRaw) -> StdResult<Uint128> {
+    match asset {
+        AssetRaw::Native(denom) => {
+            let balance = deps
+                .querier
+                .query_balance(env.contract.address.as_str(), denom)?;
+            Ok(balance.amount + read_total_released(deps.storage, asset)?)
+        }
+        AssetRaw::Cw20(token) => read_cw20_received(deps.storage, token),
+    }
+}
+
+/// Total amount ever received of every asset held or accounted. Native coins
+/// are not accounted on receipt, their total is the balance of the contract
+/// plus what was released already.
+fn read_received(deps: Deps, env: &Env) -> StdResult<Vec<(AssetRaw, Uint128)>> {
+    let mut received: Vec<(AssetRaw, Uint128)> = read_all_cw20_received(deps.storage)?
+        .into_iter()
+        .map(|(token, amount)| (AssetRaw::Cw20(token), amount))
+        .collect();
+    for coin in deps
+        .querier
+        .query_all_balances(env.contract.address.as_str())?
+    {
+        let asset = AssetRaw::Native(coin.denom);
+        let released = read_total_released(deps.storage, &asset)?;
+        received.push((asset, coin.amount + released));
+    }
+    Ok(received)
+}
+
+/// Share of `payee` in what was received, minus what was released to it
+fn releasable(
+    deps: Deps,
+    config: &Config,
+    payee: &PayeeRaw,
+    received: &[(AssetRaw, Uint128)],
+) -> StdResult<Vec<(AssetRaw, Uint128)>> {
+    let total_weight = config.total_weight();
+    let mut releasable = vec![];
+    for (asset, amount) in received {
+        let share = amount.multiply_ratio(payee.weight, total_weight);
+        let due = share - read_released(deps.storage, &payee.address, asset)?;
+        if !due.is_zero() {
+            releasable.push((asset.clone(), due));
+        }
+    }
+    Ok(releasable)
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_binary(&query_config(deps)?),
+        QueryMsg::Payee { address } => to_binary(&query_payee(deps, env, address)?),
+    }
+}
+
+pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
+    let config: Config = read_config(deps.storage)?;
+    let payees = config
+        .payees
+        .into_iter()
+        .map(|payee| {
+            Ok(Payee {
+                address: deps.api.addr_humanize(&payee.address)?.to_string(),
+                weight: payee.weight,
+            })
+        })
+        .collect::<StdResult<Vec<Payee>>>()?;
+    Ok(ConfigResponse { payees })
+}
+
+pub fn query_payee(deps: Deps, env: Env, address: String) -> StdResult<PayeeResponse> {
+    let config: Config = read_config(deps.storage)?;
+    let address_raw = deps.api.addr_canonicalize(&address)?;
+    let payee = match config
+        .payees
+        .iter()
+        .find(|payee| payee.address == address_raw)
+    {
+        Some(payee) => payee,
+        None => return Err(StdError::not_found("Payee")),
+    };
+
+    let received = read_received(deps, &env)?;
+    let to_amounts = |assets: Vec<(AssetRaw, Uint128)>| {
+        assets
+            .into_iter()
+            .map(|(asset, amount)| {
+                Ok(AssetAmount {
+                    asset: asset_response(deps, asset)?,
+                    amount,
+                })
+            })
+            .collect::<StdResult<Vec<AssetAmount>>>()
+    };
+    Ok(PayeeResponse {
+        address,
+        weight: payee.weight,
+        released: to_amounts(read_all_released(deps.storage, &payee.address)?)?,
+        releasable: to_amounts(releasable(deps, &config, payee, &received)?)?,
+    })
+}
+
+fn asset_raw(deps: Deps, asset: Asset) -> StdResult<AssetRaw> {
+    Ok(match asset {
+        Asset::Native { denom } => AssetRaw::Native(denom),
+        Asset::Cw20 { contract_addr } => {
+            AssetRaw::Cw20(deps.api.addr_canonicalize(&contract_addr)?)
+        }
+    })
+}
+
+fn asset_response(deps: Deps, asset: AssetRaw) -> StdResult<Asset> {
+    Ok(match asset {
+        AssetRaw::Native(denom) => Asset::Native { denom },
+        AssetRaw::Cw20(token) => Asset::Cw20 {
+            contract_addr: deps.api.addr_humanize(&token)?.to_string(),
+        },
+    })
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    Ok(Response::default())
+}
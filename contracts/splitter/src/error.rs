@@ -0,0 +1,17 @@
+use cosmwasm_std::StdError;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Payees must be unique with weights greater than zero")]
+    InvalidPayees {},
+
+    #[error("Amount must be greater than zero")]
+    NoFunds {},
+
+    #[error("Nothing to release")]
+    NothingToRelease {},
+}
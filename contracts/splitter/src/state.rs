@@ -0,0 +1,149 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::{CanonicalAddr, Order, StdError, StdResult, Storage, Uint128};
+use cosmwasm_storage::{singleton, singleton_read, Bucket, ReadonlyBucket};
+
+static KEY_CONFIG: &[u8] = b"config";
+
+static PREFIX_CW20_RECEIVED: &[u8] = b"cw20_received";
+static PREFIX_TOTAL_RELEASED: &[u8] = b"total_released";
+static PREFIX_RELEASED: &[u8] = b"released";
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Config {
+    pub payees: Vec<PayeeRaw>,
+}
+
+impl Config {
+    pub fn total_weight(&self) -> u64 {
+        self.payees.iter().map(|payee| payee.weight).sum()
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PayeeRaw {
+    pub address: CanonicalAddr,
+    pub weight: u64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum AssetRaw {
+    Native(String),
+    Cw20(CanonicalAddr),
+}
+
+const NATIVE_TAG: u8 = b'n';
+const CW20_TAG: u8 = b'c';
+
+impl AssetRaw {
+    /// Storage key of the asset, a tag byte followed by the denom or address
+    pub fn key(&self) -> Vec<u8> {
+        match self {
+            AssetRaw::Native(denom) => [&[NATIVE_TAG], denom.as_bytes()].concat(),
+            AssetRaw::Cw20(contract_addr) => [&[CW20_TAG], contract_addr.as_slice()].concat(),
+        }
+    }
+
+    pub fn from_key(key: &[u8]) -> StdResult<AssetRaw> {
+        match key.split_first() {
+            Some((&NATIVE_TAG, denom)) => Ok(AssetRaw::Native(
+                String::from_utf8(denom.to_vec()).map_err(StdError::invalid_utf8)?,
+            )),
+            Some((&CW20_TAG, contract_addr)) => {
+                Ok(AssetRaw::Cw20(CanonicalAddr::from(contract_addr.to_vec())))
+            }
+            _ => Err(StdError::generic_err("Invalid asset key")),
+        }
+    }
+}
+
+pub fn store_config(storage: &mut dyn Storage, config: &Config) -> StdResult<()> {
+    singleton(storage, KEY_CONFIG).save(config)
+}
+
+pub fn read_config(storage: &dyn Storage) -> StdResult<Config> {
+    singleton_read(storage, KEY_CONFIG).load()
+}
+
+pub fn store_cw20_received(
+    storage: &mut dyn Storage,
+    token: &CanonicalAddr,
+    amount: Uint128,
+) -> StdResult<()> {
+    let mut received_bucket: Bucket<Uint128> = Bucket::new(storage, PREFIX_CW20_RECEIVED);
+    received_bucket.save(token.as_slice(), &amount)
+}
+
+pub fn read_cw20_received(storage: &dyn Storage, token: &CanonicalAddr) -> StdResult<Uint128> {
+    let received_bucket: ReadonlyBucket<Uint128> =
+        ReadonlyBucket::new(storage, PREFIX_CW20_RECEIVED);
+    Ok(received_bucket
+        .may_load(token.as_slice())?
+        .unwrap_or_default())
+}
+
+/// Every cw20 token received so far with the total amount received
+pub fn read_all_cw20_received(storage: &dyn Storage) -> StdResult<Vec<(CanonicalAddr, Uint128)>> {
+    let received_bucket: ReadonlyBucket<Uint128> =
+        ReadonlyBucket::new(storage, PREFIX_CW20_RECEIVED);
+    received_bucket
+        .range(None, None, Order::Ascending)
+        .map(|item| {
+            let (k, v) = item?;
+            Ok((CanonicalAddr::from(k), v))
+        })
+        .collect()
+}
+
+pub fn store_total_released(
+    storage: &mut dyn Storage,
+    asset: &AssetRaw,
+    amount: Uint128,
+) -> StdResult<()> {
+    let mut released_bucket: Bucket<Uint128> = Bucket::new(storage, PREFIX_TOTAL_RELEASED);
+    released_bucket.save(&asset.key(), &amount)
+}
+
+pub fn read_total_released(storage: &dyn Storage, asset: &AssetRaw) -> StdResult<Uint128> {
+    let released_bucket: ReadonlyBucket<Uint128> =
+        ReadonlyBucket::new(storage, PREFIX_TOTAL_RELEASED);
+    Ok(released_bucket.may_load(&asset.key())?.unwrap_or_default())
+}
+
+pub fn store_released(
+    storage: &mut dyn Storage,
+    payee: &CanonicalAddr,
+    asset: &AssetRaw,
+    amount: Uint128,
+) -> StdResult<()> {
+    let mut released_bucket: Bucket<Uint128> =
+        Bucket::multilevel(storage, &[PREFIX_RELEASED, payee.as_slice()]);
+    released_bucket.save(&asset.key(), &amount)
+}
+
+pub fn read_released(
+    storage: &dyn Storage,
+    payee: &CanonicalAddr,
+    asset: &AssetRaw,
+) -> StdResult<Uint128> {
+    let released_bucket: ReadonlyBucket<Uint128> =
+        ReadonlyBucket::multilevel(storage, &[PREFIX_RELEASED, payee.as_slice()]);
+    Ok(released_bucket.may_load(&asset.key())?.unwrap_or_default())
+}
+
+/// Everything released to `payee` so far, by asset
+pub fn read_all_released(
+    storage: &dyn Storage,
+    payee: &CanonicalAddr,
+) -> StdResult<Vec<(AssetRaw, Uint128)>> {
+    let released_bucket: ReadonlyBucket<Uint128> =
+        ReadonlyBucket::multilevel(storage, &[PREFIX_RELEASED, payee.as_slice()]);
+    released_bucket
+        .range(None, None, Order::Ascending)
+        .map(|item| {
+            let (k, v) = item?;
+            Ok((AssetRaw::from_key(&k)?, v))
+        })
+        .collect()
+}
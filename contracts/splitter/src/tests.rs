@@ -0,0 +1,233 @@
+use crate::contract::{execute, instantiate, query};
+use crate::error::ContractError;
+use cosmwasm_std::testing::{
+    mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage, MOCK_CONTRACT_ADDR,
+};
+use cosmwasm_std::{
+    coins, from_binary, to_binary, BankMsg, Coin, CosmosMsg, OwnedDeps, StdError, SubMsg, Uint128,
+    WasmMsg,
+};
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use shard_token::splitter::{
+    Asset, AssetAmount, ConfigResponse, ExecuteMsg, InstantiateMsg, Payee, PayeeResponse, QueryMsg,
+};
+
+fn setup(balance: &[Coin]) -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+    let mut deps = mock_dependencies(balance);
+    let msg = InstantiateMsg {
+        payees: vec![
+            Payee {
+                address: "alice0000".to_string(),
+                weight: 1,
+            },
+            Payee {
+                address: "bob0000".to_string(),
+                weight: 3,
+            },
+        ],
+    };
+    instantiate(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+    deps
+}
+
+fn receive(deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>, token: &str, amount: u128) {
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "payer0000".to_string(),
+        amount: Uint128::from(amount),
+        msg: to_binary(&"").unwrap(),
+    });
+    execute(deps.as_mut(), mock_env(), mock_info(token, &[]), msg).unwrap();
+}
+
+fn release(
+    deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+    asset: Asset,
+) -> Result<Vec<SubMsg>, ContractError> {
+    let res = execute(
+        deps.as_mut(),
+        mock_env(),
+        mock_info("anyone0000", &[]),
+        ExecuteMsg::Release { asset },
+    )?;
+    Ok(res.messages)
+}
+
+fn cw20(token: &str) -> Asset {
+    Asset::Cw20 {
+        contract_addr: token.to_string(),
+    }
+}
+
+fn native(denom: &str) -> Asset {
+    Asset::Native {
+        denom: denom.to_string(),
+    }
+}
+
+fn transfer_msg(token: &str, recipient: &str, amount: u128) -> SubMsg {
+    SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: token.to_string(),
+        msg: to_binary(&Cw20ExecuteMsg::Transfer {
+            recipient: recipient.to_string(),
+            amount: Uint128::from(amount),
+        })
+        .unwrap(),
+        funds: vec![],
+    }))
+}
+
+fn bank_msg(recipient: &str, amount: u128) -> SubMsg {
+    SubMsg::new(CosmosMsg::Bank(BankMsg::Send {
+        to_address: recipient.to_string(),
+        amount: coins(amount, "ushard"),
+    }))
+}
+
+#[test]
+fn instantiate_validates_payees() {
+    let mut deps = mock_dependencies(&[]);
+    let payee = Payee {
+        address: "alice0000".to_string(),
+        weight: 1,
+    };
+    let msg = InstantiateMsg {
+        payees: vec![payee.clone(), payee.clone()],
+    };
+    let err = instantiate(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg);
+    assert_eq!(err, Err(ContractError::InvalidPayees {}));
+
+    let msg = InstantiateMsg {
+        payees: vec![Payee { weight: 0, ..payee }],
+    };
+    let err = instantiate(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg);
+    assert_eq!(err, Err(ContractError::InvalidPayees {}));
+
+    let deps = setup(&[]);
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+    let config: ConfigResponse = from_binary(&res).unwrap();
+    assert_eq!(config.payees.len(), 2);
+}
+
+#[test]
+fn release_splits_cw20_tokens_pro_rata() {
+    let mut deps = setup(&[]);
+    assert_eq!(
+        release(&mut deps, cw20("shard0000")),
+        Err(ContractError::NothingToRelease {})
+    );
+
+    receive(&mut deps, "shard0000", 100);
+    receive(&mut deps, "shard0000", 100);
+    receive(&mut deps, "other0000", 8);
+    assert_eq!(
+        release(&mut deps, cw20("shard0000")).unwrap(),
+        vec![
+            transfer_msg("shard0000", "alice0000", 50),
+            transfer_msg("shard0000", "bob0000", 150),
+        ]
+    );
+    assert_eq!(
+        release(&mut deps, cw20("shard0000")),
+        Err(ContractError::NothingToRelease {})
+    );
+    assert_eq!(
+        release(&mut deps, cw20("other0000")).unwrap(),
+        vec![
+            transfer_msg("other0000", "alice0000", 2),
+            transfer_msg("other0000", "bob0000", 6),
+        ]
+    );
+
+    // only the new payment is released
+    receive(&mut deps, "shard0000", 40);
+    assert_eq!(
+        release(&mut deps, cw20("shard0000")).unwrap(),
+        vec![
+            transfer_msg("shard0000", "alice0000", 10),
+            transfer_msg("shard0000", "bob0000", 30),
+        ]
+    );
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Payee {
+            address: "bob0000".to_string(),
+        },
+    )
+    .unwrap();
+    let payee: PayeeResponse = from_binary(&res).unwrap();
+    assert_eq!(payee.weight, 3);
+    assert!(payee.releasable.is_empty());
+    assert!(payee.released.contains(&AssetAmount {
+        asset: Asset::Cw20 {
+            contract_addr: "shard0000".to_string()
+        },
+        amount: Uint128::from(180u128),
+    }));
+}
+
+#[test]
+fn release_splits_native_balance() {
+    let mut deps = setup(&coins(400, "ushard"));
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Payee {
+            address: "alice0000".to_string(),
+        },
+    )
+    .unwrap();
+    let payee: PayeeResponse = from_binary(&res).unwrap();
+    assert_eq!(
+        payee.releasable,
+        vec![AssetAmount {
+            asset: Asset::Native {
+                denom: "ushard".to_string()
+            },
+            amount: Uint128::from(100u128),
+        }]
+    );
+
+    assert_eq!(
+        release(&mut deps, native("ushard")).unwrap(),
+        vec![bank_msg("alice0000", 100), bank_msg("bob0000", 300)]
+    );
+
+    // the released coins left the contract and 40 more arrived
+    deps.querier
+        .update_balance(MOCK_CONTRACT_ADDR, coins(40, "ushard"));
+    assert_eq!(
+        release(&mut deps, native("ushard")).unwrap(),
+        vec![bank_msg("alice0000", 10), bank_msg("bob0000", 30)]
+    );
+
+    let err = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Payee {
+            address: "carol0000".to_string(),
+        },
+    );
+    assert_eq!(err, Err(StdError::not_found("Payee")));
+}
+
+#[test]
+fn failing_token_does_not_block_other_assets() {
+    let mut deps = setup(&coins(400, "ushard"));
+    receive(&mut deps, "shard0000", 200);
+    // Any contract can call the hook, including one whose transfers fail
+    receive(&mut deps, "broken0000", 1000);
+
+    assert_eq!(
+        release(&mut deps, native("ushard")).unwrap(),
+        vec![bank_msg("alice0000", 100), bank_msg("bob0000", 300)]
+    );
+    assert_eq!(
+        release(&mut deps, cw20("shard0000")).unwrap(),
+        vec![
+            transfer_msg("shard0000", "alice0000", 50),
+            transfer_msg("shard0000", "bob0000", 150),
+        ]
+    );
+}
@@ -7,8 +7,8 @@ use cosmwasm_std::{
 };
 
 use shard_token::staking::{
-    ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg,
-    StakerInfoResponse, StateResponse,
+    ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, PendingRewardsResponse,
+    QueryMsg, StakedResponse, StakerInfoResponse, StateResponse,
 };
 
 use crate::{
@@ -83,6 +83,15 @@ pub fn receive_cw20(
             let cw20_sender = deps.api.addr_validate(&cw20_msg.sender)?;
             bond(deps, env, cw20_sender, cw20_msg.amount)
         }
+        Ok(Cw20HookMsg::FundRewards { start, end }) => {
+            // only reward token contract can execute this message
+            if config.shard_token != deps.api.addr_canonicalize(info.sender.as_str())? {
+                return Err(StdError::generic_err("unauthorized"));
+            }
+
+            let cw20_sender = deps.api.addr_validate(&cw20_msg.sender)?;
+            fund_rewards(deps, env, cw20_sender, cw20_msg.amount, start, end)
+        }
         Err(_) => Err(StdError::generic_err("data should be given")),
     }
 }
@@ -230,6 +239,36 @@ pub fn update_config(
     Ok(Response::new().add_attributes(vec![("action", "update_config")]))
 }
 
+/// Appends a distribution of the funded amount to the schedule. The funder has
+/// to be gov, as for `UpdateConfig`.
+pub fn fund_rewards(
+    deps: DepsMut,
+    env: Env,
+    funder: Addr,
+    amount: Uint128,
+    start: u64,
+    end: u64,
+) -> StdResult<Response> {
+    let mut config: Config = read_config(deps.storage)?;
+    let anc_token: Addr = deps.api.addr_humanize(&config.shard_token)?;
+    if funder.as_str() != query_anc_minter(&deps.querier, anc_token)? {
+        return Err(StdError::generic_err("unauthorized"));
+    }
+    if start >= end || start < env.block.time.seconds() {
+        return Err(StdError::generic_err("invalid distribution period"));
+    }
+
+    config.distribution_schedule.push((start, end, amount));
+    store_config(deps.storage, &config)?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "fund_rewards"),
+        ("amount", amount.to_string().as_str()),
+        ("start", start.to_string().as_str()),
+        ("end", end.to_string().as_str()),
+    ]))
+}
+
 pub fn migrate_staking(
     deps: DepsMut,
     env: Env,
@@ -358,13 +397,17 @@ fn compute_staker_reward(state: &State, staker_info: &mut StakerInfo) -> StdResu
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Config {} => to_binary(&query_config(deps)?),
         QueryMsg::State { block_time } => to_binary(&query_state(deps, block_time)?),
         QueryMsg::StakerInfo { staker, block_time } => {
             to_binary(&query_staker_info(deps, staker, block_time)?)
         }
+        QueryMsg::Staked { address } => to_binary(&query_staked(deps, address)?),
+        QueryMsg::PendingRewards { address } => {
+            to_binary(&query_pending_rewards(deps, env, address)?)
+        }
     }
 }
 
@@ -417,6 +460,26 @@ pub fn query_staker_info(
     })
 }
 
+pub fn query_staked(deps: Deps, address: String) -> StdResult<StakedResponse> {
+    let staker_info = query_staker_info(deps, address, None)?;
+    Ok(StakedResponse {
+        address: staker_info.staker,
+        staked: staker_info.bond_amount,
+    })
+}
+
+pub fn query_pending_rewards(
+    deps: Deps,
+    env: Env,
+    address: String,
+) -> StdResult<PendingRewardsResponse> {
+    let staker_info = query_staker_info(deps, address, Some(env.block.time.seconds()))?;
+    Ok(PendingRewardsResponse {
+        address: staker_info.staker,
+        pending_rewards: staker_info.pending_reward,
+    })
+}
+
 pub fn assert_new_schedules(
     config: &Config,
     state: &State,
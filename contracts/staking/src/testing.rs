@@ -2,12 +2,12 @@ use crate::contract::{execute, instantiate, query};
 use crate::mock_querier::mock_dependencies;
 use shard_token::staking::ExecuteMsg::UpdateConfig;
 use shard_token::staking::{
-    ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, QueryMsg, StakerInfoResponse,
-    StateResponse,
+    ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, PendingRewardsResponse, QueryMsg,
+    StakedResponse, StakerInfoResponse, StateResponse,
 };
 use cosmwasm_std::testing::{mock_env, mock_info};
 use cosmwasm_std::{
-    attr, from_binary, to_binary, CosmosMsg, Decimal, StdError, SubMsg, Uint128, WasmMsg,
+    attr, from_binary, to_binary, Binary, CosmosMsg, Decimal, StdError, SubMsg, Uint128, WasmMsg,
 };
 use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
 
@@ -1047,3 +1047,109 @@ fn test_update_config() {
         ]
     );
 }
+
+#[test]
+fn test_fund_rewards_and_queries() {
+    let mut deps = mock_dependencies(&[]);
+
+    let msg = InstantiateMsg {
+        shard_token: "reward0000".to_string(),
+        staking_token: "reward0000".to_string(),
+        distribution_schedule: vec![],
+    };
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+    deps.querier.with_anc_minter("gov0000".to_string());
+
+    let fund_msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(1000u128),
+        msg: to_binary(&Cw20HookMsg::FundRewards {
+            start: mock_env().block.time.seconds(),
+            end: mock_env().block.time.seconds() + 100,
+        })
+        .unwrap(),
+    });
+    let info = mock_info("reward0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), fund_msg);
+    match res {
+        Err(StdError::GenericErr { msg, .. }) => assert_eq!(msg, "unauthorized"),
+        _ => panic!("Must return unauthorized error"),
+    }
+
+    let fund_msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "gov0000".to_string(),
+        amount: Uint128::from(1000u128),
+        msg: to_binary(&Cw20HookMsg::FundRewards {
+            start: mock_env().block.time.seconds(),
+            end: mock_env().block.time.seconds() + 100,
+        })
+        .unwrap(),
+    });
+    let _res = execute(deps.as_mut(), mock_env(), info.clone(), fund_msg).unwrap();
+
+    // stake the reward token itself
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: "addr0000".to_string(),
+        amount: Uint128::from(100u128),
+        msg: to_binary(&Cw20HookMsg::Bond {}).unwrap(),
+    });
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Staked {
+            address: "addr0000".to_string(),
+        },
+    )
+    .unwrap();
+    let staked: StakedResponse = from_binary(&res).unwrap();
+    assert_eq!(staked.staked, Uint128::from(100u128));
+
+    // half of the period is passed
+    let mut env = mock_env();
+    env.block.time = env.block.time.plus_seconds(50);
+    let res = query(
+        deps.as_ref(),
+        env.clone(),
+        QueryMsg::PendingRewards {
+            address: "addr0000".to_string(),
+        },
+    )
+    .unwrap();
+    let pending: PendingRewardsResponse = from_binary(&res).unwrap();
+    assert_eq!(pending.pending_rewards, Uint128::from(500u128));
+
+    // unstake and claim are accepted as aliases
+    let msg: ExecuteMsg = from_binary(&Binary::from(br#"{"claim":{}}"#.to_vec())).unwrap();
+    assert_eq!(msg, ExecuteMsg::Withdraw {});
+    let info = mock_info("addr0000", &[]);
+    let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+    assert_eq!(
+        res.messages,
+        vec![SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+            contract_addr: "reward0000".to_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: "addr0000".to_string(),
+                amount: Uint128::from(500u128),
+            })
+            .unwrap(),
+            funds: vec![],
+        }))]
+    );
+
+    let msg: ExecuteMsg =
+        from_binary(&Binary::from(br#"{"unstake":{"amount":"100"}}"#.to_vec())).unwrap();
+    let _res = execute(deps.as_mut(), env, info, msg).unwrap();
+    let res = query(
+        deps.as_ref(),
+        mock_env(),
+        QueryMsg::Staked {
+            address: "addr0000".to_string(),
+        },
+    )
+    .unwrap();
+    let staked: StakedResponse = from_binary(&res).unwrap();
+    assert_eq!(staked.staked, Uint128::zero());
+}
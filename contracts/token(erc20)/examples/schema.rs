@@ -1,22 +1,27 @@
 use std::env::current_dir;
-use std::fs::create_dir_all;
 
-use cosmwasm_schema::{export_schema, remove_schemas, schema_for};
+use cosmwasm_schema::{export_schema, schema_for, write_api};
 
 use cw_erc20::{
-    AllowanceResponse, BalanceResponse, Constants, ExecuteMsg, InstantiateMsg, QueryMsg,
+    AttestationAck, AttestationPacket, Constants, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg,
+    ReceiveMsg, SudoMsg,
 };
 
 fn main() {
+    // Message schemas along with the response of every query, as read by ts-codegen
+    write_api! {
+        instantiate: InstantiateMsg,
+        execute: ExecuteMsg,
+        query: QueryMsg,
+        migrate: MigrateMsg,
+        sudo: SudoMsg,
+    }
+
+    // Types exchanged outside of the entry point messages
     let mut out_dir = current_dir().unwrap();
     out_dir.push("schema");
-    create_dir_all(&out_dir).unwrap();
-    remove_schemas(&out_dir).unwrap();
-
-    export_schema(&schema_for!(InstantiateMsg), &out_dir);
-    export_schema(&schema_for!(ExecuteMsg), &out_dir);
-    export_schema(&schema_for!(QueryMsg), &out_dir);
-    export_schema(&schema_for!(BalanceResponse), &out_dir);
-    export_schema(&schema_for!(AllowanceResponse), &out_dir);
+    export_schema(&schema_for!(ReceiveMsg), &out_dir);
+    export_schema(&schema_for!(AttestationPacket), &out_dir);
+    export_schema(&schema_for!(AttestationAck), &out_dir);
     export_schema(&schema_for!(Constants), &out_dir);
 }
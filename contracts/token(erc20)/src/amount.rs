@@ -0,0 +1,132 @@
+use cosmwasm_std::Uint128;
+use serde::de::{self, Deserializer, Visitor};
+use std::fmt;
+
+// Uint128 only deserializes from strings, but some older SDKs send amounts as
+// JSON numbers. Amount fields of execute messages accept both through
+// `#[serde(deserialize_with = "amount::deserialize")]`. Numbers are limited to
+// what the JSON parser reads as an integer (u64); larger amounts need a string.
+
+struct AmountVisitor;
+
+impl<'de> Visitor<'de> for AmountVisitor {
+    type Value = Uint128;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an amount as string or non-negative integer")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        match v.parse::<u128>() {
+            Ok(amount) => Ok(Uint128::from(amount)),
+            Err(e) => Err(E::custom(format!("invalid amount '{}': {}", v, e))),
+        }
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+        Ok(Uint128::from(v))
+    }
+
+    fn visit_u128<E: de::Error>(self, v: u128) -> Result<Self::Value, E> {
+        Ok(Uint128::from(v))
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+        if v < 0 {
+            return Err(E::custom(format!("invalid amount '{}': negative", v)));
+        }
+        Ok(Uint128::from(v as u64))
+    }
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Uint128, D::Error> {
+    deserializer.deserialize_any(AmountVisitor)
+}
+
+struct OptionalAmountVisitor;
+
+impl<'de> Visitor<'de> for OptionalAmountVisitor {
+    type Value = Option<Uint128>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an optional amount as string or non-negative integer")
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(None)
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+        Ok(None)
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserialize(deserializer).map(Some)
+    }
+}
+
+/// For `Option<Uint128>` fields, which also need `#[serde(default)]` to stay optional
+pub fn deserialize_option<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Uint128>, D::Error> {
+    deserializer.deserialize_option(OptionalAmountVisitor)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::msg::{ExecuteMsg, InitialBalance};
+    use cosmwasm_std::{from_slice, Uint128};
+
+    #[test]
+    fn accepts_strings_and_numbers() {
+        let msg: ExecuteMsg =
+            from_slice(br#"{"transfer":{"recipient":"addr0000","amount":123}}"#).unwrap();
+        match msg {
+            ExecuteMsg::Transfer { amount, memo, .. } => {
+                assert_eq!(amount, Uint128::from(123u128));
+                assert_eq!(memo, None);
+            }
+            _ => panic!("unexpected message"),
+        }
+        let msg: ExecuteMsg =
+            from_slice(br#"{"transfer":{"recipient":"addr0000","amount":"123"}}"#).unwrap();
+        match msg {
+            ExecuteMsg::Transfer { amount, .. } => assert_eq!(amount, Uint128::from(123u128)),
+            _ => panic!("unexpected message"),
+        }
+    }
+
+    #[test]
+    fn accepts_large_values() {
+        let balance: InitialBalance =
+            from_slice(br#"{"address":"addr0000","amount":18446744073709551615}"#).unwrap();
+        assert_eq!(balance.amount, Uint128::from(u64::MAX));
+        let balance: InitialBalance = from_slice(
+            br#"{"address":"addr0000","amount":"340282366920938463463374607431768211455"}"#,
+        )
+        .unwrap();
+        assert_eq!(balance.amount, Uint128::MAX);
+    }
+
+    #[test]
+    fn rejects_negative_and_fractional_amounts() {
+        assert!(from_slice::<InitialBalance>(br#"{"address":"addr0000","amount":-1}"#).is_err());
+        assert!(from_slice::<InitialBalance>(br#"{"address":"addr0000","amount":1.5}"#).is_err());
+        assert!(from_slice::<InitialBalance>(br#"{"address":"addr0000","amount":"1.5"}"#).is_err());
+    }
+
+    #[test]
+    fn optional_amounts_stay_optional() {
+        let msg: ExecuteMsg = from_slice(br#"{"fund_dividends":{}}"#).unwrap();
+        assert_eq!(msg, ExecuteMsg::FundDividends { amount: None });
+        let msg: ExecuteMsg = from_slice(br#"{"fund_dividends":{"amount":null}}"#).unwrap();
+        assert_eq!(msg, ExecuteMsg::FundDividends { amount: None });
+        let msg: ExecuteMsg = from_slice(br#"{"fund_dividends":{"amount":5}}"#).unwrap();
+        assert_eq!(
+            msg,
+            ExecuteMsg::FundDividends {
+                amount: Some(Uint128::from(5u128))
+            }
+        );
+    }
+}
@@ -0,0 +1,252 @@
+use cosmwasm_std::{from_slice, to_vec, DepsMut, Env, MessageInfo, Response, Storage, Uint128};
+use cosmwasm_storage::{PrefixedStorage, ReadonlyPrefixedStorage};
+
+use crate::contract::{assert_owner, burn_balance, checked_add, PREFIX_CONFIG};
+use crate::error::ContractError;
+use crate::events;
+use crate::recovery;
+use crate::state::Buyback;
+
+pub const KEY_BUYBACK: &[u8] = b"buyback";
+
+// While enabled, tokens transferred to the contract's own address (e.g. fees
+// routed here by other protocols) are collected for burning. They are kept
+// apart from the escrows on the same account and burned by `BurnCollected`.
+
+pub fn read_buyback(store: &dyn Storage) -> Result<Buyback, ContractError> {
+    let config_store = ReadonlyPrefixedStorage::new(store, PREFIX_CONFIG);
+    match config_store.get(KEY_BUYBACK) {
+        Some(data) => Ok(from_slice(&data)?),
+        None => Ok(Buyback::default()),
+    }
+}
+
+fn write_buyback(store: &mut dyn Storage, buyback: &Buyback) -> Result<(), ContractError> {
+    let mut config_store = PrefixedStorage::new(store, PREFIX_CONFIG);
+    config_store.set(KEY_BUYBACK, &to_vec(buyback)?);
+    Ok(())
+}
+
+/// Start or stop collecting tokens sent to the contract. Owner only.
+pub fn try_update_buyback(
+    deps: DepsMut,
+    info: MessageInfo,
+    enabled: bool,
+) -> Result<Response, ContractError> {
+    assert_owner(deps.storage, &info.sender)?;
+    let mut buyback = read_buyback(deps.storage)?;
+    buyback.enabled = enabled;
+    write_buyback(deps.storage, &buyback)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "update_buyback")
+        .add_attribute("enabled", enabled.to_string()))
+}
+
+/// Counts `amount` the contract received outside of an escrow: as collected
+/// while collection is enabled, as stuck otherwise. Called by the transfer
+/// itself, see `settle_transfer`.
+pub fn record_deposit(store: &mut dyn Storage, amount: u128) -> Result<(), ContractError> {
+    let mut buyback = read_buyback(store)?;
+    if !buyback.enabled {
+        return recovery::add_stuck_balance(store, amount);
+    }
+    buyback.collected = Uint128::from(checked_add(buyback.collected.u128(), amount)?);
+    write_buyback(store, &buyback)
+}
+
+/// Burn everything collected so far. Anyone can trigger it.
+pub fn try_burn_collected(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let mut buyback = read_buyback(deps.storage)?;
+    if buyback.collected.is_zero() {
+        return Err(ContractError::NothingCollected {});
+    }
+    let amount = buyback.collected;
+    burn_balance(
+        deps.storage,
+        deps.api,
+        &env,
+        &env.contract.address,
+        amount.u128(),
+    )?;
+    buyback.collected = Uint128::zero();
+    buyback.burned = Uint128::from(checked_add(buyback.burned.u128(), amount.u128())?);
+    write_buyback(deps.storage, &buyback)?;
+
+    Ok(Response::new()
+        .add_event(events::burn_event(
+            deps.storage,
+            deps.api,
+            &env.contract.address,
+            amount.u128(),
+        )?)
+        .add_attribute("action", "burn_collected")
+        .add_attribute("amount", amount.to_string())
+        .add_attribute("total_burned", buyback.burned.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate, query, read_balance, read_total_supply};
+    use crate::msg::{BuybackResponse, ExecuteMsg, InitialBalance, InstantiateMsg, QueryMsg};
+    use crate::testing::default_instantiate_msg;
+    use cosmwasm_std::testing::{
+        mock_dependencies_with_balance, mock_env, mock_info, MOCK_CONTRACT_ADDR,
+    };
+    use cosmwasm_std::{Addr, Binary};
+
+    fn setup(deps: DepsMut) {
+        let instantiate_msg = InstantiateMsg {
+            initial_balances: vec![InitialBalance {
+                address: "protocol".to_string(),
+                amount: Uint128::from(1000u128),
+            }],
+            ..default_instantiate_msg()
+        };
+        instantiate(deps, mock_env(), mock_info("creator", &[]), instantiate_msg).unwrap();
+    }
+
+    fn deposit(deps: DepsMut, amount: u128) {
+        let msg = ExecuteMsg::Transfer {
+            recipient: MOCK_CONTRACT_ADDR.to_string(),
+            amount: Uint128::from(amount),
+            memo: None,
+            on_behalf_of: None,
+        };
+        execute(deps, mock_env(), mock_info("protocol", &[]), msg).unwrap();
+    }
+
+    fn update_buyback(deps: DepsMut, enabled: bool) {
+        let msg = ExecuteMsg::UpdateBuyback { enabled };
+        execute(deps, mock_env(), mock_info("creator", &[]), msg).unwrap();
+    }
+
+    #[test]
+    fn burns_collected_deposits() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        update_buyback(deps.as_mut(), true);
+        deposit(deps.as_mut(), 300);
+        deposit(deps.as_mut(), 100);
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("anyone", &[]),
+            ExecuteMsg::BurnCollected {},
+        )
+        .unwrap();
+        assert_eq!(res.attributes[1].value, "400");
+        assert_eq!(read_total_supply(&deps.storage).unwrap(), 600);
+        assert_eq!(
+            read_balance(
+                &deps.storage,
+                &deps.api,
+                &Addr::unchecked(MOCK_CONTRACT_ADDR)
+            )
+            .unwrap(),
+            0
+        );
+
+        let res: BuybackResponse =
+            from_slice(&query(deps.as_ref(), mock_env(), QueryMsg::Buyback {}).unwrap()).unwrap();
+        assert!(res.enabled);
+        assert_eq!(res.collected, Uint128::zero());
+        assert_eq!(res.total_burned, Uint128::from(400u128));
+
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("anyone", &[]),
+            ExecuteMsg::BurnCollected {},
+        )
+        .unwrap_err();
+        match err {
+            ContractError::NothingCollected {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn collects_sends_and_fees() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        update_buyback(deps.as_mut(), true);
+        let msg = ExecuteMsg::Send {
+            contract: MOCK_CONTRACT_ADDR.to_string(),
+            amount: Uint128::from(100u128),
+            msg: Binary::default(),
+            on_failure: None,
+            memo: None,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("protocol", &[]), msg).unwrap();
+
+        let msg = ExecuteMsg::UpdateFeeConfig {
+            enabled: true,
+            rate_bps: 100,
+            treasury: MOCK_CONTRACT_ADDR.to_string(),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let msg = ExecuteMsg::Transfer {
+            recipient: "addr1111".to_string(),
+            amount: Uint128::from(500u128),
+            memo: None,
+            on_behalf_of: None,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("protocol", &[]), msg).unwrap();
+
+        let res: BuybackResponse =
+            from_slice(&query(deps.as_ref(), mock_env(), QueryMsg::Buyback {}).unwrap()).unwrap();
+        assert_eq!(res.collected, Uint128::from(105u128));
+    }
+
+    #[test]
+    fn escrows_are_not_collected() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        update_buyback(deps.as_mut(), true);
+        let msg = ExecuteMsg::TransferLocked {
+            recipient: "addr1111".to_string(),
+            amount: Uint128::from(300u128),
+            release_at: mock_env().block.time.seconds() + 100,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("protocol", &[]), msg).unwrap();
+        deposit(deps.as_mut(), 100);
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("anyone", &[]),
+            ExecuteMsg::BurnCollected {},
+        )
+        .unwrap();
+        assert_eq!(
+            read_balance(
+                &deps.storage,
+                &deps.api,
+                &Addr::unchecked(MOCK_CONTRACT_ADDR)
+            )
+            .unwrap(),
+            300
+        );
+    }
+
+    #[test]
+    fn nothing_is_collected_while_disabled() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        deposit(deps.as_mut(), 100);
+        assert_eq!(
+            read_buyback(&deps.storage).unwrap().collected,
+            Uint128::zero()
+        );
+
+        let msg = ExecuteMsg::UpdateBuyback { enabled: true };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("protocol", &[]), msg).unwrap_err();
+        match err {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+}
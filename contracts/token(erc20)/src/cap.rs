@@ -0,0 +1,98 @@
+use cosmwasm_std::Storage;
+use cosmwasm_storage::{PrefixedStorage, ReadonlyPrefixedStorage};
+
+use crate::contract::{bytes_to_u128, PREFIX_CONFIG};
+use crate::error::ContractError;
+
+pub const KEY_MAX_SUPPLY: &[u8] = b"max_supply";
+
+// The maximum supply is fixed at instantiation. Every path growing the total
+// supply, mints and rebases alike, checks it before writing.
+
+pub fn read_max_supply(store: &dyn Storage) -> Result<Option<u128>, ContractError> {
+    let config_store = ReadonlyPrefixedStorage::new(store, PREFIX_CONFIG);
+    match config_store.get(KEY_MAX_SUPPLY) {
+        Some(data) => Ok(Some(bytes_to_u128(&data)?)),
+        None => Ok(None),
+    }
+}
+
+pub fn write_max_supply(store: &mut dyn Storage, max_supply: u128) {
+    let mut config_store = PrefixedStorage::new(store, PREFIX_CONFIG);
+    config_store.set(KEY_MAX_SUPPLY, &max_supply.to_be_bytes());
+}
+
+/// Errors with CannotExceedCap if `total_supply` is above the maximum supply
+pub fn assert_within_cap(store: &dyn Storage, total_supply: u128) -> Result<(), ContractError> {
+    match read_max_supply(store)? {
+        Some(max_supply) if total_supply > max_supply => Err(ContractError::CannotExceedCap {}),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate, query};
+    use crate::msg::{CapResponse, ExecuteMsg, InitialBalance, InstantiateMsg, QueryMsg};
+    use crate::testing::default_instantiate_msg;
+    use cosmwasm_std::testing::{mock_dependencies_with_balance, mock_env, mock_info};
+    use cosmwasm_std::{coins, from_slice, DepsMut, Response, Uint128};
+
+    fn instantiate_msg(initial: u128, max_supply: Option<u128>) -> InstantiateMsg {
+        InstantiateMsg {
+            name: "Wrapped Luna".to_string(),
+            symbol: "WLUNA".to_string(),
+            decimals: 6,
+            initial_balances: vec![InitialBalance {
+                address: "addr0000".to_string(),
+                amount: Uint128::from(initial),
+            }],
+            wrapped_denom: Some("uluna".to_string()),
+            max_supply: max_supply.map(Uint128::from),
+            ..default_instantiate_msg()
+        }
+    }
+
+    fn deposit(deps: DepsMut, amount: u128) -> Result<Response, ContractError> {
+        let info = mock_info("addr0000", &coins(amount, "uluna"));
+        execute(deps, mock_env(), info, ExecuteMsg::Deposit {})
+    }
+
+    #[test]
+    fn initial_balances_must_fit_the_cap() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        let msg = instantiate_msg(1001, Some(1000));
+        match instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap_err() {
+            ContractError::CannotExceedCap {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn mints_stop_at_the_cap() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        let msg = instantiate_msg(600, Some(1000));
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let res: CapResponse =
+            from_slice(&query(deps.as_ref(), mock_env(), QueryMsg::Cap {}).unwrap()).unwrap();
+        assert_eq!(res.cap, Some(Uint128::from(1000u128)));
+
+        deposit(deps.as_mut(), 400).unwrap();
+        match deposit(deps.as_mut(), 1).unwrap_err() {
+            ContractError::CannotExceedCap {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn no_cap_by_default() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        let msg = instantiate_msg(600, None);
+        instantiate(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let res: CapResponse =
+            from_slice(&query(deps.as_ref(), mock_env(), QueryMsg::Cap {}).unwrap()).unwrap();
+        assert_eq!(res.cap, None);
+        deposit(deps.as_mut(), u64::MAX.into()).unwrap();
+    }
+}
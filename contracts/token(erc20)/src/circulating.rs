@@ -0,0 +1,174 @@
+use cosmwasm_std::{Addr, Api, DepsMut, MessageInfo, Order, Response, Storage, Uint128};
+use cosmwasm_storage::{PrefixedStorage, ReadonlyPrefixedStorage};
+
+use crate::contract::{assert_owner, checked_add, checked_sub, read_balance, read_total_supply};
+use crate::error::ContractError;
+use crate::msg::{AddressBalance, CirculatingSupplyResponse};
+
+pub const PREFIX_SUPPLY_EXCLUSIONS: &[u8] = b"supply_exclusions";
+/// Maximum number of accounts excluded from the circulating supply
+pub const MAX_SUPPLY_EXCLUSIONS: usize = 50;
+
+// Accounts whose balances do not circulate, e.g. the treasury, vesting and
+// bridge escrow. Stored as keys without values.
+
+pub fn read_supply_exclusions(store: &dyn Storage) -> Result<Vec<Addr>, ContractError> {
+    let exclusions_store = ReadonlyPrefixedStorage::new(store, PREFIX_SUPPLY_EXCLUSIONS);
+    exclusions_store
+        .range(None, None, Order::Ascending)
+        .map(|(key, _)| match String::from_utf8(key) {
+            Ok(address) => Ok(Addr::unchecked(address)),
+            Err(_) => Err(ContractError::CorruptedDataFound {}),
+        })
+        .collect()
+}
+
+/// Add accounts to and remove accounts from the circulating supply exclusions.
+/// Owner only.
+pub fn try_update_supply_exclusions(
+    deps: DepsMut,
+    info: MessageInfo,
+    add: Vec<String>,
+    remove: Vec<String>,
+) -> Result<Response, ContractError> {
+    assert_owner(deps.storage, &info.sender)?;
+    let add = add
+        .iter()
+        .map(|address| deps.api.addr_validate(address))
+        .collect::<Result<Vec<_>, _>>()?;
+    let remove = remove
+        .iter()
+        .map(|address| deps.api.addr_validate(address))
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut exclusions_store = PrefixedStorage::new(deps.storage, PREFIX_SUPPLY_EXCLUSIONS);
+    for address in add.iter() {
+        exclusions_store.set(address.as_str().as_bytes(), &[1]);
+    }
+    for address in remove.iter() {
+        exclusions_store.remove(address.as_str().as_bytes());
+    }
+    if read_supply_exclusions(deps.storage)?.len() > MAX_SUPPLY_EXCLUSIONS {
+        return Err(ContractError::TooManySupplyExclusions {
+            max: MAX_SUPPLY_EXCLUSIONS,
+        });
+    }
+
+    let join = |addresses: Vec<Addr>| {
+        addresses
+            .iter()
+            .map(|address| address.as_str())
+            .collect::<Vec<_>>()
+            .join(",")
+    };
+    Ok(Response::new()
+        .add_attribute("action", "update_supply_exclusions")
+        .add_attribute("added", join(add))
+        .add_attribute("removed", join(remove)))
+}
+
+/// Total supply minus the balances of the excluded accounts
+pub fn query_circulating_supply(
+    store: &dyn Storage,
+    api: &dyn Api,
+) -> Result<CirculatingSupplyResponse, ContractError> {
+    let total_supply = read_total_supply(store)?;
+    let mut excluded_total = 0u128;
+    let excluded = read_supply_exclusions(store)?
+        .into_iter()
+        .map(|address| {
+            let balance = read_balance(store, api, &address)?;
+            excluded_total = checked_add(excluded_total, balance)?;
+            Ok(AddressBalance {
+                address: address.to_string(),
+                balance: Uint128::from(balance),
+            })
+        })
+        .collect::<Result<Vec<_>, ContractError>>()?;
+
+    Ok(CirculatingSupplyResponse {
+        circulating_supply: Uint128::from(checked_sub(total_supply, excluded_total)?),
+        total_supply: Uint128::from(total_supply),
+        excluded,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate, query};
+    use crate::msg::{ExecuteMsg, InitialBalance, InstantiateMsg, QueryMsg};
+    use crate::testing::default_instantiate_msg;
+    use cosmwasm_std::testing::{mock_dependencies_with_balance, mock_env, mock_info};
+    use cosmwasm_std::{from_slice, Deps};
+
+    fn setup(deps: DepsMut) {
+        let instantiate_msg = InstantiateMsg {
+            initial_balances: vec![
+                InitialBalance {
+                    address: "addr0000".to_string(),
+                    amount: Uint128::from(1000u128),
+                },
+                InitialBalance {
+                    address: "treasury".to_string(),
+                    amount: Uint128::from(5000u128),
+                },
+                InitialBalance {
+                    address: "vesting".to_string(),
+                    amount: Uint128::from(3000u128),
+                },
+            ],
+            ..default_instantiate_msg()
+        };
+        instantiate(deps, mock_env(), mock_info("creator", &[]), instantiate_msg).unwrap();
+    }
+
+    fn update(
+        deps: DepsMut,
+        sender: &str,
+        add: &[&str],
+        remove: &[&str],
+    ) -> Result<Response, ContractError> {
+        let msg = ExecuteMsg::UpdateSupplyExclusions {
+            add: add.iter().map(|address| address.to_string()).collect(),
+            remove: remove.iter().map(|address| address.to_string()).collect(),
+        };
+        execute(deps, mock_env(), mock_info(sender, &[]), msg)
+    }
+
+    fn circulating(deps: Deps) -> CirculatingSupplyResponse {
+        let res = query(deps, mock_env(), QueryMsg::CirculatingSupply {}).unwrap();
+        from_slice(&res).unwrap()
+    }
+
+    #[test]
+    fn excludes_listed_balances() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        assert_eq!(
+            circulating(deps.as_ref()).circulating_supply,
+            Uint128::from(9000u128)
+        );
+
+        update(deps.as_mut(), "creator", &["treasury", "vesting"], &[]).unwrap();
+        let res = circulating(deps.as_ref());
+        assert_eq!(res.circulating_supply, Uint128::from(1000u128));
+        assert_eq!(res.total_supply, Uint128::from(9000u128));
+        assert_eq!(res.excluded.len(), 2);
+
+        update(deps.as_mut(), "creator", &[], &["vesting"]).unwrap();
+        assert_eq!(
+            circulating(deps.as_ref()).circulating_supply,
+            Uint128::from(4000u128)
+        );
+    }
+
+    #[test]
+    fn only_owner_can_update() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        match update(deps.as_mut(), "addr0000", &["treasury"], &[]).unwrap_err() {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+}
@@ -0,0 +1,320 @@
+use cosmwasm_std::{from_slice, to_vec, DepsMut, Env, MessageInfo, Response, Storage, Uint128};
+use cosmwasm_storage::{PrefixedStorage, ReadonlyPrefixedStorage};
+use std::convert::TryInto;
+
+use crate::contract::{checked_add, checked_sub, escrow_deposit, escrow_payout, PREFIX_CONFIG};
+use crate::error::ContractError;
+use crate::events;
+use crate::largetransfer;
+use crate::state::Claimable;
+
+pub const PREFIX_CLAIMABLES: &[u8] = b"claimables";
+pub const KEY_CLAIMABLE_COUNT: &[u8] = b"claimable_count";
+
+// Claimable tokens are escrowed on the contract's own account. A record is
+// dropped once fully claimed or reclaimed.
+
+pub fn read_claimable(store: &dyn Storage, id: u64) -> Result<Claimable, ContractError> {
+    let claimables_store = ReadonlyPrefixedStorage::new(store, PREFIX_CLAIMABLES);
+    match claimables_store.get(&id.to_be_bytes()) {
+        Some(data) => Ok(from_slice(&data)?),
+        None => Err(ContractError::ClaimableNotFound { id }),
+    }
+}
+
+fn write_claimable(
+    store: &mut dyn Storage,
+    id: u64,
+    claimable: &Claimable,
+) -> Result<(), ContractError> {
+    let mut claimables_store = PrefixedStorage::new(store, PREFIX_CLAIMABLES);
+    if claimable.claimed < claimable.amount {
+        claimables_store.set(&id.to_be_bytes(), &to_vec(claimable)?);
+    } else {
+        claimables_store.remove(&id.to_be_bytes());
+    }
+    Ok(())
+}
+
+/// Escrow tokens `recipient` can claim in one or several parts until `expires_at`
+///
+/// @param expires_at block time in seconds after which the sender can reclaim
+/// the unclaimed remainder
+pub fn try_transfer_claimable(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipient: String,
+    amount: Uint128,
+    expires_at: u64,
+) -> Result<Response, ContractError> {
+    let recipient_address = deps.api.addr_validate(&recipient)?;
+    if expires_at <= env.block.time.seconds() {
+        return Err(ContractError::InvalidReleaseTime {});
+    }
+    largetransfer::assert_not_large(deps.storage, &info.sender, amount.u128())?;
+    let outcome = escrow_deposit(
+        deps.storage,
+        deps.api,
+        &deps.querier,
+        &env,
+        &info.sender,
+        amount.u128(),
+    )?;
+    let transfer = events::transfer_event(
+        deps.storage,
+        deps.api,
+        &info.sender,
+        &env.contract.address,
+        amount.u128(),
+        outcome,
+    )?;
+
+    let mut config_store = PrefixedStorage::new(deps.storage, PREFIX_CONFIG);
+    let id = match config_store.get(KEY_CLAIMABLE_COUNT) {
+        Some(data) => match data[..].try_into() {
+            Ok(bytes) => u64::from_be_bytes(bytes),
+            Err(_) => return Err(ContractError::CorruptedDataFound {}),
+        },
+        None => 0u64,
+    };
+    config_store.set(KEY_CLAIMABLE_COUNT, &(id + 1).to_be_bytes());
+    write_claimable(
+        deps.storage,
+        id,
+        &Claimable {
+            sender: info.sender.clone(),
+            recipient: recipient_address,
+            amount,
+            claimed: Uint128::zero(),
+            expires_at,
+        },
+    )?;
+
+    Ok(Response::new()
+        .add_event(transfer)
+        .add_attribute("action", "transfer_claimable")
+        .add_attribute("id", id.to_string())
+        .add_attribute("sender", info.sender)
+        .add_attribute("recipient", recipient)
+        .add_attribute("amount", amount.to_string())
+        .add_attribute("expires_at", expires_at.to_string()))
+}
+
+/// Claim `amount` of a claimable transfer before it expires. Recipient only.
+pub fn try_claim(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let mut claimable = read_claimable(deps.storage, id)?;
+    if info.sender != claimable.recipient {
+        return Err(ContractError::Unauthorized {});
+    }
+    if env.block.time.seconds() > claimable.expires_at {
+        return Err(ContractError::ClaimableExpired { id });
+    }
+    let remaining = checked_sub(claimable.amount.u128(), claimable.claimed.u128())?;
+    if amount.u128() > remaining {
+        return Err(ContractError::ClaimExceedsRemaining {
+            remaining,
+            required: amount.u128(),
+        });
+    }
+
+    claimable.claimed = Uint128::from(checked_add(claimable.claimed.u128(), amount.u128())?);
+    write_claimable(deps.storage, id, &claimable)?;
+    let outcome = escrow_payout(
+        deps.storage,
+        deps.api,
+        &deps.querier,
+        &env,
+        &claimable.recipient,
+        amount.u128(),
+    )?;
+    let transfer = events::transfer_event(
+        deps.storage,
+        deps.api,
+        &env.contract.address,
+        &claimable.recipient,
+        amount.u128(),
+        outcome,
+    )?;
+
+    Ok(Response::new()
+        .add_event(transfer)
+        .add_attribute("action", "claim")
+        .add_attribute("id", id.to_string())
+        .add_attribute("recipient", info.sender)
+        .add_attribute("amount", amount.to_string())
+        .add_attribute("remaining", (remaining - amount.u128()).to_string()))
+}
+
+/// Take back the unclaimed remainder of an expired claimable transfer. Sender only.
+pub fn try_reclaim(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    let claimable = read_claimable(deps.storage, id)?;
+    if info.sender != claimable.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    if env.block.time.seconds() <= claimable.expires_at {
+        return Err(ContractError::ClaimableNotExpired { id });
+    }
+
+    let remaining = claimable.amount - claimable.claimed;
+    let mut claimables_store = PrefixedStorage::new(deps.storage, PREFIX_CLAIMABLES);
+    claimables_store.remove(&id.to_be_bytes());
+    let outcome = escrow_payout(
+        deps.storage,
+        deps.api,
+        &deps.querier,
+        &env,
+        &claimable.sender,
+        remaining.u128(),
+    )?;
+    let transfer = events::transfer_event(
+        deps.storage,
+        deps.api,
+        &env.contract.address,
+        &claimable.sender,
+        remaining.u128(),
+        outcome,
+    )?;
+
+    Ok(Response::new()
+        .add_event(transfer)
+        .add_attribute("action", "reclaim")
+        .add_attribute("id", id.to_string())
+        .add_attribute("sender", info.sender)
+        .add_attribute("amount", remaining.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate, query, read_balance};
+    use crate::msg::{ClaimableResponse, ExecuteMsg, InitialBalance, InstantiateMsg, QueryMsg};
+    use crate::testing::{default_instantiate_msg, env_at};
+    use cosmwasm_std::testing::{
+        mock_dependencies_with_balance, mock_info, MockApi, MOCK_CONTRACT_ADDR,
+    };
+    use cosmwasm_std::Addr;
+
+    fn setup(mut deps: DepsMut) {
+        let instantiate_msg = InstantiateMsg {
+            initial_balances: vec![InitialBalance {
+                address: "customer".to_string(),
+                amount: Uint128::from(1000u128),
+            }],
+            ..default_instantiate_msg()
+        };
+        instantiate(
+            deps.branch(),
+            env_at(0),
+            mock_info("creator", &[]),
+            instantiate_msg,
+        )
+        .unwrap();
+        let msg = ExecuteMsg::TransferClaimable {
+            recipient: "vendor".to_string(),
+            amount: Uint128::from(600u128),
+            expires_at: 1000,
+        };
+        execute(deps, env_at(100), mock_info("customer", &[]), msg).unwrap();
+    }
+
+    fn claim(deps: DepsMut, time: u64, amount: u128) -> Result<Response, ContractError> {
+        let msg = ExecuteMsg::Claim {
+            id: 0,
+            amount: Uint128::from(amount),
+        };
+        execute(deps, env_at(time), mock_info("vendor", &[]), msg)
+    }
+
+    fn reclaim(deps: DepsMut, time: u64) -> Result<Response, ContractError> {
+        let msg = ExecuteMsg::Reclaim { id: 0 };
+        execute(deps, env_at(time), mock_info("customer", &[]), msg)
+    }
+
+    fn balance(storage: &dyn Storage, address: &str) -> u128 {
+        read_balance(storage, &MockApi::default(), &Addr::unchecked(address)).unwrap()
+    }
+
+    #[test]
+    fn claims_in_parts() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        assert_eq!(balance(&deps.storage, "customer"), 400);
+        assert_eq!(balance(&deps.storage, MOCK_CONTRACT_ADDR), 600);
+
+        let res = claim(deps.as_mut(), 200, 250).unwrap();
+        assert_eq!(res.attributes[4].value, "350");
+        claim(deps.as_mut(), 300, 250).unwrap();
+        let res: ClaimableResponse =
+            from_slice(&query(deps.as_ref(), env_at(300), QueryMsg::Claimable { id: 0 }).unwrap())
+                .unwrap();
+        assert_eq!(res.claimed, Uint128::from(500u128));
+
+        match claim(deps.as_mut(), 400, 101).unwrap_err() {
+            ContractError::ClaimExceedsRemaining {
+                remaining,
+                required,
+            } => {
+                assert_eq!(remaining, 100);
+                assert_eq!(required, 101);
+            }
+            e => panic!("unexpected error: {:?}", e),
+        }
+        claim(deps.as_mut(), 400, 100).unwrap();
+        assert_eq!(balance(&deps.storage, "vendor"), 600);
+        assert_eq!(balance(&deps.storage, MOCK_CONTRACT_ADDR), 0);
+
+        // fully claimed transfers are gone
+        match claim(deps.as_mut(), 400, 1).unwrap_err() {
+            ContractError::ClaimableNotFound { id } => assert_eq!(id, 0),
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn sender_reclaims_only_the_remainder_after_expiry() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        claim(deps.as_mut(), 200, 250).unwrap();
+
+        match reclaim(deps.as_mut(), 1000).unwrap_err() {
+            ContractError::ClaimableNotExpired { id } => assert_eq!(id, 0),
+            e => panic!("unexpected error: {:?}", e),
+        }
+        reclaim(deps.as_mut(), 1001).unwrap();
+        assert_eq!(balance(&deps.storage, "customer"), 750);
+        assert_eq!(balance(&deps.storage, "vendor"), 250);
+        assert_eq!(balance(&deps.storage, MOCK_CONTRACT_ADDR), 0);
+        reclaim(deps.as_mut(), 1001).unwrap_err();
+    }
+
+    #[test]
+    fn recipient_cannot_claim_after_expiry() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        match claim(deps.as_mut(), 1001, 1).unwrap_err() {
+            ContractError::ClaimableExpired { id } => assert_eq!(id, 0),
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        let msg = ExecuteMsg::Claim {
+            id: 0,
+            amount: Uint128::from(1u128),
+        };
+        match execute(deps.as_mut(), env_at(200), mock_info("customer", &[]), msg).unwrap_err() {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+}
@@ -0,0 +1,224 @@
+use cosmwasm_std::{Addr, DepsMut, Env, MessageInfo, Response, Storage, Uint128};
+use cosmwasm_storage::{PrefixedStorage, ReadonlyPrefixedStorage};
+
+use crate::buyback;
+use crate::contract::{move_balance, read_addr, read_balance, PREFIX_CONFIG};
+use crate::error::ContractError;
+use crate::events;
+use crate::locks;
+
+pub const KEY_CLAWBACK_ADMIN: &[u8] = b"clawback_admin";
+
+// The clawback admin is set at instantiate. Deployments instantiated without
+// one, or whose admin renounced the role, can never force transfers again
+// except through chain governance, see `sudo`.
+
+pub fn read_clawback_admin(store: &dyn Storage) -> Result<Option<Addr>, ContractError> {
+    let config_store = ReadonlyPrefixedStorage::new(store, PREFIX_CONFIG);
+    read_addr(&config_store, KEY_CLAWBACK_ADMIN)
+}
+
+pub fn write_clawback_admin(store: &mut dyn Storage, admin: Option<&Addr>) {
+    let mut config_store = PrefixedStorage::new(store, PREFIX_CONFIG);
+    match admin {
+        Some(admin) => config_store.set(KEY_CLAWBACK_ADMIN, admin.as_str().as_bytes()),
+        None => config_store.remove(KEY_CLAWBACK_ADMIN),
+    }
+}
+
+// Errors unless `sender` holds the clawback role
+fn assert_clawback_admin(store: &dyn Storage, sender: &Addr) -> Result<(), ContractError> {
+    match read_clawback_admin(store)? {
+        Some(admin) if admin == sender => Ok(()),
+        Some(_) => Err(ContractError::Unauthorized {}),
+        None => Err(ContractError::ClawbackDisabled {}),
+    }
+}
+
+/// Move tokens out of a compromised or sanctioned account. Clawback admin only.
+pub fn try_force_transfer(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    owner: String,
+    recipient: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    assert_clawback_admin(deps.storage, &info.sender)?;
+    let res = force_transfer(deps, env, owner, recipient, amount)?;
+    Ok(res.add_attribute("authority", info.sender))
+}
+
+/// Hand the clawback role to `admin`, or give it up for good with `None`.
+/// Clawback admin only.
+pub fn try_update_clawback_admin(
+    deps: DepsMut,
+    info: MessageInfo,
+    admin: Option<String>,
+) -> Result<Response, ContractError> {
+    assert_clawback_admin(deps.storage, &info.sender)?;
+    let admin = admin
+        .map(|admin| deps.api.addr_validate(&admin))
+        .transpose()?;
+    write_clawback_admin(deps.storage, admin.as_ref());
+
+    Ok(Response::new()
+        .add_attribute("action", "update_clawback_admin")
+        .add_attribute(
+            "clawback_admin",
+            admin.map(|addr| addr.to_string()).unwrap_or_default(),
+        ))
+}
+
+/// Move tokens out of an account regardless of pause, fees, limits and the
+/// timelock, without checking who asked for it. Matured locks of `from` are
+/// released first; tokens still locked or held by the contract in escrow cannot
+/// be moved.
+pub fn force_transfer(
+    deps: DepsMut,
+    env: Env,
+    from: String,
+    to: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let from = deps.api.addr_validate(&from)?;
+    let to = deps.api.addr_validate(&to)?;
+    if from == env.contract.address {
+        return Err(ContractError::EscrowNotMovable {});
+    }
+    locks::release_matured(deps.storage, deps.api, &env, &from)?;
+    let balance = read_balance(deps.storage, deps.api, &from)?;
+    if balance < amount.u128() {
+        return Err(ContractError::InsufficientFunds {
+            balance,
+            required: amount.u128(),
+        });
+    }
+    move_balance(deps.storage, deps.api, &env, &from, &to, amount.u128())?;
+    if to == env.contract.address {
+        buyback::record_deposit(deps.storage, amount.u128())?;
+    }
+
+    Ok(Response::new()
+        .add_event(events::force_transfer_event(
+            deps.storage,
+            deps.api,
+            &from,
+            &to,
+            amount.u128(),
+        )?)
+        .add_attribute("action", "force_transfer")
+        .add_attribute("sender", from)
+        .add_attribute("recipient", to)
+        .add_attribute("amount", amount.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate};
+    use crate::msg::{ExecuteMsg, InitialBalance, InstantiateMsg};
+    use crate::testing::default_instantiate_msg;
+    use cosmwasm_std::testing::{mock_dependencies_with_balance, mock_env, mock_info};
+
+    fn setup(deps: DepsMut, clawback_admin: Option<String>) {
+        let instantiate_msg = InstantiateMsg {
+            initial_balances: vec![InitialBalance {
+                address: "addr0000".to_string(),
+                amount: Uint128::from(1000u128),
+            }],
+            clawback_admin,
+            ..default_instantiate_msg()
+        };
+        instantiate(deps, mock_env(), mock_info("creator", &[]), instantiate_msg).unwrap();
+    }
+
+    fn force_transfer_msg(amount: u128) -> ExecuteMsg {
+        ExecuteMsg::ForceTransfer {
+            owner: "addr0000".to_string(),
+            recipient: "recovery".to_string(),
+            amount: Uint128::from(amount),
+        }
+    }
+
+    #[test]
+    fn clawback_admin_moves_tokens() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut(), Some("compliance".to_string()));
+
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            force_transfer_msg(400),
+        )
+        .unwrap_err();
+        match err {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("compliance", &[]),
+            force_transfer_msg(400),
+        )
+        .unwrap();
+        assert_eq!(res.events[0].ty, "force_transfer");
+        let balance = |address: &str| {
+            read_balance(&deps.storage, &deps.api, &Addr::unchecked(address)).unwrap()
+        };
+        assert_eq!(balance("addr0000"), 600);
+        assert_eq!(balance("recovery"), 400);
+    }
+
+    #[test]
+    fn force_transfer_to_same_account_changes_nothing() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut(), Some("compliance".to_string()));
+        let msg = ExecuteMsg::ForceTransfer {
+            owner: "addr0000".to_string(),
+            recipient: "addr0000".to_string(),
+            amount: Uint128::from(400u128),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("compliance", &[]), msg).unwrap();
+        assert_eq!(
+            read_balance(&deps.storage, &deps.api, &Addr::unchecked("addr0000")).unwrap(),
+            1000
+        );
+    }
+
+    #[test]
+    fn clawback_can_be_disabled() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut(), None);
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            force_transfer_msg(1),
+        )
+        .unwrap_err();
+        match err {
+            ContractError::ClawbackDisabled {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut(), Some("compliance".to_string()));
+        let msg = ExecuteMsg::UpdateClawbackAdmin { admin: None };
+        execute(deps.as_mut(), mock_env(), mock_info("compliance", &[]), msg).unwrap();
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("compliance", &[]),
+            force_transfer_msg(1),
+        )
+        .unwrap_err();
+        match err {
+            ContractError::ClawbackDisabled {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+}
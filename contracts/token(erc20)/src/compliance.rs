@@ -0,0 +1,183 @@
+use cosmwasm_std::{Addr, DepsMut, MessageInfo, QuerierWrapper, Response, Storage, Uint128};
+use cosmwasm_storage::{PrefixedStorage, ReadonlyPrefixedStorage};
+
+use crate::contract::{assert_owner, read_addr, PREFIX_CONFIG};
+use crate::error::ContractError;
+use crate::msg::{ComplianceQueryMsg, ComplianceResponse};
+
+pub const KEY_COMPLIANCE_CONTRACT: &[u8] = b"compliance_contract";
+
+// Security tokens delegate KYC and transfer restrictions to an external contract.
+// Every transfer asks it first; mints, burns and forced transfers do not.
+
+pub fn read_compliance_contract(store: &dyn Storage) -> Result<Option<Addr>, ContractError> {
+    let config_store = ReadonlyPrefixedStorage::new(store, PREFIX_CONFIG);
+    read_addr(&config_store, KEY_COMPLIANCE_CONTRACT)
+}
+
+fn write_compliance_contract(store: &mut dyn Storage, contract: Option<&Addr>) {
+    let mut config_store = PrefixedStorage::new(store, PREFIX_CONFIG);
+    match contract {
+        Some(contract) => config_store.set(KEY_COMPLIANCE_CONTRACT, contract.as_str().as_bytes()),
+        None => config_store.remove(KEY_COMPLIANCE_CONTRACT),
+    }
+}
+
+/// Check every transfer against `address` from now on. Owner only.
+pub fn try_set_compliance_contract(
+    deps: DepsMut,
+    info: MessageInfo,
+    address: String,
+) -> Result<Response, ContractError> {
+    assert_owner(deps.storage, &info.sender)?;
+    let contract = deps.api.addr_validate(&address)?;
+    write_compliance_contract(deps.storage, Some(&contract));
+
+    Ok(Response::new()
+        .add_attribute("action", "set_compliance_contract")
+        .add_attribute("compliance_contract", contract))
+}
+
+/// Stop checking transfers. Owner only.
+pub fn try_clear_compliance_contract(
+    deps: DepsMut,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    assert_owner(deps.storage, &info.sender)?;
+    write_compliance_contract(deps.storage, None);
+
+    Ok(Response::new().add_attribute("action", "clear_compliance_contract"))
+}
+
+/// Errors unless the compliance contract, if any, allows the transfer. A failing
+/// query rejects the transfer as well.
+pub fn assert_compliant(
+    store: &dyn Storage,
+    querier: &QuerierWrapper,
+    from: &Addr,
+    to: &Addr,
+    amount: u128,
+) -> Result<(), ContractError> {
+    let contract = match read_compliance_contract(store)? {
+        Some(contract) => contract,
+        None => return Ok(()),
+    };
+    let query = ComplianceQueryMsg::CheckTransfer {
+        from: from.to_string(),
+        to: to.to_string(),
+        amount: Uint128::from(amount),
+    };
+    let res: ComplianceResponse = querier.query_wasm_smart(contract, &query)?;
+    if !res.allowed {
+        return Err(ContractError::NotCompliant {
+            reason: res.reason.unwrap_or_default(),
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate, query, read_balance};
+    use crate::msg::{
+        ComplianceContractResponse, ExecuteMsg, InitialBalance, InstantiateMsg, QueryMsg,
+    };
+    use crate::testing::default_instantiate_msg;
+    use cosmwasm_std::testing::{mock_dependencies_with_balance, mock_env, mock_info};
+    use cosmwasm_std::{
+        from_binary, from_slice, to_binary, ContractResult, Deps, SystemResult, WasmQuery,
+    };
+
+    fn setup(deps: DepsMut) {
+        let instantiate_msg = InstantiateMsg {
+            initial_balances: vec![InitialBalance {
+                address: "addr0000".to_string(),
+                amount: Uint128::from(10_000u128),
+            }],
+            ..default_instantiate_msg()
+        };
+        instantiate(deps, mock_env(), mock_info("creator", &[]), instantiate_msg).unwrap();
+    }
+
+    fn transfer(deps: DepsMut, recipient: &str, amount: u128) -> Result<Response, ContractError> {
+        let msg = ExecuteMsg::Transfer {
+            recipient: recipient.to_string(),
+            amount: Uint128::from(amount),
+            memo: None,
+            on_behalf_of: None,
+        };
+        execute(deps, mock_env(), mock_info("addr0000", &[]), msg)
+    }
+
+    fn query_compliance_contract(deps: Deps) -> Option<String> {
+        let res: ComplianceContractResponse =
+            from_slice(&query(deps, mock_env(), QueryMsg::ComplianceContract {}).unwrap()).unwrap();
+        res.address
+    }
+
+    #[test]
+    fn checks_transfers_against_compliance_contract() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        // The compliance contract only allows transfers to addr1111
+        deps.querier.update_wasm(|query| match query {
+            WasmQuery::Smart { contract_addr, msg } if contract_addr == "kyc" => {
+                let ComplianceQueryMsg::CheckTransfer { to, .. } = from_binary(msg).unwrap();
+                let res = if to == "addr1111" {
+                    ComplianceResponse {
+                        allowed: true,
+                        reason: None,
+                    }
+                } else {
+                    ComplianceResponse {
+                        allowed: false,
+                        reason: Some("recipient not verified".to_string()),
+                    }
+                };
+                SystemResult::Ok(ContractResult::Ok(to_binary(&res).unwrap()))
+            }
+            _ => panic!("unexpected query"),
+        });
+        let msg = ExecuteMsg::SetComplianceContract {
+            address: "kyc".to_string(),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        assert_eq!(
+            query_compliance_contract(deps.as_ref()),
+            Some("kyc".to_string())
+        );
+
+        transfer(deps.as_mut(), "addr1111", 100).unwrap();
+        let err = transfer(deps.as_mut(), "addr2222", 100).unwrap_err();
+        match err {
+            ContractError::NotCompliant { reason } => {
+                assert_eq!(reason, "recipient not verified");
+            }
+            e => panic!("unexpected error: {:?}", e),
+        }
+        assert_eq!(
+            read_balance(&deps.storage, &deps.api, &Addr::unchecked("addr2222")).unwrap(),
+            0
+        );
+
+        let msg = ExecuteMsg::ClearComplianceContract {};
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        assert_eq!(query_compliance_contract(deps.as_ref()), None);
+        transfer(deps.as_mut(), "addr2222", 100).unwrap();
+    }
+
+    #[test]
+    fn only_owner_can_set_compliance_contract() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        let msg = ExecuteMsg::SetComplianceContract {
+            address: "kyc".to_string(),
+        };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap_err();
+        match err {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+}
@@ -1,37 +1,145 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    entry_point, to_binary, to_vec, Addr, Binary, Deps, DepsMut, Env, MessageInfo, Response,
-    StdResult, Storage, Uint128,
+    from_slice, to_binary, to_vec, Addr, Api, Binary, CanonicalAddr, Deps, DepsMut, Env,
+    MessageInfo, Order, QuerierWrapper, Response, StdError, StdResult, Storage, Uint128,
 };
 use cosmwasm_storage::{PrefixedStorage, ReadonlyPrefixedStorage};
+use cw2::{get_contract_version, set_contract_version};
+use cw_utils::Expiration;
+use std::collections::BTreeSet;
 use std::convert::TryInto;
 
+use crate::buyback;
+use crate::cap;
+use crate::circulating;
+use crate::claimable;
+use crate::clawback;
+use crate::compliance;
+use crate::deflation;
+use crate::distribute;
+use crate::dividends;
+use crate::drip;
+use crate::dualcontrol;
 use crate::error::ContractError;
-use crate::msg::{AllowanceResponse, BalanceResponse, ExecuteMsg, InstantiateMsg, QueryMsg};
-use crate::state::Constants;
+use crate::events;
+use crate::export;
+use crate::fees;
+use crate::foreign;
+use crate::forwarder;
+use crate::freeze;
+use crate::holders;
+use crate::interfaces;
+use crate::largetransfer;
+use crate::limits;
+use crate::locks;
+use crate::lockup;
+use crate::marketing;
+use crate::migrate;
+use crate::mint;
+use crate::msg::{
+    AccountStatsResponse, AddressBalance, AdminProposalsResponse, AllAccountsResponse,
+    AllAllowancesResponse, AllowanceInfo, AllowanceResponse, ApprovalEntry, AssetCardResponse,
+    AssetRestrictions, BalanceResponse, BalancesResponse, BucketsResponse, BurnReceiptInfo,
+    BurnReceiptResponse, BuybackResponse, CapResponse, ClaimableResponse, ClawbackAdminResponse,
+    ComplianceContractResponse, ConfigResponse, DualControlResponse, ExecuteMsg,
+    FailedSendsResponse, FeeConfigResponse, ForeignBalancesResponse, FrozenResponse,
+    HolderCountResponse, HoldersAboveResponse, InstantiateMsg, LargeTransferPolicyResponse,
+    LockerResponse, LocksResponse, NonceResponse, PauseStateResponse, PendingDividendsResponse,
+    PendingTransferResponse, QueryMsg, RateLimitResponse, RebaseStateResponse, RenouncedResponse,
+    Role, SpendableBalanceResponse, SpendingReportResponse, StreamsResponse, StuckBalanceResponse,
+    SubscriptionsResponse, SupportedInterfacesResponse, TimelockResponse,
+    TimelockedTransfersResponse, TokenInfoResponse, TopHoldersResponse, TotalSupplyResponse,
+    TransferBurnResponse, TransferLimitsResponse, TreasuryResponse, VotingPowerResponse,
+    WrappedDenomResponse,
+};
+use crate::multicall;
+use crate::pagination::{Page, LIST_LIMITS};
+use crate::pause;
+use crate::periodic;
+use crate::permit;
+use crate::ratelimit;
+use crate::rebase;
+use crate::receipt;
+use crate::recovery;
+use crate::relay;
+use crate::renounce;
+use crate::report;
+use crate::send;
+use crate::simulate;
+use crate::state::{Constants, Minter, PauseState, WrapConfig};
+use crate::streams;
+use crate::subscriptions;
+use crate::timelock;
+use crate::treasury;
+use crate::votes;
+use crate::wrap;
+
+// Version info stored with cw2
+pub const CONTRACT_NAME: &str = "crates.io:cw-erc20";
+pub const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 pub const PREFIX_CONFIG: &[u8] = b"config";
 pub const PREFIX_BALANCES: &[u8] = b"balances";
 pub const PREFIX_ALLOWANCES: &[u8] = b"allowances";
+pub const PREFIX_ALLOWANCE_CHECKPOINTS: &[u8] = b"allowance_checkpoints";
+pub const PREFIX_ALLOWANCE_EXPIRATIONS: &[u8] = b"allowance_expirations";
+pub const PREFIX_SENT: &[u8] = b"sent";
+pub const PREFIX_RECEIVED: &[u8] = b"received";
+pub const PREFIX_BURNED: &[u8] = b"burned";
 
 pub const KEY_CONSTANTS: &[u8] = b"constants";
 pub const KEY_TOTAL_SUPPLY: &[u8] = b"total_supply";
+pub const KEY_OWNER: &[u8] = b"owner";
+
+pub const MAX_MEMO_BYTES: usize = 256;
+/// Allowance that spends never decrease, see `ExecuteMsg::Approve`
+pub const UNLIMITED_ALLOWANCE: u128 = u128::MAX;
+/// Maximum number of addresses in a `QueryMsg::Balances`
+pub const MAX_BALANCES_BATCH: usize = 100;
+/// Maximum number of entries in an `ExecuteMsg::ApproveMany`
+pub const MAX_APPROVE_ENTRIES: usize = 100;
 
-#[entry_point]
+#[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
-    _env: Env,
-    _info: MessageInfo,
+    env: Env,
+    info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
     let mut total_supply: u128 = 0;
-    {
-        // Initial balances
-        let mut balances_store = PrefixedStorage::new(deps.storage, PREFIX_BALANCES);
-        for row in msg.initial_balances {
-            let amount_raw = row.amount.u128();
-            balances_store.set(row.address.as_str().as_bytes(), &amount_raw.to_be_bytes());
-            total_supply += amount_raw;
+    // Initial balances
+    let mut holders = BTreeSet::new();
+    let mut minted = vec![];
+    for row in msg.initial_balances {
+        let amount_raw = row.amount.u128();
+        let address = deps.api.addr_validate(&row.address)?;
+        if !holders.insert(address.clone()) {
+            return Err(ContractError::DuplicateInitialBalance {
+                address: row.address,
+            });
+        }
+        total_supply = total_supply
+            .checked_add(amount_raw)
+            .ok_or(ContractError::InitialSupplyOverflow {})?;
+        write_shares(deps.storage, deps.api, &env, &address, 0, amount_raw)?;
+        minted.push((address, amount_raw));
+    }
+    if let Some(drip) = &msg.drip {
+        let contract = env.contract.address.clone();
+        if !holders.insert(contract.clone()) {
+            return Err(ContractError::DuplicateInitialBalance {
+                address: contract.to_string(),
+            });
         }
+        let beneficiary = deps.api.addr_validate(&drip.beneficiary)?;
+        drip::init_drip(deps.storage, &env, beneficiary, drip)?;
+        let amount_raw = drip.amount.u128();
+        total_supply = total_supply
+            .checked_add(amount_raw)
+            .ok_or(ContractError::InitialSupplyOverflow {})?;
+        write_shares(deps.storage, deps.api, &env, &contract, 0, amount_raw)?;
+        minted.push((contract, amount_raw));
     }
 
     // Check name, symbol, decimals
@@ -44,7 +152,13 @@ pub fn instantiate(
     if msg.decimals > 18 {
         return Err(ContractError::DecimalsExceeded {});
     }
+    if let Some(max_supply) = msg.max_supply {
+        cap::write_max_supply(deps.storage, max_supply.u128());
+        cap::assert_within_cap(deps.storage, total_supply)?;
+    }
 
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    migrate::write_state_version(deps.storage, migrate::STATE_VERSION);
     let mut config_store = PrefixedStorage::new(deps.storage, PREFIX_CONFIG);
     let constants = to_vec(&Constants {
         name: msg.name,
@@ -53,116 +167,1400 @@ pub fn instantiate(
     })?;
     config_store.set(KEY_CONSTANTS, &constants);
     config_store.set(KEY_TOTAL_SUPPLY, &total_supply.to_be_bytes());
+    // Shares start out 1:1 with tokens
+    config_store.set(rebase::KEY_TOTAL_SHARES, &total_supply.to_be_bytes());
+    config_store.set(KEY_OWNER, info.sender.as_str().as_bytes());
+    votes::write_supply_checkpoint(deps.storage, env.block.height, total_supply)?;
+    pause::write_pause_state(
+        deps.storage,
+        &PauseState {
+            max_pause_duration: msg.max_pause_duration,
+            ..PauseState::default()
+        },
+    )?;
+    for interface in interfaces::INTERFACES {
+        interfaces::register_interface(deps.storage, *interface);
+    }
+    if let Some(admin) = msg.clawback_admin {
+        let admin = deps.api.addr_validate(&admin)?;
+        clawback::write_clawback_admin(deps.storage, Some(&admin));
+    }
+    if let Some(mint) = msg.mint {
+        let minter = Minter {
+            minter: deps.api.addr_validate(&mint.minter)?,
+            mint_deadline: mint.mint_deadline,
+        };
+        mint::write_minter(deps.storage, &minter)?;
+        interfaces::register_interface(deps.storage, interfaces::MINT_INTERFACE);
+    }
+    if let Some(denom) = msg.wrapped_denom {
+        wrap::write_wrap_config(deps.storage, &WrapConfig { denom })?;
+        interfaces::register_interface(deps.storage, interfaces::WRAP_INTERFACE);
+    }
+    if msg.drip.is_some() {
+        interfaces::register_interface(deps.storage, interfaces::DRIP_INTERFACE);
+    }
+    limits::write_allow_zero_transfers(deps.storage, msg.allow_zero_transfers);
 
-    Ok(Response::default())
+    let mut res = Response::default();
+    for (address, amount) in minted {
+        res = res.add_event(events::mint_event(
+            deps.storage,
+            deps.api,
+            &address,
+            amount,
+        )?);
+    }
+    Ok(res)
 }
 
-#[entry_point]
+#[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
     msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    dualcontrol::assert_not_required(deps.storage, &info.sender, &msg)?;
+    dispatch(deps, env, info, msg)
+}
+
+// Executes `msg` without the dual-control check, which confirmed proposals passed
+pub fn dispatch(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
     match msg {
-        ExecuteMsg::Approve { spender, amount } => try_approve(deps, env, info, spender, &amount),
-        ExecuteMsg::Transfer { recipient, amount } => {
-            try_transfer(deps, env, info, recipient, &amount)
+        ExecuteMsg::Approve {
+            spender,
+            amount,
+            expected_current,
+            on_behalf_of,
+        } => {
+            let info = forwarder::resolve_sender(deps.storage, deps.api, info, on_behalf_of)?;
+            try_approve(deps, env, info, spender, &amount, expected_current)
+        }
+        ExecuteMsg::Transfer {
+            recipient,
+            amount,
+            memo,
+            on_behalf_of,
+        } => {
+            let info = forwarder::resolve_sender(deps.storage, deps.api, info, on_behalf_of)?;
+            try_transfer(deps, env, info, recipient, &amount, memo)
         }
         ExecuteMsg::TransferFrom {
             owner,
             recipient,
             amount,
-        } => try_transfer_from(deps, env, info, owner, recipient, &amount),
-        ExecuteMsg::Burn { amount } => try_burn(deps, env, info, &amount),
+            memo,
+        } => try_transfer_from(deps, env, info, owner, recipient, &amount, memo),
+        ExecuteMsg::Burn { amount, memo } => try_burn(deps, env, info, &amount, memo),
+        ExecuteMsg::BurnFrom {
+            owner,
+            amount,
+            memo,
+        } => try_burn_from(deps, env, info, owner, &amount, memo),
+        ExecuteMsg::Delegate { delegatee } => votes::try_delegate(deps, env, info, delegatee),
+        ExecuteMsg::Permit {
+            owner,
+            spender,
+            amount,
+            deadline,
+            nonce,
+            public_key,
+            signature,
+        } => permit::try_permit(
+            deps, env, owner, spender, amount, deadline, nonce, public_key, signature,
+        ),
+        ExecuteMsg::RelayedTransfer {
+            owner,
+            recipient,
+            amount,
+            nonce,
+            expiry,
+            public_key,
+            signature,
+        } => relay::try_relayed_transfer(
+            deps, env, info, owner, recipient, amount, nonce, expiry, public_key, signature,
+        ),
+        ExecuteMsg::SetBucket {
+            name,
+            spenders,
+            spend_limit,
+        } => treasury::try_set_bucket(deps, info, name, spenders, spend_limit),
+        ExecuteMsg::FundBucket { name, amount } => {
+            treasury::try_fund_bucket(deps, env, info, name, amount)
+        }
+        ExecuteMsg::MoveBetweenBuckets { from, to, amount } => {
+            treasury::try_move_between_buckets(deps, info, from, to, amount)
+        }
+        ExecuteMsg::SpendFromBucket {
+            name,
+            recipient,
+            amount,
+        } => treasury::try_spend_from_bucket(deps, env, info, name, recipient, amount),
+        ExecuteMsg::Pause {} => pause::try_pause(deps, env, info),
+        ExecuteMsg::Unpause {} => pause::try_unpause(deps, info),
+        ExecuteMsg::VoteUnpause {} => pause::try_vote_unpause(deps, env, info),
+        ExecuteMsg::UpdateFeeConfig {
+            enabled,
+            rate_bps,
+            treasury,
+        } => fees::try_update_fee_config(deps, info, enabled, rate_bps, treasury),
+        ExecuteMsg::UpdateFeeCurve { curve } => fees::try_update_fee_curve(deps, info, curve),
+        ExecuteMsg::UpdateTransferLimits {
+            max_tx_amount,
+            max_wallet_amount,
+            exempt,
+        } => {
+            limits::try_update_transfer_limits(deps, info, max_tx_amount, max_wallet_amount, exempt)
+        }
+        ExecuteMsg::SetMinTransferAmount { amount } => {
+            limits::try_set_min_transfer_amount(deps, info, amount)
+        }
+        ExecuteMsg::UpdateTransferBurn { rate_bps, exempt } => {
+            deflation::try_update_transfer_burn(deps, info, rate_bps, exempt)
+        }
+        ExecuteMsg::Multicall { msgs } => multicall::try_multicall(deps, env, info, msgs),
+        ExecuteMsg::SetRateLimit { address, limit } => {
+            ratelimit::try_set_rate_limit(deps, info, address, limit)
+        }
+        ExecuteMsg::SetLargeTransferPolicy {
+            threshold,
+            window,
+            co_signer,
+        } => largetransfer::try_set_policy(deps, info, threshold, window, co_signer),
+        ExecuteMsg::InitiateLargeTransfer { recipient, amount } => {
+            largetransfer::try_initiate(deps, env, info, recipient, amount)
+        }
+        ExecuteMsg::ConfirmLargeTransfer { id } => largetransfer::try_confirm(deps, env, info, id),
+        ExecuteMsg::CancelLargeTransfer { id } => largetransfer::try_cancel(deps, info, id),
+        ExecuteMsg::TransferLocked {
+            recipient,
+            amount,
+            release_at,
+        } => locks::try_transfer_locked(deps, env, info, recipient, amount, release_at),
+        ExecuteMsg::SetRebaser { rebaser } => rebase::try_set_rebaser(deps, info, rebaser),
+        ExecuteMsg::Rebase { delta } => rebase::try_rebase(deps, env, info, delta),
+        ExecuteMsg::Mint { recipient, amount } => {
+            mint::try_mint(deps, env, info, recipient, amount)
+        }
+        ExecuteMsg::MintMany { mints } => mint::try_mint_many(deps, env, info, mints),
+        ExecuteMsg::UpdateMinter { new_minter } => mint::try_update_minter(deps, info, new_minter),
+        ExecuteMsg::Deposit {} => wrap::try_deposit(deps, env, info),
+        ExecuteMsg::Withdraw { amount } => wrap::try_withdraw(deps, env, info, amount),
+        ExecuteMsg::TransferClaimable {
+            recipient,
+            amount,
+            expires_at,
+        } => claimable::try_transfer_claimable(deps, env, info, recipient, amount, expires_at),
+        ExecuteMsg::Claim { id, amount } => claimable::try_claim(deps, env, info, id, amount),
+        ExecuteMsg::Reclaim { id } => claimable::try_reclaim(deps, env, info, id),
+        ExecuteMsg::UpdateMarketingAdmin { admin } => {
+            marketing::try_update_marketing_admin(deps, info, admin)
+        }
+        ExecuteMsg::UpdateDisplaySymbol { display_symbol } => {
+            marketing::try_update_display_symbol(deps, info, display_symbol)
+        }
+        ExecuteMsg::UpdateTokenMetadata { name, symbol } => {
+            marketing::try_update_token_metadata(deps, info, name, symbol)
+        }
+        ExecuteMsg::CreateStream {
+            recipient,
+            amount,
+            start,
+            end,
+        } => streams::try_create_stream(deps, env, info, recipient, amount, start, end),
+        ExecuteMsg::WithdrawFromStream { id } => {
+            streams::try_withdraw_from_stream(deps, env, info, id)
+        }
+        ExecuteMsg::CancelStream { id } => streams::try_cancel_stream(deps, env, info, id),
+        ExecuteMsg::ReleaseDrip {} => drip::try_release_drip(deps, env),
+        ExecuteMsg::CreateSubscription {
+            payee,
+            amount,
+            interval,
+        } => subscriptions::try_create_subscription(deps, env, info, payee, amount, interval),
+        ExecuteMsg::ProcessSubscription { id } => {
+            subscriptions::try_process_subscription(deps, env, id)
+        }
+        ExecuteMsg::CancelSubscription { id } => {
+            subscriptions::try_cancel_subscription(deps, info, id)
+        }
+        ExecuteMsg::SetBurnReceipt { receipt } => {
+            receipt::try_set_burn_receipt(deps, info, receipt)
+        }
+        ExecuteMsg::FundDividends { amount } => {
+            dividends::try_fund_dividends(deps, env, info, amount)
+        }
+        ExecuteMsg::ClaimDividends {} => dividends::try_claim_dividends(deps, env, info),
+        ExecuteMsg::UpdateBuyback { enabled } => buyback::try_update_buyback(deps, info, enabled),
+        ExecuteMsg::BurnCollected {} => buyback::try_burn_collected(deps, env),
+        ExecuteMsg::UpdateTimelock { timelock } => {
+            timelock::try_update_timelock(deps, info, timelock)
+        }
+        ExecuteMsg::ExecutePendingTransfer { id } => timelock::try_execute(deps, env, info, id),
+        ExecuteMsg::CancelTimelockedTransfer { id } => timelock::try_cancel(deps, env, info, id),
+        ExecuteMsg::RenounceOwnership {} => renounce::try_renounce_role(deps, info, Role::Owner),
+        ExecuteMsg::RenounceRole { role } => renounce::try_renounce_role(deps, info, role),
+        ExecuteMsg::SetDualControl { confirmer } => {
+            dualcontrol::try_set_dual_control(deps, info, confirmer)
+        }
+        ExecuteMsg::ProposeAdminAction { action } => {
+            dualcontrol::try_propose(deps, env, info, *action)
+        }
+        ExecuteMsg::ConfirmAdminAction { id } => dualcontrol::try_confirm(deps, env, info, id),
+        ExecuteMsg::CancelAdminAction { id } => dualcontrol::try_cancel(deps, info, id),
+        ExecuteMsg::Send {
+            contract,
+            amount,
+            msg,
+            on_failure,
+            memo,
+        } => send::try_send(deps, env, info, contract, amount, msg, on_failure, memo),
+        ExecuteMsg::TransferAndCall {
+            recipient,
+            amount,
+            msg,
+        } => try_transfer_and_call(deps, env, info, recipient, amount, msg),
+        ExecuteMsg::RetrySend { id } => send::try_retry_send(deps, info, id),
+        ExecuteMsg::ForceTransfer {
+            owner,
+            recipient,
+            amount,
+        } => clawback::try_force_transfer(deps, env, info, owner, recipient, amount),
+        ExecuteMsg::UpdateClawbackAdmin { admin } => {
+            clawback::try_update_clawback_admin(deps, info, admin)
+        }
+        ExecuteMsg::Receive(msg) => foreign::try_receive(deps, env, info, msg),
+        ExecuteMsg::SweepForeign { token, recipient } => {
+            foreign::try_sweep_foreign(deps, env, info, token, recipient)
+        }
+        ExecuteMsg::SweepNative { denom, recipient } => {
+            recovery::try_sweep_native(deps, env, info, denom, recipient)
+        }
+        ExecuteMsg::RecoverOwnBalance { recipient, amount } => {
+            recovery::try_recover_own_balance(deps, env, info, recipient, amount)
+        }
+        ExecuteMsg::SetComplianceContract { address } => {
+            compliance::try_set_compliance_contract(deps, info, address)
+        }
+        ExecuteMsg::ClearComplianceContract {} => {
+            compliance::try_clear_compliance_contract(deps, info)
+        }
+        ExecuteMsg::ApprovePeriodic {
+            spender,
+            amount_per_period,
+            period_seconds,
+        } => periodic::try_approve_periodic(
+            deps,
+            env,
+            info,
+            spender,
+            amount_per_period,
+            period_seconds,
+        ),
+        ExecuteMsg::SetLocker { locker } => lockup::try_set_locker(deps, info, locker),
+        ExecuteMsg::LockBalance {
+            address,
+            amount,
+            unlock_at,
+        } => lockup::try_lock_balance(deps, env, info, address, amount, unlock_at),
+        ExecuteMsg::Distribute { entries } => distribute::try_distribute(deps, env, info, entries),
+        ExecuteMsg::Freeze { address, expires } => {
+            freeze::try_freeze(deps, env, info, address, expires)
+        }
+        ExecuteMsg::Unfreeze { address } => freeze::try_unfreeze(deps, info, address),
+        ExecuteMsg::SetTrustedForwarder { forwarder } => {
+            forwarder::try_set_trusted_forwarder(deps, info, forwarder)
+        }
+        ExecuteMsg::UpdateSupplyExclusions { add, remove } => {
+            circulating::try_update_supply_exclusions(deps, info, add, remove)
+        }
+        ExecuteMsg::SetTreasury { treasury } => treasury::try_set_treasury(deps, info, treasury),
+        ExecuteMsg::ApproveMany { approvals } => try_approve_many(deps, env, info, approvals),
+        ExecuteMsg::RevokeAllAllowances {} => try_revoke_all_allowances(deps, env, info),
+        ExecuteMsg::IncreaseAllowance {
+            spender,
+            amount,
+            expires,
+        } => try_update_allowance(deps, env, info, spender, amount.u128(), true, expires),
+        ExecuteMsg::DecreaseAllowance {
+            spender,
+            amount,
+            expires,
+        } => try_update_allowance(deps, env, info, spender, amount.u128(), false, expires),
+        ExecuteMsg::SendFrom {
+            owner,
+            contract,
+            amount,
+            msg,
+        } => send::try_send_from(deps, env, info, owner, contract, amount, msg),
+        ExecuteMsg::UpdateMarketing {
+            project,
+            description,
+            marketing,
+        } => marketing::try_update_marketing(deps, info, project, description, marketing),
+        ExecuteMsg::UploadLogo(logo) => marketing::try_upload_logo(deps, info, logo),
     }
 }
 
-#[entry_point]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
-    match msg {
-        QueryMsg::Balance { address } => {
-            let address_key = deps.api.addr_validate(&address)?;
-            let balance = read_balance(deps.storage, &address_key)?;
-            let out = to_binary(&BalanceResponse {
-                balance: Uint128::from(balance),
-            })?;
-            Ok(out)
-        }
-        QueryMsg::Allowance { owner, spender } => {
-            let owner_key = deps.api.addr_validate(&owner)?;
-            let spender_key = deps.api.addr_validate(&spender)?;
-            let allowance = read_allowance(deps.storage, &owner_key, &spender_key)?;
-            let out = to_binary(&AllowanceResponse {
-                allowance: Uint128::from(allowance),
-            })?;
-            Ok(out)
-        }
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, ContractError> {
+    match msg {
+        QueryMsg::Balance { address } => {
+            let address_key = deps.api.addr_validate(&address)?;
+            let out = to_binary(&BalanceResponse {
+                balance: Uint128::from(query_balance(deps, &env, &address_key)?),
+            })?;
+            Ok(out)
+        }
+        QueryMsg::Balances { addresses } => {
+            if addresses.len() > MAX_BALANCES_BATCH {
+                return Err(ContractError::TooManyAddresses {
+                    max: MAX_BALANCES_BATCH,
+                });
+            }
+            let balances = addresses
+                .into_iter()
+                .map(|address| {
+                    let address_key = deps.api.addr_validate(&address)?;
+                    Ok(AddressBalance {
+                        address,
+                        balance: Uint128::from(query_balance(deps, &env, &address_key)?),
+                    })
+                })
+                .collect::<Result<Vec<_>, ContractError>>()?;
+            let out = to_binary(&BalancesResponse { balances })?;
+            Ok(out)
+        }
+        QueryMsg::SimulateTransfer {
+            sender,
+            recipient,
+            amount,
+        } => {
+            let sender_address = deps.api.addr_validate(&sender)?;
+            let recipient_address = deps.api.addr_validate(&recipient)?;
+            let out = to_binary(&simulate::simulate_transfer(
+                deps,
+                &env,
+                &sender_address,
+                &recipient_address,
+                amount.u128(),
+            ))?;
+            Ok(out)
+        }
+        QueryMsg::Allowance { owner, spender } => {
+            let owner_key = deps.api.addr_validate(&owner)?;
+            let spender_key = deps.api.addr_validate(&spender)?;
+            let allowance = read_allowance(deps.storage, deps.api, &env, &owner_key, &spender_key)?;
+            let expires =
+                read_allowance_expiration(deps.storage, deps.api, &owner_key, &spender_key)?;
+            let out = to_binary(&AllowanceResponse {
+                allowance: Uint128::from(allowance),
+                expires: expires.unwrap_or_default(),
+            })?;
+            Ok(out)
+        }
+        QueryMsg::PeriodicAllowance { owner, spender } => {
+            let owner_key = deps.api.addr_validate(&owner)?;
+            let spender_key = deps.api.addr_validate(&spender)?;
+            let out = to_binary(&periodic::query_periodic_allowance(
+                deps.storage,
+                &env,
+                &owner_key,
+                &spender_key,
+            )?)?;
+            Ok(out)
+        }
+        QueryMsg::AllowanceAt {
+            owner,
+            spender,
+            height,
+        } => {
+            let owner_key = deps.api.addr_validate(&owner)?;
+            let spender_key = deps.api.addr_validate(&spender)?;
+            let allowance =
+                read_allowance_at(deps.storage, deps.api, &owner_key, &spender_key, height)?;
+            let out = to_binary(&AllowanceResponse {
+                allowance: Uint128::from(allowance),
+                expires: Expiration::Never {},
+            })?;
+            Ok(out)
+        }
+        QueryMsg::AccountStats { address } => {
+            let address_key = deps
+                .api
+                .addr_canonicalize(deps.api.addr_validate(&address)?.as_str())?;
+            let sent = read_u128(
+                &ReadonlyPrefixedStorage::new(deps.storage, PREFIX_SENT),
+                &address_key,
+            )?;
+            let received = read_u128(
+                &ReadonlyPrefixedStorage::new(deps.storage, PREFIX_RECEIVED),
+                &address_key,
+            )?;
+            let burned = read_u128(
+                &ReadonlyPrefixedStorage::new(deps.storage, PREFIX_BURNED),
+                &address_key,
+            )?;
+            let out = to_binary(&AccountStatsResponse {
+                sent: Uint128::from(sent),
+                received: Uint128::from(received),
+                burned: Uint128::from(burned),
+            })?;
+            Ok(out)
+        }
+        QueryMsg::VotingPower { address } => {
+            let address_key = deps.api.addr_validate(&address)?;
+            let voting_power = votes::read_voting_power(deps.storage, &address_key, None)?;
+            let out = to_binary(&VotingPowerResponse {
+                voting_power: Uint128::from(voting_power),
+            })?;
+            Ok(out)
+        }
+        QueryMsg::VotingPowerAt { address, height } => {
+            let address_key = deps.api.addr_validate(&address)?;
+            let voting_power = votes::read_voting_power(deps.storage, &address_key, Some(height))?;
+            let out = to_binary(&VotingPowerResponse {
+                voting_power: Uint128::from(voting_power),
+            })?;
+            Ok(out)
+        }
+        QueryMsg::Nonce { address } => {
+            let address_key = deps.api.addr_validate(&address)?;
+            let nonce = permit::read_nonce(deps.storage, deps.api, &address_key)?;
+            let out = to_binary(&NonceResponse { nonce })?;
+            Ok(out)
+        }
+        QueryMsg::CirculatingSupply {} => {
+            let out = to_binary(&circulating::query_circulating_supply(
+                deps.storage,
+                deps.api,
+            )?)?;
+            Ok(out)
+        }
+        QueryMsg::TotalSupplyAt { height } => {
+            let total_supply = votes::read_supply_at(deps.storage, height)?;
+            let out = to_binary(&TotalSupplyResponse {
+                total_supply: Uint128::from(total_supply),
+            })?;
+            Ok(out)
+        }
+        QueryMsg::Bucket { name } => {
+            let out = to_binary(&treasury::query_bucket(deps.storage, name)?)?;
+            Ok(out)
+        }
+        QueryMsg::Buckets {} => {
+            let out = to_binary(&BucketsResponse {
+                buckets: treasury::query_buckets(deps.storage)?,
+            })?;
+            Ok(out)
+        }
+        QueryMsg::Treasury {} => {
+            let out = to_binary(&TreasuryResponse {
+                treasury: treasury::read_treasury(deps.storage)?.map(|addr| addr.to_string()),
+            })?;
+            Ok(out)
+        }
+        QueryMsg::PauseState {} => {
+            let state = pause::read_pause_state(deps.storage)?;
+            let out = to_binary(&PauseStateResponse {
+                paused: state.paused,
+                paused_at: state.paused_at,
+                max_pause_duration: state.max_pause_duration,
+                unpause_votes: state.unpause_votes,
+            })?;
+            Ok(out)
+        }
+        QueryMsg::FeeConfig {} => {
+            let config = fees::read_fee_config(deps.storage)?;
+            let out = to_binary(&FeeConfigResponse {
+                enabled: config.enabled,
+                rate_bps: config.rate_bps,
+                treasury: config.treasury.map(|addr| addr.to_string()),
+            })?;
+            Ok(out)
+        }
+        QueryMsg::CurrentFee {} => {
+            let out = to_binary(&fees::query_current_fee(deps.storage, &env)?)?;
+            Ok(out)
+        }
+        QueryMsg::TransferLimits {} => {
+            let limits = limits::read_transfer_limits(deps.storage)?;
+            let out = to_binary(&TransferLimitsResponse {
+                max_tx_amount: limits.max_tx_amount,
+                max_wallet_amount: limits.max_wallet_amount,
+                exempt: limits.exempt.iter().map(|addr| addr.to_string()).collect(),
+            })?;
+            Ok(out)
+        }
+        QueryMsg::ExportState {
+            section,
+            start_after,
+            limit,
+        } => {
+            let out = to_binary(&export::export_state(
+                deps.storage,
+                deps.api,
+                section,
+                start_after,
+                limit,
+            )?)?;
+            Ok(out)
+        }
+        QueryMsg::VerifySupply { start_after, limit } => {
+            let out = to_binary(&export::verify_supply(deps.storage, start_after, limit)?)?;
+            Ok(out)
+        }
+        QueryMsg::ContractInfo {} => {
+            let out = to_binary(&get_contract_version(deps.storage)?)?;
+            Ok(out)
+        }
+        QueryMsg::ComplianceContract {} => {
+            let out = to_binary(&ComplianceContractResponse {
+                address: compliance::read_compliance_contract(deps.storage)?
+                    .map(|addr| addr.to_string()),
+            })?;
+            Ok(out)
+        }
+        QueryMsg::SpendableBalance { address } => {
+            let address_key = deps.api.addr_validate(&address)?;
+            let balance = checked_add(
+                read_balance(deps.storage, deps.api, &address_key)?,
+                locks::read_matured(deps.storage, &env, &address_key)?,
+            )?;
+            let lockups = lockup::read_active_lockups(deps.storage, &env, &address_key)?;
+            let locked_up = lockups
+                .iter()
+                .try_fold(0u128, |sum, lockup| checked_add(sum, lockup.amount.u128()))?;
+            let out = to_binary(&SpendableBalanceResponse {
+                balance: Uint128::from(balance),
+                spendable: Uint128::from(balance.saturating_sub(locked_up)),
+                lockups,
+            })?;
+            Ok(out)
+        }
+        QueryMsg::Locker {} => {
+            let out = to_binary(&LockerResponse {
+                locker: lockup::read_locker(deps.storage)?.map(|addr| addr.to_string()),
+            })?;
+            Ok(out)
+        }
+        QueryMsg::Frozen { address } => {
+            let account = deps.api.addr_validate(&address)?;
+            let out = to_binary(&FrozenResponse {
+                frozen: freeze::is_frozen(deps.storage, &env, &account)?,
+                expires: freeze::read_freeze(deps.storage, &account)?,
+            })?;
+            Ok(out)
+        }
+        QueryMsg::TransferBurn {} => {
+            let burn = deflation::read_transfer_burn(deps.storage)?;
+            let out = to_binary(&TransferBurnResponse {
+                rate_bps: burn.rate_bps,
+                exempt: burn.exempt.iter().map(|addr| addr.to_string()).collect(),
+            })?;
+            Ok(out)
+        }
+        QueryMsg::RateLimit { address } => {
+            let address_key = deps.api.addr_validate(&address)?;
+            let limit = ratelimit::read_rate_limit(deps.storage, &address_key)?;
+            let used = match &limit {
+                Some(limit) => {
+                    ratelimit::read_usage(deps.storage, &env, &address_key, limit.window)?
+                }
+                None => 0u128,
+            };
+            let out = to_binary(&RateLimitResponse {
+                limit,
+                used: Uint128::from(used),
+            })?;
+            Ok(out)
+        }
+        QueryMsg::LargeTransferPolicy { address } => {
+            let address_key = deps.api.addr_validate(&address)?;
+            let policy = largetransfer::read_policy(deps.storage, &address_key)?;
+            let out = to_binary(&LargeTransferPolicyResponse {
+                threshold: policy.as_ref().map(|policy| policy.threshold),
+                window: policy
+                    .as_ref()
+                    .map(|policy| policy.window)
+                    .unwrap_or_default(),
+                co_signer: policy
+                    .and_then(|policy| policy.co_signer)
+                    .map(|addr| addr.to_string()),
+            })?;
+            Ok(out)
+        }
+        QueryMsg::PendingTransfer { id } => {
+            let pending = largetransfer::read_pending_transfer(deps.storage, id)?;
+            let out = to_binary(&PendingTransferResponse {
+                id,
+                sender: pending.sender.to_string(),
+                recipient: pending.recipient.to_string(),
+                amount: pending.amount,
+                expires_at: pending.expires_at,
+            })?;
+            Ok(out)
+        }
+        QueryMsg::Locks { address } => {
+            let address_key = deps.api.addr_validate(&address)?;
+            let out = to_binary(&LocksResponse {
+                locks: locks::read_locks(deps.storage, &address_key)?,
+            })?;
+            Ok(out)
+        }
+        QueryMsg::RebaseState {} => {
+            let out = to_binary(&RebaseStateResponse {
+                rebaser: rebase::read_rebaser(deps.storage)?.map(|addr| addr.to_string()),
+                total_supply: Uint128::from(read_total_supply(deps.storage)?),
+                total_shares: Uint128::from(rebase::read_total_shares(deps.storage)?),
+            })?;
+            Ok(out)
+        }
+        QueryMsg::AssetCard { address } => {
+            let out = to_binary(&query_asset_card(deps, &env, address)?)?;
+            Ok(out)
+        }
+        QueryMsg::WrappedDenom {} => {
+            let out = to_binary(&WrappedDenomResponse {
+                denom: wrap::read_wrap_config(deps.storage)?.map(|config| config.denom),
+            })?;
+            Ok(out)
+        }
+        QueryMsg::Claimable { id } => {
+            let claimable = claimable::read_claimable(deps.storage, id)?;
+            let out = to_binary(&ClaimableResponse {
+                id,
+                sender: claimable.sender.to_string(),
+                recipient: claimable.recipient.to_string(),
+                amount: claimable.amount,
+                claimed: claimable.claimed,
+                expires_at: claimable.expires_at,
+            })?;
+            Ok(out)
+        }
+        QueryMsg::SupportedInterfaces {} => {
+            let out = to_binary(&SupportedInterfacesResponse {
+                interfaces: interfaces::read_supported_interfaces(deps.storage)?,
+            })?;
+            Ok(out)
+        }
+        QueryMsg::Interfaces {} => {
+            let out = to_binary(&interfaces::query_interfaces(deps.storage)?)?;
+            Ok(out)
+        }
+        QueryMsg::Config {} => {
+            let out = to_binary(&query_config(deps)?)?;
+            Ok(out)
+        }
+        QueryMsg::TokenInfo {} => {
+            let constants = read_constants(deps.storage)?;
+            let out = to_binary(&TokenInfoResponse {
+                name: constants.name,
+                symbol: constants.symbol,
+                display_symbol: marketing::read_display_symbol(deps.storage)?,
+                decimals: constants.decimals,
+                total_supply: Uint128::from(read_total_supply(deps.storage)?),
+            })?;
+            Ok(out)
+        }
+        QueryMsg::Stream { id } => {
+            let stream = streams::read_stream(deps.storage, id)?;
+            let out = to_binary(&streams::stream_response(id, stream, &env))?;
+            Ok(out)
+        }
+        QueryMsg::Drip {} => {
+            let drip = drip::read_drip(deps.storage)?.ok_or(ContractError::NoDrip {})?;
+            let out = to_binary(&drip::drip_response(drip, &env))?;
+            Ok(out)
+        }
+        QueryMsg::StreamsByRecipient {
+            recipient,
+            start_after,
+            limit,
+        } => {
+            let recipient_address = deps.api.addr_validate(&recipient)?;
+            let out = to_binary(&StreamsResponse {
+                streams: streams::read_streams_by_recipient(
+                    deps.storage,
+                    &env,
+                    &recipient_address,
+                    start_after,
+                    limit,
+                )?,
+            })?;
+            Ok(out)
+        }
+        QueryMsg::SubscriptionsByPayer {
+            payer,
+            start_after,
+            limit,
+        } => {
+            let payer_address = deps.api.addr_validate(&payer)?;
+            let out = to_binary(&SubscriptionsResponse {
+                subscriptions: subscriptions::read_subscriptions_by_payer(
+                    deps.storage,
+                    &payer_address,
+                    start_after,
+                    limit,
+                )?,
+            })?;
+            Ok(out)
+        }
+        QueryMsg::SubscriptionsByPayee {
+            payee,
+            start_after,
+            limit,
+        } => {
+            let payee_address = deps.api.addr_validate(&payee)?;
+            let out = to_binary(&SubscriptionsResponse {
+                subscriptions: subscriptions::read_subscriptions_by_payee(
+                    deps.storage,
+                    &payee_address,
+                    start_after,
+                    limit,
+                )?,
+            })?;
+            Ok(out)
+        }
+        QueryMsg::BurnReceipt {} => {
+            let receipt = receipt::read_burn_receipt(deps.storage)?;
+            let out = to_binary(&BurnReceiptResponse {
+                receipt: receipt.map(|receipt| BurnReceiptInfo {
+                    contract: receipt.contract.to_string(),
+                    mode: receipt.mode,
+                    ratio: receipt.ratio,
+                }),
+            })?;
+            Ok(out)
+        }
+        QueryMsg::PendingDividends { address } => {
+            let address_key = deps.api.addr_validate(&address)?;
+            let out = to_binary(&PendingDividendsResponse {
+                dividends: dividends::read_pending(deps.storage, deps.api, &env, &address_key)?,
+            })?;
+            Ok(out)
+        }
+        QueryMsg::Buyback {} => {
+            let buyback = buyback::read_buyback(deps.storage)?;
+            let out = to_binary(&BuybackResponse {
+                enabled: buyback.enabled,
+                collected: buyback.collected,
+                total_burned: buyback.burned,
+            })?;
+            Ok(out)
+        }
+        QueryMsg::SpendingReport { address, epoch } => {
+            let address_key = deps.api.addr_validate(&address)?;
+            let counters = report::read_counters(deps.storage, &address_key, epoch)?;
+            let (start, end) = report::epoch_bounds(epoch)?;
+            let out = to_binary(&SpendingReportResponse {
+                address,
+                epoch,
+                start,
+                end,
+                sent: counters.sent,
+                fees_paid: counters.fees_paid,
+                burned: counters.burned,
+                rewards: counters.rewards,
+            })?;
+            Ok(out)
+        }
+        QueryMsg::Timelock {} => {
+            let out = to_binary(&TimelockResponse {
+                timelock: timelock::read_timelock(deps.storage)?,
+            })?;
+            Ok(out)
+        }
+        QueryMsg::TimelockedTransfer { id } => {
+            let transfer = timelock::read_timelocked_transfer(deps.storage, id)?;
+            let out = to_binary(&timelock::timelocked_transfer_response(id, transfer))?;
+            Ok(out)
+        }
+        QueryMsg::TimelockedTransfers { start_after, limit } => {
+            let out = to_binary(&TimelockedTransfersResponse {
+                transfers: timelock::read_timelocked_transfers(deps.storage, start_after, limit)?,
+            })?;
+            Ok(out)
+        }
+        QueryMsg::DualControl {} => {
+            let out = to_binary(&DualControlResponse {
+                confirmer: dualcontrol::read_confirmer(deps.storage)?.map(|addr| addr.to_string()),
+            })?;
+            Ok(out)
+        }
+        QueryMsg::AdminProposal { id } => {
+            let proposal = dualcontrol::read_admin_proposal(deps.storage, id)?;
+            let out = to_binary(&dualcontrol::admin_proposal_response(id, proposal))?;
+            Ok(out)
+        }
+        QueryMsg::AdminProposals { start_after, limit } => {
+            let out = to_binary(&AdminProposalsResponse {
+                proposals: dualcontrol::read_admin_proposals(deps.storage, start_after, limit)?,
+            })?;
+            Ok(out)
+        }
+        QueryMsg::FailedSends { start_after, limit } => {
+            let out = to_binary(&FailedSendsResponse {
+                sends: send::read_failed_sends(deps.storage, start_after, limit)?,
+            })?;
+            Ok(out)
+        }
+        QueryMsg::HolderCount {} => {
+            let out = to_binary(&HolderCountResponse {
+                count: holders::read_holder_count(deps.storage)?,
+            })?;
+            Ok(out)
+        }
+        QueryMsg::TopHolders { limit } => {
+            let out = to_binary(&TopHoldersResponse {
+                holders: holders::read_top_holders(deps.storage, limit)?,
+            })?;
+            Ok(out)
+        }
+        QueryMsg::HoldersAbove {
+            threshold,
+            start_after,
+            limit,
+        } => {
+            let start_after = match start_after {
+                Some(address) => Some(deps.api.addr_validate(&address)?),
+                None => None,
+            };
+            let out = to_binary(&HoldersAboveResponse {
+                holders: holders::read_holders_above(
+                    deps.storage,
+                    deps.api,
+                    threshold.u128(),
+                    start_after,
+                    limit,
+                )?,
+            })?;
+            Ok(out)
+        }
+        QueryMsg::StuckBalance {} => {
+            let out = to_binary(&StuckBalanceResponse {
+                amount: Uint128::from(recovery::read_stuck_balance(deps.storage)?),
+            })?;
+            Ok(out)
+        }
+        QueryMsg::ForeignBalances { start_after, limit } => {
+            let start_after = start_after
+                .map(|token| deps.api.addr_validate(&token))
+                .transpose()?;
+            let out = to_binary(&ForeignBalancesResponse {
+                balances: foreign::read_foreign_balances(deps.storage, start_after, limit)?,
+            })?;
+            Ok(out)
+        }
+        QueryMsg::Cap {} => {
+            let out = to_binary(&CapResponse {
+                cap: cap::read_max_supply(deps.storage)?.map(Uint128::from),
+            })?;
+            Ok(out)
+        }
+        QueryMsg::Minter {} => {
+            let out = to_binary(&mint::minter_response(deps.storage)?)?;
+            Ok(out)
+        }
+        QueryMsg::Renounced {} => {
+            let out = to_binary(&RenouncedResponse {
+                roles: renounce::read_renounced(deps.storage)?,
+            })?;
+            Ok(out)
+        }
+        QueryMsg::ClawbackAdmin {} => {
+            let out = to_binary(&ClawbackAdminResponse {
+                admin: clawback::read_clawback_admin(deps.storage)?.map(|addr| addr.to_string()),
+            })?;
+            Ok(out)
+        }
+        QueryMsg::AllAllowances {
+            owner,
+            start_after,
+            limit,
+        } => {
+            let owner = deps.api.addr_validate(&owner)?;
+            let start_after = start_after
+                .map(|address| deps.api.addr_validate(&address))
+                .transpose()?;
+            let out = to_binary(&AllAllowancesResponse {
+                allowances: read_all_allowances(
+                    deps.storage,
+                    deps.api,
+                    &env,
+                    &owner,
+                    start_after,
+                    limit,
+                )?,
+            })?;
+            Ok(out)
+        }
+        QueryMsg::AllAccounts { start_after, limit } => {
+            let start_after = start_after
+                .map(|address| deps.api.addr_validate(&address))
+                .transpose()?;
+            let out = to_binary(&AllAccountsResponse {
+                accounts: read_all_accounts(deps.storage, deps.api, start_after, limit)?,
+            })?;
+            Ok(out)
+        }
+        QueryMsg::MarketingInfo {} => {
+            let out = to_binary(&marketing::query_marketing_info(deps.storage)?)?;
+            Ok(out)
+        }
+        QueryMsg::DownloadLogo {} => {
+            let out = to_binary(&marketing::query_download_logo(deps.storage)?)?;
+            Ok(out)
+        }
+    }
+}
+
+// Everything a wallet needs to render the token for one account in a single round trip
+fn query_asset_card(
+    deps: Deps,
+    env: &Env,
+    address: String,
+) -> Result<AssetCardResponse, ContractError> {
+    let address_key = deps.api.addr_validate(&address)?;
+    let constants = read_constants(deps.storage)?;
+    let delegate = votes::read_delegate(deps.storage, &address_key)?;
+    let fee_config = fees::read_fee_config(deps.storage)?;
+    let limits = limits::read_transfer_limits(deps.storage)?;
+    let transfer_fee_bps = fees::current_rate_bps(deps.storage, env, &fee_config)?;
+
+    Ok(AssetCardResponse {
+        name: constants.name,
+        symbol: constants.symbol,
+        display_symbol: marketing::read_display_symbol(deps.storage)?,
+        decimals: constants.decimals,
+        total_supply: Uint128::from(read_total_supply(deps.storage)?),
+        balance: Uint128::from(checked_add(
+            read_balance(deps.storage, deps.api, &address_key)?,
+            locks::read_matured(deps.storage, env, &address_key)?,
+        )?),
+        locked: Uint128::from(locks::read_locked(deps.storage, env, &address_key)?),
+        voting_power: Uint128::from(votes::read_voting_power(deps.storage, &address_key, None)?),
+        delegate: delegate.map(|addr| addr.to_string()),
+        restrictions: AssetRestrictions {
+            paused: pause::read_pause_state(deps.storage)?.paused,
+            transfer_fee_bps,
+            max_tx_amount: limits.max_tx_amount,
+            max_wallet_amount: limits.max_wallet_amount,
+        },
+    })
+}
+
+// Constants and every operational setting in a single round trip
+fn query_config(deps: Deps) -> Result<ConfigResponse, ContractError> {
+    let to_string = |addr: Addr| addr.to_string();
+    let fee_config = fees::read_fee_config(deps.storage)?;
+    let transfer_burn = deflation::read_transfer_burn(deps.storage)?;
+
+    Ok(ConfigResponse {
+        constants: read_constants(deps.storage)?,
+        owner: read_owner(deps.storage)?.map(to_string),
+        minter: mint::minter_response(deps.storage)?,
+        paused: pause::read_pause_state(deps.storage)?.paused,
+        fee_config: FeeConfigResponse {
+            enabled: fee_config.enabled,
+            rate_bps: fee_config.rate_bps,
+            treasury: fee_config.treasury.map(to_string),
+        },
+        transfer_burn: TransferBurnResponse {
+            rate_bps: transfer_burn.rate_bps,
+            exempt: transfer_burn.exempt.into_iter().map(to_string).collect(),
+        },
+        max_supply: cap::read_max_supply(deps.storage)?.map(Uint128::from),
+        wrapped_denom: wrap::read_wrap_config(deps.storage)?.map(|config| config.denom),
+        rebaser: rebase::read_rebaser(deps.storage)?.map(to_string),
+        clawback_admin: clawback::read_clawback_admin(deps.storage)?.map(to_string),
+        compliance_contract: compliance::read_compliance_contract(deps.storage)?.map(to_string),
+        locker: lockup::read_locker(deps.storage)?.map(to_string),
+        min_transfer_amount: Uint128::from(limits::read_min_transfer_amount(deps.storage)?),
+        allow_zero_transfers: limits::read_allow_zero_transfers(deps.storage),
+        trusted_forwarder: forwarder::read_trusted_forwarder(deps.storage)?.map(to_string),
+        treasury: treasury::read_treasury(deps.storage)?.map(to_string),
+    })
+}
+
+fn try_transfer(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipient: String,
+    amount: &Uint128,
+    memo: Option<String>,
+) -> Result<Response, ContractError> {
+    validate_memo(&memo)?;
+    assert_transfer_allowed(deps.storage, &info.sender, amount.u128())?;
+    let recipient_address = deps.api.addr_validate(recipient.as_str())?;
+    let outcome = perform_transfer(
+        deps.storage,
+        deps.api,
+        &deps.querier,
+        &env,
+        &info.sender,
+        &recipient_address,
+        amount.u128(),
+    )?;
+    let event = events::transfer_event(
+        deps.storage,
+        deps.api,
+        &info.sender,
+        &recipient_address,
+        amount.u128(),
+        outcome,
+    )?;
+    let res = Response::new()
+        .set_data(balance_data(deps.storage, deps.api, &info.sender)?)
+        .add_event(event)
+        .add_attribute("action", "transfer")
+        .add_attribute("sender", info.sender)
+        .add_attribute("recipient", recipient);
+    Ok(add_memo(add_outcome(res, outcome), memo))
+}
+
+// Sends to contracts so their receive hook runs, transfers to anyone else
+fn try_transfer_and_call(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipient: String,
+    amount: Uint128,
+    msg: Binary,
+) -> Result<Response, ContractError> {
+    let recipient_address = deps.api.addr_validate(&recipient)?;
+    if send::is_contract(&deps.querier, &recipient_address) {
+        send::try_send(deps, env, info, recipient, amount, msg, None, None)
+    } else {
+        try_transfer(deps, env, info, recipient, &amount, None)
+    }
+}
+
+fn try_transfer_from(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    owner: String,
+    recipient: String,
+    amount: &Uint128,
+    memo: Option<String>,
+) -> Result<Response, ContractError> {
+    validate_memo(&memo)?;
+    let owner_address = deps.api.addr_validate(owner.as_str())?;
+    let recipient_address = deps.api.addr_validate(recipient.as_str())?;
+    let amount_raw = amount.u128();
+    assert_transfer_allowed(deps.storage, &owner_address, amount_raw)?;
+    check_allowance(
+        deps.storage,
+        deps.api,
+        &env,
+        &owner_address,
+        &info.sender,
+        amount_raw,
+    )?;
+
+    let outcome = perform_transfer(
+        deps.storage,
+        deps.api,
+        &deps.querier,
+        &env,
+        &owner_address,
+        &recipient_address,
+        amount_raw,
+    )?;
+    let event = events::transfer_event(
+        deps.storage,
+        deps.api,
+        &owner_address,
+        &recipient_address,
+        amount_raw,
+        outcome,
+    )?;
+    let mut res = Response::new().add_event(event);
+    // A queued transfer spends the allowance when it is executed, so cancelling
+    // it leaves the allowance untouched
+    let allowance = match outcome {
+        TransferOutcome::Completed { .. } => {
+            let allowance = spend_allowance(
+                deps.storage,
+                deps.api,
+                &env,
+                &owner_address,
+                &info.sender,
+                amount_raw,
+            )?;
+            res = res.add_event(events::approve_event(
+                &owner_address,
+                &info.sender,
+                allowance,
+            ));
+            allowance
+        }
+        TransferOutcome::Queued { id } => {
+            timelock::record_spender(deps.storage, id, &info.sender)?;
+            check_allowance(
+                deps.storage,
+                deps.api,
+                &env,
+                &owner_address,
+                &info.sender,
+                0,
+            )?
+        }
+    };
+
+    let res = res
+        .set_data(allowance_data(allowance)?)
+        .add_attribute("action", "transfer_from")
+        .add_attribute("spender", &info.sender)
+        .add_attribute("sender", owner)
+        .add_attribute("recipient", recipient);
+    Ok(add_memo(add_outcome(res, outcome), memo))
+}
+
+// Errors unless `spender` could spend `amount` of `owner` right now, like
+// `spend_allowance` but without writing. Returns what would be left.
+pub fn check_allowance(
+    store: &dyn Storage,
+    api: &dyn Api,
+    env: &Env,
+    owner: &Addr,
+    spender: &Addr,
+    amount: u128,
+) -> Result<u128, ContractError> {
+    let allowance = match periodic::remaining(store, env, owner, spender)? {
+        Some(remaining) => remaining,
+        None => match read_allowance(store, api, env, owner, spender)? {
+            UNLIMITED_ALLOWANCE => return Ok(UNLIMITED_ALLOWANCE),
+            allowance => allowance,
+        },
+    };
+    allowance
+        .checked_sub(amount)
+        .ok_or(ContractError::InsufficientAllowance {
+            allowance,
+            required: amount,
+        })
+}
+
+// Takes `amount` out of the allowance of `spender` and returns what is left.
+// A periodic allowance takes precedence over the plain one. Unlimited
+// allowances are neither decremented nor written.
+pub fn spend_allowance(
+    store: &mut dyn Storage,
+    api: &dyn Api,
+    env: &Env,
+    owner: &Addr,
+    spender: &Addr,
+    amount: u128,
+) -> Result<u128, ContractError> {
+    if let Some(remaining) = periodic::spend(store, env, owner, spender, amount)? {
+        return Ok(remaining);
+    }
+    let allowance = read_allowance(store, api, env, owner, spender)?;
+    if allowance == UNLIMITED_ALLOWANCE {
+        return Ok(allowance);
+    }
+    if allowance < amount {
+        return Err(ContractError::InsufficientAllowance {
+            allowance,
+            required: amount,
+        });
     }
+    let remaining = checked_sub(allowance, amount)?;
+    write_allowance(store, api, env, owner, spender, remaining)?;
+    Ok(remaining)
 }
 
-fn try_transfer(
+fn try_approve(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
-    recipient: String,
+    spender: String,
     amount: &Uint128,
+    expected_current: Option<Uint128>,
 ) -> Result<Response, ContractError> {
-    perform_transfer(
+    let spender_address = deps.api.addr_validate(spender.as_str())?;
+    if let Some(expected) = expected_current {
+        let current = read_allowance(deps.storage, deps.api, &env, &info.sender, &spender_address)?;
+        if current != expected.u128() {
+            return Err(ContractError::AllowanceChanged {
+                expected: expected.u128(),
+                current,
+            });
+        }
+    }
+    write_allowance(
         deps.storage,
+        deps.api,
+        &env,
         &info.sender,
-        &deps.api.addr_validate(recipient.as_str())?,
+        &spender_address,
         amount.u128(),
     )?;
+    write_allowance_expiration(deps.storage, deps.api, &info.sender, &spender_address, None)?;
+    if amount.is_zero() {
+        periodic::revoke(deps.storage, &info.sender, &spender_address)?;
+    }
     Ok(Response::new()
-        .add_attribute("action", "transfer")
-        .add_attribute("sender", info.sender)
-        .add_attribute("recipient", recipient))
+        .set_data(allowance_data(amount.u128())?)
+        .add_event(events::approve_event(
+            &info.sender,
+            &spender_address,
+            amount.u128(),
+        ))
+        .add_attribute("action", "approve")
+        .add_attribute("owner", info.sender)
+        .add_attribute("spender", spender))
 }
 
-fn try_transfer_from(
+/// Set several allowances of the signer, each with an optional expiration.
+/// Every entry is validated before anything is written, later entries for the
+/// same spender override earlier ones.
+fn try_approve_many(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
-    owner: String,
-    recipient: String,
-    amount: &Uint128,
+    approvals: Vec<ApprovalEntry>,
 ) -> Result<Response, ContractError> {
-    let owner_address = deps.api.addr_validate(owner.as_str())?;
-    let recipient_address = deps.api.addr_validate(recipient.as_str())?;
-    let amount_raw = amount.u128();
-
-    let mut allowance = read_allowance(deps.storage, &owner_address, &info.sender)?;
-    if allowance < amount_raw {
-        return Err(ContractError::InsufficientAllowance {
-            allowance,
-            required: amount_raw,
+    if approvals.len() > MAX_APPROVE_ENTRIES {
+        return Err(ContractError::TooManyApprovals {
+            max: MAX_APPROVE_ENTRIES,
         });
     }
-    allowance -= amount_raw;
-    write_allowance(deps.storage, &owner_address, &info.sender, allowance)?;
-    perform_transfer(deps.storage, &owner_address, &recipient_address, amount_raw)?;
+    let mut validated = Vec::with_capacity(approvals.len());
+    for approval in approvals {
+        let spender = deps.api.addr_validate(&approval.spender)?;
+        if let Some(expires) = approval.expires {
+            if expires.is_expired(&env.block) {
+                return Err(ContractError::InvalidAllowanceExpiration {});
+            }
+        }
+        validated.push((spender, approval.amount.u128(), approval.expires));
+    }
 
-    Ok(Response::new()
-        .add_attribute("action", "transfer_from")
-        .add_attribute("spender", &info.sender)
-        .add_attribute("sender", owner)
-        .add_attribute("recipient", recipient))
+    let mut res = Response::new();
+    for (spender, amount, expires) in &validated {
+        write_allowance(deps.storage, deps.api, &env, &info.sender, spender, *amount)?;
+        write_allowance_expiration(deps.storage, deps.api, &info.sender, spender, *expires)?;
+        if *amount == 0 {
+            periodic::revoke(deps.storage, &info.sender, spender)?;
+        }
+        let mut event = events::approve_event(&info.sender, spender, *amount);
+        if let Some(expires) = expires {
+            event = event.add_attribute("expires", expires.to_string());
+        }
+        res = res.add_event(event);
+    }
+    Ok(res
+        .add_attribute("action", "approve_many")
+        .add_attribute("owner", info.sender)
+        .add_attribute("approvals", validated.len().to_string()))
 }
 
-fn try_approve(
+/// IncreaseAllowance and DecreaseAllowance of cw20. Unlike Approve they keep
+/// the expiration of the allowance unless `expires` replaces it. A decrease
+/// also revokes the periodic allowance, which would otherwise take precedence.
+fn try_update_allowance(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     spender: String,
-    amount: &Uint128,
+    amount: u128,
+    increase: bool,
+    expires: Option<Expiration>,
 ) -> Result<Response, ContractError> {
-    let spender_address = deps.api.addr_validate(spender.as_str())?;
-    write_allowance(deps.storage, &info.sender, &spender_address, amount.u128())?;
+    let spender_address = deps.api.addr_validate(&spender)?;
+    if let Some(expires) = expires {
+        if expires.is_expired(&env.block) {
+            return Err(ContractError::InvalidAllowanceExpiration {});
+        }
+    }
+    let current = read_allowance(deps.storage, deps.api, &env, &info.sender, &spender_address)?;
+    let allowance = if increase {
+        current.saturating_add(amount)
+    } else {
+        periodic::revoke(deps.storage, &info.sender, &spender_address)?;
+        current.saturating_sub(amount)
+    };
+    write_allowance(
+        deps.storage,
+        deps.api,
+        &env,
+        &info.sender,
+        &spender_address,
+        allowance,
+    )?;
+    let mut event = events::approve_event(&info.sender, &spender_address, allowance);
+    if let Some(expires) = expires {
+        write_allowance_expiration(
+            deps.storage,
+            deps.api,
+            &info.sender,
+            &spender_address,
+            Some(expires),
+        )?;
+        event = event.add_attribute("expires", expires.to_string());
+    }
+    let action = if increase {
+        "increase_allowance"
+    } else {
+        "decrease_allowance"
+    };
+
     Ok(Response::new()
-        .add_attribute("action", "approve")
+        .set_data(allowance_data(allowance)?)
+        .add_event(event)
+        .add_attribute("action", action)
         .add_attribute("owner", info.sender)
-        .add_attribute("spender", spender))
+        .add_attribute("spender", spender)
+        .add_attribute("amount", amount.to_string()))
+}
+
+/// Delete all allowances of the signer, plain and periodic. Emits an approve
+/// event with a zero allowance for every spender that lost one.
+fn try_revoke_all_allowances(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let owner = info.sender;
+    let owner_key = deps.api.addr_canonicalize(owner.as_str())?;
+    let namespace = [PREFIX_ALLOWANCES, owner_key.as_slice()];
+    let spender_keys: Vec<Vec<u8>> = ReadonlyPrefixedStorage::multilevel(deps.storage, &namespace)
+        .range(None, None, Order::Ascending)
+        .map(|(key, _)| key)
+        .collect();
+    let mut spenders = BTreeSet::new();
+    for spender_key in spender_keys {
+        PrefixedStorage::multilevel(deps.storage, &namespace).remove(&spender_key);
+        let spender_key = CanonicalAddr::from(spender_key);
+        votes::write_checkpoint(
+            deps.storage,
+            &allowance_checkpoints(&owner_key, &spender_key),
+            env.block.height,
+            0,
+        );
+        let spender = deps.api.addr_humanize(&spender_key)?;
+        write_allowance_expiration(deps.storage, deps.api, &owner, &spender, None)?;
+        spenders.insert(spender);
+    }
+    spenders.extend(periodic::revoke_all(deps.storage, &owner)?);
+
+    let res = spenders.iter().fold(Response::new(), |res, spender| {
+        res.add_event(events::approve_event(&owner, spender, 0))
+    });
+    Ok(res
+        .add_attribute("action", "revoke_all_allowances")
+        .add_attribute("owner", owner)
+        .add_attribute("revoked", spenders.len().to_string()))
 }
 
 /// Burn tokens
@@ -170,126 +1568,848 @@ fn try_approve(
 /// Remove `amount` tokens from the system irreversibly, from signer account
 ///
 /// @param amount the amount of money to burn
+/// @param memo optional reason for the audit trail
 fn try_burn(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount: &Uint128,
+    memo: Option<String>,
+) -> Result<Response, ContractError> {
+    validate_memo(&memo)?;
+    let res = burn_tokens(deps.branch(), &env, &info.sender, amount.u128(), &memo)?;
+    let res = res
+        .set_data(balance_data(deps.storage, deps.api, &info.sender)?)
+        .add_attribute("action", "burn")
+        .add_attribute("account", info.sender)
+        .add_attribute("amount", amount.to_string());
+    Ok(add_memo(res, memo))
+}
+
+/// Burn tokens of `owner` within the allowance of the signer
+fn try_burn_from(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
+    owner: String,
     amount: &Uint128,
+    memo: Option<String>,
 ) -> Result<Response, ContractError> {
+    validate_memo(&memo)?;
+    let owner_address = deps.api.addr_validate(owner.as_str())?;
     let amount_raw = amount.u128();
+    let allowance = spend_allowance(
+        deps.storage,
+        deps.api,
+        &env,
+        &owner_address,
+        &info.sender,
+        amount_raw,
+    )?;
+
+    let res = burn_tokens(deps, &env, &owner_address, amount_raw, &memo)?;
+    let res = res
+        .set_data(allowance_data(allowance)?)
+        .add_event(events::approve_event(
+            &owner_address,
+            &info.sender,
+            allowance,
+        ))
+        .add_attribute("action", "burn_from")
+        .add_attribute("spender", info.sender)
+        .add_attribute("account", owner)
+        .add_attribute("amount", amount.to_string());
+    Ok(add_memo(res, memo))
+}
+
+// Burns `amount` of `owner` with its event and burn receipt, if any
+fn burn_tokens(
+    deps: DepsMut,
+    env: &Env,
+    owner: &Addr,
+    amount: u128,
+    memo: &Option<String>,
+) -> Result<Response, ContractError> {
+    pause::assert_not_paused(deps.storage)?;
+    freeze::assert_not_frozen(deps.storage, env, owner)?;
+    locks::release_matured(deps.storage, deps.api, env, owner)?;
+    assert_balance_spendable(deps.storage, deps.api, env, owner, amount)?;
+    burn_balance(deps.storage, deps.api, env, owner, amount)?;
 
-    let mut account_balance = read_balance(deps.storage, &info.sender)?;
+    let mut event = events::burn_event(deps.storage, deps.api, owner, amount)?;
+    if let Some(memo) = memo {
+        event = event.add_attribute("memo", memo);
+    }
+    let res = Response::new().add_event(event);
+    Ok(match receipt::receipt_msg(deps.storage, owner, amount)? {
+        Some(msg) => res.add_message(msg),
+        None => res,
+    })
+}
 
-    if account_balance < amount_raw {
+// Removes `amount` from the balance of `owner` and the total supply
+pub fn burn_balance(
+    store: &mut dyn Storage,
+    api: &dyn Api,
+    env: &Env,
+    owner: &Addr,
+    amount: u128,
+) -> Result<(), ContractError> {
+    let account_balance = read_balance(store, api, owner)?;
+    if account_balance < amount {
         return Err(ContractError::InsufficientFunds {
             balance: account_balance,
-            required: amount_raw,
+            required: amount,
         });
     }
-    account_balance -= amount_raw;
+    dividends::settle(store, api, env, owner)?;
+    let shares = rebase::to_shares_ceil(store, amount)?;
+    let account_shares = read_shares(store, api, owner)?;
+    let total_shares = checked_sub(rebase::read_total_shares(store)?, shares)?;
+    let total_supply = checked_sub(read_total_supply(store)?, amount)?;
 
-    let mut balances_store = PrefixedStorage::new(deps.storage, PREFIX_BALANCES);
-    balances_store.set(
-        info.sender.as_str().as_bytes(),
-        &account_balance.to_be_bytes(),
-    );
+    write_shares(
+        store,
+        api,
+        env,
+        owner,
+        account_shares,
+        checked_sub(account_shares, shares)?,
+    )?;
+    rebase::write_total_shares(store, total_shares);
+    let mut config_store = PrefixedStorage::new(store, PREFIX_CONFIG);
+    config_store.set(KEY_TOTAL_SUPPLY, &total_supply.to_be_bytes());
 
-    let mut config_store = PrefixedStorage::new(deps.storage, PREFIX_CONFIG);
-    let data = config_store
-        .get(KEY_TOTAL_SUPPLY)
-        .expect("no total supply data stored");
-    let mut total_supply = bytes_to_u128(&data).unwrap();
+    votes::write_supply_checkpoint(store, env.block.height, total_supply)?;
+    let delegate = votes::read_delegate(store, owner)?;
+    votes::move_voting_power(store, env.block.height, delegate.as_ref(), None, shares)?;
+    add_u128(store, api, PREFIX_BURNED, owner, amount)?;
+    report::record_burn(store, env, owner, amount)
+}
 
-    total_supply -= amount_raw;
+// Adds `amount` newly created tokens to the balance of `owner` and the total supply
+pub fn mint_balance(
+    store: &mut dyn Storage,
+    api: &dyn Api,
+    env: &Env,
+    owner: &Addr,
+    amount: u128,
+) -> Result<(), ContractError> {
+    dividends::settle(store, api, env, owner)?;
+    let shares = rebase::to_shares(store, amount)?;
+    if amount > 0 && shares == 0 {
+        return Err(ContractError::BelowOneShare { amount });
+    }
+    let account_shares = read_shares(store, api, owner)?;
+    let total_shares = checked_add(rebase::read_total_shares(store)?, shares)?;
+    let total_supply = checked_add(read_total_supply(store)?, amount)?;
+    cap::assert_within_cap(store, total_supply)?;
 
+    write_shares(
+        store,
+        api,
+        env,
+        owner,
+        account_shares,
+        checked_add(account_shares, shares)?,
+    )?;
+    rebase::write_total_shares(store, total_shares);
+    let mut config_store = PrefixedStorage::new(store, PREFIX_CONFIG);
     config_store.set(KEY_TOTAL_SUPPLY, &total_supply.to_be_bytes());
 
-    Ok(Response::new()
-        .add_attribute("action", "burn")
-        .add_attribute("account", info.sender)
-        .add_attribute("amount", amount.to_string()))
+    votes::write_supply_checkpoint(store, env.block.height, total_supply)?;
+    let delegate = votes::read_delegate(store, owner)?;
+    votes::move_voting_power(store, env.block.height, None, delegate.as_ref(), shares)?;
+    Ok(())
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TransferOutcome {
+    /// `fee` went to the treasury and `burned` was destroyed, see `deflation`
+    Completed { fee: u128, burned: u128 },
+    /// Waiting for its timelock, see `timelock`
+    Queued { id: u64 },
+}
+
+/// How a transfer relates to the escrows on the contract's own account
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum TransferKind {
+    /// Between two accounts. Tokens sent to the contract this way are not escrowed.
+    Direct,
+    /// Into an escrow, not charged
+    EscrowDeposit,
+    /// Out of an escrow, charged like a direct transfer
+    EscrowPayout,
+}
+
+impl TransferOutcome {
+    /// Part of the amount the recipient did not receive (yet)
+    pub fn withheld(&self, amount: u128) -> Result<u128, ContractError> {
+        match self {
+            TransferOutcome::Completed { fee, burned } => checked_add(*fee, *burned),
+            TransferOutcome::Queued { .. } => Ok(amount),
+        }
+    }
+}
+
+// Checks of Transfer, TransferFrom and Send that come before the transfer itself,
+// shared with SimulateTransfer so a simulation fails where the real call would
+pub fn assert_transfer_allowed(
+    store: &dyn Storage,
+    owner: &Addr,
+    amount: u128,
+) -> Result<(), ContractError> {
+    limits::assert_zero_transfer_allowed(store, amount)?;
+    largetransfer::assert_not_large(store, owner, amount)
+}
+
+// Moves `amount` from `from` to `to` like `complete_transfer`, unless the transfer
+// is large enough to be queued behind the timelock. Rejects transfers the
+// compliance contract does not allow.
+pub fn perform_transfer(
+    store: &mut dyn Storage,
+    api: &dyn Api,
+    querier: &QuerierWrapper,
+    env: &Env,
+    from: &Addr,
+    to: &Addr,
+    amount: u128,
+) -> Result<TransferOutcome, ContractError> {
+    limits::assert_min_transfer(store, amount)?;
+    compliance::assert_compliant(store, querier, from, to, amount)?;
+    if let Some(id) = timelock::queue_if_timelocked(store, api, env, from, to, amount)? {
+        return Ok(TransferOutcome::Queued { id });
+    }
+    complete_transfer(store, api, env, from, to, amount, TransferKind::Direct)
+}
+
+// Moves `amount` from `from` into escrow on the contract's own account. Deposits
+// are not charged, so the escrow holds exactly what it records; the fee is taken
+// when the tokens leave escrow again, see `escrow_payout`.
+pub fn escrow_deposit(
+    store: &mut dyn Storage,
+    api: &dyn Api,
+    querier: &QuerierWrapper,
+    env: &Env,
+    from: &Addr,
+    amount: u128,
+) -> Result<TransferOutcome, ContractError> {
+    let escrow = &env.contract.address;
+    limits::assert_min_transfer(store, amount)?;
+    compliance::assert_compliant(store, querier, from, escrow, amount)?;
+    complete_transfer(
+        store,
+        api,
+        env,
+        from,
+        escrow,
+        amount,
+        TransferKind::EscrowDeposit,
+    )
+}
+
+// Pays `amount` out of escrow to `to`, charged like a direct transfer
+pub fn escrow_payout(
+    store: &mut dyn Storage,
+    api: &dyn Api,
+    querier: &QuerierWrapper,
+    env: &Env,
+    to: &Addr,
+    amount: u128,
+) -> Result<TransferOutcome, ContractError> {
+    let escrow = &env.contract.address;
+    compliance::assert_compliant(store, querier, escrow, to, amount)?;
+    complete_transfer(
+        store,
+        api,
+        env,
+        escrow,
+        to,
+        amount,
+        TransferKind::EscrowPayout,
+    )
+}
+
+// Moves `amount` from `from` to `to`, less the transfer fee which goes to the treasury
+// and the transfer burn which is destroyed. Returns what was charged.
+pub fn complete_transfer(
+    store: &mut dyn Storage,
+    api: &dyn Api,
+    env: &Env,
+    from: &Addr,
+    to: &Addr,
+    amount: u128,
+    kind: TransferKind,
+) -> Result<TransferOutcome, ContractError> {
+    pause::assert_not_paused(store)?;
+    freeze::assert_not_frozen(store, env, from)?;
+    locks::release_matured(store, api, env, from)?;
+    locks::release_matured(store, api, env, to)?;
+
+    assert_balance_spendable(store, api, env, from, amount)?;
+    // A transfer to oneself moves nothing, it only has to be affordable
+    if from == to {
+        return Ok(TransferOutcome::Completed { fee: 0, burned: 0 });
+    }
+    settle_transfer(store, api, env, from, to, amount, kind)
+}
+
+// Moves the balances of a transfer that passed the checks of `complete_transfer`.
+// Matured locks are paid out of escrow through here directly.
+pub fn settle_transfer(
+    store: &mut dyn Storage,
+    api: &dyn Api,
+    env: &Env,
+    from: &Addr,
+    to: &Addr,
+    amount: u128,
+    kind: TransferKind,
+) -> Result<TransferOutcome, ContractError> {
+    let (fee, burned) = match kind {
+        TransferKind::EscrowDeposit => (None, 0),
+        TransferKind::Direct | TransferKind::EscrowPayout => (
+            fees::compute_fee(store, env, from, to, amount)?,
+            deflation::compute_burn(store, from, to, amount)?,
+        ),
+    };
+    let withheld = checked_add(fee.as_ref().map_or(0, |(fee, _)| *fee), burned)?;
+    let received = checked_sub(amount, withheld)?;
+    if received > 0 && rebase::to_shares(store, received)? == 0 {
+        return Err(ContractError::BelowOneShare { amount: received });
+    }
+    match kind {
+        TransferKind::Direct => {
+            limits::check_transfer(store, api, env, from, to, amount, received)?
+        }
+        TransferKind::EscrowDeposit => limits::assert_max_tx(store, from, to, amount)?,
+        TransferKind::EscrowPayout => {
+            limits::assert_max_wallet(store, api, env, from, to, received)?
+        }
+    }
+    ratelimit::record_transfer(store, env, from, amount)?;
+    if kind != TransferKind::EscrowDeposit {
+        fees::record_volume(store, env, amount)?;
+    }
+
+    move_balance(store, api, env, from, to, received)?;
+    let fee = match fee {
+        Some((fee, treasury)) => {
+            move_balance(store, api, env, from, &treasury, fee)?;
+            // Fees routed to the contract itself are there to be bought back
+            if treasury == env.contract.address {
+                buyback::record_deposit(store, fee)?;
+            }
+            fee
+        }
+        None => 0,
+    };
+    if burned > 0 {
+        burn_balance(store, api, env, from, burned)?;
+    }
+    // Tokens sent to the contract outside of an escrow are collected or stuck
+    if kind == TransferKind::Direct && *to == env.contract.address {
+        buyback::record_deposit(store, received)?;
+    }
+    report::record_transfer(store, env, from, amount, fee)?;
+    Ok(TransferOutcome::Completed { fee, burned })
+}
+
+// Checks that `owner` holds `amount` outside of its lockups
+pub fn assert_balance_spendable(
+    store: &dyn Storage,
+    api: &dyn Api,
+    env: &Env,
+    owner: &Addr,
+    amount: u128,
+) -> Result<(), ContractError> {
+    let balance = read_balance(store, api, owner)?;
+    if balance < amount {
+        return Err(ContractError::InsufficientFunds {
+            balance,
+            required: amount,
+        });
+    }
+    lockup::assert_spendable(store, env, owner, balance, amount)
 }
 
-fn perform_transfer(
+pub fn move_balance(
     store: &mut dyn Storage,
+    api: &dyn Api,
+    env: &Env,
     from: &Addr,
     to: &Addr,
     amount: u128,
+) -> Result<(), ContractError> {
+    // The sender pays the rounded up share count and the recipient gets the
+    // rounded down one, the dust is retired from the total shares
+    let shares = rebase::to_shares_ceil(store, amount)?;
+    let credited = rebase::to_shares(store, amount)?;
+    let dust = checked_sub(shares, credited)?;
+    let from_shares = read_shares(store, api, from)?;
+    if from_shares < shares {
+        return Err(ContractError::InsufficientFunds {
+            balance: rebase::to_amount(store, from_shares)?,
+            required: amount,
+        });
+    }
+    // Writing both sides would let the second write clobber the first, e.g. for
+    // a force transfer to the same account
+    if from == to {
+        return Ok(());
+    }
+    let to_shares = read_shares(store, api, to)?;
+    dividends::settle(store, api, env, from)?;
+    dividends::settle(store, api, env, to)?;
+
+    write_shares(
+        store,
+        api,
+        env,
+        from,
+        from_shares,
+        checked_sub(from_shares, shares)?,
+    )?;
+    write_shares(
+        store,
+        api,
+        env,
+        to,
+        to_shares,
+        checked_add(to_shares, credited)?,
+    )?;
+    if dust > 0 {
+        let total_shares = rebase::read_total_shares(store)?;
+        rebase::write_total_shares(store, checked_sub(total_shares, dust)?);
+        let total_supply = read_total_supply(store)?;
+        votes::write_supply_checkpoint(store, env.block.height, total_supply)?;
+    }
+
+    // Lifetime statistics
+    add_u128(store, api, PREFIX_SENT, from, amount)?;
+    add_u128(store, api, PREFIX_RECEIVED, to, amount)?;
+
+    // Voting power follows the balances of delegators
+    let from_delegate = votes::read_delegate(store, from)?;
+    let to_delegate = votes::read_delegate(store, to)?;
+    votes::move_voting_power(
+        store,
+        env.block.height,
+        from_delegate.as_ref(),
+        to_delegate.as_ref(),
+        credited,
+    )?;
+    votes::move_voting_power(store, env.block.height, from_delegate.as_ref(), None, dust)?;
+
+    Ok(())
+}
+
+// Stores the shares of `owner`, keeping the holder count and index in step
+pub fn write_shares(
+    store: &mut dyn Storage,
+    api: &dyn Api,
+    env: &Env,
+    owner: &Addr,
+    old_shares: u128,
+    new_shares: u128,
 ) -> Result<(), ContractError> {
     let mut balances_store = PrefixedStorage::new(store, PREFIX_BALANCES);
+    balances_store.set(
+        api.addr_canonicalize(owner.as_str())?.as_slice(),
+        &new_shares.to_be_bytes(),
+    );
+    holders::update_holders(store, env, owner, old_shares, new_shares)
+}
 
-    let mut from_balance = match balances_store.get(from.as_str().as_bytes()) {
+// Adds `amount` to the 16 byte counter stored under `key` in the given prefix
+fn add_u128(
+    store: &mut dyn Storage,
+    api: &dyn Api,
+    prefix: &[u8],
+    key: &Addr,
+    amount: u128,
+) -> Result<(), ContractError> {
+    let key = api.addr_canonicalize(key.as_str())?;
+    let mut counter_store = PrefixedStorage::new(store, prefix);
+    let current = match counter_store.get(key.as_slice()) {
         Some(data) => bytes_to_u128(&data),
         None => Ok(0u128),
     }?;
+    counter_store.set(key.as_slice(), &checked_add(current, amount)?.to_be_bytes());
+    Ok(())
+}
 
-    if from_balance < amount {
-        return Err(ContractError::InsufficientFunds {
-            balance: from_balance,
-            required: amount,
-        });
+// u128 arithmetic on balances, supplies and counters, surfacing overflows as
+// errors rather than panics
+pub fn checked_add(a: u128, b: u128) -> Result<u128, ContractError> {
+    a.checked_add(b).ok_or(ContractError::Overflow {})
+}
+
+pub fn checked_sub(a: u128, b: u128) -> Result<u128, ContractError> {
+    a.checked_sub(b).ok_or(ContractError::Underflow {})
+}
+
+pub fn checked_mul(a: u128, b: u128) -> Result<u128, ContractError> {
+    a.checked_mul(b).ok_or(ContractError::Overflow {})
+}
+
+// Converts 16 bytes value into u128
+// Errors if data found that is not 16 bytes
+pub fn bytes_to_u128(data: &[u8]) -> Result<u128, ContractError> {
+    match data[0..16].try_into() {
+        Ok(bytes) => Ok(u128::from_be_bytes(bytes)),
+        Err(_) => Err(ContractError::CorruptedDataFound {}),
     }
-    from_balance -= amount;
-    balances_store.set(from.as_str().as_bytes(), &from_balance.to_be_bytes());
+}
 
-    let mut to_balance = match balances_store.get(to.as_str().as_bytes()) {
+// Reads 16 byte storage value into u128
+// Returns zero if key does not exist. Errors if data found that is not 16 bytes
+pub fn read_u128(
+    store: &ReadonlyPrefixedStorage,
+    key: &CanonicalAddr,
+) -> Result<u128, ContractError> {
+    let result = store.get(key.as_slice());
+    match result {
         Some(data) => bytes_to_u128(&data),
         None => Ok(0u128),
-    }?;
-    to_balance += amount;
-    balances_store.set(to.as_str().as_bytes(), &to_balance.to_be_bytes());
+    }
+}
 
-    Ok(())
+// Reads an address stored as UTF-8 bytes
+// Returns None if key does not exist
+pub fn read_addr(
+    store: &ReadonlyPrefixedStorage,
+    key: &[u8],
+) -> Result<Option<Addr>, ContractError> {
+    match store.get(key) {
+        Some(data) => match String::from_utf8(data) {
+            Ok(address) => Ok(Some(Addr::unchecked(address))),
+            Err(err) => Err(StdError::invalid_utf8(err.to_string()).into()),
+        },
+        None => Ok(None),
+    }
+}
+
+pub fn read_owner(store: &dyn Storage) -> Result<Option<Addr>, ContractError> {
+    let config_store = ReadonlyPrefixedStorage::new(store, PREFIX_CONFIG);
+    read_addr(&config_store, KEY_OWNER)
+}
+
+// Errors with Unauthorized unless `sender` is the contract owner
+pub fn assert_owner(store: &dyn Storage, sender: &Addr) -> Result<(), ContractError> {
+    match read_owner(store)? {
+        Some(owner) if owner == *sender => Ok(()),
+        _ => Err(ContractError::Unauthorized {}),
+    }
 }
 
-// Converts 16 bytes value into u128
-// Errors if data found that is not 16 bytes
-pub fn bytes_to_u128(data: &[u8]) -> Result<u128, ContractError> {
-    match data[0..16].try_into() {
-        Ok(bytes) => Ok(u128::from_be_bytes(bytes)),
-        Err(_) => Err(ContractError::CorruptedDataFound {}),
+pub fn read_constants(store: &dyn Storage) -> Result<Constants, ContractError> {
+    let config_store = ReadonlyPrefixedStorage::new(store, PREFIX_CONFIG);
+    match config_store.get(KEY_CONSTANTS) {
+        Some(data) => Ok(from_slice(&data)?),
+        None => Err(ContractError::CorruptedDataFound {}),
     }
 }
 
-// Reads 16 byte storage value into u128
-// Returns zero if key does not exist. Errors if data found that is not 16 bytes
-pub fn read_u128(store: &ReadonlyPrefixedStorage, key: &Addr) -> Result<u128, ContractError> {
-    let result = store.get(key.as_str().as_bytes());
-    match result {
+pub fn read_total_supply(store: &dyn Storage) -> Result<u128, ContractError> {
+    let config_store = ReadonlyPrefixedStorage::new(store, PREFIX_CONFIG);
+    match config_store.get(KEY_TOTAL_SUPPLY) {
         Some(data) => bytes_to_u128(&data),
         None => Ok(0u128),
     }
 }
 
-fn read_balance(store: &dyn Storage, owner: &Addr) -> Result<u128, ContractError> {
+// Balance as reported by queries. Matured locks count as liquid even before
+// they are released.
+fn query_balance(deps: Deps, env: &Env, owner: &Addr) -> Result<u128, ContractError> {
+    checked_add(
+        read_balance(deps.storage, deps.api, owner)?,
+        locks::read_matured(deps.storage, env, owner)?,
+    )
+}
+
+// Response data of executions that change the balance of `owner`, so calling
+// contracts can read the new balance in their reply handler
+pub fn balance_data(
+    store: &dyn Storage,
+    api: &dyn Api,
+    owner: &Addr,
+) -> Result<Binary, ContractError> {
+    Ok(to_binary(&BalanceResponse {
+        balance: Uint128::from(read_balance(store, api, owner)?),
+    })?)
+}
+
+// Response data of executions that set an allowance
+pub fn allowance_data(allowance: u128) -> Result<Binary, ContractError> {
+    Ok(to_binary(&AllowanceResponse {
+        allowance: Uint128::from(allowance),
+        expires: Expiration::Never {},
+    })?)
+}
+
+// Balances are stored as shares of the total supply, see `rebase`
+pub fn read_balance(
+    store: &dyn Storage,
+    api: &dyn Api,
+    owner: &Addr,
+) -> Result<u128, ContractError> {
+    rebase::to_amount(store, read_shares(store, api, owner)?)
+}
+
+// Balances and allowances are keyed by canonical addresses
+pub fn read_shares(
+    store: &dyn Storage,
+    api: &dyn Api,
+    owner: &Addr,
+) -> Result<u128, ContractError> {
     let balance_store = ReadonlyPrefixedStorage::new(store, PREFIX_BALANCES);
-    read_u128(&balance_store, owner)
+    match balance_store.get(api.addr_canonicalize(owner.as_str())?.as_slice()) {
+        Some(data) => bytes_to_u128(&data),
+        None => Ok(0u128),
+    }
 }
 
+// Expired allowances read as zero, their stored amount is left in place
 fn read_allowance(
     store: &dyn Storage,
+    api: &dyn Api,
+    env: &Env,
     owner: &Addr,
     spender: &Addr,
 ) -> Result<u128, ContractError> {
+    if let Some(expires) = read_allowance_expiration(store, api, owner, spender)? {
+        if expires.is_expired(&env.block) {
+            return Ok(0);
+        }
+    }
+    let owner_key = api.addr_canonicalize(owner.as_str())?;
     let owner_store =
-        ReadonlyPrefixedStorage::multilevel(store, &[PREFIX_ALLOWANCES, owner.as_str().as_bytes()]);
-    read_u128(&owner_store, spender)
+        ReadonlyPrefixedStorage::multilevel(store, &[PREFIX_ALLOWANCES, owner_key.as_slice()]);
+    match owner_store.get(api.addr_canonicalize(spender.as_str())?.as_slice()) {
+        Some(data) => bytes_to_u128(&data),
+        None => Ok(0u128),
+    }
+}
+
+/// Allowance of `spender` over the tokens of `owner` at the end of block `height`.
+/// Allowances last written before checkpoints were kept read as zero.
+pub fn read_allowance_at(
+    store: &dyn Storage,
+    api: &dyn Api,
+    owner: &Addr,
+    spender: &Addr,
+    height: u64,
+) -> Result<u128, ContractError> {
+    let owner = api.addr_canonicalize(owner.as_str())?;
+    let spender = api.addr_canonicalize(spender.as_str())?;
+    votes::read_checkpoint(
+        store,
+        &allowance_checkpoints(&owner, &spender),
+        Some(height),
+    )
 }
 
-#[allow(clippy::unnecessary_wraps)]
-fn write_allowance(
+// Stores the allowance and checkpoints it at the current height, like voting power
+pub fn write_allowance(
     store: &mut dyn Storage,
+    api: &dyn Api,
+    env: &Env,
     owner: &Addr,
     spender: &Addr,
     amount: u128,
 ) -> StdResult<()> {
+    let owner_key = api.addr_canonicalize(owner.as_str())?;
+    let spender_key = api.addr_canonicalize(spender.as_str())?;
     let mut owner_store =
-        PrefixedStorage::multilevel(store, &[PREFIX_ALLOWANCES, owner.as_str().as_bytes()]);
-    owner_store.set(spender.as_str().as_bytes(), &amount.to_be_bytes());
+        PrefixedStorage::multilevel(store, &[PREFIX_ALLOWANCES, owner_key.as_slice()]);
+    owner_store.set(spender_key.as_slice(), &amount.to_be_bytes());
+    votes::write_checkpoint(
+        store,
+        &allowance_checkpoints(&owner_key, &spender_key),
+        env.block.height,
+        amount,
+    );
+    Ok(())
+}
+
+pub fn read_allowance_expiration(
+    store: &dyn Storage,
+    api: &dyn Api,
+    owner: &Addr,
+    spender: &Addr,
+) -> Result<Option<Expiration>, ContractError> {
+    let owner_key = api.addr_canonicalize(owner.as_str())?;
+    let expirations_store = ReadonlyPrefixedStorage::multilevel(
+        store,
+        &[PREFIX_ALLOWANCE_EXPIRATIONS, owner_key.as_slice()],
+    );
+    match expirations_store.get(api.addr_canonicalize(spender.as_str())?.as_slice()) {
+        Some(data) => Ok(Some(from_slice(&data)?)),
+        None => Ok(None),
+    }
+}
+
+// Plain approvals and permits clear the expiration, spends keep it
+pub fn write_allowance_expiration(
+    store: &mut dyn Storage,
+    api: &dyn Api,
+    owner: &Addr,
+    spender: &Addr,
+    expires: Option<Expiration>,
+) -> Result<(), ContractError> {
+    let owner_key = api.addr_canonicalize(owner.as_str())?;
+    let spender_key = api.addr_canonicalize(spender.as_str())?;
+    let mut expirations_store =
+        PrefixedStorage::multilevel(store, &[PREFIX_ALLOWANCE_EXPIRATIONS, owner_key.as_slice()]);
+    match expires {
+        Some(expires) => expirations_store.set(spender_key.as_slice(), &to_vec(&expires)?),
+        None => expirations_store.remove(spender_key.as_slice()),
+    }
     Ok(())
 }
 
-fn is_valid_name(name: &str) -> bool {
+/// Allowances granted by `owner` ordered by the canonical address of the
+/// spender, expired ones reading as zero
+pub fn read_all_allowances(
+    store: &dyn Storage,
+    api: &dyn Api,
+    env: &Env,
+    owner: &Addr,
+    start_after: Option<Addr>,
+    limit: Option<u32>,
+) -> Result<Vec<AllowanceInfo>, ContractError> {
+    let start_after = match start_after {
+        Some(spender) => Some(api.addr_canonicalize(spender.as_str())?.to_vec()),
+        None => None,
+    };
+    let page = Page::after_key(start_after, limit, &LIST_LIMITS, Order::Ascending);
+    let owner_key = api.addr_canonicalize(owner.as_str())?;
+    let owner_store =
+        ReadonlyPrefixedStorage::multilevel(store, &[PREFIX_ALLOWANCES, owner_key.as_slice()]);
+    owner_store
+        .range(page.start(), page.end(), page.order)
+        .take(page.limit)
+        .map(|(key, _)| {
+            let spender = addr_from_canonical_key(api, key)?;
+            Ok(AllowanceInfo {
+                allowance: Uint128::from(read_allowance(store, api, env, owner, &spender)?),
+                expires: read_allowance_expiration(store, api, owner, &spender)?
+                    .unwrap_or_default(),
+                spender: spender.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Accounts with a stored balance ordered by canonical address, emptied ones
+/// included
+pub fn read_all_accounts(
+    store: &dyn Storage,
+    api: &dyn Api,
+    start_after: Option<Addr>,
+    limit: Option<u32>,
+) -> Result<Vec<String>, ContractError> {
+    let start_after = match start_after {
+        Some(address) => Some(api.addr_canonicalize(address.as_str())?.to_vec()),
+        None => None,
+    };
+    let page = Page::after_key(start_after, limit, &LIST_LIMITS, Order::Ascending);
+    let balances_store = ReadonlyPrefixedStorage::new(store, PREFIX_BALANCES);
+    balances_store
+        .range(page.start(), page.end(), page.order)
+        .take(page.limit)
+        .map(|(key, _)| Ok(addr_from_canonical_key(api, key)?.to_string()))
+        .collect()
+}
+
+fn allowance_checkpoints<'a>(
+    owner: &'a CanonicalAddr,
+    spender: &'a CanonicalAddr,
+) -> [&'a [u8]; 3] {
+    [
+        PREFIX_ALLOWANCE_CHECKPOINTS,
+        owner.as_slice(),
+        spender.as_slice(),
+    ]
+}
+
+// Reads an address used as a storage key
+pub fn addr_from_key(key: Vec<u8>) -> Result<Addr, ContractError> {
+    let address = String::from_utf8(key).map_err(|_| ContractError::CorruptedDataFound {})?;
+    Ok(Addr::unchecked(address))
+}
+
+// Reads a canonical address used as a storage key
+pub fn addr_from_canonical_key(api: &dyn Api, key: Vec<u8>) -> Result<Addr, ContractError> {
+    Ok(api.addr_humanize(&CanonicalAddr::from(key))?)
+}
+
+// Allowances are stored under the length prefixed owner followed by the
+// spender
+pub fn split_length_prefixed(key: &[u8]) -> Result<(&[u8], &[u8]), ContractError> {
+    if key.len() < 2 {
+        return Err(ContractError::CorruptedDataFound {});
+    }
+    let (len, rest) = key.split_at(2);
+    let len = match len.try_into() {
+        Ok(bytes) => u16::from_be_bytes(bytes) as usize,
+        Err(_) => return Err(ContractError::CorruptedDataFound {}),
+    };
+    if rest.len() < len {
+        return Err(ContractError::CorruptedDataFound {});
+    }
+    Ok(rest.split_at(len))
+}
+
+pub fn split_allowance_key(api: &dyn Api, key: &[u8]) -> Result<(Addr, Addr), ContractError> {
+    let (owner, spender) = split_length_prefixed(key)?;
+    Ok((
+        addr_from_canonical_key(api, owner.to_vec())?,
+        addr_from_canonical_key(api, spender.to_vec())?,
+    ))
+}
+
+pub fn validate_memo(memo: &Option<String>) -> Result<(), ContractError> {
+    match memo {
+        Some(memo) if memo.len() > MAX_MEMO_BYTES => Err(ContractError::MemoTooLong {
+            max: MAX_MEMO_BYTES,
+        }),
+        _ => Ok(()),
+    }
+}
+
+pub fn add_fee(res: Response, fee: u128) -> Response {
+    if fee == 0 {
+        return res;
+    }
+    res.add_attribute("fee_amount", fee.to_string())
+}
+
+// Reports the fee of a completed transfer or the id of a queued one
+pub fn add_outcome(res: Response, outcome: TransferOutcome) -> Response {
+    match outcome {
+        TransferOutcome::Completed { fee, burned } => {
+            let res = add_fee(res, fee);
+            if burned == 0 {
+                return res;
+            }
+            res.add_attribute("burned_amount", burned.to_string())
+        }
+        TransferOutcome::Queued { id } => {
+            res.add_attribute("timelocked_transfer_id", id.to_string())
+        }
+    }
+}
+
+// Memos are emitted as an attribute so indexers can credit deposits
+pub fn add_memo(res: Response, memo: Option<String>) -> Response {
+    match memo {
+        Some(memo) => res.add_attribute("memo", memo),
+        None => res,
+    }
+}
+
+pub fn is_valid_name(name: &str) -> bool {
     let bytes = name.as_bytes();
     if bytes.len() < 3 || bytes.len() > 30 {
         return false;
@@ -297,7 +2417,7 @@ fn is_valid_name(name: &str) -> bool {
     true
 }
 
-fn is_valid_symbol(symbol: &str) -> bool {
+pub fn is_valid_symbol(symbol: &str) -> bool {
     let bytes = symbol.as_bytes();
     if bytes.len() < 3 || bytes.len() > 6 {
         return false;
@@ -314,7 +2434,7 @@ fn is_valid_symbol(symbol: &str) -> bool {
 mod tests {
     use super::*;
     use crate::msg::InitialBalance;
-    use cosmwasm_std::testing::{mock_dependencies_with_balance, mock_env, mock_info};
+    use cosmwasm_std::testing::{mock_dependencies_with_balance, mock_env, mock_info, MockApi};
     use cosmwasm_std::{from_slice, Addr, Env, MessageInfo, Storage, Timestamp, Uint128};
     use cosmwasm_storage::ReadonlyPrefixedStorage;
 
@@ -343,34 +2463,39 @@ mod tests {
     }
 
     fn get_balance(storage: &dyn Storage, address: &Addr) -> u128 {
+        let address = MockApi::default()
+            .addr_canonicalize(address.as_str())
+            .unwrap();
         let balances_storage = ReadonlyPrefixedStorage::new(storage, PREFIX_BALANCES);
-        return read_u128(&balances_storage, address).unwrap();
+        let data = balances_storage.get(address.as_slice());
+        return data.map_or(0, |data| bytes_to_u128(&data).unwrap());
     }
 
     fn get_allowance(storage: &dyn Storage, owner: &Addr, spender: &Addr) -> u128 {
-        let owner_storage = ReadonlyPrefixedStorage::multilevel(
-            storage,
-            &[PREFIX_ALLOWANCES, owner.as_str().as_bytes()],
-        );
-        return read_u128(&owner_storage, spender).unwrap();
+        let api = MockApi::default();
+        let owner = api.addr_canonicalize(owner.as_str()).unwrap();
+        let spender = api.addr_canonicalize(spender.as_str()).unwrap();
+        let owner_storage =
+            ReadonlyPrefixedStorage::multilevel(storage, &[PREFIX_ALLOWANCES, owner.as_slice()]);
+        let data = owner_storage.get(spender.as_slice());
+        return data.map_or(0, |data| bytes_to_u128(&data).unwrap());
     }
 
     mod instantiate {
         use super::*;
         use crate::error::ContractError;
+        use crate::testing::default_instantiate_msg;
 
         #[test]
         fn works() {
             let mut deps = mock_dependencies_with_balance(&[]);
             let instantiate_msg = InstantiateMsg {
-                name: "Cash Token".to_string(),
-                symbol: "CASH".to_string(),
-                decimals: 9,
                 initial_balances: [InitialBalance {
                     address: "addr0000".to_string(),
                     amount: Uint128::from(4000u128),
                 }]
                 .to_vec(),
+                ..default_instantiate_msg()
             };
             let (env, info) = mock_env_height("creator", 450, 550);
             let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
@@ -390,14 +2515,64 @@ mod tests {
             assert_eq!(get_total_supply(&deps.storage), 4000);
         }
 
+        #[test]
+        fn fails_for_duplicate_initial_balance() {
+            let mut deps = mock_dependencies_with_balance(&[]);
+            let instantiate_msg = InstantiateMsg {
+                initial_balances: vec![
+                    InitialBalance {
+                        address: "addr0000".to_string(),
+                        amount: Uint128::from(11u128),
+                    },
+                    InitialBalance {
+                        address: "addr0000".to_string(),
+                        amount: Uint128::from(22u128),
+                    },
+                ],
+                ..default_instantiate_msg()
+            };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            let result = instantiate(deps.as_mut(), env, info, instantiate_msg);
+            match result {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::DuplicateInitialBalance { address }) => {
+                    assert_eq!(address, "addr0000")
+                }
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+        }
+
+        #[test]
+        fn fails_for_initial_supply_overflow() {
+            let mut deps = mock_dependencies_with_balance(&[]);
+            let instantiate_msg = InstantiateMsg {
+                initial_balances: vec![
+                    InitialBalance {
+                        address: "addr0000".to_string(),
+                        amount: Uint128::MAX,
+                    },
+                    InitialBalance {
+                        address: "addr1111".to_string(),
+                        amount: Uint128::from(1u128),
+                    },
+                ],
+                ..default_instantiate_msg()
+            };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            let result = instantiate(deps.as_mut(), env, info, instantiate_msg);
+            match result {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::InitialSupplyOverflow {}) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+        }
+
         #[test]
         fn works_with_empty_balance() {
             let mut deps = mock_dependencies_with_balance(&[]);
             let instantiate_msg = InstantiateMsg {
-                name: "Cash Token".to_string(),
-                symbol: "CASH".to_string(),
-                decimals: 9,
-                initial_balances: [].to_vec(),
+                initial_balances: vec![],
+                ..default_instantiate_msg()
             };
             let (env, info) = mock_env_height("creator", 450, 550);
             let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
@@ -409,9 +2584,6 @@ mod tests {
         fn works_with_multiple_balances() {
             let mut deps = mock_dependencies_with_balance(&[]);
             let instantiate_msg = InstantiateMsg {
-                name: "Cash Token".to_string(),
-                symbol: "CASH".to_string(),
-                decimals: 9,
                 initial_balances: [
                     InitialBalance {
                         address: "addr0000".to_string(),
@@ -427,6 +2599,7 @@ mod tests {
                     },
                 ]
                 .to_vec(),
+                ..default_instantiate_msg()
             };
             let (env, info) = mock_env_height("creator", 450, 550);
             let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
@@ -454,14 +2627,12 @@ mod tests {
             //   echo '{ "value": 9007199254740993 }' | jq
             // return 9007199254740992
             let instantiate_msg = InstantiateMsg {
-                name: "Cash Token".to_string(),
-                symbol: "CASH".to_string(),
-                decimals: 9,
                 initial_balances: [InitialBalance {
                     address: "addr0000".to_string(),
                     amount: Uint128::from(9007199254740993u128),
                 }]
                 .to_vec(),
+                ..default_instantiate_msg()
             };
             let (env, info) = mock_env_height("creator", 450, 550);
             let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
@@ -478,14 +2649,12 @@ mod tests {
         fn works_with_balance_larger_than_64_bit() {
             let mut deps = mock_dependencies_with_balance(&[]);
             let instantiate_msg = InstantiateMsg {
-                name: "Cash Token".to_string(),
-                symbol: "CASH".to_string(),
-                decimals: 9,
                 initial_balances: [InitialBalance {
                     address: "addr0000".to_string(),
                     amount: Uint128::from(100000000000000000000000000u128),
                 }]
                 .to_vec(),
+                ..default_instantiate_msg()
             };
             let (env, info) = mock_env_height("creator", 450, 550);
             let res = instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
@@ -501,10 +2670,8 @@ mod tests {
         fn fails_for_large_decimals() {
             let mut deps = mock_dependencies_with_balance(&[]);
             let instantiate_msg = InstantiateMsg {
-                name: "Cash Token".to_string(),
-                symbol: "CASH".to_string(),
                 decimals: 42,
-                initial_balances: [].to_vec(),
+                ..default_instantiate_msg()
             };
             let (env, info) = mock_env_height("creator", 450, 550);
             let result = instantiate(deps.as_mut(), env, info, instantiate_msg);
@@ -520,9 +2687,7 @@ mod tests {
             let mut deps = mock_dependencies_with_balance(&[]);
             let instantiate_msg = InstantiateMsg {
                 name: "CC".to_string(),
-                symbol: "CASH".to_string(),
-                decimals: 9,
-                initial_balances: [].to_vec(),
+                ..default_instantiate_msg()
             };
             let (env, info) = mock_env_height("creator", 450, 550);
             let result = instantiate(deps.as_mut(), env, info, instantiate_msg);
@@ -538,9 +2703,7 @@ mod tests {
             let mut deps = mock_dependencies_with_balance(&[]);
             let instantiate_msg = InstantiateMsg {
                 name: "Cash coin. Cash coin. Cash coin. Cash coin.".to_string(),
-                symbol: "CASH".to_string(),
-                decimals: 9,
-                initial_balances: [].to_vec(),
+                ..default_instantiate_msg()
             };
             let (env, info) = mock_env_height("creator", 450, 550);
             let result = instantiate(deps.as_mut(), env, info, instantiate_msg);
@@ -557,8 +2720,7 @@ mod tests {
             let instantiate_msg = InstantiateMsg {
                 name: "De De".to_string(),
                 symbol: "DD".to_string(),
-                decimals: 9,
-                initial_balances: [].to_vec(),
+                ..default_instantiate_msg()
             };
             let (env, info) = mock_env_height("creator", 450, 550);
             let result = instantiate(deps.as_mut(), env, info, instantiate_msg);
@@ -575,8 +2737,7 @@ mod tests {
             let instantiate_msg = InstantiateMsg {
                 name: "Super Coin".to_string(),
                 symbol: "SUPERCOIN".to_string(),
-                decimals: 9,
-                initial_balances: [].to_vec(),
+                ..default_instantiate_msg()
             };
             let (env, info) = mock_env_height("creator", 450, 550);
             let result = instantiate(deps.as_mut(), env, info, instantiate_msg);
@@ -591,10 +2752,8 @@ mod tests {
         fn fails_for_symbol_lowercase() {
             let mut deps = mock_dependencies_with_balance(&[]);
             let instantiate_msg = InstantiateMsg {
-                name: "Cash Token".to_string(),
                 symbol: "CaSH".to_string(),
-                decimals: 9,
-                initial_balances: [].to_vec(),
+                ..default_instantiate_msg()
             };
             let (env, info) = mock_env_height("creator", 450, 550);
             let result = instantiate(deps.as_mut(), env, info, instantiate_msg);
@@ -609,13 +2768,11 @@ mod tests {
     mod transfer {
         use super::*;
         use crate::error::ContractError;
+        use crate::testing::default_instantiate_msg;
         use cosmwasm_std::attr;
 
         fn make_instantiate_msg() -> InstantiateMsg {
             InstantiateMsg {
-                name: "Cash Token".to_string(),
-                symbol: "CASH".to_string(),
-                decimals: 9,
                 initial_balances: vec![
                     InitialBalance {
                         address: "addr0000".to_string(),
@@ -630,6 +2787,7 @@ mod tests {
                         amount: Uint128::from(33u128),
                     },
                 ],
+                ..default_instantiate_msg()
             }
         }
 
@@ -658,6 +2816,8 @@ mod tests {
             let transfer_msg = ExecuteMsg::Transfer {
                 recipient: "addr1111".to_string(),
                 amount: Uint128::from(1u128),
+                memo: None,
+                on_behalf_of: None,
             };
             let (env, info) = mock_env_height("addr0000", 450, 550);
             let transfer_result = execute(deps.as_mut(), env, info, transfer_msg).unwrap();
@@ -711,6 +2871,8 @@ mod tests {
             let transfer_msg = ExecuteMsg::Transfer {
                 recipient: "addr2323".to_string(),
                 amount: Uint128::from(1u128),
+                memo: None,
+                on_behalf_of: None,
             };
             let (env, info) = mock_env_height("addr0000", 450, 550);
             let transfer_result = execute(deps.as_mut(), env, info, transfer_msg).unwrap();
@@ -768,6 +2930,8 @@ mod tests {
             let transfer_msg = ExecuteMsg::Transfer {
                 recipient: "addr1111".to_string(),
                 amount: Uint128::from(0u128),
+                memo: None,
+                on_behalf_of: None,
             };
             let (env, info) = mock_env_height("addr0000", 450, 550);
             let transfer_result = execute(deps.as_mut(), env, info, transfer_msg).unwrap();
@@ -810,6 +2974,8 @@ mod tests {
             let transfer_msg = ExecuteMsg::Transfer {
                 recipient: sender.to_string(),
                 amount: Uint128::from(3u128),
+                memo: None,
+                on_behalf_of: None,
             };
             let (env, info) = mock_env_height(&sender, 450, 550);
             let transfer_result = execute(deps.as_mut(), env, info, transfer_msg).unwrap();
@@ -850,44 +3016,230 @@ mod tests {
             // Transfer
             let transfer_msg = ExecuteMsg::Transfer {
                 recipient: "addr1111".to_string(),
-                amount: Uint128::from(12u128),
+                amount: Uint128::from(12u128),
+                memo: None,
+                on_behalf_of: None,
+            };
+            let (env, info) = mock_env_height("addr0000", 450, 550);
+            let transfer_result = execute(deps.as_mut(), env, info, transfer_msg);
+            match transfer_result {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::InsufficientFunds {
+                    balance: 11,
+                    required: 12,
+                }) => {}
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+            // New state (unchanged)
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addr0000".to_string())),
+                11
+            );
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addr1111".to_string())),
+                22
+            );
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addrbbbb".to_string())),
+                33
+            );
+            assert_eq!(get_total_supply(&deps.storage), 66);
+        }
+
+        #[test]
+        fn sets_new_sender_balance_as_data() {
+            let mut deps = mock_dependencies_with_balance(&[]);
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg()).unwrap();
+            let transfer_msg = ExecuteMsg::Transfer {
+                recipient: "addr1111".to_string(),
+                amount: Uint128::from(1u128),
+                memo: None,
+                on_behalf_of: None,
+            };
+            let (env, info) = mock_env_height("addr0000", 450, 550);
+            let res = execute(deps.as_mut(), env, info, transfer_msg).unwrap();
+            let data: BalanceResponse = from_slice(&res.data.unwrap()).unwrap();
+            assert_eq!(data.balance, Uint128::from(10u128));
+        }
+
+        #[test]
+        fn self_transfer_changes_nothing() {
+            let mut deps = mock_dependencies_with_balance(&[]);
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg()).unwrap();
+            let fee_msg = ExecuteMsg::UpdateFeeConfig {
+                enabled: true,
+                rate_bps: 1000,
+                treasury: "addrbbbb".to_string(),
+            };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            execute(deps.as_mut(), env, info, fee_msg).unwrap();
+
+            let transfer_msg = ExecuteMsg::Transfer {
+                recipient: "addr0000".to_string(),
+                amount: Uint128::from(10u128),
+                memo: None,
+                on_behalf_of: None,
+            };
+            let (env, info) = mock_env_height("addr0000", 450, 550);
+            let res = execute(deps.as_mut(), env, info, transfer_msg).unwrap();
+            let data: BalanceResponse = from_slice(&res.data.unwrap()).unwrap();
+            assert_eq!(data.balance, Uint128::from(11u128));
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addr0000".to_string())),
+                11
+            );
+            assert_eq!(
+                get_balance(&deps.storage, &Addr::unchecked("addrbbbb".to_string())),
+                33
+            );
+            assert_eq!(get_total_supply(&deps.storage), 66);
+
+            // Still has to be affordable
+            let transfer_msg = ExecuteMsg::Transfer {
+                recipient: "addr0000".to_string(),
+                amount: Uint128::from(12u128),
+                memo: None,
+                on_behalf_of: None,
+            };
+            let (env, info) = mock_env_height("addr0000", 450, 550);
+            match execute(deps.as_mut(), env, info, transfer_msg).unwrap_err() {
+                ContractError::InsufficientFunds {
+                    balance: 11,
+                    required: 12,
+                } => {}
+                e => panic!("unexpected error: {:?}", e),
+            }
+        }
+
+        #[test]
+        fn overflowing_recipient_balance_errors() {
+            let mut deps = mock_dependencies_with_balance(&[]);
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env.clone(), info, make_instantiate_msg()).unwrap();
+            let recipient = Addr::unchecked("addr1111");
+            write_shares(
+                &mut deps.storage,
+                &deps.api,
+                &env,
+                &recipient,
+                22,
+                u128::MAX,
+            )
+            .unwrap();
+
+            let transfer_msg = ExecuteMsg::Transfer {
+                recipient: "addr1111".to_string(),
+                amount: Uint128::from(1u128),
+                memo: None,
+                on_behalf_of: None,
+            };
+            let (env, info) = mock_env_height("addr0000", 450, 550);
+            match execute(deps.as_mut(), env, info, transfer_msg).unwrap_err() {
+                ContractError::Overflow {} => {}
+                e => panic!("unexpected error: {:?}", e),
+            }
+        }
+    }
+
+    mod memo {
+        use super::*;
+        use crate::error::ContractError;
+        use crate::testing::default_instantiate_msg;
+        use cosmwasm_std::attr;
+
+        fn make_instantiate_msg() -> InstantiateMsg {
+            InstantiateMsg {
+                initial_balances: vec![InitialBalance {
+                    address: "addr0000".to_string(),
+                    amount: Uint128::from(11u128),
+                }],
+                ..default_instantiate_msg()
+            }
+        }
+
+        #[test]
+        fn transfer_emits_memo() {
+            let mut deps = mock_dependencies_with_balance(&[]);
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg()).unwrap();
+            let transfer_msg = ExecuteMsg::Transfer {
+                recipient: "addr1111".to_string(),
+                amount: Uint128::from(1u128),
+                memo: Some("deposit 1234".to_string()),
+                on_behalf_of: None,
+            };
+            let (env, info) = mock_env_height("addr0000", 450, 550);
+            let transfer_result = execute(deps.as_mut(), env, info, transfer_msg).unwrap();
+            assert_eq!(
+                transfer_result.attributes,
+                vec![
+                    attr("action", "transfer"),
+                    attr("sender", "addr0000"),
+                    attr("recipient", "addr1111"),
+                    attr("memo", "deposit 1234"),
+                ]
+            );
+        }
+
+        #[test]
+        fn transfer_from_emits_memo() {
+            let mut deps = mock_dependencies_with_balance(&[]);
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg()).unwrap();
+            let approve_msg = ExecuteMsg::Approve {
+                spender: "addr1111".to_string(),
+                amount: Uint128::from(5u128),
+                expected_current: None,
+                on_behalf_of: None,
+            };
+            let (env, info) = mock_env_height("addr0000", 450, 550);
+            execute(deps.as_mut(), env, info, approve_msg).unwrap();
+            let transfer_from_msg = ExecuteMsg::TransferFrom {
+                owner: "addr0000".to_string(),
+                recipient: "addr2222".to_string(),
+                amount: Uint128::from(5u128),
+                memo: Some("invoice 7".to_string()),
+            };
+            let (env, info) = mock_env_height("addr1111", 450, 550);
+            let result = execute(deps.as_mut(), env, info, transfer_from_msg).unwrap();
+            assert!(result.attributes.contains(&attr("memo", "invoice 7")));
+        }
+
+        #[test]
+        fn fails_for_memo_too_long() {
+            let mut deps = mock_dependencies_with_balance(&[]);
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg()).unwrap();
+            let transfer_msg = ExecuteMsg::Transfer {
+                recipient: "addr1111".to_string(),
+                amount: Uint128::from(1u128),
+                memo: Some("x".repeat(MAX_MEMO_BYTES + 1)),
+                on_behalf_of: None,
             };
             let (env, info) = mock_env_height("addr0000", 450, 550);
-            let transfer_result = execute(deps.as_mut(), env, info, transfer_msg);
-            match transfer_result {
+            let result = execute(deps.as_mut(), env, info, transfer_msg);
+            match result {
                 Ok(_) => panic!("expected error"),
-                Err(ContractError::InsufficientFunds {
-                    balance: 11,
-                    required: 12,
-                }) => {}
+                Err(ContractError::MemoTooLong { max }) => assert_eq!(max, MAX_MEMO_BYTES),
                 Err(e) => panic!("unexpected error: {:?}", e),
             }
-            // New state (unchanged)
             assert_eq!(
                 get_balance(&deps.storage, &Addr::unchecked("addr0000".to_string())),
                 11
             );
-            assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked("addr1111".to_string())),
-                22
-            );
-            assert_eq!(
-                get_balance(&deps.storage, &Addr::unchecked("addrbbbb".to_string())),
-                33
-            );
-            assert_eq!(get_total_supply(&deps.storage), 66);
         }
     }
 
     mod approve {
         use super::*;
+        use crate::error::ContractError;
+        use crate::testing::default_instantiate_msg;
         use cosmwasm_std::attr;
 
         fn make_instantiate_msg() -> InstantiateMsg {
             InstantiateMsg {
-                name: "Cash Token".to_string(),
-                symbol: "CASH".to_string(),
-                decimals: 9,
                 initial_balances: vec![
                     InitialBalance {
                         address: "addr0000".to_string(),
@@ -902,6 +3254,7 @@ mod tests {
                         amount: Uint128::from(33u128),
                     },
                 ],
+                ..default_instantiate_msg()
             }
         }
 
@@ -932,6 +3285,106 @@ mod tests {
             );
         }
 
+        #[test]
+        fn approve_fails_if_allowance_changed() {
+            let mut deps = mock_dependencies_with_balance(&[]);
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg()).unwrap();
+            let approve = |expected_current: Option<u128>, amount: u128| ExecuteMsg::Approve {
+                spender: make_spender().to_string(),
+                amount: Uint128::from(amount),
+                expected_current: expected_current.map(Uint128::from),
+                on_behalf_of: None,
+            };
+            let (env, info) = mock_env_height("addr0000", 450, 550);
+            execute(
+                deps.as_mut(),
+                env.clone(),
+                info.clone(),
+                approve(Some(0), 50),
+            )
+            .unwrap();
+
+            let result = execute(
+                deps.as_mut(),
+                env.clone(),
+                info.clone(),
+                approve(Some(0), 20),
+            );
+            match result {
+                Ok(_) => panic!("expected error"),
+                Err(ContractError::AllowanceChanged { expected, current }) => {
+                    assert_eq!(expected, 0);
+                    assert_eq!(current, 50);
+                }
+                Err(e) => panic!("unexpected error: {:?}", e),
+            }
+            execute(deps.as_mut(), env, info, approve(Some(50), 20)).unwrap();
+            assert_eq!(
+                get_allowance(&deps.storage, &Addr::unchecked("addr0000"), &make_spender()),
+                20
+            );
+        }
+
+        #[test]
+        fn increase_and_decrease_allowance() {
+            let mut deps = mock_dependencies_with_balance(&[]);
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg()).unwrap();
+            let owner = Addr::unchecked("addr0000");
+            let (env, info) = mock_env_height(owner.as_str(), 450, 550);
+            let update = |increase: bool, amount: u128, expires: Option<Expiration>| {
+                let spender = make_spender().to_string();
+                let amount = Uint128::from(amount);
+                if increase {
+                    ExecuteMsg::IncreaseAllowance {
+                        spender,
+                        amount,
+                        expires,
+                    }
+                } else {
+                    ExecuteMsg::DecreaseAllowance {
+                        spender,
+                        amount,
+                        expires,
+                    }
+                }
+            };
+
+            let expires = Expiration::AtHeight(500);
+            let msg = update(true, 30, Some(expires));
+            let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+            assert_eq!(res.attributes[0], attr("action", "increase_allowance"));
+            let msg = update(false, 10, None);
+            let res = execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+            let data: AllowanceResponse = from_slice(&res.data.unwrap()).unwrap();
+            assert_eq!(data.allowance, Uint128::from(20u128));
+            assert_eq!(
+                read_allowance_expiration(&deps.storage, &deps.api, &owner, &make_spender())
+                    .unwrap(),
+                Some(expires)
+            );
+
+            // floors at zero and saturates into an unlimited allowance
+            let msg = update(false, 50, None);
+            execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+            assert_eq!(get_allowance(&deps.storage, &owner, &make_spender()), 0);
+            let msg = update(true, UNLIMITED_ALLOWANCE, None);
+            execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+            let msg = update(true, 1, None);
+            execute(deps.as_mut(), env.clone(), info.clone(), msg).unwrap();
+            assert_eq!(
+                get_allowance(&deps.storage, &owner, &make_spender()),
+                UNLIMITED_ALLOWANCE
+            );
+
+            let msg = update(true, 1, Some(Expiration::AtHeight(400)));
+            match execute(deps.as_mut(), env, info, msg).unwrap_err() {
+                ContractError::InvalidAllowanceExpiration {} => {}
+                e => panic!("unexpected error: {:?}", e),
+            }
+        }
+
         #[test]
         fn can_set_allowance() {
             let mut deps = mock_dependencies_with_balance(&[]);
@@ -953,6 +3406,8 @@ mod tests {
             let approve_msg1 = ExecuteMsg::Approve {
                 spender: spender.clone().to_string().to_string(),
                 amount: Uint128::from(334422u128),
+                expected_current: None,
+                on_behalf_of: None,
             };
             let (env, info) = mock_env_height(&owner.as_str(), 450, 550);
             let approve_result1 = execute(deps.as_mut(), env, info, approve_msg1).unwrap();
@@ -965,6 +3420,8 @@ mod tests {
                     attr("spender", spender.clone().to_string()),
                 ]
             );
+            let data: AllowanceResponse = from_slice(&approve_result1.data.unwrap()).unwrap();
+            assert_eq!(data.allowance, Uint128::from(334422u128));
             assert_eq!(
                 get_allowance(&deps.storage, &owner, &make_spender()),
                 334422
@@ -973,6 +3430,8 @@ mod tests {
             let approve_msg = ExecuteMsg::Approve {
                 spender: spender.clone().to_string().to_string(),
                 amount: Uint128::from(777888u128),
+                expected_current: None,
+                on_behalf_of: None,
             };
             let (env, info) = mock_env_height(&owner.as_str(), 450, 550);
             let approve_result2 = execute(deps.as_mut(), env, info, approve_msg).unwrap();
@@ -987,18 +3446,202 @@ mod tests {
             );
             assert_eq!(get_allowance(&deps.storage, &owner, &spender), 777888);
         }
+
+        #[test]
+        fn approve_many_sets_allowances_with_expirations() {
+            let mut deps = mock_dependencies_with_balance(&[]);
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg()).unwrap();
+            let owner = Addr::unchecked("addr0000");
+            let msg = ExecuteMsg::ApproveMany {
+                approvals: vec![
+                    ApprovalEntry {
+                        spender: make_spender().to_string(),
+                        amount: Uint128::from(5u128),
+                        expires: Some(Expiration::AtHeight(500)),
+                    },
+                    ApprovalEntry {
+                        spender: "addr1111".to_string(),
+                        amount: Uint128::from(7u128),
+                        expires: None,
+                    },
+                ],
+            };
+            let (env, info) = mock_env_height(owner.as_str(), 450, 550);
+            let res = execute(deps.as_mut(), env.clone(), info, msg).unwrap();
+            assert_eq!(res.events.len(), 2);
+            assert_eq!(res.events[0].ty, "approve");
+            assert!(res.events[0]
+                .attributes
+                .contains(&attr("expires", "expiration height: 500")));
+            assert_eq!(get_allowance(&deps.storage, &owner, &make_spender()), 5);
+            assert_eq!(
+                get_allowance(&deps.storage, &owner, &Addr::unchecked("addr1111")),
+                7
+            );
+
+            let query_msg = QueryMsg::Allowance {
+                owner: owner.to_string(),
+                spender: make_spender().to_string(),
+            };
+            let allowance: AllowanceResponse =
+                from_slice(&query(deps.as_ref(), env, query_msg.clone()).unwrap()).unwrap();
+            assert_eq!(allowance.allowance, Uint128::from(5u128));
+            assert_eq!(allowance.expires, Expiration::AtHeight(500));
+
+            // Expired allowances read as zero and cannot be spent
+            let (env, info) = mock_env_height(make_spender().as_str(), 500, 560);
+            let allowance: AllowanceResponse =
+                from_slice(&query(deps.as_ref(), env.clone(), query_msg).unwrap()).unwrap();
+            assert_eq!(allowance.allowance, Uint128::zero());
+            let transfer_from = ExecuteMsg::TransferFrom {
+                owner: owner.to_string(),
+                recipient: "addr2222".to_string(),
+                amount: Uint128::from(1u128),
+                memo: None,
+            };
+            match execute(deps.as_mut(), env, info, transfer_from).unwrap_err() {
+                ContractError::InsufficientAllowance { allowance, .. } => assert_eq!(allowance, 0),
+                e => panic!("unexpected error: {:?}", e),
+            }
+        }
+
+        #[test]
+        fn approve_many_writes_nothing_if_an_entry_is_invalid() {
+            let mut deps = mock_dependencies_with_balance(&[]);
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg()).unwrap();
+            let owner = Addr::unchecked("addr0000");
+            let msg = ExecuteMsg::ApproveMany {
+                approvals: vec![
+                    ApprovalEntry {
+                        spender: make_spender().to_string(),
+                        amount: Uint128::from(5u128),
+                        expires: None,
+                    },
+                    ApprovalEntry {
+                        spender: "addr1111".to_string(),
+                        amount: Uint128::from(7u128),
+                        expires: Some(Expiration::AtHeight(450)),
+                    },
+                ],
+            };
+            let (env, info) = mock_env_height(owner.as_str(), 450, 550);
+            match execute(deps.as_mut(), env, info, msg).unwrap_err() {
+                ContractError::InvalidAllowanceExpiration {} => {}
+                e => panic!("unexpected error: {:?}", e),
+            }
+            assert_eq!(get_allowance(&deps.storage, &owner, &make_spender()), 0);
+        }
+
+        #[test]
+        fn approve_clears_expiration() {
+            let mut deps = mock_dependencies_with_balance(&[]);
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg()).unwrap();
+            let owner = Addr::unchecked("addr0000");
+            let (env, info) = mock_env_height(owner.as_str(), 450, 550);
+            let approve_many = ExecuteMsg::ApproveMany {
+                approvals: vec![ApprovalEntry {
+                    spender: make_spender().to_string(),
+                    amount: Uint128::from(5u128),
+                    expires: Some(Expiration::AtHeight(500)),
+                }],
+            };
+            execute(deps.as_mut(), env.clone(), info.clone(), approve_many).unwrap();
+            let approve = ExecuteMsg::Approve {
+                spender: make_spender().to_string(),
+                amount: Uint128::from(9u128),
+                expected_current: None,
+                on_behalf_of: None,
+            };
+            execute(deps.as_mut(), env, info, approve).unwrap();
+            assert_eq!(
+                read_allowance_expiration(&deps.storage, &deps.api, &owner, &make_spender())
+                    .unwrap(),
+                None
+            );
+        }
+
+        #[test]
+        fn revoke_all_allowances_deletes_every_approval_of_the_owner() {
+            let mut deps = mock_dependencies_with_balance(&[]);
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg()).unwrap();
+            let owner = Addr::unchecked("addr0000");
+            let other_owner = Addr::unchecked("addr1111");
+            let (env, info) = mock_env_height(owner.as_str(), 450, 550);
+            let approve_many = ExecuteMsg::ApproveMany {
+                approvals: vec![
+                    ApprovalEntry {
+                        spender: make_spender().to_string(),
+                        amount: Uint128::from(5u128),
+                        expires: Some(Expiration::AtHeight(500)),
+                    },
+                    ApprovalEntry {
+                        spender: "addrbbbb".to_string(),
+                        amount: Uint128::from(7u128),
+                        expires: None,
+                    },
+                ],
+            };
+            execute(deps.as_mut(), env.clone(), info.clone(), approve_many).unwrap();
+            let periodic = ExecuteMsg::ApprovePeriodic {
+                spender: "addr2222".to_string(),
+                amount_per_period: Uint128::from(3u128),
+                period_seconds: 60,
+            };
+            execute(deps.as_mut(), env.clone(), info.clone(), periodic).unwrap();
+            let approve = ExecuteMsg::Approve {
+                spender: make_spender().to_string(),
+                amount: Uint128::from(9u128),
+                expected_current: None,
+                on_behalf_of: None,
+            };
+            let (other_env, other_info) = mock_env_height(other_owner.as_str(), 450, 550);
+            execute(deps.as_mut(), other_env, other_info, approve).unwrap();
+
+            let res =
+                execute(deps.as_mut(), env, info, ExecuteMsg::RevokeAllAllowances {}).unwrap();
+            assert_eq!(res.events.len(), 3);
+            assert!(res.attributes.contains(&attr("revoked", "3")));
+            let spenders = ReadonlyPrefixedStorage::multilevel(
+                &deps.storage,
+                &[PREFIX_ALLOWANCES, owner.as_str().as_bytes()],
+            )
+            .range(None, None, Order::Ascending)
+            .count();
+            assert_eq!(spenders, 0);
+            assert_eq!(
+                read_allowance_expiration(&deps.storage, &deps.api, &owner, &make_spender())
+                    .unwrap(),
+                None
+            );
+            assert_eq!(
+                periodic::read_periodic_allowance(
+                    &deps.storage,
+                    &owner,
+                    &Addr::unchecked("addr2222")
+                )
+                .unwrap(),
+                None
+            );
+            // Allowances granted by other owners are kept
+            assert_eq!(
+                get_allowance(&deps.storage, &other_owner, &make_spender()),
+                9
+            );
+        }
     }
 
     mod transfer_from {
         use super::*;
         use crate::error::ContractError;
+        use crate::testing::default_instantiate_msg;
         use cosmwasm_std::{attr, Addr};
 
         fn make_instantiate_msg() -> InstantiateMsg {
             InstantiateMsg {
-                name: "Cash Token".to_string(),
-                symbol: "CASH".to_string(),
-                decimals: 9,
                 initial_balances: vec![
                     InitialBalance {
                         address: "addr0000".to_string(),
@@ -1013,6 +3656,7 @@ mod tests {
                         amount: Uint128::from(33u128),
                     },
                 ],
+                ..default_instantiate_msg()
             }
         }
 
@@ -1034,6 +3678,8 @@ mod tests {
             let approve_msg = ExecuteMsg::Approve {
                 spender: spender.clone().to_string().to_string(),
                 amount: Uint128::from(4u128),
+                expected_current: None,
+                on_behalf_of: None,
             };
             let (env, info) = mock_env_height(&owner.clone(), 450, 550);
             let approve_result = execute(deps.as_mut(), env, info, approve_msg).unwrap();
@@ -1059,6 +3705,7 @@ mod tests {
                 owner: owner.clone().to_string().to_string(),
                 recipient: recipient.clone().to_string(),
                 amount: Uint128::from(3u128),
+                memo: None,
             };
             let (env, info) = mock_env_height(&spender.as_str(), 450, 550);
             let transfer_from_result =
@@ -1081,6 +3728,37 @@ mod tests {
             );
         }
 
+        #[test]
+        fn unlimited_allowance_is_not_decreased() {
+            let mut deps = mock_dependencies_with_balance(&[]);
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg()).unwrap();
+            let owner = Addr::unchecked("addr0000");
+            let spender = make_spender();
+            let approve_msg = ExecuteMsg::Approve {
+                spender: spender.to_string(),
+                amount: Uint128::MAX,
+                expected_current: None,
+                on_behalf_of: None,
+            };
+            let (env, info) = mock_env_height(owner.as_str(), 450, 550);
+            execute(deps.as_mut(), env, info, approve_msg).unwrap();
+
+            let transfer_from_msg = ExecuteMsg::TransferFrom {
+                owner: owner.to_string(),
+                recipient: "addr1212".to_string(),
+                amount: Uint128::from(3u128),
+                memo: None,
+            };
+            let (env, info) = mock_env_height(spender.as_str(), 450, 550);
+            execute(deps.as_mut(), env, info, transfer_from_msg).unwrap();
+            assert_eq!(get_balance(&deps.storage, &owner), 8);
+            assert_eq!(
+                get_allowance(&deps.storage, &owner, &spender),
+                UNLIMITED_ALLOWANCE
+            );
+        }
+
         #[test]
         fn fails_when_allowance_too_low() {
             let mut deps = mock_dependencies_with_balance(&[]);
@@ -1095,6 +3773,8 @@ mod tests {
             let approve_msg = ExecuteMsg::Approve {
                 spender: spender.clone().to_string(),
                 amount: Uint128::from(2u128),
+                expected_current: None,
+                on_behalf_of: None,
             };
             let (env, info) = mock_env_height(&owner.clone(), 450, 550);
             let approve_result = execute(deps.as_mut(), env, info, approve_msg).unwrap();
@@ -1117,6 +3797,7 @@ mod tests {
                 owner: owner.clone().to_string(),
                 recipient: recipient.clone().to_string(),
                 amount: Uint128::from(3u128),
+                memo: None,
             };
             let (env, info) = mock_env_height(&spender.as_str(), 450, 550);
             let transfer_result = execute(deps.as_mut(), env, info, fransfer_from_msg);
@@ -1144,6 +3825,8 @@ mod tests {
             let approve_msg = ExecuteMsg::Approve {
                 spender: spender.clone().to_string(),
                 amount: Uint128::from(20u128),
+                expected_current: None,
+                on_behalf_of: None,
             };
             let (env, info) = mock_env_height(&owner.clone(), 450, 550);
             let approve_result = execute(deps.as_mut(), env, info, approve_msg).unwrap();
@@ -1166,6 +3849,7 @@ mod tests {
                 owner: owner.clone().to_string(),
                 recipient: recipient.clone().to_string(),
                 amount: Uint128::from(15u128),
+                memo: None,
             };
             let (env, info) = mock_env_height(&spender.as_str(), 450, 550);
             let transfer_result = execute(deps.as_mut(), env, info, fransfer_from_msg);
@@ -1183,13 +3867,11 @@ mod tests {
     mod burn {
         use super::*;
         use crate::error::ContractError;
+        use crate::testing::default_instantiate_msg;
         use cosmwasm_std::{attr, Addr};
 
         fn make_instantiate_msg() -> InstantiateMsg {
             InstantiateMsg {
-                name: "Cash Token".to_string(),
-                symbol: "CASH".to_string(),
-                decimals: 9,
                 initial_balances: vec![
                     InitialBalance {
                         address: "addr0000".to_string(),
@@ -1200,6 +3882,7 @@ mod tests {
                         amount: Uint128::from(22u128),
                     },
                 ],
+                ..default_instantiate_msg()
             }
         }
 
@@ -1223,6 +3906,7 @@ mod tests {
             // Burn
             let burn_msg = ExecuteMsg::Burn {
                 amount: Uint128::from(1u128),
+                memo: None,
             };
             let (env, info) = mock_env_height("addr0000", 450, 550);
             let burn_result = execute(deps.as_mut(), env, info, burn_msg).unwrap();
@@ -1267,6 +3951,7 @@ mod tests {
             // Burn
             let burn_msg = ExecuteMsg::Burn {
                 amount: Uint128::from(0u128),
+                memo: None,
             };
             let (env, info) = mock_env_height("addr0000", 450, 550);
             let burn_result = execute(deps.as_mut(), env, info, burn_msg).unwrap();
@@ -1311,6 +3996,7 @@ mod tests {
             // Burn
             let burn_msg = ExecuteMsg::Burn {
                 amount: Uint128::from(12u128),
+                memo: None,
             };
             let (env, info) = mock_env_height("addr0000", 450, 550);
             let burn_result = execute(deps.as_mut(), env, info, burn_msg);
@@ -1337,6 +4023,7 @@ mod tests {
 
     mod query {
         use super::*;
+        use crate::testing::default_instantiate_msg;
         use cosmwasm_std::{attr, Addr};
 
         fn address(index: u8) -> Addr {
@@ -1352,9 +4039,6 @@ mod tests {
 
         fn make_instantiate_msg() -> InstantiateMsg {
             InstantiateMsg {
-                name: "Cash Token".to_string(),
-                symbol: "CASH".to_string(),
-                decimals: 9,
                 initial_balances: vec![
                     InitialBalance {
                         address: address(1).to_string(),
@@ -1369,6 +4053,7 @@ mod tests {
                         amount: Uint128::from(33u128),
                     },
                 ],
+                ..default_instantiate_msg()
             }
         }
 
@@ -1400,6 +4085,64 @@ mod tests {
             assert_eq!(query_result.as_slice(), b"{\"balance\":\"0\"}");
         }
 
+        #[test]
+        fn can_query_config() {
+            let mut deps = mock_dependencies_with_balance(&[]);
+            let instantiate_msg = make_instantiate_msg();
+            let (env, info) = mock_env_height(address(0).as_str(), 450, 550);
+            instantiate(deps.as_mut(), env.clone(), info, instantiate_msg).unwrap();
+            let query_result = query(deps.as_ref(), env, QueryMsg::Config {}).unwrap();
+            let res: ConfigResponse = from_slice(&query_result).unwrap();
+            assert_eq!(
+                res.constants,
+                Constants {
+                    name: "Cash Token".to_string(),
+                    symbol: "CASH".to_string(),
+                    decimals: 9,
+                }
+            );
+            assert_eq!(res.owner, Some(address(0).to_string()));
+            assert_eq!(res.minter, None);
+            assert!(!res.paused);
+            assert!(!res.fee_config.enabled);
+            assert_eq!(res.max_supply, None);
+        }
+
+        #[test]
+        fn can_query_balances_in_batch() {
+            let mut deps = mock_dependencies_with_balance(&[]);
+            let instantiate_msg = make_instantiate_msg();
+            let (env, info) = mock_env_height(address(0).as_str(), 450, 550);
+            instantiate(deps.as_mut(), env.clone(), info, instantiate_msg).unwrap();
+            let query_msg = QueryMsg::Balances {
+                addresses: vec![address(3).to_string(), address(4).to_string()],
+            };
+            let query_result = query(deps.as_ref(), env.clone(), query_msg).unwrap();
+            let res: BalancesResponse = from_slice(&query_result).unwrap();
+            assert_eq!(
+                res.balances,
+                vec![
+                    AddressBalance {
+                        address: address(3).to_string(),
+                        balance: Uint128::from(33u128),
+                    },
+                    AddressBalance {
+                        address: address(4).to_string(),
+                        balance: Uint128::zero(),
+                    },
+                ]
+            );
+
+            let query_msg = QueryMsg::Balances {
+                addresses: vec![address(1).to_string(); MAX_BALANCES_BATCH + 1],
+            };
+            let err = query(deps.as_ref(), env, query_msg).unwrap_err();
+            match err {
+                ContractError::TooManyAddresses { max } => assert_eq!(max, MAX_BALANCES_BATCH),
+                e => panic!("unexpected error: {:?}", e),
+            }
+        }
+
         #[test]
         fn can_query_allowance_of_existing_addresses() {
             let mut deps = mock_dependencies_with_balance(&[]);
@@ -1412,6 +4155,8 @@ mod tests {
             let approve_msg = ExecuteMsg::Approve {
                 spender: spender.clone().to_string(),
                 amount: Uint128::from(42u128),
+                expected_current: None,
+                on_behalf_of: None,
             };
             let (env, info) = mock_env_height(&owner.as_str(), 450, 550);
             let action_result = execute(deps.as_mut(), env.clone(), info, approve_msg).unwrap();
@@ -1429,7 +4174,10 @@ mod tests {
                 spender: spender.clone().to_string(),
             };
             let query_result = query(deps.as_ref(), env.clone(), query_msg).unwrap();
-            assert_eq!(query_result.as_slice(), b"{\"allowance\":\"42\"}");
+            assert_eq!(
+                query_result.as_slice(),
+                b"{\"allowance\":\"42\",\"expires\":{\"never\":{}}}"
+            );
         }
 
         #[test]
@@ -1445,6 +4193,8 @@ mod tests {
             let approve_msg = ExecuteMsg::Approve {
                 spender: spender.clone().to_string(),
                 amount: Uint128::from(42u128),
+                expected_current: None,
+                on_behalf_of: None,
             };
             let (env, info) = mock_env_height(&owner.as_str(), 450, 550);
             let approve_result = execute(deps.as_mut(), env.clone(), info, approve_msg).unwrap();
@@ -1463,14 +4213,250 @@ mod tests {
                 spender: bob.clone().to_string(),
             };
             let query_result = query(deps.as_ref(), env.clone(), query_msg).unwrap();
-            assert_eq!(query_result.as_slice(), b"{\"allowance\":\"0\"}");
+            assert_eq!(
+                query_result.as_slice(),
+                b"{\"allowance\":\"0\",\"expires\":{\"never\":{}}}"
+            );
             // differnet owner
             let query_msg = QueryMsg::Allowance {
                 owner: bob.clone().to_string(),
                 spender: spender.clone().to_string(),
             };
             let query_result = query(deps.as_ref(), env.clone(), query_msg).unwrap();
-            assert_eq!(query_result.as_slice(), b"{\"allowance\":\"0\"}");
+            assert_eq!(
+                query_result.as_slice(),
+                b"{\"allowance\":\"0\",\"expires\":{\"never\":{}}}"
+            );
+        }
+
+        #[test]
+        fn can_query_allowance_at_height() {
+            let mut deps = mock_dependencies_with_balance(&[]);
+            let instantiate_msg = make_instantiate_msg();
+            let (env, info) = mock_env_height(address(0).as_str(), 450, 550);
+            instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+            let owner = address(1);
+            let spender = address(2);
+            for (height, amount) in &[(500u64, 42u128), (600, 7)] {
+                let approve_msg = ExecuteMsg::Approve {
+                    spender: spender.to_string(),
+                    amount: Uint128::from(*amount),
+                    expected_current: None,
+                    on_behalf_of: None,
+                };
+                let (env, info) = mock_env_height(owner.as_str(), *height, 550);
+                execute(deps.as_mut(), env, info, approve_msg).unwrap();
+            }
+
+            for (height, expected) in &[(499u64, 0u128), (500, 42), (599, 42), (600, 7)] {
+                let query_msg = QueryMsg::AllowanceAt {
+                    owner: owner.to_string(),
+                    spender: spender.to_string(),
+                    height: *height,
+                };
+                let query_result = query(deps.as_ref(), mock_env(), query_msg).unwrap();
+                let res: AllowanceResponse = from_slice(&query_result).unwrap();
+                assert_eq!(res.allowance, Uint128::from(*expected));
+            }
+        }
+    }
+
+    mod account_stats {
+        use super::*;
+        use crate::error::ContractError;
+        use crate::testing::default_instantiate_msg;
+        use cosmwasm_std::attr;
+
+        fn make_instantiate_msg() -> InstantiateMsg {
+            InstantiateMsg {
+                initial_balances: vec![
+                    InitialBalance {
+                        address: "addr0000".to_string(),
+                        amount: Uint128::from(11u128),
+                    },
+                    InitialBalance {
+                        address: "addr1111".to_string(),
+                        amount: Uint128::from(22u128),
+                    },
+                ],
+                ..default_instantiate_msg()
+            }
+        }
+
+        fn query_stats(deps: Deps, address: &str) -> AccountStatsResponse {
+            let query_msg = QueryMsg::AccountStats {
+                address: address.to_string(),
+            };
+            from_slice(&query(deps, mock_env(), query_msg).unwrap()).unwrap()
+        }
+
+        #[test]
+        fn starts_at_zero() {
+            let mut deps = mock_dependencies_with_balance(&[]);
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg()).unwrap();
+            let stats = query_stats(deps.as_ref(), "addr0000");
+            assert_eq!(stats.sent, Uint128::zero());
+            assert_eq!(stats.received, Uint128::zero());
+        }
+
+        #[test]
+        fn tracks_transfers_and_transfers_from() {
+            let mut deps = mock_dependencies_with_balance(&[]);
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg()).unwrap();
+
+            let transfer_msg = ExecuteMsg::Transfer {
+                recipient: "addr1111".to_string(),
+                amount: Uint128::from(3u128),
+                memo: None,
+                on_behalf_of: None,
+            };
+            let (env, info) = mock_env_height("addr0000", 450, 550);
+            execute(deps.as_mut(), env, info, transfer_msg).unwrap();
+
+            let approve_msg = ExecuteMsg::Approve {
+                spender: "addr2222".to_string(),
+                amount: Uint128::from(10u128),
+                expected_current: None,
+                on_behalf_of: None,
+            };
+            let (env, info) = mock_env_height("addr1111", 450, 550);
+            execute(deps.as_mut(), env, info, approve_msg).unwrap();
+            let transfer_from_msg = ExecuteMsg::TransferFrom {
+                owner: "addr1111".to_string(),
+                recipient: "addr0000".to_string(),
+                amount: Uint128::from(7u128),
+                memo: None,
+            };
+            let (env, info) = mock_env_height("addr2222", 450, 550);
+            execute(deps.as_mut(), env, info, transfer_from_msg).unwrap();
+
+            let stats = query_stats(deps.as_ref(), "addr0000");
+            assert_eq!(stats.sent, Uint128::from(3u128));
+            assert_eq!(stats.received, Uint128::from(7u128));
+            let stats = query_stats(deps.as_ref(), "addr1111");
+            assert_eq!(stats.sent, Uint128::from(7u128));
+            assert_eq!(stats.received, Uint128::from(3u128));
+            // the spender only moved funds, it neither sent nor received any
+            let stats = query_stats(deps.as_ref(), "addr2222");
+            assert_eq!(stats.sent, Uint128::zero());
+            assert_eq!(stats.received, Uint128::zero());
+        }
+
+        #[test]
+        fn burn_is_not_counted() {
+            let mut deps = mock_dependencies_with_balance(&[]);
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg()).unwrap();
+            let burn_msg = ExecuteMsg::Burn {
+                amount: Uint128::from(5u128),
+                memo: None,
+            };
+            let (env, info) = mock_env_height("addr0000", 450, 550);
+            execute(deps.as_mut(), env, info, burn_msg).unwrap();
+            let stats = query_stats(deps.as_ref(), "addr0000");
+            assert_eq!(stats.sent, Uint128::zero());
+        }
+
+        #[test]
+        fn tracks_burns_and_burns_from() {
+            let mut deps = mock_dependencies_with_balance(&[]);
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env, info, make_instantiate_msg()).unwrap();
+            let burn_msg = ExecuteMsg::Burn {
+                amount: Uint128::from(5u128),
+                memo: Some("fee rebate".to_string()),
+            };
+            let (env, info) = mock_env_height("addr0000", 450, 550);
+            let res = execute(deps.as_mut(), env, info, burn_msg).unwrap();
+            assert!(res.attributes.contains(&attr("memo", "fee rebate")));
+            assert!(res.events[0]
+                .attributes
+                .contains(&attr("memo", "fee rebate")));
+
+            let approve_msg = ExecuteMsg::Approve {
+                spender: "addr2222".to_string(),
+                amount: Uint128::from(10u128),
+                expected_current: None,
+                on_behalf_of: None,
+            };
+            let (env, info) = mock_env_height("addr1111", 450, 550);
+            execute(deps.as_mut(), env, info, approve_msg).unwrap();
+            let burn_from_msg = ExecuteMsg::BurnFrom {
+                owner: "addr1111".to_string(),
+                amount: Uint128::from(11u128),
+                memo: None,
+            };
+            let (env, info) = mock_env_height("addr2222", 450, 550);
+            let err = execute(deps.as_mut(), env, info, burn_from_msg).unwrap_err();
+            match err {
+                ContractError::InsufficientAllowance { .. } => {}
+                e => panic!("unexpected error: {:?}", e),
+            }
+            let burn_from_msg = ExecuteMsg::BurnFrom {
+                owner: "addr1111".to_string(),
+                amount: Uint128::from(4u128),
+                memo: None,
+            };
+            let (env, info) = mock_env_height("addr2222", 450, 550);
+            execute(deps.as_mut(), env, info, burn_from_msg).unwrap();
+
+            assert_eq!(
+                query_stats(deps.as_ref(), "addr0000").burned,
+                Uint128::from(5u128)
+            );
+            assert_eq!(
+                query_stats(deps.as_ref(), "addr1111").burned,
+                Uint128::from(4u128)
+            );
+            assert_eq!(
+                query_stats(deps.as_ref(), "addr2222").burned,
+                Uint128::zero()
+            );
+            assert_eq!(read_total_supply(&deps.storage).unwrap(), 24);
+        }
+    }
+
+    mod asset_card {
+        use super::*;
+        use crate::testing::default_instantiate_msg;
+
+        #[test]
+        fn combines_token_info_balance_and_restrictions() {
+            let mut deps = mock_dependencies_with_balance(&[]);
+            let instantiate_msg = InstantiateMsg {
+                initial_balances: vec![InitialBalance {
+                    address: "addr0000".to_string(),
+                    amount: Uint128::from(11u128),
+                }],
+                ..default_instantiate_msg()
+            };
+            let (env, info) = mock_env_height("creator", 450, 550);
+            instantiate(deps.as_mut(), env, info, instantiate_msg).unwrap();
+            let delegate_msg = ExecuteMsg::Delegate {
+                delegatee: "addr0000".to_string(),
+            };
+            let (env, info) = mock_env_height("addr0000", 451, 551);
+            execute(deps.as_mut(), env, info, delegate_msg).unwrap();
+            let (env, info) = mock_env_height("creator", 452, 552);
+            execute(deps.as_mut(), env, info, ExecuteMsg::Pause {}).unwrap();
+
+            let query_msg = QueryMsg::AssetCard {
+                address: "addr0000".to_string(),
+            };
+            let card: AssetCardResponse =
+                from_slice(&query(deps.as_ref(), mock_env(), query_msg).unwrap()).unwrap();
+            assert_eq!(card.name, "Cash Token");
+            assert_eq!(card.symbol, "CASH");
+            assert_eq!(card.display_symbol, None);
+            assert_eq!(card.decimals, 9);
+            assert_eq!(card.total_supply, Uint128::from(11u128));
+            assert_eq!(card.balance, Uint128::from(11u128));
+            assert_eq!(card.voting_power, Uint128::from(11u128));
+            assert_eq!(card.delegate, Some("addr0000".to_string()));
+            assert!(card.restrictions.paused);
+            assert_eq!(card.restrictions.transfer_fee_bps, 0);
         }
     }
 }
@@ -0,0 +1,209 @@
+use cosmwasm_std::{from_slice, to_vec, Addr, DepsMut, MessageInfo, Response, Storage, Uint128};
+use cosmwasm_storage::{PrefixedStorage, ReadonlyPrefixedStorage};
+
+use crate::contract::{assert_owner, PREFIX_CONFIG};
+use crate::error::ContractError;
+use crate::state::TransferBurn;
+
+pub const KEY_TRANSFER_BURN: &[u8] = b"transfer_burn";
+
+/// Upper bound for the transfer burn, in basis points
+pub const MAX_BURN_BPS: u16 = 1_000;
+
+// A deflationary token destroys a share of every transfer, on top of the fee.
+// The burn is charged to the sender and lowers the total supply.
+
+pub fn read_transfer_burn(store: &dyn Storage) -> Result<TransferBurn, ContractError> {
+    let config_store = ReadonlyPrefixedStorage::new(store, PREFIX_CONFIG);
+    match config_store.get(KEY_TRANSFER_BURN) {
+        Some(data) => Ok(from_slice(&data)?),
+        None => Ok(TransferBurn::default()),
+    }
+}
+
+/// Replace the transfer burn. Owner only.
+///
+/// @param rate_bps share of every transfer to burn in basis points, at most
+/// `MAX_BURN_BPS`. Zero disables the burn.
+/// @param exempt accounts (e.g. pools, treasury) whose transfers are not burned
+pub fn try_update_transfer_burn(
+    deps: DepsMut,
+    info: MessageInfo,
+    rate_bps: u16,
+    exempt: Vec<String>,
+) -> Result<Response, ContractError> {
+    assert_owner(deps.storage, &info.sender)?;
+    if rate_bps > MAX_BURN_BPS {
+        return Err(ContractError::BurnRateTooHigh {
+            max: MAX_BURN_BPS,
+            rate: rate_bps,
+        });
+    }
+    let exempt = exempt
+        .iter()
+        .map(|address| deps.api.addr_validate(address))
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut config_store = PrefixedStorage::new(deps.storage, PREFIX_CONFIG);
+    config_store.set(
+        KEY_TRANSFER_BURN,
+        &to_vec(&TransferBurn { rate_bps, exempt })?,
+    );
+
+    Ok(Response::new()
+        .add_attribute("action", "update_transfer_burn")
+        .add_attribute("rate_bps", rate_bps.to_string()))
+}
+
+/// Amount of a transfer to burn. Transfers from or to an exempt account are not
+/// burned, deposits into escrow are burned when paid out (see `escrow_deposit`).
+pub fn compute_burn(
+    store: &dyn Storage,
+    from: &Addr,
+    to: &Addr,
+    amount: u128,
+) -> Result<u128, ContractError> {
+    let burn = read_transfer_burn(store)?;
+    if burn.rate_bps == 0 {
+        return Ok(0);
+    }
+    if burn.exempt.contains(from) || burn.exempt.contains(to) {
+        return Ok(0);
+    }
+    Ok(Uint128::from(amount)
+        .multiply_ratio(burn.rate_bps as u128, 10_000u128)
+        .u128())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate, query, read_balance, read_total_supply};
+    use crate::locks;
+    use crate::msg::{ExecuteMsg, InitialBalance, InstantiateMsg, QueryMsg, TransferBurnResponse};
+    use crate::testing::default_instantiate_msg;
+    use cosmwasm_std::testing::{
+        mock_dependencies_with_balance, mock_env, mock_info, MOCK_CONTRACT_ADDR,
+    };
+    use cosmwasm_std::{from_slice, Deps};
+
+    fn setup(deps: DepsMut) {
+        let instantiate_msg = InstantiateMsg {
+            initial_balances: vec![InitialBalance {
+                address: "addr0000".to_string(),
+                amount: Uint128::from(10_000u128),
+            }],
+            ..default_instantiate_msg()
+        };
+        instantiate(deps, mock_env(), mock_info("creator", &[]), instantiate_msg).unwrap();
+    }
+
+    fn update_transfer_burn(
+        deps: DepsMut,
+        signer: &str,
+        rate_bps: u16,
+        exempt: &[&str],
+    ) -> Result<Response, ContractError> {
+        let msg = ExecuteMsg::UpdateTransferBurn {
+            rate_bps,
+            exempt: exempt.iter().map(|address| address.to_string()).collect(),
+        };
+        execute(deps, mock_env(), mock_info(signer, &[]), msg)
+    }
+
+    fn transfer(deps: DepsMut, recipient: &str, amount: u128) -> Response {
+        let msg = ExecuteMsg::Transfer {
+            recipient: recipient.to_string(),
+            amount: Uint128::from(amount),
+            memo: None,
+            on_behalf_of: None,
+        };
+        execute(deps, mock_env(), mock_info("addr0000", &[]), msg).unwrap()
+    }
+
+    fn balance(deps: Deps, address: &str) -> u128 {
+        read_balance(deps.storage, deps.api, &Addr::unchecked(address)).unwrap()
+    }
+
+    #[test]
+    fn burns_share_of_transfers() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        update_transfer_burn(deps.as_mut(), "creator", 200, &["pool"]).unwrap();
+        let res: TransferBurnResponse =
+            from_slice(&query(deps.as_ref(), mock_env(), QueryMsg::TransferBurn {}).unwrap())
+                .unwrap();
+        assert_eq!(res.rate_bps, 200);
+        assert_eq!(res.exempt, vec!["pool".to_string()]);
+
+        let res = transfer(deps.as_mut(), "addr1111", 1_000);
+        assert!(res
+            .attributes
+            .iter()
+            .any(|attr| attr.key == "burned_amount" && attr.value == "20"));
+        assert!(res.events[0]
+            .attributes
+            .iter()
+            .any(|attr| attr.key == "total_supply" && attr.value == "9980"));
+        assert_eq!(balance(deps.as_ref(), "addr0000"), 9_000);
+        assert_eq!(balance(deps.as_ref(), "addr1111"), 980);
+        assert_eq!(read_total_supply(&deps.storage).unwrap(), 9_980);
+
+        // exempt recipient
+        transfer(deps.as_mut(), "pool", 1_000);
+        assert_eq!(balance(deps.as_ref(), "pool"), 1_000);
+        assert_eq!(read_total_supply(&deps.storage).unwrap(), 9_980);
+    }
+
+    #[test]
+    fn escrow_is_burned_on_payout() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        update_transfer_burn(deps.as_mut(), "creator", 200, &[]).unwrap();
+
+        let release_at = mock_env().block.time.seconds() + 100;
+        let lock_msg = ExecuteMsg::TransferLocked {
+            recipient: "addr1111".to_string(),
+            amount: Uint128::from(1_000u128),
+            release_at,
+        };
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("addr0000", &[]),
+            lock_msg,
+        )
+        .unwrap();
+        assert_eq!(balance(deps.as_ref(), MOCK_CONTRACT_ADDR), 1_000);
+        assert_eq!(read_total_supply(&deps.storage).unwrap(), 10_000);
+
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(100);
+        locks::release_matured(
+            &mut deps.storage,
+            &deps.api,
+            &env,
+            &Addr::unchecked("addr1111"),
+        )
+        .unwrap();
+        assert_eq!(balance(deps.as_ref(), "addr1111"), 980);
+        assert_eq!(balance(deps.as_ref(), MOCK_CONTRACT_ADDR), 0);
+        assert_eq!(read_total_supply(&deps.storage).unwrap(), 9_980);
+    }
+
+    #[test]
+    fn owner_only_and_bounded() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        match update_transfer_burn(deps.as_mut(), "addr0000", 200, &[]).unwrap_err() {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        match update_transfer_burn(deps.as_mut(), "creator", MAX_BURN_BPS + 1, &[]).unwrap_err() {
+            ContractError::BurnRateTooHigh { max, rate } => {
+                assert_eq!(max, MAX_BURN_BPS);
+                assert_eq!(rate, MAX_BURN_BPS + 1);
+            }
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+}
@@ -0,0 +1,167 @@
+use cosmwasm_std::{DepsMut, Env, MessageInfo, Response, Uint128};
+
+use crate::contract::{balance_data, checked_add, perform_transfer, validate_memo};
+use crate::error::ContractError;
+use crate::events;
+use crate::largetransfer;
+use crate::msg::DistributionEntry;
+
+/// Maximum number of entries in an `ExecuteMsg::Distribute`
+pub const MAX_DISTRIBUTION_ENTRIES: usize = 100;
+
+/// Pay every entry from the signer's balance, e.g. a payroll run. Each payment
+/// gets its own transfer event carrying the entry's memo.
+pub fn try_distribute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    entries: Vec<DistributionEntry>,
+) -> Result<Response, ContractError> {
+    if entries.len() > MAX_DISTRIBUTION_ENTRIES {
+        return Err(ContractError::TooManyDistributionEntries {
+            max: MAX_DISTRIBUTION_ENTRIES,
+        });
+    }
+    for entry in entries.iter() {
+        validate_memo(&entry.memo)?;
+    }
+
+    let count = entries.len();
+    let mut res = Response::new();
+    let mut total = Uint128::zero();
+    for entry in entries {
+        let recipient_address = deps.api.addr_validate(&entry.address)?;
+        let amount = entry.amount.u128();
+        largetransfer::assert_not_large(deps.storage, &info.sender, amount)?;
+        let outcome = perform_transfer(
+            deps.storage,
+            deps.api,
+            &deps.querier,
+            &env,
+            &info.sender,
+            &recipient_address,
+            amount,
+        )?;
+        let mut event = events::transfer_event(
+            deps.storage,
+            deps.api,
+            &info.sender,
+            &recipient_address,
+            amount,
+            outcome,
+        )?;
+        if let Some(memo) = entry.memo {
+            event = event.add_attribute("memo", memo);
+        }
+        total = Uint128::from(checked_add(total.u128(), amount)?);
+        res = res.add_event(event);
+    }
+
+    Ok(res
+        .set_data(balance_data(deps.storage, deps.api, &info.sender)?)
+        .add_attribute("action", "distribute")
+        .add_attribute("sender", info.sender)
+        .add_attribute("entries", count.to_string())
+        .add_attribute("total_amount", total.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate, read_balance};
+    use crate::msg::{ExecuteMsg, InitialBalance, InstantiateMsg};
+    use crate::testing::default_instantiate_msg;
+    use cosmwasm_std::testing::{mock_dependencies_with_balance, mock_env, mock_info};
+    use cosmwasm_std::Addr;
+
+    fn setup(deps: DepsMut) {
+        let instantiate_msg = InstantiateMsg {
+            initial_balances: vec![InitialBalance {
+                address: "employer".to_string(),
+                amount: Uint128::from(10_000u128),
+            }],
+            ..default_instantiate_msg()
+        };
+        instantiate(deps, mock_env(), mock_info("creator", &[]), instantiate_msg).unwrap();
+    }
+
+    fn entry(address: &str, amount: u128, memo: Option<&str>) -> DistributionEntry {
+        DistributionEntry {
+            address: address.to_string(),
+            amount: Uint128::from(amount),
+            memo: memo.map(|memo| memo.to_string()),
+        }
+    }
+
+    #[test]
+    fn pays_every_entry_with_its_memo() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+
+        let msg = ExecuteMsg::Distribute {
+            entries: vec![
+                entry("alice", 3000, Some("salary march")),
+                entry("bob", 2000, None),
+            ],
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("employer", &[]), msg).unwrap();
+        assert_eq!(res.events.len(), 2);
+        let memos: Vec<Option<&str>> = res
+            .events
+            .iter()
+            .map(|event| {
+                event
+                    .attributes
+                    .iter()
+                    .find(|attr| attr.key == "memo")
+                    .map(|attr| attr.value.as_str())
+            })
+            .collect();
+        assert_eq!(memos, vec![Some("salary march"), None]);
+        let total = res
+            .attributes
+            .iter()
+            .find(|attr| attr.key == "total_amount")
+            .unwrap();
+        assert_eq!(total.value, "5000");
+
+        let balance =
+            |address: &str| read_balance(&deps.storage, &deps.api, &Addr::unchecked(address));
+        assert_eq!(balance("alice").unwrap(), 3000);
+        assert_eq!(balance("bob").unwrap(), 2000);
+        assert_eq!(balance("employer").unwrap(), 5000);
+    }
+
+    #[test]
+    fn fails_as_a_whole_when_funds_run_out() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+
+        let msg = ExecuteMsg::Distribute {
+            entries: vec![entry("alice", 6000, None), entry("bob", 6000, None)],
+        };
+        match execute(deps.as_mut(), mock_env(), mock_info("employer", &[]), msg).unwrap_err() {
+            ContractError::InsufficientFunds { balance, required } => {
+                assert_eq!(balance, 4000);
+                assert_eq!(required, 6000);
+            }
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn rejects_too_many_entries() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+
+        let msg = ExecuteMsg::Distribute {
+            entries: vec![entry("alice", 1, None); MAX_DISTRIBUTION_ENTRIES + 1],
+        };
+        match execute(deps.as_mut(), mock_env(), mock_info("employer", &[]), msg).unwrap_err() {
+            ContractError::TooManyDistributionEntries { max } => {
+                assert_eq!(max, MAX_DISTRIBUTION_ENTRIES)
+            }
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+}
@@ -0,0 +1,385 @@
+use cosmwasm_std::{
+    coins, from_slice, to_vec, Addr, Api, BankMsg, Decimal, DepsMut, Env, MessageInfo, Order,
+    Response, Storage, Uint128,
+};
+use cosmwasm_storage::{PrefixedStorage, ReadonlyPrefixedStorage};
+
+use crate::contract::{escrow_deposit, escrow_payout, read_shares};
+use crate::error::ContractError;
+use crate::events;
+use crate::msg::DividendResponse;
+use crate::rebase;
+use crate::report;
+use crate::state::{DividendAccount, DividendAsset, DividendPool};
+
+pub const PREFIX_DIVIDEND_POOLS: &[u8] = b"dividend_pools";
+pub const PREFIX_DIVIDEND_ACCOUNTS: &[u8] = b"dividend_accounts";
+
+/// Every balance change settles all pools, so their number is bounded
+pub const MAX_DIVIDEND_POOLS: usize = 8;
+
+// Each pool keeps a cumulative dividend per share. Funding raises it by the
+// funded amount over the shares of all holders at that moment, and holders are
+// settled against it right before their balance changes, so everyone earns in
+// proportion to the balance held while the dividends came in. The contract's
+// own account (escrows, locks) does not earn dividends.
+
+fn asset_key(asset: &DividendAsset) -> Vec<u8> {
+    match asset {
+        DividendAsset::Native { denom } => [b"native:", denom.as_bytes()].concat(),
+        DividendAsset::Token {} => b"token".to_vec(),
+    }
+}
+
+pub fn read_pools(store: &dyn Storage) -> Result<Vec<DividendPool>, ContractError> {
+    let pools_store = ReadonlyPrefixedStorage::new(store, PREFIX_DIVIDEND_POOLS);
+    pools_store
+        .range(None, None, Order::Ascending)
+        .map(|(_, data)| Ok(from_slice(&data)?))
+        .collect()
+}
+
+fn read_account(
+    store: &dyn Storage,
+    asset: &DividendAsset,
+    owner: &Addr,
+) -> Result<DividendAccount, ContractError> {
+    let accounts_store =
+        ReadonlyPrefixedStorage::multilevel(store, &[PREFIX_DIVIDEND_ACCOUNTS, &asset_key(asset)]);
+    match accounts_store.get(owner.as_str().as_bytes()) {
+        Some(data) => Ok(from_slice(&data)?),
+        None => Ok(DividendAccount::default()),
+    }
+}
+
+fn settled(pool: &DividendPool, account: DividendAccount, shares: u128) -> DividendAccount {
+    DividendAccount {
+        index: pool.index,
+        pending: account.pending + Uint128::from(shares) * (pool.index - account.index),
+    }
+}
+
+/// Settles the dividends of `owner` up to now. Has to run before every change
+/// of its shares.
+pub fn settle(
+    store: &mut dyn Storage,
+    api: &dyn Api,
+    env: &Env,
+    owner: &Addr,
+) -> Result<(), ContractError> {
+    if owner == env.contract.address {
+        return Ok(());
+    }
+    let shares = read_shares(store, api, owner)?;
+    for pool in read_pools(store)? {
+        let account = read_account(store, &pool.asset, owner)?;
+        if account.index == pool.index {
+            continue;
+        }
+        let account = settled(&pool, account, shares);
+        let mut accounts_store = PrefixedStorage::multilevel(
+            store,
+            &[PREFIX_DIVIDEND_ACCOUNTS, &asset_key(&pool.asset)],
+        );
+        accounts_store.set(owner.as_str().as_bytes(), &to_vec(&account)?);
+    }
+    Ok(())
+}
+
+/// Dividends `owner` could claim now, per funded asset
+pub fn read_pending(
+    store: &dyn Storage,
+    api: &dyn Api,
+    env: &Env,
+    owner: &Addr,
+) -> Result<Vec<DividendResponse>, ContractError> {
+    let shares = if owner == env.contract.address {
+        0
+    } else {
+        read_shares(store, api, owner)?
+    };
+    read_pools(store)?
+        .into_iter()
+        .map(|pool| {
+            let account = settled(&pool, read_account(store, &pool.asset, owner)?, shares);
+            Ok(DividendResponse {
+                asset: pool.asset,
+                amount: account.pending,
+            })
+        })
+        .collect()
+}
+
+fn add_to_pool(
+    store: &mut dyn Storage,
+    asset: DividendAsset,
+    amount: Uint128,
+    holder_shares: u128,
+) -> Result<(), ContractError> {
+    let key = asset_key(&asset);
+    let mut pools_store = PrefixedStorage::new(store, PREFIX_DIVIDEND_POOLS);
+    let mut pool = match pools_store.get(&key) {
+        Some(data) => from_slice(&data)?,
+        None => DividendPool {
+            asset,
+            index: Decimal::zero(),
+        },
+    };
+    pool.index += Decimal::from_ratio(amount, holder_shares);
+    pools_store.set(&key, &to_vec(&pool)?);
+
+    let pools = read_pools(store)?.len();
+    if pools > MAX_DIVIDEND_POOLS {
+        return Err(ContractError::TooManyDividendPools {
+            max: MAX_DIVIDEND_POOLS,
+        });
+    }
+    Ok(())
+}
+
+/// Split the attached coins and `amount` tokens of the signer among all
+/// current holders, pro rata to their balances
+pub fn try_fund_dividends(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    let amount = amount.unwrap_or_default();
+    if amount.is_zero() && info.funds.iter().all(|coin| coin.amount.is_zero()) {
+        return Err(ContractError::InvalidDividendFunding {});
+    }
+    let mut res = Response::new();
+    if !amount.is_zero() {
+        let outcome = escrow_deposit(
+            deps.storage,
+            deps.api,
+            &deps.querier,
+            &env,
+            &info.sender,
+            amount.u128(),
+        )?;
+        res = res.add_event(events::transfer_event(
+            deps.storage,
+            deps.api,
+            &info.sender,
+            &env.contract.address,
+            amount.u128(),
+            outcome,
+        )?);
+    }
+
+    // Shares on the contract's own account do not earn dividends
+    let holder_shares = rebase::read_total_shares(deps.storage)?
+        - read_shares(deps.storage, deps.api, &env.contract.address)?;
+    if holder_shares == 0 {
+        return Err(ContractError::NoDividendHolders {});
+    }
+    if !amount.is_zero() {
+        add_to_pool(deps.storage, DividendAsset::Token {}, amount, holder_shares)?;
+    }
+    for coin in info.funds.iter().filter(|coin| !coin.amount.is_zero()) {
+        let asset = DividendAsset::Native {
+            denom: coin.denom.clone(),
+        };
+        add_to_pool(deps.storage, asset, coin.amount, holder_shares)?;
+    }
+
+    Ok(res
+        .add_attribute("action", "fund_dividends")
+        .add_attribute("funder", info.sender)
+        .add_attribute("amount", amount.to_string()))
+}
+
+/// Pay out all pending dividends of the signer
+pub fn try_claim_dividends(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    settle(deps.storage, deps.api, &env, &info.sender)?;
+    let mut res = Response::new()
+        .add_attribute("action", "claim_dividends")
+        .add_attribute("account", info.sender.clone());
+    for pool in read_pools(deps.storage)? {
+        let mut account = read_account(deps.storage, &pool.asset, &info.sender)?;
+        if account.pending.is_zero() {
+            continue;
+        }
+        let pending = account.pending;
+        account.pending = Uint128::zero();
+        let mut accounts_store = PrefixedStorage::multilevel(
+            deps.storage,
+            &[PREFIX_DIVIDEND_ACCOUNTS, &asset_key(&pool.asset)],
+        );
+        accounts_store.set(info.sender.as_str().as_bytes(), &to_vec(&account)?);
+
+        match pool.asset {
+            DividendAsset::Native { denom } => {
+                res = res.add_message(BankMsg::Send {
+                    to_address: info.sender.to_string(),
+                    amount: coins(pending.u128(), denom),
+                });
+            }
+            DividendAsset::Token {} => {
+                let outcome = escrow_payout(
+                    deps.storage,
+                    deps.api,
+                    &deps.querier,
+                    &env,
+                    &info.sender,
+                    pending.u128(),
+                )?;
+                res = res.add_event(events::transfer_event(
+                    deps.storage,
+                    deps.api,
+                    &env.contract.address,
+                    &info.sender,
+                    pending.u128(),
+                    outcome,
+                )?);
+                report::record_rewards(deps.storage, &env, &info.sender, pending.u128())?;
+                res = res.add_attribute("amount", pending.to_string());
+            }
+        }
+    }
+    Ok(res)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate, query, read_balance};
+    use crate::msg::{
+        ExecuteMsg, InitialBalance, InstantiateMsg, PendingDividendsResponse, QueryMsg,
+    };
+    use crate::testing::default_instantiate_msg;
+    use cosmwasm_std::testing::{
+        mock_dependencies_with_balance, mock_env, mock_info, MOCK_CONTRACT_ADDR,
+    };
+    use cosmwasm_std::{coin, from_slice, CosmosMsg, Deps};
+
+    fn setup(deps: DepsMut) {
+        let instantiate_msg = InstantiateMsg {
+            initial_balances: vec![
+                InitialBalance {
+                    address: "addr0000".to_string(),
+                    amount: Uint128::from(1000u128),
+                },
+                InitialBalance {
+                    address: "addr1111".to_string(),
+                    amount: Uint128::from(3000u128),
+                },
+            ],
+            ..default_instantiate_msg()
+        };
+        instantiate(deps, mock_env(), mock_info("creator", &[]), instantiate_msg).unwrap();
+    }
+
+    fn fund_native(deps: DepsMut, amount: u128) {
+        let msg = ExecuteMsg::FundDividends { amount: None };
+        let info = mock_info("funder", &[coin(amount, "uatom")]);
+        execute(deps, mock_env(), info, msg).unwrap();
+    }
+
+    fn pending(deps: Deps, address: &str) -> Vec<DividendResponse> {
+        let query_msg = QueryMsg::PendingDividends {
+            address: address.to_string(),
+        };
+        let res: PendingDividendsResponse =
+            from_slice(&query(deps, mock_env(), query_msg).unwrap()).unwrap();
+        res.dividends
+    }
+
+    fn native(amount: u128) -> Vec<DividendResponse> {
+        vec![DividendResponse {
+            asset: DividendAsset::Native {
+                denom: "uatom".to_string(),
+            },
+            amount: Uint128::from(amount),
+        }]
+    }
+
+    #[test]
+    fn holders_earn_on_balance_held_at_funding() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        fund_native(deps.as_mut(), 400);
+        assert_eq!(pending(deps.as_ref(), "addr0000"), native(100));
+        assert_eq!(pending(deps.as_ref(), "addr1111"), native(300));
+
+        // dividends funded before a transfer stay with the sender
+        let transfer_msg = ExecuteMsg::Transfer {
+            recipient: "addr1111".to_string(),
+            amount: Uint128::from(1000u128),
+            memo: None,
+            on_behalf_of: None,
+        };
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("addr0000", &[]),
+            transfer_msg,
+        )
+        .unwrap();
+        fund_native(deps.as_mut(), 400);
+        assert_eq!(pending(deps.as_ref(), "addr0000"), native(100));
+        assert_eq!(pending(deps.as_ref(), "addr1111"), native(700));
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("addr1111", &[]),
+            ExecuteMsg::ClaimDividends {},
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "addr1111".to_string(),
+                amount: coins(700, "uatom"),
+            })
+        );
+        assert_eq!(pending(deps.as_ref(), "addr1111"), native(0));
+    }
+
+    #[test]
+    fn token_dividends_skip_the_contract_account() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        let msg = ExecuteMsg::FundDividends {
+            amount: Some(Uint128::from(800u128)),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+        assert_eq!(
+            pending(deps.as_ref(), MOCK_CONTRACT_ADDR)[0].amount,
+            Uint128::zero()
+        );
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("addr1111", &[]),
+            ExecuteMsg::ClaimDividends {},
+        )
+        .unwrap();
+        let balance = |address: &str| {
+            read_balance(&deps.storage, &deps.api, &Addr::unchecked(address)).unwrap()
+        };
+        assert_eq!(balance("addr1111"), 3750);
+        // the share of addr0000 on its remaining 200 tokens
+        assert_eq!(balance(MOCK_CONTRACT_ADDR), 50);
+    }
+
+    #[test]
+    fn funding_requires_an_amount() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        let msg = ExecuteMsg::FundDividends { amount: None };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("funder", &[]), msg).unwrap_err();
+        match err {
+            ContractError::InvalidDividendFunding {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+}
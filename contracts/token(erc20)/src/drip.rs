@@ -0,0 +1,223 @@
+use cosmwasm_std::{from_slice, to_vec, Addr, DepsMut, Env, Response, Storage, Uint128};
+use cosmwasm_storage::{PrefixedStorage, ReadonlyPrefixedStorage};
+
+use crate::contract::{checked_add, checked_sub, escrow_payout, PREFIX_CONFIG};
+use crate::error::ContractError;
+use crate::events;
+use crate::msg::{DripInfo, DripResponse};
+use crate::state::Drip;
+
+pub const KEY_DRIP: &[u8] = b"drip";
+
+// Tokens preminted at instantiation onto the contract's own account and
+// released linearly to the beneficiary. Anyone can trigger a release, so
+// emissions keep flowing without a minter key.
+
+pub fn read_drip(store: &dyn Storage) -> Result<Option<Drip>, ContractError> {
+    let config_store = ReadonlyPrefixedStorage::new(store, PREFIX_CONFIG);
+    match config_store.get(KEY_DRIP) {
+        Some(data) => Ok(Some(from_slice(&data)?)),
+        None => Ok(None),
+    }
+}
+
+fn write_drip(store: &mut dyn Storage, drip: &Drip) -> Result<(), ContractError> {
+    let mut config_store = PrefixedStorage::new(store, PREFIX_CONFIG);
+    config_store.set(KEY_DRIP, &to_vec(drip)?);
+    Ok(())
+}
+
+/// Validate and store the drip schedule. The caller credits the premint.
+pub fn init_drip(
+    store: &mut dyn Storage,
+    env: &Env,
+    beneficiary: Addr,
+    info: &DripInfo,
+) -> Result<(), ContractError> {
+    if info.end <= info.start || info.end <= env.block.time.seconds() {
+        return Err(ContractError::InvalidDripSchedule {});
+    }
+    write_drip(
+        store,
+        &Drip {
+            beneficiary,
+            amount: info.amount,
+            released: Uint128::zero(),
+            start: info.start,
+            end: info.end,
+        },
+    )
+}
+
+/// Amount of the drip released by now, linear between start and end
+pub fn vested(drip: &Drip, env: &Env) -> u128 {
+    let now = env.block.time.seconds();
+    if now <= drip.start {
+        return 0;
+    }
+    if now >= drip.end {
+        return drip.amount.u128();
+    }
+    drip.amount
+        .multiply_ratio(now - drip.start, drip.end - drip.start)
+        .u128()
+}
+
+pub fn drip_response(drip: Drip, env: &Env) -> DripResponse {
+    let releasable = Uint128::from(vested(&drip, env)) - drip.released;
+    DripResponse {
+        beneficiary: drip.beneficiary.to_string(),
+        amount: drip.amount,
+        released: drip.released,
+        start: drip.start,
+        end: drip.end,
+        releasable,
+    }
+}
+
+/// Pay the vested part of the drip out to the beneficiary. Anyone can call.
+pub fn try_release_drip(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let mut drip = read_drip(deps.storage)?.ok_or(ContractError::NoDrip {})?;
+    let amount = checked_sub(vested(&drip, &env), drip.released.u128())?;
+    if amount == 0 {
+        return Err(ContractError::NothingToRelease {});
+    }
+    drip.released = Uint128::from(checked_add(drip.released.u128(), amount)?);
+    write_drip(deps.storage, &drip)?;
+    let outcome = escrow_payout(
+        deps.storage,
+        deps.api,
+        &deps.querier,
+        &env,
+        &drip.beneficiary,
+        amount,
+    )?;
+    let transfer = events::transfer_event(
+        deps.storage,
+        deps.api,
+        &env.contract.address,
+        &drip.beneficiary,
+        amount,
+        outcome,
+    )?;
+
+    Ok(Response::new()
+        .add_event(transfer)
+        .add_attribute("action", "release_drip")
+        .add_attribute("beneficiary", drip.beneficiary)
+        .add_attribute("amount", amount.to_string())
+        .add_attribute("released", drip.released.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate, query, read_balance, read_total_supply};
+    use crate::msg::{ExecuteMsg, InitialBalance, InstantiateMsg, QueryMsg};
+    use crate::testing::{default_instantiate_msg, env_at};
+    use cosmwasm_std::testing::{mock_dependencies_with_balance, mock_info};
+    use cosmwasm_std::{from_slice, Deps};
+
+    fn instantiate_msg(drip: Option<DripInfo>) -> InstantiateMsg {
+        InstantiateMsg {
+            initial_balances: vec![InitialBalance {
+                address: "addr0000".to_string(),
+                amount: Uint128::from(1000u128),
+            }],
+            drip,
+            ..default_instantiate_msg()
+        }
+    }
+
+    fn setup(deps: DepsMut) {
+        let drip = DripInfo {
+            beneficiary: "treasury".to_string(),
+            amount: Uint128::from(400u128),
+            start: 1_000,
+            end: 2_000,
+        };
+        instantiate(
+            deps,
+            env_at(500),
+            mock_info("creator", &[]),
+            instantiate_msg(Some(drip)),
+        )
+        .unwrap();
+    }
+
+    fn balance(deps: Deps, address: &str) -> u128 {
+        read_balance(deps.storage, deps.api, &Addr::unchecked(address)).unwrap()
+    }
+
+    fn release(deps: DepsMut, seconds: u64) -> Result<Response, ContractError> {
+        execute(
+            deps,
+            env_at(seconds),
+            mock_info("anyone", &[]),
+            ExecuteMsg::ReleaseDrip {},
+        )
+    }
+
+    #[test]
+    fn premint_is_held_by_the_contract() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        let contract = env_at(500).contract.address;
+        assert_eq!(balance(deps.as_ref(), contract.as_str()), 400);
+        assert_eq!(read_total_supply(&deps.storage).unwrap(), 1_400);
+    }
+
+    #[test]
+    fn releases_linearly_to_the_beneficiary() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+
+        let err = release(deps.as_mut(), 900).unwrap_err();
+        assert!(matches!(err, ContractError::NothingToRelease {}));
+
+        release(deps.as_mut(), 1_250).unwrap();
+        assert_eq!(balance(deps.as_ref(), "treasury"), 100);
+        let err = release(deps.as_mut(), 1_250).unwrap_err();
+        assert!(matches!(err, ContractError::NothingToRelease {}));
+
+        let res: DripResponse =
+            from_slice(&query(deps.as_ref(), env_at(1_500), QueryMsg::Drip {}).unwrap()).unwrap();
+        assert_eq!(res.released, Uint128::from(100u128));
+        assert_eq!(res.releasable, Uint128::from(100u128));
+
+        release(deps.as_mut(), 5_000).unwrap();
+        assert_eq!(balance(deps.as_ref(), "treasury"), 400);
+        let contract = env_at(500).contract.address;
+        assert_eq!(balance(deps.as_ref(), contract.as_str()), 0);
+    }
+
+    #[test]
+    fn rejects_bad_schedules_and_missing_drips() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        let drip = DripInfo {
+            beneficiary: "treasury".to_string(),
+            amount: Uint128::from(400u128),
+            start: 1_000,
+            end: 1_000,
+        };
+        let err = instantiate(
+            deps.as_mut(),
+            env_at(500),
+            mock_info("creator", &[]),
+            instantiate_msg(Some(drip)),
+        )
+        .unwrap_err();
+        assert!(matches!(err, ContractError::InvalidDripSchedule {}));
+
+        let mut deps = mock_dependencies_with_balance(&[]);
+        instantiate(
+            deps.as_mut(),
+            env_at(500),
+            mock_info("creator", &[]),
+            instantiate_msg(None),
+        )
+        .unwrap();
+        let err = release(deps.as_mut(), 1_500).unwrap_err();
+        assert!(matches!(err, ContractError::NoDrip {}));
+    }
+}
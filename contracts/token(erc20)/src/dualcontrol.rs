@@ -0,0 +1,600 @@
+use cosmwasm_std::{from_slice, to_vec, Addr, DepsMut, Env, MessageInfo, Order, Response, Storage};
+use cosmwasm_storage::{PrefixedStorage, ReadonlyPrefixedStorage};
+use std::convert::TryInto;
+
+use crate::contract::{assert_owner, dispatch, read_addr, PREFIX_CONFIG};
+use crate::error::ContractError;
+use crate::msg::{AdminProposalResponse, ExecuteMsg, Role};
+use crate::pagination::{Cursor, Page, LIST_LIMITS};
+use crate::renounce;
+use crate::state::AdminProposal;
+
+pub const KEY_CONFIRMER: &[u8] = b"confirmer";
+pub const PREFIX_ADMIN_PROPOSALS: &[u8] = b"admin_proposals";
+pub const KEY_ADMIN_PROPOSAL_COUNT: &[u8] = b"admin_proposal_count";
+
+// With dual control enabled the holder of the role a privileged message needs
+// cannot execute it directly. The holder or the confirmer proposes it and the
+// other confirms it, which executes it as the holder.
+
+pub fn read_confirmer(store: &dyn Storage) -> Result<Option<Addr>, ContractError> {
+    let config_store = ReadonlyPrefixedStorage::new(store, PREFIX_CONFIG);
+    read_addr(&config_store, KEY_CONFIRMER)
+}
+
+/// Messages that change the configuration of the token on behalf of its owner
+pub fn is_privileged(msg: &ExecuteMsg) -> bool {
+    matches!(
+        msg,
+        ExecuteMsg::Pause {}
+            | ExecuteMsg::Unpause {}
+            | ExecuteMsg::UpdateFeeConfig { .. }
+            | ExecuteMsg::UpdateFeeCurve { .. }
+            | ExecuteMsg::UpdateTransferLimits { .. }
+            | ExecuteMsg::SetMinTransferAmount { .. }
+            | ExecuteMsg::UpdateTransferBurn { .. }
+            | ExecuteMsg::SetRateLimit { .. }
+            | ExecuteMsg::SetRebaser { .. }
+            | ExecuteMsg::SetBucket { .. }
+            | ExecuteMsg::MoveBetweenBuckets { .. }
+            | ExecuteMsg::UpdateMarketingAdmin { .. }
+            | ExecuteMsg::UpdateTokenMetadata { .. }
+            | ExecuteMsg::SetBurnReceipt { .. }
+            | ExecuteMsg::UpdateBuyback { .. }
+            | ExecuteMsg::UpdateTimelock { .. }
+            | ExecuteMsg::CancelTimelockedTransfer { .. }
+            | ExecuteMsg::SetDualControl { .. }
+            | ExecuteMsg::RecoverOwnBalance { .. }
+            | ExecuteMsg::SweepForeign { .. }
+            | ExecuteMsg::SweepNative { .. }
+            | ExecuteMsg::SetComplianceContract { .. }
+            | ExecuteMsg::ClearComplianceContract {}
+            | ExecuteMsg::SetLocker { .. }
+            | ExecuteMsg::Freeze { .. }
+            | ExecuteMsg::Unfreeze { .. }
+            | ExecuteMsg::SetTrustedForwarder { .. }
+            | ExecuteMsg::UpdateSupplyExclusions { .. }
+            | ExecuteMsg::SetTreasury { .. }
+            | ExecuteMsg::Mint { .. }
+            | ExecuteMsg::MintMany { .. }
+            | ExecuteMsg::UpdateMinter { .. }
+            | ExecuteMsg::ForceTransfer { .. }
+            | ExecuteMsg::UpdateClawbackAdmin { .. }
+            | ExecuteMsg::RenounceOwnership {}
+            | ExecuteMsg::RenounceRole { role: Role::Owner }
+    )
+}
+
+// Role authorizing a privileged message, whose holder executes it once confirmed
+fn required_role(msg: &ExecuteMsg) -> Role {
+    match msg {
+        ExecuteMsg::Mint { .. } | ExecuteMsg::MintMany { .. } | ExecuteMsg::UpdateMinter { .. } => {
+            Role::Minter
+        }
+        ExecuteMsg::ForceTransfer { .. } | ExecuteMsg::UpdateClawbackAdmin { .. } => {
+            Role::ClawbackAdmin
+        }
+        _ => Role::Owner,
+    }
+}
+
+/// Errors if the holder of the role a privileged message needs sends it while
+/// dual control is enabled
+pub fn assert_not_required(
+    store: &dyn Storage,
+    sender: &Addr,
+    msg: &ExecuteMsg,
+) -> Result<(), ContractError> {
+    if read_confirmer(store)?.is_none() || !is_privileged(msg) {
+        return Ok(());
+    }
+    if renounce::read_holder(store, required_role(msg))?.as_ref() == Some(sender) {
+        return Err(ContractError::DualControlRequired {});
+    }
+    Ok(())
+}
+
+pub fn read_admin_proposal(store: &dyn Storage, id: u64) -> Result<AdminProposal, ContractError> {
+    let proposals_store = ReadonlyPrefixedStorage::new(store, PREFIX_ADMIN_PROPOSALS);
+    match proposals_store.get(&id.to_be_bytes()) {
+        Some(data) => Ok(from_slice(&data)?),
+        None => Err(ContractError::AdminProposalNotFound { id }),
+    }
+}
+
+pub fn admin_proposal_response(id: u64, proposal: AdminProposal) -> AdminProposalResponse {
+    AdminProposalResponse {
+        id,
+        proposer: proposal.proposer.to_string(),
+        action: proposal.action,
+        proposed_at: proposal.proposed_at,
+    }
+}
+
+/// Pending proposals ordered by id
+pub fn read_admin_proposals(
+    store: &dyn Storage,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> Result<Vec<AdminProposalResponse>, ContractError> {
+    let page = Page::new(start_after, limit, &LIST_LIMITS, Order::Ascending);
+    let proposals_store = ReadonlyPrefixedStorage::new(store, PREFIX_ADMIN_PROPOSALS);
+    proposals_store
+        .range(page.start(), page.end(), page.order)
+        .take(page.limit)
+        .map(|(key, data)| {
+            let id = u64::from_key(&key)?;
+            Ok(admin_proposal_response(id, from_slice(&data)?))
+        })
+        .collect()
+}
+
+// Errors with Unauthorized unless `sender` is the confirmer or holds the role
+// `action` needs
+fn assert_admin(
+    store: &dyn Storage,
+    sender: &Addr,
+    action: &ExecuteMsg,
+) -> Result<(), ContractError> {
+    if read_confirmer(store)?.as_ref() == Some(sender)
+        || renounce::read_holder(store, required_role(action))?.as_ref() == Some(sender)
+    {
+        return Ok(());
+    }
+    Err(ContractError::Unauthorized {})
+}
+
+/// Require a second key for privileged messages, or stop doing so with `None`.
+/// Owner only; once enabled, changing it needs a confirmed proposal too.
+pub fn try_set_dual_control(
+    deps: DepsMut,
+    info: MessageInfo,
+    confirmer: Option<String>,
+) -> Result<Response, ContractError> {
+    assert_owner(deps.storage, &info.sender)?;
+    let confirmer = confirmer
+        .map(|confirmer| deps.api.addr_validate(&confirmer))
+        .transpose()?;
+    if confirmer.as_ref() == Some(&info.sender) {
+        return Err(ContractError::InvalidConfirmer {});
+    }
+    let mut config_store = PrefixedStorage::new(deps.storage, PREFIX_CONFIG);
+    match &confirmer {
+        Some(confirmer) => config_store.set(KEY_CONFIRMER, confirmer.as_str().as_bytes()),
+        None => config_store.remove(KEY_CONFIRMER),
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "set_dual_control")
+        .add_attribute(
+            "confirmer",
+            confirmer.map(|addr| addr.to_string()).unwrap_or_default(),
+        ))
+}
+
+/// Propose a privileged message. Confirmer or holder of the role it needs only.
+pub fn try_propose(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    action: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    if read_confirmer(deps.storage)?.is_none() {
+        return Err(ContractError::DualControlDisabled {});
+    }
+    if !is_privileged(&action) {
+        return Err(ContractError::NotPrivileged {});
+    }
+    assert_admin(deps.storage, &info.sender, &action)?;
+
+    let mut config_store = PrefixedStorage::new(deps.storage, PREFIX_CONFIG);
+    let id = match config_store.get(KEY_ADMIN_PROPOSAL_COUNT) {
+        Some(data) => match data[..].try_into() {
+            Ok(bytes) => u64::from_be_bytes(bytes),
+            Err(_) => return Err(ContractError::CorruptedDataFound {}),
+        },
+        None => 0u64,
+    };
+    let next_id = id.checked_add(1).ok_or(ContractError::Overflow {})?;
+    config_store.set(KEY_ADMIN_PROPOSAL_COUNT, &next_id.to_be_bytes());
+
+    let mut proposals_store = PrefixedStorage::new(deps.storage, PREFIX_ADMIN_PROPOSALS);
+    proposals_store.set(
+        &id.to_be_bytes(),
+        &to_vec(&AdminProposal {
+            proposer: info.sender.clone(),
+            action,
+            proposed_at: env.block.time.seconds(),
+        })?,
+    );
+
+    Ok(Response::new()
+        .add_attribute("action", "propose_admin_action")
+        .add_attribute("id", id.to_string())
+        .add_attribute("proposer", info.sender))
+}
+
+/// Confirm and execute a proposal of the other admin key
+pub fn try_confirm(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    let proposal = read_admin_proposal(deps.storage, id)?;
+    assert_admin(deps.storage, &info.sender, &proposal.action)?;
+    if proposal.proposer == info.sender {
+        return Err(ContractError::SecondAdminRequired {});
+    }
+    let mut proposals_store = PrefixedStorage::new(deps.storage, PREFIX_ADMIN_PROPOSALS);
+    proposals_store.remove(&id.to_be_bytes());
+
+    let holder = match renounce::read_holder(deps.storage, required_role(&proposal.action))? {
+        Some(holder) => holder,
+        None => return Err(ContractError::Unauthorized {}),
+    };
+    let holder_info = MessageInfo {
+        sender: holder,
+        funds: vec![],
+    };
+    let res = dispatch(deps, env, holder_info, proposal.action)?;
+    Ok(res
+        .add_attribute("admin_proposal_id", id.to_string())
+        .add_attribute("confirmer", info.sender))
+}
+
+/// Drop a proposal. Confirmer or holder of the role it needs only.
+pub fn try_cancel(deps: DepsMut, info: MessageInfo, id: u64) -> Result<Response, ContractError> {
+    let proposal = read_admin_proposal(deps.storage, id)?;
+    assert_admin(deps.storage, &info.sender, &proposal.action)?;
+    let mut proposals_store = PrefixedStorage::new(deps.storage, PREFIX_ADMIN_PROPOSALS);
+    proposals_store.remove(&id.to_be_bytes());
+
+    Ok(Response::new()
+        .add_attribute("action", "cancel_admin_action")
+        .add_attribute("id", id.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clawback;
+    use crate::contract::{execute, instantiate, query, read_balance};
+    use crate::mint;
+    use crate::msg::{
+        AdminProposalsResponse, InitialBalance, InstantiateMsg, MinterInfo, QueryMsg,
+    };
+    use crate::pause::read_pause_state;
+    use crate::state::Minter;
+    use crate::testing::{default_instantiate_msg, env_at};
+    use cosmwasm_std::testing::{mock_dependencies_with_balance, mock_info, MockApi};
+    use cosmwasm_std::{attr, Timestamp, Uint128};
+    use cw_utils::Expiration;
+
+    fn setup(mut deps: DepsMut) {
+        let instantiate_msg = InstantiateMsg {
+            initial_balances: vec![InitialBalance {
+                address: "addr0000".to_string(),
+                amount: Uint128::from(10_000u128),
+            }],
+            clawback_admin: Some("creator".to_string()),
+            mint: Some(MinterInfo {
+                minter: "creator".to_string(),
+                mint_deadline: None,
+            }),
+            ..default_instantiate_msg()
+        };
+        instantiate(
+            deps.branch(),
+            env_at(0),
+            mock_info("creator", &[]),
+            instantiate_msg,
+        )
+        .unwrap();
+        let msg = ExecuteMsg::SetDualControl {
+            confirmer: Some("guardian".to_string()),
+        };
+        execute(deps, env_at(0), mock_info("creator", &[]), msg).unwrap();
+    }
+
+    fn propose(deps: DepsMut, sender: &str, action: ExecuteMsg) -> Result<Response, ContractError> {
+        let msg = ExecuteMsg::ProposeAdminAction {
+            action: Box::new(action),
+        };
+        execute(deps, env_at(100), mock_info(sender, &[]), msg)
+    }
+
+    // Checks the owner cannot send `action` directly, then executes it through a
+    // proposal the guardian confirms
+    fn execute_confirmed(mut deps: DepsMut, action: ExecuteMsg) -> Response {
+        let err = execute(
+            deps.branch(),
+            env_at(100),
+            mock_info("creator", &[]),
+            action.clone(),
+        )
+        .unwrap_err();
+        match err {
+            ContractError::DualControlRequired {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        let res = propose(deps.branch(), "creator", action).unwrap();
+        let id = res
+            .attributes
+            .iter()
+            .find(|attr| attr.key == "id")
+            .unwrap()
+            .value
+            .parse()
+            .unwrap();
+        let msg = ExecuteMsg::ConfirmAdminAction { id };
+        execute(deps, env_at(200), mock_info("guardian", &[]), msg).unwrap()
+    }
+
+    fn balance(storage: &dyn Storage, address: &str) -> u128 {
+        read_balance(storage, &MockApi::default(), &Addr::unchecked(address)).unwrap()
+    }
+
+    #[test]
+    fn owner_needs_a_confirmed_proposal() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+
+        let err = execute(
+            deps.as_mut(),
+            env_at(100),
+            mock_info("creator", &[]),
+            ExecuteMsg::Pause {},
+        )
+        .unwrap_err();
+        match err {
+            ContractError::DualControlRequired {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        let res = propose(deps.as_mut(), "creator", ExecuteMsg::Pause {}).unwrap();
+        assert!(res.attributes.contains(&attr("id", "0")));
+        assert!(!read_pause_state(&deps.storage).unwrap().paused);
+
+        let res: AdminProposalsResponse = from_slice(
+            &query(
+                deps.as_ref(),
+                env_at(100),
+                QueryMsg::AdminProposals {
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(res.proposals.len(), 1);
+        assert_eq!(res.proposals[0].proposer, "creator");
+        assert_eq!(res.proposals[0].action, ExecuteMsg::Pause {});
+
+        let msg = ExecuteMsg::ConfirmAdminAction { id: 0 };
+        let err = execute(
+            deps.as_mut(),
+            env_at(200),
+            mock_info("creator", &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        match err {
+            ContractError::SecondAdminRequired {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        let err = execute(
+            deps.as_mut(),
+            env_at(200),
+            mock_info("addr0000", &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        match err {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        let res = execute(deps.as_mut(), env_at(200), mock_info("guardian", &[]), msg).unwrap();
+        assert!(res.attributes.contains(&attr("confirmer", "guardian")));
+        assert!(read_pause_state(&deps.storage).unwrap().paused);
+        match read_admin_proposal(&deps.storage, 0).unwrap_err() {
+            ContractError::AdminProposalNotFound { id } => assert_eq!(id, 0),
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn mint_needs_dual_control() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        let action = ExecuteMsg::Mint {
+            recipient: "addr1111".to_string(),
+            amount: Uint128::from(100u128),
+        };
+        execute_confirmed(deps.as_mut(), action);
+        assert_eq!(balance(&deps.storage, "addr1111"), 100);
+    }
+
+    #[test]
+    fn mint_many_needs_dual_control() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        let action = ExecuteMsg::MintMany {
+            mints: vec![
+                InitialBalance {
+                    address: "addr1111".to_string(),
+                    amount: Uint128::from(100u128),
+                },
+                InitialBalance {
+                    address: "addr2222".to_string(),
+                    amount: Uint128::from(200u128),
+                },
+            ],
+        };
+        execute_confirmed(deps.as_mut(), action);
+        assert_eq!(balance(&deps.storage, "addr1111"), 100);
+        assert_eq!(balance(&deps.storage, "addr2222"), 200);
+    }
+
+    #[test]
+    fn confirmed_mint_respects_the_deadline() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        let minter = Minter {
+            minter: Addr::unchecked("creator"),
+            mint_deadline: Some(Expiration::AtTime(Timestamp::from_seconds(150))),
+        };
+        mint::write_minter(&mut deps.storage, &minter).unwrap();
+
+        // proposed before the deadline, confirmed after it
+        let action = ExecuteMsg::Mint {
+            recipient: "addr1111".to_string(),
+            amount: Uint128::from(100u128),
+        };
+        propose(deps.as_mut(), "creator", action).unwrap();
+        let msg = ExecuteMsg::ConfirmAdminAction { id: 0 };
+        let err = execute(deps.as_mut(), env_at(200), mock_info("guardian", &[]), msg).unwrap_err();
+        match err {
+            ContractError::MintingEnded {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        assert_eq!(balance(&deps.storage, "addr1111"), 0);
+    }
+
+    #[test]
+    fn update_minter_needs_dual_control() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        let action = ExecuteMsg::UpdateMinter {
+            new_minter: Some("addr1111".to_string()),
+        };
+        execute_confirmed(deps.as_mut(), action);
+        let minter = mint::read_minter(&deps.storage).unwrap().unwrap();
+        assert_eq!(minter.minter, Addr::unchecked("addr1111"));
+    }
+
+    #[test]
+    fn force_transfer_needs_dual_control() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        let action = ExecuteMsg::ForceTransfer {
+            owner: "addr0000".to_string(),
+            recipient: "addr1111".to_string(),
+            amount: Uint128::from(100u128),
+        };
+        execute_confirmed(deps.as_mut(), action);
+        assert_eq!(balance(&deps.storage, "addr0000"), 9_900);
+        assert_eq!(balance(&deps.storage, "addr1111"), 100);
+    }
+
+    #[test]
+    fn update_clawback_admin_needs_dual_control() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        let action = ExecuteMsg::UpdateClawbackAdmin {
+            admin: Some("addr1111".to_string()),
+        };
+        execute_confirmed(deps.as_mut(), action);
+        assert_eq!(
+            clawback::read_clawback_admin(&deps.storage).unwrap(),
+            Some(Addr::unchecked("addr1111"))
+        );
+    }
+
+    #[test]
+    fn role_holders_other_than_the_owner_need_dual_control() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        let minter = Minter {
+            minter: Addr::unchecked("minter"),
+            mint_deadline: None,
+        };
+        mint::write_minter(&mut deps.storage, &minter).unwrap();
+        clawback::write_clawback_admin(&mut deps.storage, Some(&Addr::unchecked("sheriff")));
+
+        let action = ExecuteMsg::Mint {
+            recipient: "addr1111".to_string(),
+            amount: Uint128::from(100u128),
+        };
+        let err = execute(
+            deps.as_mut(),
+            env_at(100),
+            mock_info("minter", &[]),
+            action.clone(),
+        )
+        .unwrap_err();
+        match err {
+            ContractError::DualControlRequired {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        // the owner does not hold the role, so it can neither propose nor confirm
+        match propose(deps.as_mut(), "creator", action.clone()).unwrap_err() {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        propose(deps.as_mut(), "guardian", action).unwrap();
+        let msg = ExecuteMsg::ConfirmAdminAction { id: 0 };
+        let err = execute(
+            deps.as_mut(),
+            env_at(200),
+            mock_info("creator", &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        match err {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        let res = execute(deps.as_mut(), env_at(200), mock_info("minter", &[]), msg).unwrap();
+        assert!(res.attributes.contains(&attr("minter", "minter")));
+        assert_eq!(balance(&deps.storage, "addr1111"), 100);
+
+        let action = ExecuteMsg::ForceTransfer {
+            owner: "addr0000".to_string(),
+            recipient: "addr2222".to_string(),
+            amount: Uint128::from(100u128),
+        };
+        let err = execute(
+            deps.as_mut(),
+            env_at(100),
+            mock_info("sheriff", &[]),
+            action.clone(),
+        )
+        .unwrap_err();
+        match err {
+            ContractError::DualControlRequired {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        propose(deps.as_mut(), "sheriff", action).unwrap();
+        let msg = ExecuteMsg::ConfirmAdminAction { id: 1 };
+        let res = execute(deps.as_mut(), env_at(200), mock_info("guardian", &[]), msg).unwrap();
+        assert!(res.attributes.contains(&attr("authority", "sheriff")));
+        assert_eq!(balance(&deps.storage, "addr2222"), 100);
+    }
+
+    #[test]
+    fn only_privileged_actions_can_be_proposed() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+
+        let action = ExecuteMsg::Transfer {
+            recipient: "addr1111".to_string(),
+            amount: Uint128::from(1u128),
+            memo: None,
+            on_behalf_of: None,
+        };
+        match propose(deps.as_mut(), "guardian", action).unwrap_err() {
+            ContractError::NotPrivileged {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        propose(deps.as_mut(), "guardian", ExecuteMsg::Pause {}).unwrap();
+        let msg = ExecuteMsg::CancelAdminAction { id: 0 };
+        execute(deps.as_mut(), env_at(200), mock_info("creator", &[]), msg).unwrap();
+        assert!(read_admin_proposals(&deps.storage, None, None)
+            .unwrap()
+            .is_empty());
+    }
+}
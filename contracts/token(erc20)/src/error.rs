@@ -12,6 +12,9 @@ pub enum ContractError {
     #[error("Ticker symbol is not in expected format [A-Z]{{3,6}}")]
     TickerWrongSymbolFormat {},
 
+    #[error("Display symbol is not in expected format [A-Za-z0-9.-]{{3,20}}")]
+    DisplaySymbolWrongFormat {},
+
     #[error("Decimals must not exceed 18")]
     DecimalsExceeded {},
 
@@ -23,4 +26,289 @@ pub enum ContractError {
 
     #[error("Corrupted data found (16 byte expected)")]
     CorruptedDataFound {},
+
+    #[error("Permit deadline passed")]
+    PermitExpired {},
+
+    #[error("Relayed transfer expired")]
+    RelayExpired {},
+
+    #[error("Invalid nonce (expected {expected}, got {actual})")]
+    InvalidNonce { expected: u64, actual: u64 },
+
+    #[error("Signature verification failed")]
+    InvalidSignature {},
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Bucket name is not in the expected format [a-z0-9_]{{1,32}}")]
+    InvalidBucketName {},
+
+    #[error("Bucket {name} not found")]
+    BucketNotFound { name: String },
+
+    #[error("Bucket spend limit exceeded (limit {limit}, required={required})")]
+    BucketLimitExceeded { limit: u128, required: u128 },
+
+    #[error("Insufficient bucket funds (balance {balance}, required={required})")]
+    InsufficientBucketFunds { balance: u128, required: u128 },
+
+    #[error("Contract is paused")]
+    Paused {},
+
+    #[error("Contract is not paused")]
+    NotPaused {},
+
+    #[error("Unpause voting is disabled")]
+    UnpauseVotingDisabled {},
+
+    #[error("Unpause voting opens after {opens_at}")]
+    UnpauseVotingNotOpen { opens_at: u64 },
+
+    #[error("Pausing is locked until {pausable_at} after a holder vote unpaused")]
+    PauseCooldown { pausable_at: u64 },
+
+    #[error("Already voted")]
+    AlreadyVoted {},
+
+    #[error("No voting power at the snapshot height")]
+    NoVotingPower {},
+
+    #[error("Memo exceeds {max} bytes")]
+    MemoTooLong { max: usize },
+
+    #[error("Fee rate {rate} exceeds the maximum of {max} basis points")]
+    FeeRateTooHigh { max: u16, rate: u16 },
+
+    #[error("Fee curve needs a non-zero epoch length and steps of ascending volume")]
+    InvalidFeeCurve {},
+
+    #[error("Rebase would reduce the total supply to zero")]
+    InvalidRebase {},
+
+    #[error("Amount {amount} is worth less than one share")]
+    BelowOneShare { amount: u128 },
+
+    #[error("Transfer exceeds the maximum of {max} per transaction (amount {amount})")]
+    MaxTxAmountExceeded { max: u128, amount: u128 },
+
+    #[error("Transfer is below the minimum of {min} (amount {amount})")]
+    BelowMinimumTransfer { min: u128, amount: u128 },
+
+    #[error("Recipient balance would exceed the maximum of {max} per wallet (balance {balance})")]
+    MaxWalletAmountExceeded { max: u128, balance: u128 },
+
+    #[error("Rate limit exceeded (available {available}, required={required})")]
+    RateLimitExceeded { available: u128, required: u128 },
+
+    #[error("Transfers above {threshold} must be initiated and confirmed")]
+    LargeTransferNeedsConfirmation { threshold: u128 },
+
+    #[error("No large transfer policy set for the sender")]
+    NoLargeTransferPolicy {},
+
+    #[error("Pending transfer {id} not found")]
+    PendingTransferNotFound { id: u64 },
+
+    #[error("Pending transfer {id} expired")]
+    PendingTransferExpired { id: u64 },
+
+    #[error("Release time must be in the future")]
+    InvalidReleaseTime {},
+
+    #[error("Only unordered IBC channels are supported")]
+    OnlyUnorderedChannel {},
+
+    #[error("Unsupported IBC channel version {version}")]
+    InvalidIbcVersion { version: String },
+
+    #[error("Token does not wrap a native denom")]
+    NotAWrapper {},
+
+    #[error("Deposit requires a non-zero amount of {denom} and no other coins")]
+    InvalidDeposit { denom: String },
+
+    #[error("Claimable transfer {id} not found")]
+    ClaimableNotFound { id: u64 },
+
+    #[error("Claimable transfer {id} expired")]
+    ClaimableExpired { id: u64 },
+
+    #[error("Claimable transfer {id} has not expired yet")]
+    ClaimableNotExpired { id: u64 },
+
+    #[error("Claim exceeds the unclaimed amount (remaining {remaining}, required={required})")]
+    ClaimExceedsRemaining { remaining: u128, required: u128 },
+
+    #[error("Stream {id} not found")]
+    StreamNotFound { id: u64 },
+
+    #[error("Stream must end after it starts and in the future")]
+    InvalidStreamSchedule {},
+
+    #[error("Drip must end after it starts and in the future")]
+    InvalidDripSchedule {},
+
+    #[error("Role {role} was renounced")]
+    RoleRenounced { role: String },
+
+    #[error("Nothing to sweep")]
+    NothingToSweep {},
+
+    #[error("Coins of {denom} back the token and cannot be swept")]
+    ReservedDenom { denom: String },
+
+    #[error("No treasury set")]
+    NoTreasury {},
+
+    #[error("Token has no drip")]
+    NoDrip {},
+
+    #[error("Nothing to release yet")]
+    NothingToRelease {},
+
+    #[error("Dividend funding requires coins or a non-zero token amount")]
+    InvalidDividendFunding {},
+
+    #[error("No holders to distribute dividends to")]
+    NoDividendHolders {},
+
+    #[error("Dividends can be paid in at most {max} assets")]
+    TooManyDividendPools { max: usize },
+
+    #[error("No tokens collected for burning")]
+    NothingCollected {},
+
+    #[error("Timelocked transfer {id} not found")]
+    TimelockedTransferNotFound { id: u64 },
+
+    #[error("Timelocked transfer {id} cannot be executed before {executable_at}")]
+    TimelockNotExpired { id: u64, executable_at: u64 },
+
+    #[error("Timelocked transfer {id} can no longer be cancelled")]
+    TimelockExpired { id: u64 },
+
+    #[error("Dual control is enabled, propose the action instead")]
+    DualControlRequired {},
+
+    #[error("Dual control is not enabled")]
+    DualControlDisabled {},
+
+    #[error("Only privileged actions can be proposed")]
+    NotPrivileged {},
+
+    #[error("Admin proposal {id} not found")]
+    AdminProposalNotFound { id: u64 },
+
+    #[error("Proposal must be confirmed by the other admin key")]
+    SecondAdminRequired {},
+
+    #[error("Confirmer must differ from the owner")]
+    InvalidConfirmer {},
+
+    #[error("Tokens held by the contract are escrowed and cannot be moved")]
+    EscrowNotMovable {},
+
+    #[error("Send exceeds the timelock threshold, use Transfer instead")]
+    SendTimelocked {},
+
+    #[error("Failed send {id} not found")]
+    FailedSendNotFound { id: u64 },
+
+    #[error("Clawback is disabled")]
+    ClawbackDisabled {},
+
+    #[error("Rescaling the decimals overflows")]
+    RescaleOverflow {},
+
+    #[error("Rescaling the decimals needs {record} to be cleared first")]
+    RescaleBlocked { record: String },
+
+    #[error("Minting cannot exceed the cap")]
+    CannotExceedCap {},
+
+    #[error("Minting has ended")]
+    MintingEnded {},
+
+    #[error("Burn rate {rate} exceeds the maximum of {max} basis points")]
+    BurnRateTooHigh { max: u16, rate: u16 },
+
+    #[error("Multicall cannot contain another multicall")]
+    NestedMulticall {},
+
+    #[error("Multicall does not accept funds")]
+    MulticallFunds {},
+
+    #[error("Cannot migrate from {contract}")]
+    CannotMigrate { contract: String },
+
+    #[error("Migrating a deployment without an owner needs one")]
+    OwnerRequired {},
+
+    #[error("Only a deployment without an owner can be given one by migrating")]
+    OwnerAlreadySet {},
+
+    #[error("Cannot migrate state version {stored} down to {current}")]
+    StateDowngrade { stored: u16, current: u16 },
+
+    #[error("Transfer rejected by the compliance contract: {reason}")]
+    NotCompliant { reason: String },
+
+    #[error("Insufficient unlocked funds (spendable {spendable}, required={required})")]
+    BalanceLockedUp { spendable: u128, required: u128 },
+
+    #[error("At most {max} addresses can be queried at once")]
+    TooManyAddresses { max: usize },
+
+    #[error("Duplicate initial balance for {address}")]
+    DuplicateInitialBalance { address: String },
+
+    #[error("Initial balances exceed the maximum total supply")]
+    InitialSupplyOverflow {},
+
+    #[error("Invalid pagination cursor")]
+    InvalidCursor {},
+
+    #[error("Allowance changed (expected {expected}, current {current})")]
+    AllowanceChanged { expected: u128, current: u128 },
+
+    #[error("Period must be longer than zero seconds")]
+    InvalidPeriod {},
+
+    #[error("Subscription {id} not found")]
+    SubscriptionNotFound { id: u64 },
+
+    #[error("Subscription payment not due until {next_payment_at}")]
+    SubscriptionNotDue { next_payment_at: u64 },
+
+    #[error("At most {max} entries can be distributed at once")]
+    TooManyDistributionEntries { max: usize },
+
+    #[error("Account {address} is frozen")]
+    AccountFrozen { address: String },
+
+    #[error("Transfers of zero tokens are disabled")]
+    ZeroTransfer {},
+
+    #[error("Arithmetic overflow")]
+    Overflow {},
+
+    #[error("Arithmetic underflow")]
+    Underflow {},
+
+    #[error("At most {max} accounts can be excluded from the circulating supply")]
+    TooManySupplyExclusions { max: usize },
+
+    #[error("At most {max} approvals can be set at once")]
+    TooManyApprovals { max: usize },
+
+    #[error("Allowance expiration must be in the future")]
+    InvalidAllowanceExpiration {},
+
+    #[error("Embedded logo must be at most {max} bytes")]
+    LogoTooBig { max: usize },
+
+    #[error("Embedded logo is not a valid {format} image")]
+    InvalidLogo { format: String },
 }
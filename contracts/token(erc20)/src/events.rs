@@ -0,0 +1,210 @@
+use cosmwasm_std::{Addr, Api, Event, Storage};
+
+use crate::contract::{read_balance, read_total_supply, TransferOutcome};
+use crate::error::ContractError;
+use crate::msg::Role;
+use crate::renounce::role_name;
+use crate::state::Constants;
+
+// Typed events for indexers, emitted next to the action attributes. Each one
+// carries the balances after the change so no state has to be re-derived.
+
+/// "transfer" for a completed transfer, "transfer_queued" for one waiting for
+/// its timelock
+pub fn transfer_event(
+    store: &dyn Storage,
+    api: &dyn Api,
+    from: &Addr,
+    to: &Addr,
+    amount: u128,
+    outcome: TransferOutcome,
+) -> Result<Event, ContractError> {
+    let event = match outcome {
+        TransferOutcome::Completed { fee, burned } => {
+            let event = Event::new("transfer")
+                .add_attribute("from", from)
+                .add_attribute("to", to)
+                .add_attribute("amount", amount.to_string())
+                .add_attribute("fee", fee.to_string())
+                .add_attribute("burned", burned.to_string())
+                .add_attribute("from_balance", read_balance(store, api, from)?.to_string())
+                .add_attribute("to_balance", read_balance(store, api, to)?.to_string());
+            // A deflationary burn shrinks the supply as a side effect of the transfer
+            if burned > 0 {
+                event.add_attribute("total_supply", read_total_supply(store)?.to_string())
+            } else {
+                event
+            }
+        }
+        TransferOutcome::Queued { id } => Event::new("transfer_queued")
+            .add_attribute("from", from)
+            .add_attribute("to", to)
+            .add_attribute("amount", amount.to_string())
+            .add_attribute("timelocked_transfer_id", id.to_string()),
+    };
+    Ok(event)
+}
+
+/// "force_transfer" for tokens moved without the consent of `from`
+pub fn force_transfer_event(
+    store: &dyn Storage,
+    api: &dyn Api,
+    from: &Addr,
+    to: &Addr,
+    amount: u128,
+) -> Result<Event, ContractError> {
+    Ok(Event::new("force_transfer")
+        .add_attribute("from", from)
+        .add_attribute("to", to)
+        .add_attribute("amount", amount.to_string())
+        .add_attribute("from_balance", read_balance(store, api, from)?.to_string())
+        .add_attribute("to_balance", read_balance(store, api, to)?.to_string()))
+}
+
+pub fn approve_event(owner: &Addr, spender: &Addr, allowance: u128) -> Event {
+    Event::new("approve")
+        .add_attribute("owner", owner)
+        .add_attribute("spender", spender)
+        .add_attribute("allowance", allowance.to_string())
+}
+
+pub fn burn_event(
+    store: &dyn Storage,
+    api: &dyn Api,
+    from: &Addr,
+    amount: u128,
+) -> Result<Event, ContractError> {
+    Ok(Event::new("burn")
+        .add_attribute("from", from)
+        .add_attribute("amount", amount.to_string())
+        .add_attribute("from_balance", read_balance(store, api, from)?.to_string())
+        .add_attribute("total_supply", read_total_supply(store)?.to_string()))
+}
+
+pub fn mint_event(
+    store: &dyn Storage,
+    api: &dyn Api,
+    to: &Addr,
+    amount: u128,
+) -> Result<Event, ContractError> {
+    Ok(Event::new("mint")
+        .add_attribute("to", to)
+        .add_attribute("amount", amount.to_string())
+        .add_attribute("to_balance", read_balance(store, api, to)?.to_string())
+        .add_attribute("total_supply", read_total_supply(store)?.to_string()))
+}
+
+/// "renounce" for a role given up for good
+pub fn renounce_event(account: &Addr, role: Role) -> Event {
+    Event::new("renounce")
+        .add_attribute("role", role_name(role))
+        .add_attribute("account", account)
+}
+
+/// "rebase" for a supply change that rescales every balance at once
+pub fn rebase_event(store: &dyn Storage, old_total_supply: u128) -> Result<Event, ContractError> {
+    Ok(Event::new("rebase")
+        .add_attribute("old_total_supply", old_total_supply.to_string())
+        .add_attribute("total_supply", read_total_supply(store)?.to_string()))
+}
+
+pub fn token_metadata_event(old: &Constants, name: &str, symbol: &str) -> Event {
+    Event::new("token_metadata")
+        .add_attribute("old_name", &old.name)
+        .add_attribute("old_symbol", &old.symbol)
+        .add_attribute("name", name)
+        .add_attribute("symbol", symbol)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::contract::{execute, instantiate};
+    use crate::msg::{ExecuteMsg, InitialBalance, InstantiateMsg};
+    use crate::testing::default_instantiate_msg;
+    use cosmwasm_std::testing::{mock_dependencies_with_balance, mock_env, mock_info};
+    use cosmwasm_std::{attr, DepsMut, Event, Uint128};
+
+    fn setup(deps: DepsMut) {
+        let instantiate_msg = InstantiateMsg {
+            initial_balances: vec![InitialBalance {
+                address: "addr0000".to_string(),
+                amount: Uint128::from(1000u128),
+            }],
+            ..default_instantiate_msg()
+        };
+        let res =
+            instantiate(deps, mock_env(), mock_info("creator", &[]), instantiate_msg).unwrap();
+        assert_eq!(
+            res.events,
+            vec![Event::new("mint").add_attributes(vec![
+                attr("to", "addr0000"),
+                attr("amount", "1000"),
+                attr("to_balance", "1000"),
+                attr("total_supply", "1000"),
+            ])]
+        );
+    }
+
+    #[test]
+    fn transfer_and_burn_carry_new_balances() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+
+        let msg = ExecuteMsg::Transfer {
+            recipient: "addr1111".to_string(),
+            amount: Uint128::from(300u128),
+            memo: None,
+            on_behalf_of: None,
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+        assert_eq!(
+            res.events,
+            vec![Event::new("transfer").add_attributes(vec![
+                attr("from", "addr0000"),
+                attr("to", "addr1111"),
+                attr("amount", "300"),
+                attr("fee", "0"),
+                attr("burned", "0"),
+                attr("from_balance", "700"),
+                attr("to_balance", "300"),
+            ])]
+        );
+
+        let msg = ExecuteMsg::Burn {
+            amount: Uint128::from(100u128),
+            memo: None,
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("addr1111", &[]), msg).unwrap();
+        assert_eq!(
+            res.events,
+            vec![Event::new("burn").add_attributes(vec![
+                attr("from", "addr1111"),
+                attr("amount", "100"),
+                attr("from_balance", "200"),
+                attr("total_supply", "900"),
+            ])]
+        );
+    }
+
+    #[test]
+    fn approve_reports_the_allowance() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+
+        let msg = ExecuteMsg::Approve {
+            spender: "spender".to_string(),
+            amount: Uint128::from(250u128),
+            expected_current: None,
+            on_behalf_of: None,
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+        assert_eq!(
+            res.events,
+            vec![Event::new("approve").add_attributes(vec![
+                attr("owner", "addr0000"),
+                attr("spender", "spender"),
+                attr("allowance", "250"),
+            ])]
+        );
+    }
+}
@@ -0,0 +1,295 @@
+use cosmwasm_std::{Api, Binary, Order, Storage, Uint128};
+use cosmwasm_storage::ReadonlyPrefixedStorage;
+
+use crate::cap;
+use crate::contract::{
+    addr_from_canonical_key, bytes_to_u128, read_constants, read_owner, read_total_supply,
+    split_allowance_key, PREFIX_ALLOWANCES, PREFIX_BALANCES,
+};
+use crate::error::ContractError;
+use crate::holders;
+use crate::msg::{
+    ExportSection, ExportStateResponse, ExportedAllowance, ExportedBalance, ExportedConfig,
+    VerifySupplyResponse,
+};
+use crate::pagination::{next_cursor, Page, EXPORT_LIMITS};
+use crate::rebase;
+
+// Pages of balances and allowances follow the storage order. The cursor is the
+// storage key of the last entry of a page, opaque to the caller. Empty entries
+// are skipped.
+
+/// One page of `section`, starting after the `next` cursor of the previous page
+pub fn export_state(
+    store: &dyn Storage,
+    api: &dyn Api,
+    section: ExportSection,
+    start_after: Option<Binary>,
+    limit: Option<u32>,
+) -> Result<ExportStateResponse, ContractError> {
+    let page = Page::new(start_after, limit, &EXPORT_LIMITS, Order::Ascending);
+    match section {
+        ExportSection::Balances => {
+            let balances_store = ReadonlyPrefixedStorage::new(store, PREFIX_BALANCES);
+            let entries = balances_store
+                .range(page.start(), page.end(), page.order)
+                .map(|(key, data)| Ok((key, bytes_to_u128(&data)?)))
+                .filter(|entry: &Result<_, ContractError>| !matches!(entry, Ok((_, 0))))
+                .take(page.limit)
+                .collect::<Result<Vec<_>, ContractError>>()?;
+            let next = next_cursor(
+                entries.last().map(|(key, _)| key),
+                entries.len(),
+                page.limit,
+            );
+            let balances = entries
+                .into_iter()
+                .map(|(key, shares)| {
+                    Ok(ExportedBalance {
+                        address: addr_from_canonical_key(api, key)?.to_string(),
+                        balance: Uint128::from(rebase::to_amount(store, shares)?),
+                        shares: Uint128::from(shares),
+                    })
+                })
+                .collect::<Result<_, ContractError>>()?;
+            Ok(ExportStateResponse::Balances { balances, next })
+        }
+        ExportSection::Allowances => {
+            let allowances_store = ReadonlyPrefixedStorage::new(store, PREFIX_ALLOWANCES);
+            let entries = allowances_store
+                .range(page.start(), page.end(), page.order)
+                .map(|(key, data)| Ok((key, bytes_to_u128(&data)?)))
+                .filter(|entry: &Result<_, ContractError>| !matches!(entry, Ok((_, 0))))
+                .take(page.limit)
+                .collect::<Result<Vec<_>, ContractError>>()?;
+            let next = next_cursor(
+                entries.last().map(|(key, _)| key),
+                entries.len(),
+                page.limit,
+            );
+            let allowances = entries
+                .into_iter()
+                .map(|(key, allowance)| {
+                    let (owner, spender) = split_allowance_key(api, &key)?;
+                    Ok(ExportedAllowance {
+                        owner: owner.to_string(),
+                        spender: spender.to_string(),
+                        allowance: Uint128::from(allowance),
+                    })
+                })
+                .collect::<Result<_, ContractError>>()?;
+            Ok(ExportStateResponse::Allowances { allowances, next })
+        }
+        ExportSection::Config => {
+            let constants = read_constants(store)?;
+            Ok(ExportStateResponse::Config {
+                config: ExportedConfig {
+                    name: constants.name,
+                    symbol: constants.symbol,
+                    decimals: constants.decimals,
+                    owner: read_owner(store)?.map(|owner| owner.to_string()),
+                    total_supply: Uint128::from(read_total_supply(store)?),
+                    total_shares: Uint128::from(rebase::read_total_shares(store)?),
+                    max_supply: cap::read_max_supply(store)?.map(Uint128::from),
+                    holder_count: holders::read_holder_count(store)?,
+                },
+            })
+        }
+    }
+}
+
+/// One page of the supply audit. Sums the stored shares of every account and,
+/// on the last page, checks the sum against the total shares, so that the
+/// balances add up to `KEY_TOTAL_SUPPLY`.
+///
+/// The cursor carries the running total along with the last key, so each page
+/// only reads `limit` accounts.
+pub fn verify_supply(
+    store: &dyn Storage,
+    start_after: Option<Binary>,
+    limit: Option<u32>,
+) -> Result<VerifySupplyResponse, ContractError> {
+    let (mut running_shares, start_after) = match start_after {
+        Some(cursor) if cursor.len() >= 16 => {
+            let (total, key) = cursor.split_at(16);
+            (bytes_to_u128(total)?, Some(key.to_vec()))
+        }
+        Some(_) => return Err(ContractError::InvalidCursor {}),
+        None => (0u128, None),
+    };
+    let page = Page::after_key(start_after, limit, &EXPORT_LIMITS, Order::Ascending);
+
+    let balances_store = ReadonlyPrefixedStorage::new(store, PREFIX_BALANCES);
+    let entries = balances_store
+        .range(page.start(), page.end(), page.order)
+        .take(page.limit)
+        .map(|(key, data)| Ok((key, bytes_to_u128(&data)?)))
+        .collect::<Result<Vec<_>, ContractError>>()?;
+    for (_, shares) in &entries {
+        running_shares = running_shares
+            .checked_add(*shares)
+            .ok_or(ContractError::CorruptedDataFound {})?;
+    }
+
+    let total_shares = rebase::read_total_shares(store)?;
+    let next = next_cursor(
+        entries.last().map(|(key, _)| key),
+        entries.len(),
+        page.limit,
+    )
+    .map(|key| Binary::from([&running_shares.to_be_bytes()[..], key.as_slice()].concat()));
+    let verified = match next {
+        Some(_) => None,
+        None => Some(running_shares == total_shares),
+    };
+    Ok(VerifySupplyResponse {
+        running_total: Uint128::from(rebase::to_amount(store, running_shares)?),
+        running_shares: Uint128::from(running_shares),
+        total_supply: Uint128::from(read_total_supply(store)?),
+        total_shares: Uint128::from(total_shares),
+        next,
+        verified,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::contract::{execute, instantiate, query};
+    use crate::msg::{
+        ExecuteMsg, ExportSection, ExportStateResponse, InitialBalance, InstantiateMsg, QueryMsg,
+        VerifySupplyResponse,
+    };
+    use crate::testing::default_instantiate_msg;
+    use cosmwasm_std::testing::{mock_dependencies_with_balance, mock_env, mock_info};
+    use cosmwasm_std::{from_slice, Binary, Deps, DepsMut, Uint128};
+
+    fn setup(deps: DepsMut) {
+        let instantiate_msg = InstantiateMsg {
+            initial_balances: vec![
+                InitialBalance {
+                    address: "addr0000".to_string(),
+                    amount: Uint128::from(300u128),
+                },
+                InitialBalance {
+                    address: "addr1111".to_string(),
+                    amount: Uint128::from(200u128),
+                },
+                InitialBalance {
+                    address: "addr2222".to_string(),
+                    amount: Uint128::from(100u128),
+                },
+            ],
+            ..default_instantiate_msg()
+        };
+        instantiate(deps, mock_env(), mock_info("creator", &[]), instantiate_msg).unwrap();
+    }
+
+    fn export(
+        deps: Deps,
+        section: ExportSection,
+        start_after: Option<Binary>,
+        limit: u32,
+    ) -> ExportStateResponse {
+        let msg = QueryMsg::ExportState {
+            section,
+            start_after,
+            limit: Some(limit),
+        };
+        from_slice(&query(deps, mock_env(), msg).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn pages_through_balances() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        // emptied accounts are skipped
+        let msg = ExecuteMsg::Transfer {
+            recipient: "addr0000".to_string(),
+            amount: Uint128::from(200u128),
+            memo: None,
+            on_behalf_of: None,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("addr1111", &[]), msg).unwrap();
+
+        let (balances, next) = match export(deps.as_ref(), ExportSection::Balances, None, 1) {
+            ExportStateResponse::Balances { balances, next } => (balances, next),
+            res => panic!("unexpected response: {:?}", res),
+        };
+        assert_eq!(balances.len(), 1);
+        assert_eq!(balances[0].address, "addr0000");
+        assert_eq!(balances[0].balance, Uint128::from(500u128));
+
+        let (balances, next) = match export(deps.as_ref(), ExportSection::Balances, next, 1) {
+            ExportStateResponse::Balances { balances, next } => (balances, next),
+            res => panic!("unexpected response: {:?}", res),
+        };
+        assert_eq!(balances[0].address, "addr2222");
+
+        match export(deps.as_ref(), ExportSection::Balances, next, 1) {
+            ExportStateResponse::Balances { balances, next } => {
+                assert!(balances.is_empty());
+                assert_eq!(next, None);
+            }
+            res => panic!("unexpected response: {:?}", res),
+        }
+    }
+
+    #[test]
+    fn verifies_supply_across_pages() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        let verify = |start_after: Option<Binary>| -> VerifySupplyResponse {
+            let msg = QueryMsg::VerifySupply {
+                start_after,
+                limit: Some(2),
+            };
+            from_slice(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap()
+        };
+
+        let res = verify(None);
+        assert_eq!(res.running_total, Uint128::from(500u128));
+        assert_eq!(res.total_supply, Uint128::from(600u128));
+        assert_eq!(res.verified, None);
+
+        let res = verify(res.next);
+        assert_eq!(res.running_total, Uint128::from(600u128));
+        assert_eq!(res.next, None);
+        assert_eq!(res.verified, Some(true));
+    }
+
+    #[test]
+    fn exports_allowances_and_config() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        for spender in &["spender1", "spender2"] {
+            let msg = ExecuteMsg::Approve {
+                spender: spender.to_string(),
+                amount: Uint128::from(50u128),
+                expected_current: None,
+                on_behalf_of: None,
+            };
+            execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+        }
+
+        match export(deps.as_ref(), ExportSection::Allowances, None, 10) {
+            ExportStateResponse::Allowances { allowances, next } => {
+                assert_eq!(allowances.len(), 2);
+                assert_eq!(allowances[1].owner, "addr0000");
+                assert_eq!(allowances[1].spender, "spender2");
+                assert_eq!(allowances[1].allowance, Uint128::from(50u128));
+                assert_eq!(next, None);
+            }
+            res => panic!("unexpected response: {:?}", res),
+        }
+
+        match export(deps.as_ref(), ExportSection::Config, None, 10) {
+            ExportStateResponse::Config { config } => {
+                assert_eq!(config.symbol, "CASH");
+                assert_eq!(config.owner, Some("creator".to_string()));
+                assert_eq!(config.total_supply, Uint128::from(600u128));
+                assert_eq!(config.holder_count, 3);
+            }
+            res => panic!("unexpected response: {:?}", res),
+        }
+    }
+}
@@ -0,0 +1,495 @@
+use cosmwasm_std::{
+    from_slice, to_vec, Addr, DepsMut, Env, MessageInfo, Response, Storage, Uint128,
+};
+use cosmwasm_storage::{PrefixedStorage, ReadonlyPrefixedStorage};
+
+use crate::contract::{assert_owner, checked_add, PREFIX_CONFIG};
+use crate::error::ContractError;
+use crate::msg::CurrentFeeResponse;
+use crate::state::{FeeConfig, FeeCurve, FeeVolume};
+use crate::treasury;
+
+pub const KEY_FEE_CONFIG: &[u8] = b"fee_config";
+pub const KEY_FEE_CURVE: &[u8] = b"fee_curve";
+pub const KEY_FEE_VOLUME: &[u8] = b"fee_volume";
+
+/// Upper bound for the transfer fee, in basis points
+pub const MAX_FEE_BPS: u16 = 1_000;
+
+pub fn read_fee_config(store: &dyn Storage) -> Result<FeeConfig, ContractError> {
+    let config_store = ReadonlyPrefixedStorage::new(store, PREFIX_CONFIG);
+    match config_store.get(KEY_FEE_CONFIG) {
+        Some(data) => Ok(from_slice(&data)?),
+        None => Ok(FeeConfig::default()),
+    }
+}
+
+pub fn write_fee_config(store: &mut dyn Storage, config: &FeeConfig) -> Result<(), ContractError> {
+    let mut config_store = PrefixedStorage::new(store, PREFIX_CONFIG);
+    config_store.set(KEY_FEE_CONFIG, &to_vec(config)?);
+    Ok(())
+}
+
+pub fn read_fee_curve(store: &dyn Storage) -> Result<Option<FeeCurve>, ContractError> {
+    let config_store = ReadonlyPrefixedStorage::new(store, PREFIX_CONFIG);
+    match config_store.get(KEY_FEE_CURVE) {
+        Some(data) => Ok(Some(from_slice(&data)?)),
+        None => Ok(None),
+    }
+}
+
+/// Volume tracked for `curve`, rolled over to the current epoch. Epochs are only
+/// stored when a transfer happens in them, so the rollover is done lazily here.
+pub fn read_fee_volume(
+    store: &dyn Storage,
+    env: &Env,
+    curve: &FeeCurve,
+) -> Result<FeeVolume, ContractError> {
+    let config_store = ReadonlyPrefixedStorage::new(store, PREFIX_CONFIG);
+    let stored: FeeVolume = match config_store.get(KEY_FEE_VOLUME) {
+        Some(data) => from_slice(&data)?,
+        None => FeeVolume::default(),
+    };
+    let epoch = env.block.time.seconds() / curve.epoch_length;
+    if stored.epoch == epoch {
+        Ok(stored)
+    } else if stored.epoch + 1 == epoch {
+        Ok(FeeVolume {
+            epoch,
+            volume: Uint128::zero(),
+            previous_volume: stored.volume,
+        })
+    } else {
+        Ok(FeeVolume {
+            epoch,
+            ..FeeVolume::default()
+        })
+    }
+}
+
+/// Fee rate in effect now, zero if the fee is disabled
+pub fn current_rate_bps(
+    store: &dyn Storage,
+    env: &Env,
+    config: &FeeConfig,
+) -> Result<u16, ContractError> {
+    if !config.enabled {
+        return Ok(0);
+    }
+    let curve = match read_fee_curve(store)? {
+        Some(curve) => curve,
+        None => return Ok(config.rate_bps),
+    };
+    let previous_volume = read_fee_volume(store, env, &curve)?.previous_volume;
+    Ok(curve
+        .steps
+        .iter()
+        .rev()
+        .find(|step| previous_volume >= step.volume)
+        .map_or(config.rate_bps, |step| step.rate_bps))
+}
+
+/// Adds a transfer to the volume of the current epoch. Only tracked while a fee
+/// curve is set; deposits into escrow do not count.
+pub fn record_volume(
+    store: &mut dyn Storage,
+    env: &Env,
+    amount: u128,
+) -> Result<(), ContractError> {
+    let curve = match read_fee_curve(store)? {
+        Some(curve) => curve,
+        None => return Ok(()),
+    };
+    let mut volume = read_fee_volume(store, env, &curve)?;
+    volume.volume = Uint128::from(checked_add(volume.volume.u128(), amount)?);
+    let mut config_store = PrefixedStorage::new(store, PREFIX_CONFIG);
+    config_store.set(KEY_FEE_VOLUME, &to_vec(&volume)?);
+    Ok(())
+}
+
+pub fn query_current_fee(
+    store: &dyn Storage,
+    env: &Env,
+) -> Result<CurrentFeeResponse, ContractError> {
+    let config = read_fee_config(store)?;
+    let curve = read_fee_curve(store)?;
+    let volume = match &curve {
+        Some(curve) => read_fee_volume(store, env, curve)?,
+        None => FeeVolume::default(),
+    };
+    Ok(CurrentFeeResponse {
+        rate_bps: current_rate_bps(store, env, &config)?,
+        curve,
+        epoch: volume.epoch,
+        epoch_volume: volume.volume,
+        previous_epoch_volume: volume.previous_volume,
+    })
+}
+
+/// Update the transfer fee. Owner only.
+///
+/// @param rate_bps fee in basis points of every transfer, at most `MAX_FEE_BPS`
+/// @param treasury the account receiving the fees
+pub fn try_update_fee_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    enabled: bool,
+    rate_bps: u16,
+    treasury: String,
+) -> Result<Response, ContractError> {
+    assert_owner(deps.storage, &info.sender)?;
+    if rate_bps > MAX_FEE_BPS {
+        return Err(ContractError::FeeRateTooHigh {
+            max: MAX_FEE_BPS,
+            rate: rate_bps,
+        });
+    }
+    let treasury_address = deps.api.addr_validate(treasury.as_str())?;
+    write_fee_config(
+        deps.storage,
+        &FeeConfig {
+            enabled,
+            rate_bps,
+            treasury: Some(treasury_address),
+        },
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("action", "update_fee_config")
+        .add_attribute("enabled", enabled.to_string())
+        .add_attribute("rate_bps", rate_bps.to_string())
+        .add_attribute("treasury", treasury))
+}
+
+/// Replace the fee curve, or remove it with `None`. Owner only. Tracked volume
+/// is reset, as epochs of another length are not comparable.
+///
+/// @param curve steps of ascending volume with rates of at most `MAX_FEE_BPS`
+pub fn try_update_fee_curve(
+    deps: DepsMut,
+    info: MessageInfo,
+    curve: Option<FeeCurve>,
+) -> Result<Response, ContractError> {
+    assert_owner(deps.storage, &info.sender)?;
+    if let Some(curve) = &curve {
+        if curve.epoch_length == 0
+            || curve
+                .steps
+                .windows(2)
+                .any(|steps| steps[0].volume >= steps[1].volume)
+        {
+            return Err(ContractError::InvalidFeeCurve {});
+        }
+        if let Some(step) = curve.steps.iter().find(|step| step.rate_bps > MAX_FEE_BPS) {
+            return Err(ContractError::FeeRateTooHigh {
+                max: MAX_FEE_BPS,
+                rate: step.rate_bps,
+            });
+        }
+    }
+    let mut config_store = PrefixedStorage::new(deps.storage, PREFIX_CONFIG);
+    config_store.remove(KEY_FEE_VOLUME);
+    match &curve {
+        Some(curve) => config_store.set(KEY_FEE_CURVE, &to_vec(curve)?),
+        None => config_store.remove(KEY_FEE_CURVE),
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "update_fee_curve")
+        .add_attribute("enabled", curve.is_some().to_string()))
+}
+
+/// Returns the fee charged on a transfer of `amount` from `from` to `to` and
+/// the account it is routed to: the protocol treasury if set, the treasury of
+/// the fee config otherwise. Transfers touching the treasury are exempt, deposits
+/// into escrow are not charged at all (see `escrow_deposit`).
+pub fn compute_fee(
+    store: &dyn Storage,
+    env: &Env,
+    from: &Addr,
+    to: &Addr,
+    amount: u128,
+) -> Result<Option<(u128, Addr)>, ContractError> {
+    let config = read_fee_config(store)?;
+    let rate_bps = current_rate_bps(store, env, &config)?;
+    let treasury = match treasury::read_treasury(store)?.or(config.treasury) {
+        Some(treasury) if rate_bps > 0 => treasury,
+        _ => return Ok(None),
+    };
+    if from == treasury || to == treasury {
+        return Ok(None);
+    }
+    let fee = Uint128::from(amount)
+        .multiply_ratio(rate_bps as u128, 10_000u128)
+        .u128();
+    if fee == 0 {
+        return Ok(None);
+    }
+    Ok(Some((fee, treasury)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate, query, read_balance};
+    use crate::locks;
+    use crate::msg::{
+        BalanceResponse, ExecuteMsg, FeeConfigResponse, InitialBalance, InstantiateMsg, QueryMsg,
+    };
+    use crate::state::FeeStep;
+    use crate::testing::{default_instantiate_msg, env_at};
+    use cosmwasm_std::attr;
+    use cosmwasm_std::testing::{
+        mock_dependencies_with_balance, mock_env, mock_info, MockApi, MOCK_CONTRACT_ADDR,
+    };
+
+    fn setup(deps: DepsMut) {
+        let instantiate_msg = InstantiateMsg {
+            initial_balances: vec![InitialBalance {
+                address: "addr0000".to_string(),
+                amount: Uint128::from(10_000u128),
+            }],
+            ..default_instantiate_msg()
+        };
+        instantiate(deps, mock_env(), mock_info("creator", &[]), instantiate_msg).unwrap();
+    }
+
+    fn update_fee_config(enabled: bool, rate_bps: u16) -> ExecuteMsg {
+        ExecuteMsg::UpdateFeeConfig {
+            enabled,
+            rate_bps,
+            treasury: "treasury".to_string(),
+        }
+    }
+
+    fn transfer(amount: u128) -> ExecuteMsg {
+        ExecuteMsg::Transfer {
+            recipient: "addr1111".to_string(),
+            amount: Uint128::from(amount),
+            memo: None,
+            on_behalf_of: None,
+        }
+    }
+
+    fn balance(storage: &dyn Storage, address: &str) -> u128 {
+        read_balance(storage, &MockApi::default(), &Addr::unchecked(address)).unwrap()
+    }
+
+    #[test]
+    fn fee_is_routed_to_treasury() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            update_fee_config(true, 250),
+        )
+        .unwrap();
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("addr0000", &[]),
+            transfer(1000),
+        )
+        .unwrap();
+        assert!(res.attributes.contains(&attr("fee_amount", "25")));
+        assert_eq!(balance(&deps.storage, "addr0000"), 9000);
+        assert_eq!(balance(&deps.storage, "addr1111"), 975);
+        assert_eq!(balance(&deps.storage, "treasury"), 25);
+    }
+
+    #[test]
+    fn escrow_is_charged_on_payout() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            update_fee_config(true, 250),
+        )
+        .unwrap();
+
+        let release_at = mock_env().block.time.seconds() + 100;
+        let lock_msg = ExecuteMsg::TransferLocked {
+            recipient: "addr1111".to_string(),
+            amount: Uint128::from(1000u128),
+            release_at,
+        };
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("addr0000", &[]),
+            lock_msg,
+        )
+        .unwrap();
+        assert_eq!(balance(&deps.storage, MOCK_CONTRACT_ADDR), 1000);
+        assert_eq!(balance(&deps.storage, "treasury"), 0);
+
+        let env = env_at(release_at);
+        let query_msg = QueryMsg::Balance {
+            address: "addr1111".to_string(),
+        };
+        let res: BalanceResponse =
+            from_slice(&query(deps.as_ref(), env.clone(), query_msg).unwrap()).unwrap();
+        assert_eq!(res.balance, Uint128::from(975u128));
+
+        locks::release_matured(
+            &mut deps.storage,
+            &deps.api,
+            &env,
+            &Addr::unchecked("addr1111"),
+        )
+        .unwrap();
+        assert_eq!(balance(&deps.storage, "addr1111"), 975);
+        assert_eq!(balance(&deps.storage, "treasury"), 25);
+        assert_eq!(balance(&deps.storage, MOCK_CONTRACT_ADDR), 0);
+    }
+
+    #[test]
+    fn disabled_fee_is_not_charged() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            update_fee_config(false, 250),
+        )
+        .unwrap();
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("addr0000", &[]),
+            transfer(1000),
+        )
+        .unwrap();
+        assert!(!res.attributes.iter().any(|a| a.key == "fee_amount"));
+        assert_eq!(balance(&deps.storage, "addr1111"), 1000);
+
+        let res: FeeConfigResponse =
+            from_slice(&query(deps.as_ref(), mock_env(), QueryMsg::FeeConfig {}).unwrap()).unwrap();
+        assert!(!res.enabled);
+        assert_eq!(res.rate_bps, 250);
+        assert_eq!(res.treasury, Some("treasury".to_string()));
+    }
+
+    #[test]
+    fn rate_is_bounded() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            update_fee_config(true, MAX_FEE_BPS + 1),
+        )
+        .unwrap_err();
+        match err {
+            ContractError::FeeRateTooHigh { max, rate } => {
+                assert_eq!(max, MAX_FEE_BPS);
+                assert_eq!(rate, MAX_FEE_BPS + 1);
+            }
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn only_owner_can_update() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("addr0000", &[]),
+            update_fee_config(true, 100),
+        )
+        .unwrap_err();
+        match err {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn curve_scales_rate_with_previous_epoch_volume() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            update_fee_config(true, 0),
+        )
+        .unwrap();
+        let curve = FeeCurve {
+            epoch_length: 100,
+            steps: vec![
+                FeeStep {
+                    volume: Uint128::from(1000u128),
+                    rate_bps: 100,
+                },
+                FeeStep {
+                    volume: Uint128::from(5000u128),
+                    rate_bps: 500,
+                },
+            ],
+        };
+        let msg = ExecuteMsg::UpdateFeeCurve { curve: Some(curve) };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        execute(
+            deps.as_mut(),
+            env_at(10),
+            mock_info("addr0000", &[]),
+            transfer(2000),
+        )
+        .unwrap();
+        assert_eq!(balance(&deps.storage, "addr1111"), 2000);
+
+        let res: CurrentFeeResponse =
+            from_slice(&query(deps.as_ref(), env_at(150), QueryMsg::CurrentFee {}).unwrap())
+                .unwrap();
+        assert_eq!(res.rate_bps, 100);
+        assert_eq!(res.epoch, 1);
+        assert_eq!(res.previous_epoch_volume, Uint128::from(2000u128));
+        execute(
+            deps.as_mut(),
+            env_at(150),
+            mock_info("addr0000", &[]),
+            transfer(1000),
+        )
+        .unwrap();
+        assert_eq!(balance(&deps.storage, "treasury"), 10);
+
+        // an epoch without transfers resets the rate
+        let res: CurrentFeeResponse =
+            from_slice(&query(deps.as_ref(), env_at(350), QueryMsg::CurrentFee {}).unwrap())
+                .unwrap();
+        assert_eq!(res.rate_bps, 0);
+        assert_eq!(res.previous_epoch_volume, Uint128::zero());
+    }
+
+    #[test]
+    fn curve_steps_must_ascend() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        let step = FeeStep {
+            volume: Uint128::from(1000u128),
+            rate_bps: 100,
+        };
+        let msg = ExecuteMsg::UpdateFeeCurve {
+            curve: Some(FeeCurve {
+                epoch_length: 100,
+                steps: vec![step.clone(), step],
+            }),
+        };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap_err();
+        match err {
+            ContractError::InvalidFeeCurve {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+}
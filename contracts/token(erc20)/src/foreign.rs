@@ -0,0 +1,308 @@
+use cosmwasm_std::{
+    to_binary, Addr, DepsMut, Env, MessageInfo, Order, Response, Storage, Uint128, WasmMsg,
+};
+use cosmwasm_storage::{PrefixedStorage, ReadonlyPrefixedStorage};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::contract::{assert_owner, bytes_to_u128};
+use crate::error::ContractError;
+use crate::msg::{ForeignBalance, ReceiveMsg};
+use crate::pagination::{Cursor, Page, LIST_LIMITS};
+
+pub const PREFIX_FOREIGN_BALANCES: &[u8] = b"foreign_balances";
+
+// Other cw20 tokens sent here with Send are recorded per token contract so the
+// owner can forward them out. Tokens moved with a plain cw20 Transfer call no
+// hook and go unnoticed. Any contract can call the hook, so a recorded balance
+// is only what the caller claimed; sweeping checks it against the token itself.
+
+// The subset of the cw20 execute messages sent to foreign tokens
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+enum ForeignExecuteMsg {
+    Transfer { recipient: String, amount: Uint128 },
+}
+
+// The subset of the cw20 queries sent to foreign tokens
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+enum ForeignQueryMsg {
+    Balance { address: String },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+struct ForeignBalanceResponse {
+    balance: Uint128,
+}
+
+pub fn read_foreign_balance(store: &dyn Storage, token: &Addr) -> Result<u128, ContractError> {
+    let balances_store = ReadonlyPrefixedStorage::new(store, PREFIX_FOREIGN_BALANCES);
+    match balances_store.get(token.as_str().as_bytes()) {
+        Some(data) => bytes_to_u128(&data),
+        None => Ok(0),
+    }
+}
+
+/// Foreign balances ordered by token address
+pub fn read_foreign_balances(
+    store: &dyn Storage,
+    start_after: Option<Addr>,
+    limit: Option<u32>,
+) -> Result<Vec<ForeignBalance>, ContractError> {
+    let page = Page::new(start_after, limit, &LIST_LIMITS, Order::Ascending);
+    let balances_store = ReadonlyPrefixedStorage::new(store, PREFIX_FOREIGN_BALANCES);
+    balances_store
+        .range(page.start(), page.end(), page.order)
+        .take(page.limit)
+        .map(|(key, data)| {
+            Ok(ForeignBalance {
+                token: Addr::from_key(&key)?.to_string(),
+                amount: Uint128::from(bytes_to_u128(&data)?),
+            })
+        })
+        .collect()
+}
+
+/// Record a cw20 deposit of the token contract signing `info`
+pub fn try_receive(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ReceiveMsg,
+) -> Result<Response, ContractError> {
+    // Send of this token calls the hook of the recipient, never our own
+    if info.sender == env.contract.address {
+        return Err(ContractError::Unauthorized {});
+    }
+    let balance = read_foreign_balance(deps.storage, &info.sender)?
+        .checked_add(msg.amount.u128())
+        .ok_or(ContractError::Overflow {})?;
+    let mut balances_store = PrefixedStorage::new(deps.storage, PREFIX_FOREIGN_BALANCES);
+    balances_store.set(info.sender.as_str().as_bytes(), &balance.to_be_bytes());
+
+    Ok(Response::new()
+        .add_attribute("action", "receive_foreign")
+        .add_attribute("token", info.sender)
+        .add_attribute("sender", msg.sender)
+        .add_attribute("amount", msg.amount.to_string()))
+}
+
+/// Forward the recorded balance of `token` to `recipient`, at most what the
+/// token reports the contract holds. The record is cleared either way, so
+/// entries of contracts that hold nothing for us can be removed. Owner only.
+pub fn try_sweep_foreign(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    token: String,
+    recipient: String,
+) -> Result<Response, ContractError> {
+    assert_owner(deps.storage, &info.sender)?;
+    let token = deps.api.addr_validate(&token)?;
+    let recipient = deps.api.addr_validate(&recipient)?;
+    let recorded = read_foreign_balance(deps.storage, &token)?;
+    if recorded == 0 {
+        return Err(ContractError::NothingToSweep {});
+    }
+    let mut balances_store = PrefixedStorage::new(deps.storage, PREFIX_FOREIGN_BALANCES);
+    balances_store.remove(token.as_str().as_bytes());
+    // A contract that cannot answer the query holds nothing we could move
+    let query = ForeignQueryMsg::Balance {
+        address: env.contract.address.to_string(),
+    };
+    let held = deps
+        .querier
+        .query_wasm_smart::<ForeignBalanceResponse>(&token, &query)
+        .map_or(0, |res| res.balance.u128());
+    let amount = recorded.min(held);
+
+    let mut res = Response::new();
+    if amount > 0 {
+        res = res.add_message(WasmMsg::Execute {
+            contract_addr: token.to_string(),
+            msg: to_binary(&ForeignExecuteMsg::Transfer {
+                recipient: recipient.to_string(),
+                amount: Uint128::from(amount),
+            })?,
+            funds: vec![],
+        });
+    }
+    Ok(res
+        .add_attribute("action", "sweep_foreign")
+        .add_attribute("token", token)
+        .add_attribute("recipient", recipient)
+        .add_attribute("amount", amount.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate, query};
+    use crate::msg::{ExecuteMsg, ForeignBalancesResponse, QueryMsg};
+    use crate::testing::default_instantiate_msg;
+    use cosmwasm_std::testing::{
+        mock_dependencies_with_balance, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
+        MOCK_CONTRACT_ADDR,
+    };
+    use cosmwasm_std::{
+        from_binary, from_slice, Binary, ContractResult, CosmosMsg, Deps, OwnedDeps, SystemError,
+        SystemResult, WasmQuery,
+    };
+
+    fn setup(deps: DepsMut) {
+        let instantiate_msg = default_instantiate_msg();
+        instantiate(deps, mock_env(), mock_info("creator", &[]), instantiate_msg).unwrap();
+    }
+
+    fn receive(deps: DepsMut, token: &str, amount: u128) -> Result<Response, ContractError> {
+        let msg = ExecuteMsg::Receive(ReceiveMsg {
+            sender: "addr0000".to_string(),
+            amount: Uint128::from(amount),
+            msg: Binary::default(),
+        });
+        execute(deps, mock_env(), mock_info(token, &[]), msg)
+    }
+
+    // "usdc" reports holding `usdc_held` for the contract, other tokens fail
+    fn with_usdc_held(deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>, usdc_held: u128) {
+        deps.querier.update_wasm(move |query| match query {
+            WasmQuery::Smart { contract_addr, msg } if contract_addr == "usdc" => {
+                let ForeignQueryMsg::Balance { address } = from_binary(msg).unwrap();
+                assert_eq!(address, MOCK_CONTRACT_ADDR);
+                let res = ForeignBalanceResponse {
+                    balance: Uint128::from(usdc_held),
+                };
+                SystemResult::Ok(ContractResult::Ok(to_binary(&res).unwrap()))
+            }
+            _ => SystemResult::Err(SystemError::UnsupportedRequest {
+                kind: "wasm".to_string(),
+            }),
+        });
+    }
+
+    fn sweep(deps: DepsMut, token: &str) -> Result<Response, ContractError> {
+        let msg = ExecuteMsg::SweepForeign {
+            token: token.to_string(),
+            recipient: "addr0000".to_string(),
+        };
+        execute(deps, mock_env(), mock_info("creator", &[]), msg)
+    }
+
+    fn foreign_balances(deps: Deps, start_after: Option<String>) -> Vec<ForeignBalance> {
+        let msg = QueryMsg::ForeignBalances {
+            start_after,
+            limit: None,
+        };
+        let res: ForeignBalancesResponse =
+            from_slice(&query(deps, mock_env(), msg).unwrap()).unwrap();
+        res.balances
+    }
+
+    #[test]
+    fn records_deposits_per_token() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        receive(deps.as_mut(), "usdc", 100).unwrap();
+        receive(deps.as_mut(), "atom", 5).unwrap();
+        receive(deps.as_mut(), "usdc", 50).unwrap();
+
+        let contract = mock_env().contract.address;
+        match receive(deps.as_mut(), contract.as_str(), 1).unwrap_err() {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        let balances = foreign_balances(deps.as_ref(), None);
+        assert_eq!(
+            balances,
+            vec![
+                ForeignBalance {
+                    token: "atom".to_string(),
+                    amount: Uint128::from(5u128),
+                },
+                ForeignBalance {
+                    token: "usdc".to_string(),
+                    amount: Uint128::from(150u128),
+                },
+            ]
+        );
+        assert_eq!(
+            foreign_balances(deps.as_ref(), Some("atom".to_string())),
+            balances[1..].to_vec()
+        );
+    }
+
+    #[test]
+    fn owner_sweeps_a_token() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        with_usdc_held(&mut deps, 150);
+        receive(deps.as_mut(), "usdc", 150).unwrap();
+
+        let msg = ExecuteMsg::SweepForeign {
+            token: "usdc".to_string(),
+            recipient: "addr0000".to_string(),
+        };
+        match execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("addr0000", &[]),
+            msg.clone(),
+        ) {
+            Err(ContractError::Unauthorized {}) => {}
+            res => panic!("unexpected result: {:?}", res),
+        }
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            msg.clone(),
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr: "usdc".to_string(),
+                msg: to_binary(&ForeignExecuteMsg::Transfer {
+                    recipient: "addr0000".to_string(),
+                    amount: Uint128::from(150u128),
+                })
+                .unwrap(),
+                funds: vec![],
+            })
+        );
+        assert!(foreign_balances(deps.as_ref(), None).is_empty());
+
+        match execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg) {
+            Err(ContractError::NothingToSweep {}) => {}
+            res => panic!("unexpected result: {:?}", res),
+        }
+    }
+
+    #[test]
+    fn sweeps_no_more_than_the_token_reports() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        with_usdc_held(&mut deps, 40);
+        receive(deps.as_mut(), "usdc", 150).unwrap();
+        receive(deps.as_mut(), "fake", 1_000_000).unwrap();
+
+        let res = sweep(deps.as_mut(), "usdc").unwrap();
+        match &res.messages[0].msg {
+            CosmosMsg::Wasm(WasmMsg::Execute { msg, .. }) => assert_eq!(
+                from_binary::<ForeignExecuteMsg>(msg).unwrap(),
+                ForeignExecuteMsg::Transfer {
+                    recipient: "addr0000".to_string(),
+                    amount: Uint128::from(40u128),
+                }
+            ),
+            msg => panic!("unexpected message: {:?}", msg),
+        }
+
+        // a claimed deposit the token cannot confirm is only cleared
+        let res = sweep(deps.as_mut(), "fake").unwrap();
+        assert!(res.messages.is_empty());
+        assert!(foreign_balances(deps.as_ref(), None).is_empty());
+    }
+}
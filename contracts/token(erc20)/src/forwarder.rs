@@ -0,0 +1,151 @@
+use cosmwasm_std::{Addr, Api, DepsMut, MessageInfo, Response, Storage};
+use cosmwasm_storage::{PrefixedStorage, ReadonlyPrefixedStorage};
+
+use crate::contract::{assert_owner, read_addr, PREFIX_CONFIG};
+use crate::error::ContractError;
+
+pub const KEY_TRUSTED_FORWARDER: &[u8] = b"trusted_forwarder";
+
+// Smart contract wallets execute through a forwarding contract, so the signer of
+// their messages is the forwarder. Transfer and Approve accept an `on_behalf_of`
+// account which, when sent by the trusted forwarder, replaces the signer.
+
+pub fn read_trusted_forwarder(store: &dyn Storage) -> Result<Option<Addr>, ContractError> {
+    let config_store = ReadonlyPrefixedStorage::new(store, PREFIX_CONFIG);
+    read_addr(&config_store, KEY_TRUSTED_FORWARDER)
+}
+
+/// Set or remove the forwarder allowed to act on behalf of other accounts.
+/// Owner only.
+pub fn try_set_trusted_forwarder(
+    deps: DepsMut,
+    info: MessageInfo,
+    forwarder: Option<String>,
+) -> Result<Response, ContractError> {
+    assert_owner(deps.storage, &info.sender)?;
+    let mut config_store = PrefixedStorage::new(deps.storage, PREFIX_CONFIG);
+    match &forwarder {
+        Some(forwarder) => {
+            let forwarder_address = deps.api.addr_validate(forwarder.as_str())?;
+            config_store.set(KEY_TRUSTED_FORWARDER, forwarder_address.as_str().as_bytes());
+        }
+        None => config_store.remove(KEY_TRUSTED_FORWARDER),
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "set_trusted_forwarder")
+        .add_attribute("forwarder", forwarder.unwrap_or_default()))
+}
+
+/// The account a message acts for: `on_behalf_of` if set, which only the
+/// trusted forwarder may do, the signer otherwise
+pub fn resolve_sender(
+    store: &dyn Storage,
+    api: &dyn Api,
+    info: MessageInfo,
+    on_behalf_of: Option<String>,
+) -> Result<MessageInfo, ContractError> {
+    let on_behalf_of = match on_behalf_of {
+        Some(on_behalf_of) => on_behalf_of,
+        None => return Ok(info),
+    };
+    match read_trusted_forwarder(store)? {
+        Some(forwarder) if forwarder == info.sender => Ok(MessageInfo {
+            sender: api.addr_validate(&on_behalf_of)?,
+            funds: info.funds,
+        }),
+        _ => Err(ContractError::Unauthorized {}),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate, query, read_balance};
+    use crate::msg::{AllowanceResponse, ExecuteMsg, InitialBalance, InstantiateMsg, QueryMsg};
+    use crate::testing::default_instantiate_msg;
+    use cosmwasm_std::testing::{mock_dependencies_with_balance, mock_env, mock_info};
+    use cosmwasm_std::{from_slice, Uint128};
+
+    fn setup(mut deps: DepsMut) {
+        let instantiate_msg = InstantiateMsg {
+            initial_balances: vec![InitialBalance {
+                address: "wallet".to_string(),
+                amount: Uint128::from(1000u128),
+            }],
+            ..default_instantiate_msg()
+        };
+        instantiate(
+            deps.branch(),
+            mock_env(),
+            mock_info("creator", &[]),
+            instantiate_msg,
+        )
+        .unwrap();
+        let msg = ExecuteMsg::SetTrustedForwarder {
+            forwarder: Some("forwarder".to_string()),
+        };
+        execute(deps, mock_env(), mock_info("creator", &[]), msg).unwrap();
+    }
+
+    fn transfer_on_behalf(deps: DepsMut, sender: &str) -> Result<Response, ContractError> {
+        let msg = ExecuteMsg::Transfer {
+            recipient: "addr1111".to_string(),
+            amount: Uint128::from(100u128),
+            memo: None,
+            on_behalf_of: Some("wallet".to_string()),
+        };
+        execute(deps, mock_env(), mock_info(sender, &[]), msg)
+    }
+
+    #[test]
+    fn forwarder_transfers_for_end_user() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        transfer_on_behalf(deps.as_mut(), "forwarder").unwrap();
+
+        let balance =
+            |address: &str| read_balance(&deps.storage, &deps.api, &Addr::unchecked(address));
+        assert_eq!(balance("wallet").unwrap(), 900);
+        assert_eq!(balance("addr1111").unwrap(), 100);
+        assert_eq!(balance("forwarder").unwrap(), 0);
+    }
+
+    #[test]
+    fn forwarder_approves_for_end_user() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        let msg = ExecuteMsg::Approve {
+            spender: "spender".to_string(),
+            amount: Uint128::from(50u128),
+            expected_current: None,
+            on_behalf_of: Some("wallet".to_string()),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("forwarder", &[]), msg).unwrap();
+
+        let query_msg = QueryMsg::Allowance {
+            owner: "wallet".to_string(),
+            spender: "spender".to_string(),
+        };
+        let res: AllowanceResponse =
+            from_slice(&query(deps.as_ref(), mock_env(), query_msg).unwrap()).unwrap();
+        assert_eq!(res.allowance, Uint128::from(50u128));
+    }
+
+    #[test]
+    fn only_trusted_forwarder_acts_on_behalf() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        match transfer_on_behalf(deps.as_mut(), "addr1111").unwrap_err() {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        let msg = ExecuteMsg::SetTrustedForwarder { forwarder: None };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        match transfer_on_behalf(deps.as_mut(), "forwarder").unwrap_err() {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+}
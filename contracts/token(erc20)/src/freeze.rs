@@ -0,0 +1,222 @@
+use cosmwasm_std::{from_slice, to_vec, Addr, DepsMut, Env, MessageInfo, Response, Storage};
+use cosmwasm_storage::{PrefixedStorage, ReadonlyPrefixedStorage};
+use cw_utils::Expiration;
+
+use crate::contract::assert_owner;
+use crate::error::ContractError;
+
+pub const PREFIX_FROZEN: &[u8] = b"frozen";
+
+// A frozen account cannot send tokens. Freezes are stored with their expiration,
+// `Expiration::Never` for ones that last until an explicit Unfreeze. Expired
+// freezes are simply ignored, they are not cleaned up.
+
+/// Expiration of the freeze on `address`, if it was ever frozen and not unfrozen
+pub fn read_freeze(
+    store: &dyn Storage,
+    address: &Addr,
+) -> Result<Option<Expiration>, ContractError> {
+    let frozen_store = ReadonlyPrefixedStorage::new(store, PREFIX_FROZEN);
+    match frozen_store.get(address.as_str().as_bytes()) {
+        Some(data) => Ok(Some(from_slice(&data)?)),
+        None => Ok(None),
+    }
+}
+
+pub fn is_frozen(store: &dyn Storage, env: &Env, address: &Addr) -> Result<bool, ContractError> {
+    Ok(match read_freeze(store, address)? {
+        Some(expires) => !expires.is_expired(&env.block),
+        None => false,
+    })
+}
+
+pub fn assert_not_frozen(
+    store: &dyn Storage,
+    env: &Env,
+    address: &Addr,
+) -> Result<(), ContractError> {
+    if is_frozen(store, env, address)? {
+        return Err(ContractError::AccountFrozen {
+            address: address.to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Stop `address` from sending tokens until `expires`, or until unfrozen if
+/// unset. Replaces an earlier freeze of the account. Owner only.
+pub fn try_freeze(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    address: String,
+    expires: Option<Expiration>,
+) -> Result<Response, ContractError> {
+    assert_owner(deps.storage, &info.sender)?;
+    let account = deps.api.addr_validate(&address)?;
+    let expires = expires.unwrap_or_default();
+    if expires.is_expired(&env.block) {
+        return Err(ContractError::InvalidReleaseTime {});
+    }
+    let mut frozen_store = PrefixedStorage::new(deps.storage, PREFIX_FROZEN);
+    frozen_store.set(account.as_str().as_bytes(), &to_vec(&expires)?);
+
+    Ok(Response::new()
+        .add_attribute("action", "freeze")
+        .add_attribute("address", address)
+        .add_attribute("expires", expires.to_string()))
+}
+
+/// Lift the freeze of `address` before it expires. Owner only.
+pub fn try_unfreeze(
+    deps: DepsMut,
+    info: MessageInfo,
+    address: String,
+) -> Result<Response, ContractError> {
+    assert_owner(deps.storage, &info.sender)?;
+    let account = deps.api.addr_validate(&address)?;
+    let mut frozen_store = PrefixedStorage::new(deps.storage, PREFIX_FROZEN);
+    frozen_store.remove(account.as_str().as_bytes());
+
+    Ok(Response::new()
+        .add_attribute("action", "unfreeze")
+        .add_attribute("address", address))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate, query};
+    use crate::msg::{ExecuteMsg, FrozenResponse, InitialBalance, InstantiateMsg, QueryMsg};
+    use crate::testing::{default_instantiate_msg, env_at};
+    use cosmwasm_std::testing::{mock_dependencies_with_balance, mock_info};
+    use cosmwasm_std::{Timestamp, Uint128};
+
+    fn setup(deps: DepsMut) {
+        let instantiate_msg = InstantiateMsg {
+            initial_balances: vec![InitialBalance {
+                address: "addr0000".to_string(),
+                amount: Uint128::from(1000u128),
+            }],
+            wrapped_denom: Some("uluna".to_string()),
+            ..default_instantiate_msg()
+        };
+        instantiate(deps, env_at(0), mock_info("creator", &[]), instantiate_msg).unwrap();
+    }
+
+    fn freeze(deps: DepsMut, expires: Option<Expiration>) -> Result<Response, ContractError> {
+        let msg = ExecuteMsg::Freeze {
+            address: "addr0000".to_string(),
+            expires,
+        };
+        execute(deps, env_at(100), mock_info("creator", &[]), msg)
+    }
+
+    fn transfer(deps: DepsMut, time: u64) -> Result<Response, ContractError> {
+        let msg = ExecuteMsg::Transfer {
+            recipient: "addr1111".to_string(),
+            amount: Uint128::from(10u128),
+            memo: None,
+            on_behalf_of: None,
+        };
+        execute(deps, env_at(time), mock_info("addr0000", &[]), msg)
+    }
+
+    #[test]
+    fn freeze_lapses_at_expiry() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        let expires = Expiration::AtTime(Timestamp::from_seconds(1000));
+        freeze(deps.as_mut(), Some(expires)).unwrap();
+
+        match transfer(deps.as_mut(), 999).unwrap_err() {
+            ContractError::AccountFrozen { address } => assert_eq!(address, "addr0000"),
+            e => panic!("unexpected error: {:?}", e),
+        }
+        let query_msg = QueryMsg::Frozen {
+            address: "addr0000".to_string(),
+        };
+        let res: FrozenResponse =
+            from_slice(&query(deps.as_ref(), env_at(999), query_msg.clone()).unwrap()).unwrap();
+        assert!(res.frozen);
+        assert_eq!(res.expires, Some(expires));
+
+        transfer(deps.as_mut(), 1000).unwrap();
+        let res: FrozenResponse =
+            from_slice(&query(deps.as_ref(), env_at(1000), query_msg).unwrap()).unwrap();
+        assert!(!res.frozen);
+    }
+
+    #[test]
+    fn freeze_without_expiry_lasts_until_unfrozen() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        freeze(deps.as_mut(), None).unwrap();
+        match transfer(deps.as_mut(), 1_000_000).unwrap_err() {
+            ContractError::AccountFrozen { .. } => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        let msg = ExecuteMsg::Unfreeze {
+            address: "addr0000".to_string(),
+        };
+        execute(deps.as_mut(), env_at(200), mock_info("creator", &[]), msg).unwrap();
+        transfer(deps.as_mut(), 300).unwrap();
+    }
+
+    #[test]
+    fn frozen_account_cannot_burn_or_withdraw() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        let msg = ExecuteMsg::IncreaseAllowance {
+            spender: "addr1111".to_string(),
+            amount: Uint128::from(100u128),
+            expires: None,
+        };
+        execute(deps.as_mut(), env_at(50), mock_info("addr0000", &[]), msg).unwrap();
+        freeze(deps.as_mut(), None).unwrap();
+
+        let burn_msg = ExecuteMsg::Burn {
+            amount: Uint128::from(10u128),
+            memo: None,
+        };
+        let burn_from_msg = ExecuteMsg::BurnFrom {
+            owner: "addr0000".to_string(),
+            amount: Uint128::from(10u128),
+            memo: None,
+        };
+        let withdraw_msg = ExecuteMsg::Withdraw {
+            amount: Uint128::from(10u128),
+        };
+        for (sender, msg) in [
+            ("addr0000", burn_msg),
+            ("addr1111", burn_from_msg),
+            ("addr0000", withdraw_msg),
+        ] {
+            match execute(deps.as_mut(), env_at(200), mock_info(sender, &[]), msg).unwrap_err() {
+                ContractError::AccountFrozen { address } => assert_eq!(address, "addr0000"),
+                e => panic!("unexpected error: {:?}", e),
+            }
+        }
+    }
+
+    #[test]
+    fn rejects_expired_freeze_and_non_owner() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        let expires = Expiration::AtTime(Timestamp::from_seconds(100));
+        match freeze(deps.as_mut(), Some(expires)).unwrap_err() {
+            ContractError::InvalidReleaseTime {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        let msg = ExecuteMsg::Freeze {
+            address: "addr1111".to_string(),
+            expires: None,
+        };
+        match execute(deps.as_mut(), env_at(100), mock_info("addr0000", &[]), msg).unwrap_err() {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::{
+    to_binary, Addr, Binary, CosmosMsg, QuerierWrapper, StdResult, Uint128, WasmMsg,
+};
+
+use crate::msg::{AllowanceResponse, BalanceResponse, ExecuteMsg, QueryMsg};
+
+/// Typed handle on a deployed shard token, for contracts integrating with it.
+/// Build the messages to send and run the queries without writing JSON by hand.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ShardTokenContract(pub Addr);
+
+impl ShardTokenContract {
+    pub fn addr(&self) -> Addr {
+        self.0.clone()
+    }
+
+    /// Wrap `msg` into a message executing it on the token, without funds
+    pub fn call(&self, msg: ExecuteMsg) -> StdResult<CosmosMsg> {
+        Ok(WasmMsg::Execute {
+            contract_addr: self.addr().into(),
+            msg: to_binary(&msg)?,
+            funds: vec![],
+        }
+        .into())
+    }
+
+    pub fn transfer_msg(
+        &self,
+        recipient: impl Into<String>,
+        amount: Uint128,
+    ) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::Transfer {
+            recipient: recipient.into(),
+            amount,
+            memo: None,
+            on_behalf_of: None,
+        })
+    }
+
+    /// Transfer to `contract` and call its receive hook with `msg`
+    pub fn send_msg(
+        &self,
+        contract: impl Into<String>,
+        amount: Uint128,
+        msg: Binary,
+    ) -> StdResult<CosmosMsg> {
+        self.call(ExecuteMsg::Send {
+            contract: contract.into(),
+            amount,
+            msg,
+            on_failure: None,
+            memo: None,
+        })
+    }
+
+    pub fn balance(
+        &self,
+        querier: &QuerierWrapper,
+        address: impl Into<String>,
+    ) -> StdResult<Uint128> {
+        let query = QueryMsg::Balance {
+            address: address.into(),
+        };
+        let res: BalanceResponse = querier.query_wasm_smart(self.addr(), &query)?;
+        Ok(res.balance)
+    }
+
+    pub fn allowance(
+        &self,
+        querier: &QuerierWrapper,
+        owner: impl Into<String>,
+        spender: impl Into<String>,
+    ) -> StdResult<Uint128> {
+        let query = QueryMsg::Allowance {
+            owner: owner.into(),
+            spender: spender.into(),
+        };
+        let res: AllowanceResponse = querier.query_wasm_smart(self.addr(), &query)?;
+        Ok(res.allowance)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::from_binary;
+
+    #[test]
+    fn builds_transfer_msg() {
+        let token = ShardTokenContract(Addr::unchecked("token"));
+        let msg = token
+            .transfer_msg("addr1111", Uint128::from(5u128))
+            .unwrap();
+        match msg {
+            CosmosMsg::Wasm(WasmMsg::Execute {
+                contract_addr,
+                msg,
+                funds,
+            }) => {
+                assert_eq!(contract_addr, "token");
+                assert!(funds.is_empty());
+                let msg: ExecuteMsg = from_binary(&msg).unwrap();
+                assert_eq!(
+                    msg,
+                    ExecuteMsg::Transfer {
+                        recipient: "addr1111".to_string(),
+                        amount: Uint128::from(5u128),
+                        memo: None,
+                        on_behalf_of: None,
+                    }
+                );
+            }
+            msg => panic!("unexpected message: {:?}", msg),
+        }
+    }
+}
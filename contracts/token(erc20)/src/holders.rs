@@ -0,0 +1,269 @@
+use cosmwasm_std::{Addr, Api, Env, Order, Storage, Uint128};
+use cosmwasm_storage::{PrefixedStorage, ReadonlyPrefixedStorage};
+use std::convert::TryInto;
+
+use crate::contract::{read_shares, PREFIX_CONFIG};
+use crate::error::ContractError;
+use crate::msg::HolderResponse;
+use crate::pagination::{Page, LIST_LIMITS};
+use crate::rebase;
+
+pub const KEY_HOLDER_COUNT: &[u8] = b"holder_count";
+// Holders ordered by shares: 16 byte big endian shares followed by the address
+pub const PREFIX_HOLDERS_BY_SHARES: &[u8] = b"holders_by_shares";
+
+/// Number of accounts with a nonzero balance, not counting the contract itself
+pub fn read_holder_count(store: &dyn Storage) -> Result<u64, ContractError> {
+    let config_store = ReadonlyPrefixedStorage::new(store, PREFIX_CONFIG);
+    match config_store.get(KEY_HOLDER_COUNT) {
+        Some(data) => match data[..].try_into() {
+            Ok(bytes) => Ok(u64::from_be_bytes(bytes)),
+            Err(_) => Err(ContractError::CorruptedDataFound {}),
+        },
+        None => Ok(0),
+    }
+}
+
+fn index_key(shares: u128, owner: &Addr) -> Vec<u8> {
+    let mut key = shares.to_be_bytes().to_vec();
+    key.extend_from_slice(owner.as_str().as_bytes());
+    key
+}
+
+fn split_index_key(key: &[u8]) -> Result<(u128, String), ContractError> {
+    if key.len() < 16 {
+        return Err(ContractError::CorruptedDataFound {});
+    }
+    let (shares, address) = key.split_at(16);
+    let shares = match shares.try_into() {
+        Ok(bytes) => u128::from_be_bytes(bytes),
+        Err(_) => return Err(ContractError::CorruptedDataFound {}),
+    };
+    match String::from_utf8(address.to_vec()) {
+        Ok(address) => Ok((shares, address)),
+        Err(_) => Err(ContractError::CorruptedDataFound {}),
+    }
+}
+
+/// Accounts with the largest balances first, not counting the contract itself
+pub fn read_top_holders(
+    store: &dyn Storage,
+    limit: Option<u32>,
+) -> Result<Vec<HolderResponse>, ContractError> {
+    let page = Page::after_key(None, limit, &LIST_LIMITS, Order::Descending);
+    let index_store = ReadonlyPrefixedStorage::new(store, PREFIX_HOLDERS_BY_SHARES);
+    index_store
+        .range(page.start(), page.end(), page.order)
+        .take(page.limit)
+        .map(|(key, _)| {
+            let (shares, address) = split_index_key(&key)?;
+            Ok(HolderResponse {
+                address,
+                balance: Uint128::from(rebase::to_amount(store, shares)?),
+            })
+        })
+        .collect()
+}
+
+/// Accounts with a balance above `threshold`, largest first. Pages continue
+/// after the position `start_after` currently holds in the index, so a page
+/// boundary shifts if that account's balance changes in between.
+pub fn read_holders_above(
+    store: &dyn Storage,
+    api: &dyn Api,
+    threshold: u128,
+    start_after: Option<Addr>,
+    limit: Option<u32>,
+) -> Result<Vec<HolderResponse>, ContractError> {
+    let start_after = match start_after {
+        Some(address) => Some(index_key(read_shares(store, api, &address)?, &address)),
+        None => None,
+    };
+    let page = Page::after_key(start_after, limit, &LIST_LIMITS, Order::Descending);
+    let index_store = ReadonlyPrefixedStorage::new(store, PREFIX_HOLDERS_BY_SHARES);
+    let mut holders = vec![];
+    for (key, _) in index_store.range(page.start(), page.end(), page.order) {
+        if holders.len() == page.limit {
+            break;
+        }
+        let (shares, address) = split_index_key(&key)?;
+        let balance = rebase::to_amount(store, shares)?;
+        // the index is ordered by balance, the rest is below the threshold
+        if balance <= threshold {
+            break;
+        }
+        holders.push(HolderResponse {
+            address,
+            balance: Uint128::from(balance),
+        });
+    }
+    Ok(holders)
+}
+
+// Keeps the holder count and the index by shares in step with a write of the
+// shares of `owner`. Shares are zero exactly when the balance is, whatever the
+// rebase ratio.
+pub fn update_holders(
+    store: &mut dyn Storage,
+    env: &Env,
+    owner: &Addr,
+    old_shares: u128,
+    new_shares: u128,
+) -> Result<(), ContractError> {
+    if owner == env.contract.address || old_shares == new_shares {
+        return Ok(());
+    }
+    let mut index_store = PrefixedStorage::new(store, PREFIX_HOLDERS_BY_SHARES);
+    if old_shares > 0 {
+        index_store.remove(&index_key(old_shares, owner));
+    }
+    if new_shares > 0 {
+        index_store.set(&index_key(new_shares, owner), &[1]);
+    }
+
+    if (old_shares == 0) == (new_shares == 0) {
+        return Ok(());
+    }
+    let count = read_holder_count(store)?;
+    let count = if new_shares == 0 {
+        count - 1
+    } else {
+        count + 1
+    };
+    let mut config_store = PrefixedStorage::new(store, PREFIX_CONFIG);
+    config_store.set(KEY_HOLDER_COUNT, &count.to_be_bytes());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate, query};
+    use crate::msg::{
+        ExecuteMsg, HolderCountResponse, HoldersAboveResponse, InitialBalance, InstantiateMsg,
+        QueryMsg, TopHoldersResponse,
+    };
+    use crate::testing::default_instantiate_msg;
+    use cosmwasm_std::testing::{mock_dependencies_with_balance, mock_env, mock_info};
+    use cosmwasm_std::{from_slice, Deps, DepsMut};
+
+    fn setup(deps: DepsMut) {
+        let instantiate_msg = InstantiateMsg {
+            initial_balances: vec![
+                InitialBalance {
+                    address: "addr0000".to_string(),
+                    amount: Uint128::from(1000u128),
+                },
+                InitialBalance {
+                    address: "addr1111".to_string(),
+                    amount: Uint128::zero(),
+                },
+            ],
+            ..default_instantiate_msg()
+        };
+        instantiate(deps, mock_env(), mock_info("creator", &[]), instantiate_msg).unwrap();
+    }
+
+    fn holder_count(deps: Deps) -> u64 {
+        let res: HolderCountResponse =
+            from_slice(&query(deps, mock_env(), QueryMsg::HolderCount {}).unwrap()).unwrap();
+        res.count
+    }
+
+    fn transfer(deps: DepsMut, sender: &str, recipient: &str, amount: u128) {
+        let msg = ExecuteMsg::Transfer {
+            recipient: recipient.to_string(),
+            amount: Uint128::from(amount),
+            memo: None,
+            on_behalf_of: None,
+        };
+        execute(deps, mock_env(), mock_info(sender, &[]), msg).unwrap();
+    }
+
+    #[test]
+    fn counts_accounts_with_a_balance() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        assert_eq!(holder_count(deps.as_ref()), 1);
+
+        transfer(deps.as_mut(), "addr0000", "addr1111", 400);
+        assert_eq!(holder_count(deps.as_ref()), 2);
+        transfer(deps.as_mut(), "addr1111", "addr2222", 100);
+        assert_eq!(holder_count(deps.as_ref()), 3);
+
+        // Emptying an account counts it out
+        transfer(deps.as_mut(), "addr1111", "addr0000", 300);
+        assert_eq!(holder_count(deps.as_ref()), 2);
+
+        let msg = ExecuteMsg::Burn {
+            amount: Uint128::from(100u128),
+            memo: None,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("addr2222", &[]), msg).unwrap();
+        assert_eq!(holder_count(deps.as_ref()), 1);
+    }
+
+    #[test]
+    fn top_holders_follow_balances() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        transfer(deps.as_mut(), "addr0000", "addr1111", 300);
+        transfer(deps.as_mut(), "addr0000", "addr2222", 500);
+
+        let holders = read_top_holders(&deps.storage, None).unwrap();
+        let balances: Vec<(&str, u128)> = holders
+            .iter()
+            .map(|holder| (holder.address.as_str(), holder.balance.u128()))
+            .collect();
+        assert_eq!(
+            balances,
+            vec![("addr2222", 500), ("addr1111", 300), ("addr0000", 200)]
+        );
+
+        transfer(deps.as_mut(), "addr2222", "addr0000", 500);
+        let msg = QueryMsg::TopHolders { limit: Some(1) };
+        let res: TopHoldersResponse =
+            from_slice(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert_eq!(
+            res.holders,
+            vec![HolderResponse {
+                address: "addr0000".to_string(),
+                balance: Uint128::from(700u128),
+            }]
+        );
+        assert_eq!(read_top_holders(&deps.storage, None).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn holders_above_pages_through_the_index() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        transfer(deps.as_mut(), "addr0000", "addr1111", 300);
+        transfer(deps.as_mut(), "addr0000", "addr2222", 100);
+        transfer(deps.as_mut(), "addr0000", "addr3333", 50);
+
+        let holders_above = |deps: Deps, start_after: Option<&str>| {
+            let msg = QueryMsg::HoldersAbove {
+                threshold: Uint128::from(50u128),
+                start_after: start_after.map(|address| address.to_string()),
+                limit: Some(2),
+            };
+            let res: HoldersAboveResponse =
+                from_slice(&query(deps, mock_env(), msg).unwrap()).unwrap();
+            res.holders
+                .into_iter()
+                .map(|holder| (holder.address, holder.balance.u128()))
+                .collect::<Vec<(String, u128)>>()
+        };
+        assert_eq!(
+            holders_above(deps.as_ref(), None),
+            vec![("addr0000".to_string(), 550), ("addr1111".to_string(), 300)]
+        );
+        // addr3333 holds exactly the threshold and is left out
+        assert_eq!(
+            holders_above(deps.as_ref(), Some("addr1111")),
+            vec![("addr2222".to_string(), 100)]
+        );
+        assert!(holders_above(deps.as_ref(), Some("addr2222")).is_empty());
+    }
+}
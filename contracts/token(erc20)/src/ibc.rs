@@ -0,0 +1,225 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    from_slice, to_binary, Binary, DepsMut, Env, IbcBasicResponse, IbcChannelCloseMsg,
+    IbcChannelConnectMsg, IbcChannelOpenMsg, IbcChannelOpenResponse, IbcOrder, IbcPacketAckMsg,
+    IbcPacketReceiveMsg, IbcPacketTimeoutMsg, IbcReceiveResponse, Timestamp, Uint128,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::contract::read_balance;
+use crate::error::ContractError;
+
+// Remote contracts open an unordered channel with this version and send
+// `AttestationPacket`s. Each packet is answered in its acknowledgement, which
+// the remote chain verifies against our state through the IBC light client.
+pub const IBC_VERSION: &str = "shard-token-attest-1";
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AttestationPacket {
+    /// Request the current balance of `address`
+    Balance { address: String },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AttestationAck {
+    Result(BalanceAttestation),
+    Error(String),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BalanceAttestation {
+    pub chain_id: String,
+    pub token: String,
+    pub address: String,
+    pub balance: Uint128,
+    /// Block the balance was read at
+    pub height: u64,
+    pub time: Timestamp,
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_channel_open(
+    _deps: DepsMut,
+    _env: Env,
+    msg: IbcChannelOpenMsg,
+) -> Result<IbcChannelOpenResponse, ContractError> {
+    let channel = msg.channel();
+    if channel.order != IbcOrder::Unordered {
+        return Err(ContractError::OnlyUnorderedChannel {});
+    }
+    if channel.version != IBC_VERSION {
+        return Err(ContractError::InvalidIbcVersion {
+            version: channel.version.clone(),
+        });
+    }
+    if let Some(version) = msg.counterparty_version() {
+        if version != IBC_VERSION {
+            return Err(ContractError::InvalidIbcVersion {
+                version: version.to_string(),
+            });
+        }
+    }
+    Ok(())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_channel_connect(
+    _deps: DepsMut,
+    _env: Env,
+    msg: IbcChannelConnectMsg,
+) -> Result<IbcBasicResponse, ContractError> {
+    let channel = msg.channel();
+    Ok(IbcBasicResponse::new()
+        .add_attribute("action", "ibc_connect")
+        .add_attribute("channel_id", &channel.endpoint.channel_id))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_channel_close(
+    _deps: DepsMut,
+    _env: Env,
+    msg: IbcChannelCloseMsg,
+) -> Result<IbcBasicResponse, ContractError> {
+    let channel = msg.channel();
+    Ok(IbcBasicResponse::new()
+        .add_attribute("action", "ibc_close")
+        .add_attribute("channel_id", &channel.endpoint.channel_id))
+}
+
+/// Answers an attestation request. Failures are reported in the
+/// acknowledgement instead of aborting, so the sender always gets a reply.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_packet_receive(
+    deps: DepsMut,
+    env: Env,
+    msg: IbcPacketReceiveMsg,
+) -> Result<IbcReceiveResponse, ContractError> {
+    let ack = match attest(deps, &env, &msg.packet.data) {
+        Ok(attestation) => AttestationAck::Result(attestation),
+        Err(err) => AttestationAck::Error(err.to_string()),
+    };
+    Ok(IbcReceiveResponse::new()
+        .set_ack(to_binary(&ack)?)
+        .add_attribute("action", "ibc_attest")
+        .add_attribute("channel_id", &msg.packet.dest.channel_id))
+}
+
+fn attest(deps: DepsMut, env: &Env, data: &Binary) -> Result<BalanceAttestation, ContractError> {
+    match from_slice(data)? {
+        AttestationPacket::Balance { address } => {
+            let address_key = deps.api.addr_validate(&address)?;
+            Ok(BalanceAttestation {
+                chain_id: env.block.chain_id.clone(),
+                token: env.contract.address.to_string(),
+                address,
+                balance: Uint128::from(read_balance(deps.storage, deps.api, &address_key)?),
+                height: env.block.height,
+                time: env.block.time,
+            })
+        }
+    }
+}
+
+// This contract never sends packets
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_packet_ack(
+    _deps: DepsMut,
+    _env: Env,
+    _msg: IbcPacketAckMsg,
+) -> Result<IbcBasicResponse, ContractError> {
+    Ok(IbcBasicResponse::new())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_packet_timeout(
+    _deps: DepsMut,
+    _env: Env,
+    _msg: IbcPacketTimeoutMsg,
+) -> Result<IbcBasicResponse, ContractError> {
+    Ok(IbcBasicResponse::new())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::instantiate;
+    use crate::msg::{InitialBalance, InstantiateMsg};
+    use crate::testing::default_instantiate_msg;
+    use cosmwasm_std::testing::{
+        mock_dependencies_with_balance, mock_env, mock_ibc_channel_open_init, mock_ibc_packet_recv,
+        mock_info,
+    };
+
+    fn setup(deps: DepsMut) {
+        let instantiate_msg = InstantiateMsg {
+            initial_balances: vec![InitialBalance {
+                address: "addr0000".to_string(),
+                amount: Uint128::from(11u128),
+            }],
+            ..default_instantiate_msg()
+        };
+        instantiate(deps, mock_env(), mock_info("creator", &[]), instantiate_msg).unwrap();
+    }
+
+    #[test]
+    fn enforces_version_and_ordering() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        let msg = mock_ibc_channel_open_init("channel-0", IbcOrder::Unordered, IBC_VERSION);
+        ibc_channel_open(deps.as_mut(), mock_env(), msg).unwrap();
+
+        let msg = mock_ibc_channel_open_init("channel-0", IbcOrder::Ordered, IBC_VERSION);
+        let err = ibc_channel_open(deps.as_mut(), mock_env(), msg).unwrap_err();
+        match err {
+            ContractError::OnlyUnorderedChannel {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        let msg = mock_ibc_channel_open_init("channel-0", IbcOrder::Unordered, "ics20-1");
+        let err = ibc_channel_open(deps.as_mut(), mock_env(), msg).unwrap_err();
+        match err {
+            ContractError::InvalidIbcVersion { version } => assert_eq!(version, "ics20-1"),
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn acknowledges_with_balance() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        let packet = AttestationPacket::Balance {
+            address: "addr0000".to_string(),
+        };
+        let msg = mock_ibc_packet_recv("channel-0", &packet).unwrap();
+        let res = ibc_packet_receive(deps.as_mut(), mock_env(), msg).unwrap();
+        let ack: AttestationAck = from_slice(&res.acknowledgement).unwrap();
+        let env = mock_env();
+        assert_eq!(
+            ack,
+            AttestationAck::Result(BalanceAttestation {
+                chain_id: env.block.chain_id,
+                token: env.contract.address.to_string(),
+                address: "addr0000".to_string(),
+                balance: Uint128::from(11u128),
+                height: env.block.height,
+                time: env.block.time,
+            })
+        );
+    }
+
+    #[test]
+    fn acknowledges_invalid_packet_with_error() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        let msg = mock_ibc_packet_recv("channel-0", &"unknown").unwrap();
+        let res = ibc_packet_receive(deps.as_mut(), mock_env(), msg).unwrap();
+        let ack: AttestationAck = from_slice(&res.acknowledgement).unwrap();
+        match ack {
+            AttestationAck::Error(_) => {}
+            ack => panic!("unexpected ack: {:?}", ack),
+        }
+    }
+}
@@ -0,0 +1,345 @@
+use cosmwasm_std::{Order, Storage};
+use cosmwasm_storage::{PrefixedStorage, ReadonlyPrefixedStorage};
+use cw2::get_contract_version;
+
+use crate::error::ContractError;
+use crate::msg::{InterfacesResponse, MessageFamily, SupportedInterface};
+
+pub const PREFIX_SUPPORTED_INTERFACES: &[u8] = b"supported_interfaces";
+
+/// Interfaces implemented by every deployment, as (name, version)
+pub const INTERFACES: &[(&str, &str)] = &[
+    ("erc20", "1.0.0"),
+    ("shard-token:buckets", "1.0.0"),
+    ("shard-token:claimable", "1.0.0"),
+    ("shard-token:dividends", "1.0.0"),
+    ("shard-token:dual-control", "1.0.0"),
+    ("shard-token:fees", "1.0.0"),
+    ("shard-token:foreign-assets", "1.0.0"),
+    ("shard-token:ibc-attestation", "1.0.0"),
+    ("shard-token:locks", "1.0.0"),
+    ("shard-token:marketing", "1.0.0"),
+    ("shard-token:pause", "1.0.0"),
+    ("shard-token:permit", "1.0.0"),
+    ("shard-token:rebase", "1.0.0"),
+    ("shard-token:renounce", "1.0.0"),
+    ("shard-token:burn-receipt", "1.0.0"),
+    ("shard-token:buyback", "1.0.0"),
+    ("shard-token:send", "1.0.0"),
+    ("shard-token:streams", "1.0.0"),
+    ("shard-token:timelock", "1.0.0"),
+    ("shard-token:votes", "1.0.0"),
+];
+
+/// Implemented when the token has a minter
+pub const MINT_INTERFACE: (&str, &str) = ("shard-token:mint", "1.0.0");
+
+/// Implemented when the token wraps a native denom
+pub const WRAP_INTERFACE: (&str, &str) = ("shard-token:wrap", "1.0.0");
+
+/// Implemented when the token was instantiated with a premint drip
+pub const DRIP_INTERFACE: (&str, &str) = ("shard-token:drip", "1.0.0");
+
+/// Execute and query messages of each interface, as (name, execute, query).
+/// Interfaces without messages of their own, like IBC attestation, are listed
+/// with empty families.
+const MESSAGE_FAMILIES: &[(&str, &[&str], &[&str])] = &[
+    (
+        "erc20",
+        &[
+            "approve",
+            "approve_many",
+            "increase_allowance",
+            "decrease_allowance",
+            "revoke_all_allowances",
+            "transfer",
+            "transfer_from",
+            "burn",
+            "burn_from",
+            "multicall",
+            "distribute",
+        ],
+        &[
+            "balance",
+            "balances",
+            "simulate_transfer",
+            "allowance",
+            "all_allowances",
+            "all_accounts",
+            "account_stats",
+            "spendable_balance",
+            "token_info",
+            "config",
+            "contract_info",
+        ],
+    ),
+    (
+        "shard-token:buckets",
+        &[
+            "set_bucket",
+            "fund_bucket",
+            "move_between_buckets",
+            "spend_from_bucket",
+        ],
+        &["bucket", "buckets"],
+    ),
+    (
+        "shard-token:claimable",
+        &["transfer_claimable", "claim", "reclaim"],
+        &["claimable"],
+    ),
+    (
+        "shard-token:dividends",
+        &["fund_dividends", "claim_dividends"],
+        &["pending_dividends"],
+    ),
+    (
+        "shard-token:dual-control",
+        &[
+            "set_dual_control",
+            "propose_admin_action",
+            "confirm_admin_action",
+            "cancel_admin_action",
+        ],
+        &["dual_control", "admin_proposal", "admin_proposals"],
+    ),
+    (
+        "shard-token:fees",
+        &["update_fee_config", "update_fee_curve", "set_treasury"],
+        &["fee_config", "current_fee", "treasury"],
+    ),
+    (
+        "shard-token:foreign-assets",
+        &["receive", "sweep_foreign", "sweep_native"],
+        &["foreign_balances"],
+    ),
+    ("shard-token:locks", &["transfer_locked"], &["locks"]),
+    (
+        "shard-token:marketing",
+        &[
+            "update_marketing_admin",
+            "update_display_symbol",
+            "update_token_metadata",
+            "update_marketing",
+            "upload_logo",
+        ],
+        &["asset_card", "marketing_info", "download_logo"],
+    ),
+    (
+        "shard-token:pause",
+        &["pause", "unpause", "vote_unpause"],
+        &["pause_state"],
+    ),
+    (
+        "shard-token:permit",
+        &["permit", "relayed_transfer"],
+        &["nonce"],
+    ),
+    (
+        "shard-token:rebase",
+        &["set_rebaser", "rebase"],
+        &["rebase_state"],
+    ),
+    (
+        "shard-token:renounce",
+        &["renounce_ownership", "renounce_role"],
+        &["renounced"],
+    ),
+    (
+        "shard-token:burn-receipt",
+        &["set_burn_receipt"],
+        &["burn_receipt"],
+    ),
+    (
+        "shard-token:buyback",
+        &["update_buyback", "burn_collected"],
+        &["buyback"],
+    ),
+    (
+        "shard-token:send",
+        &["send", "send_from", "transfer_and_call", "retry_send"],
+        &["failed_sends"],
+    ),
+    (
+        "shard-token:streams",
+        &["create_stream", "withdraw_from_stream", "cancel_stream"],
+        &["stream", "streams_by_recipient"],
+    ),
+    (
+        "shard-token:timelock",
+        &[
+            "update_timelock",
+            "execute_pending_transfer",
+            "cancel_timelocked_transfer",
+        ],
+        &["timelock", "timelocked_transfer", "timelocked_transfers"],
+    ),
+    (
+        "shard-token:votes",
+        &["delegate"],
+        &[
+            "voting_power",
+            "voting_power_at",
+            "total_supply_at",
+            "allowance_at",
+        ],
+    ),
+    (
+        "shard-token:mint",
+        &["mint", "mint_many", "update_minter"],
+        &["minter", "cap"],
+    ),
+    (
+        "shard-token:wrap",
+        &["deposit", "withdraw"],
+        &["wrapped_denom"],
+    ),
+    ("shard-token:drip", &["release_drip"], &["drip"]),
+];
+
+// Interfaces are declared in storage at instantiation, keyed by name with the
+// version as value, so registries can detect them with a single raw query too.
+
+pub fn register_interface(store: &mut dyn Storage, interface: (&str, &str)) {
+    let (name, version) = interface;
+    let mut interfaces_store = PrefixedStorage::new(store, PREFIX_SUPPORTED_INTERFACES);
+    interfaces_store.set(name.as_bytes(), version.as_bytes());
+}
+
+/// All declared interfaces ordered by name
+pub fn read_supported_interfaces(
+    store: &dyn Storage,
+) -> Result<Vec<SupportedInterface>, ContractError> {
+    let interfaces_store = ReadonlyPrefixedStorage::new(store, PREFIX_SUPPORTED_INTERFACES);
+    interfaces_store
+        .range(None, None, Order::Ascending)
+        .map(
+            |(name, version)| match (String::from_utf8(name), String::from_utf8(version)) {
+                (Ok(interface), Ok(version)) => Ok(SupportedInterface { interface, version }),
+                _ => Err(ContractError::CorruptedDataFound {}),
+            },
+        )
+        .collect()
+}
+
+/// Contract version and the message families of all declared interfaces
+pub fn query_interfaces(store: &dyn Storage) -> Result<InterfacesResponse, ContractError> {
+    let contract = get_contract_version(store)?;
+    let families = read_supported_interfaces(store)?
+        .into_iter()
+        .map(|SupportedInterface { interface, version }| {
+            let (execute, query) = MESSAGE_FAMILIES
+                .iter()
+                .find(|(name, _, _)| *name == interface)
+                .map(|(_, execute, query)| (*execute, *query))
+                .unwrap_or_default();
+            MessageFamily {
+                interface,
+                version,
+                execute: execute.iter().map(|msg| msg.to_string()).collect(),
+                query: query.iter().map(|msg| msg.to_string()).collect(),
+            }
+        })
+        .collect();
+    Ok(InterfacesResponse {
+        contract: contract.contract,
+        version: contract.version,
+        families,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{instantiate, query};
+    use crate::msg::{
+        InitialBalance, InstantiateMsg, InterfacesResponse, QueryMsg, SupportedInterfacesResponse,
+    };
+    use crate::testing::default_instantiate_msg;
+    use cosmwasm_std::testing::{mock_dependencies_with_balance, mock_env, mock_info};
+    use cosmwasm_std::{from_slice, Deps, DepsMut, Uint128};
+
+    fn setup(deps: DepsMut, wrapped_denom: Option<String>) {
+        let instantiate_msg = InstantiateMsg {
+            initial_balances: vec![InitialBalance {
+                address: "addr0000".to_string(),
+                amount: Uint128::from(1000u128),
+            }],
+            wrapped_denom,
+            ..default_instantiate_msg()
+        };
+        instantiate(deps, mock_env(), mock_info("creator", &[]), instantiate_msg).unwrap();
+    }
+
+    fn query_interfaces(deps: Deps) -> Vec<String> {
+        let res: SupportedInterfacesResponse =
+            from_slice(&query(deps, mock_env(), QueryMsg::SupportedInterfaces {}).unwrap())
+                .unwrap();
+        res.interfaces
+            .into_iter()
+            .map(|interface| interface.interface)
+            .collect()
+    }
+
+    #[test]
+    fn declares_interfaces_at_instantiation() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut(), None);
+        let interfaces = query_interfaces(deps.as_ref());
+        assert_eq!(interfaces.len(), INTERFACES.len());
+        assert_eq!(interfaces[0], "erc20");
+        assert!(!interfaces.contains(&WRAP_INTERFACE.0.to_string()));
+
+        // also readable without a smart query
+        let interfaces_store =
+            ReadonlyPrefixedStorage::new(&deps.storage, PREFIX_SUPPORTED_INTERFACES);
+        assert_eq!(
+            interfaces_store.get(b"shard-token:votes"),
+            Some(b"1.0.0".to_vec())
+        );
+    }
+
+    #[test]
+    fn wrappers_declare_wrap_interface() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut(), Some("uluna".to_string()));
+        let interfaces = query_interfaces(deps.as_ref());
+        assert_eq!(interfaces.len(), INTERFACES.len() + 1);
+        assert!(interfaces.contains(&WRAP_INTERFACE.0.to_string()));
+    }
+
+    #[test]
+    fn interfaces_list_message_families_and_version() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut(), None);
+        let res: InterfacesResponse =
+            from_slice(&query(deps.as_ref(), mock_env(), QueryMsg::Interfaces {}).unwrap())
+                .unwrap();
+        assert_eq!(res.contract, "crates.io:cw-erc20");
+        assert_eq!(res.version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(res.families.len(), INTERFACES.len());
+        let fees = res
+            .families
+            .iter()
+            .find(|family| family.interface == "shard-token:fees")
+            .unwrap();
+        assert!(fees.execute.contains(&"update_fee_config".to_string()));
+        assert!(fees.query.contains(&"fee_config".to_string()));
+        assert!(!res
+            .families
+            .iter()
+            .any(|family| family.interface == MINT_INTERFACE.0));
+    }
+
+    #[test]
+    fn every_interface_has_a_message_family() {
+        for (name, _) in INTERFACES.iter().chain(&[MINT_INTERFACE, WRAP_INTERFACE]) {
+            assert!(
+                MESSAGE_FAMILIES.iter().any(|(family, _, _)| family == name)
+                    || *name == "shard-token:ibc-attestation",
+                "no message family for {}",
+                name
+            );
+        }
+    }
+}
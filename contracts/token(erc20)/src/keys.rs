@@ -0,0 +1,133 @@
+//! Raw storage keys of the token, for contracts that read its state with
+//! `WasmQuery::Raw` instead of a smart query.
+//!
+//! Balances and allowances are keyed by canonical address, get it from the
+//! address with `Api::addr_canonicalize`. Balances are stored as shares, see
+//! `RebaseState`. Convert them with `shares_to_balance` using the raw total
+//! supply and total shares. Raw allowances ignore expirations and matured
+//! locks are not included in raw balances.
+
+use cosmwasm_std::{from_slice, CanonicalAddr, StdError, StdResult, Uint128};
+use cosmwasm_storage::{to_length_prefixed, to_length_prefixed_nested};
+use std::convert::TryInto;
+
+use crate::contract::{
+    KEY_CONSTANTS, KEY_TOTAL_SUPPLY, PREFIX_ALLOWANCES, PREFIX_BALANCES, PREFIX_CONFIG,
+};
+use crate::rebase::KEY_TOTAL_SHARES;
+use crate::state::Constants;
+
+fn config_key(key: &[u8]) -> Vec<u8> {
+    [&to_length_prefixed(PREFIX_CONFIG)[..], key].concat()
+}
+
+/// Key of the shares of `address`, a 16 byte big endian value
+pub fn balance_key(address: &CanonicalAddr) -> Vec<u8> {
+    [&to_length_prefixed(PREFIX_BALANCES)[..], address.as_slice()].concat()
+}
+
+/// Key of the allowance of `spender` over the tokens of `owner`, a 16 byte big
+/// endian value
+pub fn allowance_key(owner: &CanonicalAddr, spender: &CanonicalAddr) -> Vec<u8> {
+    let namespace = to_length_prefixed_nested(&[PREFIX_ALLOWANCES, owner.as_slice()]);
+    [&namespace[..], spender.as_slice()].concat()
+}
+
+/// Key of the name, symbol and decimals, stored as JSON `Constants`
+pub fn constants_key() -> Vec<u8> {
+    config_key(KEY_CONSTANTS)
+}
+
+/// Key of the total supply, a 16 byte big endian value
+pub fn total_supply_key() -> Vec<u8> {
+    config_key(KEY_TOTAL_SUPPLY)
+}
+
+/// Key of the total shares, a 16 byte big endian value. Unset until the
+/// first rebase on deployments from before rebasing, read the total supply then.
+pub fn total_shares_key() -> Vec<u8> {
+    config_key(KEY_TOTAL_SHARES)
+}
+
+/// Decode a stored amount. A missing value reads as zero.
+pub fn decode_u128(data: Option<&[u8]>) -> StdResult<u128> {
+    match data {
+        Some(data) => match data.try_into() {
+            Ok(bytes) => Ok(u128::from_be_bytes(bytes)),
+            Err(_) => Err(StdError::parse_err("u128", "expected 16 bytes")),
+        },
+        None => Ok(0),
+    }
+}
+
+pub fn decode_constants(data: &[u8]) -> StdResult<Constants> {
+    from_slice(data)
+}
+
+/// Tokens worth `shares`, rounded down, the way `Balance` computes them
+pub fn shares_to_balance(shares: u128, total_supply: u128, total_shares: u128) -> u128 {
+    if total_supply == 0 || total_shares == 0 {
+        return shares;
+    }
+    Uint128::from(shares)
+        .multiply_ratio(total_supply, total_shares)
+        .u128()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate};
+    use crate::msg::{ExecuteMsg, InitialBalance, InstantiateMsg, SupplyDelta};
+    use crate::testing::default_instantiate_msg;
+    use cosmwasm_std::testing::{mock_dependencies_with_balance, mock_env, mock_info};
+    use cosmwasm_std::{Api, Storage};
+
+    #[test]
+    fn keys_read_the_stored_state() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        let instantiate_msg = InstantiateMsg {
+            initial_balances: vec![InitialBalance {
+                address: "addr0000".to_string(),
+                amount: Uint128::from(300u128),
+            }],
+            ..default_instantiate_msg()
+        };
+        let info = mock_info("creator", &[]);
+        instantiate(deps.as_mut(), mock_env(), info.clone(), instantiate_msg).unwrap();
+        let msg = ExecuteMsg::Approve {
+            spender: "spender".to_string(),
+            amount: Uint128::from(50u128),
+            expected_current: None,
+            on_behalf_of: None,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+        let msg = ExecuteMsg::SetRebaser {
+            rebaser: Some("creator".to_string()),
+        };
+        execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        let msg = ExecuteMsg::Rebase {
+            delta: SupplyDelta::Increase(Uint128::from(300u128)),
+        };
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let raw = |key: Vec<u8>| deps.storage.get(&key);
+        let canonical = |address: &str| deps.api.addr_canonicalize(address).unwrap();
+        let constants = decode_constants(&raw(constants_key()).unwrap()).unwrap();
+        assert_eq!(constants.symbol, "CASH");
+        let total_supply = decode_u128(raw(total_supply_key()).as_deref()).unwrap();
+        let total_shares = decode_u128(raw(total_shares_key()).as_deref()).unwrap();
+        assert_eq!((total_supply, total_shares), (600, 300));
+        let shares = decode_u128(raw(balance_key(&canonical("addr0000"))).as_deref()).unwrap();
+        assert_eq!(shares_to_balance(shares, total_supply, total_shares), 600);
+        let allowance = decode_u128(
+            raw(allowance_key(&canonical("addr0000"), &canonical("spender"))).as_deref(),
+        )
+        .unwrap();
+        assert_eq!(allowance, 50);
+        assert_eq!(
+            decode_u128(raw(balance_key(&canonical("addr1111"))).as_deref()).unwrap(),
+            0
+        );
+    }
+}
@@ -0,0 +1,318 @@
+use cosmwasm_std::{
+    from_slice, to_vec, Addr, DepsMut, Env, MessageInfo, Response, Storage, Uint128,
+};
+use cosmwasm_storage::{PrefixedStorage, ReadonlyPrefixedStorage};
+use std::convert::TryInto;
+
+use crate::contract::{add_outcome, perform_transfer, PREFIX_CONFIG};
+use crate::error::ContractError;
+use crate::events;
+use crate::state::{LargeTransferPolicy, PendingTransfer};
+
+pub const PREFIX_LARGE_TRANSFER_POLICIES: &[u8] = b"large_transfer_policies";
+pub const PREFIX_PENDING_TRANSFERS: &[u8] = b"pending_transfers";
+pub const KEY_PENDING_TRANSFER_COUNT: &[u8] = b"pending_transfer_count";
+
+pub fn read_policy(
+    store: &dyn Storage,
+    owner: &Addr,
+) -> Result<Option<LargeTransferPolicy>, ContractError> {
+    let policies_store = ReadonlyPrefixedStorage::new(store, PREFIX_LARGE_TRANSFER_POLICIES);
+    match policies_store.get(owner.as_str().as_bytes()) {
+        Some(data) => Ok(Some(from_slice(&data)?)),
+        None => Ok(None),
+    }
+}
+
+pub fn read_pending_transfer(
+    store: &dyn Storage,
+    id: u64,
+) -> Result<PendingTransfer, ContractError> {
+    let pending_store = ReadonlyPrefixedStorage::new(store, PREFIX_PENDING_TRANSFERS);
+    match pending_store.get(&id.to_be_bytes()) {
+        Some(data) => Ok(from_slice(&data)?),
+        None => Err(ContractError::PendingTransferNotFound { id }),
+    }
+}
+
+/// Errors if `owner` requires the two-phase flow for a transfer of `amount`
+pub fn assert_not_large(
+    store: &dyn Storage,
+    owner: &Addr,
+    amount: u128,
+) -> Result<(), ContractError> {
+    if let Some(policy) = read_policy(store, owner)? {
+        if amount > policy.threshold.u128() {
+            return Err(ContractError::LargeTransferNeedsConfirmation {
+                threshold: policy.threshold.u128(),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Opt in to (or out of, with `None`) the two-phase flow for transfers of the
+/// signer above a threshold
+pub fn try_set_policy(
+    deps: DepsMut,
+    info: MessageInfo,
+    threshold: Option<Uint128>,
+    window: u64,
+    co_signer: Option<String>,
+) -> Result<Response, ContractError> {
+    let co_signer = co_signer
+        .map(|co_signer| deps.api.addr_validate(&co_signer))
+        .transpose()?;
+    let mut policies_store = PrefixedStorage::new(deps.storage, PREFIX_LARGE_TRANSFER_POLICIES);
+    match threshold {
+        Some(threshold) => policies_store.set(
+            info.sender.as_str().as_bytes(),
+            &to_vec(&LargeTransferPolicy {
+                threshold,
+                window,
+                co_signer,
+            })?,
+        ),
+        None => policies_store.remove(info.sender.as_str().as_bytes()),
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "set_large_transfer_policy")
+        .add_attribute("account", info.sender))
+}
+
+/// First phase of a large transfer. Records the transfer without moving any
+/// tokens; it has to be confirmed within the window of the sender's policy.
+pub fn try_initiate(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipient: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let recipient_address = deps.api.addr_validate(&recipient)?;
+    let window = match read_policy(deps.storage, &info.sender)? {
+        Some(policy) => policy.window,
+        None => return Err(ContractError::NoLargeTransferPolicy {}),
+    };
+
+    let mut config_store = PrefixedStorage::new(deps.storage, PREFIX_CONFIG);
+    let id = match config_store.get(KEY_PENDING_TRANSFER_COUNT) {
+        Some(data) => match data[..].try_into() {
+            Ok(bytes) => u64::from_be_bytes(bytes),
+            Err(_) => return Err(ContractError::CorruptedDataFound {}),
+        },
+        None => 0u64,
+    };
+    config_store.set(KEY_PENDING_TRANSFER_COUNT, &(id + 1).to_be_bytes());
+
+    let mut pending_store = PrefixedStorage::new(deps.storage, PREFIX_PENDING_TRANSFERS);
+    pending_store.set(
+        &id.to_be_bytes(),
+        &to_vec(&PendingTransfer {
+            sender: info.sender.clone(),
+            recipient: recipient_address,
+            amount,
+            expires_at: env.block.time.seconds() + window,
+        })?,
+    );
+
+    Ok(Response::new()
+        .add_attribute("action", "initiate_large_transfer")
+        .add_attribute("id", id.to_string())
+        .add_attribute("sender", info.sender)
+        .add_attribute("recipient", recipient)
+        .add_attribute("amount", amount.to_string()))
+}
+
+/// Second phase of a large transfer, by the sender or the co-signer of its policy
+pub fn try_confirm(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    let pending = read_pending_transfer(deps.storage, id)?;
+    let co_signer = read_policy(deps.storage, &pending.sender)?.and_then(|policy| policy.co_signer);
+    if info.sender != pending.sender && Some(&info.sender) != co_signer.as_ref() {
+        return Err(ContractError::Unauthorized {});
+    }
+    if env.block.time.seconds() > pending.expires_at {
+        return Err(ContractError::PendingTransferExpired { id });
+    }
+
+    let mut pending_store = PrefixedStorage::new(deps.storage, PREFIX_PENDING_TRANSFERS);
+    pending_store.remove(&id.to_be_bytes());
+    let outcome = perform_transfer(
+        deps.storage,
+        deps.api,
+        &deps.querier,
+        &env,
+        &pending.sender,
+        &pending.recipient,
+        pending.amount.u128(),
+    )?;
+    let event = events::transfer_event(
+        deps.storage,
+        deps.api,
+        &pending.sender,
+        &pending.recipient,
+        pending.amount.u128(),
+        outcome,
+    )?;
+
+    let res = Response::new()
+        .add_event(event)
+        .add_attribute("action", "confirm_large_transfer")
+        .add_attribute("id", id.to_string())
+        .add_attribute("sender", pending.sender)
+        .add_attribute("recipient", pending.recipient);
+    Ok(add_outcome(res, outcome))
+}
+
+/// Drop a pending large transfer. Sender only.
+pub fn try_cancel(deps: DepsMut, info: MessageInfo, id: u64) -> Result<Response, ContractError> {
+    let pending = read_pending_transfer(deps.storage, id)?;
+    if info.sender != pending.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+    let mut pending_store = PrefixedStorage::new(deps.storage, PREFIX_PENDING_TRANSFERS);
+    pending_store.remove(&id.to_be_bytes());
+
+    Ok(Response::new()
+        .add_attribute("action", "cancel_large_transfer")
+        .add_attribute("id", id.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate, read_balance};
+    use crate::msg::{ExecuteMsg, InitialBalance, InstantiateMsg};
+    use crate::testing::{default_instantiate_msg, env_at};
+    use cosmwasm_std::testing::{mock_dependencies_with_balance, mock_info, MockApi};
+
+    fn setup(deps: DepsMut) {
+        let instantiate_msg = InstantiateMsg {
+            initial_balances: vec![InitialBalance {
+                address: "treasury".to_string(),
+                amount: Uint128::from(10_000u128),
+            }],
+            ..default_instantiate_msg()
+        };
+        instantiate(deps, env_at(0), mock_info("creator", &[]), instantiate_msg).unwrap();
+    }
+
+    fn set_policy(deps: DepsMut) {
+        let msg = ExecuteMsg::SetLargeTransferPolicy {
+            threshold: Some(Uint128::from(1000u128)),
+            window: 3600,
+            co_signer: Some("cfo".to_string()),
+        };
+        execute(deps, env_at(0), mock_info("treasury", &[]), msg).unwrap();
+    }
+
+    fn initiate(deps: DepsMut, amount: u128) {
+        let msg = ExecuteMsg::InitiateLargeTransfer {
+            recipient: "vendor".to_string(),
+            amount: Uint128::from(amount),
+        };
+        execute(deps, env_at(100), mock_info("treasury", &[]), msg).unwrap();
+    }
+
+    fn confirm(deps: DepsMut, signer: &str, time: u64, id: u64) -> Result<Response, ContractError> {
+        let msg = ExecuteMsg::ConfirmLargeTransfer { id };
+        execute(deps, env_at(time), mock_info(signer, &[]), msg)
+    }
+
+    fn balance(storage: &dyn Storage, address: &str) -> u128 {
+        read_balance(storage, &MockApi::default(), &Addr::unchecked(address)).unwrap()
+    }
+
+    #[test]
+    fn large_direct_transfer_is_rejected() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        set_policy(deps.as_mut());
+
+        let transfer_msg = ExecuteMsg::Transfer {
+            recipient: "vendor".to_string(),
+            amount: Uint128::from(1001u128),
+            memo: None,
+            on_behalf_of: None,
+        };
+        let err = execute(
+            deps.as_mut(),
+            env_at(100),
+            mock_info("treasury", &[]),
+            transfer_msg,
+        )
+        .unwrap_err();
+        match err {
+            ContractError::LargeTransferNeedsConfirmation { threshold } => {
+                assert_eq!(threshold, 1000)
+            }
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        // amounts up to the threshold go through directly
+        let transfer_msg = ExecuteMsg::Transfer {
+            recipient: "vendor".to_string(),
+            amount: Uint128::from(1000u128),
+            memo: None,
+            on_behalf_of: None,
+        };
+        execute(
+            deps.as_mut(),
+            env_at(100),
+            mock_info("treasury", &[]),
+            transfer_msg,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn co_signer_confirms_within_window() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        set_policy(deps.as_mut());
+        initiate(deps.as_mut(), 5000);
+        assert_eq!(balance(&deps.storage, "treasury"), 10_000);
+
+        let err = confirm(deps.as_mut(), "intruder", 200, 0).unwrap_err();
+        match err {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        confirm(deps.as_mut(), "cfo", 200, 0).unwrap();
+        assert_eq!(balance(&deps.storage, "treasury"), 5000);
+        assert_eq!(balance(&deps.storage, "vendor"), 5000);
+
+        // cannot be confirmed twice
+        let err = confirm(deps.as_mut(), "treasury", 200, 0).unwrap_err();
+        match err {
+            ContractError::PendingTransferNotFound { id } => assert_eq!(id, 0),
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn expired_or_cancelled_transfers_move_nothing() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        set_policy(deps.as_mut());
+        initiate(deps.as_mut(), 5000);
+        initiate(deps.as_mut(), 6000);
+
+        let err = confirm(deps.as_mut(), "treasury", 3701, 0).unwrap_err();
+        match err {
+            ContractError::PendingTransferExpired { id } => assert_eq!(id, 0),
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        let msg = ExecuteMsg::CancelLargeTransfer { id: 1 };
+        execute(deps.as_mut(), env_at(200), mock_info("treasury", &[]), msg).unwrap();
+        confirm(deps.as_mut(), "treasury", 200, 1).unwrap_err();
+        assert_eq!(balance(&deps.storage, "treasury"), 10_000);
+    }
+}
@@ -1,9 +1,87 @@
+mod amount;
+mod buyback;
+mod cap;
+mod circulating;
+mod claimable;
+mod clawback;
+mod compliance;
 pub mod contract;
+mod deflation;
+mod distribute;
+mod dividends;
+mod drip;
+mod dualcontrol;
 mod error;
-mod msg;
+mod events;
+mod export;
+mod fees;
+mod foreign;
+mod forwarder;
+mod freeze;
+mod helpers;
+mod holders;
+pub mod ibc;
+mod interfaces;
+pub mod keys;
+mod largetransfer;
+mod limits;
+mod locks;
+mod lockup;
+mod marketing;
+mod migrate;
+mod mint;
+pub mod msg;
+mod multicall;
+mod pagination;
+mod pause;
+mod periodic;
+mod permit;
+mod ratelimit;
+mod rebase;
+mod receipt;
+mod recovery;
+mod relay;
+mod renounce;
+mod report;
+mod send;
+mod signing;
+mod simulate;
 mod state;
+mod streams;
+mod subscriptions;
+mod sudo;
+#[cfg(test)]
+mod testing;
+mod timelock;
+mod treasury;
+mod votes;
+mod wrap;
 
+pub use error::ContractError;
+pub use helpers::ShardTokenContract;
+pub use ibc::{AttestationAck, AttestationPacket, BalanceAttestation, IBC_VERSION};
+pub use migrate::migrate;
 pub use msg::{
-    AllowanceResponse, BalanceResponse, ExecuteMsg, InitialBalance, InstantiateMsg, QueryMsg,
+    AccountStatsResponse, AdminProposalResponse, AdminProposalsResponse, AllAccountsResponse,
+    AllAllowancesResponse, AllowanceInfo, AllowanceResponse, AssetCardResponse, AssetRestrictions,
+    BalanceResponse, BucketResponse, BucketsResponse, BurnReceiptInfo, BurnReceiptResponse,
+    BuybackResponse, CapResponse, ClaimableResponse, ClawbackAdminResponse, CurrentFeeResponse,
+    DividendResponse, DownloadLogoResponse, DripInfo, DripResponse, DualControlResponse,
+    EmbeddedLogo, ExecuteMsg, ExportSection, ExportStateResponse, ExportedAllowance,
+    ExportedBalance, ExportedConfig, FailedSendResponse, FailedSendsResponse, FeeConfigResponse,
+    ForeignBalance, ForeignBalancesResponse, HolderCountResponse, HolderResponse,
+    HoldersAboveResponse, InitialBalance, InstantiateMsg, LargeTransferPolicyResponse,
+    LockResponse, LocksResponse, Logo, LogoInfo, MarketingInfoResponse, MigrateMsg, MinterInfo,
+    MinterResponse, NonceResponse, PauseStateResponse, PendingDividendsResponse,
+    PendingTransferResponse, QueryMsg, RateLimitResponse, RebaseStateResponse, ReceiveMsg,
+    RenouncedResponse, Role, SpendingReportResponse, StreamResponse, StreamsResponse,
+    StuckBalanceResponse, SudoMsg, SupplyDelta, SupportedInterface, SupportedInterfacesResponse,
+    TimelockResponse, TimelockedTransferResponse, TimelockedTransfersResponse, TokenInfoResponse,
+    TopHoldersResponse, TotalSupplyResponse, TransferBurnResponse, TransferLimitsResponse,
+    VotingPowerResponse, WrappedDenomResponse,
 };
-pub use state::Constants;
+pub use send::reply;
+pub use state::{
+    Constants, DividendAsset, FeeCurve, FeeStep, RateLimit, ReceiptMode, SendFailureMode, Timelock,
+};
+pub use sudo::sudo;
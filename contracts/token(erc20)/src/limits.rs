@@ -0,0 +1,453 @@
+use cosmwasm_std::{
+    from_slice, to_vec, Addr, Api, DepsMut, Env, MessageInfo, Response, Storage, Uint128,
+};
+use cosmwasm_storage::{PrefixedStorage, ReadonlyPrefixedStorage};
+
+use crate::contract::{assert_owner, bytes_to_u128, checked_add, read_balance, PREFIX_CONFIG};
+use crate::error::ContractError;
+use crate::locks;
+use crate::state::TransferLimits;
+
+pub const KEY_TRANSFER_LIMITS: &[u8] = b"transfer_limits";
+pub const KEY_MIN_TRANSFER_AMOUNT: &[u8] = b"min_transfer_amount";
+pub const KEY_ZERO_TRANSFERS_DISABLED: &[u8] = b"zero_transfers_disabled";
+
+pub fn read_transfer_limits(store: &dyn Storage) -> Result<TransferLimits, ContractError> {
+    let config_store = ReadonlyPrefixedStorage::new(store, PREFIX_CONFIG);
+    match config_store.get(KEY_TRANSFER_LIMITS) {
+        Some(data) => Ok(from_slice(&data)?),
+        None => Ok(TransferLimits::default()),
+    }
+}
+
+pub fn write_transfer_limits(
+    store: &mut dyn Storage,
+    limits: &TransferLimits,
+) -> Result<(), ContractError> {
+    let mut config_store = PrefixedStorage::new(store, PREFIX_CONFIG);
+    config_store.set(KEY_TRANSFER_LIMITS, &to_vec(limits)?);
+    Ok(())
+}
+
+/// Replace the anti-whale limits. Owner only.
+///
+/// @param max_tx_amount largest amount a single transfer may move, unlimited if unset
+/// @param max_wallet_amount largest balance a transfer may leave the recipient with,
+/// unlimited if unset
+/// @param exempt accounts (e.g. pools, treasury) to which neither limit applies
+pub fn try_update_transfer_limits(
+    deps: DepsMut,
+    info: MessageInfo,
+    max_tx_amount: Option<Uint128>,
+    max_wallet_amount: Option<Uint128>,
+    exempt: Vec<String>,
+) -> Result<Response, ContractError> {
+    assert_owner(deps.storage, &info.sender)?;
+    update_transfer_limits(deps, max_tx_amount, max_wallet_amount, exempt)
+}
+
+// Replaces the anti-whale limits, without checking who asked for it
+pub fn update_transfer_limits(
+    deps: DepsMut,
+    max_tx_amount: Option<Uint128>,
+    max_wallet_amount: Option<Uint128>,
+    exempt: Vec<String>,
+) -> Result<Response, ContractError> {
+    let exempt = exempt
+        .iter()
+        .map(|address| deps.api.addr_validate(address))
+        .collect::<Result<Vec<_>, _>>()?;
+    write_transfer_limits(
+        deps.storage,
+        &TransferLimits {
+            max_tx_amount,
+            max_wallet_amount,
+            exempt,
+        },
+    )?;
+
+    Ok(Response::new().add_attribute("action", "update_transfer_limits"))
+}
+
+/// Smallest amount a transfer may move, zero if there is no minimum
+pub fn read_min_transfer_amount(store: &dyn Storage) -> Result<u128, ContractError> {
+    let config_store = ReadonlyPrefixedStorage::new(store, PREFIX_CONFIG);
+    match config_store.get(KEY_MIN_TRANSFER_AMOUNT) {
+        Some(data) => bytes_to_u128(&data),
+        None => Ok(0),
+    }
+}
+
+/// Reject transfers below `amount` to keep dust out of the state, or allow any
+/// amount again with zero. Mints and burns are not affected. Owner only.
+pub fn try_set_min_transfer_amount(
+    deps: DepsMut,
+    info: MessageInfo,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    assert_owner(deps.storage, &info.sender)?;
+    let mut config_store = PrefixedStorage::new(deps.storage, PREFIX_CONFIG);
+    config_store.set(KEY_MIN_TRANSFER_AMOUNT, &amount.u128().to_be_bytes());
+
+    Ok(Response::new()
+        .add_attribute("action", "set_min_transfer_amount")
+        .add_attribute("amount", amount.to_string()))
+}
+
+/// Whether Transfer, TransferFrom and Send accept an amount of zero. Set at
+/// instantiation, contracts instantiated before the flag existed allow them.
+pub fn read_allow_zero_transfers(store: &dyn Storage) -> bool {
+    let config_store = ReadonlyPrefixedStorage::new(store, PREFIX_CONFIG);
+    config_store.get(KEY_ZERO_TRANSFERS_DISABLED).is_none()
+}
+
+pub fn write_allow_zero_transfers(store: &mut dyn Storage, allow: bool) {
+    let mut config_store = PrefixedStorage::new(store, PREFIX_CONFIG);
+    if allow {
+        config_store.remove(KEY_ZERO_TRANSFERS_DISABLED);
+    } else {
+        config_store.set(KEY_ZERO_TRANSFERS_DISABLED, &[1]);
+    }
+}
+
+pub fn assert_zero_transfer_allowed(
+    store: &dyn Storage,
+    amount: u128,
+) -> Result<(), ContractError> {
+    if amount == 0 && !read_allow_zero_transfers(store) {
+        return Err(ContractError::ZeroTransfer {});
+    }
+    Ok(())
+}
+
+/// Errors if `amount` is below the minimum transfer amount. Payouts from escrow
+/// do not check it, so escrowed balances can always be paid out.
+pub fn assert_min_transfer(store: &dyn Storage, amount: u128) -> Result<(), ContractError> {
+    let min = read_min_transfer_amount(store)?;
+    if amount < min {
+        return Err(ContractError::BelowMinimumTransfer { min, amount });
+    }
+    Ok(())
+}
+
+/// Checks a transfer of `amount` from `from` of which `to` receives `received`
+/// against both limits.
+///
+/// Transfers from or to an exempt account skip both limits, so pools can still
+/// be filled and drained.
+pub fn check_transfer(
+    store: &dyn Storage,
+    api: &dyn Api,
+    env: &Env,
+    from: &Addr,
+    to: &Addr,
+    amount: u128,
+    received: u128,
+) -> Result<(), ContractError> {
+    assert_max_tx(store, from, to, amount)?;
+    assert_max_wallet(store, api, env, from, to, received)
+}
+
+/// Errors if a transfer of `amount` from `from` to `to` exceeds the maximum per
+/// transaction. Checked for deposits into escrow as well.
+pub fn assert_max_tx(
+    store: &dyn Storage,
+    from: &Addr,
+    to: &Addr,
+    amount: u128,
+) -> Result<(), ContractError> {
+    let limits = read_transfer_limits(store)?;
+    if limits.exempt.contains(from) || limits.exempt.contains(to) {
+        return Ok(());
+    }
+    match limits.max_tx_amount {
+        Some(max) if amount > max.u128() => Err(ContractError::MaxTxAmountExceeded {
+            max: max.u128(),
+            amount,
+        }),
+        _ => Ok(()),
+    }
+}
+
+/// Errors if crediting `received` leaves `to` above the maximum wallet amount.
+/// Checked whenever tokens leave escrow, and locks count towards the balance
+/// since they are credited once they mature. The contract's own account holds
+/// the escrows and has no maximum.
+pub fn assert_max_wallet(
+    store: &dyn Storage,
+    api: &dyn Api,
+    env: &Env,
+    from: &Addr,
+    to: &Addr,
+    received: u128,
+) -> Result<(), ContractError> {
+    let limits = read_transfer_limits(store)?;
+    if limits.exempt.contains(from) || limits.exempt.contains(to) || *to == env.contract.address {
+        return Ok(());
+    }
+    let max = match limits.max_wallet_amount {
+        Some(max) => max.u128(),
+        None => return Ok(()),
+    };
+    let locked = locks::read_locks(store, to)?
+        .iter()
+        .try_fold(0u128, |sum, lock| checked_add(sum, lock.amount.u128()))?;
+    let balance = checked_add(
+        checked_add(read_balance(store, api, to)?, locked)?,
+        received,
+    )?;
+    if balance > max {
+        return Err(ContractError::MaxWalletAmountExceeded { max, balance });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate, query};
+    use crate::msg::{
+        ExecuteMsg, InitialBalance, InstantiateMsg, QueryMsg, TransferLimitsResponse,
+    };
+    use crate::testing::default_instantiate_msg;
+    use cosmwasm_std::testing::{mock_dependencies_with_balance, mock_env, mock_info};
+
+    fn setup(mut deps: DepsMut) {
+        let instantiate_msg = InstantiateMsg {
+            initial_balances: vec![
+                InitialBalance {
+                    address: "addr0000".to_string(),
+                    amount: Uint128::from(1000u128),
+                },
+                InitialBalance {
+                    address: "pool".to_string(),
+                    amount: Uint128::from(1000u128),
+                },
+            ],
+            ..default_instantiate_msg()
+        };
+        instantiate(
+            deps.branch(),
+            mock_env(),
+            mock_info("creator", &[]),
+            instantiate_msg,
+        )
+        .unwrap();
+        let msg = ExecuteMsg::UpdateTransferLimits {
+            max_tx_amount: Some(Uint128::from(100u128)),
+            max_wallet_amount: Some(Uint128::from(150u128)),
+            exempt: vec!["pool".to_string()],
+        };
+        execute(deps, mock_env(), mock_info("creator", &[]), msg).unwrap();
+    }
+
+    fn transfer(
+        deps: DepsMut,
+        sender: &str,
+        recipient: &str,
+        amount: u128,
+    ) -> Result<Response, ContractError> {
+        let msg = ExecuteMsg::Transfer {
+            recipient: recipient.to_string(),
+            amount: Uint128::from(amount),
+            memo: None,
+            on_behalf_of: None,
+        };
+        execute(deps, mock_env(), mock_info(sender, &[]), msg)
+    }
+
+    #[test]
+    fn enforces_max_tx_amount() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        transfer(deps.as_mut(), "addr0000", "addr1111", 100).unwrap();
+        let err = transfer(deps.as_mut(), "addr0000", "addr2222", 101).unwrap_err();
+        match err {
+            ContractError::MaxTxAmountExceeded { max, amount } => {
+                assert_eq!(max, 100);
+                assert_eq!(amount, 101);
+            }
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn enforces_max_wallet_amount() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        transfer(deps.as_mut(), "addr0000", "addr1111", 100).unwrap();
+        let err = transfer(deps.as_mut(), "addr0000", "addr1111", 51).unwrap_err();
+        match err {
+            ContractError::MaxWalletAmountExceeded { max, balance } => {
+                assert_eq!(max, 150);
+                assert_eq!(balance, 151);
+            }
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn escrows_respect_limits() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        let release_at = mock_env().block.time.seconds() + 100;
+        let transfer_locked = |deps: DepsMut, amount: u128| {
+            let msg = ExecuteMsg::TransferLocked {
+                recipient: "addr1111".to_string(),
+                amount: Uint128::from(amount),
+                release_at,
+            };
+            execute(deps, mock_env(), mock_info("addr0000", &[]), msg)
+        };
+        match transfer_locked(deps.as_mut(), 101).unwrap_err() {
+            ContractError::MaxTxAmountExceeded {
+                max: 100,
+                amount: 101,
+            } => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        transfer(deps.as_mut(), "addr0000", "addr1111", 100).unwrap();
+        match transfer_locked(deps.as_mut(), 51).unwrap_err() {
+            ContractError::MaxWalletAmountExceeded {
+                max: 150,
+                balance: 151,
+            } => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        transfer_locked(deps.as_mut(), 50).unwrap();
+        // the lock counts towards the wallet before it matures
+        match transfer(deps.as_mut(), "addr0000", "addr1111", 1).unwrap_err() {
+            ContractError::MaxWalletAmountExceeded {
+                max: 150,
+                balance: 151,
+            } => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        // and is checked again when it is credited
+        let msg = ExecuteMsg::UpdateTransferLimits {
+            max_tx_amount: Some(Uint128::from(100u128)),
+            max_wallet_amount: Some(Uint128::from(120u128)),
+            exempt: vec!["pool".to_string()],
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(100);
+        let owner = Addr::unchecked("addr1111");
+        match locks::release_matured(&mut deps.storage, &deps.api, &env, &owner).unwrap_err() {
+            ContractError::MaxWalletAmountExceeded {
+                max: 120,
+                balance: 150,
+            } => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn exempt_accounts_skip_limits() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        transfer(deps.as_mut(), "pool", "addr1111", 500).unwrap();
+        transfer(deps.as_mut(), "addr0000", "pool", 800).unwrap();
+
+        let res: TransferLimitsResponse =
+            from_slice(&query(deps.as_ref(), mock_env(), QueryMsg::TransferLimits {}).unwrap())
+                .unwrap();
+        assert_eq!(res.max_tx_amount, Some(Uint128::from(100u128)));
+        assert_eq!(res.max_wallet_amount, Some(Uint128::from(150u128)));
+        assert_eq!(res.exempt, vec!["pool".to_string()]);
+    }
+
+    #[test]
+    fn enforces_min_transfer_amount() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        let msg = ExecuteMsg::SetMinTransferAmount {
+            amount: Uint128::from(10u128),
+        };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("addr0000", &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        match err {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        transfer(deps.as_mut(), "addr0000", "addr1111", 10).unwrap();
+        let err = transfer(deps.as_mut(), "pool", "addr1111", 9).unwrap_err();
+        match err {
+            ContractError::BelowMinimumTransfer { min, amount } => {
+                assert_eq!(min, 10);
+                assert_eq!(amount, 9);
+            }
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        let msg = ExecuteMsg::Burn {
+            amount: Uint128::from(1u128),
+            memo: None,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+    }
+
+    #[test]
+    fn zero_transfers_follow_instantiate_flag() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        transfer(deps.as_mut(), "addr0000", "addr1111", 0).unwrap();
+
+        let mut deps = mock_dependencies_with_balance(&[]);
+        let instantiate_msg = InstantiateMsg {
+            initial_balances: vec![InitialBalance {
+                address: "addr0000".to_string(),
+                amount: Uint128::from(1000u128),
+            }],
+            allow_zero_transfers: false,
+            ..default_instantiate_msg()
+        };
+        instantiate(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            instantiate_msg,
+        )
+        .unwrap();
+        match transfer(deps.as_mut(), "addr0000", "addr1111", 0).unwrap_err() {
+            ContractError::ZeroTransfer {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        let msg = ExecuteMsg::TransferFrom {
+            owner: "addr0000".to_string(),
+            recipient: "addr1111".to_string(),
+            amount: Uint128::zero(),
+            memo: None,
+        };
+        match execute(deps.as_mut(), mock_env(), mock_info("addr1111", &[]), msg).unwrap_err() {
+            ContractError::ZeroTransfer {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        transfer(deps.as_mut(), "addr0000", "addr1111", 1).unwrap();
+    }
+
+    #[test]
+    fn only_owner_can_update() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        let msg = ExecuteMsg::UpdateTransferLimits {
+            max_tx_amount: None,
+            max_wallet_amount: None,
+            exempt: vec![],
+        };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap_err();
+        match err {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+}
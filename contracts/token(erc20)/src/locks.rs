@@ -0,0 +1,282 @@
+use cosmwasm_std::{Addr, Api, DepsMut, Env, MessageInfo, Order, Response, Storage, Uint128};
+use cosmwasm_storage::{PrefixedStorage, ReadonlyPrefixedStorage};
+use std::convert::TryInto;
+
+use crate::contract::{
+    bytes_to_u128, checked_add, checked_sub, escrow_deposit, settle_transfer, TransferKind,
+};
+use crate::deflation;
+use crate::error::ContractError;
+use crate::events;
+use crate::fees;
+use crate::largetransfer;
+use crate::limits;
+use crate::msg::LockResponse;
+
+pub const PREFIX_LOCKS: &[u8] = b"locks";
+
+// Locked tokens are held on the contract's own account. Locks of an account are
+// keyed by big endian release time, so matured locks form a prefix. They are
+// released lazily: every execution touching the account first moves them into
+// the liquid balance, and queries count them as liquid already.
+
+/// Transfer tokens that the recipient can only move after `release_at`
+///
+/// @param release_at block time in seconds the tokens become liquid at
+pub fn try_transfer_locked(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipient: String,
+    amount: Uint128,
+    release_at: u64,
+) -> Result<Response, ContractError> {
+    let recipient_address = deps.api.addr_validate(&recipient)?;
+    if release_at <= env.block.time.seconds() {
+        return Err(ContractError::InvalidReleaseTime {});
+    }
+    largetransfer::assert_not_large(deps.storage, &info.sender, amount.u128())?;
+    // The lock is credited to the recipient once it matures
+    limits::assert_max_wallet(
+        deps.storage,
+        deps.api,
+        &env,
+        &info.sender,
+        &recipient_address,
+        amount.u128(),
+    )?;
+    let outcome = escrow_deposit(
+        deps.storage,
+        deps.api,
+        &deps.querier,
+        &env,
+        &info.sender,
+        amount.u128(),
+    )?;
+    let transfer = events::transfer_event(
+        deps.storage,
+        deps.api,
+        &info.sender,
+        &env.contract.address,
+        amount.u128(),
+        outcome,
+    )?;
+    let namespace = [PREFIX_LOCKS, recipient_address.as_str().as_bytes()];
+    let mut locks_store = PrefixedStorage::multilevel(deps.storage, &namespace);
+    let locked = match locks_store.get(&release_at.to_be_bytes()) {
+        Some(data) => bytes_to_u128(&data)?,
+        None => 0u128,
+    };
+    locks_store.set(
+        &release_at.to_be_bytes(),
+        &checked_add(locked, amount.u128())?.to_be_bytes(),
+    );
+
+    Ok(Response::new()
+        .add_event(transfer)
+        .add_attribute("action", "transfer_locked")
+        .add_attribute("sender", info.sender)
+        .add_attribute("recipient", recipient)
+        .add_attribute("amount", amount.to_string())
+        .add_attribute("release_at", release_at.to_string()))
+}
+
+/// All locks of `owner` ordered by release time, matured ones included
+pub fn read_locks(store: &dyn Storage, owner: &Addr) -> Result<Vec<LockResponse>, ContractError> {
+    let locks_store =
+        ReadonlyPrefixedStorage::multilevel(store, &[PREFIX_LOCKS, owner.as_str().as_bytes()]);
+    locks_store
+        .range(None, None, Order::Ascending)
+        .map(|(key, data)| {
+            let release_at = match key[..].try_into() {
+                Ok(bytes) => u64::from_be_bytes(bytes),
+                Err(_) => return Err(ContractError::CorruptedDataFound {}),
+            };
+            Ok(LockResponse {
+                amount: Uint128::from(bytes_to_u128(&data)?),
+                release_at,
+            })
+        })
+        .collect()
+}
+
+/// Sum of the locks of `owner` that matured by now, less the fee and burn their
+/// release will be charged
+pub fn read_matured(store: &dyn Storage, env: &Env, owner: &Addr) -> Result<u128, ContractError> {
+    let matured = read_locks(store, owner)?
+        .iter()
+        .filter(|lock| lock.release_at <= env.block.time.seconds())
+        .try_fold(0u128, |sum, lock| checked_add(sum, lock.amount.u128()))?;
+    let escrow = &env.contract.address;
+    let fee = fees::compute_fee(store, env, escrow, owner, matured)?;
+    let burned = deflation::compute_burn(store, escrow, owner, matured)?;
+    checked_sub(matured, checked_add(fee.map_or(0, |(fee, _)| fee), burned)?)
+}
+
+/// Sum of the locks of `owner` that are still locked
+pub fn read_locked(store: &dyn Storage, env: &Env, owner: &Addr) -> Result<u128, ContractError> {
+    read_locks(store, owner)?
+        .iter()
+        .filter(|lock| lock.release_at > env.block.time.seconds())
+        .try_fold(0u128, |sum, lock| checked_add(sum, lock.amount.u128()))
+}
+
+/// Moves matured locks of `owner` into its liquid balance. Idempotent: once
+/// released, a lock is gone.
+pub fn release_matured(
+    store: &mut dyn Storage,
+    api: &dyn Api,
+    env: &Env,
+    owner: &Addr,
+) -> Result<u128, ContractError> {
+    if owner == env.contract.address {
+        return Ok(0);
+    }
+    let end = (env.block.time.seconds() + 1).to_be_bytes();
+    let namespace = [PREFIX_LOCKS, owner.as_str().as_bytes()];
+    let mut locks_store = PrefixedStorage::multilevel(store, &namespace);
+    let matured: Vec<(Vec<u8>, Vec<u8>)> = locks_store
+        .range(None, Some(&end[..]), Order::Ascending)
+        .collect();
+    let mut released = 0u128;
+    for (key, data) in matured {
+        released = checked_add(released, bytes_to_u128(&data)?)?;
+        locks_store.remove(&key);
+    }
+    if released > 0 {
+        let escrow = &env.contract.address;
+        settle_transfer(
+            store,
+            api,
+            env,
+            escrow,
+            owner,
+            released,
+            TransferKind::EscrowPayout,
+        )?;
+    }
+    Ok(released)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate, query, read_balance};
+    use crate::msg::{BalanceResponse, ExecuteMsg, InitialBalance, InstantiateMsg, QueryMsg};
+    use crate::testing::{default_instantiate_msg, env_at};
+    use cosmwasm_std::testing::{
+        mock_dependencies_with_balance, mock_info, MockApi, MOCK_CONTRACT_ADDR,
+    };
+    use cosmwasm_std::{from_slice, Deps};
+
+    fn setup(deps: DepsMut) {
+        let instantiate_msg = InstantiateMsg {
+            initial_balances: vec![InitialBalance {
+                address: "addr0000".to_string(),
+                amount: Uint128::from(1000u128),
+            }],
+            ..default_instantiate_msg()
+        };
+        instantiate(deps, env_at(0), mock_info("creator", &[]), instantiate_msg).unwrap();
+    }
+
+    fn transfer_locked(deps: DepsMut, amount: u128, release_at: u64) {
+        let msg = ExecuteMsg::TransferLocked {
+            recipient: "addr1111".to_string(),
+            amount: Uint128::from(amount),
+            release_at,
+        };
+        execute(deps, env_at(100), mock_info("addr0000", &[]), msg).unwrap();
+    }
+
+    fn query_balance(deps: Deps, time: u64) -> u128 {
+        let query_msg = QueryMsg::Balance {
+            address: "addr1111".to_string(),
+        };
+        let res: BalanceResponse =
+            from_slice(&query(deps, env_at(time), query_msg).unwrap()).unwrap();
+        res.balance.u128()
+    }
+
+    fn liquid(storage: &dyn Storage, address: &str) -> u128 {
+        read_balance(storage, &MockApi::default(), &Addr::unchecked(address)).unwrap()
+    }
+
+    #[test]
+    fn locked_tokens_cannot_move_before_release() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        transfer_locked(deps.as_mut(), 300, 200);
+        assert_eq!(query_balance(deps.as_ref(), 199), 0);
+
+        let transfer_msg = ExecuteMsg::Transfer {
+            recipient: "addr2222".to_string(),
+            amount: Uint128::from(1u128),
+            memo: None,
+            on_behalf_of: None,
+        };
+        let err = execute(
+            deps.as_mut(),
+            env_at(199),
+            mock_info("addr1111", &[]),
+            transfer_msg,
+        )
+        .unwrap_err();
+        match err {
+            ContractError::InsufficientFunds { .. } => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn matured_locks_release_on_first_touch() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        transfer_locked(deps.as_mut(), 300, 200);
+        transfer_locked(deps.as_mut(), 200, 300);
+
+        // queries see matured locks as liquid without a release
+        assert_eq!(query_balance(deps.as_ref(), 250), 300);
+        assert_eq!(liquid(&deps.storage, "addr1111"), 0);
+
+        let transfer_msg = ExecuteMsg::Transfer {
+            recipient: "addr2222".to_string(),
+            amount: Uint128::from(100u128),
+            memo: None,
+            on_behalf_of: None,
+        };
+        execute(
+            deps.as_mut(),
+            env_at(250),
+            mock_info("addr1111", &[]),
+            transfer_msg,
+        )
+        .unwrap();
+        assert_eq!(liquid(&deps.storage, "addr1111"), 200);
+        assert_eq!(query_balance(deps.as_ref(), 250), 200);
+        assert_eq!(query_balance(deps.as_ref(), 300), 400);
+        assert_eq!(liquid(&deps.storage, MOCK_CONTRACT_ADDR), 200);
+    }
+
+    #[test]
+    fn release_is_idempotent() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        transfer_locked(deps.as_mut(), 300, 200);
+
+        let owner = Addr::unchecked("addr1111");
+        let env = env_at(200);
+        assert_eq!(
+            release_matured(&mut deps.storage, &deps.api, &env, &owner).unwrap(),
+            300
+        );
+        assert_eq!(
+            release_matured(&mut deps.storage, &deps.api, &env, &owner).unwrap(),
+            0
+        );
+        assert_eq!(liquid(&deps.storage, "addr1111"), 300);
+        assert_eq!(liquid(&deps.storage, MOCK_CONTRACT_ADDR), 0);
+        assert_eq!(query_balance(deps.as_ref(), 200), 300);
+        assert!(read_locks(&deps.storage, &owner).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,345 @@
+use cosmwasm_std::{Addr, DepsMut, Env, MessageInfo, Order, Response, Storage, Uint128};
+use cosmwasm_storage::{PrefixedStorage, ReadonlyPrefixedStorage};
+use std::convert::TryInto;
+
+use crate::contract::{
+    assert_owner, bytes_to_u128, checked_add, read_addr, read_owner, PREFIX_CONFIG,
+};
+use crate::error::ContractError;
+use crate::msg::{LockupResponse, Role};
+use crate::renounce;
+
+pub const KEY_LOCKER: &[u8] = b"locker";
+pub const PREFIX_LOCKUPS: &[u8] = b"lockups";
+
+// Unlike `locks`, a lockup leaves the tokens on the account and only restricts
+// what the account can transfer until `unlock_at`. Lockups of an account are
+// keyed by big endian unlock time, so active ones form a suffix and expired ones
+// are dropped the next time a lockup is placed on the account.
+
+pub fn read_locker(store: &dyn Storage) -> Result<Option<Addr>, ContractError> {
+    let config_store = ReadonlyPrefixedStorage::new(store, PREFIX_CONFIG);
+    read_addr(&config_store, KEY_LOCKER)
+}
+
+/// Set or remove the account allowed to place lockups besides the owner.
+/// Owner only.
+pub fn try_set_locker(
+    deps: DepsMut,
+    info: MessageInfo,
+    locker: Option<String>,
+) -> Result<Response, ContractError> {
+    assert_owner(deps.storage, &info.sender)?;
+    if locker.is_some() {
+        renounce::assert_not_renounced(deps.storage, Role::Locker)?;
+    }
+    let mut config_store = PrefixedStorage::new(deps.storage, PREFIX_CONFIG);
+    match &locker {
+        Some(locker) => {
+            let locker_address = deps.api.addr_validate(locker.as_str())?;
+            config_store.set(KEY_LOCKER, locker_address.as_str().as_bytes());
+        }
+        None => config_store.remove(KEY_LOCKER),
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "set_locker")
+        .add_attribute("locker", locker.unwrap_or_default()))
+}
+
+/// Lock `amount` of the balance of `address` until `unlock_at`. Owner or locker only.
+///
+/// The amount may exceed the current balance, e.g. to lock tokens before they
+/// are distributed. Lockups with the same unlock time add up.
+///
+/// @param unlock_at block time in seconds the tokens become transferable at
+pub fn try_lock_balance(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    address: String,
+    amount: Uint128,
+    unlock_at: u64,
+) -> Result<Response, ContractError> {
+    let owner = read_owner(deps.storage)?;
+    let locker = read_locker(deps.storage)?;
+    if owner.as_ref() != Some(&info.sender) && locker.as_ref() != Some(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+    if unlock_at <= env.block.time.seconds() {
+        return Err(ContractError::InvalidReleaseTime {});
+    }
+    let address_key = deps.api.addr_validate(&address)?;
+
+    let namespace = [PREFIX_LOCKUPS, address_key.as_str().as_bytes()];
+    let mut lockups_store = PrefixedStorage::multilevel(deps.storage, &namespace);
+    let end = (env.block.time.seconds() + 1).to_be_bytes();
+    let expired: Vec<Vec<u8>> = lockups_store
+        .range(None, Some(&end[..]), Order::Ascending)
+        .map(|(key, _)| key)
+        .collect();
+    for key in expired {
+        lockups_store.remove(&key);
+    }
+    let locked = match lockups_store.get(&unlock_at.to_be_bytes()) {
+        Some(data) => bytes_to_u128(&data)?,
+        None => 0u128,
+    };
+    lockups_store.set(
+        &unlock_at.to_be_bytes(),
+        &checked_add(locked, amount.u128())?.to_be_bytes(),
+    );
+
+    Ok(Response::new()
+        .add_attribute("action", "lock_balance")
+        .add_attribute("address", address)
+        .add_attribute("amount", amount.to_string())
+        .add_attribute("unlock_at", unlock_at.to_string()))
+}
+
+/// Lockups of `owner` that are still active, ordered by unlock time
+pub fn read_active_lockups(
+    store: &dyn Storage,
+    env: &Env,
+    owner: &Addr,
+) -> Result<Vec<LockupResponse>, ContractError> {
+    let lockups_store =
+        ReadonlyPrefixedStorage::multilevel(store, &[PREFIX_LOCKUPS, owner.as_str().as_bytes()]);
+    let start = (env.block.time.seconds() + 1).to_be_bytes();
+    lockups_store
+        .range(Some(&start[..]), None, Order::Ascending)
+        .map(|(key, data)| {
+            let unlock_at = match key[..].try_into() {
+                Ok(bytes) => u64::from_be_bytes(bytes),
+                Err(_) => return Err(ContractError::CorruptedDataFound {}),
+            };
+            Ok(LockupResponse {
+                amount: Uint128::from(bytes_to_u128(&data)?),
+                unlock_at,
+            })
+        })
+        .collect()
+}
+
+/// Sum of the active lockups of `owner`
+pub fn read_locked_up(store: &dyn Storage, env: &Env, owner: &Addr) -> Result<u128, ContractError> {
+    read_active_lockups(store, env, owner)?
+        .iter()
+        .try_fold(0u128, |sum, lockup| checked_add(sum, lockup.amount.u128()))
+}
+
+/// Errors unless `owner` can spend `amount` out of `balance` without touching
+/// tokens that are locked up
+pub fn assert_spendable(
+    store: &dyn Storage,
+    env: &Env,
+    owner: &Addr,
+    balance: u128,
+    amount: u128,
+) -> Result<(), ContractError> {
+    let spendable = balance.saturating_sub(read_locked_up(store, env, owner)?);
+    if spendable < amount {
+        return Err(ContractError::BalanceLockedUp {
+            spendable,
+            required: amount,
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate, query};
+    use crate::msg::{
+        ExecuteMsg, InitialBalance, InstantiateMsg, QueryMsg, SpendableBalanceResponse,
+    };
+    use crate::testing::{default_instantiate_msg, env_at};
+    use cosmwasm_std::testing::{mock_dependencies_with_balance, mock_info};
+    use cosmwasm_std::{from_slice, Deps};
+
+    fn setup(deps: DepsMut) {
+        let instantiate_msg = InstantiateMsg {
+            initial_balances: vec![InitialBalance {
+                address: "addr0000".to_string(),
+                amount: Uint128::from(1000u128),
+            }],
+            wrapped_denom: Some("uluna".to_string()),
+            ..default_instantiate_msg()
+        };
+        instantiate(
+            deps,
+            env_at(500),
+            mock_info("creator", &[]),
+            instantiate_msg,
+        )
+        .unwrap();
+    }
+
+    fn lock_balance(
+        deps: DepsMut,
+        signer: &str,
+        amount: u128,
+        unlock_at: u64,
+    ) -> Result<Response, ContractError> {
+        let msg = ExecuteMsg::LockBalance {
+            address: "addr0000".to_string(),
+            amount: Uint128::from(amount),
+            unlock_at,
+        };
+        execute(deps, env_at(500), mock_info(signer, &[]), msg)
+    }
+
+    fn transfer(deps: DepsMut, time: u64, amount: u128) -> Result<Response, ContractError> {
+        let msg = ExecuteMsg::Transfer {
+            recipient: "addr1111".to_string(),
+            amount: Uint128::from(amount),
+            memo: None,
+            on_behalf_of: None,
+        };
+        execute(deps, env_at(time), mock_info("addr0000", &[]), msg)
+    }
+
+    fn query_spendable(deps: Deps, time: u64) -> SpendableBalanceResponse {
+        let query_msg = QueryMsg::SpendableBalance {
+            address: "addr0000".to_string(),
+        };
+        from_slice(&query(deps, env_at(time), query_msg).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn locked_up_tokens_cannot_be_transferred() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        lock_balance(deps.as_mut(), "creator", 600, 1000).unwrap();
+        lock_balance(deps.as_mut(), "creator", 200, 2000).unwrap();
+
+        let res = query_spendable(deps.as_ref(), 500);
+        assert_eq!(res.balance, Uint128::from(1000u128));
+        assert_eq!(res.spendable, Uint128::from(200u128));
+        assert_eq!(
+            res.lockups,
+            vec![
+                LockupResponse {
+                    amount: Uint128::from(600u128),
+                    unlock_at: 1000,
+                },
+                LockupResponse {
+                    amount: Uint128::from(200u128),
+                    unlock_at: 2000,
+                },
+            ]
+        );
+
+        let err = transfer(deps.as_mut(), 500, 201).unwrap_err();
+        match err {
+            ContractError::BalanceLockedUp {
+                spendable,
+                required,
+            } => {
+                assert_eq!(spendable, 200);
+                assert_eq!(required, 201);
+            }
+            e => panic!("unexpected error: {:?}", e),
+        }
+        transfer(deps.as_mut(), 500, 200).unwrap();
+
+        // The first lockup expired
+        let res = query_spendable(deps.as_ref(), 1000);
+        assert_eq!(res.balance, Uint128::from(800u128));
+        assert_eq!(res.spendable, Uint128::from(600u128));
+        assert_eq!(res.lockups.len(), 1);
+        transfer(deps.as_mut(), 1000, 600).unwrap();
+        transfer(deps.as_mut(), 1000, 1).unwrap_err();
+    }
+
+    #[test]
+    fn locked_up_tokens_cannot_be_burned_or_withdrawn() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        lock_balance(deps.as_mut(), "creator", 600, 1000).unwrap();
+        let msg = ExecuteMsg::IncreaseAllowance {
+            spender: "addr1111".to_string(),
+            amount: Uint128::from(1000u128),
+            expires: None,
+        };
+        execute(deps.as_mut(), env_at(500), mock_info("addr0000", &[]), msg).unwrap();
+
+        let burn_msg = ExecuteMsg::Burn {
+            amount: Uint128::from(401u128),
+            memo: None,
+        };
+        let burn_from_msg = ExecuteMsg::BurnFrom {
+            owner: "addr0000".to_string(),
+            amount: Uint128::from(401u128),
+            memo: None,
+        };
+        let withdraw_msg = ExecuteMsg::Withdraw {
+            amount: Uint128::from(401u128),
+        };
+        for (sender, msg) in [
+            ("addr0000", burn_msg),
+            ("addr1111", burn_from_msg),
+            ("addr0000", withdraw_msg),
+        ] {
+            match execute(deps.as_mut(), env_at(500), mock_info(sender, &[]), msg).unwrap_err() {
+                ContractError::BalanceLockedUp {
+                    spendable,
+                    required,
+                } => {
+                    assert_eq!(spendable, 400);
+                    assert_eq!(required, 401);
+                }
+                e => panic!("unexpected error: {:?}", e),
+            }
+        }
+
+        let burn_msg = ExecuteMsg::Burn {
+            amount: Uint128::from(400u128),
+            memo: None,
+        };
+        execute(
+            deps.as_mut(),
+            env_at(500),
+            mock_info("addr0000", &[]),
+            burn_msg,
+        )
+        .unwrap();
+        assert_eq!(
+            query_spendable(deps.as_ref(), 500).spendable,
+            Uint128::zero()
+        );
+    }
+
+    #[test]
+    fn locker_can_place_lockups() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        let err = lock_balance(deps.as_mut(), "locker", 100, 1000).unwrap_err();
+        match err {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        let msg = ExecuteMsg::SetLocker {
+            locker: Some("locker".to_string()),
+        };
+        execute(deps.as_mut(), env_at(500), mock_info("creator", &[]), msg).unwrap();
+        lock_balance(deps.as_mut(), "locker", 100, 1000).unwrap();
+        assert_eq!(
+            query_spendable(deps.as_ref(), 500).spendable,
+            Uint128::from(900u128)
+        );
+    }
+
+    #[test]
+    fn unlock_time_must_be_in_the_future() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        let err = lock_balance(deps.as_mut(), "creator", 100, 500).unwrap_err();
+        match err {
+            ContractError::InvalidReleaseTime {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+}
@@ -0,0 +1,446 @@
+use cosmwasm_std::{from_slice, to_vec, Addr, DepsMut, MessageInfo, Response, StdError, Storage};
+use cosmwasm_storage::{PrefixedStorage, ReadonlyPrefixedStorage};
+
+use crate::contract::{
+    assert_owner, is_valid_name, is_valid_symbol, read_addr, read_constants, read_owner,
+    KEY_CONSTANTS, PREFIX_CONFIG,
+};
+use crate::error::ContractError;
+use crate::events;
+use crate::msg::{DownloadLogoResponse, EmbeddedLogo, Logo, LogoInfo, MarketingInfoResponse, Role};
+use crate::renounce;
+
+pub const KEY_MARKETING_ADMIN: &[u8] = b"marketing_admin";
+pub const KEY_DISPLAY_SYMBOL: &[u8] = b"display_symbol";
+pub const KEY_PROJECT: &[u8] = b"project";
+pub const KEY_DESCRIPTION: &[u8] = b"description";
+pub const KEY_LOGO: &[u8] = b"logo";
+
+/// Maximum size of an embedded logo in bytes
+pub const MAX_LOGO_SIZE: usize = 5 * 1024;
+
+const PNG_HEADER: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+pub fn read_marketing_admin(store: &dyn Storage) -> Result<Option<Addr>, ContractError> {
+    let config_store = ReadonlyPrefixedStorage::new(store, PREFIX_CONFIG);
+    read_addr(&config_store, KEY_MARKETING_ADMIN)
+}
+
+fn read_string(store: &dyn Storage, key: &[u8]) -> Result<Option<String>, ContractError> {
+    let config_store = ReadonlyPrefixedStorage::new(store, PREFIX_CONFIG);
+    match config_store.get(key) {
+        Some(data) => match String::from_utf8(data) {
+            Ok(value) => Ok(Some(value)),
+            Err(err) => Err(StdError::invalid_utf8(err.to_string()).into()),
+        },
+        None => Ok(None),
+    }
+}
+
+fn read_logo(store: &dyn Storage) -> Result<Option<Logo>, ContractError> {
+    let config_store = ReadonlyPrefixedStorage::new(store, PREFIX_CONFIG);
+    match config_store.get(KEY_LOGO) {
+        Some(data) => Ok(Some(from_slice(&data)?)),
+        None => Ok(None),
+    }
+}
+
+/// Symbol to show instead of the canonical one, e.g. `CASH.axl` on a bridged deployment
+pub fn read_display_symbol(store: &dyn Storage) -> Result<Option<String>, ContractError> {
+    read_string(store, KEY_DISPLAY_SYMBOL)
+}
+
+pub fn query_marketing_info(store: &dyn Storage) -> Result<MarketingInfoResponse, ContractError> {
+    Ok(MarketingInfoResponse {
+        project: read_string(store, KEY_PROJECT)?,
+        description: read_string(store, KEY_DESCRIPTION)?,
+        logo: read_logo(store)?.map(|logo| match logo {
+            Logo::Url(url) => LogoInfo::Url(url),
+            Logo::Embedded(_) => LogoInfo::Embedded,
+        }),
+        marketing: read_marketing_admin(store)?.map(|admin| admin.to_string()),
+    })
+}
+
+/// The embedded logo with its mime type. Errors for linked or missing logos.
+pub fn query_download_logo(store: &dyn Storage) -> Result<DownloadLogoResponse, ContractError> {
+    match read_logo(store)? {
+        Some(Logo::Embedded(EmbeddedLogo::Svg(data))) => Ok(DownloadLogoResponse {
+            mime_type: "image/svg+xml".to_string(),
+            data,
+        }),
+        Some(Logo::Embedded(EmbeddedLogo::Png(data))) => Ok(DownloadLogoResponse {
+            mime_type: "image/png".to_string(),
+            data,
+        }),
+        _ => Err(StdError::not_found("logo").into()),
+    }
+}
+
+/// Hand the marketing admin role over, or remove it with `None`. Owner or
+/// current marketing admin only.
+pub fn try_update_marketing_admin(
+    deps: DepsMut,
+    info: MessageInfo,
+    admin: Option<String>,
+) -> Result<Response, ContractError> {
+    let owner = read_owner(deps.storage)?;
+    let marketing_admin = read_marketing_admin(deps.storage)?;
+    if Some(&info.sender) != owner.as_ref() && Some(&info.sender) != marketing_admin.as_ref() {
+        return Err(ContractError::Unauthorized {});
+    }
+    if admin.is_some() {
+        renounce::assert_not_renounced(deps.storage, Role::MarketingAdmin)?;
+    }
+    let mut config_store = PrefixedStorage::new(deps.storage, PREFIX_CONFIG);
+    match &admin {
+        Some(admin) => {
+            let admin_address = deps.api.addr_validate(admin)?;
+            config_store.set(KEY_MARKETING_ADMIN, admin_address.as_str().as_bytes());
+        }
+        None => config_store.remove(KEY_MARKETING_ADMIN),
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "update_marketing_admin")
+        .add_attribute("admin", admin.unwrap_or_default()))
+}
+
+/// Update the cw20 marketing info. `None` keeps a field, an empty string
+/// clears it. `marketing` hands the marketing admin role over. Marketing
+/// admin only.
+pub fn try_update_marketing(
+    deps: DepsMut,
+    info: MessageInfo,
+    project: Option<String>,
+    description: Option<String>,
+    marketing: Option<String>,
+) -> Result<Response, ContractError> {
+    if read_marketing_admin(deps.storage)? != Some(info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+    let marketing = match marketing {
+        Some(marketing) if marketing.is_empty() => Some(None),
+        Some(marketing) => {
+            renounce::assert_not_renounced(deps.storage, Role::MarketingAdmin)?;
+            Some(Some(deps.api.addr_validate(&marketing)?))
+        }
+        None => None,
+    };
+    let mut config_store = PrefixedStorage::new(deps.storage, PREFIX_CONFIG);
+    for (key, value) in [(KEY_PROJECT, &project), (KEY_DESCRIPTION, &description)] {
+        match value.as_deref() {
+            Some("") => config_store.remove(key),
+            Some(value) => config_store.set(key, value.as_bytes()),
+            None => {}
+        }
+    }
+    match &marketing {
+        Some(Some(admin)) => config_store.set(KEY_MARKETING_ADMIN, admin.as_str().as_bytes()),
+        Some(None) => config_store.remove(KEY_MARKETING_ADMIN),
+        None => {}
+    }
+
+    Ok(Response::new().add_attribute("action", "update_marketing"))
+}
+
+/// Set the logo, a link or an embedded SVG or PNG image of at most
+/// `MAX_LOGO_SIZE` bytes. Marketing admin only.
+pub fn try_upload_logo(
+    deps: DepsMut,
+    info: MessageInfo,
+    logo: Logo,
+) -> Result<Response, ContractError> {
+    if read_marketing_admin(deps.storage)? != Some(info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+    if let Logo::Embedded(embedded) = &logo {
+        let (data, format, valid) = match embedded {
+            EmbeddedLogo::Svg(data) => (data, "svg", is_svg(data)),
+            EmbeddedLogo::Png(data) => (data, "png", data.starts_with(PNG_HEADER)),
+        };
+        if data.len() > MAX_LOGO_SIZE {
+            return Err(ContractError::LogoTooBig { max: MAX_LOGO_SIZE });
+        }
+        if !valid {
+            return Err(ContractError::InvalidLogo {
+                format: format.to_string(),
+            });
+        }
+    }
+    let mut config_store = PrefixedStorage::new(deps.storage, PREFIX_CONFIG);
+    config_store.set(KEY_LOGO, &to_vec(&logo)?);
+
+    Ok(Response::new().add_attribute("action", "upload_logo"))
+}
+
+// An SVG document opens with an XML declaration or the svg element
+fn is_svg(data: &[u8]) -> bool {
+    let start = data.iter().position(|byte| !byte.is_ascii_whitespace());
+    let data = &data[start.unwrap_or(data.len())..];
+    data.starts_with(b"<?xml") || data.starts_with(b"<svg")
+}
+
+/// Set or clear the display symbol. Marketing admin only.
+pub fn try_update_display_symbol(
+    deps: DepsMut,
+    info: MessageInfo,
+    display_symbol: Option<String>,
+) -> Result<Response, ContractError> {
+    if read_marketing_admin(deps.storage)? != Some(info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+    let mut config_store = PrefixedStorage::new(deps.storage, PREFIX_CONFIG);
+    match &display_symbol {
+        Some(display_symbol) => {
+            if !is_valid_display_symbol(display_symbol) {
+                return Err(ContractError::DisplaySymbolWrongFormat {});
+            }
+            config_store.set(KEY_DISPLAY_SYMBOL, display_symbol.as_bytes());
+        }
+        None => config_store.remove(KEY_DISPLAY_SYMBOL),
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "update_display_symbol")
+        .add_attribute("display_symbol", display_symbol.unwrap_or_default()))
+}
+
+/// Rename the token. Decimals stay as they are. Owner only.
+pub fn try_update_token_metadata(
+    deps: DepsMut,
+    info: MessageInfo,
+    name: String,
+    symbol: String,
+) -> Result<Response, ContractError> {
+    assert_owner(deps.storage, &info.sender)?;
+    if !is_valid_name(&name) {
+        return Err(ContractError::NameWrongFormat {});
+    }
+    if !is_valid_symbol(&symbol) {
+        return Err(ContractError::TickerWrongSymbolFormat {});
+    }
+    let mut constants = read_constants(deps.storage)?;
+    let event = events::token_metadata_event(&constants, &name, &symbol);
+    constants.name = name;
+    constants.symbol = symbol;
+    let mut config_store = PrefixedStorage::new(deps.storage, PREFIX_CONFIG);
+    config_store.set(KEY_CONSTANTS, &to_vec(&constants)?);
+
+    Ok(Response::new()
+        .add_event(event)
+        .add_attribute("action", "update_token_metadata")
+        .add_attribute("name", constants.name)
+        .add_attribute("symbol", constants.symbol))
+}
+
+// Like a symbol but allowing provenance suffixes such as `.axl` or `-wh`
+fn is_valid_display_symbol(display_symbol: &str) -> bool {
+    let bytes = display_symbol.as_bytes();
+    if bytes.len() < 3 || bytes.len() > 20 {
+        return false;
+    }
+    bytes
+        .iter()
+        .all(|byte| byte.is_ascii_alphanumeric() || *byte == b'.' || *byte == b'-')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate, query};
+    use crate::msg::{ExecuteMsg, InitialBalance, InstantiateMsg, QueryMsg, TokenInfoResponse};
+    use crate::testing::default_instantiate_msg;
+    use cosmwasm_std::testing::{mock_dependencies_with_balance, mock_env, mock_info};
+    use cosmwasm_std::{from_slice, Binary, Deps, Uint128};
+
+    fn setup(deps: DepsMut) {
+        let instantiate_msg = InstantiateMsg {
+            initial_balances: vec![InitialBalance {
+                address: "addr0000".to_string(),
+                amount: Uint128::from(1000u128),
+            }],
+            ..default_instantiate_msg()
+        };
+        instantiate(deps, mock_env(), mock_info("creator", &[]), instantiate_msg).unwrap();
+    }
+
+    fn update_display_symbol(
+        deps: DepsMut,
+        signer: &str,
+        display_symbol: Option<&str>,
+    ) -> Result<Response, ContractError> {
+        let msg = ExecuteMsg::UpdateDisplaySymbol {
+            display_symbol: display_symbol.map(|symbol| symbol.to_string()),
+        };
+        execute(deps, mock_env(), mock_info(signer, &[]), msg)
+    }
+
+    fn query_token_info(deps: Deps) -> TokenInfoResponse {
+        from_slice(&query(deps, mock_env(), QueryMsg::TokenInfo {}).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn marketing_admin_sets_display_symbol() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        let msg = ExecuteMsg::UpdateMarketingAdmin {
+            admin: Some("marketing".to_string()),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        update_display_symbol(deps.as_mut(), "marketing", Some("CASH.axl")).unwrap();
+        let info = query_token_info(deps.as_ref());
+        assert_eq!(info.symbol, "CASH");
+        assert_eq!(info.display_symbol, Some("CASH.axl".to_string()));
+        assert_eq!(info.total_supply, Uint128::from(1000u128));
+
+        update_display_symbol(deps.as_mut(), "marketing", None).unwrap();
+        assert_eq!(query_token_info(deps.as_ref()).display_symbol, None);
+    }
+
+    #[test]
+    fn only_marketing_admin_sets_display_symbol() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        // not even the owner while no marketing admin is assigned
+        match update_display_symbol(deps.as_mut(), "creator", Some("CASH.axl")).unwrap_err() {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn owner_renames_token() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        let msg = ExecuteMsg::UpdateTokenMetadata {
+            name: "Cash Token".to_string(),
+            symbol: "CASH".to_string(),
+        };
+        match execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap_err() {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        let msg = ExecuteMsg::UpdateTokenMetadata {
+            name: "Cash Token".to_string(),
+            symbol: "cash".to_string(),
+        };
+        match execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap_err() {
+            ContractError::TickerWrongSymbolFormat {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        let msg = ExecuteMsg::UpdateTokenMetadata {
+            name: "Dollar Token".to_string(),
+            symbol: "DOLLAR".to_string(),
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        assert_eq!(res.events[0].ty, "token_metadata");
+        let info = query_token_info(deps.as_ref());
+        assert_eq!(info.name, "Dollar Token");
+        assert_eq!(info.symbol, "DOLLAR");
+        assert_eq!(info.decimals, 9);
+    }
+
+    #[test]
+    fn marketing_admin_updates_marketing_info() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        let msg = ExecuteMsg::UpdateMarketingAdmin {
+            admin: Some("marketing".to_string()),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let update_marketing =
+            |project: Option<&str>, marketing: Option<&str>| ExecuteMsg::UpdateMarketing {
+                project: project.map(|project| project.to_string()),
+                description: Some("Cash for everyone".to_string()),
+                marketing: marketing.map(|marketing| marketing.to_string()),
+            };
+        let msg = update_marketing(Some("https://cash.example"), None);
+        match execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            msg.clone(),
+        ) {
+            Err(ContractError::Unauthorized {}) => {}
+            res => panic!("unexpected result: {:?}", res),
+        }
+        execute(deps.as_mut(), mock_env(), mock_info("marketing", &[]), msg).unwrap();
+        let svg = Binary::from(b"<svg xmlns=\"http://www.w3.org/2000/svg\"/>".to_vec());
+        let msg = ExecuteMsg::UploadLogo(Logo::Embedded(EmbeddedLogo::Svg(svg.clone())));
+        execute(deps.as_mut(), mock_env(), mock_info("marketing", &[]), msg).unwrap();
+
+        let info = query_marketing_info(&deps.storage).unwrap();
+        assert_eq!(
+            info,
+            MarketingInfoResponse {
+                project: Some("https://cash.example".to_string()),
+                description: Some("Cash for everyone".to_string()),
+                logo: Some(LogoInfo::Embedded),
+                marketing: Some("marketing".to_string()),
+            }
+        );
+        let logo = query_download_logo(&deps.storage).unwrap();
+        assert_eq!(logo.mime_type, "image/svg+xml");
+        assert_eq!(logo.data, svg);
+
+        // empty strings clear, unset fields stay
+        let msg = update_marketing(Some(""), Some("dao"));
+        execute(deps.as_mut(), mock_env(), mock_info("marketing", &[]), msg).unwrap();
+        let info = query_marketing_info(&deps.storage).unwrap();
+        assert_eq!(info.project, None);
+        assert_eq!(info.description, Some("Cash for everyone".to_string()));
+        assert_eq!(info.marketing, Some("dao".to_string()));
+    }
+
+    #[test]
+    fn rejects_invalid_logos() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        let msg = ExecuteMsg::UpdateMarketingAdmin {
+            admin: Some("marketing".to_string()),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let upload = |deps: DepsMut, logo: EmbeddedLogo| {
+            let msg = ExecuteMsg::UploadLogo(Logo::Embedded(logo));
+            execute(deps, mock_env(), mock_info("marketing", &[]), msg)
+        };
+
+        let not_png = EmbeddedLogo::Png(Binary::from(b"<svg/>".to_vec()));
+        match upload(deps.as_mut(), not_png).unwrap_err() {
+            ContractError::InvalidLogo { format } => assert_eq!(format, "png"),
+            e => panic!("unexpected error: {:?}", e),
+        }
+        let too_big =
+            EmbeddedLogo::Png(Binary::from([PNG_HEADER, &[0; MAX_LOGO_SIZE][..]].concat()));
+        match upload(deps.as_mut(), too_big).unwrap_err() {
+            ContractError::LogoTooBig { max } => assert_eq!(max, MAX_LOGO_SIZE),
+            e => panic!("unexpected error: {:?}", e),
+        }
+        match query(deps.as_ref(), mock_env(), QueryMsg::DownloadLogo {}).unwrap_err() {
+            ContractError::Std(StdError::NotFound { .. }) => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_display_symbol() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        let msg = ExecuteMsg::UpdateMarketingAdmin {
+            admin: Some("marketing".to_string()),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        for display_symbol in &["C", "CASH axl", "CASH.axl.wormhole.xyz"] {
+            match update_display_symbol(deps.as_mut(), "marketing", Some(display_symbol))
+                .unwrap_err()
+            {
+                ContractError::DisplaySymbolWrongFormat {} => {}
+                e => panic!("unexpected error: {:?}", e),
+            }
+        }
+    }
+}
@@ -0,0 +1,1032 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    from_slice, to_vec, Addr, Api, DepsMut, Env, Order, Response, Storage, Uint128,
+};
+use cosmwasm_storage::{to_length_prefixed, PrefixedStorage, ReadonlyPrefixedStorage};
+use cw2::{get_contract_version, set_contract_version};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::btree_map::Entry;
+use std::collections::BTreeMap;
+use std::convert::TryInto;
+
+use crate::buyback::{self, KEY_BUYBACK};
+use crate::cap;
+use crate::claimable::PREFIX_CLAIMABLES;
+use crate::contract::{
+    addr_from_canonical_key, addr_from_key, bytes_to_u128, checked_add, read_constants, read_owner,
+    read_shares, read_total_supply, split_allowance_key, split_length_prefixed, write_allowance,
+    write_shares, CONTRACT_NAME, CONTRACT_VERSION, KEY_CONSTANTS, KEY_OWNER, KEY_TOTAL_SUPPLY,
+    PREFIX_ALLOWANCES, PREFIX_ALLOWANCE_CHECKPOINTS, PREFIX_ALLOWANCE_EXPIRATIONS, PREFIX_BALANCES,
+    PREFIX_BURNED, PREFIX_CONFIG, PREFIX_RECEIVED, PREFIX_SENT, UNLIMITED_ALLOWANCE,
+};
+use crate::dividends::PREFIX_DIVIDEND_POOLS;
+use crate::drip::{self, KEY_DRIP};
+use crate::dualcontrol::PREFIX_ADMIN_PROPOSALS;
+use crate::error::ContractError;
+use crate::fees;
+use crate::holders::{self, KEY_HOLDER_COUNT, PREFIX_HOLDERS_BY_SHARES};
+use crate::interfaces;
+use crate::largetransfer::{PREFIX_LARGE_TRANSFER_POLICIES, PREFIX_PENDING_TRANSFERS};
+use crate::limits::{self, KEY_MIN_TRANSFER_AMOUNT, KEY_TRANSFER_LIMITS};
+use crate::locks::PREFIX_LOCKS;
+use crate::lockup::PREFIX_LOCKUPS;
+use crate::mint;
+use crate::msg::{MigrateMsg, Role};
+use crate::pause::{self, KEY_PAUSE};
+use crate::periodic::PREFIX_PERIODIC_ALLOWANCES;
+use crate::permit::PREFIX_NONCES;
+use crate::ratelimit::PREFIX_RATE_LIMITS;
+use crate::rebase;
+use crate::renounce;
+use crate::report::PREFIX_SPENDING;
+use crate::send::PREFIX_SENT_HOOKS;
+use crate::state::{Bucket, SpendingCounters, TransferLimits};
+use crate::streams::PREFIX_STREAMS;
+use crate::subscriptions::PREFIX_SUBSCRIPTIONS;
+use crate::timelock::{self, PREFIX_TIMELOCKED_TRANSFERS};
+use crate::treasury::PREFIX_BUCKETS;
+use crate::votes::{
+    self, PREFIX_CHECKPOINTS, PREFIX_DELEGATES, PREFIX_SHARES_CHECKPOINTS,
+    PREFIX_SUPPLY_CHECKPOINTS,
+};
+use crate::wrap;
+
+pub const KEY_STATE_VERSION: &[u8] = b"state_version";
+
+/// Version of the storage layout this code reads and writes
+pub const STATE_VERSION: u16 = 5;
+
+type Migration = fn(&mut dyn Storage, &dyn Api, &Env) -> Result<(), ContractError>;
+
+// Storage layout migrations as (from version, step), each bringing the layout
+// one version up. Deployments from before the version was stored are at
+// version 1. A layout change adds a step here and bumps STATE_VERSION.
+const MIGRATIONS: &[(u16, Migration)] = &[
+    (1, declare_interfaces),
+    (2, rebuild_holder_index),
+    (3, checkpoint_votes_in_shares),
+    (4, canonicalize_keys),
+];
+
+pub fn read_state_version(store: &dyn Storage) -> Result<u16, ContractError> {
+    let config_store = ReadonlyPrefixedStorage::new(store, PREFIX_CONFIG);
+    match config_store.get(KEY_STATE_VERSION) {
+        Some(data) => match data[..].try_into() {
+            Ok(bytes) => Ok(u16::from_be_bytes(bytes)),
+            Err(_) => Err(ContractError::CorruptedDataFound {}),
+        },
+        None => Ok(1),
+    }
+}
+
+pub fn write_state_version(store: &mut dyn Storage, version: u16) {
+    let mut config_store = PrefixedStorage::new(store, PREFIX_CONFIG);
+    config_store.set(KEY_STATE_VERSION, &version.to_be_bytes());
+}
+
+// Deployments from before the contract had an owner never stored one. Anyone
+// else without an owner renounced it, which is recorded.
+fn needs_owner(store: &dyn Storage) -> Result<bool, ContractError> {
+    Ok(read_owner(store)?.is_none() && !renounce::read_renounced(store)?.contains(&Role::Owner))
+}
+
+// Applies the migration steps from the stored version up to STATE_VERSION in
+// order. Returns the version the state was at.
+fn migrate_state(store: &mut dyn Storage, api: &dyn Api, env: &Env) -> Result<u16, ContractError> {
+    let stored = read_state_version(store)?;
+    if stored > STATE_VERSION {
+        return Err(ContractError::StateDowngrade {
+            stored,
+            current: STATE_VERSION,
+        });
+    }
+    for (from, step) in MIGRATIONS {
+        if *from >= stored {
+            step(store, api, env)?;
+        }
+    }
+    write_state_version(store, STATE_VERSION);
+    Ok(stored)
+}
+
+// Deployments from before cw2 was used have no version stored and may migrate
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
+    if let Ok(previous) = get_contract_version(deps.storage) {
+        if previous.contract != CONTRACT_NAME {
+            return Err(ContractError::CannotMigrate {
+                contract: previous.contract,
+            });
+        }
+    }
+    // Deployments without an owner get one before anything else is migrated
+    let new_owner = match (needs_owner(deps.storage)?, &msg) {
+        (true, MigrateMsg::Upgrade { owner: Some(owner) }) => Some(deps.api.addr_validate(owner)?),
+        (true, _) => return Err(ContractError::OwnerRequired {}),
+        (false, MigrateMsg::Upgrade { owner: Some(_) }) => {
+            return Err(ContractError::OwnerAlreadySet {})
+        }
+        (false, _) => None,
+    };
+    let previous_state_version = migrate_state(deps.storage, deps.api, &env)?;
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    let mut res = match msg {
+        MigrateMsg::Upgrade { .. } => Response::new().add_attribute("action", "upgrade"),
+        MigrateMsg::RescaleDecimals { new_decimals } => {
+            rescale_decimals(deps.storage, deps.api, &env, new_decimals)?
+        }
+    };
+    if let Some(owner) = new_owner {
+        let mut config_store = PrefixedStorage::new(deps.storage, PREFIX_CONFIG);
+        config_store.set(KEY_OWNER, owner.as_str().as_bytes());
+        res = res.add_attribute("owner", owner);
+    }
+    Ok(res
+        .add_attribute("previous_state_version", previous_state_version.to_string())
+        .add_attribute("state_version", STATE_VERSION.to_string()))
+}
+
+// v1 -> v2: interfaces used to be declared only by deployments instantiated
+// after interface detection was added
+fn declare_interfaces(
+    store: &mut dyn Storage,
+    _api: &dyn Api,
+    _env: &Env,
+) -> Result<(), ContractError> {
+    for interface in interfaces::INTERFACES {
+        interfaces::register_interface(store, *interface);
+    }
+    if mint::read_minter(store)?.is_some() {
+        interfaces::register_interface(store, interfaces::MINT_INTERFACE);
+    }
+    if wrap::read_wrap_config(store)?.is_some() {
+        interfaces::register_interface(store, interfaces::WRAP_INTERFACE);
+    }
+    if drip::read_drip(store)?.is_some() {
+        interfaces::register_interface(store, interfaces::DRIP_INTERFACE);
+    }
+    Ok(())
+}
+
+// v2 -> v3: the holder index and count are rebuilt from the balances, which
+// older deployments kept without an index
+fn rebuild_holder_index(
+    store: &mut dyn Storage,
+    _api: &dyn Api,
+    env: &Env,
+) -> Result<(), ContractError> {
+    let balances = ReadonlyPrefixedStorage::new(store, PREFIX_BALANCES)
+        .range(None, None, Order::Ascending)
+        .map(|(key, data)| Ok((addr_from_key(key)?, bytes_to_u128(&data)?)))
+        .collect::<Result<Vec<_>, ContractError>>()?;
+    index_holders(store, env, balances)
+}
+
+// Replaces the holder index and count with ones built from `balances`
+fn index_holders(
+    store: &mut dyn Storage,
+    env: &Env,
+    balances: Vec<(Addr, u128)>,
+) -> Result<(), ContractError> {
+    take_entries(store, PREFIX_HOLDERS_BY_SHARES);
+    let mut config_store = PrefixedStorage::new(store, PREFIX_CONFIG);
+    config_store.remove(KEY_HOLDER_COUNT);
+    for (owner, shares) in balances {
+        holders::update_holders(store, env, &owner, 0, shares)?;
+    }
+    Ok(())
+}
+
+// v3 -> v4: votes used to be checkpointed in tokens, which a rebase left behind.
+// The current votes of every delegate are recomputed from the shares of its
+// delegators, and the total shares get their first checkpoint.
+fn checkpoint_votes_in_shares(
+    store: &mut dyn Storage,
+    _api: &dyn Api,
+    env: &Env,
+) -> Result<(), ContractError> {
+    // Balances are keyed by the address string until v5
+    checkpoint_votes(
+        store,
+        env,
+        &|store, owner| match ReadonlyPrefixedStorage::new(store, PREFIX_BALANCES)
+            .get(owner.as_str().as_bytes())
+        {
+            Some(data) => bytes_to_u128(&data),
+            None => Ok(0),
+        },
+    )
+}
+
+fn checkpoint_votes(
+    store: &mut dyn Storage,
+    env: &Env,
+    shares_of: &dyn Fn(&dyn Storage, &Addr) -> Result<u128, ContractError>,
+) -> Result<(), ContractError> {
+    let delegations = ReadonlyPrefixedStorage::new(store, PREFIX_DELEGATES)
+        .range(None, None, Order::Ascending)
+        .map(|(delegator, delegatee)| Ok((addr_from_key(delegator)?, addr_from_key(delegatee)?)))
+        .collect::<Result<Vec<_>, ContractError>>()?;
+    let mut delegated: Vec<(Addr, u128)> = vec![];
+    for (delegator, delegatee) in delegations {
+        let shares = shares_of(store, &delegator)?;
+        match delegated.iter_mut().find(|(addr, _)| *addr == delegatee) {
+            Some((_, total)) => *total = checked_add(*total, shares)?,
+            None => delegated.push((delegatee, shares)),
+        }
+    }
+    for (delegatee, shares) in delegated {
+        let namespace = [PREFIX_CHECKPOINTS, delegatee.as_str().as_bytes()];
+        votes::write_checkpoint(store, &namespace, env.block.height, shares);
+    }
+    let total_supply = read_total_supply(store)?;
+    votes::write_supply_checkpoint(store, env.block.height, total_supply)
+}
+
+// v4 -> v5: balances, allowances and the other records kept per account used
+// to be keyed by the address string, which let two spellings of one address
+// hold separate entries. They are re-keyed by the canonical address. Colliding
+// balances and counters are added up, of colliding allowances and nonces the
+// larger one is kept.
+fn canonicalize_keys(
+    store: &mut dyn Storage,
+    api: &dyn Api,
+    env: &Env,
+) -> Result<(), ContractError> {
+    let canonical = |key: &[u8]| -> Result<Vec<u8>, ContractError> {
+        let address = addr_from_key(key.to_vec())?;
+        Ok(api.addr_canonicalize(address.as_str())?.to_vec())
+    };
+    // Length prefixed owner followed by the spender
+    let pair = |key: &[u8]| -> Result<Vec<u8>, ContractError> {
+        let (owner, spender) = split_length_prefixed(key)?;
+        Ok([to_length_prefixed(&canonical(owner)?), canonical(spender)?].concat())
+    };
+    // Length prefixed owner and spender followed by the height
+    let checkpoint = |key: &[u8]| -> Result<Vec<u8>, ContractError> {
+        let (owner, rest) = split_length_prefixed(key)?;
+        let (spender, height) = split_length_prefixed(rest)?;
+        Ok([
+            to_length_prefixed(&canonical(owner)?),
+            to_length_prefixed(&canonical(spender)?),
+            height.to_vec(),
+        ]
+        .concat())
+    };
+
+    for prefix in [PREFIX_BALANCES, PREFIX_SENT, PREFIX_RECEIVED, PREFIX_BURNED] {
+        rekey(store, prefix, &canonical, add_amounts)?;
+    }
+    rekey(store, PREFIX_NONCES, &canonical, keep_larger)?;
+    rekey(
+        store,
+        PREFIX_ALLOWANCE_CHECKPOINTS,
+        &checkpoint,
+        keep_larger,
+    )?;
+    let allowances = rekey(store, PREFIX_ALLOWANCES, &pair, keep_larger)?;
+    // An expiration stays with the allowance it was granted with
+    let expirations = take_entries(store, PREFIX_ALLOWANCE_EXPIRATIONS);
+    let mut expirations_store = PrefixedStorage::new(store, PREFIX_ALLOWANCE_EXPIRATIONS);
+    for (key, data) in expirations {
+        let new_key = pair(&key)?;
+        if allowances.get(&new_key) == Some(&key) {
+            expirations_store.set(&new_key, &data);
+        }
+    }
+
+    // Merged balances leave the index with an entry per spelling
+    let balances = ReadonlyPrefixedStorage::new(store, PREFIX_BALANCES)
+        .range(None, None, Order::Ascending)
+        .map(|(key, data)| Ok((addr_from_canonical_key(api, key)?, bytes_to_u128(&data)?)))
+        .collect::<Result<Vec<_>, ContractError>>()?;
+    index_holders(store, env, balances)
+}
+
+// Removes and returns every entry under `prefix`
+fn take_entries(store: &mut dyn Storage, prefix: &[u8]) -> Vec<(Vec<u8>, Vec<u8>)> {
+    let mut prefix_store = PrefixedStorage::new(store, prefix);
+    let entries: Vec<(Vec<u8>, Vec<u8>)> =
+        prefix_store.range(None, None, Order::Ascending).collect();
+    for (key, _) in &entries {
+        prefix_store.remove(key);
+    }
+    entries
+}
+
+type KeyMap<'a> = dyn Fn(&[u8]) -> Result<Vec<u8>, ContractError> + 'a;
+type Merge = fn(&[u8], &[u8]) -> Result<Vec<u8>, ContractError>;
+
+// Moves every entry under `prefix` to the key `new_key` maps it to, combining
+// entries that end up under one key with `merge`. Returns the old key whose
+// value each new key holds.
+fn rekey(
+    store: &mut dyn Storage,
+    prefix: &[u8],
+    new_key: &KeyMap,
+    merge: Merge,
+) -> Result<BTreeMap<Vec<u8>, Vec<u8>>, ContractError> {
+    let mut merged: BTreeMap<Vec<u8>, (Vec<u8>, Vec<u8>)> = BTreeMap::new();
+    for (key, data) in take_entries(store, prefix) {
+        match merged.entry(new_key(&key)?) {
+            Entry::Vacant(entry) => {
+                entry.insert((key, data));
+            }
+            Entry::Occupied(mut entry) => {
+                let (kept_key, kept) = entry.get_mut();
+                let value = merge(kept, &data)?;
+                if value != *kept {
+                    *kept_key = key;
+                }
+                *kept = value;
+            }
+        }
+    }
+    let mut prefix_store = PrefixedStorage::new(store, prefix);
+    Ok(merged
+        .into_iter()
+        .map(|(new_key, (old_key, data))| {
+            prefix_store.set(&new_key, &data);
+            (new_key, old_key)
+        })
+        .collect())
+}
+
+fn add_amounts(a: &[u8], b: &[u8]) -> Result<Vec<u8>, ContractError> {
+    Ok(checked_add(bytes_to_u128(a)?, bytes_to_u128(b)?)?
+        .to_be_bytes()
+        .to_vec())
+}
+
+// Big endian values of one width compare like the numbers they encode
+fn keep_larger(a: &[u8], b: &[u8]) -> Result<Vec<u8>, ContractError> {
+    Ok(a.max(b).to_vec())
+}
+
+// Converts amounts between two decimal precisions. Scaling down rounds down.
+struct Rescale {
+    up: bool,
+    factor: u128,
+}
+
+impl Rescale {
+    fn new(old_decimals: u8, new_decimals: u8) -> Result<Self, ContractError> {
+        let up = new_decimals >= old_decimals;
+        let exponent = if up {
+            new_decimals - old_decimals
+        } else {
+            old_decimals - new_decimals
+        };
+        let factor = 10u128
+            .checked_pow(exponent.into())
+            .ok_or(ContractError::RescaleOverflow {})?;
+        Ok(Rescale { up, factor })
+    }
+
+    fn apply(&self, amount: u128) -> Result<u128, ContractError> {
+        if self.up {
+            amount
+                .checked_mul(self.factor)
+                .ok_or(ContractError::RescaleOverflow {})
+        } else {
+            Ok(amount / self.factor)
+        }
+    }
+
+    fn apply_uint(&self, amount: Uint128) -> Result<Uint128, ContractError> {
+        Ok(Uint128::from(self.apply(amount.u128())?))
+    }
+
+    // Unlimited allowances stay unlimited
+    fn apply_allowance(&self, allowance: u128) -> Result<u128, ContractError> {
+        match allowance {
+            UNLIMITED_ALLOWANCE => Ok(UNLIMITED_ALLOWANCE),
+            allowance => self.apply(allowance),
+        }
+    }
+}
+
+// Records owing, escrowing or limiting fixed amounts, which have to be settled
+// or removed before a rescale. Tokens escrowed on the contract's own account
+// and the options checked in `assert_rescalable` block it as well.
+const RESCALE_BLOCKERS: &[(&[u8], &str)] = &[
+    (PREFIX_STREAMS, "streams"),
+    (PREFIX_CLAIMABLES, "claimables"),
+    (PREFIX_LOCKS, "locks"),
+    (PREFIX_LOCKUPS, "lockups"),
+    (PREFIX_SUBSCRIPTIONS, "subscriptions"),
+    (PREFIX_TIMELOCKED_TRANSFERS, "timelocked transfers"),
+    (PREFIX_PENDING_TRANSFERS, "pending large transfers"),
+    (PREFIX_LARGE_TRANSFER_POLICIES, "large transfer policies"),
+    (PREFIX_SENT_HOOKS, "failed sends"),
+    (PREFIX_ADMIN_PROPOSALS, "admin proposals"),
+    (PREFIX_PERIODIC_ALLOWANCES, "periodic allowances"),
+    (PREFIX_RATE_LIMITS, "rate limits"),
+    (PREFIX_DIVIDEND_POOLS, "dividend pools"),
+];
+
+// Records holding a plain amount per key, rescaled in place. Checkpoints are
+// rescaled down to the first one so historical queries keep the new precision.
+const RESCALED_AMOUNTS: &[&[u8]] = &[
+    PREFIX_CHECKPOINTS,
+    PREFIX_SUPPLY_CHECKPOINTS,
+    PREFIX_SHARES_CHECKPOINTS,
+    PREFIX_SENT,
+    PREFIX_RECEIVED,
+    PREFIX_BURNED,
+];
+
+fn assert_rescalable(store: &dyn Storage, api: &dyn Api, env: &Env) -> Result<(), ContractError> {
+    for (prefix, record) in RESCALE_BLOCKERS {
+        let records = ReadonlyPrefixedStorage::new(store, prefix);
+        if records.range(None, None, Order::Ascending).next().is_some() {
+            return Err(ContractError::RescaleBlocked {
+                record: record.to_string(),
+            });
+        }
+    }
+    let options = [
+        (
+            read_shares(store, api, &env.contract.address)? > 0,
+            "escrowed tokens",
+        ),
+        (timelock::read_timelock(store)?.is_some(), "the timelock"),
+        (fees::read_fee_curve(store)?.is_some(), "the fee curve"),
+        // Wrapped tokens are backed 1:1 by the native denom
+        (
+            wrap::read_wrap_config(store)?.is_some(),
+            "the wrapped denom",
+        ),
+    ];
+    match options.iter().find(|(set, _)| *set) {
+        Some((_, record)) => Err(ContractError::RescaleBlocked {
+            record: record.to_string(),
+        }),
+        None => Ok(()),
+    }
+}
+
+// Rescales the JSON records under `prefix` with `rescale_record`
+fn rescale_records<T: Serialize + DeserializeOwned>(
+    store: &dyn Storage,
+    prefix: &'static [u8],
+    writes: &mut Vec<(&'static [u8], Vec<u8>, Vec<u8>)>,
+    rescale_record: impl Fn(T) -> Result<T, ContractError>,
+) -> Result<(), ContractError> {
+    for (key, data) in
+        ReadonlyPrefixedStorage::new(store, prefix).range(None, None, Order::Ascending)
+    {
+        let record = rescale_record(from_slice(&data)?)?;
+        writes.push((prefix, key, to_vec(&record)?));
+    }
+    Ok(())
+}
+
+// Shares are rescaled along with the total supply rather than only the latter,
+// so that scaling up keeps the smallest unit transferable. Everything holding an
+// amount is rescaled with them, anything that cannot be converted safely blocks
+// the rescale, see `assert_rescalable`.
+fn rescale_decimals(
+    store: &mut dyn Storage,
+    api: &dyn Api,
+    env: &Env,
+    new_decimals: u8,
+) -> Result<Response, ContractError> {
+    assert_rescalable(store, api, env)?;
+    let mut constants = read_constants(store)?;
+    let old_decimals = constants.decimals;
+    let rescale = Rescale::new(old_decimals, new_decimals)?;
+
+    // Everything is computed before the first write so an overflow leaves the
+    // state untouched
+    let balances = ReadonlyPrefixedStorage::new(store, PREFIX_BALANCES)
+        .range(None, None, Order::Ascending)
+        .map(|(key, data)| {
+            let owner = addr_from_canonical_key(api, key)?;
+            let shares = bytes_to_u128(&data)?;
+            Ok((owner, shares, rescale.apply(shares)?))
+        })
+        .collect::<Result<Vec<_>, ContractError>>()?;
+    // The totals are the sums of what the holders are left with, so rounding
+    // down cannot leave supply that no balance accounts for
+    let total_shares = balances
+        .iter()
+        .try_fold(0u128, |total, (_, _, shares)| checked_add(total, *shares))?;
+    let old_total_shares = rebase::read_total_shares(store)?;
+    let total_supply = if old_total_shares == 0 {
+        0
+    } else {
+        // Keeps the ratio a rebase left between the supply and the shares
+        Uint128::from(read_total_supply(store)?)
+            .checked_multiply_ratio(total_shares, old_total_shares)
+            .map_err(|_| ContractError::RescaleOverflow {})?
+            .u128()
+    };
+    let max_supply = match cap::read_max_supply(store)? {
+        Some(max_supply) => Some(rescale.apply(max_supply)?),
+        None => None,
+    };
+    let allowances = ReadonlyPrefixedStorage::new(store, PREFIX_ALLOWANCES)
+        .range(None, None, Order::Ascending)
+        .map(|(key, data)| {
+            let (owner, spender) = split_allowance_key(api, &key)?;
+            let allowance = rescale.apply_allowance(bytes_to_u128(&data)?)?;
+            Ok((owner, spender, allowance))
+        })
+        .collect::<Result<Vec<_>, ContractError>>()?;
+
+    let mut writes: Vec<(&'static [u8], Vec<u8>, Vec<u8>)> = vec![];
+    for prefix in RESCALED_AMOUNTS {
+        for (key, data) in
+            ReadonlyPrefixedStorage::new(store, prefix).range(None, None, Order::Ascending)
+        {
+            let amount = rescale.apply(bytes_to_u128(&data)?)?;
+            writes.push((prefix, key, amount.to_be_bytes().to_vec()));
+        }
+    }
+    for (key, data) in ReadonlyPrefixedStorage::new(store, PREFIX_ALLOWANCE_CHECKPOINTS).range(
+        None,
+        None,
+        Order::Ascending,
+    ) {
+        let allowance = rescale.apply_allowance(bytes_to_u128(&data)?)?;
+        writes.push((
+            PREFIX_ALLOWANCE_CHECKPOINTS,
+            key,
+            allowance.to_be_bytes().to_vec(),
+        ));
+    }
+    rescale_records(
+        store,
+        PREFIX_SPENDING,
+        &mut writes,
+        |counters: SpendingCounters| {
+            Ok(SpendingCounters {
+                sent: rescale.apply_uint(counters.sent)?,
+                fees_paid: rescale.apply_uint(counters.fees_paid)?,
+                burned: rescale.apply_uint(counters.burned)?,
+                rewards: rescale.apply_uint(counters.rewards)?,
+            })
+        },
+    )?;
+    rescale_records(store, PREFIX_BUCKETS, &mut writes, |bucket: Bucket| {
+        Ok(Bucket {
+            spend_limit: bucket
+                .spend_limit
+                .map(|limit| rescale.apply_uint(limit))
+                .transpose()?,
+            balance: rescale.apply_uint(bucket.balance)?,
+            ..bucket
+        })
+    })?;
+
+    let limits = limits::read_transfer_limits(store)?;
+    let limits = TransferLimits {
+        max_tx_amount: limits
+            .max_tx_amount
+            .map(|amount| rescale.apply_uint(amount))
+            .transpose()?,
+        max_wallet_amount: limits
+            .max_wallet_amount
+            .map(|amount| rescale.apply_uint(amount))
+            .transpose()?,
+        ..limits
+    };
+    writes.push((
+        PREFIX_CONFIG,
+        KEY_TRANSFER_LIMITS.to_vec(),
+        to_vec(&limits)?,
+    ));
+    let min_transfer_amount = rescale.apply(limits::read_min_transfer_amount(store)?)?;
+    writes.push((
+        PREFIX_CONFIG,
+        KEY_MIN_TRANSFER_AMOUNT.to_vec(),
+        min_transfer_amount.to_be_bytes().to_vec(),
+    ));
+    let mut pause_state = pause::read_pause_state(store)?;
+    pause_state.unpause_votes = rescale.apply_uint(pause_state.unpause_votes)?;
+    writes.push((PREFIX_CONFIG, KEY_PAUSE.to_vec(), to_vec(&pause_state)?));
+    let mut buyback = buyback::read_buyback(store)?;
+    buyback.collected = rescale.apply_uint(buyback.collected)?;
+    buyback.burned = rescale.apply_uint(buyback.burned)?;
+    writes.push((PREFIX_CONFIG, KEY_BUYBACK.to_vec(), to_vec(&buyback)?));
+    // Only a drip that is paid out completely gets here, its escrow is empty
+    if let Some(mut drip) = drip::read_drip(store)? {
+        drip.amount = rescale.apply_uint(drip.amount)?;
+        drip.released = rescale.apply_uint(drip.released)?;
+        writes.push((PREFIX_CONFIG, KEY_DRIP.to_vec(), to_vec(&drip)?));
+    }
+
+    for (prefix, key, value) in writes {
+        PrefixedStorage::new(store, prefix).set(&key, &value);
+    }
+    for (owner, old_shares, new_shares) in balances {
+        write_shares(store, api, env, &owner, old_shares, new_shares)?;
+    }
+    for (owner, spender, amount) in allowances {
+        write_allowance(store, api, env, &owner, &spender, amount)?;
+    }
+    rebase::write_total_shares(store, total_shares);
+    if let Some(max_supply) = max_supply {
+        cap::write_max_supply(store, max_supply);
+    }
+    constants.decimals = new_decimals;
+    let mut config_store = PrefixedStorage::new(store, PREFIX_CONFIG);
+    config_store.set(KEY_TOTAL_SUPPLY, &total_supply.to_be_bytes());
+    config_store.set(KEY_CONSTANTS, &to_vec(&constants)?);
+    // Rounding each checkpoint down separately can leave a delegate with votes
+    // its delegators no longer back, so the current votes and totals are
+    // checkpointed again from the rescaled balances
+    checkpoint_votes(store, env, &|store, owner| read_shares(store, api, owner))?;
+
+    Ok(Response::new()
+        .add_attribute("action", "rescale_decimals")
+        .add_attribute("old_decimals", old_decimals.to_string())
+        .add_attribute("new_decimals", new_decimals.to_string())
+        .add_attribute("total_supply", total_supply.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate, query};
+    use crate::interfaces::PREFIX_SUPPORTED_INTERFACES;
+    use crate::msg::{
+        AccountStatsResponse, AllowanceResponse, BalanceResponse, ExecuteMsg, InitialBalance,
+        InstantiateMsg, QueryMsg, SupportedInterfacesResponse, TokenInfoResponse,
+        TopHoldersResponse, VotingPowerResponse,
+    };
+    use crate::testing::default_instantiate_msg;
+    use cosmwasm_std::testing::{mock_dependencies_with_balance, mock_env, mock_info};
+    use cosmwasm_std::{attr, from_slice, Deps, Uint128};
+    use cw2::ContractVersion;
+    use cw_utils::Expiration;
+
+    fn setup(mut deps: DepsMut) {
+        let instantiate_msg = InstantiateMsg {
+            initial_balances: vec![
+                InitialBalance {
+                    address: "addr0000".to_string(),
+                    amount: Uint128::from(1_500_000u128),
+                },
+                InitialBalance {
+                    address: "addr1111".to_string(),
+                    amount: Uint128::from(2_000_000u128),
+                },
+            ],
+            ..default_instantiate_msg()
+        };
+        instantiate(
+            deps.branch(),
+            mock_env(),
+            mock_info("creator", &[]),
+            instantiate_msg,
+        )
+        .unwrap();
+        let approve_msg = ExecuteMsg::Approve {
+            spender: "spender".to_string(),
+            amount: Uint128::from(250_000u128),
+            expected_current: None,
+            on_behalf_of: None,
+        };
+        execute(deps, mock_env(), mock_info("addr0000", &[]), approve_msg).unwrap();
+    }
+
+    fn balance(deps: Deps, address: &str) -> u128 {
+        let msg = QueryMsg::Balance {
+            address: address.to_string(),
+        };
+        let res: BalanceResponse = from_slice(&query(deps, mock_env(), msg).unwrap()).unwrap();
+        res.balance.u128()
+    }
+
+    fn allowance(deps: Deps) -> u128 {
+        let msg = QueryMsg::Allowance {
+            owner: "addr0000".to_string(),
+            spender: "spender".to_string(),
+        };
+        let res: AllowanceResponse = from_slice(&query(deps, mock_env(), msg).unwrap()).unwrap();
+        res.allowance.u128()
+    }
+
+    fn token_info(deps: Deps) -> TokenInfoResponse {
+        from_slice(&query(deps, mock_env(), QueryMsg::TokenInfo {}).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn rescales_balances_allowances_and_supply() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+
+        let msg = MigrateMsg::RescaleDecimals { new_decimals: 6 };
+        migrate(deps.as_mut(), mock_env(), msg).unwrap();
+        assert_eq!(balance(deps.as_ref(), "addr0000"), 1_500);
+        assert_eq!(balance(deps.as_ref(), "addr1111"), 2_000);
+        assert_eq!(allowance(deps.as_ref()), 250);
+        let info = token_info(deps.as_ref());
+        assert_eq!(info.decimals, 6);
+        assert_eq!(info.total_supply, Uint128::from(3_500u128));
+
+        let msg = MigrateMsg::RescaleDecimals { new_decimals: 18 };
+        migrate(deps.as_mut(), mock_env(), msg).unwrap();
+        assert_eq!(balance(deps.as_ref(), "addr0000"), 1_500 * 10u128.pow(12));
+        assert_eq!(allowance(deps.as_ref()), 250 * 10u128.pow(12));
+        assert_eq!(token_info(deps.as_ref()).decimals, 18);
+
+        // The holder index follows the rescaled shares
+        let msg = QueryMsg::TopHolders { limit: Some(1) };
+        let res: TopHoldersResponse =
+            from_slice(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert_eq!(res.holders[0].address, "addr1111");
+        assert_eq!(res.holders[0].balance.u128(), 2_000 * 10u128.pow(12));
+    }
+
+    #[test]
+    fn totals_are_sums_of_rescaled_balances() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+
+        // Both balances lose half a unit to rounding
+        let msg = MigrateMsg::RescaleDecimals { new_decimals: 3 };
+        migrate(deps.as_mut(), mock_env(), msg).unwrap();
+        assert_eq!(balance(deps.as_ref(), "addr0000"), 1);
+        assert_eq!(balance(deps.as_ref(), "addr1111"), 2);
+        assert_eq!(token_info(deps.as_ref()).total_supply, Uint128::from(3u128));
+        assert_eq!(rebase::read_total_shares(&deps.storage).unwrap(), 3);
+    }
+
+    #[test]
+    fn rescales_voting_power() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        for delegator in ["addr0000", "addr1111"] {
+            let msg = ExecuteMsg::Delegate {
+                delegatee: "addr0000".to_string(),
+            };
+            execute(deps.as_mut(), mock_env(), mock_info(delegator, &[]), msg).unwrap();
+        }
+
+        let msg = MigrateMsg::RescaleDecimals { new_decimals: 3 };
+        migrate(deps.as_mut(), mock_env(), msg).unwrap();
+        let msg = QueryMsg::VotingPower {
+            address: "addr0000".to_string(),
+        };
+        let res: VotingPowerResponse =
+            from_slice(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert_eq!(res.voting_power, Uint128::from(3u128));
+
+        // Moving all of the rescaled balances does not underflow the votes
+        let transfer_msg = ExecuteMsg::Transfer {
+            recipient: "addr2222".to_string(),
+            amount: Uint128::from(2u128),
+            memo: None,
+            on_behalf_of: None,
+        };
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("addr1111", &[]),
+            transfer_msg,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn refuses_to_rescale_escrowed_tokens() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        let env = mock_env();
+        let stream_msg = ExecuteMsg::CreateStream {
+            recipient: "addr1111".to_string(),
+            amount: Uint128::from(1_000u128),
+            start: env.block.time.seconds(),
+            end: env.block.time.seconds() + 100,
+        };
+        execute(deps.as_mut(), env, mock_info("addr0000", &[]), stream_msg).unwrap();
+
+        let msg = MigrateMsg::RescaleDecimals { new_decimals: 6 };
+        match migrate(deps.as_mut(), mock_env(), msg).unwrap_err() {
+            ContractError::RescaleBlocked { record } => assert_eq!(record, "streams"),
+            e => panic!("unexpected error: {:?}", e),
+        }
+        assert_eq!(token_info(deps.as_ref()).decimals, 9);
+    }
+
+    #[test]
+    fn stores_contract_version() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        let res: ContractVersion =
+            from_slice(&query(deps.as_ref(), mock_env(), QueryMsg::ContractInfo {}).unwrap())
+                .unwrap();
+        assert_eq!(res.contract, CONTRACT_NAME);
+        assert_eq!(res.version, CONTRACT_VERSION);
+
+        set_contract_version(&mut deps.storage, "crates.io:cw20-base", "0.13.0").unwrap();
+        let msg = MigrateMsg::RescaleDecimals { new_decimals: 9 };
+        match migrate(deps.as_mut(), mock_env(), msg).unwrap_err() {
+            ContractError::CannotMigrate { contract } => {
+                assert_eq!(contract, "crates.io:cw20-base")
+            }
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn migrations_are_sequential() {
+        for (i, (from, _)) in MIGRATIONS.iter().enumerate() {
+            assert_eq!(*from as usize, i + 1);
+        }
+        assert_eq!(MIGRATIONS.len() + 1, STATE_VERSION as usize);
+    }
+
+    // Keys the records of accounts by the address string, as deployments before
+    // version 5 did
+    fn key_by_address_strings(store: &mut dyn Storage, api: &dyn Api) {
+        let address = |key: &[u8]| -> Result<Vec<u8>, ContractError> {
+            let address = addr_from_canonical_key(api, key.to_vec())?;
+            Ok(address.as_str().as_bytes().to_vec())
+        };
+        let pair = |key: &[u8]| -> Result<Vec<u8>, ContractError> {
+            let (owner, spender) = split_length_prefixed(key)?;
+            Ok([to_length_prefixed(&address(owner)?), address(spender)?].concat())
+        };
+        let checkpoint = |key: &[u8]| -> Result<Vec<u8>, ContractError> {
+            let (owner, rest) = split_length_prefixed(key)?;
+            let (spender, height) = split_length_prefixed(rest)?;
+            Ok([
+                to_length_prefixed(&address(owner)?),
+                to_length_prefixed(&address(spender)?),
+                height.to_vec(),
+            ]
+            .concat())
+        };
+        for prefix in [
+            PREFIX_BALANCES,
+            PREFIX_SENT,
+            PREFIX_RECEIVED,
+            PREFIX_BURNED,
+            PREFIX_NONCES,
+        ] {
+            rekey(store, prefix, &address, keep_larger).unwrap();
+        }
+        for prefix in [PREFIX_ALLOWANCES, PREFIX_ALLOWANCE_EXPIRATIONS] {
+            rekey(store, prefix, &pair, keep_larger).unwrap();
+        }
+        rekey(
+            store,
+            PREFIX_ALLOWANCE_CHECKPOINTS,
+            &checkpoint,
+            keep_larger,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn upgrades_legacy_state_step_by_step() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        assert_eq!(read_state_version(&deps.storage).unwrap(), STATE_VERSION);
+
+        // Strip what deployments at version 1 never wrote
+        let mut config_store = PrefixedStorage::new(&mut deps.storage, PREFIX_CONFIG);
+        config_store.remove(KEY_STATE_VERSION);
+        config_store.remove(KEY_HOLDER_COUNT);
+        config_store.remove(KEY_OWNER);
+        for prefix in [PREFIX_SUPPORTED_INTERFACES, PREFIX_HOLDERS_BY_SHARES] {
+            let mut prefix_store = PrefixedStorage::new(&mut deps.storage, prefix);
+            let keys: Vec<Vec<u8>> = prefix_store
+                .range(None, None, Order::Ascending)
+                .map(|(key, _)| key)
+                .collect();
+            for key in keys {
+                prefix_store.remove(&key);
+            }
+        }
+        key_by_address_strings(&mut deps.storage, &deps.api);
+
+        let msg = MigrateMsg::Upgrade { owner: None };
+        match migrate(deps.as_mut(), mock_env(), msg).unwrap_err() {
+            ContractError::OwnerRequired {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        let msg = MigrateMsg::Upgrade {
+            owner: Some("creator".to_string()),
+        };
+        let res = migrate(deps.as_mut(), mock_env(), msg).unwrap();
+        assert_eq!(
+            read_owner(&deps.storage).unwrap(),
+            Some(Addr::unchecked("creator"))
+        );
+        assert!(res
+            .attributes
+            .contains(&attr("previous_state_version", "1")));
+        assert!(res.attributes.contains(&attr("state_version", "5")));
+        assert_eq!(read_state_version(&deps.storage).unwrap(), STATE_VERSION);
+        assert_eq!(balance(deps.as_ref(), "addr0000"), 1_500_000);
+        assert_eq!(balance(deps.as_ref(), "addr1111"), 2_000_000);
+        assert_eq!(allowance(deps.as_ref()), 250_000);
+        let balances_store = ReadonlyPrefixedStorage::new(&deps.storage, PREFIX_BALANCES);
+        assert_eq!(balances_store.get(b"addr0000"), None);
+
+        let res: SupportedInterfacesResponse = from_slice(
+            &query(deps.as_ref(), mock_env(), QueryMsg::SupportedInterfaces {}).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(res.interfaces.len(), interfaces::INTERFACES.len());
+        assert_eq!(holders::read_holder_count(&deps.storage).unwrap(), 2);
+        let msg = QueryMsg::TopHolders { limit: Some(1) };
+        let res: TopHoldersResponse =
+            from_slice(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert_eq!(res.holders[0].address, "addr1111");
+
+        // Steps are idempotent, migrating again changes nothing
+        let msg = MigrateMsg::Upgrade { owner: None };
+        migrate(deps.as_mut(), mock_env(), msg).unwrap();
+        assert_eq!(holders::read_holder_count(&deps.storage).unwrap(), 2);
+
+        // The owner cannot be replaced by migrating
+        let msg = MigrateMsg::Upgrade {
+            owner: Some("usurper".to_string()),
+        };
+        match migrate(deps.as_mut(), mock_env(), msg).unwrap_err() {
+            ContractError::OwnerAlreadySet {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn merges_records_of_two_spellings_of_one_address() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        key_by_address_strings(&mut deps.storage, &deps.api);
+        write_state_version(&mut deps.storage, 4);
+
+        // Part of the balance of addr0000 and a larger allowance are held
+        // under another spelling
+        let mut balances_store = PrefixedStorage::new(&mut deps.storage, PREFIX_BALANCES);
+        balances_store.set(b"addr0000", &1_000_000u128.to_be_bytes());
+        balances_store.set(b"ADDR0000", &500_000u128.to_be_bytes());
+        let mut sent_store = PrefixedStorage::new(&mut deps.storage, PREFIX_SENT);
+        sent_store.set(b"addr0000", &5u128.to_be_bytes());
+        sent_store.set(b"ADDR0000", &7u128.to_be_bytes());
+        let expires = Expiration::AtHeight(1_000_000);
+        PrefixedStorage::multilevel(&mut deps.storage, &[PREFIX_ALLOWANCES, b"ADDR0000"])
+            .set(b"spender", &400_000u128.to_be_bytes());
+        PrefixedStorage::multilevel(
+            &mut deps.storage,
+            &[PREFIX_ALLOWANCE_EXPIRATIONS, b"ADDR0000"],
+        )
+        .set(b"spender", &to_vec(&expires).unwrap());
+
+        let msg = MigrateMsg::Upgrade { owner: None };
+        migrate(deps.as_mut(), mock_env(), msg).unwrap();
+        assert_eq!(balance(deps.as_ref(), "addr0000"), 1_500_000);
+        assert_eq!(token_info(deps.as_ref()).total_supply.u128(), 3_500_000);
+        let msg = QueryMsg::Allowance {
+            owner: "addr0000".to_string(),
+            spender: "spender".to_string(),
+        };
+        let res: AllowanceResponse =
+            from_slice(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert_eq!((res.allowance.u128(), res.expires), (400_000, expires));
+        let msg = QueryMsg::AccountStats {
+            address: "addr0000".to_string(),
+        };
+        let res: AccountStatsResponse =
+            from_slice(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert_eq!(res.sent.u128(), 12);
+        assert_eq!(holders::read_holder_count(&deps.storage).unwrap(), 2);
+    }
+
+    #[test]
+    fn rejects_downgrades() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        write_state_version(&mut deps.storage, STATE_VERSION + 1);
+        let msg = MigrateMsg::Upgrade { owner: None };
+        match migrate(deps.as_mut(), mock_env(), msg).unwrap_err() {
+            ContractError::StateDowngrade { stored, current } => {
+                assert_eq!(stored, STATE_VERSION + 1);
+                assert_eq!(current, STATE_VERSION);
+            }
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn overflow_leaves_state_untouched() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+
+        let msg = MigrateMsg::RescaleDecimals { new_decimals: 42 };
+        match migrate(deps.as_mut(), mock_env(), msg).unwrap_err() {
+            ContractError::RescaleOverflow {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        assert_eq!(balance(deps.as_ref(), "addr0000"), 1_500_000);
+        assert_eq!(token_info(deps.as_ref()).decimals, 9);
+    }
+}
@@ -0,0 +1,293 @@
+use cosmwasm_std::{from_slice, to_vec, DepsMut, Env, MessageInfo, Response, Storage, Uint128};
+use cosmwasm_storage::{PrefixedStorage, ReadonlyPrefixedStorage};
+
+use crate::cap;
+use crate::contract::{balance_data, checked_add, mint_balance, PREFIX_CONFIG};
+use crate::error::ContractError;
+use crate::events;
+use crate::msg::{InitialBalance, MinterResponse};
+use crate::pause;
+use crate::state::Minter;
+
+pub const KEY_MINTER: &[u8] = b"minter";
+
+// Without a minter the supply only grows through wrapping and rebases. The
+// minter is set at instantiation and can mint until its deadline, if any.
+
+pub fn read_minter(store: &dyn Storage) -> Result<Option<Minter>, ContractError> {
+    let config_store = ReadonlyPrefixedStorage::new(store, PREFIX_CONFIG);
+    match config_store.get(KEY_MINTER) {
+        Some(data) => Ok(Some(from_slice(&data)?)),
+        None => Ok(None),
+    }
+}
+
+pub fn write_minter(store: &mut dyn Storage, minter: &Minter) -> Result<(), ContractError> {
+    let mut config_store = PrefixedStorage::new(store, PREFIX_CONFIG);
+    config_store.set(KEY_MINTER, &to_vec(minter)?);
+    Ok(())
+}
+
+/// The minter in the cw20 format, unset without a minter
+pub fn minter_response(store: &dyn Storage) -> Result<Option<MinterResponse>, ContractError> {
+    let cap = cap::read_max_supply(store)?.map(Uint128::from);
+    Ok(read_minter(store)?.map(|minter| MinterResponse {
+        minter: minter.minter.to_string(),
+        cap,
+        mint_deadline: minter.mint_deadline,
+    }))
+}
+
+fn remove_minter(store: &mut dyn Storage) {
+    let mut config_store = PrefixedStorage::new(store, PREFIX_CONFIG);
+    config_store.remove(KEY_MINTER);
+}
+
+// Errors unless `info` is signed by the minter before its deadline
+fn assert_can_mint(
+    store: &dyn Storage,
+    env: &Env,
+    info: &MessageInfo,
+) -> Result<(), ContractError> {
+    let minter = match read_minter(store)? {
+        Some(minter) if minter.minter == info.sender => minter,
+        _ => return Err(ContractError::Unauthorized {}),
+    };
+    match minter.mint_deadline {
+        Some(deadline) if deadline.is_expired(&env.block) => Err(ContractError::MintingEnded {}),
+        _ => Ok(()),
+    }
+}
+
+/// Create `amount` new tokens for `recipient`. Minter only.
+pub fn try_mint(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipient: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    pause::assert_not_paused(deps.storage)?;
+    assert_can_mint(deps.storage, &env, &info)?;
+    let recipient = deps.api.addr_validate(&recipient)?;
+    mint_balance(deps.storage, deps.api, &env, &recipient, amount.u128())?;
+
+    Ok(Response::new()
+        .set_data(balance_data(deps.storage, deps.api, &recipient)?)
+        .add_event(events::mint_event(
+            deps.storage,
+            deps.api,
+            &recipient,
+            amount.u128(),
+        )?)
+        .add_attribute("action", "mint")
+        .add_attribute("minter", info.sender)
+        .add_attribute("recipient", recipient)
+        .add_attribute("amount", amount.to_string()))
+}
+
+/// Mint to several recipients at once. Minter only.
+pub fn try_mint_many(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    mints: Vec<InitialBalance>,
+) -> Result<Response, ContractError> {
+    pause::assert_not_paused(deps.storage)?;
+    assert_can_mint(deps.storage, &env, &info)?;
+    let mut res = Response::new();
+    let mut total = 0u128;
+    for mint in mints {
+        let recipient = deps.api.addr_validate(&mint.address)?;
+        mint_balance(deps.storage, deps.api, &env, &recipient, mint.amount.u128())?;
+        res = res.add_event(events::mint_event(
+            deps.storage,
+            deps.api,
+            &recipient,
+            mint.amount.u128(),
+        )?);
+        total = checked_add(total, mint.amount.u128())?;
+    }
+
+    Ok(res
+        .add_attribute("action", "mint_many")
+        .add_attribute("minter", info.sender)
+        .add_attribute("amount", total.to_string()))
+}
+
+/// Hand the minter role to `new_minter`, keeping the deadline, or give it up
+/// for good with `None`. Minter only.
+pub fn try_update_minter(
+    deps: DepsMut,
+    info: MessageInfo,
+    new_minter: Option<String>,
+) -> Result<Response, ContractError> {
+    let minter = match read_minter(deps.storage)? {
+        Some(minter) if minter.minter == info.sender => minter,
+        _ => return Err(ContractError::Unauthorized {}),
+    };
+    let new_minter = match new_minter {
+        Some(new_minter) => {
+            let new_minter = deps.api.addr_validate(&new_minter)?;
+            write_minter(
+                deps.storage,
+                &Minter {
+                    minter: new_minter.clone(),
+                    ..minter
+                },
+            )?;
+            new_minter.to_string()
+        }
+        None => {
+            remove_minter(deps.storage);
+            String::new()
+        }
+    };
+
+    Ok(Response::new()
+        .add_attribute("action", "update_minter")
+        .add_attribute("minter", info.sender)
+        .add_attribute("new_minter", new_minter))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate, query, read_balance, read_total_supply};
+    use crate::msg::{ExecuteMsg, InstantiateMsg, MinterInfo, MinterResponse, QueryMsg};
+    use crate::testing::{default_instantiate_msg, env_at};
+    use cosmwasm_std::testing::{mock_dependencies_with_balance, mock_env, mock_info};
+    use cosmwasm_std::{from_slice, Addr, Timestamp};
+    use cw_utils::Expiration;
+
+    fn setup(deps: DepsMut, mint: Option<MinterInfo>) {
+        let instantiate_msg = InstantiateMsg {
+            mint,
+            ..default_instantiate_msg()
+        };
+        instantiate(deps, mock_env(), mock_info("creator", &[]), instantiate_msg).unwrap();
+    }
+
+    fn mint(deps: DepsMut, env: Env, signer: &str) -> Result<Response, ContractError> {
+        let msg = ExecuteMsg::Mint {
+            recipient: "addr0000".to_string(),
+            amount: Uint128::from(100u128),
+        };
+        execute(deps, env, mock_info(signer, &[]), msg)
+    }
+
+    #[test]
+    fn minter_mints_until_deadline() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        let deadline = Expiration::AtTime(Timestamp::from_seconds(2_000));
+        setup(
+            deps.as_mut(),
+            Some(MinterInfo {
+                minter: "minter".to_string(),
+                mint_deadline: Some(deadline),
+            }),
+        );
+        let res: Option<MinterResponse> =
+            from_slice(&query(deps.as_ref(), mock_env(), QueryMsg::Minter {}).unwrap()).unwrap();
+        assert_eq!(
+            res,
+            Some(MinterResponse {
+                minter: "minter".to_string(),
+                cap: None,
+                mint_deadline: Some(deadline),
+            })
+        );
+
+        match mint(deps.as_mut(), env_at(1_000), "creator").unwrap_err() {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        mint(deps.as_mut(), env_at(1_000), "minter").unwrap();
+        let msg = ExecuteMsg::MintMany {
+            mints: vec![
+                InitialBalance {
+                    address: "addr0000".to_string(),
+                    amount: Uint128::from(50u128),
+                },
+                InitialBalance {
+                    address: "addr1111".to_string(),
+                    amount: Uint128::from(25u128),
+                },
+            ],
+        };
+        execute(deps.as_mut(), env_at(1_500), mock_info("minter", &[]), msg).unwrap();
+        let balance = read_balance(&deps.storage, &deps.api, &Addr::unchecked("addr0000")).unwrap();
+        assert_eq!(balance, 150);
+        assert_eq!(read_total_supply(&deps.storage).unwrap(), 175);
+
+        match mint(deps.as_mut(), env_at(2_000), "minter").unwrap_err() {
+            ContractError::MintingEnded {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        let msg = ExecuteMsg::MintMany { mints: vec![] };
+        match execute(deps.as_mut(), env_at(2_000), mock_info("minter", &[]), msg).unwrap_err() {
+            ContractError::MintingEnded {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn minter_hands_over_or_gives_up_the_role() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(
+            deps.as_mut(),
+            Some(MinterInfo {
+                minter: "minter".to_string(),
+                mint_deadline: None,
+            }),
+        );
+        let msg = ExecuteMsg::UpdateMinter {
+            new_minter: Some("dao".to_string()),
+        };
+        match execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            msg.clone(),
+        ) {
+            Err(ContractError::Unauthorized {}) => {}
+            res => panic!("unexpected result: {:?}", res),
+        }
+        execute(deps.as_mut(), mock_env(), mock_info("minter", &[]), msg).unwrap();
+        match mint(deps.as_mut(), mock_env(), "minter").unwrap_err() {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        mint(deps.as_mut(), mock_env(), "dao").unwrap();
+
+        let msg = ExecuteMsg::UpdateMinter { new_minter: None };
+        execute(deps.as_mut(), mock_env(), mock_info("dao", &[]), msg).unwrap();
+        let res: Option<MinterResponse> =
+            from_slice(&query(deps.as_ref(), mock_env(), QueryMsg::Minter {}).unwrap()).unwrap();
+        assert_eq!(res, None);
+        match mint(deps.as_mut(), mock_env(), "dao").unwrap_err() {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        let msg = ExecuteMsg::UpdateMinter {
+            new_minter: Some("dao".to_string()),
+        };
+        match execute(deps.as_mut(), mock_env(), mock_info("dao", &[]), msg).unwrap_err() {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn no_minting_without_minter() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut(), None);
+        let res: Option<MinterResponse> =
+            from_slice(&query(deps.as_ref(), mock_env(), QueryMsg::Minter {}).unwrap()).unwrap();
+        assert_eq!(res, None);
+        match mint(deps.as_mut(), mock_env(), "creator").unwrap_err() {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+}
@@ -1,48 +1,878 @@
+use cosmwasm_schema::QueryResponses;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use cosmwasm_std::Uint128;
+use cosmwasm_std::{Binary, Decimal, Uint128};
+use cw2::ContractVersion;
+use cw_utils::Expiration;
 
-#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+use crate::amount;
+use crate::state::{
+    Constants, DividendAsset, FeeCurve, RateLimit, ReceiptMode, SendFailureMode, Timelock,
+};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct InitialBalance {
     pub address: String,
+    #[serde(deserialize_with = "amount::deserialize")]
     pub amount: Uint128,
 }
 
-#[derive(Serialize, Deserialize, JsonSchema)]
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
 pub struct InstantiateMsg {
     pub name: String,
     pub symbol: String,
     pub decimals: u8,
     pub initial_balances: Vec<InitialBalance>,
+    /// Pause duration in seconds after which holders may vote to unpause.
+    /// If unset, only the owner can unpause.
+    pub max_pause_duration: Option<u64>,
+    /// Native denom the token wraps 1:1 through Deposit and Withdraw.
+    /// If unset, the token wraps nothing.
+    pub wrapped_denom: Option<String>,
+    /// Account allowed to force transfers out of any account.
+    /// If unset, clawback is disabled for good.
+    pub clawback_admin: Option<String>,
+    /// Cap on the total supply, checked by every mint.
+    /// If unset, the supply is unbounded.
+    pub max_supply: Option<Uint128>,
+    /// Account allowed to mint, optionally until a deadline.
+    /// If unset, nobody can mint.
+    pub mint: Option<MinterInfo>,
+    /// Whether Transfer, TransferFrom and Send accept an amount of zero, e.g. as
+    /// pings. Defaults to true.
+    #[serde(default = "default_true")]
+    pub allow_zero_transfers: bool,
+    /// Supply minted onto the contract itself and released linearly to a
+    /// beneficiary through ReleaseDrip. If unset, nothing is preminted.
+    #[serde(default)]
+    pub drip: Option<DripInfo>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MinterInfo {
+    pub minter: String,
+    /// Minting is rejected once this has expired
+    pub mint_deadline: Option<Expiration>,
 }
 
-#[derive(Serialize, Deserialize, JsonSchema)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DripInfo {
+    pub beneficiary: String,
+    pub amount: Uint128,
+    /// Block time in seconds
+    pub start: u64,
+    /// Block time in seconds
+    pub end: u64,
+}
+
+/// Transfers, sends, burns and mints set the new balance of the debited or
+/// minted account as response data, encoded as `BalanceResponse`. Approvals and
+/// spends from an allowance set the remaining allowance as `AllowanceResponse`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg {
+    /// Set the allowance of `spender` over the signer's tokens. An `amount` of
+    /// `Uint128::MAX` is unlimited and not decreased by spends. Zero also
+    /// revokes a periodic allowance.
     Approve {
         spender: String,
+        #[serde(deserialize_with = "amount::deserialize")]
         amount: Uint128,
+        /// Only set the allowance if it currently equals this value, to rule out
+        /// spends front-running the change
+        #[serde(default, deserialize_with = "amount::deserialize_option")]
+        expected_current: Option<Uint128>,
+        /// Account to approve for instead of the signer. Trusted forwarder only.
+        #[serde(default)]
+        on_behalf_of: Option<String>,
     },
     Transfer {
         recipient: String,
+        #[serde(deserialize_with = "amount::deserialize")]
         amount: Uint128,
+        /// Optional memo emitted as event attribute, e.g. an exchange deposit reference
+        memo: Option<String>,
+        /// Account to transfer from instead of the signer. Trusted forwarder only.
+        #[serde(default)]
+        on_behalf_of: Option<String>,
     },
     TransferFrom {
         owner: String,
         recipient: String,
+        #[serde(deserialize_with = "amount::deserialize")]
         amount: Uint128,
+        memo: Option<String>,
     },
     Burn {
+        #[serde(deserialize_with = "amount::deserialize")]
+        amount: Uint128,
+        /// Optional reason emitted as event attribute, for the audit trail
+        memo: Option<String>,
+    },
+    /// Burn tokens of `owner` within the allowance of the signer
+    BurnFrom {
+        owner: String,
+        #[serde(deserialize_with = "amount::deserialize")]
+        amount: Uint128,
+        memo: Option<String>,
+    },
+    /// Delegate the voting power of the signer's balance to `delegatee`
+    Delegate { delegatee: String },
+    /// Set an allowance on behalf of `owner` using a signature of `owner`
+    Permit {
+        owner: String,
+        spender: String,
+        #[serde(deserialize_with = "amount::deserialize")]
+        amount: Uint128,
+        deadline: u64,
+        nonce: u64,
+        public_key: Binary,
+        signature: Binary,
+    },
+    /// Transfer tokens of `owner` to `recipient` using a signature of `owner`.
+    /// Any account can relay the signed transfer.
+    RelayedTransfer {
+        owner: String,
+        recipient: String,
+        #[serde(deserialize_with = "amount::deserialize")]
+        amount: Uint128,
+        nonce: u64,
+        expiry: u64,
+        public_key: Binary,
+        signature: Binary,
+    },
+    /// Create or reconfigure a treasury bucket. Owner only.
+    SetBucket {
+        name: String,
+        spenders: Vec<String>,
+        #[serde(default, deserialize_with = "amount::deserialize_option")]
+        spend_limit: Option<Uint128>,
+    },
+    /// Move tokens from the signer's balance into a treasury bucket
+    FundBucket {
+        name: String,
+        #[serde(deserialize_with = "amount::deserialize")]
+        amount: Uint128,
+    },
+    /// Reallocate tokens between treasury buckets. Owner only.
+    MoveBetweenBuckets {
+        from: String,
+        to: String,
+        #[serde(deserialize_with = "amount::deserialize")]
+        amount: Uint128,
+    },
+    /// Pay out of a treasury bucket. Bucket spenders only.
+    SpendFromBucket {
+        name: String,
+        recipient: String,
+        #[serde(deserialize_with = "amount::deserialize")]
+        amount: Uint128,
+    },
+    /// Halt all token movements. Owner only.
+    Pause {},
+    /// Resume token movements. Owner only.
+    Unpause {},
+    /// Vote to unpause with the voting power held at the pause height
+    VoteUnpause {},
+    /// Configure the transfer fee routed to a treasury. Owner only.
+    UpdateFeeConfig {
+        enabled: bool,
+        rate_bps: u16,
+        treasury: String,
+    },
+    /// Scale the transfer fee with the transfer volume, or go back to the fixed
+    /// rate with `None`. Owner only.
+    UpdateFeeCurve { curve: Option<FeeCurve> },
+    /// Replace the max-transaction and max-wallet limits. Owner only.
+    UpdateTransferLimits {
+        #[serde(default, deserialize_with = "amount::deserialize_option")]
+        max_tx_amount: Option<Uint128>,
+        #[serde(default, deserialize_with = "amount::deserialize_option")]
+        max_wallet_amount: Option<Uint128>,
+        /// Accounts neither limit applies to, e.g. pools and the treasury
+        exempt: Vec<String>,
+    },
+    /// Reject transfers below `amount`, or allow any amount again with zero.
+    /// Mints and burns are not affected. Owner only.
+    SetMinTransferAmount {
+        #[serde(deserialize_with = "amount::deserialize")]
+        amount: Uint128,
+    },
+    /// Burn `rate_bps` basis points of every transfer, or stop with zero. Owner only.
+    UpdateTransferBurn {
+        rate_bps: u16,
+        /// Accounts whose transfers are not burned, e.g. pools and the treasury
+        exempt: Vec<String>,
+    },
+    /// Execute several messages in order as the signer, failing all of them on
+    /// the first error
+    Multicall { msgs: Vec<ExecuteMsg> },
+    /// Throttle the outgoing transfers of `address`, or lift the throttle. Owner only.
+    SetRateLimit {
+        address: String,
+        limit: Option<RateLimit>,
+    },
+    /// Require transfers of the signer above `threshold` to go through
+    /// InitiateLargeTransfer and ConfirmLargeTransfer. `None` opts out.
+    SetLargeTransferPolicy {
+        #[serde(default, deserialize_with = "amount::deserialize_option")]
+        threshold: Option<Uint128>,
+        /// Seconds a pending transfer can be confirmed in
+        window: u64,
+        /// Account allowed to confirm besides the signer
+        co_signer: Option<String>,
+    },
+    /// Record a large transfer of the signer without moving tokens yet
+    InitiateLargeTransfer {
+        recipient: String,
+        #[serde(deserialize_with = "amount::deserialize")]
+        amount: Uint128,
+    },
+    /// Execute a pending large transfer. Sender or co-signer only.
+    ConfirmLargeTransfer { id: u64 },
+    /// Drop a pending large transfer. Sender only.
+    CancelLargeTransfer { id: u64 },
+    /// Transfer tokens the recipient can only move after `release_at` (block time in seconds)
+    TransferLocked {
+        recipient: String,
+        #[serde(deserialize_with = "amount::deserialize")]
+        amount: Uint128,
+        release_at: u64,
+    },
+    /// Assign or remove the role allowed to rebase the supply. Owner only.
+    SetRebaser { rebaser: Option<String> },
+    /// Scale all balances by changing the total supply by `delta`. Rebaser only.
+    Rebase { delta: SupplyDelta },
+    /// Create new tokens for `recipient`. Minter only, until the mint deadline.
+    Mint {
+        recipient: String,
+        #[serde(deserialize_with = "amount::deserialize")]
+        amount: Uint128,
+    },
+    /// Mint to several recipients in one message. Minter only, until the mint
+    /// deadline.
+    MintMany { mints: Vec<InitialBalance> },
+    /// Hand the minter role to `new_minter`, or remove minting for good with
+    /// `None`. Minter only.
+    UpdateMinter { new_minter: Option<String> },
+    /// Mint tokens 1:1 for the attached coins of the wrapped denom
+    Deposit {},
+    /// Burn tokens and get the same amount of the wrapped denom back
+    Withdraw {
+        #[serde(deserialize_with = "amount::deserialize")]
+        amount: Uint128,
+    },
+    /// Escrow tokens the recipient can claim, in one or several parts, until
+    /// `expires_at` (block time in seconds)
+    TransferClaimable {
+        recipient: String,
+        #[serde(deserialize_with = "amount::deserialize")]
+        amount: Uint128,
+        expires_at: u64,
+    },
+    /// Claim part of a claimable transfer. Recipient only.
+    Claim {
+        id: u64,
+        #[serde(deserialize_with = "amount::deserialize")]
         amount: Uint128,
     },
+    /// Take back the unclaimed remainder of an expired claimable transfer. Sender only.
+    Reclaim { id: u64 },
+    /// Hand over or remove the marketing admin role. Owner or marketing admin only.
+    UpdateMarketingAdmin { admin: Option<String> },
+    /// Set or clear the symbol shown instead of the canonical one, e.g. `CASH.axl`
+    /// on a bridged deployment. Marketing admin only.
+    UpdateDisplaySymbol { display_symbol: Option<String> },
+    /// Rename the token, e.g. after a rebrand. Owner only.
+    UpdateTokenMetadata { name: String, symbol: String },
+    /// Lock tokens that vest linearly to `recipient` between `start` and `end`
+    /// (block times in seconds)
+    CreateStream {
+        recipient: String,
+        #[serde(deserialize_with = "amount::deserialize")]
+        amount: Uint128,
+        start: u64,
+        end: u64,
+    },
+    /// Pay the vested part of a stream out to its recipient. Sender or recipient only.
+    WithdrawFromStream { id: u64 },
+    /// Stop a stream, paying the vested part to the recipient and the rest
+    /// back to the sender. Sender or recipient only.
+    CancelStream { id: u64 },
+    /// Pay the part of the premint released by now to the drip beneficiary.
+    /// Anyone can call.
+    ReleaseDrip {},
+    /// Pay `amount` to `payee` every `interval` seconds, starting now
+    CreateSubscription {
+        payee: String,
+        #[serde(deserialize_with = "amount::deserialize")]
+        amount: Uint128,
+        interval: u64,
+    },
+    /// Make the payment that is due on a subscription. Anyone can call this.
+    ProcessSubscription { id: u64 },
+    /// Stop a subscription. Payer only.
+    CancelSubscription { id: u64 },
+    /// Pay burners a secondary cw20 asset, or stop doing so with `None`. Owner only.
+    SetBurnReceipt { receipt: Option<BurnReceiptInfo> },
+    /// Distribute the attached coins and `amount` tokens of the signer to all
+    /// holders pro rata to their current balances
+    FundDividends {
+        #[serde(default, deserialize_with = "amount::deserialize_option")]
+        amount: Option<Uint128>,
+    },
+    /// Pay out all dividends of the signer
+    ClaimDividends {},
+    /// Start or stop collecting tokens sent to the contract's own address for
+    /// burning. Owner only.
+    UpdateBuyback { enabled: bool },
+    /// Burn all collected tokens. Anyone can call it.
+    BurnCollected {},
+    /// Queue transfers above a threshold for a delay, or stop with `None`. Owner only.
+    UpdateTimelock { timelock: Option<Timelock> },
+    /// Complete a timelocked transfer after its delay. Sender, recipient or, for a
+    /// TransferFrom, the spender only.
+    ExecutePendingTransfer { id: u64 },
+    /// Drop a timelocked transfer within its delay. Owner only.
+    CancelTimelockedTransfer { id: u64 },
+    /// Give up ownership for good, e.g. for a trustless launch. Owner only.
+    RenounceOwnership {},
+    /// Give up `role` for good; nobody can be assigned it again. Holder of the
+    /// role only.
+    RenounceRole { role: Role },
+    /// Require a second key to confirm privileged actions of the owner, the
+    /// minter and the clawback admin, or stop with `None`. Owner only.
+    SetDualControl { confirmer: Option<String> },
+    /// Propose a privileged action. Confirmer or holder of the role it needs only.
+    ProposeAdminAction { action: Box<ExecuteMsg> },
+    /// Confirm and execute a proposal of the other admin key
+    ConfirmAdminAction { id: u64 },
+    /// Drop a proposal. Confirmer or holder of the role it needs only.
+    CancelAdminAction { id: u64 },
+    /// Transfer to a contract and call its cw20 receive hook with `msg`.
+    /// A failing hook reverts the transfer unless `on_failure` is `record`.
+    Send {
+        contract: String,
+        #[serde(deserialize_with = "amount::deserialize")]
+        amount: Uint128,
+        msg: Binary,
+        on_failure: Option<SendFailureMode>,
+        /// Optional memo emitted as event attribute, as on Transfer
+        memo: Option<String>,
+    },
+    /// Send to `recipient` with `msg` if it is a contract, so its receive hook
+    /// runs, and transfer plainly otherwise
+    TransferAndCall {
+        recipient: String,
+        #[serde(deserialize_with = "amount::deserialize")]
+        amount: Uint128,
+        msg: Binary,
+    },
+    /// Call the receive hook of a recorded failed send again. Sender only.
+    RetrySend { id: u64 },
+    /// Move tokens out of `owner` regardless of pause, fees, limits and the
+    /// timelock. Clawback admin only.
+    ForceTransfer {
+        owner: String,
+        recipient: String,
+        #[serde(deserialize_with = "amount::deserialize")]
+        amount: Uint128,
+    },
+    /// Hand the clawback role over, or give it up for good with `None`.
+    /// Clawback admin only.
+    UpdateClawbackAdmin { admin: Option<String> },
+    /// Return tokens transferred to the contract's own address by mistake.
+    /// Checked and charged like any transfer. Owner only.
+    RecoverOwnBalance {
+        recipient: String,
+        #[serde(deserialize_with = "amount::deserialize")]
+        amount: Uint128,
+    },
+    /// Record a deposit of another cw20 token, called by that token's Send
+    Receive(ReceiveMsg),
+    /// Forward the recorded balance of the foreign cw20 `token` to `recipient`,
+    /// at most what the token reports the contract holds. Clears the record.
+    /// Owner only.
+    SweepForeign { token: String, recipient: String },
+    /// Send all native coins of `denom` held by the contract to `recipient`, or to
+    /// the treasury if unset. The wrapped denom and denoms paid as dividends
+    /// cannot be swept. Owner only.
+    SweepNative {
+        denom: String,
+        recipient: Option<String>,
+    },
+    /// Check every transfer against the compliance contract at `address`. Owner only.
+    SetComplianceContract { address: String },
+    /// Stop checking transfers against a compliance contract. Owner only.
+    ClearComplianceContract {},
+    /// Let `spender` move up to `amount_per_period` of the signer's tokens in
+    /// every period of `period_seconds`. Replaces the plain allowance of `spender`
+    /// in TransferFrom and BurnFrom. Zero `amount_per_period` revokes it.
+    ApprovePeriodic {
+        spender: String,
+        #[serde(deserialize_with = "amount::deserialize")]
+        amount_per_period: Uint128,
+        period_seconds: u64,
+    },
+    /// Set or remove the account allowed to place lockups. Owner only.
+    SetLocker { locker: Option<String> },
+    /// Keep `amount` of the balance of `address` from being transferred until
+    /// `unlock_at`. Owner or locker only.
+    LockBalance {
+        address: String,
+        #[serde(deserialize_with = "amount::deserialize")]
+        amount: Uint128,
+        unlock_at: u64,
+    },
+    /// Pay every entry from the signer's balance in one go, e.g. a payroll run.
+    /// At most `MAX_DISTRIBUTION_ENTRIES` entries.
+    Distribute { entries: Vec<DistributionEntry> },
+    /// Stop `address` from sending tokens until `expires`, or until unfrozen if
+    /// unset. Owner only.
+    Freeze {
+        address: String,
+        expires: Option<Expiration>,
+    },
+    /// Lift the freeze of `address` before it expires. Owner only.
+    Unfreeze { address: String },
+    /// Set or remove the forwarder allowed to send Transfer and Approve on
+    /// behalf of other accounts, e.g. for smart contract wallets. Owner only.
+    SetTrustedForwarder { forwarder: Option<String> },
+    /// Add accounts whose balances do not count towards the circulating supply,
+    /// e.g. treasury, vesting and bridge escrow, and remove others. Owner only.
+    UpdateSupplyExclusions {
+        add: Vec<String>,
+        remove: Vec<String>,
+    },
+    /// Set or remove the protocol treasury, which receives transfer fees in
+    /// place of the fee config's account. Owner only.
+    SetTreasury { treasury: Option<String> },
+    /// Set several allowances of the signer at once, each optionally expiring.
+    /// All or none are written. At most `MAX_APPROVE_ENTRIES` entries. A zero
+    /// entry also revokes a periodic allowance, as with `Approve`.
+    ApproveMany { approvals: Vec<ApprovalEntry> },
+    /// Delete every allowance and periodic allowance granted by the signer,
+    /// e.g. after a wallet compromise
+    RevokeAllAllowances {},
+    /// Raise the allowance of `spender` by `amount`, as in cw20. An allowance
+    /// reaching `Uint128::MAX` becomes unlimited. `expires` replaces the
+    /// expiration if set.
+    IncreaseAllowance {
+        spender: String,
+        #[serde(deserialize_with = "amount::deserialize")]
+        amount: Uint128,
+        expires: Option<Expiration>,
+    },
+    /// Lower the allowance of `spender` by `amount`, down to zero at most, as
+    /// in cw20. `expires` replaces the expiration if set. Also revokes a
+    /// periodic allowance of `spender`.
+    DecreaseAllowance {
+        spender: String,
+        #[serde(deserialize_with = "amount::deserialize")]
+        amount: Uint128,
+        expires: Option<Expiration>,
+    },
+    /// Send tokens of `owner` within the allowance of the signer. The receive
+    /// hook sees the signer as sender, as in cw20.
+    SendFrom {
+        owner: String,
+        contract: String,
+        #[serde(deserialize_with = "amount::deserialize")]
+        amount: Uint128,
+        msg: Binary,
+    },
+    /// Update the cw20 marketing info. Unset fields stay as they are, empty
+    /// strings clear them. `marketing` hands over the marketing admin role.
+    /// Marketing admin only.
+    UpdateMarketing {
+        project: Option<String>,
+        description: Option<String>,
+        marketing: Option<String>,
+    },
+    /// Set the logo shown by wallets. Marketing admin only.
+    UploadLogo(Logo),
 }
 
+/// Logo in the cw20 format, a link or an embedded image
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
+pub enum Logo {
+    Url(String),
+    Embedded(EmbeddedLogo),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum EmbeddedLogo {
+    /// At most `MAX_LOGO_SIZE` bytes of SVG
+    Svg(Binary),
+    /// At most `MAX_LOGO_SIZE` bytes of PNG
+    Png(Binary),
+}
+
+/// Where to get the logo. Embedded logos are read with `QueryMsg::DownloadLogo`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LogoInfo {
+    Url(String),
+    Embedded,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ApprovalEntry {
+    pub spender: String,
+    #[serde(deserialize_with = "amount::deserialize")]
+    pub amount: Uint128,
+    /// The allowance reads as zero from then on. Never expires if unset.
+    #[serde(default)]
+    pub expires: Option<Expiration>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DistributionEntry {
+    pub address: String,
+    #[serde(deserialize_with = "amount::deserialize")]
+    pub amount: Uint128,
+    /// Added to the transfer event of this entry
+    pub memo: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SupplyDelta {
+    Increase(Uint128),
+    Decrease(Uint128),
+}
+
+/// Privileged role that can be renounced, see RenounceRole
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    Owner,
+    Minter,
+    MarketingAdmin,
+    ClawbackAdmin,
+    Rebaser,
+    Locker,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, QueryResponses)]
+#[serde(rename_all = "snake_case")]
 pub enum QueryMsg {
+    #[returns(BalanceResponse)]
     Balance { address: String },
+    /// Balances of up to `MAX_BALANCES_BATCH` addresses, in request order
+    #[returns(BalancesResponse)]
+    Balances { addresses: Vec<String> },
+    /// What a transfer would do right now, without executing it
+    #[returns(SimulateTransferResponse)]
+    SimulateTransfer {
+        sender: String,
+        recipient: String,
+        #[serde(deserialize_with = "amount::deserialize")]
+        amount: Uint128,
+    },
+    #[returns(AllowanceResponse)]
     Allowance { owner: String, spender: String },
+    /// Renewable budget of `spender` and what is left of it in the current period
+    #[returns(PeriodicAllowanceResponse)]
+    PeriodicAllowance { owner: String, spender: String },
+    /// Allowance at the end of block `height`
+    #[returns(AllowanceResponse)]
+    AllowanceAt {
+        owner: String,
+        spender: String,
+        height: u64,
+    },
+    /// Lifetime sent, received and burned totals of an account
+    #[returns(AccountStatsResponse)]
+    AccountStats { address: String },
+    /// Current voting power delegated to an account
+    #[returns(VotingPowerResponse)]
+    VotingPower { address: String },
+    /// Voting power delegated to an account at the end of block `height`
+    #[returns(VotingPowerResponse)]
+    VotingPowerAt { address: String, height: u64 },
+    /// Next permit nonce expected for an account
+    #[returns(NonceResponse)]
+    Nonce { address: String },
+    /// Total supply at the end of block `height`
+    #[returns(TotalSupplyResponse)]
+    TotalSupplyAt { height: u64 },
+    /// Total supply minus the balances of the accounts excluded by
+    /// `ExecuteMsg::UpdateSupplyExclusions`
+    #[returns(CirculatingSupplyResponse)]
+    CirculatingSupply {},
+    /// A single treasury bucket
+    #[returns(BucketResponse)]
+    Bucket { name: String },
+    /// All treasury buckets ordered by name
+    #[returns(BucketsResponse)]
+    Buckets {},
+    /// Protocol treasury, see `ExecuteMsg::SetTreasury`
+    #[returns(TreasuryResponse)]
+    Treasury {},
+    /// Pause status and unpause vote tally
+    #[returns(PauseStateResponse)]
+    PauseState {},
+    /// Transfer fee configuration
+    #[returns(FeeConfigResponse)]
+    FeeConfig {},
+    /// Transfer fee rate in effect now and the volume it is based on
+    #[returns(CurrentFeeResponse)]
+    CurrentFee {},
+    /// Max-transaction and max-wallet limits
+    #[returns(TransferLimitsResponse)]
+    TransferLimits {},
+    /// Rate limit of an account and its usage in the current window
+    #[returns(RateLimitResponse)]
+    RateLimit { address: String },
+    /// Two-phase transfer policy of an account
+    #[returns(LargeTransferPolicyResponse)]
+    LargeTransferPolicy { address: String },
+    /// A large transfer awaiting confirmation
+    #[returns(PendingTransferResponse)]
+    PendingTransfer { id: u64 },
+    /// Pending locks of an account, including matured but not yet released ones
+    #[returns(LocksResponse)]
+    Locks { address: String },
+    /// Rebaser role and the share to token ratio
+    #[returns(RebaseStateResponse)]
+    RebaseState {},
+    /// Token info, balance and restrictions relevant to `address` in one response, for wallet UIs
+    #[returns(AssetCardResponse)]
+    AssetCard { address: String },
+    /// Native denom the token wraps, if any
+    #[returns(WrappedDenomResponse)]
+    WrappedDenom {},
+    /// A claimable transfer that is not fully claimed or reclaimed yet
+    #[returns(ClaimableResponse)]
+    Claimable { id: u64 },
+    /// Interfaces declared by this deployment, for routers and registries
+    #[returns(SupportedInterfacesResponse)]
+    SupportedInterfaces {},
+    /// Contract version and the execute and query messages of every declared
+    /// interface, for tooling that adapts to optional extensions
+    #[returns(InterfacesResponse)]
+    Interfaces {},
+    /// Token metadata including the display symbol, and the total supply
+    #[returns(TokenInfoResponse)]
+    TokenInfo {},
+    /// Name, symbol, decimals and all operational settings at once
+    #[returns(ConfigResponse)]
+    Config {},
+    /// A stream that is not fully withdrawn or cancelled yet
+    #[returns(StreamResponse)]
+    Stream { id: u64 },
+    /// Open streams to `recipient` ordered by id
+    #[returns(StreamsResponse)]
+    StreamsByRecipient {
+        recipient: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Schedule and progress of the premint drip, if the token has one
+    #[returns(DripResponse)]
+    Drip {},
+    /// Active subscriptions paid by `payer` ordered by id
+    #[returns(SubscriptionsResponse)]
+    SubscriptionsByPayer {
+        payer: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Active subscriptions paying `payee` ordered by id
+    #[returns(SubscriptionsResponse)]
+    SubscriptionsByPayee {
+        payee: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Secondary asset paid to burners, if any
+    #[returns(BurnReceiptResponse)]
+    BurnReceipt {},
+    /// Dividends an account can claim, per funded asset
+    #[returns(PendingDividendsResponse)]
+    PendingDividends { address: String },
+    /// Tokens collected for burning and the amount burned so far
+    #[returns(BuybackResponse)]
+    Buyback {},
+    /// Outgoing transfers, fees, burns and rewards of an account within
+    /// reporting epoch `epoch` (block time divided by one day)
+    #[returns(SpendingReportResponse)]
+    SpendingReport { address: String, epoch: u64 },
+    /// Timelock mode settings, if enabled
+    #[returns(TimelockResponse)]
+    Timelock {},
+    /// A timelocked transfer that is not executed or cancelled yet
+    #[returns(TimelockedTransferResponse)]
+    TimelockedTransfer { id: u64 },
+    /// Queued timelocked transfers ordered by id
+    #[returns(TimelockedTransfersResponse)]
+    TimelockedTransfers {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Second admin key confirming privileged actions, if enabled
+    #[returns(DualControlResponse)]
+    DualControl {},
+    /// A privileged action waiting for confirmation
+    #[returns(AdminProposalResponse)]
+    AdminProposal { id: u64 },
+    /// Privileged actions waiting for confirmation ordered by id
+    #[returns(AdminProposalsResponse)]
+    AdminProposals {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Sends whose receive hook failed ordered by id
+    #[returns(FailedSendsResponse)]
+    FailedSends {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Number of accounts holding a nonzero balance
+    #[returns(HolderCountResponse)]
+    HolderCount {},
+    /// Accounts with the largest balances first
+    #[returns(TopHoldersResponse)]
+    TopHolders { limit: Option<u32> },
+    /// Accounts with a balance above `threshold`, largest first. Pages continue
+    /// after the `start_after` account.
+    #[returns(HoldersAboveResponse)]
+    HoldersAbove {
+        threshold: Uint128,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Account allowed to force transfers, if clawback is enabled
+    #[returns(ClawbackAdminResponse)]
+    ClawbackAdmin {},
+    /// Roles given up for good, in the order they were renounced
+    #[returns(RenouncedResponse)]
+    Renounced {},
+    /// Tokens transferred to the contract's own address that no escrow accounts for
+    #[returns(StuckBalanceResponse)]
+    StuckBalance {},
+    /// Foreign cw20 tokens received through their Send, ordered by token address.
+    /// Amounts are as claimed by the calling contract and not verified.
+    #[returns(ForeignBalancesResponse)]
+    ForeignBalances {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Maximum total supply, if capped
+    #[returns(CapResponse)]
+    Cap {},
+    /// Account allowed to mint and its deadline
+    /// Unset without a minter, as in cw20
+    #[returns(Option<MinterResponse>)]
+    Minter {},
+    /// Share of every transfer that is burned and the exempt accounts
+    #[returns(TransferBurnResponse)]
+    TransferBurn {},
+    /// One page of balances, allowances or the configuration, for snapshots.
+    /// Pass the `next` cursor of a page as `start_after` to get the following one.
+    #[returns(ExportStateResponse)]
+    ExportState {
+        section: ExportSection,
+        start_after: Option<Binary>,
+        limit: Option<u32>,
+    },
+    /// Check page by page that the balances add up to the total supply.
+    /// Pass the `next` cursor of a page as `start_after` to continue the sum.
+    #[returns(VerifySupplyResponse)]
+    VerifySupply {
+        start_after: Option<Binary>,
+        limit: Option<u32>,
+    },
+    /// Name and version of the code, as stored with cw2
+    #[returns(ContractVersion)]
+    ContractInfo {},
+    /// Contract every transfer is checked against, if any
+    #[returns(ComplianceContractResponse)]
+    ComplianceContract {},
+    /// Balance of `address` that is not locked up, with its active lockups
+    #[returns(SpendableBalanceResponse)]
+    SpendableBalance { address: String },
+    /// Account allowed to place lockups besides the owner
+    #[returns(LockerResponse)]
+    Locker {},
+
+    /// Allowances granted by `owner` ordered by the canonical address of the
+    /// spender, as in cw20
+    #[returns(AllAllowancesResponse)]
+    AllAllowances {
+        owner: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Accounts with a stored balance, emptied ones included, ordered by
+    /// canonical address, as in cw20
+    #[returns(AllAccountsResponse)]
+    AllAccounts {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    #[returns(MarketingInfoResponse)]
+    MarketingInfo {},
+    /// The embedded logo. Errors if the logo is a link or unset.
+    #[returns(DownloadLogoResponse)]
+    DownloadLogo {},
+    /// Whether `address` is frozen right now
+    #[returns(FrozenResponse)]
+    Frozen { address: String },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportSection {
+    Balances,
+    Allowances,
+    Config,
+}
+
+/// Migrations, run by the admin of the contract. Each one first brings the
+/// storage layout up to date and rejects state written by a newer version.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum MigrateMsg {
+    /// Only bring the storage layout up to date. Deployments from before the
+    /// contract had an owner must name one, others must not.
+    Upgrade { owner: Option<String> },
+    /// Fix the decimals chosen at instantiation. Balances, allowances, voting
+    /// checkpoints, limits and counters are converted to the new precision,
+    /// scaling down rounds down and the total supply becomes the sum of what is
+    /// left. Fails while escrows or other records owing fixed amounts exist.
+    RescaleDecimals { new_decimals: u8 },
+}
+
+/// Messages only the chain can send, e.g. through a governance proposal
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SudoMsg {
+    Pause {},
+    Unpause {},
+    /// Replace the anti-whale limits, see `ExecuteMsg::UpdateTransferLimits`
+    UpdateLimits {
+        #[serde(default, deserialize_with = "amount::deserialize_option")]
+        max_tx_amount: Option<Uint128>,
+        #[serde(default, deserialize_with = "amount::deserialize_option")]
+        max_wallet_amount: Option<Uint128>,
+        exempt: Vec<String>,
+    },
+    /// Move tokens regardless of pause, fees, limits and the timelock
+    ForceTransfer {
+        from: String,
+        to: String,
+        #[serde(deserialize_with = "amount::deserialize")]
+        amount: Uint128,
+    },
 }
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
@@ -50,7 +880,637 @@ pub struct BalanceResponse {
     pub balance: Uint128,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AddressBalance {
+    pub address: String,
+    pub balance: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct BalancesResponse {
+    pub balances: Vec<AddressBalance>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SimulateTransferResponse {
+    /// Paid to the treasury
+    pub fee: Uint128,
+    /// Destroyed by the transfer burn
+    pub burned: Uint128,
+    /// What the recipient would receive
+    pub net_amount: Uint128,
+    /// The transfer would wait for its timelock instead of completing
+    pub queued: bool,
+    /// Why the transfer would fail, unset if it would go through
+    pub error: Option<String>,
+}
+
 #[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
 pub struct AllowanceResponse {
     pub allowance: Uint128,
+    /// Expiration set by `ApproveMany` or the cw20 allowance messages. `Never`
+    /// for allowances that do not expire and for `QueryMsg::AllowanceAt`.
+    #[serde(default)]
+    pub expires: Expiration,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AllowanceInfo {
+    pub spender: String,
+    pub allowance: Uint128,
+    pub expires: Expiration,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct AllAllowancesResponse {
+    pub allowances: Vec<AllowanceInfo>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct AllAccountsResponse {
+    pub accounts: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PeriodicAllowanceResponse {
+    /// Zero if there is no periodic allowance
+    pub amount_per_period: Uint128,
+    pub period_seconds: u64,
+    /// Left to spend in the current period
+    pub remaining: Uint128,
+    /// Block time in seconds the next period begins at
+    pub resets_at: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct AccountStatsResponse {
+    pub sent: Uint128,
+    pub received: Uint128,
+    /// Lifetime amount burned from the account
+    pub burned: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct VotingPowerResponse {
+    pub voting_power: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct TotalSupplyResponse {
+    pub total_supply: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct CirculatingSupplyResponse {
+    pub circulating_supply: Uint128,
+    pub total_supply: Uint128,
+    /// Balances left out of the circulating supply, ordered by address
+    pub excluded: Vec<AddressBalance>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct NonceResponse {
+    pub nonce: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct TreasuryResponse {
+    pub treasury: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct BucketResponse {
+    pub name: String,
+    pub balance: Uint128,
+    pub spenders: Vec<String>,
+    pub spend_limit: Option<Uint128>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct BucketsResponse {
+    pub buckets: Vec<BucketResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct PauseStateResponse {
+    pub paused: bool,
+    pub paused_at: u64,
+    pub max_pause_duration: Option<u64>,
+    pub unpause_votes: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct FeeConfigResponse {
+    pub enabled: bool,
+    pub rate_bps: u16,
+    pub treasury: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct ConfigResponse {
+    pub constants: Constants,
+    pub owner: Option<String>,
+    pub minter: Option<MinterResponse>,
+    pub paused: bool,
+    pub fee_config: FeeConfigResponse,
+    pub transfer_burn: TransferBurnResponse,
+    pub max_supply: Option<Uint128>,
+    pub wrapped_denom: Option<String>,
+    pub rebaser: Option<String>,
+    pub clawback_admin: Option<String>,
+    pub compliance_contract: Option<String>,
+    pub locker: Option<String>,
+    /// Zero if transfers of any amount are allowed
+    pub min_transfer_amount: Uint128,
+    pub allow_zero_transfers: bool,
+    pub trusted_forwarder: Option<String>,
+    pub treasury: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct CurrentFeeResponse {
+    /// Zero if the fee is disabled
+    pub rate_bps: u16,
+    pub curve: Option<FeeCurve>,
+    pub epoch: u64,
+    pub epoch_volume: Uint128,
+    pub previous_epoch_volume: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct AssetRestrictions {
+    /// All token movements are halted
+    pub paused: bool,
+    /// Fee charged on transfers in basis points, zero if disabled
+    pub transfer_fee_bps: u16,
+    pub max_tx_amount: Option<Uint128>,
+    pub max_wallet_amount: Option<Uint128>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct AssetCardResponse {
+    pub name: String,
+    pub symbol: String,
+    pub display_symbol: Option<String>,
+    pub decimals: u8,
+    pub total_supply: Uint128,
+    pub balance: Uint128,
+    /// Tokens received with TransferLocked that are not released yet
+    pub locked: Uint128,
+    pub voting_power: Uint128,
+    pub delegate: Option<String>,
+    pub restrictions: AssetRestrictions,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct RebaseStateResponse {
+    pub rebaser: Option<String>,
+    pub total_supply: Uint128,
+    pub total_shares: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct TransferLimitsResponse {
+    pub max_tx_amount: Option<Uint128>,
+    pub max_wallet_amount: Option<Uint128>,
+    pub exempt: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct RateLimitResponse {
+    pub limit: Option<RateLimit>,
+    /// Amount sent within the current window
+    pub used: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct LargeTransferPolicyResponse {
+    /// Unset if the account did not opt in
+    pub threshold: Option<Uint128>,
+    pub window: u64,
+    pub co_signer: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct PendingTransferResponse {
+    pub id: u64,
+    pub sender: String,
+    pub recipient: String,
+    pub amount: Uint128,
+    /// Block time in seconds after which the transfer can no longer be confirmed
+    pub expires_at: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LockResponse {
+    pub amount: Uint128,
+    pub release_at: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct LocksResponse {
+    pub locks: Vec<LockResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LockupResponse {
+    pub amount: Uint128,
+    pub unlock_at: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct SpendableBalanceResponse {
+    pub balance: Uint128,
+    /// Balance minus the active lockups
+    pub spendable: Uint128,
+    pub lockups: Vec<LockupResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct LockerResponse {
+    pub locker: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FrozenResponse {
+    /// False once the freeze expired
+    pub frozen: bool,
+    /// Expiration of the latest freeze, `Never` if it lasts until unfrozen.
+    /// Unset if the account was never frozen or was unfrozen.
+    pub expires: Option<Expiration>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct WrappedDenomResponse {
+    pub denom: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct ClaimableResponse {
+    pub id: u64,
+    pub sender: String,
+    pub recipient: String,
+    pub amount: Uint128,
+    pub claimed: Uint128,
+    /// Block time in seconds after which only the sender can take back the remainder
+    pub expires_at: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SupportedInterface {
+    /// Interface name, e.g. `erc20` or `shard-token:votes`
+    pub interface: String,
+    /// Semver version of the interface
+    pub version: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct SupportedInterfacesResponse {
+    pub interfaces: Vec<SupportedInterface>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MessageFamily {
+    /// Interface name, e.g. `erc20` or `shard-token:votes`
+    pub interface: String,
+    /// Semver version of the interface
+    pub version: String,
+    /// Names of the execute messages of the interface, in snake case
+    pub execute: Vec<String>,
+    /// Names of the query messages of the interface, in snake case
+    pub query: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct InterfacesResponse {
+    /// Contract name stored with cw2
+    pub contract: String,
+    /// Contract version stored with cw2, updated by migrations
+    pub version: String,
+    pub families: Vec<MessageFamily>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct TokenInfoResponse {
+    pub name: String,
+    pub symbol: String,
+    /// Symbol to show instead of `symbol`, e.g. with a bridge suffix
+    pub display_symbol: Option<String>,
+    pub decimals: u8,
+    pub total_supply: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct StreamResponse {
+    pub id: u64,
+    pub sender: String,
+    pub recipient: String,
+    pub amount: Uint128,
+    pub withdrawn: Uint128,
+    pub start: u64,
+    pub end: u64,
+    /// Vested tokens the recipient has not withdrawn yet
+    pub withdrawable: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct StreamsResponse {
+    pub streams: Vec<StreamResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DripResponse {
+    pub beneficiary: String,
+    pub amount: Uint128,
+    pub released: Uint128,
+    pub start: u64,
+    pub end: u64,
+    /// Tokens released by the schedule but not paid out yet
+    pub releasable: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SubscriptionResponse {
+    pub id: u64,
+    pub payer: String,
+    pub payee: String,
+    pub amount: Uint128,
+    pub interval: u64,
+    /// Block time in seconds the next payment is due at
+    pub next_payment_at: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct SubscriptionsResponse {
+    pub subscriptions: Vec<SubscriptionResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BurnReceiptInfo {
+    /// cw20 contract of the receipt token
+    pub contract: String,
+    pub mode: ReceiptMode,
+    /// Receipt tokens per burned token
+    pub ratio: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct BurnReceiptResponse {
+    pub receipt: Option<BurnReceiptInfo>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DividendResponse {
+    pub asset: DividendAsset,
+    pub amount: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct PendingDividendsResponse {
+    pub dividends: Vec<DividendResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct BuybackResponse {
+    pub enabled: bool,
+    pub collected: Uint128,
+    pub total_burned: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SpendingReportResponse {
+    pub address: String,
+    pub epoch: u64,
+    /// Block time in seconds the epoch starts at, inclusive
+    pub start: u64,
+    /// Block time in seconds the epoch ends at, exclusive
+    pub end: u64,
+    pub sent: Uint128,
+    pub fees_paid: Uint128,
+    pub burned: Uint128,
+    pub rewards: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct TimelockResponse {
+    pub timelock: Option<Timelock>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TimelockedTransferResponse {
+    pub id: u64,
+    pub sender: String,
+    pub recipient: String,
+    pub amount: Uint128,
+    pub executable_at: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct TimelockedTransfersResponse {
+    pub transfers: Vec<TimelockedTransferResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct DualControlResponse {
+    pub confirmer: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct AdminProposalResponse {
+    pub id: u64,
+    pub proposer: String,
+    pub action: ExecuteMsg,
+    pub proposed_at: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct AdminProposalsResponse {
+    pub proposals: Vec<AdminProposalResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ForeignBalance {
+    pub token: String,
+    pub amount: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct ForeignBalancesResponse {
+    pub balances: Vec<ForeignBalance>,
+}
+
+/// Payload of the receive hook called by Send, in the cw20 format
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ReceiveMsg {
+    pub sender: String,
+    pub amount: Uint128,
+    pub msg: Binary,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct FailedSendResponse {
+    pub id: u64,
+    pub sender: String,
+    pub contract: String,
+    pub amount: Uint128,
+    pub msg: Binary,
+    pub error: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct FailedSendsResponse {
+    pub sends: Vec<FailedSendResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct HolderCountResponse {
+    pub count: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct HolderResponse {
+    pub address: String,
+    pub balance: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct TopHoldersResponse {
+    pub holders: Vec<HolderResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct HoldersAboveResponse {
+    pub holders: Vec<HolderResponse>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct ClawbackAdminResponse {
+    pub admin: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RenouncedResponse {
+    pub roles: Vec<Role>,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct StuckBalanceResponse {
+    pub amount: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct CapResponse {
+    pub cap: Option<Uint128>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MinterResponse {
+    pub minter: String,
+    /// Maximum total supply, see `QueryMsg::Cap`
+    pub cap: Option<Uint128>,
+    pub mint_deadline: Option<Expiration>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MarketingInfoResponse {
+    pub project: Option<String>,
+    pub description: Option<String>,
+    pub logo: Option<LogoInfo>,
+    /// The marketing admin
+    pub marketing: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct DownloadLogoResponse {
+    pub mime_type: String,
+    pub data: Binary,
+}
+
+#[derive(Serialize, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct TransferBurnResponse {
+    pub rate_bps: u16,
+    pub exempt: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportStateResponse {
+    Balances {
+        balances: Vec<ExportedBalance>,
+        /// Cursor of the next page, unset on the last one
+        next: Option<Binary>,
+    },
+    Allowances {
+        allowances: Vec<ExportedAllowance>,
+        /// Cursor of the next page, unset on the last one
+        next: Option<Binary>,
+    },
+    Config {
+        config: ExportedConfig,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ExportedBalance {
+    pub address: String,
+    pub balance: Uint128,
+    /// Stored shares the balance is derived from, see `RebaseState`
+    pub shares: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ExportedAllowance {
+    pub owner: String,
+    pub spender: String,
+    pub allowance: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ExportedConfig {
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+    pub owner: Option<String>,
+    pub total_supply: Uint128,
+    pub total_shares: Uint128,
+    pub max_supply: Option<Uint128>,
+    pub holder_count: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ComplianceContractResponse {
+    pub address: Option<String>,
+}
+
+/// Query sent to the compliance contract before every transfer
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ComplianceQueryMsg {
+    CheckTransfer {
+        from: String,
+        to: String,
+        amount: Uint128,
+    },
+}
+
+/// Answer of the compliance contract to `ComplianceQueryMsg::CheckTransfer`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ComplianceResponse {
+    pub allowed: bool,
+    /// Why the transfer is not allowed
+    pub reason: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VerifySupplyResponse {
+    /// Sum of the balances counted so far
+    pub running_total: Uint128,
+    /// Sum of the shares counted so far, see `RebaseState`
+    pub running_shares: Uint128,
+    pub total_supply: Uint128,
+    pub total_shares: Uint128,
+    /// Cursor of the next page, carrying the running total. Unset on the last page.
+    pub next: Option<Binary>,
+    /// Whether the counted shares match the total shares, set on the last page
+    pub verified: Option<bool>,
 }
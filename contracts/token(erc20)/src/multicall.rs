@@ -0,0 +1,131 @@
+use cosmwasm_std::{DepsMut, Env, MessageInfo, Response};
+
+use crate::contract::execute;
+use crate::error::ContractError;
+use crate::msg::ExecuteMsg;
+
+/// Execute `msgs` in order on behalf of the signer. The first error fails the
+/// whole batch, so the messages executed before it are reverted too.
+///
+/// Each message goes through `execute` again, dual control included. Funds are
+/// rejected as it would be ambiguous which message receives them.
+pub fn try_multicall(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msgs: Vec<ExecuteMsg>,
+) -> Result<Response, ContractError> {
+    if !info.funds.is_empty() {
+        return Err(ContractError::MulticallFunds {});
+    }
+    let count = msgs.len();
+    let mut res = Response::new();
+    for msg in msgs {
+        if let ExecuteMsg::Multicall { .. } = msg {
+            return Err(ContractError::NestedMulticall {});
+        }
+        let inner = execute(deps.branch(), env.clone(), info.clone(), msg)?;
+        res = res
+            .add_submessages(inner.messages)
+            .add_events(inner.events)
+            .add_attributes(inner.attributes);
+    }
+
+    Ok(res
+        .add_attribute("action", "multicall")
+        .add_attribute("count", count.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{instantiate, read_balance};
+    use crate::msg::{InitialBalance, InstantiateMsg};
+    use crate::testing::default_instantiate_msg;
+    use cosmwasm_std::testing::{mock_dependencies_with_balance, mock_env, mock_info};
+    use cosmwasm_std::{coins, Addr, Uint128};
+
+    fn setup(deps: DepsMut) {
+        let instantiate_msg = InstantiateMsg {
+            initial_balances: vec![InitialBalance {
+                address: "addr0000".to_string(),
+                amount: Uint128::from(1000u128),
+            }],
+            ..default_instantiate_msg()
+        };
+        instantiate(deps, mock_env(), mock_info("creator", &[]), instantiate_msg).unwrap();
+    }
+
+    fn transfer(recipient: &str, amount: u128) -> ExecuteMsg {
+        ExecuteMsg::Transfer {
+            recipient: recipient.to_string(),
+            amount: Uint128::from(amount),
+            memo: None,
+            on_behalf_of: None,
+        }
+    }
+
+    #[test]
+    fn executes_messages_in_order() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        let msg = ExecuteMsg::Multicall {
+            msgs: vec![
+                ExecuteMsg::Approve {
+                    spender: "spender".to_string(),
+                    amount: Uint128::from(50u128),
+                    expected_current: None,
+                    on_behalf_of: None,
+                },
+                transfer("addr1111", 300),
+                transfer("addr2222", 200),
+            ],
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+        assert_eq!(res.events.len(), 3);
+        let balance = |address: &str| {
+            read_balance(&deps.storage, &deps.api, &Addr::unchecked(address)).unwrap()
+        };
+        assert_eq!(balance("addr0000"), 500);
+        assert_eq!(balance("addr1111"), 300);
+        assert_eq!(balance("addr2222"), 200);
+    }
+
+    #[test]
+    fn fails_with_first_error() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        let msg = ExecuteMsg::Multicall {
+            msgs: vec![transfer("addr1111", 600), transfer("addr2222", 600)],
+        };
+        match execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap_err() {
+            ContractError::InsufficientFunds { balance, required } => {
+                assert_eq!(balance, 400);
+                assert_eq!(required, 600);
+            }
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn rejects_nesting_and_funds() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        let msg = ExecuteMsg::Multicall {
+            msgs: vec![ExecuteMsg::Multicall { msgs: vec![] }],
+        };
+        match execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap_err() {
+            ContractError::NestedMulticall {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        let msg = ExecuteMsg::Multicall {
+            msgs: vec![ExecuteMsg::Deposit {}],
+        };
+        let info = mock_info("addr0000", &coins(100, "uluna"));
+        match execute(deps.as_mut(), mock_env(), info, msg).unwrap_err() {
+            ContractError::MulticallFunds {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+}
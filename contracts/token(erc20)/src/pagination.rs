@@ -0,0 +1,190 @@
+use cosmwasm_std::{Addr, Binary, Order};
+use std::convert::TryInto;
+
+use crate::error::ContractError;
+
+// List queries page through a prefixed store in key order. A cursor is the
+// storage key of the last entry of the previous page, typed for the queries
+// that expose it (ids, addresses) and opaque for the others.
+
+/// Page sizes of a list query
+pub struct Limits {
+    pub default: u32,
+    pub max: u32,
+}
+
+pub const LIST_LIMITS: Limits = Limits {
+    default: 10,
+    max: 30,
+};
+
+// Export entries are small and read by indexers, so their pages are larger
+pub const EXPORT_LIMITS: Limits = Limits {
+    default: 30,
+    max: 100,
+};
+
+impl Limits {
+    /// The requested page size, `default` when unset and at most `max`
+    pub fn clamp(&self, limit: Option<u32>) -> usize {
+        limit.unwrap_or(self.default).min(self.max) as usize
+    }
+}
+
+/// Values a list is cursored by, encoded as the storage key they are read from
+pub trait Cursor: Sized {
+    fn to_key(&self) -> Vec<u8>;
+    fn from_key(key: &[u8]) -> Result<Self, ContractError>;
+}
+
+// Ids are stored big endian so that they sort numerically
+impl Cursor for u64 {
+    fn to_key(&self) -> Vec<u8> {
+        self.to_be_bytes().to_vec()
+    }
+
+    fn from_key(key: &[u8]) -> Result<Self, ContractError> {
+        match key.try_into() {
+            Ok(bytes) => Ok(u64::from_be_bytes(bytes)),
+            Err(_) => Err(ContractError::CorruptedDataFound {}),
+        }
+    }
+}
+
+impl Cursor for Addr {
+    fn to_key(&self) -> Vec<u8> {
+        self.as_str().as_bytes().to_vec()
+    }
+
+    fn from_key(key: &[u8]) -> Result<Self, ContractError> {
+        match String::from_utf8(key.to_vec()) {
+            Ok(address) => Ok(Addr::unchecked(address)),
+            Err(_) => Err(ContractError::CorruptedDataFound {}),
+        }
+    }
+}
+
+// Opaque cursors are the raw key
+impl Cursor for Binary {
+    fn to_key(&self) -> Vec<u8> {
+        self.to_vec()
+    }
+
+    fn from_key(key: &[u8]) -> Result<Self, ContractError> {
+        Ok(Binary::from(key))
+    }
+}
+
+/// The range bounds and size of one page
+pub struct Page {
+    start: Option<Vec<u8>>,
+    end: Option<Vec<u8>>,
+    pub limit: usize,
+    pub order: Order,
+}
+
+impl Page {
+    /// The page after `start_after` in `order`
+    pub fn new<C: Cursor>(
+        start_after: Option<C>,
+        limit: Option<u32>,
+        limits: &Limits,
+        order: Order,
+    ) -> Page {
+        Page::after_key(
+            start_after.map(|cursor| cursor.to_key()),
+            limit,
+            limits,
+            order,
+        )
+    }
+
+    /// The page after the storage key `start_after` in `order`
+    pub fn after_key(
+        start_after: Option<Vec<u8>>,
+        limit: Option<u32>,
+        limits: &Limits,
+        order: Order,
+    ) -> Page {
+        let (start, end) = match order {
+            // The smallest key after the cursor
+            Order::Ascending => (start_after.map(|key| [key.as_slice(), &[0]].concat()), None),
+            // Range ends are exclusive, so the cursor itself bounds the page
+            Order::Descending => (None, start_after),
+        };
+        Page {
+            start,
+            end,
+            limit: limits.clamp(limit),
+            order,
+        }
+    }
+
+    pub fn start(&self) -> Option<&[u8]> {
+        self.start.as_deref()
+    }
+
+    pub fn end(&self) -> Option<&[u8]> {
+        self.end.as_deref()
+    }
+}
+
+/// Cursor of the page after one ending at `last`. `None` once a page comes
+/// back short, as the list ended.
+pub fn next_cursor(last: Option<&Vec<u8>>, len: usize, limit: usize) -> Option<Binary> {
+    match last {
+        Some(key) if len == limit => Some(Binary::from(key.as_slice())),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::MockStorage;
+    use cosmwasm_std::Storage;
+
+    fn keys(store: &MockStorage, page: &Page) -> Vec<u64> {
+        store
+            .range(page.start(), page.end(), page.order)
+            .take(page.limit)
+            .map(|(key, _)| u64::from_key(&key).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn pages_continue_after_the_cursor_in_both_orders() {
+        let mut store = MockStorage::new();
+        for id in 1..=5u64 {
+            store.set(&id.to_key(), &[1]);
+        }
+
+        let page = Page::new::<u64>(None, Some(2), &LIST_LIMITS, Order::Ascending);
+        assert_eq!(keys(&store, &page), vec![1, 2]);
+        let page = Page::new(Some(2u64), Some(2), &LIST_LIMITS, Order::Ascending);
+        assert_eq!(keys(&store, &page), vec![3, 4]);
+
+        let page = Page::new::<u64>(None, Some(2), &LIST_LIMITS, Order::Descending);
+        assert_eq!(keys(&store, &page), vec![5, 4]);
+        let page = Page::new(Some(4u64), None, &LIST_LIMITS, Order::Descending);
+        assert_eq!(keys(&store, &page), vec![3, 2, 1]);
+    }
+
+    #[test]
+    fn limits_are_clamped() {
+        assert_eq!(LIST_LIMITS.clamp(None), 10);
+        assert_eq!(LIST_LIMITS.clamp(Some(1000)), 30);
+        assert_eq!(EXPORT_LIMITS.clamp(Some(50)), 50);
+    }
+
+    #[test]
+    fn cursors_round_trip_through_keys() {
+        let addr = Addr::unchecked("addr0000");
+        assert_eq!(Addr::from_key(&addr.to_key()).unwrap(), addr);
+        assert_eq!(u64::from_key(&7u64.to_key()).unwrap(), 7);
+        assert!(matches!(
+            u64::from_key(b"short"),
+            Err(ContractError::CorruptedDataFound {})
+        ));
+    }
+}
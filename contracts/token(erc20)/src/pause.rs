@@ -0,0 +1,384 @@
+use cosmwasm_std::{from_slice, to_vec, DepsMut, Env, MessageInfo, Response, Storage, Uint128};
+use cosmwasm_storage::{PrefixedStorage, ReadonlyPrefixedStorage};
+
+use crate::contract::{assert_owner, checked_add, checked_mul, PREFIX_CONFIG};
+use crate::error::ContractError;
+use crate::state::PauseState;
+use crate::votes;
+
+pub const KEY_PAUSE: &[u8] = b"pause";
+pub const PREFIX_UNPAUSE_VOTES: &[u8] = b"unpause_votes";
+
+pub fn read_pause_state(store: &dyn Storage) -> Result<PauseState, ContractError> {
+    let config_store = ReadonlyPrefixedStorage::new(store, PREFIX_CONFIG);
+    match config_store.get(KEY_PAUSE) {
+        Some(data) => Ok(from_slice(&data)?),
+        None => Ok(PauseState::default()),
+    }
+}
+
+pub fn write_pause_state(store: &mut dyn Storage, state: &PauseState) -> Result<(), ContractError> {
+    let mut config_store = PrefixedStorage::new(store, PREFIX_CONFIG);
+    config_store.set(KEY_PAUSE, &to_vec(state)?);
+    Ok(())
+}
+
+// Errors with Paused while token movements are halted
+pub fn assert_not_paused(store: &dyn Storage) -> Result<(), ContractError> {
+    if read_pause_state(store)?.paused {
+        return Err(ContractError::Paused {});
+    }
+    Ok(())
+}
+
+// Halts token movements, without checking who asked for it
+pub fn pause(store: &mut dyn Storage, env: &Env) -> Result<(), ContractError> {
+    let mut state = read_pause_state(store)?;
+    if state.paused {
+        return Err(ContractError::Paused {});
+    }
+    state.paused = true;
+    state.paused_at = env.block.time.seconds();
+    state.paused_height = env.block.height;
+    state.pause_id = state
+        .pause_id
+        .checked_add(1)
+        .ok_or(ContractError::Overflow {})?;
+    state.unpause_votes = Uint128::zero();
+    write_pause_state(store, &state)
+}
+
+// Resumes token movements, without checking who asked for it
+pub fn unpause(store: &mut dyn Storage) -> Result<(), ContractError> {
+    let mut state = read_pause_state(store)?;
+    if !state.paused {
+        return Err(ContractError::NotPaused {});
+    }
+    state.paused = false;
+    write_pause_state(store, &state)
+}
+
+/// Halt all token movements. Owner only.
+///
+/// Voting power and total supply at the current height become the snapshot
+/// for a holder vote to unpause, see `try_vote_unpause`. After holders vote a
+/// pause away the owner cannot pause again for another `max_pause_duration`.
+pub fn try_pause(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    assert_owner(deps.storage, &info.sender)?;
+    let state = read_pause_state(deps.storage)?;
+    if let (Some(vote_unpaused_at), Some(max_pause_duration)) =
+        (state.vote_unpaused_at, state.max_pause_duration)
+    {
+        let pausable_at = vote_unpaused_at
+            .checked_add(max_pause_duration)
+            .ok_or(ContractError::Overflow {})?;
+        if env.block.time.seconds() < pausable_at {
+            return Err(ContractError::PauseCooldown { pausable_at });
+        }
+    }
+    pause(deps.storage, &env)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "pause")
+        .add_attribute("owner", info.sender))
+}
+
+/// Resume token movements. Owner only.
+pub fn try_unpause(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    assert_owner(deps.storage, &info.sender)?;
+    unpause(deps.storage)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "unpause")
+        .add_attribute("owner", info.sender))
+}
+
+/// Vote to unpause without the owner
+///
+/// Opens once the contract has been paused for longer than `max_pause_duration`.
+/// Each vote weighs the voter's voting power at the pause height. The contract
+/// unpauses as soon as the votes exceed half the total supply at that height.
+pub fn try_vote_unpause(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let mut state = read_pause_state(deps.storage)?;
+    if !state.paused {
+        return Err(ContractError::NotPaused {});
+    }
+    let opens_at = match state.max_pause_duration {
+        Some(max_pause_duration) => state
+            .paused_at
+            .checked_add(max_pause_duration)
+            .ok_or(ContractError::Overflow {})?,
+        None => return Err(ContractError::UnpauseVotingDisabled {}),
+    };
+    if env.block.time.seconds() <= opens_at {
+        return Err(ContractError::UnpauseVotingNotOpen { opens_at });
+    }
+
+    // Votes are scoped to a single pause so they cannot carry over to the next one
+    let pause_id = state.pause_id.to_be_bytes();
+    let namespace: [&[u8]; 2] = [PREFIX_UNPAUSE_VOTES, &pause_id[..]];
+    let voted = ReadonlyPrefixedStorage::multilevel(deps.storage, &namespace)
+        .get(info.sender.as_str().as_bytes())
+        .is_some();
+    if voted {
+        return Err(ContractError::AlreadyVoted {});
+    }
+    let weight = votes::read_voting_power(deps.storage, &info.sender, Some(state.paused_height))?;
+    if weight == 0 {
+        return Err(ContractError::NoVotingPower {});
+    }
+    let mut votes_store = PrefixedStorage::multilevel(deps.storage, &namespace);
+    votes_store.set(info.sender.as_str().as_bytes(), &[1]);
+    state.unpause_votes = Uint128::from(checked_add(state.unpause_votes.u128(), weight)?);
+    let total_supply = votes::read_supply_at(deps.storage, state.paused_height)?;
+    let unpaused = checked_mul(state.unpause_votes.u128(), 2)? > total_supply;
+    if unpaused {
+        state.paused = false;
+        state.vote_unpaused_at = Some(env.block.time.seconds());
+    }
+    write_pause_state(deps.storage, &state)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "vote_unpause")
+        .add_attribute("voter", info.sender)
+        .add_attribute("weight", weight.to_string())
+        .add_attribute("unpause_votes", state.unpause_votes.to_string())
+        .add_attribute("unpaused", unpaused.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate, query};
+    use crate::msg::{ExecuteMsg, InitialBalance, InstantiateMsg, PauseStateResponse, QueryMsg};
+    use crate::testing::default_instantiate_msg;
+    use cosmwasm_std::testing::{mock_dependencies_with_balance, mock_env, mock_info};
+    use cosmwasm_std::{Deps, Timestamp};
+
+    fn env_at(height: u64, time: u64) -> Env {
+        let mut env = mock_env();
+        env.block.height = height;
+        env.block.time = Timestamp::from_seconds(time);
+        env
+    }
+
+    fn setup(deps: DepsMut, max_pause_duration: Option<u64>) {
+        let instantiate_msg = InstantiateMsg {
+            initial_balances: vec![
+                InitialBalance {
+                    address: "addr0000".to_string(),
+                    amount: Uint128::from(40u128),
+                },
+                InitialBalance {
+                    address: "addr1111".to_string(),
+                    amount: Uint128::from(30u128),
+                },
+                InitialBalance {
+                    address: "addr2222".to_string(),
+                    amount: Uint128::from(30u128),
+                },
+            ],
+            max_pause_duration,
+            ..default_instantiate_msg()
+        };
+        instantiate(
+            deps,
+            env_at(1, 100),
+            mock_info("creator", &[]),
+            instantiate_msg,
+        )
+        .unwrap();
+    }
+
+    fn run(deps: DepsMut, sender: &str, height: u64, time: u64, msg: ExecuteMsg) {
+        execute(deps, env_at(height, time), mock_info(sender, &[]), msg).unwrap();
+    }
+
+    fn self_delegate(deps: DepsMut, address: &str) {
+        let msg = ExecuteMsg::Delegate {
+            delegatee: address.to_string(),
+        };
+        run(deps, address, 2, 110, msg);
+    }
+
+    fn pause_state(deps: Deps) -> PauseStateResponse {
+        from_slice(&query(deps, env_at(10, 200), QueryMsg::PauseState {}).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn pause_blocks_transfers() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut(), None);
+        run(deps.as_mut(), "creator", 5, 150, ExecuteMsg::Pause {});
+        assert!(pause_state(deps.as_ref()).paused);
+
+        let transfer_msg = ExecuteMsg::Transfer {
+            recipient: "addr1111".to_string(),
+            amount: Uint128::from(1u128),
+            memo: None,
+            on_behalf_of: None,
+        };
+        let err = execute(
+            deps.as_mut(),
+            env_at(6, 160),
+            mock_info("addr0000", &[]),
+            transfer_msg.clone(),
+        )
+        .unwrap_err();
+        match err {
+            ContractError::Paused {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        run(deps.as_mut(), "creator", 7, 170, ExecuteMsg::Unpause {});
+        run(deps.as_mut(), "addr0000", 8, 180, transfer_msg);
+    }
+
+    #[test]
+    fn only_owner_can_pause() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut(), None);
+        let err = execute(
+            deps.as_mut(),
+            env_at(5, 150),
+            mock_info("addr0000", &[]),
+            ExecuteMsg::Pause {},
+        )
+        .unwrap_err();
+        match err {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn holders_can_unpause_after_grace_period() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut(), Some(1000));
+        self_delegate(deps.as_mut(), "addr0000");
+        self_delegate(deps.as_mut(), "addr1111");
+        run(deps.as_mut(), "creator", 5, 200, ExecuteMsg::Pause {});
+
+        // voting opens only after the grace period
+        let err = execute(
+            deps.as_mut(),
+            env_at(6, 1200),
+            mock_info("addr0000", &[]),
+            ExecuteMsg::VoteUnpause {},
+        )
+        .unwrap_err();
+        match err {
+            ContractError::UnpauseVotingNotOpen { opens_at } => assert_eq!(opens_at, 1200),
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        // 40 of 100 is not a majority
+        run(
+            deps.as_mut(),
+            "addr0000",
+            7,
+            1201,
+            ExecuteMsg::VoteUnpause {},
+        );
+        let state = pause_state(deps.as_ref());
+        assert!(state.paused);
+        assert_eq!(state.unpause_votes, Uint128::from(40u128));
+
+        let err = execute(
+            deps.as_mut(),
+            env_at(8, 1202),
+            mock_info("addr0000", &[]),
+            ExecuteMsg::VoteUnpause {},
+        )
+        .unwrap_err();
+        match err {
+            ContractError::AlreadyVoted {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        // undelegated balances carry no weight
+        let err = execute(
+            deps.as_mut(),
+            env_at(8, 1202),
+            mock_info("addr2222", &[]),
+            ExecuteMsg::VoteUnpause {},
+        )
+        .unwrap_err();
+        match err {
+            ContractError::NoVotingPower {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        // 70 of 100 unpauses
+        run(
+            deps.as_mut(),
+            "addr1111",
+            9,
+            1203,
+            ExecuteMsg::VoteUnpause {},
+        );
+        assert!(!pause_state(deps.as_ref()).paused);
+    }
+
+    #[test]
+    fn owner_cannot_pause_right_after_holders_unpause() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut(), Some(1000));
+        self_delegate(deps.as_mut(), "addr0000");
+        self_delegate(deps.as_mut(), "addr1111");
+        run(deps.as_mut(), "creator", 5, 200, ExecuteMsg::Pause {});
+        run(
+            deps.as_mut(),
+            "addr0000",
+            6,
+            1201,
+            ExecuteMsg::VoteUnpause {},
+        );
+        run(
+            deps.as_mut(),
+            "addr1111",
+            7,
+            1202,
+            ExecuteMsg::VoteUnpause {},
+        );
+        assert!(!pause_state(deps.as_ref()).paused);
+
+        let err = execute(
+            deps.as_mut(),
+            env_at(8, 2201),
+            mock_info("creator", &[]),
+            ExecuteMsg::Pause {},
+        )
+        .unwrap_err();
+        match err {
+            ContractError::PauseCooldown { pausable_at } => assert_eq!(pausable_at, 2202),
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        run(deps.as_mut(), "creator", 9, 2202, ExecuteMsg::Pause {});
+        assert!(pause_state(deps.as_ref()).paused);
+    }
+
+    #[test]
+    fn voting_disabled_without_max_duration() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut(), None);
+        self_delegate(deps.as_mut(), "addr0000");
+        run(deps.as_mut(), "creator", 5, 200, ExecuteMsg::Pause {});
+        let err = execute(
+            deps.as_mut(),
+            env_at(6, 1_000_000),
+            mock_info("addr0000", &[]),
+            ExecuteMsg::VoteUnpause {},
+        )
+        .unwrap_err();
+        match err {
+            ContractError::UnpauseVotingDisabled {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+}
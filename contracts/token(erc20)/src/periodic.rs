@@ -0,0 +1,322 @@
+use cosmwasm_std::{
+    from_slice, to_vec, Addr, DepsMut, Env, MessageInfo, Order, Response, Storage, Uint128,
+};
+use cosmwasm_storage::{PrefixedStorage, ReadonlyPrefixedStorage};
+
+use crate::contract::addr_from_key;
+use crate::error::ContractError;
+use crate::msg::PeriodicAllowanceResponse;
+use crate::state::PeriodicAllowance;
+
+pub const PREFIX_PERIODIC_ALLOWANCES: &[u8] = b"periodic_allowances";
+
+// A periodic allowance grants a spender a budget that renews every period, e.g.
+// for subscriptions. Periods are aligned to the grant time. While one exists for
+// an owner and spender it takes the place of their plain allowance in TransferFrom
+// and BurnFrom, so a zero Approve or a DecreaseAllowance revokes it as well.
+
+pub fn read_periodic_allowance(
+    store: &dyn Storage,
+    owner: &Addr,
+    spender: &Addr,
+) -> Result<Option<PeriodicAllowance>, ContractError> {
+    let allowances_store = ReadonlyPrefixedStorage::multilevel(
+        store,
+        &[PREFIX_PERIODIC_ALLOWANCES, owner.as_str().as_bytes()],
+    );
+    match allowances_store.get(spender.as_str().as_bytes()) {
+        Some(data) => Ok(Some(from_slice(&data)?)),
+        None => Ok(None),
+    }
+}
+
+fn write_periodic_allowance(
+    store: &mut dyn Storage,
+    owner: &Addr,
+    spender: &Addr,
+    allowance: Option<&PeriodicAllowance>,
+) -> Result<(), ContractError> {
+    let mut allowances_store = PrefixedStorage::multilevel(
+        store,
+        &[PREFIX_PERIODIC_ALLOWANCES, owner.as_str().as_bytes()],
+    );
+    match allowance {
+        Some(allowance) => allowances_store.set(spender.as_str().as_bytes(), &to_vec(allowance)?),
+        None => allowances_store.remove(spender.as_str().as_bytes()),
+    }
+    Ok(())
+}
+
+/// Let `spender` move up to `amount_per_period` of the signer's tokens every
+/// `period_seconds`, starting now. Zero `amount_per_period` revokes the grant.
+pub fn try_approve_periodic(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    spender: String,
+    amount_per_period: Uint128,
+    period_seconds: u64,
+) -> Result<Response, ContractError> {
+    let spender_address = deps.api.addr_validate(&spender)?;
+    if amount_per_period.is_zero() {
+        write_periodic_allowance(deps.storage, &info.sender, &spender_address, None)?;
+    } else {
+        if period_seconds == 0 {
+            return Err(ContractError::InvalidPeriod {});
+        }
+        let allowance = PeriodicAllowance {
+            amount_per_period,
+            period_seconds,
+            period_start: env.block.time.seconds(),
+            spent: Uint128::zero(),
+        };
+        write_periodic_allowance(
+            deps.storage,
+            &info.sender,
+            &spender_address,
+            Some(&allowance),
+        )?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "approve_periodic")
+        .add_attribute("owner", info.sender)
+        .add_attribute("spender", spender)
+        .add_attribute("amount_per_period", amount_per_period.to_string())
+        .add_attribute("period_seconds", period_seconds.to_string()))
+}
+
+// Moves the allowance into the current period, resetting the spent amount if a
+// new period began
+fn current_period(allowance: PeriodicAllowance, env: &Env) -> PeriodicAllowance {
+    let now = env.block.time.seconds();
+    let elapsed_periods = (now - allowance.period_start) / allowance.period_seconds;
+    if elapsed_periods == 0 {
+        return allowance;
+    }
+    PeriodicAllowance {
+        period_start: allowance.period_start + elapsed_periods * allowance.period_seconds,
+        spent: Uint128::zero(),
+        ..allowance
+    }
+}
+
+/// What is left of the current period's budget of `spender`, or `None` if there
+/// is no periodic allowance for the pair
+pub fn remaining(
+    store: &dyn Storage,
+    env: &Env,
+    owner: &Addr,
+    spender: &Addr,
+) -> Result<Option<u128>, ContractError> {
+    Ok(
+        read_periodic_allowance(store, owner, spender)?.map(|allowance| {
+            let allowance = current_period(allowance, env);
+            allowance.amount_per_period.u128() - allowance.spent.u128()
+        }),
+    )
+}
+
+/// Takes `amount` out of the current period's budget of `spender`. Returns what
+/// is left of it, or `None` if there is no periodic allowance for the pair.
+pub fn spend(
+    store: &mut dyn Storage,
+    env: &Env,
+    owner: &Addr,
+    spender: &Addr,
+    amount: u128,
+) -> Result<Option<u128>, ContractError> {
+    let allowance = match read_periodic_allowance(store, owner, spender)? {
+        Some(allowance) => current_period(allowance, env),
+        None => return Ok(None),
+    };
+    let remaining = allowance.amount_per_period.u128() - allowance.spent.u128();
+    if remaining < amount {
+        return Err(ContractError::InsufficientAllowance {
+            allowance: remaining,
+            required: amount,
+        });
+    }
+    let allowance = PeriodicAllowance {
+        spent: allowance.spent + Uint128::from(amount),
+        ..allowance
+    };
+    write_periodic_allowance(store, owner, spender, Some(&allowance))?;
+    Ok(Some(remaining - amount))
+}
+
+/// Removes the periodic allowance of `spender` over the tokens of `owner`, if any
+pub fn revoke(store: &mut dyn Storage, owner: &Addr, spender: &Addr) -> Result<(), ContractError> {
+    write_periodic_allowance(store, owner, spender, None)
+}
+
+/// Removes every periodic allowance granted by `owner` and returns their spenders
+pub fn revoke_all(store: &mut dyn Storage, owner: &Addr) -> Result<Vec<Addr>, ContractError> {
+    let namespace = [PREFIX_PERIODIC_ALLOWANCES, owner.as_str().as_bytes()];
+    let spenders = ReadonlyPrefixedStorage::multilevel(store, &namespace)
+        .range(None, None, Order::Ascending)
+        .map(|(key, _)| addr_from_key(key))
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut allowances_store = PrefixedStorage::multilevel(store, &namespace);
+    for spender in &spenders {
+        allowances_store.remove(spender.as_str().as_bytes());
+    }
+    Ok(spenders)
+}
+
+pub fn query_periodic_allowance(
+    store: &dyn Storage,
+    env: &Env,
+    owner: &Addr,
+    spender: &Addr,
+) -> Result<PeriodicAllowanceResponse, ContractError> {
+    Ok(match read_periodic_allowance(store, owner, spender)? {
+        Some(allowance) => {
+            let allowance = current_period(allowance, env);
+            PeriodicAllowanceResponse {
+                amount_per_period: allowance.amount_per_period,
+                period_seconds: allowance.period_seconds,
+                remaining: allowance.amount_per_period - allowance.spent,
+                resets_at: allowance.period_start + allowance.period_seconds,
+            }
+        }
+        None => PeriodicAllowanceResponse {
+            amount_per_period: Uint128::zero(),
+            period_seconds: 0,
+            remaining: Uint128::zero(),
+            resets_at: 0,
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate, query, read_balance};
+    use crate::msg::{ExecuteMsg, InitialBalance, InstantiateMsg, QueryMsg};
+    use crate::testing::{default_instantiate_msg, env_at};
+    use cosmwasm_std::testing::{mock_dependencies_with_balance, mock_info};
+    use cosmwasm_std::Deps;
+
+    fn setup(mut deps: DepsMut) {
+        let instantiate_msg = InstantiateMsg {
+            initial_balances: vec![InitialBalance {
+                address: "addr0000".to_string(),
+                amount: Uint128::from(1000u128),
+            }],
+            ..default_instantiate_msg()
+        };
+        instantiate(
+            deps.branch(),
+            env_at(1000),
+            mock_info("creator", &[]),
+            instantiate_msg,
+        )
+        .unwrap();
+        let msg = ExecuteMsg::ApprovePeriodic {
+            spender: "merchant".to_string(),
+            amount_per_period: Uint128::from(100u128),
+            period_seconds: 3600,
+        };
+        execute(deps, env_at(1000), mock_info("addr0000", &[]), msg).unwrap();
+    }
+
+    fn charge(deps: DepsMut, time: u64, amount: u128) -> Result<Response, ContractError> {
+        let msg = ExecuteMsg::TransferFrom {
+            owner: "addr0000".to_string(),
+            recipient: "merchant".to_string(),
+            amount: Uint128::from(amount),
+            memo: None,
+        };
+        execute(deps, env_at(time), mock_info("merchant", &[]), msg)
+    }
+
+    fn query_remaining(deps: Deps, time: u64) -> PeriodicAllowanceResponse {
+        let msg = QueryMsg::PeriodicAllowance {
+            owner: "addr0000".to_string(),
+            spender: "merchant".to_string(),
+        };
+        from_slice(&query(deps, env_at(time), msg).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn budget_renews_every_period() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+
+        charge(deps.as_mut(), 1000, 60).unwrap();
+        charge(deps.as_mut(), 2000, 40).unwrap();
+        let err = charge(deps.as_mut(), 4599, 1).unwrap_err();
+        match err {
+            ContractError::InsufficientAllowance {
+                allowance,
+                required,
+            } => {
+                assert_eq!(allowance, 0);
+                assert_eq!(required, 1);
+            }
+            e => panic!("unexpected error: {:?}", e),
+        }
+        assert_eq!(query_remaining(deps.as_ref(), 4599).resets_at, 4600);
+
+        // a new period began
+        assert_eq!(
+            query_remaining(deps.as_ref(), 4600).remaining,
+            Uint128::from(100u128)
+        );
+        charge(deps.as_mut(), 4600, 100).unwrap();
+        assert_eq!(
+            read_balance(&deps.storage, &deps.api, &Addr::unchecked("merchant")).unwrap(),
+            200
+        );
+    }
+
+    #[test]
+    fn zero_amount_revokes() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        let msg = ExecuteMsg::ApprovePeriodic {
+            spender: "merchant".to_string(),
+            amount_per_period: Uint128::zero(),
+            period_seconds: 0,
+        };
+        execute(deps.as_mut(), env_at(1000), mock_info("addr0000", &[]), msg).unwrap();
+        // back to the plain allowance, which is zero
+        let err = charge(deps.as_mut(), 1000, 1).unwrap_err();
+        match err {
+            ContractError::InsufficientAllowance { .. } => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn zero_approve_and_decrease_revoke() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        let msg = ExecuteMsg::Approve {
+            spender: "merchant".to_string(),
+            amount: Uint128::zero(),
+            expected_current: None,
+            on_behalf_of: None,
+        };
+        execute(deps.as_mut(), env_at(1000), mock_info("addr0000", &[]), msg).unwrap();
+        assert_eq!(
+            query_remaining(deps.as_ref(), 1000).amount_per_period,
+            Uint128::zero()
+        );
+        assert!(charge(deps.as_mut(), 1000, 1).is_err());
+
+        setup(deps.as_mut());
+        let msg = ExecuteMsg::DecreaseAllowance {
+            spender: "merchant".to_string(),
+            amount: Uint128::from(1u128),
+            expires: None,
+        };
+        execute(deps.as_mut(), env_at(1000), mock_info("addr0000", &[]), msg).unwrap();
+        assert_eq!(
+            query_remaining(deps.as_ref(), 1000).amount_per_period,
+            Uint128::zero()
+        );
+        assert!(charge(deps.as_mut(), 1000, 1).is_err());
+    }
+}
@@ -0,0 +1,275 @@
+use cosmwasm_std::{Addr, Api, Binary, DepsMut, Env, Response, Storage, Uint128};
+use cosmwasm_storage::{PrefixedStorage, ReadonlyPrefixedStorage};
+use serde::Serialize;
+use std::convert::TryInto;
+
+use crate::contract::{allowance_data, write_allowance, write_allowance_expiration};
+use crate::error::ContractError;
+use crate::events;
+use crate::signing;
+
+pub const PREFIX_NONCES: &[u8] = b"nonces";
+pub const PERMIT_MESSAGE_TYPE: &str = "permit";
+
+/// The message an owner signs to authorize a permit, wrapped in the canonical
+/// signing payload of `signing::signing_bytes`. Fields in lexicographic order.
+#[derive(Serialize)]
+struct PermitMessage<'a> {
+    amount: Uint128,
+    deadline: u64,
+    nonce: u64,
+    owner: &'a str,
+    spender: &'a str,
+}
+
+/// Approve by signature
+///
+/// Sets the allowance of `spender` over the tokens of `owner` to `amount`, authorized
+/// by a secp256k1 signature of `owner` instead of a transaction signed by `owner`.
+/// Anyone (e.g. a relayer) can submit the permit.
+///
+/// @param deadline block time in seconds after which the permit is no longer valid
+/// @param nonce must equal the current nonce of `owner`, see `QueryMsg::Nonce`
+/// @param public_key compressed secp256k1 public key the `owner` address derives from
+/// @param signature 64 byte compact signature over the permit payload
+#[allow(clippy::too_many_arguments)]
+pub fn try_permit(
+    deps: DepsMut,
+    env: Env,
+    owner: String,
+    spender: String,
+    amount: Uint128,
+    deadline: u64,
+    nonce: u64,
+    public_key: Binary,
+    signature: Binary,
+) -> Result<Response, ContractError> {
+    if env.block.time.seconds() > deadline {
+        return Err(ContractError::PermitExpired {});
+    }
+    let owner_address = deps.api.addr_validate(owner.as_str())?;
+    let spender_address = deps.api.addr_validate(spender.as_str())?;
+
+    assert_nonce(deps.storage, deps.api, &owner_address, nonce)?;
+
+    let message = PermitMessage {
+        amount,
+        deadline,
+        nonce,
+        owner: owner_address.as_str(),
+        spender: spender_address.as_str(),
+    };
+    signing::verify_signature(
+        deps.api,
+        &env,
+        PERMIT_MESSAGE_TYPE,
+        &message,
+        &owner_address,
+        &public_key,
+        &signature,
+    )?;
+
+    let next_nonce = nonce.checked_add(1).ok_or(ContractError::Overflow {})?;
+    write_nonce(deps.storage, deps.api, &owner_address, next_nonce)?;
+    write_allowance(
+        deps.storage,
+        deps.api,
+        &env,
+        &owner_address,
+        &spender_address,
+        amount.u128(),
+    )?;
+    write_allowance_expiration(
+        deps.storage,
+        deps.api,
+        &owner_address,
+        &spender_address,
+        None,
+    )?;
+
+    Ok(Response::new()
+        .set_data(allowance_data(amount.u128())?)
+        .add_event(events::approve_event(
+            &owner_address,
+            &spender_address,
+            amount.u128(),
+        ))
+        .add_attribute("action", "permit")
+        .add_attribute("owner", owner)
+        .add_attribute("spender", spender)
+        .add_attribute("nonce", nonce.to_string()))
+}
+
+// Reads the next expected permit nonce of `owner`, starting at zero
+pub fn read_nonce(store: &dyn Storage, api: &dyn Api, owner: &Addr) -> Result<u64, ContractError> {
+    let nonces_store = ReadonlyPrefixedStorage::new(store, PREFIX_NONCES);
+    match nonces_store.get(api.addr_canonicalize(owner.as_str())?.as_slice()) {
+        Some(data) => match data[..].try_into() {
+            Ok(bytes) => Ok(u64::from_be_bytes(bytes)),
+            Err(_) => Err(ContractError::CorruptedDataFound {}),
+        },
+        None => Ok(0u64),
+    }
+}
+
+// Fails unless `nonce` is the next expected nonce of `owner`
+pub fn assert_nonce(
+    store: &dyn Storage,
+    api: &dyn Api,
+    owner: &Addr,
+    nonce: u64,
+) -> Result<(), ContractError> {
+    let expected_nonce = read_nonce(store, api, owner)?;
+    if nonce != expected_nonce {
+        return Err(ContractError::InvalidNonce {
+            expected: expected_nonce,
+            actual: nonce,
+        });
+    }
+    Ok(())
+}
+
+pub fn write_nonce(
+    store: &mut dyn Storage,
+    api: &dyn Api,
+    owner: &Addr,
+    nonce: u64,
+) -> Result<(), ContractError> {
+    let owner = api.addr_canonicalize(owner.as_str())?;
+    let mut nonces_store = PrefixedStorage::new(store, PREFIX_NONCES);
+    nonces_store.set(owner.as_slice(), &nonce.to_be_bytes());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate, query};
+    use crate::msg::{AllowanceResponse, ExecuteMsg, NonceResponse, QueryMsg};
+    use crate::testing::{default_instantiate_msg, env_at};
+    use cosmwasm_std::testing::{mock_dependencies_with_balance, mock_info};
+    use cosmwasm_std::{from_slice, Deps};
+
+    // Test key derived from the secret scalar 1234567
+    const OWNER: &str = "cosmos1c4aphwsj5vf588un9kxdmg23cm6hppacgps24a";
+    const OWNER_PUBKEY: &str = "028208f5abf04066bad1db9d46f8bcf5a6cc11d0558ab523e7bd3c0ec08bdb782f";
+    // Test key derived from the secret scalar 7654321
+    const OTHER_PUBKEY: &str = "03085d77a89c0fc7b307c0f75edc54a78e3cd7de9c1d5345ebb037fc3ecdf7fefd";
+    // Signatures of the payload for spender addr1111, amount 500, deadline 1000, nonce 0
+    const OWNER_SIGNATURE: &str = "053f73352d0ade141c01f1e7473a6c1af9a2a5a5a80bfc11b3f35fd821df717a40693c463aa67c99c07cb41bcad09846f4bd40e17dca90f6cd8a4d2be1d5c1c6";
+    const OTHER_SIGNATURE: &str = "018c1c5006b1d13d1dcafa3dba7acd6a644d964aecb40e8d09ed148fbfd595ee679dc7796b8372810ba9c1cf321f1bec5d89aeb0f525d7340430473160aa205f";
+
+    fn make_permit(amount: u128, public_key: &str, signature: &str) -> ExecuteMsg {
+        ExecuteMsg::Permit {
+            owner: OWNER.to_string(),
+            spender: "addr1111".to_string(),
+            amount: Uint128::from(amount),
+            deadline: 1000,
+            nonce: 0,
+            public_key: Binary::from(hex::decode(public_key).unwrap()),
+            signature: Binary::from(hex::decode(signature).unwrap()),
+        }
+    }
+
+    fn setup(deps: DepsMut) {
+        let instantiate_msg = default_instantiate_msg();
+        instantiate(
+            deps,
+            env_at(500),
+            mock_info("creator", &[]),
+            instantiate_msg,
+        )
+        .unwrap();
+    }
+
+    fn query_allowance(deps: Deps) -> Uint128 {
+        let query_msg = QueryMsg::Allowance {
+            owner: OWNER.to_string(),
+            spender: "addr1111".to_string(),
+        };
+        let res: AllowanceResponse =
+            from_slice(&query(deps, env_at(500), query_msg).unwrap()).unwrap();
+        res.allowance
+    }
+
+    fn query_nonce(deps: Deps) -> u64 {
+        let query_msg = QueryMsg::Nonce {
+            address: OWNER.to_string(),
+        };
+        let res: NonceResponse = from_slice(&query(deps, env_at(500), query_msg).unwrap()).unwrap();
+        res.nonce
+    }
+
+    #[test]
+    fn relayer_can_submit_permit() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        assert_eq!(query_nonce(deps.as_ref()), 0);
+
+        let msg = make_permit(500, OWNER_PUBKEY, OWNER_SIGNATURE);
+        execute(deps.as_mut(), env_at(550), mock_info("relayer", &[]), msg).unwrap();
+        assert_eq!(query_allowance(deps.as_ref()), Uint128::from(500u128));
+        assert_eq!(query_nonce(deps.as_ref()), 1);
+    }
+
+    #[test]
+    fn permit_cannot_be_replayed() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        let msg = make_permit(500, OWNER_PUBKEY, OWNER_SIGNATURE);
+        execute(
+            deps.as_mut(),
+            env_at(550),
+            mock_info("relayer", &[]),
+            msg.clone(),
+        )
+        .unwrap();
+        let err = execute(deps.as_mut(), env_at(550), mock_info("relayer", &[]), msg).unwrap_err();
+        match err {
+            ContractError::InvalidNonce { expected, actual } => {
+                assert_eq!(expected, 1);
+                assert_eq!(actual, 0);
+            }
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn fails_after_deadline() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        let msg = make_permit(500, OWNER_PUBKEY, OWNER_SIGNATURE);
+        let err = execute(deps.as_mut(), env_at(1001), mock_info("relayer", &[]), msg).unwrap_err();
+        match err {
+            ContractError::PermitExpired {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn fails_for_tampered_payload() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        let msg = make_permit(501, OWNER_PUBKEY, OWNER_SIGNATURE);
+        let err = execute(deps.as_mut(), env_at(550), mock_info("relayer", &[]), msg).unwrap_err();
+        match err {
+            ContractError::InvalidSignature {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        assert_eq!(query_allowance(deps.as_ref()), Uint128::zero());
+    }
+
+    #[test]
+    fn fails_for_key_of_other_account() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        // valid signature, but the key does not belong to the owner
+        let msg = make_permit(500, OTHER_PUBKEY, OTHER_SIGNATURE);
+        let err = execute(deps.as_mut(), env_at(550), mock_info("relayer", &[]), msg).unwrap_err();
+        match err {
+            ContractError::InvalidSignature {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        assert_eq!(query_nonce(deps.as_ref()), 0);
+    }
+}
@@ -0,0 +1,213 @@
+use cosmwasm_std::{from_slice, to_vec, Addr, DepsMut, Env, MessageInfo, Order, Response, Storage};
+use cosmwasm_storage::{PrefixedStorage, ReadonlyPrefixedStorage};
+
+use crate::contract::{assert_owner, bytes_to_u128, checked_add};
+use crate::error::ContractError;
+use crate::state::RateLimit;
+
+pub const PREFIX_RATE_LIMITS: &[u8] = b"rate_limits";
+pub const PREFIX_RATE_USAGE: &[u8] = b"rate_usage";
+
+pub fn read_rate_limit(
+    store: &dyn Storage,
+    owner: &Addr,
+) -> Result<Option<RateLimit>, ContractError> {
+    let limits_store = ReadonlyPrefixedStorage::new(store, PREFIX_RATE_LIMITS);
+    match limits_store.get(owner.as_str().as_bytes()) {
+        Some(data) => Ok(Some(from_slice(&data)?)),
+        None => Ok(None),
+    }
+}
+
+/// Throttle the outgoing transfers of `address`, or lift the throttle with `None`.
+/// Owner only.
+pub fn try_set_rate_limit(
+    deps: DepsMut,
+    info: MessageInfo,
+    address: String,
+    limit: Option<RateLimit>,
+) -> Result<Response, ContractError> {
+    assert_owner(deps.storage, &info.sender)?;
+    let address_key = deps.api.addr_validate(&address)?;
+    let mut limits_store = PrefixedStorage::new(deps.storage, PREFIX_RATE_LIMITS);
+    match &limit {
+        Some(limit) => limits_store.set(address_key.as_str().as_bytes(), &to_vec(limit)?),
+        None => limits_store.remove(address_key.as_str().as_bytes()),
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "set_rate_limit")
+        .add_attribute("address", address)
+        .add_attribute("limited", limit.is_some().to_string()))
+}
+
+/// Amount sent by `owner` within the sliding window of `window` seconds ending now
+pub fn read_usage(
+    store: &dyn Storage,
+    env: &Env,
+    owner: &Addr,
+    window: u64,
+) -> Result<u128, ContractError> {
+    let start = env.block.time.seconds().saturating_sub(window) + 1;
+    let usage_store =
+        ReadonlyPrefixedStorage::multilevel(store, &[PREFIX_RATE_USAGE, owner.as_str().as_bytes()]);
+    let usage = usage_store
+        .range(Some(&start.to_be_bytes()[..]), None, Order::Ascending)
+        .try_fold(0u128, |sum, (_, data)| {
+            checked_add(sum, bytes_to_u128(&data)?)
+        });
+    usage
+}
+
+/// Checks an outgoing transfer of `owner` against its rate limit and records it.
+/// Usage entries older than the window are pruned on the way.
+pub fn record_transfer(
+    store: &mut dyn Storage,
+    env: &Env,
+    owner: &Addr,
+    amount: u128,
+) -> Result<(), ContractError> {
+    let limit = match read_rate_limit(store, owner)? {
+        Some(limit) => limit,
+        None => return Ok(()),
+    };
+    let used = read_usage(store, env, owner, limit.window)?;
+    let available = limit.max_amount.u128().saturating_sub(used);
+    if amount > available {
+        return Err(ContractError::RateLimitExceeded {
+            available,
+            required: amount,
+        });
+    }
+
+    // Usage is keyed by big endian block time, so expired entries form a prefix
+    let now = env.block.time.seconds();
+    let start = now.saturating_sub(limit.window) + 1;
+    let mut usage_store =
+        PrefixedStorage::multilevel(store, &[PREFIX_RATE_USAGE, owner.as_str().as_bytes()]);
+    let expired: Vec<Vec<u8>> = usage_store
+        .range(None, Some(&start.to_be_bytes()[..]), Order::Ascending)
+        .map(|(key, _)| key)
+        .collect();
+    for key in expired {
+        usage_store.remove(&key);
+    }
+    let current = match usage_store.get(&now.to_be_bytes()) {
+        Some(data) => bytes_to_u128(&data)?,
+        None => 0u128,
+    };
+    usage_store.set(
+        &now.to_be_bytes(),
+        &checked_add(current, amount)?.to_be_bytes(),
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate, query};
+    use crate::msg::{ExecuteMsg, InitialBalance, InstantiateMsg, QueryMsg, RateLimitResponse};
+    use crate::testing::{default_instantiate_msg, env_at};
+    use cosmwasm_std::testing::{mock_dependencies_with_balance, mock_info};
+    use cosmwasm_std::{Deps, Uint128};
+
+    fn setup(deps: DepsMut) {
+        let instantiate_msg = InstantiateMsg {
+            initial_balances: vec![InitialBalance {
+                address: "addr0000".to_string(),
+                amount: Uint128::from(1000u128),
+            }],
+            ..default_instantiate_msg()
+        };
+        instantiate(deps, env_at(0), mock_info("creator", &[]), instantiate_msg).unwrap();
+    }
+
+    fn set_limit(deps: DepsMut, limit: Option<RateLimit>) {
+        let msg = ExecuteMsg::SetRateLimit {
+            address: "addr0000".to_string(),
+            limit,
+        };
+        execute(deps, env_at(0), mock_info("creator", &[]), msg).unwrap();
+    }
+
+    fn transfer(deps: DepsMut, time: u64, amount: u128) -> Result<Response, ContractError> {
+        let msg = ExecuteMsg::Transfer {
+            recipient: "addr1111".to_string(),
+            amount: Uint128::from(amount),
+            memo: None,
+            on_behalf_of: None,
+        };
+        execute(deps, env_at(time), mock_info("addr0000", &[]), msg)
+    }
+
+    fn query_rate_limit(deps: Deps, time: u64) -> RateLimitResponse {
+        let query_msg = QueryMsg::RateLimit {
+            address: "addr0000".to_string(),
+        };
+        from_slice(&query(deps, env_at(time), query_msg).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn throttles_within_sliding_window() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        set_limit(
+            deps.as_mut(),
+            Some(RateLimit {
+                max_amount: Uint128::from(100u128),
+                window: 60,
+            }),
+        );
+
+        transfer(deps.as_mut(), 100, 60).unwrap();
+        transfer(deps.as_mut(), 130, 40).unwrap();
+        let err = transfer(deps.as_mut(), 159, 1).unwrap_err();
+        match err {
+            ContractError::RateLimitExceeded {
+                available,
+                required,
+            } => {
+                assert_eq!(available, 0);
+                assert_eq!(required, 1);
+            }
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        // the first transfer leaves the window after 60 seconds
+        let res = query_rate_limit(deps.as_ref(), 160);
+        assert_eq!(res.used, Uint128::from(40u128));
+        transfer(deps.as_mut(), 160, 60).unwrap();
+        let err = transfer(deps.as_mut(), 160, 1).unwrap_err();
+        match err {
+            ContractError::RateLimitExceeded { .. } => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn unlimited_without_config() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        transfer(deps.as_mut(), 100, 1000).unwrap();
+        let res = query_rate_limit(deps.as_ref(), 100);
+        assert_eq!(res.limit, None);
+        assert_eq!(res.used, Uint128::zero());
+    }
+
+    #[test]
+    fn limit_can_be_lifted() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        set_limit(
+            deps.as_mut(),
+            Some(RateLimit {
+                max_amount: Uint128::from(10u128),
+                window: 60,
+            }),
+        );
+        transfer(deps.as_mut(), 100, 11).unwrap_err();
+        set_limit(deps.as_mut(), None);
+        transfer(deps.as_mut(), 100, 11).unwrap();
+    }
+}
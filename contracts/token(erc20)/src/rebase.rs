@@ -0,0 +1,467 @@
+use cosmwasm_std::{Addr, DepsMut, Env, MessageInfo, Response, Storage, Uint128, Uint256};
+use cosmwasm_storage::{PrefixedStorage, ReadonlyPrefixedStorage};
+use std::convert::TryFrom;
+
+use crate::cap;
+use crate::contract::{
+    assert_owner, bytes_to_u128, checked_add, checked_sub, read_addr, read_shares,
+    read_total_supply, write_shares, KEY_TOTAL_SUPPLY, PREFIX_CONFIG,
+};
+use crate::error::ContractError;
+use crate::events;
+use crate::keys;
+use crate::msg::{Role, SupplyDelta};
+use crate::renounce;
+use crate::votes;
+
+pub const KEY_TOTAL_SHARES: &[u8] = b"total_shares";
+pub const KEY_REBASER: &[u8] = b"rebaser";
+
+// Balances are stored as shares. The balance of an account is its share of the
+// total supply, so a rebase only has to change the total supply to scale all
+// balances at once. Without a rebase shares and tokens stay 1:1. The contract's
+// own balance is escrow and is not rebased, see `try_rebase`.
+
+pub fn read_total_shares(store: &dyn Storage) -> Result<u128, ContractError> {
+    let config_store = ReadonlyPrefixedStorage::new(store, PREFIX_CONFIG);
+    match config_store.get(KEY_TOTAL_SHARES) {
+        Some(data) => bytes_to_u128(&data),
+        None => read_total_supply(store),
+    }
+}
+
+pub fn write_total_shares(store: &mut dyn Storage, total_shares: u128) {
+    let mut config_store = PrefixedStorage::new(store, PREFIX_CONFIG);
+    config_store.set(KEY_TOTAL_SHARES, &total_shares.to_be_bytes());
+}
+
+pub fn read_rebaser(store: &dyn Storage) -> Result<Option<Addr>, ContractError> {
+    let config_store = ReadonlyPrefixedStorage::new(store, PREFIX_CONFIG);
+    read_addr(&config_store, KEY_REBASER)
+}
+
+/// Shares worth `amount` tokens, rounded down
+pub fn to_shares(store: &dyn Storage, amount: u128) -> Result<u128, ContractError> {
+    let total_supply = read_total_supply(store)?;
+    let total_shares = read_total_shares(store)?;
+    if total_supply == 0 || total_shares == 0 {
+        return Ok(amount);
+    }
+    Ok(Uint128::from(amount)
+        .multiply_ratio(total_shares, total_supply)
+        .u128())
+}
+
+/// Shares worth `amount` tokens, rounded up. Debits use this so that moving
+/// tokens never takes fewer shares than they are worth.
+pub fn to_shares_ceil(store: &dyn Storage, amount: u128) -> Result<u128, ContractError> {
+    let total_supply = read_total_supply(store)?;
+    let total_shares = read_total_shares(store)?;
+    if total_supply == 0 || total_shares == 0 {
+        return Ok(amount);
+    }
+    multiply_ratio_ceil(amount, total_shares, total_supply)
+}
+
+// `value * numerator / denominator`, rounded up
+fn multiply_ratio_ceil(
+    value: u128,
+    numerator: u128,
+    denominator: u128,
+) -> Result<u128, ContractError> {
+    let product = Uint128::from(value).full_mul(numerator);
+    let denominator = Uint256::from(denominator);
+    let mut quotient = product / denominator;
+    if quotient * denominator < product {
+        quotient += Uint256::from(1u8);
+    }
+    Uint128::try_from(quotient)
+        .map(|quotient| quotient.u128())
+        .map_err(|_| ContractError::Overflow {})
+}
+
+/// Tokens worth `shares` shares, rounded down
+pub fn to_amount(store: &dyn Storage, shares: u128) -> Result<u128, ContractError> {
+    let total_supply = read_total_supply(store)?;
+    let total_shares = read_total_shares(store)?;
+    Ok(keys::shares_to_balance(shares, total_supply, total_shares))
+}
+
+/// Assign the rebaser role, or remove it with `None`. Owner only.
+pub fn try_set_rebaser(
+    deps: DepsMut,
+    info: MessageInfo,
+    rebaser: Option<String>,
+) -> Result<Response, ContractError> {
+    assert_owner(deps.storage, &info.sender)?;
+    if rebaser.is_some() {
+        renounce::assert_not_renounced(deps.storage, Role::Rebaser)?;
+    }
+    let mut config_store = PrefixedStorage::new(deps.storage, PREFIX_CONFIG);
+    match &rebaser {
+        Some(rebaser) => {
+            let rebaser_address = deps.api.addr_validate(rebaser.as_str())?;
+            config_store.set(KEY_REBASER, rebaser_address.as_str().as_bytes());
+        }
+        None => config_store.remove(KEY_REBASER),
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "set_rebaser")
+        .add_attribute("rebaser", rebaser.unwrap_or_default()))
+}
+
+/// Change the total supply by `delta`, scaling every balance proportionally.
+/// Rebaser only.
+pub fn try_rebase(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    delta: SupplyDelta,
+) -> Result<Response, ContractError> {
+    if read_rebaser(deps.storage)?.as_ref() != Some(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+    let total_shares = read_total_shares(deps.storage)?;
+    if total_shares == 0 {
+        return Err(ContractError::InvalidRebase {});
+    }
+
+    let old_total_supply = read_total_supply(deps.storage)?;
+    let total_supply = match delta {
+        SupplyDelta::Increase(amount) => {
+            let total_supply = checked_add(old_total_supply, amount.u128())?;
+            cap::assert_within_cap(deps.storage, total_supply)?;
+            total_supply
+        }
+        SupplyDelta::Decrease(amount) => old_total_supply
+            .checked_sub(amount.u128())
+            .ok_or(ContractError::InvalidRebase {})?,
+    };
+
+    // Escrows owe fixed token amounts, so the contract keeps its balance and the
+    // delta is spread over the other holders only
+    let contract_shares = read_shares(deps.storage, deps.api, &env.contract.address)?;
+    let escrowed = keys::shares_to_balance(contract_shares, old_total_supply, total_shares);
+    let other_shares = checked_sub(total_shares, contract_shares)?;
+    let other_supply = match total_supply.checked_sub(escrowed) {
+        Some(other_supply) if other_supply > 0 && other_shares > 0 => other_supply,
+        _ => return Err(ContractError::InvalidRebase {}),
+    };
+    let new_contract_shares = multiply_ratio_ceil(escrowed, other_shares, other_supply)?;
+    write_shares(
+        deps.storage,
+        deps.api,
+        &env,
+        &env.contract.address,
+        contract_shares,
+        new_contract_shares,
+    )?;
+    // Also pins the share count the first time it moves away from the supply
+    write_total_shares(
+        deps.storage,
+        checked_add(other_shares, new_contract_shares)?,
+    );
+
+    let mut config_store = PrefixedStorage::new(deps.storage, PREFIX_CONFIG);
+    config_store.set(KEY_TOTAL_SUPPLY, &total_supply.to_be_bytes());
+    votes::write_supply_checkpoint(deps.storage, env.block.height, total_supply)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "rebase")
+        .add_attribute("rebaser", info.sender)
+        .add_attribute("total_supply", total_supply.to_string())
+        .add_event(events::rebase_event(deps.storage, old_total_supply)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate, query};
+    use crate::msg::{
+        BalanceResponse, ExecuteMsg, InitialBalance, InstantiateMsg, QueryMsg, RebaseStateResponse,
+        VotingPowerResponse,
+    };
+    use crate::testing::default_instantiate_msg;
+    use cosmwasm_std::testing::{
+        mock_dependencies_with_balance, mock_env, mock_info, MOCK_CONTRACT_ADDR,
+    };
+    use cosmwasm_std::{from_slice, Deps, Event};
+
+    fn setup(deps: DepsMut) {
+        let instantiate_msg = InstantiateMsg {
+            initial_balances: vec![
+                InitialBalance {
+                    address: "addr0000".to_string(),
+                    amount: Uint128::from(300u128),
+                },
+                InitialBalance {
+                    address: "addr1111".to_string(),
+                    amount: Uint128::from(100u128),
+                },
+            ],
+            ..default_instantiate_msg()
+        };
+        instantiate(deps, mock_env(), mock_info("creator", &[]), instantiate_msg).unwrap();
+    }
+
+    fn set_rebaser(deps: DepsMut) {
+        let msg = ExecuteMsg::SetRebaser {
+            rebaser: Some("rebaser".to_string()),
+        };
+        execute(deps, mock_env(), mock_info("creator", &[]), msg).unwrap();
+    }
+
+    fn rebase(deps: DepsMut, delta: SupplyDelta) -> Result<Response, ContractError> {
+        let msg = ExecuteMsg::Rebase { delta };
+        execute(deps, mock_env(), mock_info("rebaser", &[]), msg)
+    }
+
+    fn balance(deps: Deps, address: &str) -> u128 {
+        let query_msg = QueryMsg::Balance {
+            address: address.to_string(),
+        };
+        let res: BalanceResponse =
+            from_slice(&query(deps, mock_env(), query_msg).unwrap()).unwrap();
+        res.balance.u128()
+    }
+
+    #[test]
+    fn rebase_scales_balances() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        set_rebaser(deps.as_mut());
+
+        let res = rebase(deps.as_mut(), SupplyDelta::Increase(Uint128::from(400u128))).unwrap();
+        assert_eq!(
+            res.events,
+            vec![Event::new("rebase")
+                .add_attribute("old_total_supply", "400")
+                .add_attribute("total_supply", "800")]
+        );
+        assert_eq!(balance(deps.as_ref(), "addr0000"), 600);
+        assert_eq!(balance(deps.as_ref(), "addr1111"), 200);
+
+        rebase(deps.as_mut(), SupplyDelta::Decrease(Uint128::from(600u128))).unwrap();
+        assert_eq!(balance(deps.as_ref(), "addr0000"), 150);
+        assert_eq!(balance(deps.as_ref(), "addr1111"), 50);
+
+        let res: RebaseStateResponse =
+            from_slice(&query(deps.as_ref(), mock_env(), QueryMsg::RebaseState {}).unwrap())
+                .unwrap();
+        assert_eq!(res.rebaser, Some("rebaser".to_string()));
+        assert_eq!(res.total_supply, Uint128::from(200u128));
+        assert_eq!(res.total_shares, Uint128::from(400u128));
+    }
+
+    #[test]
+    fn transfers_move_scaled_amounts() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        set_rebaser(deps.as_mut());
+        rebase(deps.as_mut(), SupplyDelta::Increase(Uint128::from(400u128))).unwrap();
+
+        let transfer_msg = ExecuteMsg::Transfer {
+            recipient: "addr2222".to_string(),
+            amount: Uint128::from(100u128),
+            memo: None,
+            on_behalf_of: None,
+        };
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("addr0000", &[]),
+            transfer_msg,
+        )
+        .unwrap();
+        assert_eq!(balance(deps.as_ref(), "addr0000"), 500);
+        assert_eq!(balance(deps.as_ref(), "addr2222"), 100);
+
+        let burn_msg = ExecuteMsg::Burn {
+            amount: Uint128::from(200u128),
+            memo: None,
+        };
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("addr1111", &[]),
+            burn_msg,
+        )
+        .unwrap();
+        assert_eq!(balance(deps.as_ref(), "addr1111"), 0);
+        assert_eq!(balance(deps.as_ref(), "addr0000"), 500);
+    }
+
+    #[test]
+    fn rebase_scales_voting_power() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        set_rebaser(deps.as_mut());
+        let delegate_msg = ExecuteMsg::Delegate {
+            delegatee: "addr0000".to_string(),
+        };
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("addr0000", &[]),
+            delegate_msg,
+        )
+        .unwrap();
+        let voting_power = |deps: Deps| {
+            let query_msg = QueryMsg::VotingPower {
+                address: "addr0000".to_string(),
+            };
+            let res: VotingPowerResponse =
+                from_slice(&query(deps, mock_env(), query_msg).unwrap()).unwrap();
+            res.voting_power.u128()
+        };
+        assert_eq!(voting_power(deps.as_ref()), 300);
+
+        rebase(deps.as_mut(), SupplyDelta::Increase(Uint128::from(400u128))).unwrap();
+        assert_eq!(voting_power(deps.as_ref()), 600);
+
+        // Moving the full scaled balance takes all of the votes with it
+        let transfer_msg = ExecuteMsg::Transfer {
+            recipient: "addr2222".to_string(),
+            amount: Uint128::from(600u128),
+            memo: None,
+            on_behalf_of: None,
+        };
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("addr0000", &[]),
+            transfer_msg,
+        )
+        .unwrap();
+        assert_eq!(balance(deps.as_ref(), "addr0000"), 0);
+        assert_eq!(voting_power(deps.as_ref()), 0);
+    }
+
+    #[test]
+    fn debits_round_up_and_credits_round_down() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        set_rebaser(deps.as_mut());
+        // 4 shares are worth 3 tokens
+        rebase(deps.as_mut(), SupplyDelta::Decrease(Uint128::from(100u128))).unwrap();
+
+        let transfer_msg = ExecuteMsg::Transfer {
+            recipient: "addr2222".to_string(),
+            amount: Uint128::from(1u128),
+            memo: None,
+            on_behalf_of: None,
+        };
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("addr0000", &[]),
+            transfer_msg,
+        )
+        .unwrap();
+        let shares = |address: &str| {
+            read_shares(&deps.storage, &deps.api, &Addr::unchecked(address)).unwrap()
+        };
+        assert_eq!(shares("addr0000"), 298);
+        assert_eq!(shares("addr2222"), 1);
+        assert_eq!(read_total_shares(&deps.storage).unwrap(), 399);
+        assert_eq!(read_total_supply(&deps.storage).unwrap(), 300);
+    }
+
+    #[test]
+    fn transfers_worth_no_shares_are_rejected() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        set_rebaser(deps.as_mut());
+        // a share is worth 2 tokens
+        rebase(deps.as_mut(), SupplyDelta::Increase(Uint128::from(400u128))).unwrap();
+
+        let transfer_msg = ExecuteMsg::Transfer {
+            recipient: "addr2222".to_string(),
+            amount: Uint128::from(1u128),
+            memo: None,
+            on_behalf_of: None,
+        };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("addr0000", &[]),
+            transfer_msg,
+        )
+        .unwrap_err();
+        match err {
+            ContractError::BelowOneShare { amount: 1 } => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        assert_eq!(balance(deps.as_ref(), "addr0000"), 600);
+    }
+
+    #[test]
+    fn rebase_leaves_escrowed_tokens_alone() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        set_rebaser(deps.as_mut());
+        let release_at = mock_env().block.time.seconds() + 100;
+        let lock_msg = ExecuteMsg::TransferLocked {
+            recipient: "addr2222".to_string(),
+            amount: Uint128::from(100u128),
+            release_at,
+        };
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("addr0000", &[]),
+            lock_msg,
+        )
+        .unwrap();
+
+        rebase(deps.as_mut(), SupplyDelta::Increase(Uint128::from(300u128))).unwrap();
+        assert_eq!(balance(deps.as_ref(), MOCK_CONTRACT_ADDR), 100);
+        assert_eq!(balance(deps.as_ref(), "addr0000"), 400);
+        assert_eq!(balance(deps.as_ref(), "addr1111"), 200);
+
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(100);
+        let transfer_msg = ExecuteMsg::Transfer {
+            recipient: "addr3333".to_string(),
+            amount: Uint128::from(100u128),
+            memo: None,
+            on_behalf_of: None,
+        };
+        execute(deps.as_mut(), env, mock_info("addr2222", &[]), transfer_msg).unwrap();
+        assert_eq!(balance(deps.as_ref(), "addr3333"), 100);
+        assert_eq!(balance(deps.as_ref(), MOCK_CONTRACT_ADDR), 0);
+    }
+
+    #[test]
+    fn rebase_rejects_supply_overflow() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        set_rebaser(deps.as_mut());
+        let delta = SupplyDelta::Increase(Uint128::from(u128::MAX - 399));
+        match rebase(deps.as_mut(), delta).unwrap_err() {
+            ContractError::Overflow {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        assert_eq!(read_total_supply(&deps.storage).unwrap(), 400);
+    }
+
+    #[test]
+    fn only_rebaser_can_rebase() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        // nobody holds the role by default
+        let err = rebase(deps.as_mut(), SupplyDelta::Increase(Uint128::from(1u128))).unwrap_err();
+        match err {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        set_rebaser(deps.as_mut());
+        let err = rebase(deps.as_mut(), SupplyDelta::Decrease(Uint128::from(400u128))).unwrap_err();
+        match err {
+            ContractError::InvalidRebase {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+}
@@ -0,0 +1,201 @@
+use cosmwasm_std::{
+    from_slice, to_binary, to_vec, Addr, CosmosMsg, DepsMut, MessageInfo, Response, Storage,
+    Uint128, WasmMsg,
+};
+use cosmwasm_storage::{PrefixedStorage, ReadonlyPrefixedStorage};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::contract::{assert_owner, PREFIX_CONFIG};
+use crate::error::ContractError;
+use crate::msg::BurnReceiptInfo;
+use crate::state::{BurnReceipt, ReceiptMode};
+
+pub const KEY_BURN_RECEIPT: &[u8] = b"burn_receipt";
+
+// The subset of the cw20 execute messages sent to the receipt token
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+enum ReceiptExecuteMsg {
+    Mint { recipient: String, amount: Uint128 },
+    Transfer { recipient: String, amount: Uint128 },
+}
+
+pub fn read_burn_receipt(store: &dyn Storage) -> Result<Option<BurnReceipt>, ContractError> {
+    let config_store = ReadonlyPrefixedStorage::new(store, PREFIX_CONFIG);
+    match config_store.get(KEY_BURN_RECEIPT) {
+        Some(data) => Ok(Some(from_slice(&data)?)),
+        None => Ok(None),
+    }
+}
+
+/// Configure the secondary asset paid to burners, or stop paying with `None`.
+/// Owner only.
+pub fn try_set_burn_receipt(
+    deps: DepsMut,
+    info: MessageInfo,
+    receipt: Option<BurnReceiptInfo>,
+) -> Result<Response, ContractError> {
+    assert_owner(deps.storage, &info.sender)?;
+    let receipt = receipt
+        .map(|receipt| -> Result<BurnReceipt, ContractError> {
+            Ok(BurnReceipt {
+                contract: deps.api.addr_validate(&receipt.contract)?,
+                mode: receipt.mode,
+                ratio: receipt.ratio,
+            })
+        })
+        .transpose()?;
+    let mut config_store = PrefixedStorage::new(deps.storage, PREFIX_CONFIG);
+    match &receipt {
+        Some(receipt) => config_store.set(KEY_BURN_RECEIPT, &to_vec(receipt)?),
+        None => config_store.remove(KEY_BURN_RECEIPT),
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "set_burn_receipt")
+        .add_attribute(
+            "contract",
+            receipt
+                .map(|receipt| receipt.contract.to_string())
+                .unwrap_or_default(),
+        ))
+}
+
+/// Message paying `burner` the receipt for burning `amount`, if any is due
+pub fn receipt_msg(
+    store: &dyn Storage,
+    burner: &Addr,
+    amount: u128,
+) -> Result<Option<CosmosMsg>, ContractError> {
+    let receipt = match read_burn_receipt(store)? {
+        Some(receipt) => receipt,
+        None => return Ok(None),
+    };
+    let receipt_amount = Uint128::from(amount) * receipt.ratio;
+    if receipt_amount.is_zero() {
+        return Ok(None);
+    }
+    let msg = match receipt.mode {
+        ReceiptMode::Mint => ReceiptExecuteMsg::Mint {
+            recipient: burner.to_string(),
+            amount: receipt_amount,
+        },
+        ReceiptMode::Transfer => ReceiptExecuteMsg::Transfer {
+            recipient: burner.to_string(),
+            amount: receipt_amount,
+        },
+    };
+    Ok(Some(
+        WasmMsg::Execute {
+            contract_addr: receipt.contract.to_string(),
+            msg: to_binary(&msg)?,
+            funds: vec![],
+        }
+        .into(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate};
+    use crate::msg::{ExecuteMsg, InitialBalance, InstantiateMsg};
+    use crate::testing::default_instantiate_msg;
+    use cosmwasm_std::testing::{mock_dependencies_with_balance, mock_env, mock_info};
+    use cosmwasm_std::Decimal;
+
+    fn setup(deps: DepsMut) {
+        let instantiate_msg = InstantiateMsg {
+            initial_balances: vec![InitialBalance {
+                address: "addr0000".to_string(),
+                amount: Uint128::from(1000u128),
+            }],
+            ..default_instantiate_msg()
+        };
+        instantiate(deps, mock_env(), mock_info("creator", &[]), instantiate_msg).unwrap();
+    }
+
+    fn set_receipt(deps: DepsMut, mode: ReceiptMode) {
+        let msg = ExecuteMsg::SetBurnReceipt {
+            receipt: Some(BurnReceiptInfo {
+                contract: "receipt".to_string(),
+                mode,
+                ratio: Decimal::percent(50),
+            }),
+        };
+        execute(deps, mock_env(), mock_info("creator", &[]), msg).unwrap();
+    }
+
+    fn burn(deps: DepsMut, amount: u128) -> Response {
+        let msg = ExecuteMsg::Burn {
+            amount: Uint128::from(amount),
+            memo: None,
+        };
+        execute(deps, mock_env(), mock_info("addr0000", &[]), msg).unwrap()
+    }
+
+    fn expected_msg(msg: ReceiptExecuteMsg) -> CosmosMsg {
+        WasmMsg::Execute {
+            contract_addr: "receipt".to_string(),
+            msg: to_binary(&msg).unwrap(),
+            funds: vec![],
+        }
+        .into()
+    }
+
+    #[test]
+    fn burn_mints_receipt_at_ratio() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        set_receipt(deps.as_mut(), ReceiptMode::Mint);
+        let res = burn(deps.as_mut(), 100);
+        assert_eq!(res.messages.len(), 1);
+        assert_eq!(
+            res.messages[0].msg,
+            expected_msg(ReceiptExecuteMsg::Mint {
+                recipient: "addr0000".to_string(),
+                amount: Uint128::from(50u128),
+            })
+        );
+        assert_eq!(
+            to_binary(&ReceiptExecuteMsg::Mint {
+                recipient: "addr0000".to_string(),
+                amount: Uint128::from(50u128),
+            })
+            .unwrap()
+            .to_vec(),
+            br#"{"mint":{"recipient":"addr0000","amount":"50"}}"#.to_vec()
+        );
+    }
+
+    #[test]
+    fn burn_releases_held_receipt() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        set_receipt(deps.as_mut(), ReceiptMode::Transfer);
+        let res = burn(deps.as_mut(), 100);
+        assert_eq!(
+            res.messages[0].msg,
+            expected_msg(ReceiptExecuteMsg::Transfer {
+                recipient: "addr0000".to_string(),
+                amount: Uint128::from(50u128),
+            })
+        );
+
+        // dust rounding to no receipt sends nothing
+        let res = burn(deps.as_mut(), 1);
+        assert!(res.messages.is_empty());
+    }
+
+    #[test]
+    fn plain_burn_without_receipt() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        set_receipt(deps.as_mut(), ReceiptMode::Mint);
+        let msg = ExecuteMsg::SetBurnReceipt { receipt: None };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let res = burn(deps.as_mut(), 100);
+        assert!(res.messages.is_empty());
+    }
+}
@@ -0,0 +1,352 @@
+use cosmwasm_std::{coins, BankMsg, DepsMut, Env, MessageInfo, Response, Storage, Uint128};
+use cosmwasm_storage::{PrefixedStorage, ReadonlyPrefixedStorage};
+
+use crate::contract::{
+    add_outcome, assert_owner, bytes_to_u128, checked_add, escrow_payout, PREFIX_CONFIG,
+};
+use crate::dividends;
+use crate::error::ContractError;
+use crate::events;
+use crate::state::DividendAsset;
+use crate::treasury;
+use crate::wrap;
+
+pub const KEY_STUCK_BALANCE: &[u8] = b"stuck_balance";
+
+// Tokens transferred to the contract's own address while buyback is disabled
+// are stuck: no escrow accounts for them. They are counted apart from the
+// escrows on the same account so recovering them cannot touch the latter.
+
+pub fn read_stuck_balance(store: &dyn Storage) -> Result<u128, ContractError> {
+    let config_store = ReadonlyPrefixedStorage::new(store, PREFIX_CONFIG);
+    match config_store.get(KEY_STUCK_BALANCE) {
+        Some(data) => bytes_to_u128(&data),
+        None => Ok(0),
+    }
+}
+
+fn write_stuck_balance(store: &mut dyn Storage, amount: u128) {
+    let mut config_store = PrefixedStorage::new(store, PREFIX_CONFIG);
+    config_store.set(KEY_STUCK_BALANCE, &amount.to_be_bytes());
+}
+
+pub fn add_stuck_balance(store: &mut dyn Storage, amount: u128) -> Result<(), ContractError> {
+    let stuck = read_stuck_balance(store)?;
+    write_stuck_balance(store, checked_add(stuck, amount)?);
+    Ok(())
+}
+
+/// Return tokens stuck on the contract's own address. Owner only. They are paid
+/// out like escrow, so pause, freeze, compliance, limits and fees all apply.
+pub fn try_recover_own_balance(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipient: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    assert_owner(deps.storage, &info.sender)?;
+    let recipient = deps.api.addr_validate(&recipient)?;
+    let stuck = read_stuck_balance(deps.storage)?;
+    if stuck < amount.u128() {
+        return Err(ContractError::InsufficientFunds {
+            balance: stuck,
+            required: amount.u128(),
+        });
+    }
+    let outcome = escrow_payout(
+        deps.storage,
+        deps.api,
+        &deps.querier,
+        &env,
+        &recipient,
+        amount.u128(),
+    )?;
+    write_stuck_balance(deps.storage, stuck - amount.u128());
+    let event = events::transfer_event(
+        deps.storage,
+        deps.api,
+        &env.contract.address,
+        &recipient,
+        amount.u128(),
+        outcome,
+    )?;
+
+    let res = Response::new()
+        .add_event(event)
+        .add_attribute("action", "recover_own_balance")
+        .add_attribute("recipient", recipient)
+        .add_attribute("amount", amount.to_string());
+    Ok(add_outcome(res, outcome))
+}
+
+// Native coins back wrapped tokens and unclaimed native dividends. Since
+// neither is tracked per coin, denoms used by either are never swept.
+fn assert_sweepable(store: &dyn Storage, denom: &str) -> Result<(), ContractError> {
+    let wrapped = wrap::read_wrap_config(store)?.map(|config| config.denom);
+    let paid_out = dividends::read_pools(store)?.into_iter().any(|pool| {
+        matches!(pool.asset, DividendAsset::Native { denom: pool_denom } if pool_denom == denom)
+    });
+    if wrapped.as_deref() == Some(denom) || paid_out {
+        return Err(ContractError::ReservedDenom {
+            denom: denom.to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Send all native coins of `denom` held by the contract to `recipient`, or to
+/// the treasury if unset. Owner only.
+pub fn try_sweep_native(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    denom: String,
+    recipient: Option<String>,
+) -> Result<Response, ContractError> {
+    assert_owner(deps.storage, &info.sender)?;
+    assert_sweepable(deps.storage, &denom)?;
+    let recipient = match recipient {
+        Some(recipient) => deps.api.addr_validate(&recipient)?,
+        None => treasury::read_treasury(deps.storage)?.ok_or(ContractError::NoTreasury {})?,
+    };
+    let amount = deps
+        .querier
+        .query_balance(&env.contract.address, &denom)?
+        .amount;
+    if amount.is_zero() {
+        return Err(ContractError::NothingToSweep {});
+    }
+
+    Ok(Response::new()
+        .add_message(BankMsg::Send {
+            to_address: recipient.to_string(),
+            amount: coins(amount.u128(), &denom),
+        })
+        .add_attribute("action", "sweep_native")
+        .add_attribute("denom", denom)
+        .add_attribute("recipient", recipient)
+        .add_attribute("amount", amount.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clawback;
+    use crate::contract::{execute, instantiate, query, read_balance};
+    use crate::msg::{ExecuteMsg, InitialBalance, InstantiateMsg, QueryMsg, StuckBalanceResponse};
+    use crate::state::WrapConfig;
+    use crate::testing::default_instantiate_msg;
+    use cosmwasm_std::testing::{
+        mock_dependencies_with_balance, mock_env, mock_info, MOCK_CONTRACT_ADDR,
+    };
+    use cosmwasm_std::{from_slice, Addr, Binary, Deps};
+
+    fn setup(deps: DepsMut) {
+        let instantiate_msg = InstantiateMsg {
+            initial_balances: vec![InitialBalance {
+                address: "addr0000".to_string(),
+                amount: Uint128::from(1000u128),
+            }],
+            ..default_instantiate_msg()
+        };
+        instantiate(deps, mock_env(), mock_info("creator", &[]), instantiate_msg).unwrap();
+    }
+
+    fn stuck_balance(deps: Deps) -> u128 {
+        let res: StuckBalanceResponse =
+            from_slice(&query(deps, mock_env(), QueryMsg::StuckBalance {}).unwrap()).unwrap();
+        res.amount.u128()
+    }
+
+    fn recover(deps: DepsMut, sender: &str, amount: u128) -> Result<Response, ContractError> {
+        let msg = ExecuteMsg::RecoverOwnBalance {
+            recipient: "addr0000".to_string(),
+            amount: Uint128::from(amount),
+        };
+        execute(deps, mock_env(), mock_info(sender, &[]), msg)
+    }
+
+    #[test]
+    fn owner_recovers_tokens_sent_to_the_contract() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        let msg = ExecuteMsg::Transfer {
+            recipient: MOCK_CONTRACT_ADDR.to_string(),
+            amount: Uint128::from(300u128),
+            memo: None,
+            on_behalf_of: None,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+        assert_eq!(stuck_balance(deps.as_ref()), 300);
+
+        match recover(deps.as_mut(), "addr0000", 300).unwrap_err() {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        match recover(deps.as_mut(), "creator", 301).unwrap_err() {
+            ContractError::InsufficientFunds { balance, required } => {
+                assert_eq!(balance, 300);
+                assert_eq!(required, 301);
+            }
+            e => panic!("unexpected error: {:?}", e),
+        }
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            ExecuteMsg::Pause {},
+        )
+        .unwrap();
+        match recover(deps.as_mut(), "creator", 300).unwrap_err() {
+            ContractError::Paused {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        assert_eq!(stuck_balance(deps.as_ref()), 300);
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            ExecuteMsg::Unpause {},
+        )
+        .unwrap();
+
+        recover(deps.as_mut(), "creator", 300).unwrap();
+        assert_eq!(stuck_balance(deps.as_ref()), 0);
+        let balance = read_balance(&deps.storage, &deps.api, &Addr::unchecked("addr0000")).unwrap();
+        assert_eq!(balance, 1000);
+    }
+
+    #[test]
+    fn every_transfer_to_the_contract_is_counted() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        let msg = ExecuteMsg::Send {
+            contract: MOCK_CONTRACT_ADDR.to_string(),
+            amount: Uint128::from(100u128),
+            msg: Binary::default(),
+            on_failure: None,
+            memo: None,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+        assert_eq!(stuck_balance(deps.as_ref()), 100);
+
+        let msg = ExecuteMsg::TransferAndCall {
+            recipient: MOCK_CONTRACT_ADDR.to_string(),
+            amount: Uint128::from(50u128),
+            msg: Binary::default(),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+        assert_eq!(stuck_balance(deps.as_ref()), 150);
+
+        clawback::force_transfer(
+            deps.as_mut(),
+            mock_env(),
+            "addr0000".to_string(),
+            MOCK_CONTRACT_ADDR.to_string(),
+            Uint128::from(25u128),
+        )
+        .unwrap();
+        assert_eq!(stuck_balance(deps.as_ref()), 175);
+
+        // escrow deposits are not stuck
+        let msg = ExecuteMsg::TransferLocked {
+            recipient: "addr1111".to_string(),
+            amount: Uint128::from(10u128),
+            release_at: mock_env().block.time.seconds() + 1,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+        assert_eq!(stuck_balance(deps.as_ref()), 175);
+    }
+
+    #[test]
+    fn owner_sweeps_native_coins() {
+        let mut deps = mock_dependencies_with_balance(&coins(500, "uatom"));
+        setup(deps.as_mut());
+        let sweep = |recipient: Option<&str>| ExecuteMsg::SweepNative {
+            denom: "uatom".to_string(),
+            recipient: recipient.map(|recipient| recipient.to_string()),
+        };
+
+        match execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("addr0000", &[]),
+            sweep(None),
+        ) {
+            Err(ContractError::Unauthorized {}) => {}
+            res => panic!("unexpected result: {:?}", res),
+        }
+        match execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            sweep(None),
+        ) {
+            Err(ContractError::NoTreasury {}) => {}
+            res => panic!("unexpected result: {:?}", res),
+        }
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            sweep(Some("addr0000")),
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            BankMsg::Send {
+                to_address: "addr0000".to_string(),
+                amount: coins(500, "uatom"),
+            }
+            .into()
+        );
+
+        let msg = ExecuteMsg::SetTreasury {
+            treasury: Some("treasury".to_string()),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            sweep(None),
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            BankMsg::Send {
+                to_address: "treasury".to_string(),
+                amount: coins(500, "uatom"),
+            }
+            .into()
+        );
+
+        let msg = ExecuteMsg::SweepNative {
+            denom: "uluna".to_string(),
+            recipient: None,
+        };
+        match execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg) {
+            Err(ContractError::NothingToSweep {}) => {}
+            res => panic!("unexpected result: {:?}", res),
+        }
+    }
+
+    #[test]
+    fn wrapped_denom_is_not_swept() {
+        let mut deps = mock_dependencies_with_balance(&coins(500, "uatom"));
+        setup(deps.as_mut());
+        let config = WrapConfig {
+            denom: "uatom".to_string(),
+        };
+        wrap::write_wrap_config(&mut deps.storage, &config).unwrap();
+        let msg = ExecuteMsg::SweepNative {
+            denom: "uatom".to_string(),
+            recipient: Some("addr0000".to_string()),
+        };
+        match execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg) {
+            Err(ContractError::ReservedDenom { denom }) => assert_eq!(denom, "uatom"),
+            res => panic!("unexpected result: {:?}", res),
+        }
+    }
+}
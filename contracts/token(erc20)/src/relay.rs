@@ -0,0 +1,241 @@
+use cosmwasm_std::{Binary, DepsMut, Env, MessageInfo, Response, Uint128};
+use serde::Serialize;
+
+use crate::contract::{add_outcome, assert_transfer_allowed, balance_data, perform_transfer};
+use crate::error::ContractError;
+use crate::events;
+use crate::permit::{assert_nonce, write_nonce};
+use crate::signing;
+
+pub const RELAYED_TRANSFER_MESSAGE_TYPE: &str = "relayed_transfer";
+
+/// The message an owner signs to authorize a relayed transfer, wrapped in the canonical
+/// signing payload of `signing::signing_bytes`. Fields in lexicographic order.
+#[derive(Serialize)]
+struct RelayedTransferMessage<'a> {
+    amount: Uint128,
+    expiry: u64,
+    nonce: u64,
+    owner: &'a str,
+    recipient: &'a str,
+}
+
+/// Transfer by signature
+///
+/// Moves `amount` tokens of `owner` to `recipient`, authorized by a secp256k1 signature
+/// of `owner` instead of a transaction signed by `owner`. The signer of the transaction
+/// (the relayer) pays the gas and does not need any balance or allowance.
+///
+/// Relayed transfers share the nonce sequence of permits, so each signature of `owner`
+/// can be used exactly once.
+///
+/// @param nonce must equal the current nonce of `owner`, see `QueryMsg::Nonce`
+/// @param expiry block time in seconds after which the signature is no longer valid
+/// @param public_key compressed secp256k1 public key the `owner` address derives from
+/// @param signature 64 byte compact signature over the relayed transfer payload
+#[allow(clippy::too_many_arguments)]
+pub fn try_relayed_transfer(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    owner: String,
+    recipient: String,
+    amount: Uint128,
+    nonce: u64,
+    expiry: u64,
+    public_key: Binary,
+    signature: Binary,
+) -> Result<Response, ContractError> {
+    if env.block.time.seconds() > expiry {
+        return Err(ContractError::RelayExpired {});
+    }
+    let owner_address = deps.api.addr_validate(owner.as_str())?;
+    let recipient_address = deps.api.addr_validate(recipient.as_str())?;
+    assert_nonce(deps.storage, deps.api, &owner_address, nonce)?;
+    assert_transfer_allowed(deps.storage, &owner_address, amount.u128())?;
+
+    let message = RelayedTransferMessage {
+        amount,
+        expiry,
+        nonce,
+        owner: owner_address.as_str(),
+        recipient: recipient_address.as_str(),
+    };
+    signing::verify_signature(
+        deps.api,
+        &env,
+        RELAYED_TRANSFER_MESSAGE_TYPE,
+        &message,
+        &owner_address,
+        &public_key,
+        &signature,
+    )?;
+
+    let next_nonce = nonce.checked_add(1).ok_or(ContractError::Overflow {})?;
+    write_nonce(deps.storage, deps.api, &owner_address, next_nonce)?;
+    let outcome = perform_transfer(
+        deps.storage,
+        deps.api,
+        &deps.querier,
+        &env,
+        &owner_address,
+        &recipient_address,
+        amount.u128(),
+    )?;
+    let event = events::transfer_event(
+        deps.storage,
+        deps.api,
+        &owner_address,
+        &recipient_address,
+        amount.u128(),
+        outcome,
+    )?;
+    let res = Response::new()
+        .set_data(balance_data(deps.storage, deps.api, &owner_address)?)
+        .add_event(event)
+        .add_attribute("action", "relayed_transfer")
+        .add_attribute("owner", owner)
+        .add_attribute("recipient", recipient)
+        .add_attribute("relayer", info.sender)
+        .add_attribute("nonce", nonce.to_string());
+    Ok(add_outcome(res, outcome))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate, read_balance};
+    use crate::msg::{ExecuteMsg, InitialBalance, InstantiateMsg};
+    use crate::testing::default_instantiate_msg;
+    use crate::testing::env_at;
+    use cosmwasm_std::testing::{mock_dependencies_with_balance, mock_info};
+    use cosmwasm_std::Addr;
+
+    // Test key derived from the secret scalar 1234567
+    const OWNER: &str = "cosmos1c4aphwsj5vf588un9kxdmg23cm6hppacgps24a";
+    const OWNER_PUBKEY: &str = "028208f5abf04066bad1db9d46f8bcf5a6cc11d0558ab523e7bd3c0ec08bdb782f";
+    // Signature of the payload for recipient addr1111, amount 300, expiry 1000, nonce 0
+    const OWNER_SIGNATURE: &str = "bf0d0885a8c2964963ce6ffec75cc6c8bab4d4df4f705b067d5bfc1ec50c4ab523c6b8341cabe7825c11cb1bdd936de6a9ba3ebfd0b30d8b9eaa20c11ded61cd";
+
+    fn make_relayed_transfer(amount: u128) -> ExecuteMsg {
+        ExecuteMsg::RelayedTransfer {
+            owner: OWNER.to_string(),
+            recipient: "addr1111".to_string(),
+            amount: Uint128::from(amount),
+            nonce: 0,
+            expiry: 1000,
+            public_key: Binary::from(hex::decode(OWNER_PUBKEY).unwrap()),
+            signature: Binary::from(hex::decode(OWNER_SIGNATURE).unwrap()),
+        }
+    }
+
+    fn setup(deps: DepsMut) {
+        setup_with(deps, default_instantiate_msg());
+    }
+
+    fn setup_with(deps: DepsMut, instantiate_msg: InstantiateMsg) {
+        let instantiate_msg = InstantiateMsg {
+            initial_balances: vec![InitialBalance {
+                address: OWNER.to_string(),
+                amount: Uint128::from(1000u128),
+            }],
+            ..instantiate_msg
+        };
+        instantiate(
+            deps,
+            env_at(500),
+            mock_info("creator", &[]),
+            instantiate_msg,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn relayer_can_submit_transfer() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+
+        let msg = make_relayed_transfer(300);
+        execute(deps.as_mut(), env_at(550), mock_info("relayer", &[]), msg).unwrap();
+        assert_eq!(
+            read_balance(&deps.storage, &deps.api, &Addr::unchecked(OWNER)).unwrap(),
+            700
+        );
+        assert_eq!(
+            read_balance(&deps.storage, &deps.api, &Addr::unchecked("addr1111")).unwrap(),
+            300
+        );
+        assert_eq!(
+            read_balance(&deps.storage, &deps.api, &Addr::unchecked("relayer")).unwrap(),
+            0
+        );
+    }
+
+    #[test]
+    fn relayed_transfer_cannot_be_replayed() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        let msg = make_relayed_transfer(300);
+        execute(
+            deps.as_mut(),
+            env_at(550),
+            mock_info("relayer", &[]),
+            msg.clone(),
+        )
+        .unwrap();
+        let err = execute(deps.as_mut(), env_at(550), mock_info("relayer", &[]), msg).unwrap_err();
+        match err {
+            ContractError::InvalidNonce { expected, actual } => {
+                assert_eq!(expected, 1);
+                assert_eq!(actual, 0);
+            }
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn fails_after_expiry() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        let msg = make_relayed_transfer(300);
+        let err = execute(deps.as_mut(), env_at(1001), mock_info("relayer", &[]), msg).unwrap_err();
+        match err {
+            ContractError::RelayExpired {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn runs_the_checks_of_a_transfer() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup_with(
+            deps.as_mut(),
+            InstantiateMsg {
+                allow_zero_transfers: false,
+                ..default_instantiate_msg()
+            },
+        );
+        let msg = make_relayed_transfer(0);
+        let err = execute(deps.as_mut(), env_at(550), mock_info("relayer", &[]), msg).unwrap_err();
+        match err {
+            ContractError::ZeroTransfer {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn fails_for_tampered_payload() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        let msg = make_relayed_transfer(301);
+        let err = execute(deps.as_mut(), env_at(550), mock_info("relayer", &[]), msg).unwrap_err();
+        match err {
+            ContractError::InvalidSignature {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        assert_eq!(
+            read_balance(&deps.storage, &deps.api, &Addr::unchecked(OWNER)).unwrap(),
+            1000
+        );
+    }
+}
@@ -0,0 +1,206 @@
+use cosmwasm_std::{from_slice, to_vec, Addr, DepsMut, MessageInfo, Response, Storage};
+use cosmwasm_storage::{PrefixedStorage, ReadonlyPrefixedStorage};
+
+use crate::clawback::{self, KEY_CLAWBACK_ADMIN};
+use crate::contract::{read_owner, KEY_OWNER, PREFIX_CONFIG};
+use crate::error::ContractError;
+use crate::events;
+use crate::lockup::{self, KEY_LOCKER};
+use crate::marketing::{self, KEY_MARKETING_ADMIN};
+use crate::mint::{self, KEY_MINTER};
+use crate::msg::Role;
+use crate::rebase::{self, KEY_REBASER};
+
+pub const KEY_RENOUNCED: &[u8] = b"renounced";
+
+// A renounced role is cleared and recorded, so it can never be assigned again,
+// not even by the owner. Roles held by the owner's appointees stay with them
+// when the owner renounces and can still be renounced by their holders.
+
+pub fn role_name(role: Role) -> &'static str {
+    match role {
+        Role::Owner => "owner",
+        Role::Minter => "minter",
+        Role::MarketingAdmin => "marketing_admin",
+        Role::ClawbackAdmin => "clawback_admin",
+        Role::Rebaser => "rebaser",
+        Role::Locker => "locker",
+    }
+}
+
+pub fn read_renounced(store: &dyn Storage) -> Result<Vec<Role>, ContractError> {
+    let config_store = ReadonlyPrefixedStorage::new(store, PREFIX_CONFIG);
+    match config_store.get(KEY_RENOUNCED) {
+        Some(data) => Ok(from_slice(&data)?),
+        None => Ok(vec![]),
+    }
+}
+
+fn write_renounced(store: &mut dyn Storage, role: Role) -> Result<(), ContractError> {
+    let mut renounced = read_renounced(store)?;
+    if !renounced.contains(&role) {
+        renounced.push(role);
+    }
+    let mut config_store = PrefixedStorage::new(store, PREFIX_CONFIG);
+    config_store.set(KEY_RENOUNCED, &to_vec(&renounced)?);
+    Ok(())
+}
+
+/// Errors if `role` was renounced and must stay unassigned
+pub fn assert_not_renounced(store: &dyn Storage, role: Role) -> Result<(), ContractError> {
+    if read_renounced(store)?.contains(&role) {
+        return Err(ContractError::RoleRenounced {
+            role: role_name(role).to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Account currently holding `role`, if any
+pub fn read_holder(store: &dyn Storage, role: Role) -> Result<Option<Addr>, ContractError> {
+    match role {
+        Role::Owner => read_owner(store),
+        Role::Minter => Ok(mint::read_minter(store)?.map(|minter| minter.minter)),
+        Role::MarketingAdmin => marketing::read_marketing_admin(store),
+        Role::ClawbackAdmin => clawback::read_clawback_admin(store),
+        Role::Rebaser => rebase::read_rebaser(store),
+        Role::Locker => lockup::read_locker(store),
+    }
+}
+
+// Errors with Unauthorized unless `sender` currently holds `role`
+fn assert_holder(store: &dyn Storage, sender: &Addr, role: Role) -> Result<(), ContractError> {
+    match read_holder(store, role)? {
+        Some(holder) if holder == *sender => Ok(()),
+        _ => Err(ContractError::Unauthorized {}),
+    }
+}
+
+/// Give up `role` for good. Holder of the role only.
+pub fn try_renounce_role(
+    deps: DepsMut,
+    info: MessageInfo,
+    role: Role,
+) -> Result<Response, ContractError> {
+    assert_holder(deps.storage, &info.sender, role)?;
+    let mut config_store = PrefixedStorage::new(deps.storage, PREFIX_CONFIG);
+    config_store.remove(match role {
+        Role::Owner => KEY_OWNER,
+        Role::Minter => KEY_MINTER,
+        Role::MarketingAdmin => KEY_MARKETING_ADMIN,
+        Role::ClawbackAdmin => KEY_CLAWBACK_ADMIN,
+        Role::Rebaser => KEY_REBASER,
+        Role::Locker => KEY_LOCKER,
+    });
+    write_renounced(deps.storage, role)?;
+
+    Ok(Response::new()
+        .add_event(events::renounce_event(&info.sender, role))
+        .add_attribute("action", "renounce_role")
+        .add_attribute("role", role_name(role))
+        .add_attribute("account", info.sender))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate, query, read_owner};
+    use crate::msg::{ExecuteMsg, InstantiateMsg, MinterInfo, QueryMsg, RenouncedResponse};
+    use crate::testing::default_instantiate_msg;
+    use cosmwasm_std::testing::{mock_dependencies_with_balance, mock_env, mock_info};
+    use cosmwasm_std::{from_slice, Deps, Event};
+
+    fn setup(deps: DepsMut) {
+        let instantiate_msg = InstantiateMsg {
+            clawback_admin: Some("clawback".to_string()),
+            mint: Some(MinterInfo {
+                minter: "minter".to_string(),
+                mint_deadline: None,
+            }),
+            ..default_instantiate_msg()
+        };
+        instantiate(deps, mock_env(), mock_info("creator", &[]), instantiate_msg).unwrap();
+    }
+
+    fn renounced(deps: Deps) -> RenouncedResponse {
+        from_slice(&query(deps, mock_env(), QueryMsg::Renounced {}).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn owner_renounces_for_good() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+
+        let msg = ExecuteMsg::RenounceOwnership {};
+        match execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("minter", &[]),
+            msg.clone(),
+        ) {
+            Err(ContractError::Unauthorized {}) => {}
+            res => panic!("unexpected result: {:?}", res),
+        }
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        assert_eq!(
+            res.events,
+            vec![Event::new("renounce")
+                .add_attribute("role", "owner")
+                .add_attribute("account", "creator")]
+        );
+        assert_eq!(read_owner(&deps.storage).unwrap(), None);
+        assert_eq!(renounced(deps.as_ref()).roles, vec![Role::Owner]);
+
+        let msg = ExecuteMsg::SetRebaser {
+            rebaser: Some("creator".to_string()),
+        };
+        match execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg) {
+            Err(ContractError::Unauthorized {}) => {}
+            res => panic!("unexpected result: {:?}", res),
+        }
+    }
+
+    #[test]
+    fn holders_renounce_their_roles() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        let msg = ExecuteMsg::SetRebaser {
+            rebaser: Some("rebaser".to_string()),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        for (holder, role) in [
+            ("minter", Role::Minter),
+            ("clawback", Role::ClawbackAdmin),
+            ("rebaser", Role::Rebaser),
+        ] {
+            let msg = ExecuteMsg::RenounceRole { role };
+            match execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("creator", &[]),
+                msg.clone(),
+            ) {
+                Err(ContractError::Unauthorized {}) => {}
+                res => panic!("unexpected result: {:?}", res),
+            }
+            execute(deps.as_mut(), mock_env(), mock_info(holder, &[]), msg).unwrap();
+        }
+        assert_eq!(mint::read_minter(&deps.storage).unwrap(), None);
+        assert_eq!(clawback::read_clawback_admin(&deps.storage).unwrap(), None);
+        assert_eq!(rebase::read_rebaser(&deps.storage).unwrap(), None);
+        assert_eq!(
+            renounced(deps.as_ref()).roles,
+            vec![Role::Minter, Role::ClawbackAdmin, Role::Rebaser]
+        );
+
+        // the owner cannot hand a renounced role out again
+        let msg = ExecuteMsg::SetRebaser {
+            rebaser: Some("rebaser".to_string()),
+        };
+        match execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg) {
+            Err(ContractError::RoleRenounced { role }) => assert_eq!(role, "rebaser"),
+            res => panic!("unexpected result: {:?}", res),
+        }
+    }
+}
@@ -0,0 +1,192 @@
+use cosmwasm_std::{from_slice, to_vec, Addr, Env, Storage, Uint128};
+use cosmwasm_storage::{PrefixedStorage, ReadonlyPrefixedStorage};
+
+use crate::contract::checked_add;
+use crate::error::ContractError;
+use crate::state::SpendingCounters;
+
+pub const PREFIX_SPENDING: &[u8] = b"spending";
+
+/// Length of a reporting epoch in seconds, one UTC day
+pub const REPORT_EPOCH_LENGTH: u64 = 86_400;
+
+// Counters are kept per account and epoch, keyed by big endian epoch, and
+// updated as the amounts move. The contract's own account is not reported.
+
+pub fn epoch_at(env: &Env) -> u64 {
+    env.block.time.seconds() / REPORT_EPOCH_LENGTH
+}
+
+/// Start and end time in seconds of `epoch`, which queries take from the caller
+pub fn epoch_bounds(epoch: u64) -> Result<(u64, u64), ContractError> {
+    let start = epoch
+        .checked_mul(REPORT_EPOCH_LENGTH)
+        .ok_or(ContractError::Overflow {})?;
+    let end = start
+        .checked_add(REPORT_EPOCH_LENGTH)
+        .ok_or(ContractError::Overflow {})?;
+    Ok((start, end))
+}
+
+pub fn read_counters(
+    store: &dyn Storage,
+    owner: &Addr,
+    epoch: u64,
+) -> Result<SpendingCounters, ContractError> {
+    let spending_store =
+        ReadonlyPrefixedStorage::multilevel(store, &[PREFIX_SPENDING, owner.as_str().as_bytes()]);
+    match spending_store.get(&epoch.to_be_bytes()) {
+        Some(data) => Ok(from_slice(&data)?),
+        None => Ok(SpendingCounters::default()),
+    }
+}
+
+fn update(
+    store: &mut dyn Storage,
+    env: &Env,
+    owner: &Addr,
+    action: impl FnOnce(&mut SpendingCounters) -> Result<(), ContractError>,
+) -> Result<(), ContractError> {
+    if owner == env.contract.address {
+        return Ok(());
+    }
+    let epoch = epoch_at(env);
+    let mut counters = read_counters(store, owner, epoch)?;
+    action(&mut counters)?;
+    let mut spending_store =
+        PrefixedStorage::multilevel(store, &[PREFIX_SPENDING, owner.as_str().as_bytes()]);
+    spending_store.set(&epoch.to_be_bytes(), &to_vec(&counters)?);
+    Ok(())
+}
+
+/// Records an outgoing transfer of `amount`, of which `fee` went to the treasury
+pub fn record_transfer(
+    store: &mut dyn Storage,
+    env: &Env,
+    from: &Addr,
+    amount: u128,
+    fee: u128,
+) -> Result<(), ContractError> {
+    update(store, env, from, |counters| {
+        counters.sent = Uint128::from(checked_add(counters.sent.u128(), amount)?);
+        counters.fees_paid = Uint128::from(checked_add(counters.fees_paid.u128(), fee)?);
+        Ok(())
+    })
+}
+
+pub fn record_burn(
+    store: &mut dyn Storage,
+    env: &Env,
+    owner: &Addr,
+    amount: u128,
+) -> Result<(), ContractError> {
+    update(store, env, owner, |counters| {
+        counters.burned = Uint128::from(checked_add(counters.burned.u128(), amount)?);
+        Ok(())
+    })
+}
+
+/// Records rewards paid out in this token
+pub fn record_rewards(
+    store: &mut dyn Storage,
+    env: &Env,
+    owner: &Addr,
+    amount: u128,
+) -> Result<(), ContractError> {
+    update(store, env, owner, |counters| {
+        counters.rewards = Uint128::from(checked_add(counters.rewards.u128(), amount)?);
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate, query};
+    use crate::msg::{
+        ExecuteMsg, InitialBalance, InstantiateMsg, QueryMsg, SpendingReportResponse,
+    };
+    use crate::testing::{default_instantiate_msg, env_at};
+    use cosmwasm_std::testing::{mock_dependencies_with_balance, mock_info};
+    use cosmwasm_std::{Deps, DepsMut};
+
+    fn setup(deps: DepsMut) {
+        let instantiate_msg = InstantiateMsg {
+            initial_balances: vec![InitialBalance {
+                address: "addr0000".to_string(),
+                amount: Uint128::from(10_000u128),
+            }],
+            ..default_instantiate_msg()
+        };
+        instantiate(deps, env_at(0), mock_info("creator", &[]), instantiate_msg).unwrap();
+    }
+
+    fn transfer(deps: DepsMut, time: u64, amount: u128) {
+        let msg = ExecuteMsg::Transfer {
+            recipient: "addr1111".to_string(),
+            amount: Uint128::from(amount),
+            memo: None,
+            on_behalf_of: None,
+        };
+        execute(deps, env_at(time), mock_info("addr0000", &[]), msg).unwrap();
+    }
+
+    fn report(deps: Deps, address: &str, epoch: u64) -> SpendingReportResponse {
+        let query_msg = QueryMsg::SpendingReport {
+            address: address.to_string(),
+            epoch,
+        };
+        from_slice(&query(deps, env_at(0), query_msg).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn aggregates_per_epoch() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        let msg = ExecuteMsg::UpdateFeeConfig {
+            enabled: true,
+            rate_bps: 100,
+            treasury: "treasury".to_string(),
+        };
+        execute(deps.as_mut(), env_at(0), mock_info("creator", &[]), msg).unwrap();
+
+        transfer(deps.as_mut(), 100, 1000);
+        transfer(deps.as_mut(), 200, 500);
+        let msg = ExecuteMsg::Burn {
+            amount: Uint128::from(300u128),
+            memo: None,
+        };
+        execute(deps.as_mut(), env_at(300), mock_info("addr0000", &[]), msg).unwrap();
+        transfer(deps.as_mut(), REPORT_EPOCH_LENGTH, 2000);
+
+        let res = report(deps.as_ref(), "addr0000", 0);
+        assert_eq!(res.start, 0);
+        assert_eq!(res.end, REPORT_EPOCH_LENGTH);
+        assert_eq!(res.sent, Uint128::from(1500u128));
+        assert_eq!(res.fees_paid, Uint128::from(15u128));
+        assert_eq!(res.burned, Uint128::from(300u128));
+        assert_eq!(res.rewards, Uint128::zero());
+
+        let res = report(deps.as_ref(), "addr0000", 1);
+        assert_eq!(res.sent, Uint128::from(2000u128));
+        assert_eq!(res.fees_paid, Uint128::from(20u128));
+        assert_eq!(res.burned, Uint128::zero());
+
+        // incoming transfers are not spending
+        assert_eq!(report(deps.as_ref(), "addr1111", 0).sent, Uint128::zero());
+    }
+
+    #[test]
+    fn rejects_epochs_past_the_end_of_time() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        let query_msg = QueryMsg::SpendingReport {
+            address: "addr0000".to_string(),
+            epoch: u64::MAX / REPORT_EPOCH_LENGTH,
+        };
+        match query(deps.as_ref(), env_at(0), query_msg).unwrap_err() {
+            ContractError::Overflow {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+}
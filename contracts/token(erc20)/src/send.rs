@@ -0,0 +1,513 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    from_slice, to_binary, to_vec, Addr, Api, Binary, ContractInfoResponse, DepsMut, Env,
+    MessageInfo, Order, QuerierWrapper, Reply, Response, Storage, SubMsg, Uint128, WasmMsg,
+    WasmQuery,
+};
+use cosmwasm_storage::{PrefixedStorage, ReadonlyPrefixedStorage};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::convert::TryInto;
+
+use crate::contract::{
+    add_memo, add_outcome, allowance_data, assert_transfer_allowed, balance_data, checked_sub,
+    perform_transfer, spend_allowance, validate_memo, TransferOutcome, PREFIX_CONFIG,
+};
+use crate::error::ContractError;
+use crate::events;
+use crate::msg::{FailedSendResponse, ReceiveMsg};
+use crate::pagination::{Cursor, Page, LIST_LIMITS};
+use crate::state::{SendFailureMode, SentHook};
+
+pub const PREFIX_SENT_HOOKS: &[u8] = b"sent_hooks";
+pub const KEY_SENT_HOOK_COUNT: &[u8] = b"sent_hook_count";
+
+// Hooks sent with SendFailureMode::Record are dispatched as submessages whose
+// reply id is the id of the hook. The hook is stored until its reply: a
+// successful one removes it, a failed one records the error so the sender can
+// retry it with RetrySend.
+
+// The receive hook in the cw20 format, so cw20 receivers work unchanged
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+enum ReceiverExecuteMsg {
+    Receive(ReceiveMsg),
+}
+
+fn hook_msg(hook: &SentHook) -> Result<WasmMsg, ContractError> {
+    let msg = ReceiverExecuteMsg::Receive(ReceiveMsg {
+        sender: hook.sender.to_string(),
+        amount: hook.amount,
+        msg: hook.msg.clone(),
+    });
+    Ok(WasmMsg::Execute {
+        contract_addr: hook.contract.to_string(),
+        msg: to_binary(&msg)?,
+        funds: vec![],
+    })
+}
+
+fn read_sent_hook(store: &dyn Storage, id: u64) -> Result<SentHook, ContractError> {
+    let hooks_store = ReadonlyPrefixedStorage::new(store, PREFIX_SENT_HOOKS);
+    match hooks_store.get(&id.to_be_bytes()) {
+        Some(data) => Ok(from_slice(&data)?),
+        None => Err(ContractError::FailedSendNotFound { id }),
+    }
+}
+
+fn write_sent_hook(store: &mut dyn Storage, id: u64, hook: &SentHook) -> Result<(), ContractError> {
+    let mut hooks_store = PrefixedStorage::new(store, PREFIX_SENT_HOOKS);
+    hooks_store.set(&id.to_be_bytes(), &to_vec(hook)?);
+    Ok(())
+}
+
+/// Failed receive hooks ordered by id
+pub fn read_failed_sends(
+    store: &dyn Storage,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> Result<Vec<FailedSendResponse>, ContractError> {
+    let page = Page::new(start_after, limit, &LIST_LIMITS, Order::Ascending);
+    let hooks_store = ReadonlyPrefixedStorage::new(store, PREFIX_SENT_HOOKS);
+    hooks_store
+        .range(page.start(), page.end(), page.order)
+        .take(page.limit)
+        .map(|(key, data)| {
+            let id = u64::from_key(&key)?;
+            let hook: SentHook = from_slice(&data)?;
+            Ok(FailedSendResponse {
+                id,
+                sender: hook.sender.to_string(),
+                contract: hook.contract.to_string(),
+                amount: hook.amount,
+                msg: hook.msg,
+                error: hook.error.unwrap_or_default(),
+            })
+        })
+        .collect()
+}
+
+/// Transfer `amount` to `contract` and call its receive hook with `msg`.
+///
+/// With `SendFailureMode::Revert` a failing hook reverts the transfer. With
+/// `SendFailureMode::Record` the transfer stands and the failure is recorded
+/// for a later RetrySend.
+#[allow(clippy::too_many_arguments)]
+pub fn try_send(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    contract: String,
+    amount: Uint128,
+    msg: Binary,
+    on_failure: Option<SendFailureMode>,
+    memo: Option<String>,
+) -> Result<Response, ContractError> {
+    validate_memo(&memo)?;
+    let contract = deps.api.addr_validate(&contract)?;
+    let hook = SentHook {
+        sender: info.sender.clone(),
+        contract: contract.clone(),
+        amount,
+        msg,
+        error: None,
+    };
+    let (submsg, outcome) = send_tokens(
+        deps.storage,
+        deps.api,
+        &deps.querier,
+        &env,
+        &info.sender,
+        hook,
+        on_failure,
+    )?;
+
+    let event = events::transfer_event(
+        deps.storage,
+        deps.api,
+        &info.sender,
+        &contract,
+        amount.u128(),
+        outcome,
+    )?;
+    let res = Response::new()
+        .set_data(balance_data(deps.storage, deps.api, &info.sender)?)
+        .add_submessage(submsg)
+        .add_event(event)
+        .add_attribute("action", "send")
+        .add_attribute("sender", info.sender)
+        .add_attribute("contract", contract);
+    Ok(add_memo(add_outcome(res, outcome), memo))
+}
+
+/// Send tokens of `owner` within the allowance of the signer, as cw20
+/// SendFrom. The hook sees the signer as sender and a failing hook reverts.
+pub fn try_send_from(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    owner: String,
+    contract: String,
+    amount: Uint128,
+    msg: Binary,
+) -> Result<Response, ContractError> {
+    let owner = deps.api.addr_validate(&owner)?;
+    let contract = deps.api.addr_validate(&contract)?;
+    let allowance = spend_allowance(
+        deps.storage,
+        deps.api,
+        &env,
+        &owner,
+        &info.sender,
+        amount.u128(),
+    )?;
+    let hook = SentHook {
+        sender: info.sender.clone(),
+        contract: contract.clone(),
+        amount,
+        msg,
+        error: None,
+    };
+    let (submsg, outcome) = send_tokens(
+        deps.storage,
+        deps.api,
+        &deps.querier,
+        &env,
+        &owner,
+        hook,
+        None,
+    )?;
+
+    let event = events::transfer_event(
+        deps.storage,
+        deps.api,
+        &owner,
+        &contract,
+        amount.u128(),
+        outcome,
+    )?;
+    let res = Response::new()
+        .set_data(allowance_data(allowance)?)
+        .add_submessage(submsg)
+        .add_event(event)
+        .add_event(events::approve_event(&owner, &info.sender, allowance))
+        .add_attribute("action", "send_from")
+        .add_attribute("spender", info.sender)
+        .add_attribute("sender", owner)
+        .add_attribute("contract", contract);
+    Ok(add_outcome(res, outcome))
+}
+
+// Moves `hook.amount` from `owner` to the hook's contract and builds the hook
+// submessage, carrying the amount net of fees and burns
+fn send_tokens(
+    store: &mut dyn Storage,
+    api: &dyn Api,
+    querier: &QuerierWrapper,
+    env: &Env,
+    owner: &Addr,
+    mut hook: SentHook,
+    on_failure: Option<SendFailureMode>,
+) -> Result<(SubMsg, TransferOutcome), ContractError> {
+    let amount = hook.amount.u128();
+    assert_transfer_allowed(store, owner, amount)?;
+    let outcome = perform_transfer(store, api, querier, env, owner, &hook.contract, amount)?;
+    if let TransferOutcome::Queued { .. } = outcome {
+        return Err(ContractError::SendTimelocked {});
+    }
+    hook.amount = Uint128::from(checked_sub(amount, outcome.withheld(amount)?)?);
+
+    let submsg = match on_failure.unwrap_or(SendFailureMode::Revert) {
+        SendFailureMode::Revert => SubMsg::new(hook_msg(&hook)?),
+        SendFailureMode::Record => {
+            let mut config_store = PrefixedStorage::new(store, PREFIX_CONFIG);
+            let id = match config_store.get(KEY_SENT_HOOK_COUNT) {
+                Some(data) => match data[..].try_into() {
+                    Ok(bytes) => u64::from_be_bytes(bytes),
+                    Err(_) => return Err(ContractError::CorruptedDataFound {}),
+                },
+                None => 0u64,
+            };
+            let next_id = id.checked_add(1).ok_or(ContractError::Overflow {})?;
+            config_store.set(KEY_SENT_HOOK_COUNT, &next_id.to_be_bytes());
+            write_sent_hook(store, id, &hook)?;
+            SubMsg::reply_always(hook_msg(&hook)?, id)
+        }
+    };
+    Ok((submsg, outcome))
+}
+
+/// Whether `address` is a contract, i.e. the chain has contract info for it
+pub fn is_contract(querier: &QuerierWrapper, address: &Addr) -> bool {
+    let query = WasmQuery::ContractInfo {
+        contract_addr: address.to_string(),
+    };
+    querier.query::<ContractInfoResponse>(&query.into()).is_ok()
+}
+
+/// Call the receive hook of a failed send again. Original sender only.
+pub fn try_retry_send(
+    deps: DepsMut,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    let hook = read_sent_hook(deps.storage, id)?;
+    if hook.sender != info.sender {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    Ok(Response::new()
+        .add_submessage(SubMsg::reply_always(hook_msg(&hook)?, id))
+        .add_attribute("action", "retry_send")
+        .add_attribute("id", id.to_string()))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, _env: Env, reply: Reply) -> Result<Response, ContractError> {
+    let mut hook = read_sent_hook(deps.storage, reply.id)?;
+    match reply.result.into_result() {
+        Ok(_) => {
+            let mut hooks_store = PrefixedStorage::new(deps.storage, PREFIX_SENT_HOOKS);
+            hooks_store.remove(&reply.id.to_be_bytes());
+            Ok(Response::new()
+                .add_attribute("action", "send_delivered")
+                .add_attribute("id", reply.id.to_string()))
+        }
+        Err(error) => {
+            hook.error = Some(error.clone());
+            write_sent_hook(deps.storage, reply.id, &hook)?;
+            Ok(Response::new()
+                .add_attribute("action", "send_failed")
+                .add_attribute("id", reply.id.to_string())
+                .add_attribute("error", error))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate, query, read_balance, MAX_MEMO_BYTES};
+    use crate::msg::{ExecuteMsg, FailedSendsResponse, InitialBalance, InstantiateMsg, QueryMsg};
+    use crate::testing::default_instantiate_msg;
+    use cosmwasm_std::testing::{mock_dependencies_with_balance, mock_env, mock_info};
+    use cosmwasm_std::{
+        attr, ContractResult, Deps, ReplyOn, SubMsgResponse, SubMsgResult, SystemError,
+        SystemResult,
+    };
+
+    fn setup(deps: DepsMut) {
+        let instantiate_msg = InstantiateMsg {
+            initial_balances: vec![InitialBalance {
+                address: "addr0000".to_string(),
+                amount: Uint128::from(1000u128),
+            }],
+            ..default_instantiate_msg()
+        };
+        instantiate(deps, mock_env(), mock_info("creator", &[]), instantiate_msg).unwrap();
+    }
+
+    fn send(deps: DepsMut, on_failure: Option<SendFailureMode>) -> Response {
+        let msg = ExecuteMsg::Send {
+            contract: "vault".to_string(),
+            amount: Uint128::from(100u128),
+            msg: Binary::from(b"{}".to_vec()),
+            on_failure,
+            memo: None,
+        };
+        execute(deps, mock_env(), mock_info("addr0000", &[]), msg).unwrap()
+    }
+
+    fn failed_sends(deps: Deps) -> FailedSendsResponse {
+        let msg = QueryMsg::FailedSends {
+            start_after: None,
+            limit: None,
+        };
+        from_slice(&query(deps, mock_env(), msg).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn send_calls_the_receive_hook() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        let res = send(deps.as_mut(), None);
+
+        assert_eq!(res.messages.len(), 1);
+        assert_eq!(res.messages[0].reply_on, ReplyOn::Never);
+        let expected = ReceiverExecuteMsg::Receive(ReceiveMsg {
+            sender: "addr0000".to_string(),
+            amount: Uint128::from(100u128),
+            msg: Binary::from(b"{}".to_vec()),
+        });
+        assert_eq!(
+            res.messages[0].msg,
+            WasmMsg::Execute {
+                contract_addr: "vault".to_string(),
+                msg: to_binary(&expected).unwrap(),
+                funds: vec![],
+            }
+            .into()
+        );
+        let vault = read_balance(&deps.storage, &deps.api, &Addr::unchecked("vault")).unwrap();
+        assert_eq!(vault, 100);
+        assert!(failed_sends(deps.as_ref()).sends.is_empty());
+    }
+
+    #[test]
+    fn send_emits_the_memo() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        let send = |memo: &str| ExecuteMsg::Send {
+            contract: "vault".to_string(),
+            amount: Uint128::from(100u128),
+            msg: Binary::from(b"{}".to_vec()),
+            on_failure: None,
+            memo: Some(memo.to_string()),
+        };
+        let info = mock_info("addr0000", &[]);
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), send("deposit 42")).unwrap();
+        assert!(res.attributes.contains(&attr("memo", "deposit 42")));
+
+        let memo = "x".repeat(MAX_MEMO_BYTES + 1);
+        match execute(deps.as_mut(), mock_env(), info, send(&memo)).unwrap_err() {
+            ContractError::MemoTooLong { .. } => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        let vault = read_balance(&deps.storage, &deps.api, &Addr::unchecked("vault")).unwrap();
+        assert_eq!(vault, 100);
+    }
+
+    #[test]
+    fn send_from_spends_the_allowance() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        let msg = ExecuteMsg::Approve {
+            spender: "spender".to_string(),
+            amount: Uint128::from(150u128),
+            expected_current: None,
+            on_behalf_of: None,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+        let msg = ExecuteMsg::SendFrom {
+            owner: "addr0000".to_string(),
+            contract: "vault".to_string(),
+            amount: Uint128::from(100u128),
+            msg: Binary::from(b"{}".to_vec()),
+        };
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("spender", &[]),
+            msg.clone(),
+        )
+        .unwrap();
+        let expected = ReceiverExecuteMsg::Receive(ReceiveMsg {
+            sender: "spender".to_string(),
+            amount: Uint128::from(100u128),
+            msg: Binary::from(b"{}".to_vec()),
+        });
+        assert_eq!(
+            res.messages[0].msg,
+            WasmMsg::Execute {
+                contract_addr: "vault".to_string(),
+                msg: to_binary(&expected).unwrap(),
+                funds: vec![],
+            }
+            .into()
+        );
+        assert_eq!(
+            read_balance(&deps.storage, &deps.api, &Addr::unchecked("addr0000")).unwrap(),
+            900
+        );
+        assert_eq!(
+            read_balance(&deps.storage, &deps.api, &Addr::unchecked("vault")).unwrap(),
+            100
+        );
+
+        match execute(deps.as_mut(), mock_env(), mock_info("spender", &[]), msg).unwrap_err() {
+            ContractError::InsufficientAllowance { allowance, .. } => assert_eq!(allowance, 50),
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn transfer_and_call_detects_contracts() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        deps.querier.update_wasm(|query| match query {
+            WasmQuery::ContractInfo { contract_addr } if contract_addr == "vault" => {
+                let info = br#"{"code_id":1,"creator":"creator","admin":null,"pinned":false,"ibc_port":null}"#;
+                SystemResult::Ok(ContractResult::Ok(Binary::from(&info[..])))
+            }
+            WasmQuery::ContractInfo { contract_addr } => {
+                SystemResult::Err(SystemError::NoSuchContract {
+                    addr: contract_addr.clone(),
+                })
+            }
+            _ => panic!("unexpected query"),
+        });
+        let transfer_and_call = |recipient: &str| ExecuteMsg::TransferAndCall {
+            recipient: recipient.to_string(),
+            amount: Uint128::from(100u128),
+            msg: Binary::from(b"{}".to_vec()),
+        };
+
+        let msg = transfer_and_call("vault");
+        let res = execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+        assert_eq!(res.messages.len(), 1);
+        assert!(res.attributes.contains(&attr("action", "send")));
+
+        let msg = transfer_and_call("addr1111");
+        let res = execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+        assert!(res.messages.is_empty());
+        assert!(res.attributes.contains(&attr("action", "transfer")));
+        let balance = read_balance(&deps.storage, &deps.api, &Addr::unchecked("addr1111")).unwrap();
+        assert_eq!(balance, 100);
+    }
+
+    #[test]
+    fn recorded_failures_can_be_retried() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        let res = send(deps.as_mut(), Some(SendFailureMode::Record));
+        assert_eq!(res.messages[0].id, 0);
+        assert_eq!(res.messages[0].reply_on, ReplyOn::Always);
+
+        let failure = Reply {
+            id: 0,
+            result: SubMsgResult::Err("out of gas".to_string()),
+        };
+        reply(deps.as_mut(), mock_env(), failure).unwrap();
+        let res = failed_sends(deps.as_ref());
+        assert_eq!(res.sends.len(), 1);
+        assert_eq!(res.sends[0].contract, "vault");
+        assert_eq!(res.sends[0].error, "out of gas");
+
+        let msg = ExecuteMsg::RetrySend { id: 0 };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("addr1111", &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        match err {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        let res = execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+        assert_eq!(res.messages[0].id, 0);
+
+        let success = Reply {
+            id: 0,
+            result: SubMsgResult::Ok(SubMsgResponse {
+                events: vec![],
+                data: None,
+            }),
+        };
+        reply(deps.as_mut(), mock_env(), success).unwrap();
+        assert!(failed_sends(deps.as_ref()).sends.is_empty());
+    }
+}
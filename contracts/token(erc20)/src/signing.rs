@@ -0,0 +1,155 @@
+use bech32::FromBase32;
+use cosmwasm_std::{to_vec, Addr, Api, Env};
+use ripemd::Ripemd160;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::error::ContractError;
+
+/// Name of the signing domain, shared by every message type of this token
+pub const SIGNING_DOMAIN: &str = "shard-token";
+/// Version of the signing payload format. Must be bumped on any layout change.
+pub const SIGNING_VERSION: u8 = 1;
+
+/// Binds a signature to one deployment of the token, so it cannot be replayed
+/// on another chain or against another contract.
+#[derive(Serialize)]
+struct Domain<'a> {
+    chain_id: &'a str,
+    contract: &'a str,
+    name: &'a str,
+    version: u8,
+}
+
+// Field names of every struct serialized here must be declared in
+// lexicographic order, that is what makes the JSON canonical.
+#[derive(Serialize)]
+struct Envelope<'a, T: Serialize> {
+    domain: Domain<'a>,
+    message: &'a T,
+    #[serde(rename = "type")]
+    message_type: &'a str,
+}
+
+/// Canonical signing payload of `message`
+///
+/// The payload is compact JSON (no whitespace) with the keys of every object
+/// sorted, amounts as decimal strings and integers as numbers:
+///
+/// `{"domain":{"chain_id":..,"contract":..,"name":"shard-token","version":1},"message":{..},"type":..}`
+///
+/// Wallets sign the SHA-256 hash of these bytes, see `signing_hash`.
+pub fn signing_bytes<T: Serialize>(
+    env: &Env,
+    message_type: &str,
+    message: &T,
+) -> Result<Vec<u8>, ContractError> {
+    Ok(to_vec(&Envelope {
+        domain: Domain {
+            chain_id: &env.block.chain_id,
+            contract: env.contract.address.as_str(),
+            name: SIGNING_DOMAIN,
+            version: SIGNING_VERSION,
+        },
+        message,
+        message_type,
+    })?)
+}
+
+pub fn signing_hash<T: Serialize>(
+    env: &Env,
+    message_type: &str,
+    message: &T,
+) -> Result<Vec<u8>, ContractError> {
+    Ok(Sha256::digest(&signing_bytes(env, message_type, message)?).to_vec())
+}
+
+/// Verifies that `signer` signed `message` with the key `public_key`
+///
+/// Errors with InvalidSignature if the signature does not match or the key does
+/// not belong to `signer`.
+pub fn verify_signature<T: Serialize>(
+    api: &dyn Api,
+    env: &Env,
+    message_type: &str,
+    message: &T,
+    signer: &Addr,
+    public_key: &[u8],
+    signature: &[u8],
+) -> Result<(), ContractError> {
+    if !pubkey_matches_address(public_key, signer.as_str()) {
+        return Err(ContractError::InvalidSignature {});
+    }
+    let hash = signing_hash(env, message_type, message)?;
+    match api.secp256k1_verify(&hash, signature, public_key) {
+        Ok(true) => Ok(()),
+        _ => Err(ContractError::InvalidSignature {}),
+    }
+}
+
+// Cosmos SDK account addresses are the bech32 encoding of
+// ripemd160(sha256(compressed public key)) with a chain specific prefix
+fn pubkey_matches_address(public_key: &[u8], address: &str) -> bool {
+    let data = match bech32::decode(address) {
+        Ok((_, data, _)) => data,
+        Err(_) => return false,
+    };
+    let raw = match Vec::<u8>::from_base32(&data) {
+        Ok(raw) => raw,
+        Err(_) => return false,
+    };
+    let hash = Ripemd160::digest(Sha256::digest(public_key));
+    raw.as_slice() == hash.as_slice()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cosmwasm_std::testing::mock_env;
+    use cosmwasm_std::Uint128;
+
+    #[derive(Serialize)]
+    struct Message {
+        amount: Uint128,
+        deadline: u64,
+        to: String,
+    }
+
+    fn message() -> Message {
+        Message {
+            amount: Uint128::from(500u128),
+            deadline: 1000,
+            to: "addr1111".to_string(),
+        }
+    }
+
+    #[test]
+    fn payload_is_canonical_json() {
+        let bytes = signing_bytes(&mock_env(), "example", &message()).unwrap();
+        assert_eq!(
+            String::from_utf8(bytes).unwrap(),
+            concat!(
+                r#"{"domain":{"chain_id":"cosmos-testnet-14002","contract":"cosmos2contract","#,
+                r#""name":"shard-token","version":1},"#,
+                r#""message":{"amount":"500","deadline":1000,"to":"addr1111"},"type":"example"}"#
+            )
+        );
+    }
+
+    #[test]
+    fn hash_test_vector() {
+        let hash = signing_hash(&mock_env(), "example", &message()).unwrap();
+        assert_eq!(
+            hex::encode(hash),
+            "260ce5452ec2070606097abd036f80cd9118379b3e62cb22e46843306c6a6c50"
+        );
+    }
+
+    #[test]
+    fn domain_separates_contracts() {
+        let mut env = mock_env();
+        let hash = signing_hash(&env, "example", &message()).unwrap();
+        env.contract.address = Addr::unchecked("cosmos2other");
+        assert_ne!(hash, signing_hash(&env, "example", &message()).unwrap());
+    }
+}
@@ -0,0 +1,210 @@
+use cosmwasm_std::{Addr, Deps, Env, Order, Storage, Uint128};
+use std::collections::BTreeMap;
+use std::ops::Bound;
+
+use crate::contract::{assert_transfer_allowed, checked_sub, perform_transfer, TransferOutcome};
+use crate::msg::SimulateTransferResponse;
+
+// A transfer is simulated by running the real transfer path against a storage
+// that buffers all writes in memory, so every check applies exactly as it would
+// on execution and nothing reaches the contract's state.
+
+struct Overlay<'a> {
+    base: &'a dyn Storage,
+    /// Pending writes, `None` for removed keys
+    changes: BTreeMap<Vec<u8>, Option<Vec<u8>>>,
+}
+
+impl<'a> Overlay<'a> {
+    fn new(base: &'a dyn Storage) -> Self {
+        Overlay {
+            base,
+            changes: BTreeMap::new(),
+        }
+    }
+}
+
+impl<'a> Storage for Overlay<'a> {
+    fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        match self.changes.get(key) {
+            Some(value) => value.clone(),
+            None => self.base.get(key),
+        }
+    }
+
+    fn range<'b>(
+        &'b self,
+        start: Option<&[u8]>,
+        end: Option<&[u8]>,
+        order: Order,
+    ) -> Box<dyn Iterator<Item = (Vec<u8>, Vec<u8>)> + 'b> {
+        let mut merged: BTreeMap<Vec<u8>, Vec<u8>> =
+            self.base.range(start, end, Order::Ascending).collect();
+        let lower = start.map_or(Bound::Unbounded, |start| Bound::Included(start.to_vec()));
+        let upper = end.map_or(Bound::Unbounded, |end| Bound::Excluded(end.to_vec()));
+        for (key, value) in self.changes.range((lower, upper)) {
+            match value {
+                Some(value) => merged.insert(key.clone(), value.clone()),
+                None => merged.remove(key),
+            };
+        }
+        match order {
+            Order::Ascending => Box::new(merged.into_iter()),
+            Order::Descending => Box::new(merged.into_iter().rev()),
+        }
+    }
+
+    fn set(&mut self, key: &[u8], value: &[u8]) {
+        self.changes.insert(key.to_vec(), Some(value.to_vec()));
+    }
+
+    fn remove(&mut self, key: &[u8]) {
+        self.changes.insert(key.to_vec(), None);
+    }
+}
+
+/// What a transfer of `amount` from `sender` to `recipient` would do right now.
+/// A transfer that would fail is reported through `error` rather than failing
+/// the query.
+pub fn simulate_transfer(
+    deps: Deps,
+    env: &Env,
+    sender: &Addr,
+    recipient: &Addr,
+    amount: u128,
+) -> SimulateTransferResponse {
+    let mut store = Overlay::new(deps.storage);
+    let result = assert_transfer_allowed(&store, sender, amount)
+        .and_then(|_| {
+            perform_transfer(
+                &mut store,
+                deps.api,
+                &deps.querier,
+                env,
+                sender,
+                recipient,
+                amount,
+            )
+        })
+        .and_then(|outcome| Ok((outcome, checked_sub(amount, outcome.withheld(amount)?)?)));
+    match result {
+        Ok((TransferOutcome::Completed { fee, burned }, net_amount)) => SimulateTransferResponse {
+            fee: Uint128::from(fee),
+            burned: Uint128::from(burned),
+            net_amount: Uint128::from(net_amount),
+            queued: false,
+            error: None,
+        },
+        Ok((TransferOutcome::Queued { .. }, _)) => SimulateTransferResponse {
+            fee: Uint128::zero(),
+            burned: Uint128::zero(),
+            net_amount: Uint128::zero(),
+            queued: true,
+            error: None,
+        },
+        Err(err) => SimulateTransferResponse {
+            fee: Uint128::zero(),
+            burned: Uint128::zero(),
+            net_amount: Uint128::zero(),
+            queued: false,
+            error: Some(err.to_string()),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::contract::{execute, instantiate, query, read_balance};
+    use crate::error::ContractError;
+    use crate::limits;
+    use crate::msg::{
+        ExecuteMsg, InitialBalance, InstantiateMsg, QueryMsg, SimulateTransferResponse,
+    };
+    use crate::testing::default_instantiate_msg;
+    use cosmwasm_std::testing::{mock_dependencies_with_balance, mock_env, mock_info};
+    use cosmwasm_std::{from_slice, Addr, Deps, DepsMut, Uint128};
+
+    fn setup(mut deps: DepsMut) {
+        let instantiate_msg = InstantiateMsg {
+            initial_balances: vec![InitialBalance {
+                address: "addr0000".to_string(),
+                amount: Uint128::from(10_000u128),
+            }],
+            ..default_instantiate_msg()
+        };
+        instantiate(
+            deps.branch(),
+            mock_env(),
+            mock_info("creator", &[]),
+            instantiate_msg,
+        )
+        .unwrap();
+        let msg = ExecuteMsg::UpdateFeeConfig {
+            enabled: true,
+            rate_bps: 100,
+            treasury: "treasury".to_string(),
+        };
+        execute(deps, mock_env(), mock_info("creator", &[]), msg).unwrap();
+    }
+
+    fn simulate(deps: Deps, amount: u128) -> SimulateTransferResponse {
+        let query_msg = QueryMsg::SimulateTransfer {
+            sender: "addr0000".to_string(),
+            recipient: "addr1111".to_string(),
+            amount: Uint128::from(amount),
+        };
+        from_slice(&query(deps, mock_env(), query_msg).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn reports_fee_and_net_amount() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+
+        let res = simulate(deps.as_ref(), 1000);
+        assert_eq!(res.fee, Uint128::from(10u128));
+        assert_eq!(res.net_amount, Uint128::from(990u128));
+        assert!(!res.queued);
+        assert_eq!(res.error, None);
+
+        let balance =
+            |address: &str| read_balance(&deps.storage, &deps.api, &Addr::unchecked(address));
+        assert_eq!(balance("addr0000").unwrap(), 10_000);
+        assert_eq!(balance("addr1111").unwrap(), 0);
+        assert_eq!(balance("treasury").unwrap(), 0);
+    }
+
+    #[test]
+    fn reports_failure_instead_of_erroring() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+
+        let res = simulate(deps.as_ref(), 10_001);
+        assert_eq!(res.net_amount, Uint128::zero());
+        assert_eq!(
+            res.error,
+            Some("Insufficient funds (balance 10000, required=10001)".to_string())
+        );
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            ExecuteMsg::Pause {},
+        )
+        .unwrap();
+        assert!(simulate(deps.as_ref(), 1000).error.is_some());
+    }
+
+    #[test]
+    fn reports_rejected_zero_transfer() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        assert_eq!(simulate(deps.as_ref(), 0).error, None);
+
+        limits::write_allow_zero_transfers(&mut deps.storage, false);
+        let res = simulate(deps.as_ref(), 0);
+        assert_eq!(res.error, Some(ContractError::ZeroTransfer {}.to_string()));
+        assert!(!res.queued);
+    }
+}
@@ -1,9 +1,292 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use cosmwasm_std::{Addr, Binary, Decimal, Uint128};
+use cw_utils::Expiration;
+
+use crate::msg::ExecuteMsg;
+
 #[derive(Serialize, Debug, Deserialize, Clone, PartialEq, JsonSchema)]
 pub struct Constants {
     pub name: String,
     pub symbol: String,
     pub decimals: u8,
 }
+
+/// A named treasury allocation held on the contract's own account
+#[derive(Serialize, Debug, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct Bucket {
+    pub spenders: Vec<Addr>,
+    pub spend_limit: Option<Uint128>,
+    pub balance: Uint128,
+}
+
+#[derive(Serialize, Debug, Deserialize, Clone, Default, PartialEq, JsonSchema)]
+pub struct PauseState {
+    pub paused: bool,
+    /// Block time in seconds of the latest pause
+    pub paused_at: u64,
+    /// Block height of the latest pause, the snapshot for unpause votes
+    pub paused_height: u64,
+    /// Incremented on every pause to scope unpause votes
+    pub pause_id: u64,
+    pub unpause_votes: Uint128,
+    /// Pause duration in seconds after which holders may vote to unpause
+    pub max_pause_duration: Option<u64>,
+    /// Block time in seconds of the latest unpause by holder vote
+    #[serde(default)]
+    pub vote_unpaused_at: Option<u64>,
+}
+
+#[derive(Serialize, Debug, Deserialize, Clone, Default, PartialEq, JsonSchema)]
+pub struct FeeConfig {
+    pub enabled: bool,
+    /// Fee in basis points of the transferred amount
+    pub rate_bps: u16,
+    /// Receiver of the fees
+    pub treasury: Option<Addr>,
+}
+
+/// Volume-responsive fee. The rate of an epoch is picked by the transfer volume
+/// of the previous epoch.
+#[derive(Serialize, Debug, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct FeeCurve {
+    /// Epoch length in seconds
+    pub epoch_length: u64,
+    /// Ordered by ascending volume. Below the first step the fixed rate of the
+    /// fee config applies.
+    pub steps: Vec<FeeStep>,
+}
+
+#[derive(Serialize, Debug, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct FeeStep {
+    /// Previous epoch volume from which on `rate_bps` applies
+    pub volume: Uint128,
+    pub rate_bps: u16,
+}
+
+#[derive(Serialize, Debug, Deserialize, Clone, Default, PartialEq, JsonSchema)]
+pub struct FeeVolume {
+    pub epoch: u64,
+    pub volume: Uint128,
+    pub previous_volume: Uint128,
+}
+
+/// Anti-whale limits, unlimited when unset
+#[derive(Serialize, Debug, Deserialize, Clone, Default, PartialEq, JsonSchema)]
+pub struct TransferLimits {
+    pub max_tx_amount: Option<Uint128>,
+    pub max_wallet_amount: Option<Uint128>,
+    pub exempt: Vec<Addr>,
+}
+
+/// Share of every transfer destroyed, disabled at zero
+#[derive(Serialize, Debug, Deserialize, Clone, Default, PartialEq, JsonSchema)]
+pub struct TransferBurn {
+    pub rate_bps: u16,
+    pub exempt: Vec<Addr>,
+}
+
+/// At most `max_amount` may leave an account within any `window` seconds
+#[derive(Serialize, Debug, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct RateLimit {
+    pub max_amount: Uint128,
+    pub window: u64,
+}
+
+#[derive(Serialize, Debug, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct LargeTransferPolicy {
+    pub threshold: Uint128,
+    /// Seconds a pending transfer can be confirmed in
+    pub window: u64,
+    pub co_signer: Option<Addr>,
+}
+
+#[derive(Serialize, Debug, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct PendingTransfer {
+    pub sender: Addr,
+    pub recipient: Addr,
+    pub amount: Uint128,
+    pub expires_at: u64,
+}
+
+/// Transfers above `threshold` wait `delay` seconds before they can be executed
+#[derive(Serialize, Debug, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct Timelock {
+    pub threshold: Uint128,
+    pub delay: u64,
+}
+
+#[derive(Serialize, Debug, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct TimelockedTransfer {
+    pub sender: Addr,
+    pub recipient: Addr,
+    pub amount: Uint128,
+    /// Block time in seconds from which on the transfer can be executed
+    pub executable_at: u64,
+    /// Spender of a queued TransferFrom, whose allowance is spent at execution
+    #[serde(default)]
+    pub spender: Option<Addr>,
+}
+
+/// Privileged message waiting for the confirmation of the second admin key
+#[derive(Serialize, Debug, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct AdminProposal {
+    pub proposer: Addr,
+    pub action: ExecuteMsg,
+    /// Block time in seconds
+    pub proposed_at: u64,
+}
+
+/// Renewable budget of a spender, see `periodic`
+#[derive(Serialize, Debug, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct PeriodicAllowance {
+    pub amount_per_period: Uint128,
+    pub period_seconds: u64,
+    /// Block time in seconds the current period began at
+    pub period_start: u64,
+    /// Spent within the current period
+    pub spent: Uint128,
+}
+
+/// Account allowed to mint, see `mint`
+#[derive(Serialize, Debug, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct Minter {
+    pub minter: Addr,
+    pub mint_deadline: Option<Expiration>,
+}
+
+/// Native coin backing the token 1:1
+#[derive(Serialize, Debug, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct WrapConfig {
+    pub denom: String,
+}
+
+/// Tokens escrowed for a recipient who may claim them in parts until `expires_at`
+#[derive(Serialize, Debug, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct Claimable {
+    pub sender: Addr,
+    pub recipient: Addr,
+    pub amount: Uint128,
+    pub claimed: Uint128,
+    pub expires_at: u64,
+}
+
+/// Tokens vesting linearly to the recipient between `start` and `end`
+#[derive(Serialize, Debug, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct Stream {
+    pub sender: Addr,
+    pub recipient: Addr,
+    pub amount: Uint128,
+    pub withdrawn: Uint128,
+    /// Block time in seconds
+    pub start: u64,
+    /// Block time in seconds
+    pub end: u64,
+}
+
+/// Premint held by the contract and released to `beneficiary`, see `drip`
+#[derive(Serialize, Debug, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct Drip {
+    pub beneficiary: Addr,
+    pub amount: Uint128,
+    pub released: Uint128,
+    /// Block time in seconds
+    pub start: u64,
+    /// Block time in seconds
+    pub end: u64,
+}
+
+/// Recurring payment from `payer` to `payee`, see `subscriptions`
+#[derive(Serialize, Debug, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct Subscription {
+    pub payer: Addr,
+    pub payee: Addr,
+    pub amount: Uint128,
+    /// Seconds between payments
+    pub interval: u64,
+    /// Block time in seconds the next payment is due at
+    pub next_payment_at: u64,
+}
+
+#[derive(Serialize, Debug, Deserialize, Clone, Copy, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReceiptMode {
+    /// Mint the receipt token, which must list this contract as minter
+    Mint,
+    /// Release receipt tokens held by this contract
+    Transfer,
+}
+
+/// What happens when the receive hook of a Send fails
+#[derive(Serialize, Debug, Deserialize, Clone, Copy, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SendFailureMode {
+    /// Revert the transfer along with the hook
+    Revert,
+    /// Keep the transfer and record the failure, see `ExecuteMsg::RetrySend`
+    Record,
+}
+
+/// Receive hook of a Send waiting for its reply, or failed
+#[derive(Serialize, Debug, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct SentHook {
+    pub sender: Addr,
+    pub contract: Addr,
+    /// Amount the contract received, after the transfer fee
+    pub amount: Uint128,
+    pub msg: Binary,
+    /// Error of the latest attempt, unset until it fails
+    pub error: Option<String>,
+}
+
+/// Secondary cw20 asset paid to burners at `ratio` receipt tokens per burned token
+#[derive(Serialize, Debug, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct BurnReceipt {
+    pub contract: Addr,
+    pub mode: ReceiptMode,
+    pub ratio: Decimal,
+}
+
+#[derive(Serialize, Debug, Deserialize, Clone, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DividendAsset {
+    Native {
+        denom: String,
+    },
+    /// This token
+    Token {},
+}
+
+/// Dividends of one asset paid per share of the holders since the first funding
+#[derive(Serialize, Debug, Deserialize, Clone, PartialEq, JsonSchema)]
+pub struct DividendPool {
+    pub asset: DividendAsset,
+    pub index: Decimal,
+}
+
+/// Dividends of a holder of one asset, settled up to the pool index `index`
+#[derive(Serialize, Debug, Deserialize, Clone, Default, PartialEq, JsonSchema)]
+pub struct DividendAccount {
+    pub index: Decimal,
+    pub pending: Uint128,
+}
+
+/// Tokens sent to the contract that are waiting to be burned, and the burned total
+#[derive(Serialize, Debug, Deserialize, Clone, Default, PartialEq, JsonSchema)]
+pub struct Buyback {
+    pub enabled: bool,
+    pub collected: Uint128,
+    pub burned: Uint128,
+}
+
+/// Amounts an account moved out within one reporting epoch
+#[derive(Serialize, Debug, Deserialize, Clone, Default, PartialEq, JsonSchema)]
+pub struct SpendingCounters {
+    /// Outgoing transfers including their fees
+    pub sent: Uint128,
+    pub fees_paid: Uint128,
+    pub burned: Uint128,
+    /// Rewards paid out in this token
+    pub rewards: Uint128,
+}
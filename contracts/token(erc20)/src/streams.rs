@@ -0,0 +1,432 @@
+use cosmwasm_std::{
+    from_slice, to_vec, Addr, DepsMut, Env, MessageInfo, Order, Response, Storage, Uint128,
+};
+use cosmwasm_storage::{PrefixedStorage, ReadonlyPrefixedStorage};
+use std::convert::TryInto;
+
+use crate::contract::{checked_add, checked_sub, escrow_deposit, escrow_payout, PREFIX_CONFIG};
+use crate::error::ContractError;
+use crate::events;
+use crate::largetransfer;
+use crate::msg::StreamResponse;
+use crate::pagination::{Cursor, Page, LIST_LIMITS};
+use crate::state::Stream;
+
+pub const PREFIX_STREAMS: &[u8] = b"streams";
+pub const PREFIX_STREAMS_BY_RECIPIENT: &[u8] = b"streams_by_recipient";
+pub const KEY_STREAM_COUNT: &[u8] = b"stream_count";
+
+// Streamed tokens are held on the contract's own account. A stream is dropped
+// once fully withdrawn or cancelled. Streams are indexed by recipient with the
+// big endian id as key.
+
+pub fn read_stream(store: &dyn Storage, id: u64) -> Result<Stream, ContractError> {
+    let streams_store = ReadonlyPrefixedStorage::new(store, PREFIX_STREAMS);
+    match streams_store.get(&id.to_be_bytes()) {
+        Some(data) => Ok(from_slice(&data)?),
+        None => Err(ContractError::StreamNotFound { id }),
+    }
+}
+
+fn write_stream(store: &mut dyn Storage, id: u64, stream: &Stream) -> Result<(), ContractError> {
+    let mut streams_store = PrefixedStorage::new(store, PREFIX_STREAMS);
+    streams_store.set(&id.to_be_bytes(), &to_vec(stream)?);
+    let namespace = [
+        PREFIX_STREAMS_BY_RECIPIENT,
+        stream.recipient.as_str().as_bytes(),
+    ];
+    let mut recipient_store = PrefixedStorage::multilevel(store, &namespace);
+    recipient_store.set(&id.to_be_bytes(), &[1]);
+    Ok(())
+}
+
+fn remove_stream(store: &mut dyn Storage, id: u64, stream: &Stream) {
+    let mut streams_store = PrefixedStorage::new(store, PREFIX_STREAMS);
+    streams_store.remove(&id.to_be_bytes());
+    let namespace = [
+        PREFIX_STREAMS_BY_RECIPIENT,
+        stream.recipient.as_str().as_bytes(),
+    ];
+    let mut recipient_store = PrefixedStorage::multilevel(store, &namespace);
+    recipient_store.remove(&id.to_be_bytes());
+}
+
+/// Amount of `stream` vested by now, linear between start and end
+pub fn vested(stream: &Stream, env: &Env) -> u128 {
+    let now = env.block.time.seconds();
+    if now <= stream.start {
+        return 0;
+    }
+    if now >= stream.end {
+        return stream.amount.u128();
+    }
+    stream
+        .amount
+        .multiply_ratio(now - stream.start, stream.end - stream.start)
+        .u128()
+}
+
+pub fn stream_response(id: u64, stream: Stream, env: &Env) -> StreamResponse {
+    let withdrawable = Uint128::from(vested(&stream, env)) - stream.withdrawn;
+    StreamResponse {
+        id,
+        sender: stream.sender.to_string(),
+        recipient: stream.recipient.to_string(),
+        amount: stream.amount,
+        withdrawn: stream.withdrawn,
+        start: stream.start,
+        end: stream.end,
+        withdrawable,
+    }
+}
+
+/// Open streams to `recipient` ordered by id
+pub fn read_streams_by_recipient(
+    store: &dyn Storage,
+    env: &Env,
+    recipient: &Addr,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> Result<Vec<StreamResponse>, ContractError> {
+    let page = Page::new(start_after, limit, &LIST_LIMITS, Order::Ascending);
+    let recipient_store = ReadonlyPrefixedStorage::multilevel(
+        store,
+        &[PREFIX_STREAMS_BY_RECIPIENT, recipient.as_str().as_bytes()],
+    );
+    recipient_store
+        .range(page.start(), page.end(), page.order)
+        .take(page.limit)
+        .map(|(key, _)| {
+            let id = u64::from_key(&key)?;
+            Ok(stream_response(id, read_stream(store, id)?, env))
+        })
+        .collect()
+}
+
+/// Lock `amount` tokens of the signer that vest linearly to `recipient`
+///
+/// @param start block time in seconds vesting starts at
+/// @param end block time in seconds everything is vested at
+pub fn try_create_stream(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipient: String,
+    amount: Uint128,
+    start: u64,
+    end: u64,
+) -> Result<Response, ContractError> {
+    let recipient_address = deps.api.addr_validate(&recipient)?;
+    if start >= end || end <= env.block.time.seconds() {
+        return Err(ContractError::InvalidStreamSchedule {});
+    }
+    largetransfer::assert_not_large(deps.storage, &info.sender, amount.u128())?;
+    let outcome = escrow_deposit(
+        deps.storage,
+        deps.api,
+        &deps.querier,
+        &env,
+        &info.sender,
+        amount.u128(),
+    )?;
+    let transfer = events::transfer_event(
+        deps.storage,
+        deps.api,
+        &info.sender,
+        &env.contract.address,
+        amount.u128(),
+        outcome,
+    )?;
+
+    let mut config_store = PrefixedStorage::new(deps.storage, PREFIX_CONFIG);
+    let id = match config_store.get(KEY_STREAM_COUNT) {
+        Some(data) => match data[..].try_into() {
+            Ok(bytes) => u64::from_be_bytes(bytes),
+            Err(_) => return Err(ContractError::CorruptedDataFound {}),
+        },
+        None => 0u64,
+    };
+    config_store.set(KEY_STREAM_COUNT, &(id + 1).to_be_bytes());
+    write_stream(
+        deps.storage,
+        id,
+        &Stream {
+            sender: info.sender.clone(),
+            recipient: recipient_address,
+            amount,
+            withdrawn: Uint128::zero(),
+            start,
+            end,
+        },
+    )?;
+
+    Ok(Response::new()
+        .add_event(transfer)
+        .add_attribute("action", "create_stream")
+        .add_attribute("id", id.to_string())
+        .add_attribute("sender", info.sender)
+        .add_attribute("recipient", recipient)
+        .add_attribute("amount", amount.to_string())
+        .add_attribute("start", start.to_string())
+        .add_attribute("end", end.to_string()))
+}
+
+/// Pay the vested, not yet withdrawn part of a stream out to its recipient.
+/// Sender or recipient only.
+pub fn try_withdraw_from_stream(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    let mut stream = read_stream(deps.storage, id)?;
+    if info.sender != stream.sender && info.sender != stream.recipient {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let amount = checked_sub(vested(&stream, &env), stream.withdrawn.u128())?;
+    stream.withdrawn = Uint128::from(checked_add(stream.withdrawn.u128(), amount)?);
+    if stream.withdrawn == stream.amount {
+        remove_stream(deps.storage, id, &stream);
+    } else {
+        write_stream(deps.storage, id, &stream)?;
+    }
+    let outcome = escrow_payout(
+        deps.storage,
+        deps.api,
+        &deps.querier,
+        &env,
+        &stream.recipient,
+        amount,
+    )?;
+    let transfer = events::transfer_event(
+        deps.storage,
+        deps.api,
+        &env.contract.address,
+        &stream.recipient,
+        amount,
+        outcome,
+    )?;
+
+    Ok(Response::new()
+        .add_event(transfer)
+        .add_attribute("action", "withdraw_from_stream")
+        .add_attribute("id", id.to_string())
+        .add_attribute("recipient", stream.recipient)
+        .add_attribute("amount", amount.to_string()))
+}
+
+/// Stop a stream: the recipient gets what vested so far, the sender the rest.
+/// Sender or recipient only.
+pub fn try_cancel_stream(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    let stream = read_stream(deps.storage, id)?;
+    if info.sender != stream.sender && info.sender != stream.recipient {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let vested = vested(&stream, &env);
+    let recipient_amount = vested - stream.withdrawn.u128();
+    let sender_amount = stream.amount.u128() - vested;
+    remove_stream(deps.storage, id, &stream);
+    let outcome = escrow_payout(
+        deps.storage,
+        deps.api,
+        &deps.querier,
+        &env,
+        &stream.recipient,
+        recipient_amount,
+    )?;
+    let to_recipient = events::transfer_event(
+        deps.storage,
+        deps.api,
+        &env.contract.address,
+        &stream.recipient,
+        recipient_amount,
+        outcome,
+    )?;
+    let outcome = escrow_payout(
+        deps.storage,
+        deps.api,
+        &deps.querier,
+        &env,
+        &stream.sender,
+        sender_amount,
+    )?;
+    let to_sender = events::transfer_event(
+        deps.storage,
+        deps.api,
+        &env.contract.address,
+        &stream.sender,
+        sender_amount,
+        outcome,
+    )?;
+
+    Ok(Response::new()
+        .add_event(to_recipient)
+        .add_event(to_sender)
+        .add_attribute("action", "cancel_stream")
+        .add_attribute("id", id.to_string())
+        .add_attribute("recipient_amount", recipient_amount.to_string())
+        .add_attribute("sender_amount", sender_amount.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate, query, read_balance};
+    use crate::msg::{ExecuteMsg, InitialBalance, InstantiateMsg, QueryMsg, StreamsResponse};
+    use crate::testing::{default_instantiate_msg, env_at};
+    use cosmwasm_std::testing::{
+        mock_dependencies_with_balance, mock_info, MockApi, MOCK_CONTRACT_ADDR,
+    };
+
+    fn setup(mut deps: DepsMut) {
+        let instantiate_msg = InstantiateMsg {
+            initial_balances: vec![InitialBalance {
+                address: "employer".to_string(),
+                amount: Uint128::from(10_000u128),
+            }],
+            ..default_instantiate_msg()
+        };
+        instantiate(
+            deps.branch(),
+            env_at(0),
+            mock_info("creator", &[]),
+            instantiate_msg,
+        )
+        .unwrap();
+        let msg = ExecuteMsg::CreateStream {
+            recipient: "employee".to_string(),
+            amount: Uint128::from(1000u128),
+            start: 1000,
+            end: 2000,
+        };
+        execute(deps, env_at(100), mock_info("employer", &[]), msg).unwrap();
+    }
+
+    fn withdraw(deps: DepsMut, time: u64) -> Result<Response, ContractError> {
+        let msg = ExecuteMsg::WithdrawFromStream { id: 0 };
+        execute(deps, env_at(time), mock_info("employee", &[]), msg)
+    }
+
+    fn balance(storage: &dyn Storage, address: &str) -> u128 {
+        read_balance(storage, &MockApi::default(), &Addr::unchecked(address)).unwrap()
+    }
+
+    #[test]
+    fn withdraws_vested_portion() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        assert_eq!(balance(&deps.storage, MOCK_CONTRACT_ADDR), 1000);
+
+        withdraw(deps.as_mut(), 500).unwrap();
+        assert_eq!(balance(&deps.storage, "employee"), 0);
+        withdraw(deps.as_mut(), 1250).unwrap();
+        assert_eq!(balance(&deps.storage, "employee"), 250);
+        withdraw(deps.as_mut(), 1500).unwrap();
+        assert_eq!(balance(&deps.storage, "employee"), 500);
+
+        let res: StreamResponse =
+            from_slice(&query(deps.as_ref(), env_at(1600), QueryMsg::Stream { id: 0 }).unwrap())
+                .unwrap();
+        assert_eq!(res.withdrawn, Uint128::from(500u128));
+        assert_eq!(res.withdrawable, Uint128::from(100u128));
+
+        withdraw(deps.as_mut(), 3000).unwrap();
+        assert_eq!(balance(&deps.storage, "employee"), 1000);
+        assert_eq!(balance(&deps.storage, MOCK_CONTRACT_ADDR), 0);
+        match withdraw(deps.as_mut(), 3000).unwrap_err() {
+            ContractError::StreamNotFound { id } => assert_eq!(id, 0),
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn cancel_splits_remainder() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        withdraw(deps.as_mut(), 1200).unwrap();
+
+        let msg = ExecuteMsg::CancelStream { id: 0 };
+        let err = execute(
+            deps.as_mut(),
+            env_at(1300),
+            mock_info("intruder", &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        match err {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        execute(deps.as_mut(), env_at(1300), mock_info("employer", &[]), msg).unwrap();
+        assert_eq!(balance(&deps.storage, "employee"), 300);
+        assert_eq!(balance(&deps.storage, "employer"), 9700);
+        assert_eq!(balance(&deps.storage, MOCK_CONTRACT_ADDR), 0);
+    }
+
+    #[test]
+    fn lists_streams_by_recipient() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        let msg = ExecuteMsg::CreateStream {
+            recipient: "employee".to_string(),
+            amount: Uint128::from(500u128),
+            start: 0,
+            end: 1000,
+        };
+        execute(deps.as_mut(), env_at(100), mock_info("employer", &[]), msg).unwrap();
+
+        let query_msg = QueryMsg::StreamsByRecipient {
+            recipient: "employee".to_string(),
+            start_after: None,
+            limit: None,
+        };
+        let res: StreamsResponse =
+            from_slice(&query(deps.as_ref(), env_at(500), query_msg).unwrap()).unwrap();
+        let ids: Vec<u64> = res.streams.iter().map(|stream| stream.id).collect();
+        assert_eq!(ids, vec![0, 1]);
+        assert_eq!(res.streams[1].withdrawable, Uint128::from(250u128));
+
+        let query_msg = QueryMsg::StreamsByRecipient {
+            recipient: "employee".to_string(),
+            start_after: None,
+            limit: Some(1),
+        };
+        let res: StreamsResponse =
+            from_slice(&query(deps.as_ref(), env_at(500), query_msg).unwrap()).unwrap();
+        let ids: Vec<u64> = res.streams.iter().map(|stream| stream.id).collect();
+        assert_eq!(ids, vec![0]);
+
+        let query_msg = QueryMsg::StreamsByRecipient {
+            recipient: "employee".to_string(),
+            start_after: Some(0),
+            limit: None,
+        };
+        let res: StreamsResponse =
+            from_slice(&query(deps.as_ref(), env_at(500), query_msg).unwrap()).unwrap();
+        let ids: Vec<u64> = res.streams.iter().map(|stream| stream.id).collect();
+        assert_eq!(ids, vec![1]);
+    }
+
+    #[test]
+    fn rejects_invalid_schedule() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        let msg = ExecuteMsg::CreateStream {
+            recipient: "employee".to_string(),
+            amount: Uint128::from(500u128),
+            start: 1000,
+            end: 1000,
+        };
+        match execute(deps.as_mut(), env_at(100), mock_info("employer", &[]), msg).unwrap_err() {
+            ContractError::InvalidStreamSchedule {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+}
@@ -0,0 +1,428 @@
+use cosmwasm_std::{
+    from_slice, to_vec, Addr, DepsMut, Env, MessageInfo, Order, Response, Storage, Uint128,
+};
+use cosmwasm_storage::{PrefixedStorage, ReadonlyPrefixedStorage};
+use std::convert::TryInto;
+
+use crate::contract::{add_outcome, perform_transfer, PREFIX_CONFIG};
+use crate::error::ContractError;
+use crate::events;
+use crate::largetransfer;
+use crate::msg::SubscriptionResponse;
+use crate::pagination::{Cursor, Page, LIST_LIMITS};
+use crate::state::Subscription;
+
+pub const PREFIX_SUBSCRIPTIONS: &[u8] = b"subscriptions";
+pub const PREFIX_SUBSCRIPTIONS_BY_PAYER: &[u8] = b"subscriptions_by_payer";
+pub const PREFIX_SUBSCRIPTIONS_BY_PAYEE: &[u8] = b"subscriptions_by_payee";
+pub const KEY_SUBSCRIPTION_COUNT: &[u8] = b"subscription_count";
+
+// Subscriptions pull straight from the payer's balance, nothing is escrowed. Each
+// payment advances `next_payment_at` by one interval, so missed intervals can be
+// caught up with repeated ProcessSubscription calls. Subscriptions are indexed by
+// payer and by payee with the big endian id as key.
+
+pub fn read_subscription(store: &dyn Storage, id: u64) -> Result<Subscription, ContractError> {
+    let subscriptions_store = ReadonlyPrefixedStorage::new(store, PREFIX_SUBSCRIPTIONS);
+    match subscriptions_store.get(&id.to_be_bytes()) {
+        Some(data) => Ok(from_slice(&data)?),
+        None => Err(ContractError::SubscriptionNotFound { id }),
+    }
+}
+
+fn write_subscription(
+    store: &mut dyn Storage,
+    id: u64,
+    subscription: &Subscription,
+) -> Result<(), ContractError> {
+    let mut subscriptions_store = PrefixedStorage::new(store, PREFIX_SUBSCRIPTIONS);
+    subscriptions_store.set(&id.to_be_bytes(), &to_vec(subscription)?);
+    for (prefix, address) in [
+        (PREFIX_SUBSCRIPTIONS_BY_PAYER, &subscription.payer),
+        (PREFIX_SUBSCRIPTIONS_BY_PAYEE, &subscription.payee),
+    ] {
+        let mut index_store =
+            PrefixedStorage::multilevel(store, &[prefix, address.as_str().as_bytes()]);
+        index_store.set(&id.to_be_bytes(), &[1]);
+    }
+    Ok(())
+}
+
+fn remove_subscription(store: &mut dyn Storage, id: u64, subscription: &Subscription) {
+    let mut subscriptions_store = PrefixedStorage::new(store, PREFIX_SUBSCRIPTIONS);
+    subscriptions_store.remove(&id.to_be_bytes());
+    for (prefix, address) in [
+        (PREFIX_SUBSCRIPTIONS_BY_PAYER, &subscription.payer),
+        (PREFIX_SUBSCRIPTIONS_BY_PAYEE, &subscription.payee),
+    ] {
+        let mut index_store =
+            PrefixedStorage::multilevel(store, &[prefix, address.as_str().as_bytes()]);
+        index_store.remove(&id.to_be_bytes());
+    }
+}
+
+pub fn subscription_response(id: u64, subscription: Subscription) -> SubscriptionResponse {
+    SubscriptionResponse {
+        id,
+        payer: subscription.payer.to_string(),
+        payee: subscription.payee.to_string(),
+        amount: subscription.amount,
+        interval: subscription.interval,
+        next_payment_at: subscription.next_payment_at,
+    }
+}
+
+fn read_indexed(
+    store: &dyn Storage,
+    prefix: &[u8],
+    address: &Addr,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> Result<Vec<SubscriptionResponse>, ContractError> {
+    let page = Page::new(start_after, limit, &LIST_LIMITS, Order::Ascending);
+    let index_store =
+        ReadonlyPrefixedStorage::multilevel(store, &[prefix, address.as_str().as_bytes()]);
+    index_store
+        .range(page.start(), page.end(), page.order)
+        .take(page.limit)
+        .map(|(key, _)| {
+            let id = u64::from_key(&key)?;
+            Ok(subscription_response(id, read_subscription(store, id)?))
+        })
+        .collect()
+}
+
+/// Active subscriptions paid by `payer` ordered by id
+pub fn read_subscriptions_by_payer(
+    store: &dyn Storage,
+    payer: &Addr,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> Result<Vec<SubscriptionResponse>, ContractError> {
+    read_indexed(
+        store,
+        PREFIX_SUBSCRIPTIONS_BY_PAYER,
+        payer,
+        start_after,
+        limit,
+    )
+}
+
+/// Active subscriptions paying `payee` ordered by id
+pub fn read_subscriptions_by_payee(
+    store: &dyn Storage,
+    payee: &Addr,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> Result<Vec<SubscriptionResponse>, ContractError> {
+    read_indexed(
+        store,
+        PREFIX_SUBSCRIPTIONS_BY_PAYEE,
+        payee,
+        start_after,
+        limit,
+    )
+}
+
+/// Pay `amount` of the signer's tokens to `payee` once every `interval` seconds,
+/// the first payment being due right away
+pub fn try_create_subscription(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    payee: String,
+    amount: Uint128,
+    interval: u64,
+) -> Result<Response, ContractError> {
+    let payee_address = deps.api.addr_validate(&payee)?;
+    if interval == 0 {
+        return Err(ContractError::InvalidPeriod {});
+    }
+
+    let mut config_store = PrefixedStorage::new(deps.storage, PREFIX_CONFIG);
+    let id = match config_store.get(KEY_SUBSCRIPTION_COUNT) {
+        Some(data) => match data[..].try_into() {
+            Ok(bytes) => u64::from_be_bytes(bytes),
+            Err(_) => return Err(ContractError::CorruptedDataFound {}),
+        },
+        None => 0u64,
+    };
+    config_store.set(KEY_SUBSCRIPTION_COUNT, &(id + 1).to_be_bytes());
+    write_subscription(
+        deps.storage,
+        id,
+        &Subscription {
+            payer: info.sender.clone(),
+            payee: payee_address,
+            amount,
+            interval,
+            next_payment_at: env.block.time.seconds(),
+        },
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("action", "create_subscription")
+        .add_attribute("id", id.to_string())
+        .add_attribute("payer", info.sender)
+        .add_attribute("payee", payee)
+        .add_attribute("amount", amount.to_string())
+        .add_attribute("interval", interval.to_string()))
+}
+
+/// Make the payment that is due on a subscription. Anyone can call this.
+pub fn try_process_subscription(
+    deps: DepsMut,
+    env: Env,
+    id: u64,
+) -> Result<Response, ContractError> {
+    let mut subscription = read_subscription(deps.storage, id)?;
+    if env.block.time.seconds() < subscription.next_payment_at {
+        return Err(ContractError::SubscriptionNotDue {
+            next_payment_at: subscription.next_payment_at,
+        });
+    }
+    let paid_for = subscription.next_payment_at;
+    subscription.next_payment_at = subscription
+        .next_payment_at
+        .checked_add(subscription.interval)
+        .ok_or(ContractError::Overflow {})?;
+    write_subscription(deps.storage, id, &subscription)?;
+
+    let amount = subscription.amount.u128();
+    largetransfer::assert_not_large(deps.storage, &subscription.payer, amount)?;
+    let outcome = perform_transfer(
+        deps.storage,
+        deps.api,
+        &deps.querier,
+        &env,
+        &subscription.payer,
+        &subscription.payee,
+        amount,
+    )?;
+    let event = events::transfer_event(
+        deps.storage,
+        deps.api,
+        &subscription.payer,
+        &subscription.payee,
+        amount,
+        outcome,
+    )?;
+    let res = Response::new()
+        .add_event(event)
+        .add_attribute("action", "process_subscription")
+        .add_attribute("id", id.to_string())
+        .add_attribute("payer", subscription.payer)
+        .add_attribute("payee", subscription.payee)
+        .add_attribute("amount", subscription.amount.to_string())
+        .add_attribute("paid_for", paid_for.to_string())
+        .add_attribute("next_payment_at", subscription.next_payment_at.to_string());
+    Ok(add_outcome(res, outcome))
+}
+
+/// Stop a subscription. Payer only.
+pub fn try_cancel_subscription(
+    deps: DepsMut,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    let subscription = read_subscription(deps.storage, id)?;
+    if info.sender != subscription.payer {
+        return Err(ContractError::Unauthorized {});
+    }
+    remove_subscription(deps.storage, id, &subscription);
+
+    Ok(Response::new()
+        .add_attribute("action", "cancel_subscription")
+        .add_attribute("id", id.to_string())
+        .add_attribute("payer", subscription.payer)
+        .add_attribute("payee", subscription.payee))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate, query, read_balance};
+    use crate::msg::{ExecuteMsg, InitialBalance, InstantiateMsg, QueryMsg, SubscriptionsResponse};
+    use crate::testing::{default_instantiate_msg, env_at};
+    use cosmwasm_std::testing::{mock_dependencies_with_balance, mock_info, MockApi};
+
+    fn setup(mut deps: DepsMut) {
+        let instantiate_msg = InstantiateMsg {
+            initial_balances: vec![InitialBalance {
+                address: "subscriber".to_string(),
+                amount: Uint128::from(250u128),
+            }],
+            ..default_instantiate_msg()
+        };
+        instantiate(
+            deps.branch(),
+            env_at(0),
+            mock_info("creator", &[]),
+            instantiate_msg,
+        )
+        .unwrap();
+        let msg = ExecuteMsg::CreateSubscription {
+            payee: "service".to_string(),
+            amount: Uint128::from(100u128),
+            interval: 1000,
+        };
+        execute(deps, env_at(500), mock_info("subscriber", &[]), msg).unwrap();
+    }
+
+    fn process(deps: DepsMut, time: u64) -> Result<Response, ContractError> {
+        let msg = ExecuteMsg::ProcessSubscription { id: 0 };
+        execute(deps, env_at(time), mock_info("keeper", &[]), msg)
+    }
+
+    fn balance(storage: &dyn Storage, address: &str) -> u128 {
+        read_balance(storage, &MockApi::default(), &Addr::unchecked(address)).unwrap()
+    }
+
+    #[test]
+    fn pays_once_per_interval() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+
+        process(deps.as_mut(), 500).unwrap();
+        assert_eq!(balance(&deps.storage, "service"), 100);
+        match process(deps.as_mut(), 1499).unwrap_err() {
+            ContractError::SubscriptionNotDue { next_payment_at } => {
+                assert_eq!(next_payment_at, 1500)
+            }
+            e => panic!("unexpected error: {:?}", e),
+        }
+        process(deps.as_mut(), 1500).unwrap();
+        assert_eq!(balance(&deps.storage, "service"), 200);
+        assert_eq!(balance(&deps.storage, "subscriber"), 50);
+
+        match process(deps.as_mut(), 2500).unwrap_err() {
+            ContractError::InsufficientFunds { .. } => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn catches_up_missed_intervals() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+
+        process(deps.as_mut(), 2400).unwrap();
+        process(deps.as_mut(), 2400).unwrap();
+        match process(deps.as_mut(), 2400).unwrap_err() {
+            ContractError::SubscriptionNotDue { next_payment_at } => {
+                assert_eq!(next_payment_at, 2500)
+            }
+            e => panic!("unexpected error: {:?}", e),
+        }
+        assert_eq!(balance(&deps.storage, "service"), 200);
+    }
+
+    #[test]
+    fn rejects_next_payment_overflow() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        let msg = ExecuteMsg::CreateSubscription {
+            payee: "service".to_string(),
+            amount: Uint128::from(10u128),
+            interval: u64::MAX,
+        };
+        execute(
+            deps.as_mut(),
+            env_at(700),
+            mock_info("subscriber", &[]),
+            msg,
+        )
+        .unwrap();
+        let msg = ExecuteMsg::ProcessSubscription { id: 1 };
+        match execute(deps.as_mut(), env_at(700), mock_info("keeper", &[]), msg).unwrap_err() {
+            ContractError::Overflow {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        assert_eq!(balance(&deps.storage, "service"), 0);
+    }
+
+    #[test]
+    fn payer_cancels() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+
+        let msg = ExecuteMsg::CancelSubscription { id: 0 };
+        let err = execute(
+            deps.as_mut(),
+            env_at(600),
+            mock_info("service", &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        match err {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        execute(
+            deps.as_mut(),
+            env_at(600),
+            mock_info("subscriber", &[]),
+            msg,
+        )
+        .unwrap();
+        match process(deps.as_mut(), 600).unwrap_err() {
+            ContractError::SubscriptionNotFound { id } => assert_eq!(id, 0),
+            e => panic!("unexpected error: {:?}", e),
+        }
+        let query_msg = QueryMsg::SubscriptionsByPayee {
+            payee: "service".to_string(),
+            start_after: None,
+            limit: None,
+        };
+        let res: SubscriptionsResponse =
+            from_slice(&query(deps.as_ref(), env_at(600), query_msg).unwrap()).unwrap();
+        assert!(res.subscriptions.is_empty());
+    }
+
+    #[test]
+    fn lists_subscriptions_by_payer_and_payee() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        let msg = ExecuteMsg::CreateSubscription {
+            payee: "other_service".to_string(),
+            amount: Uint128::from(10u128),
+            interval: 60,
+        };
+        execute(
+            deps.as_mut(),
+            env_at(700),
+            mock_info("subscriber", &[]),
+            msg,
+        )
+        .unwrap();
+
+        let by_payer = |start_after: Option<u64>, limit: Option<u32>| {
+            let query_msg = QueryMsg::SubscriptionsByPayer {
+                payer: "subscriber".to_string(),
+                start_after,
+                limit,
+            };
+            let res: SubscriptionsResponse =
+                from_slice(&query(deps.as_ref(), env_at(800), query_msg).unwrap()).unwrap();
+            res.subscriptions
+        };
+        let subscriptions = by_payer(None, None);
+        let ids: Vec<u64> = subscriptions.iter().map(|sub| sub.id).collect();
+        assert_eq!(ids, vec![0, 1]);
+        assert_eq!(subscriptions[1].next_payment_at, 700);
+        let ids: Vec<u64> = by_payer(None, Some(1)).iter().map(|sub| sub.id).collect();
+        assert_eq!(ids, vec![0]);
+        let ids: Vec<u64> = by_payer(Some(0), None).iter().map(|sub| sub.id).collect();
+        assert_eq!(ids, vec![1]);
+
+        let query_msg = QueryMsg::SubscriptionsByPayee {
+            payee: "other_service".to_string(),
+            start_after: None,
+            limit: None,
+        };
+        let res: SubscriptionsResponse =
+            from_slice(&query(deps.as_ref(), env_at(800), query_msg).unwrap()).unwrap();
+        assert_eq!(res.subscriptions.len(), 1);
+        assert_eq!(res.subscriptions[0].payer, "subscriber");
+    }
+}
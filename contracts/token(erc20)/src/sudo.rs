@@ -0,0 +1,118 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{DepsMut, Env, Response};
+
+use crate::clawback;
+use crate::error::ContractError;
+use crate::limits;
+use crate::msg::SudoMsg;
+use crate::pause;
+
+// Sudo messages can only be sent by the chain itself, e.g. by a governance
+// proposal, so none of them checks a sender.
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn sudo(deps: DepsMut, env: Env, msg: SudoMsg) -> Result<Response, ContractError> {
+    match msg {
+        SudoMsg::Pause {} => {
+            pause::pause(deps.storage, &env)?;
+            Ok(Response::new().add_attribute("action", "sudo_pause"))
+        }
+        SudoMsg::Unpause {} => {
+            pause::unpause(deps.storage)?;
+            Ok(Response::new().add_attribute("action", "sudo_unpause"))
+        }
+        SudoMsg::UpdateLimits {
+            max_tx_amount,
+            max_wallet_amount,
+            exempt,
+        } => {
+            let res =
+                limits::update_transfer_limits(deps, max_tx_amount, max_wallet_amount, exempt)?;
+            Ok(res.add_attribute("authority", "sudo"))
+        }
+        SudoMsg::ForceTransfer { from, to, amount } => {
+            let res = clawback::force_transfer(deps, env, from, to, amount)?;
+            Ok(res.add_attribute("authority", "sudo"))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate, read_balance};
+    use crate::msg::{ExecuteMsg, InitialBalance, InstantiateMsg};
+    use crate::testing::{default_instantiate_msg, env_at};
+    use cosmwasm_std::testing::{mock_dependencies_with_balance, mock_info, MOCK_CONTRACT_ADDR};
+    use cosmwasm_std::{Addr, Uint128};
+
+    fn setup(deps: DepsMut) {
+        let instantiate_msg = InstantiateMsg {
+            initial_balances: vec![InitialBalance {
+                address: "addr0000".to_string(),
+                amount: Uint128::from(10_000u128),
+            }],
+            ..default_instantiate_msg()
+        };
+        instantiate(deps, env_at(0), mock_info("creator", &[]), instantiate_msg).unwrap();
+    }
+
+    #[test]
+    fn chain_pauses_without_the_owner() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+
+        sudo(deps.as_mut(), env_at(100), SudoMsg::Pause {}).unwrap();
+        assert!(pause::read_pause_state(&deps.storage).unwrap().paused);
+        let msg = ExecuteMsg::Transfer {
+            recipient: "addr1111".to_string(),
+            amount: Uint128::from(1u128),
+            memo: None,
+            on_behalf_of: None,
+        };
+        let err = execute(
+            deps.as_mut(),
+            env_at(100),
+            mock_info("addr0000", &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        match err {
+            ContractError::Paused {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        sudo(deps.as_mut(), env_at(200), SudoMsg::Unpause {}).unwrap();
+        execute(deps.as_mut(), env_at(200), mock_info("addr0000", &[]), msg).unwrap();
+    }
+
+    #[test]
+    fn force_transfer_ignores_the_pause() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        sudo(deps.as_mut(), env_at(100), SudoMsg::Pause {}).unwrap();
+
+        let msg = SudoMsg::ForceTransfer {
+            from: "addr0000".to_string(),
+            to: "addr1111".to_string(),
+            amount: Uint128::from(4000u128),
+        };
+        sudo(deps.as_mut(), env_at(100), msg).unwrap();
+        let balance = |address: &str| {
+            read_balance(&deps.storage, &deps.api, &Addr::unchecked(address)).unwrap()
+        };
+        assert_eq!(balance("addr0000"), 6000);
+        assert_eq!(balance("addr1111"), 4000);
+
+        let msg = SudoMsg::ForceTransfer {
+            from: MOCK_CONTRACT_ADDR.to_string(),
+            to: "addr1111".to_string(),
+            amount: Uint128::from(1u128),
+        };
+        match sudo(deps.as_mut(), env_at(100), msg).unwrap_err() {
+            ContractError::EscrowNotMovable {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+}
@@ -0,0 +1,28 @@
+use crate::msg::InstantiateMsg;
+use cosmwasm_std::testing::mock_env;
+use cosmwasm_std::{Env, Timestamp};
+
+// A plain instantiation for tests to start from, overriding what they need
+// with struct update syntax
+pub fn default_instantiate_msg() -> InstantiateMsg {
+    InstantiateMsg {
+        name: "Cash Token".to_string(),
+        symbol: "CASH".to_string(),
+        decimals: 9,
+        initial_balances: vec![],
+        max_pause_duration: None,
+        wrapped_denom: None,
+        clawback_admin: None,
+        max_supply: None,
+        mint: None,
+        allow_zero_transfers: true,
+        drip: None,
+    }
+}
+
+// The mock environment with the block time set, for tests of anything timed
+pub fn env_at(time: u64) -> Env {
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(time);
+    env
+}
@@ -0,0 +1,476 @@
+use cosmwasm_std::{
+    from_slice, to_vec, Addr, Api, DepsMut, Env, MessageInfo, Order, Response, Storage, Uint128,
+};
+use cosmwasm_storage::{PrefixedStorage, ReadonlyPrefixedStorage};
+use std::convert::TryInto;
+
+use crate::contract::{
+    add_outcome, assert_owner, checked_add, complete_transfer, read_balance, spend_allowance,
+    TransferKind, PREFIX_CONFIG,
+};
+use crate::error::ContractError;
+use crate::events;
+use crate::locks;
+use crate::msg::TimelockedTransferResponse;
+use crate::pagination::{Cursor, Page, LIST_LIMITS};
+use crate::pause;
+use crate::state::{Timelock, TimelockedTransfer};
+
+pub const KEY_TIMELOCK: &[u8] = b"timelock";
+pub const PREFIX_TIMELOCKED_TRANSFERS: &[u8] = b"timelocked_transfers";
+pub const KEY_TIMELOCKED_TRANSFER_COUNT: &[u8] = b"timelocked_transfer_count";
+
+// In timelock mode transfers between accounts above the threshold do not move
+// tokens right away. They are queued and can be executed once the delay has
+// passed, which gives the owner the delay to cancel transfers from compromised
+// accounts. Queued tokens are not reserved, so the sender needs the balance
+// again at execution. Transfers from or to the contract itself (escrows) are
+// never queued.
+
+pub fn read_timelock(store: &dyn Storage) -> Result<Option<Timelock>, ContractError> {
+    let config_store = ReadonlyPrefixedStorage::new(store, PREFIX_CONFIG);
+    match config_store.get(KEY_TIMELOCK) {
+        Some(data) => Ok(Some(from_slice(&data)?)),
+        None => Ok(None),
+    }
+}
+
+pub fn read_timelocked_transfer(
+    store: &dyn Storage,
+    id: u64,
+) -> Result<TimelockedTransfer, ContractError> {
+    let transfers_store = ReadonlyPrefixedStorage::new(store, PREFIX_TIMELOCKED_TRANSFERS);
+    match transfers_store.get(&id.to_be_bytes()) {
+        Some(data) => Ok(from_slice(&data)?),
+        None => Err(ContractError::TimelockedTransferNotFound { id }),
+    }
+}
+
+pub fn timelocked_transfer_response(
+    id: u64,
+    transfer: TimelockedTransfer,
+) -> TimelockedTransferResponse {
+    TimelockedTransferResponse {
+        id,
+        sender: transfer.sender.to_string(),
+        recipient: transfer.recipient.to_string(),
+        amount: transfer.amount,
+        executable_at: transfer.executable_at,
+    }
+}
+
+/// Queued transfers ordered by id
+pub fn read_timelocked_transfers(
+    store: &dyn Storage,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> Result<Vec<TimelockedTransferResponse>, ContractError> {
+    let page = Page::new(start_after, limit, &LIST_LIMITS, Order::Ascending);
+    let transfers_store = ReadonlyPrefixedStorage::new(store, PREFIX_TIMELOCKED_TRANSFERS);
+    transfers_store
+        .range(page.start(), page.end(), page.order)
+        .take(page.limit)
+        .map(|(key, data)| {
+            let id = u64::from_key(&key)?;
+            Ok(timelocked_transfer_response(id, from_slice(&data)?))
+        })
+        .collect()
+}
+
+/// Turn timelock mode on, or off with `None`. Owner only. Already queued
+/// transfers stay queued.
+///
+/// @param delay seconds a queued transfer waits before it can be executed
+pub fn try_update_timelock(
+    deps: DepsMut,
+    info: MessageInfo,
+    timelock: Option<Timelock>,
+) -> Result<Response, ContractError> {
+    assert_owner(deps.storage, &info.sender)?;
+    let mut config_store = PrefixedStorage::new(deps.storage, PREFIX_CONFIG);
+    match &timelock {
+        Some(timelock) => config_store.set(KEY_TIMELOCK, &to_vec(timelock)?),
+        None => config_store.remove(KEY_TIMELOCK),
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "update_timelock")
+        .add_attribute("enabled", timelock.is_some().to_string()))
+}
+
+/// Queues the transfer if it is above the timelock threshold and returns its id
+pub fn queue_if_timelocked(
+    store: &mut dyn Storage,
+    api: &dyn Api,
+    env: &Env,
+    from: &Addr,
+    to: &Addr,
+    amount: u128,
+) -> Result<Option<u64>, ContractError> {
+    let timelock = match read_timelock(store)? {
+        Some(timelock) if amount > timelock.threshold.u128() => timelock,
+        _ => return Ok(None),
+    };
+    pause::assert_not_paused(store)?;
+    let balance = checked_add(
+        read_balance(store, api, from)?,
+        locks::read_matured(store, env, from)?,
+    )?;
+    if balance < amount {
+        return Err(ContractError::InsufficientFunds {
+            balance,
+            required: amount,
+        });
+    }
+
+    let executable_at = env
+        .block
+        .time
+        .seconds()
+        .checked_add(timelock.delay)
+        .ok_or(ContractError::Overflow {})?;
+
+    let mut config_store = PrefixedStorage::new(store, PREFIX_CONFIG);
+    let id = match config_store.get(KEY_TIMELOCKED_TRANSFER_COUNT) {
+        Some(data) => match data[..].try_into() {
+            Ok(bytes) => u64::from_be_bytes(bytes),
+            Err(_) => return Err(ContractError::CorruptedDataFound {}),
+        },
+        None => 0u64,
+    };
+    let next_id = id.checked_add(1).ok_or(ContractError::Overflow {})?;
+    config_store.set(KEY_TIMELOCKED_TRANSFER_COUNT, &next_id.to_be_bytes());
+
+    let mut transfers_store = PrefixedStorage::new(store, PREFIX_TIMELOCKED_TRANSFERS);
+    transfers_store.set(
+        &id.to_be_bytes(),
+        &to_vec(&TimelockedTransfer {
+            sender: from.clone(),
+            recipient: to.clone(),
+            amount: Uint128::from(amount),
+            executable_at,
+            spender: None,
+        })?,
+    );
+    Ok(Some(id))
+}
+
+/// Marks the queued transfer `id` as a TransferFrom of `spender`
+pub fn record_spender(
+    store: &mut dyn Storage,
+    id: u64,
+    spender: &Addr,
+) -> Result<(), ContractError> {
+    let transfer = TimelockedTransfer {
+        spender: Some(spender.clone()),
+        ..read_timelocked_transfer(store, id)?
+    };
+    let mut transfers_store = PrefixedStorage::new(store, PREFIX_TIMELOCKED_TRANSFERS);
+    transfers_store.set(&id.to_be_bytes(), &to_vec(&transfer)?);
+    Ok(())
+}
+
+/// Complete a queued transfer once its delay passed. Sender, recipient or, for a
+/// TransferFrom, the spender only. A TransferFrom spends the allowance now.
+pub fn try_execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    let transfer = read_timelocked_transfer(deps.storage, id)?;
+    if info.sender != transfer.sender
+        && info.sender != transfer.recipient
+        && transfer.spender.as_ref() != Some(&info.sender)
+    {
+        return Err(ContractError::Unauthorized {});
+    }
+    if env.block.time.seconds() < transfer.executable_at {
+        return Err(ContractError::TimelockNotExpired {
+            id,
+            executable_at: transfer.executable_at,
+        });
+    }
+
+    let mut transfers_store = PrefixedStorage::new(deps.storage, PREFIX_TIMELOCKED_TRANSFERS);
+    transfers_store.remove(&id.to_be_bytes());
+    let mut res = Response::new();
+    if let Some(spender) = &transfer.spender {
+        let allowance = spend_allowance(
+            deps.storage,
+            deps.api,
+            &env,
+            &transfer.sender,
+            spender,
+            transfer.amount.u128(),
+        )?;
+        res = res.add_event(events::approve_event(&transfer.sender, spender, allowance));
+    }
+    let outcome = complete_transfer(
+        deps.storage,
+        deps.api,
+        &env,
+        &transfer.sender,
+        &transfer.recipient,
+        transfer.amount.u128(),
+        TransferKind::Direct,
+    )?;
+    let event = events::transfer_event(
+        deps.storage,
+        deps.api,
+        &transfer.sender,
+        &transfer.recipient,
+        transfer.amount.u128(),
+        outcome,
+    )?;
+
+    let res = res
+        .add_event(event)
+        .add_attribute("action", "execute_timelocked_transfer")
+        .add_attribute("id", id.to_string())
+        .add_attribute("sender", transfer.sender)
+        .add_attribute("recipient", transfer.recipient)
+        .add_attribute("amount", transfer.amount.to_string());
+    Ok(add_outcome(res, outcome))
+}
+
+/// Drop a queued transfer before its delay passed. Owner only.
+pub fn try_cancel(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    assert_owner(deps.storage, &info.sender)?;
+    let transfer = read_timelocked_transfer(deps.storage, id)?;
+    if env.block.time.seconds() >= transfer.executable_at {
+        return Err(ContractError::TimelockExpired { id });
+    }
+    let mut transfers_store = PrefixedStorage::new(deps.storage, PREFIX_TIMELOCKED_TRANSFERS);
+    transfers_store.remove(&id.to_be_bytes());
+
+    Ok(Response::new()
+        .add_attribute("action", "cancel_timelocked_transfer")
+        .add_attribute("id", id.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate, query};
+    use crate::msg::{
+        AllowanceResponse, ExecuteMsg, InitialBalance, InstantiateMsg, QueryMsg,
+        TimelockedTransfersResponse,
+    };
+    use crate::testing::{default_instantiate_msg, env_at};
+    use cosmwasm_std::testing::{mock_dependencies_with_balance, mock_info, MockApi};
+    use cosmwasm_std::{attr, Deps};
+
+    fn setup(mut deps: DepsMut) {
+        let instantiate_msg = InstantiateMsg {
+            initial_balances: vec![InitialBalance {
+                address: "addr0000".to_string(),
+                amount: Uint128::from(10_000u128),
+            }],
+            ..default_instantiate_msg()
+        };
+        instantiate(
+            deps.branch(),
+            env_at(0),
+            mock_info("creator", &[]),
+            instantiate_msg,
+        )
+        .unwrap();
+        let msg = ExecuteMsg::UpdateTimelock {
+            timelock: Some(Timelock {
+                threshold: Uint128::from(1000u128),
+                delay: 3600,
+            }),
+        };
+        execute(deps, env_at(0), mock_info("creator", &[]), msg).unwrap();
+    }
+
+    fn transfer(deps: DepsMut, amount: u128) -> Response {
+        let msg = ExecuteMsg::Transfer {
+            recipient: "addr1111".to_string(),
+            amount: Uint128::from(amount),
+            memo: None,
+            on_behalf_of: None,
+        };
+        execute(deps, env_at(100), mock_info("addr0000", &[]), msg).unwrap()
+    }
+
+    fn balance(storage: &dyn Storage, address: &str) -> u128 {
+        read_balance(storage, &MockApi::default(), &Addr::unchecked(address)).unwrap()
+    }
+
+    #[test]
+    fn large_transfers_wait_for_the_delay() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        transfer(deps.as_mut(), 1000);
+        assert_eq!(balance(&deps.storage, "addr1111"), 1000);
+
+        let res = transfer(deps.as_mut(), 5000);
+        assert!(res
+            .attributes
+            .contains(&attr("timelocked_transfer_id", "0")));
+        assert_eq!(balance(&deps.storage, "addr1111"), 1000);
+
+        let msg = ExecuteMsg::ExecutePendingTransfer { id: 0 };
+        let err = execute(
+            deps.as_mut(),
+            env_at(3699),
+            mock_info("addr1111", &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        match err {
+            ContractError::TimelockNotExpired { id, executable_at } => {
+                assert_eq!(id, 0);
+                assert_eq!(executable_at, 3700);
+            }
+            e => panic!("unexpected error: {:?}", e),
+        }
+        execute(deps.as_mut(), env_at(3700), mock_info("addr1111", &[]), msg).unwrap();
+        assert_eq!(balance(&deps.storage, "addr1111"), 6000);
+        assert_eq!(balance(&deps.storage, "addr0000"), 4000);
+    }
+
+    #[test]
+    fn rejects_a_delay_past_the_end_of_time() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        let msg = ExecuteMsg::UpdateTimelock {
+            timelock: Some(Timelock {
+                threshold: Uint128::from(1000u128),
+                delay: u64::MAX,
+            }),
+        };
+        execute(deps.as_mut(), env_at(0), mock_info("creator", &[]), msg).unwrap();
+
+        let msg = ExecuteMsg::Transfer {
+            recipient: "addr1111".to_string(),
+            amount: Uint128::from(5000u128),
+            memo: None,
+            on_behalf_of: None,
+        };
+        match execute(deps.as_mut(), env_at(100), mock_info("addr0000", &[]), msg).unwrap_err() {
+            ContractError::Overflow {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        assert_eq!(balance(&deps.storage, "addr1111"), 0);
+    }
+
+    #[test]
+    fn owner_cancels_within_the_delay() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        transfer(deps.as_mut(), 5000);
+        transfer(deps.as_mut(), 2000);
+
+        let res: TimelockedTransfersResponse = from_slice(
+            &query(
+                deps.as_ref(),
+                env_at(100),
+                QueryMsg::TimelockedTransfers {
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(res.transfers.len(), 2);
+        assert_eq!(res.transfers[1].amount, Uint128::from(2000u128));
+
+        let msg = ExecuteMsg::CancelTimelockedTransfer { id: 0 };
+        let err = execute(
+            deps.as_mut(),
+            env_at(200),
+            mock_info("addr0000", &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        match err {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        execute(deps.as_mut(), env_at(200), mock_info("creator", &[]), msg).unwrap();
+
+        let msg = ExecuteMsg::CancelTimelockedTransfer { id: 1 };
+        let err = execute(deps.as_mut(), env_at(3700), mock_info("creator", &[]), msg).unwrap_err();
+        match err {
+            ContractError::TimelockExpired { id } => assert_eq!(id, 1),
+            e => panic!("unexpected error: {:?}", e),
+        }
+        let res = read_timelocked_transfers(&deps.storage, None, None).unwrap();
+        assert_eq!(res.len(), 1);
+        assert_eq!(res[0].id, 1);
+    }
+
+    #[test]
+    fn queued_transfer_from_spends_the_allowance_at_execution() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        let msg = ExecuteMsg::IncreaseAllowance {
+            spender: "spender".to_string(),
+            amount: Uint128::from(8000u128),
+            expires: None,
+        };
+        execute(deps.as_mut(), env_at(50), mock_info("addr0000", &[]), msg).unwrap();
+        let allowance = |deps: Deps| {
+            let query_msg = QueryMsg::Allowance {
+                owner: "addr0000".to_string(),
+                spender: "spender".to_string(),
+            };
+            let res: AllowanceResponse =
+                from_slice(&query(deps, env_at(100), query_msg).unwrap()).unwrap();
+            res.allowance.u128()
+        };
+        let transfer_from = |amount: u128| ExecuteMsg::TransferFrom {
+            owner: "addr0000".to_string(),
+            recipient: "addr1111".to_string(),
+            amount: Uint128::from(amount),
+            memo: None,
+        };
+
+        // A queued transfer holds on to the allowance until it is executed
+        execute(
+            deps.as_mut(),
+            env_at(100),
+            mock_info("spender", &[]),
+            transfer_from(5000),
+        )
+        .unwrap();
+        assert_eq!(allowance(deps.as_ref()), 8000);
+        let msg = ExecuteMsg::CancelTimelockedTransfer { id: 0 };
+        execute(deps.as_mut(), env_at(200), mock_info("creator", &[]), msg).unwrap();
+        assert_eq!(allowance(deps.as_ref()), 8000);
+
+        // Queuing more than the allowance is rejected right away
+        let err = execute(
+            deps.as_mut(),
+            env_at(100),
+            mock_info("spender", &[]),
+            transfer_from(9000),
+        )
+        .unwrap_err();
+        match err {
+            ContractError::InsufficientAllowance { .. } => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        execute(
+            deps.as_mut(),
+            env_at(100),
+            mock_info("spender", &[]),
+            transfer_from(5000),
+        )
+        .unwrap();
+        let msg = ExecuteMsg::ExecutePendingTransfer { id: 1 };
+        execute(deps.as_mut(), env_at(3700), mock_info("spender", &[]), msg).unwrap();
+        assert_eq!(allowance(deps.as_ref()), 3000);
+        assert_eq!(balance(&deps.storage, "addr1111"), 5000);
+    }
+}
@@ -0,0 +1,492 @@
+use cosmwasm_std::{
+    from_slice, to_vec, Addr, DepsMut, Env, Event, MessageInfo, Order, Response, Storage, Uint128,
+};
+use cosmwasm_storage::{PrefixedStorage, ReadonlyPrefixedStorage};
+
+use crate::contract::{
+    assert_owner, checked_add, checked_sub, escrow_deposit, escrow_payout, read_addr, PREFIX_CONFIG,
+};
+use crate::error::ContractError;
+use crate::events;
+use crate::msg::BucketResponse;
+use crate::state::Bucket;
+
+pub const PREFIX_BUCKETS: &[u8] = b"buckets";
+pub const KEY_TREASURY: &[u8] = b"treasury";
+
+/// Protocol treasury receiving transfer fees, if set
+pub fn read_treasury(store: &dyn Storage) -> Result<Option<Addr>, ContractError> {
+    let config_store = ReadonlyPrefixedStorage::new(store, PREFIX_CONFIG);
+    read_addr(&config_store, KEY_TREASURY)
+}
+
+/// Set or remove the protocol treasury. While set, transfer fees go to it
+/// rather than to the account of the fee config. Owner only.
+pub fn try_set_treasury(
+    deps: DepsMut,
+    info: MessageInfo,
+    treasury: Option<String>,
+) -> Result<Response, ContractError> {
+    assert_owner(deps.storage, &info.sender)?;
+    let old = read_treasury(deps.storage)?;
+    let mut config_store = PrefixedStorage::new(deps.storage, PREFIX_CONFIG);
+    match &treasury {
+        Some(treasury) => {
+            let treasury_address = deps.api.addr_validate(treasury.as_str())?;
+            config_store.set(KEY_TREASURY, treasury_address.as_str().as_bytes());
+        }
+        None => config_store.remove(KEY_TREASURY),
+    }
+
+    Ok(Response::new()
+        .add_event(
+            Event::new("treasury_update")
+                .add_attribute("old", old.map(|addr| addr.to_string()).unwrap_or_default())
+                .add_attribute("new", treasury.clone().unwrap_or_default()),
+        )
+        .add_attribute("action", "set_treasury")
+        .add_attribute("owner", info.sender)
+        .add_attribute("treasury", treasury.unwrap_or_default()))
+}
+
+/// Create or update a treasury bucket
+///
+/// Buckets hold tokens on the contract's own account. Only the owner can
+/// configure them; an existing bucket keeps its balance.
+///
+/// @param spenders accounts allowed to spend from the bucket
+/// @param spend_limit maximum amount a single spend may move, if any
+pub fn try_set_bucket(
+    deps: DepsMut,
+    info: MessageInfo,
+    name: String,
+    spenders: Vec<String>,
+    spend_limit: Option<Uint128>,
+) -> Result<Response, ContractError> {
+    assert_owner(deps.storage, &info.sender)?;
+    if !is_valid_bucket_name(&name) {
+        return Err(ContractError::InvalidBucketName {});
+    }
+    let spenders = spenders
+        .iter()
+        .map(|spender| deps.api.addr_validate(spender))
+        .collect::<Result<Vec<Addr>, _>>()?;
+    let balance = match may_read_bucket(deps.storage, &name)? {
+        Some(bucket) => bucket.balance,
+        None => Uint128::zero(),
+    };
+    write_bucket(
+        deps.storage,
+        &name,
+        &Bucket {
+            spenders,
+            spend_limit,
+            balance,
+        },
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("action", "set_bucket")
+        .add_attribute("bucket", name))
+}
+
+/// Move tokens from the signer's balance into a treasury bucket. Funding is not
+/// charged, any fee or transfer burn is taken when the bucket is spent.
+pub fn try_fund_bucket(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    name: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let mut bucket = read_bucket(deps.storage, &name)?;
+    let outcome = escrow_deposit(
+        deps.storage,
+        deps.api,
+        &deps.querier,
+        &env,
+        &info.sender,
+        amount.u128(),
+    )?;
+    let transfer = events::transfer_event(
+        deps.storage,
+        deps.api,
+        &info.sender,
+        &env.contract.address,
+        amount.u128(),
+        outcome,
+    )?;
+    bucket.balance = Uint128::from(checked_add(bucket.balance.u128(), amount.u128())?);
+    write_bucket(deps.storage, &name, &bucket)?;
+
+    Ok(Response::new()
+        .add_event(transfer)
+        .add_attribute("action", "fund_bucket")
+        .add_attribute("bucket", name)
+        .add_attribute("sender", info.sender)
+        .add_attribute("amount", amount.to_string()))
+}
+
+/// Reallocate tokens between two treasury buckets. Owner only.
+pub fn try_move_between_buckets(
+    deps: DepsMut,
+    info: MessageInfo,
+    from: String,
+    to: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    assert_owner(deps.storage, &info.sender)?;
+    let mut from_bucket = read_bucket(deps.storage, &from)?;
+    let mut to_bucket = read_bucket(deps.storage, &to)?;
+    if from_bucket.balance < amount {
+        return Err(ContractError::InsufficientBucketFunds {
+            balance: from_bucket.balance.u128(),
+            required: amount.u128(),
+        });
+    }
+    if from != to {
+        from_bucket.balance =
+            Uint128::from(checked_sub(from_bucket.balance.u128(), amount.u128())?);
+        to_bucket.balance = Uint128::from(checked_add(to_bucket.balance.u128(), amount.u128())?);
+        write_bucket(deps.storage, &from, &from_bucket)?;
+        write_bucket(deps.storage, &to, &to_bucket)?;
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "move_between_buckets")
+        .add_attribute("from", from)
+        .add_attribute("to", to)
+        .add_attribute("amount", amount.to_string()))
+}
+
+/// Pay out of a treasury bucket. Only the bucket's spenders can do this,
+/// and only up to the bucket's spend limit per call.
+pub fn try_spend_from_bucket(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    name: String,
+    recipient: String,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let recipient_address = deps.api.addr_validate(recipient.as_str())?;
+    let mut bucket = read_bucket(deps.storage, &name)?;
+    if !bucket.spenders.contains(&info.sender) {
+        return Err(ContractError::Unauthorized {});
+    }
+    if let Some(limit) = bucket.spend_limit {
+        if amount > limit {
+            return Err(ContractError::BucketLimitExceeded {
+                limit: limit.u128(),
+                required: amount.u128(),
+            });
+        }
+    }
+    if bucket.balance < amount {
+        return Err(ContractError::InsufficientBucketFunds {
+            balance: bucket.balance.u128(),
+            required: amount.u128(),
+        });
+    }
+    bucket.balance = Uint128::from(checked_sub(bucket.balance.u128(), amount.u128())?);
+    write_bucket(deps.storage, &name, &bucket)?;
+    let outcome = escrow_payout(
+        deps.storage,
+        deps.api,
+        &deps.querier,
+        &env,
+        &recipient_address,
+        amount.u128(),
+    )?;
+    let transfer = events::transfer_event(
+        deps.storage,
+        deps.api,
+        &env.contract.address,
+        &recipient_address,
+        amount.u128(),
+        outcome,
+    )?;
+
+    Ok(Response::new()
+        .add_event(transfer)
+        .add_attribute("action", "spend_from_bucket")
+        .add_attribute("bucket", name)
+        .add_attribute("spender", info.sender)
+        .add_attribute("recipient", recipient)
+        .add_attribute("amount", amount.to_string()))
+}
+
+pub fn query_bucket(store: &dyn Storage, name: String) -> Result<BucketResponse, ContractError> {
+    let bucket = read_bucket(store, &name)?;
+    Ok(to_response(name, bucket))
+}
+
+pub fn query_buckets(store: &dyn Storage) -> Result<Vec<BucketResponse>, ContractError> {
+    let buckets_store = ReadonlyPrefixedStorage::new(store, PREFIX_BUCKETS);
+    buckets_store
+        .range(None, None, Order::Ascending)
+        .map(|(key, data)| -> Result<BucketResponse, ContractError> {
+            let name = String::from_utf8(key).map_err(|_| ContractError::CorruptedDataFound {})?;
+            let bucket: Bucket = from_slice(&data)?;
+            Ok(to_response(name, bucket))
+        })
+        .collect()
+}
+
+fn to_response(name: String, bucket: Bucket) -> BucketResponse {
+    BucketResponse {
+        name,
+        balance: bucket.balance,
+        spenders: bucket.spenders.into_iter().map(|a| a.to_string()).collect(),
+        spend_limit: bucket.spend_limit,
+    }
+}
+
+fn may_read_bucket(store: &dyn Storage, name: &str) -> Result<Option<Bucket>, ContractError> {
+    let buckets_store = ReadonlyPrefixedStorage::new(store, PREFIX_BUCKETS);
+    match buckets_store.get(name.as_bytes()) {
+        Some(data) => Ok(Some(from_slice(&data)?)),
+        None => Ok(None),
+    }
+}
+
+fn read_bucket(store: &dyn Storage, name: &str) -> Result<Bucket, ContractError> {
+    may_read_bucket(store, name)?.ok_or_else(|| ContractError::BucketNotFound {
+        name: name.to_string(),
+    })
+}
+
+fn write_bucket(store: &mut dyn Storage, name: &str, bucket: &Bucket) -> Result<(), ContractError> {
+    let mut buckets_store = PrefixedStorage::new(store, PREFIX_BUCKETS);
+    buckets_store.set(name.as_bytes(), &to_vec(bucket)?);
+    Ok(())
+}
+
+fn is_valid_bucket_name(name: &str) -> bool {
+    let bytes = name.as_bytes();
+    if bytes.is_empty() || bytes.len() > 32 {
+        return false;
+    }
+    bytes
+        .iter()
+        .all(|byte| byte.is_ascii_lowercase() || byte.is_ascii_digit() || *byte == b'_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate, query, read_balance};
+    use crate::msg::{
+        BucketsResponse, ExecuteMsg, InitialBalance, InstantiateMsg, QueryMsg, TreasuryResponse,
+    };
+    use crate::testing::default_instantiate_msg;
+    use cosmwasm_std::testing::{mock_dependencies_with_balance, mock_env, mock_info};
+    use cosmwasm_std::Deps;
+
+    fn setup(deps: DepsMut) {
+        let instantiate_msg = InstantiateMsg {
+            initial_balances: vec![InitialBalance {
+                address: "creator".to_string(),
+                amount: Uint128::from(1000u128),
+            }],
+            ..default_instantiate_msg()
+        };
+        instantiate(deps, mock_env(), mock_info("creator", &[]), instantiate_msg).unwrap();
+    }
+
+    fn set_bucket(deps: DepsMut, name: &str, spenders: &[&str], spend_limit: Option<u128>) {
+        let msg = ExecuteMsg::SetBucket {
+            name: name.to_string(),
+            spenders: spenders.iter().map(|s| s.to_string()).collect(),
+            spend_limit: spend_limit.map(Uint128::from),
+        };
+        execute(deps, mock_env(), mock_info("creator", &[]), msg).unwrap();
+    }
+
+    fn fund_bucket(deps: DepsMut, name: &str, amount: u128) {
+        let msg = ExecuteMsg::FundBucket {
+            name: name.to_string(),
+            amount: Uint128::from(amount),
+        };
+        execute(deps, mock_env(), mock_info("creator", &[]), msg).unwrap();
+    }
+
+    fn bucket_balance(deps: Deps, name: &str) -> u128 {
+        let query_msg = QueryMsg::Bucket {
+            name: name.to_string(),
+        };
+        let res: BucketResponse = from_slice(&query(deps, mock_env(), query_msg).unwrap()).unwrap();
+        res.balance.u128()
+    }
+
+    #[test]
+    fn only_owner_can_configure_buckets() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        let msg = ExecuteMsg::SetBucket {
+            name: "marketing".to_string(),
+            spenders: vec![],
+            spend_limit: None,
+        };
+        let err = execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap_err();
+        match err {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn funds_are_held_by_the_contract() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        set_bucket(deps.as_mut(), "marketing", &["addr0000"], None);
+        fund_bucket(deps.as_mut(), "marketing", 300);
+
+        assert_eq!(bucket_balance(deps.as_ref(), "marketing"), 300);
+        let contract = mock_env().contract.address;
+        assert_eq!(
+            read_balance(&deps.storage, &deps.api, &contract).unwrap(),
+            300
+        );
+        assert_eq!(
+            read_balance(&deps.storage, &deps.api, &Addr::unchecked("creator")).unwrap(),
+            700
+        );
+    }
+
+    #[test]
+    fn spenders_can_spend_within_limit() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        set_bucket(
+            deps.as_mut(),
+            "dev_fund",
+            &["addr0000", "addr1111"],
+            Some(100),
+        );
+        fund_bucket(deps.as_mut(), "dev_fund", 300);
+
+        let spend = |amount: u128| ExecuteMsg::SpendFromBucket {
+            name: "dev_fund".to_string(),
+            recipient: "addr2222".to_string(),
+            amount: Uint128::from(amount),
+        };
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("addr1111", &[]),
+            spend(100),
+        )
+        .unwrap();
+        assert_eq!(bucket_balance(deps.as_ref(), "dev_fund"), 200);
+        assert_eq!(
+            read_balance(&deps.storage, &deps.api, &Addr::unchecked("addr2222")).unwrap(),
+            100
+        );
+
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("addr0000", &[]),
+            spend(101),
+        )
+        .unwrap_err();
+        match err {
+            ContractError::BucketLimitExceeded { limit, required } => {
+                assert_eq!(limit, 100);
+                assert_eq!(required, 101);
+            }
+            e => panic!("unexpected error: {:?}", e),
+        }
+
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            spend(1),
+        )
+        .unwrap_err();
+        match err {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn owner_moves_between_buckets() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        set_bucket(deps.as_mut(), "reserves", &[], None);
+        set_bucket(deps.as_mut(), "marketing", &["addr0000"], None);
+        fund_bucket(deps.as_mut(), "reserves", 500);
+
+        let msg = ExecuteMsg::MoveBetweenBuckets {
+            from: "reserves".to_string(),
+            to: "marketing".to_string(),
+            amount: Uint128::from(200u128),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        assert_eq!(bucket_balance(deps.as_ref(), "reserves"), 300);
+        assert_eq!(bucket_balance(deps.as_ref(), "marketing"), 200);
+
+        let query_msg = QueryMsg::Buckets {};
+        let res: BucketsResponse =
+            from_slice(&query(deps.as_ref(), mock_env(), query_msg).unwrap()).unwrap();
+        assert_eq!(res.buckets.len(), 2);
+        assert_eq!(res.buckets[0].name, "marketing");
+        assert_eq!(res.buckets[1].name, "reserves");
+    }
+
+    #[test]
+    fn fees_route_to_protocol_treasury() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut());
+        let msg = ExecuteMsg::UpdateFeeConfig {
+            enabled: true,
+            rate_bps: 100,
+            treasury: "fee_account".to_string(),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+
+        let msg = ExecuteMsg::SetTreasury {
+            treasury: Some("protocol".to_string()),
+        };
+        let err = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("addr0000", &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        match err {
+            ContractError::Unauthorized {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        assert_eq!(res.events[0].ty, "treasury_update");
+
+        let msg = ExecuteMsg::Transfer {
+            recipient: "addr0000".to_string(),
+            amount: Uint128::from(500u128),
+            memo: None,
+            on_behalf_of: None,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        let balance =
+            |address: &str| read_balance(&deps.storage, &deps.api, &Addr::unchecked(address));
+        assert_eq!(balance("protocol").unwrap(), 5);
+        assert_eq!(balance("fee_account").unwrap(), 0);
+
+        let res: TreasuryResponse =
+            from_slice(&query(deps.as_ref(), mock_env(), QueryMsg::Treasury {}).unwrap()).unwrap();
+        assert_eq!(res.treasury, Some("protocol".to_string()));
+    }
+
+    #[test]
+    fn rejects_invalid_names() {
+        assert!(is_valid_bucket_name("dev_fund"));
+        assert!(!is_valid_bucket_name(""));
+        assert!(!is_valid_bucket_name("Marketing"));
+        assert!(!is_valid_bucket_name("dev fund"));
+    }
+}
@@ -0,0 +1,338 @@
+use cosmwasm_std::{Addr, DepsMut, Env, MessageInfo, Order, Response, Storage};
+use cosmwasm_storage::{PrefixedStorage, ReadonlyPrefixedStorage};
+
+use crate::contract::{bytes_to_u128, checked_add, checked_sub, read_addr, read_shares};
+use crate::error::ContractError;
+use crate::keys;
+use crate::rebase;
+
+pub const PREFIX_DELEGATES: &[u8] = b"delegates";
+pub const PREFIX_CHECKPOINTS: &[u8] = b"checkpoints";
+pub const PREFIX_SUPPLY_CHECKPOINTS: &[u8] = b"supply_checkpoints";
+pub const PREFIX_SHARES_CHECKPOINTS: &[u8] = b"shares_checkpoints";
+
+// Votes are checkpointed in shares, like balances, so that a rebase scales the
+// voting power of every delegate along with the balances behind it.
+
+/// Delegate voting power
+///
+/// Moves the full current balance of the signer to `delegatee`. From then on every
+/// balance change of the signer is reflected in the voting power of the delegatee.
+/// Balances of accounts that never delegated (not even to themselves) do not count.
+///
+/// @param delegatee the account receiving the voting power
+pub fn try_delegate(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    delegatee: String,
+) -> Result<Response, ContractError> {
+    let delegatee_address = deps.api.addr_validate(delegatee.as_str())?;
+    let previous = read_delegate(deps.storage, &info.sender)?;
+    let shares = read_shares(deps.storage, deps.api, &info.sender)?;
+
+    let mut delegates_store = PrefixedStorage::new(deps.storage, PREFIX_DELEGATES);
+    delegates_store.set(
+        info.sender.as_str().as_bytes(),
+        delegatee_address.as_str().as_bytes(),
+    );
+    move_voting_power(
+        deps.storage,
+        env.block.height,
+        previous.as_ref(),
+        Some(&delegatee_address),
+        shares,
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("action", "delegate")
+        .add_attribute("delegator", info.sender)
+        .add_attribute(
+            "from_delegate",
+            previous.map(|addr| addr.to_string()).unwrap_or_default(),
+        )
+        .add_attribute("to_delegate", delegatee))
+}
+
+// Returns the account `delegator` delegates its voting power to, if any
+pub fn read_delegate(store: &dyn Storage, delegator: &Addr) -> Result<Option<Addr>, ContractError> {
+    let delegates_store = ReadonlyPrefixedStorage::new(store, PREFIX_DELEGATES);
+    read_addr(&delegates_store, delegator.as_str().as_bytes())
+}
+
+/// Moves `shares` votes from the delegate `from` to the delegate `to`, checkpointing
+/// both at `height`. A missing side (no delegate) is skipped.
+pub fn move_voting_power(
+    store: &mut dyn Storage,
+    height: u64,
+    from: Option<&Addr>,
+    to: Option<&Addr>,
+    shares: u128,
+) -> Result<(), ContractError> {
+    if from == to || shares == 0 {
+        return Ok(());
+    }
+    if let Some(from) = from {
+        let namespace = [PREFIX_CHECKPOINTS, from.as_str().as_bytes()];
+        let votes = read_checkpoint(store, &namespace, None)?;
+        write_checkpoint(store, &namespace, height, checked_sub(votes, shares)?);
+    }
+    if let Some(to) = to {
+        let namespace = [PREFIX_CHECKPOINTS, to.as_str().as_bytes()];
+        let votes = read_checkpoint(store, &namespace, None)?;
+        write_checkpoint(store, &namespace, height, checked_add(votes, shares)?);
+    }
+    Ok(())
+}
+
+/// Records the total supply and total shares at `height`. Must be called on every
+/// supply change, after the total shares are written.
+pub fn write_supply_checkpoint(
+    store: &mut dyn Storage,
+    height: u64,
+    total_supply: u128,
+) -> Result<(), ContractError> {
+    let total_shares = rebase::read_total_shares(store)?;
+    write_checkpoint(store, &[PREFIX_SUPPLY_CHECKPOINTS], height, total_supply);
+    write_checkpoint(store, &[PREFIX_SHARES_CHECKPOINTS], height, total_shares);
+    Ok(())
+}
+
+/// Voting power of `address` at the end of block `height`, or the current voting
+/// power if `height` is `None`
+pub fn read_voting_power(
+    store: &dyn Storage,
+    address: &Addr,
+    height: Option<u64>,
+) -> Result<u128, ContractError> {
+    let shares = read_checkpoint(
+        store,
+        &[PREFIX_CHECKPOINTS, address.as_str().as_bytes()],
+        height,
+    )?;
+    match height {
+        None => rebase::to_amount(store, shares),
+        // Heights from before total shares were checkpointed read as zero shares,
+        // which converts 1:1
+        Some(height) => Ok(keys::shares_to_balance(
+            shares,
+            read_supply_at(store, height)?,
+            read_checkpoint(store, &[PREFIX_SHARES_CHECKPOINTS], Some(height))?,
+        )),
+    }
+}
+
+/// Total supply at the end of block `height`
+pub fn read_supply_at(store: &dyn Storage, height: u64) -> Result<u128, ContractError> {
+    read_checkpoint(store, &[PREFIX_SUPPLY_CHECKPOINTS], Some(height))
+}
+
+// Checkpoints are keyed by big endian block height, so the last checkpoint at or
+// before a given height is the first entry of a descending range ending there.
+pub fn read_checkpoint(
+    store: &dyn Storage,
+    namespace: &[&[u8]],
+    height: Option<u64>,
+) -> Result<u128, ContractError> {
+    let checkpoints = ReadonlyPrefixedStorage::multilevel(store, namespace);
+    let end = height
+        .and_then(|height| height.checked_add(1))
+        .map(|height| height.to_be_bytes());
+    let last = checkpoints
+        .range(None, end.as_ref().map(|end| &end[..]), Order::Descending)
+        .next();
+    match last {
+        Some((_, data)) => bytes_to_u128(&data),
+        None => Ok(0u128),
+    }
+}
+
+pub fn write_checkpoint(store: &mut dyn Storage, namespace: &[&[u8]], height: u64, value: u128) {
+    let mut checkpoints = PrefixedStorage::multilevel(store, namespace);
+    checkpoints.set(&height.to_be_bytes(), &value.to_be_bytes());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate, query};
+    use crate::msg::{
+        ExecuteMsg, InitialBalance, InstantiateMsg, QueryMsg, SupplyDelta, TotalSupplyResponse,
+        VotingPowerResponse,
+    };
+    use crate::testing::default_instantiate_msg;
+    use cosmwasm_std::testing::{mock_dependencies_with_balance, mock_env, mock_info};
+    use cosmwasm_std::{from_slice, Deps, Uint128};
+
+    fn env_at(height: u64) -> Env {
+        let mut env = mock_env();
+        env.block.height = height;
+        env
+    }
+
+    fn make_instantiate_msg() -> InstantiateMsg {
+        InstantiateMsg {
+            initial_balances: vec![
+                InitialBalance {
+                    address: "addr0000".to_string(),
+                    amount: Uint128::from(100u128),
+                },
+                InitialBalance {
+                    address: "addr1111".to_string(),
+                    amount: Uint128::from(50u128),
+                },
+            ],
+            ..default_instantiate_msg()
+        }
+    }
+
+    fn voting_power(deps: Deps, address: &str, height: Option<u64>) -> u128 {
+        let query_msg = match height {
+            Some(height) => QueryMsg::VotingPowerAt {
+                address: address.to_string(),
+                height,
+            },
+            None => QueryMsg::VotingPower {
+                address: address.to_string(),
+            },
+        };
+        let res: VotingPowerResponse =
+            from_slice(&query(deps, env_at(1000), query_msg).unwrap()).unwrap();
+        res.voting_power.u128()
+    }
+
+    fn delegate(deps: DepsMut, delegator: &str, delegatee: &str, height: u64) {
+        let msg = ExecuteMsg::Delegate {
+            delegatee: delegatee.to_string(),
+        };
+        execute(deps, env_at(height), mock_info(delegator, &[]), msg).unwrap();
+    }
+
+    #[test]
+    fn balances_without_delegation_have_no_voting_power() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        instantiate(
+            deps.as_mut(),
+            env_at(1),
+            mock_info("creator", &[]),
+            make_instantiate_msg(),
+        )
+        .unwrap();
+        assert_eq!(voting_power(deps.as_ref(), "addr0000", None), 0);
+    }
+
+    #[test]
+    fn delegation_follows_transfers() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        instantiate(
+            deps.as_mut(),
+            env_at(1),
+            mock_info("creator", &[]),
+            make_instantiate_msg(),
+        )
+        .unwrap();
+        delegate(deps.as_mut(), "addr0000", "addr0000", 10);
+        delegate(deps.as_mut(), "addr1111", "addr2222", 10);
+        assert_eq!(voting_power(deps.as_ref(), "addr0000", None), 100);
+        assert_eq!(voting_power(deps.as_ref(), "addr2222", None), 50);
+
+        let transfer_msg = ExecuteMsg::Transfer {
+            recipient: "addr1111".to_string(),
+            amount: Uint128::from(30u128),
+            memo: None,
+            on_behalf_of: None,
+        };
+        execute(
+            deps.as_mut(),
+            env_at(20),
+            mock_info("addr0000", &[]),
+            transfer_msg,
+        )
+        .unwrap();
+        assert_eq!(voting_power(deps.as_ref(), "addr0000", None), 70);
+        assert_eq!(voting_power(deps.as_ref(), "addr2222", None), 80);
+
+        // historical values
+        assert_eq!(voting_power(deps.as_ref(), "addr0000", Some(9)), 0);
+        assert_eq!(voting_power(deps.as_ref(), "addr0000", Some(10)), 100);
+        assert_eq!(voting_power(deps.as_ref(), "addr0000", Some(19)), 100);
+        assert_eq!(voting_power(deps.as_ref(), "addr0000", Some(20)), 70);
+        assert_eq!(voting_power(deps.as_ref(), "addr2222", Some(15)), 50);
+    }
+
+    #[test]
+    fn redelegation_moves_voting_power() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        instantiate(
+            deps.as_mut(),
+            env_at(1),
+            mock_info("creator", &[]),
+            make_instantiate_msg(),
+        )
+        .unwrap();
+        delegate(deps.as_mut(), "addr0000", "addr2222", 10);
+        delegate(deps.as_mut(), "addr0000", "addr3333", 11);
+        assert_eq!(voting_power(deps.as_ref(), "addr2222", None), 0);
+        assert_eq!(voting_power(deps.as_ref(), "addr3333", None), 100);
+        assert_eq!(voting_power(deps.as_ref(), "addr2222", Some(10)), 100);
+    }
+
+    #[test]
+    fn burn_reduces_voting_power_and_supply() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        instantiate(
+            deps.as_mut(),
+            env_at(1),
+            mock_info("creator", &[]),
+            make_instantiate_msg(),
+        )
+        .unwrap();
+        delegate(deps.as_mut(), "addr0000", "addr0000", 10);
+        let burn_msg = ExecuteMsg::Burn {
+            amount: Uint128::from(40u128),
+            memo: None,
+        };
+        execute(
+            deps.as_mut(),
+            env_at(30),
+            mock_info("addr0000", &[]),
+            burn_msg,
+        )
+        .unwrap();
+        assert_eq!(voting_power(deps.as_ref(), "addr0000", None), 60);
+
+        let query_msg = QueryMsg::TotalSupplyAt { height: 29 };
+        let res: TotalSupplyResponse =
+            from_slice(&query(deps.as_ref(), env_at(1000), query_msg).unwrap()).unwrap();
+        assert_eq!(res.total_supply, Uint128::from(150u128));
+        let query_msg = QueryMsg::TotalSupplyAt { height: 30 };
+        let res: TotalSupplyResponse =
+            from_slice(&query(deps.as_ref(), env_at(1000), query_msg).unwrap()).unwrap();
+        assert_eq!(res.total_supply, Uint128::from(110u128));
+    }
+
+    #[test]
+    fn rebase_scales_historic_voting_power() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        instantiate(
+            deps.as_mut(),
+            env_at(1),
+            mock_info("creator", &[]),
+            make_instantiate_msg(),
+        )
+        .unwrap();
+        delegate(deps.as_mut(), "addr0000", "addr0000", 10);
+        let msg = ExecuteMsg::SetRebaser {
+            rebaser: Some("rebaser".to_string()),
+        };
+        execute(deps.as_mut(), env_at(10), mock_info("creator", &[]), msg).unwrap();
+        let msg = ExecuteMsg::Rebase {
+            delta: SupplyDelta::Decrease(Uint128::from(75u128)),
+        };
+        execute(deps.as_mut(), env_at(20), mock_info("rebaser", &[]), msg).unwrap();
+
+        assert_eq!(voting_power(deps.as_ref(), "addr0000", Some(19)), 100);
+        assert_eq!(voting_power(deps.as_ref(), "addr0000", Some(20)), 50);
+        assert_eq!(voting_power(deps.as_ref(), "addr0000", None), 50);
+    }
+}
@@ -0,0 +1,204 @@
+use cosmwasm_std::{
+    coins, from_slice, to_vec, BankMsg, DepsMut, Env, MessageInfo, Response, Storage, Uint128,
+};
+use cosmwasm_storage::{PrefixedStorage, ReadonlyPrefixedStorage};
+
+use crate::contract::{assert_balance_spendable, burn_balance, mint_balance, PREFIX_CONFIG};
+use crate::error::ContractError;
+use crate::events;
+use crate::freeze;
+use crate::locks;
+use crate::pause;
+use crate::state::WrapConfig;
+
+pub const KEY_WRAP_CONFIG: &[u8] = b"wrap_config";
+
+// A wrapper token is backed 1:1 by the coins of the wrapped denom held on the
+// contract's bank account: Deposit mints for received coins, Withdraw burns
+// and pays them back.
+
+pub fn read_wrap_config(store: &dyn Storage) -> Result<Option<WrapConfig>, ContractError> {
+    let config_store = ReadonlyPrefixedStorage::new(store, PREFIX_CONFIG);
+    match config_store.get(KEY_WRAP_CONFIG) {
+        Some(data) => Ok(Some(from_slice(&data)?)),
+        None => Ok(None),
+    }
+}
+
+pub fn write_wrap_config(
+    store: &mut dyn Storage,
+    config: &WrapConfig,
+) -> Result<(), ContractError> {
+    let mut config_store = PrefixedStorage::new(store, PREFIX_CONFIG);
+    config_store.set(KEY_WRAP_CONFIG, &to_vec(config)?);
+    Ok(())
+}
+
+fn wrapped_denom(store: &dyn Storage) -> Result<String, ContractError> {
+    match read_wrap_config(store)? {
+        Some(config) => Ok(config.denom),
+        None => Err(ContractError::NotAWrapper {}),
+    }
+}
+
+/// Mint tokens 1:1 for the coins of the wrapped denom attached to the message
+pub fn try_deposit(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    pause::assert_not_paused(deps.storage)?;
+    let denom = wrapped_denom(deps.storage)?;
+    let amount = match &info.funds[..] {
+        [coin] if coin.denom == denom && !coin.amount.is_zero() => coin.amount,
+        _ => return Err(ContractError::InvalidDeposit { denom }),
+    };
+    mint_balance(deps.storage, deps.api, &env, &info.sender, amount.u128())?;
+
+    Ok(Response::new()
+        .add_event(events::mint_event(
+            deps.storage,
+            deps.api,
+            &info.sender,
+            amount.u128(),
+        )?)
+        .add_attribute("action", "deposit")
+        .add_attribute("account", info.sender)
+        .add_attribute("amount", amount.to_string()))
+}
+
+/// Burn `amount` tokens of the signer and send the same amount of the wrapped
+/// denom back
+pub fn try_withdraw(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    pause::assert_not_paused(deps.storage)?;
+    let denom = wrapped_denom(deps.storage)?;
+    freeze::assert_not_frozen(deps.storage, &env, &info.sender)?;
+    locks::release_matured(deps.storage, deps.api, &env, &info.sender)?;
+    assert_balance_spendable(deps.storage, deps.api, &env, &info.sender, amount.u128())?;
+    burn_balance(deps.storage, deps.api, &env, &info.sender, amount.u128())?;
+
+    Ok(Response::new()
+        .add_event(events::burn_event(
+            deps.storage,
+            deps.api,
+            &info.sender,
+            amount.u128(),
+        )?)
+        .add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: coins(amount.u128(), denom),
+        })
+        .add_attribute("action", "withdraw")
+        .add_attribute("account", info.sender)
+        .add_attribute("amount", amount.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::contract::{execute, instantiate, query, read_balance, read_total_supply};
+    use crate::msg::{ExecuteMsg, InitialBalance, InstantiateMsg, QueryMsg, WrappedDenomResponse};
+    use crate::testing::default_instantiate_msg;
+    use cosmwasm_std::testing::{mock_dependencies_with_balance, mock_env, mock_info, MockApi};
+    use cosmwasm_std::{coin, Addr, Coin, CosmosMsg};
+
+    fn setup(deps: DepsMut, wrapped_denom: Option<String>) {
+        let instantiate_msg = InstantiateMsg {
+            name: "Wrapped Luna".to_string(),
+            symbol: "WLUNA".to_string(),
+            decimals: 6,
+            initial_balances: vec![InitialBalance {
+                address: "addr0000".to_string(),
+                amount: Uint128::zero(),
+            }],
+            wrapped_denom,
+            ..default_instantiate_msg()
+        };
+        instantiate(deps, mock_env(), mock_info("creator", &[]), instantiate_msg).unwrap();
+    }
+
+    fn deposit(deps: DepsMut, funds: &[Coin]) -> Result<Response, ContractError> {
+        execute(
+            deps,
+            mock_env(),
+            mock_info("addr0000", funds),
+            ExecuteMsg::Deposit {},
+        )
+    }
+
+    fn balance(storage: &dyn Storage, address: &str) -> u128 {
+        read_balance(storage, &MockApi::default(), &Addr::unchecked(address)).unwrap()
+    }
+
+    #[test]
+    fn deposit_mints_one_to_one() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut(), Some("uluna".to_string()));
+        deposit(deps.as_mut(), &coins(500, "uluna")).unwrap();
+        assert_eq!(balance(&deps.storage, "addr0000"), 500);
+        assert_eq!(read_total_supply(&deps.storage).unwrap(), 500);
+
+        let res: WrappedDenomResponse =
+            from_slice(&query(deps.as_ref(), mock_env(), QueryMsg::WrappedDenom {}).unwrap())
+                .unwrap();
+        assert_eq!(res.denom, Some("uluna".to_string()));
+    }
+
+    #[test]
+    fn deposit_rejects_other_funds() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut(), Some("uluna".to_string()));
+        for funds in [
+            vec![],
+            coins(500, "uusd"),
+            coins(0, "uluna"),
+            vec![coin(500, "uluna"), coin(500, "uusd")],
+        ] {
+            match deposit(deps.as_mut(), &funds).unwrap_err() {
+                ContractError::InvalidDeposit { denom } => assert_eq!(denom, "uluna"),
+                e => panic!("unexpected error: {:?}", e),
+            }
+        }
+        assert_eq!(read_total_supply(&deps.storage).unwrap(), 0);
+    }
+
+    #[test]
+    fn withdraw_burns_and_sends_coins() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut(), Some("uluna".to_string()));
+        deposit(deps.as_mut(), &coins(500, "uluna")).unwrap();
+
+        let msg = ExecuteMsg::Withdraw {
+            amount: Uint128::from(200u128),
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "addr0000".to_string(),
+                amount: coins(200, "uluna"),
+            })
+        );
+        assert_eq!(balance(&deps.storage, "addr0000"), 300);
+        assert_eq!(read_total_supply(&deps.storage).unwrap(), 300);
+
+        let msg = ExecuteMsg::Withdraw {
+            amount: Uint128::from(301u128),
+        };
+        match execute(deps.as_mut(), mock_env(), mock_info("addr0000", &[]), msg).unwrap_err() {
+            ContractError::InsufficientFunds { .. } => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn plain_token_cannot_wrap() {
+        let mut deps = mock_dependencies_with_balance(&[]);
+        setup(deps.as_mut(), None);
+        match deposit(deps.as_mut(), &coins(500, "uluna")).unwrap_err() {
+            ContractError::NotAWrapper {} => {}
+            e => panic!("unexpected error: {:?}", e),
+        }
+    }
+}
@@ -0,0 +1,182 @@
+//! Gas benchmarks of the release wasm, metered by `cosmwasm-vm`.
+//!
+//! Build the contract with `cargo wasm`, then run `cargo gas-test -- --nocapture`
+//! to see the gas of every call next to its budget. Gas is as the VM meters it,
+//! wasm execution and host calls, without the storage and per-call costs the
+//! chain adds on top.
+//!
+//! Budgets are not guessed: each is the gas recorded in `gas_baseline.txt` plus
+//! `MARGIN_PERCENT`. Record the baseline with
+//! `UPDATE_GAS_BASELINE=1 cargo gas-test` and commit it. After a change that
+//! costs gas on purpose, re-record it and let the diff show the new numbers.
+
+use cosmwasm_std::{ContractResult, Response, Uint128};
+use cosmwasm_vm::testing::{
+    execute, instantiate, mock_env, mock_info, mock_instance_with_options, MockApi,
+    MockInstanceOptions, MockQuerier, MockStorage,
+};
+use cosmwasm_vm::{capabilities_from_csv, Instance};
+
+use cw_erc20::{ExecuteMsg, InitialBalance, InstantiateMsg};
+
+const WASM: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/../../target/wasm32-unknown-unknown/release/cw_erc20.wasm"
+);
+
+const BASELINE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/gas_baseline.txt");
+
+const GAS_LIMIT: u64 = 500_000_000_000;
+
+/// Headroom over the recorded gas before a call counts as a regression, so
+/// that small changes elsewhere in the contract do not fail the benchmark
+const MARGIN_PERCENT: u64 = 5;
+
+type TokenInstance = Instance<MockApi, MockStorage, MockQuerier>;
+
+fn instance() -> TokenInstance {
+    let wasm = std::fs::read(WASM).expect("no release wasm, build it with `cargo wasm`");
+    // The contract speaks IBC, so it requires the stargate capability
+    let options = MockInstanceOptions {
+        available_capabilities: capabilities_from_csv("iterator,staking,stargate"),
+        gas_limit: GAS_LIMIT,
+        ..MockInstanceOptions::default()
+    };
+    mock_instance_with_options(&wasm, options)
+}
+
+fn instantiate_msg() -> InstantiateMsg {
+    InstantiateMsg {
+        name: "Cash Token".to_string(),
+        symbol: "CASH".to_string(),
+        decimals: 9,
+        initial_balances: vec![InitialBalance {
+            address: "addr0000".to_string(),
+            amount: Uint128::from(1_000_000u128),
+        }],
+        max_pause_duration: None,
+        wrapped_denom: None,
+        clawback_admin: None,
+        max_supply: None,
+        mint: None,
+        allow_zero_transfers: true,
+        drip: None,
+    }
+}
+
+// Gas used by `call`, which must succeed
+fn measure(
+    instance: &mut TokenInstance,
+    call: impl FnOnce(&mut TokenInstance) -> ContractResult<Response>,
+) -> u64 {
+    let gas_before = instance.get_gas_left();
+    call(instance).unwrap();
+    gas_before - instance.get_gas_left()
+}
+
+// Recorded gas by call name, one `name gas` pair per line
+fn read_baseline() -> Vec<(String, u64)> {
+    let baseline = std::fs::read_to_string(BASELINE)
+        .expect("no gas baseline, record it with `UPDATE_GAS_BASELINE=1 cargo gas-test`");
+    baseline
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            let (name, gas) = line.split_once(' ').expect("malformed gas baseline");
+            (
+                name.to_string(),
+                gas.parse().expect("malformed gas baseline"),
+            )
+        })
+        .collect()
+}
+
+fn write_baseline(measurements: &[(&str, u64)]) {
+    let baseline: String = measurements
+        .iter()
+        .map(|(name, used)| format!("{} {}\n", name, used))
+        .collect();
+    std::fs::write(BASELINE, baseline).unwrap();
+}
+
+// Checks every measurement against its budget, or records them as the new
+// baseline if `UPDATE_GAS_BASELINE` is set
+fn assert_within_budget(measurements: &[(&str, u64)]) {
+    if std::env::var_os("UPDATE_GAS_BASELINE").is_some() {
+        write_baseline(measurements);
+        return;
+    }
+    let baseline = read_baseline();
+    for &(name, used) in measurements {
+        let recorded = baseline
+            .iter()
+            .find(|(recorded_name, _)| recorded_name == name)
+            .map(|(_, gas)| *gas)
+            .unwrap_or_else(|| panic!("{} is missing from the gas baseline", name));
+        let budget = recorded + recorded * MARGIN_PERCENT / 100;
+        println!(
+            "{}: {} gas (recorded {}, budget {})",
+            name, used, recorded, budget
+        );
+        assert!(
+            used <= budget,
+            "{} used {} gas, over its budget of {}",
+            name,
+            used,
+            budget
+        );
+    }
+}
+
+fn run(instance: &mut TokenInstance, sender: &str, msg: ExecuteMsg) -> u64 {
+    measure(instance, |instance| {
+        execute(instance, mock_env(), mock_info(sender, &[]), msg)
+    })
+}
+
+// A single test measures every call, so that recording the baseline writes
+// the file once
+#[test]
+fn calls_within_budget() {
+    let mut instance = instance();
+    let instantiate_gas = measure(&mut instance, |instance| {
+        instantiate(
+            instance,
+            mock_env(),
+            mock_info("creator", &[]),
+            instantiate_msg(),
+        )
+    });
+    let transfer = || ExecuteMsg::Transfer {
+        recipient: "addr1111".to_string(),
+        amount: Uint128::from(1_000u128),
+        memo: None,
+        on_behalf_of: None,
+    };
+
+    // the first transfer creates the balance and holder entries of the recipient
+    let transfer_new_holder_gas = run(&mut instance, "addr0000", transfer());
+    let transfer_gas = run(&mut instance, "addr0000", transfer());
+
+    let msg = ExecuteMsg::Approve {
+        spender: "spender".to_string(),
+        amount: Uint128::from(5_000u128),
+        expected_current: None,
+        on_behalf_of: None,
+    };
+    let approve_gas = run(&mut instance, "addr0000", msg);
+
+    let msg = ExecuteMsg::Burn {
+        amount: Uint128::from(500u128),
+        memo: None,
+    };
+    let burn_gas = run(&mut instance, "addr0000", msg);
+
+    assert_within_budget(&[
+        ("instantiate", instantiate_gas),
+        ("transfer_new_holder", transfer_new_holder_gas),
+        ("transfer", transfer_gas),
+        ("approve", approve_gas),
+        ("burn", burn_gas),
+    ]);
+}
@@ -0,0 +1,284 @@
+//! End-to-end flows through `cw-multi-test`, with the contract deployed in an `App`.
+//!
+//! `Suite` wraps the deployment so tests read as a list of calls:
+//!
+//! ```ignore
+//! let mut suite = Suite::with_balances(&[("addr0000", 1000)]);
+//! suite.transfer("addr0000", "addr1111", 100).unwrap();
+//! assert_eq!(suite.balance("addr1111"), 100);
+//! ```
+
+use cosmwasm_std::{Addr, Empty, Uint128};
+use cw20::{Cw20ExecuteMsg, Cw20QueryMsg};
+use cw_multi_test::{App, AppResponse, Contract, ContractWrapper, Executor};
+use cw_utils::Expiration;
+use serde::de::DeserializeOwned;
+
+use cw_erc20::contract::{execute, instantiate, query};
+use cw_erc20::{
+    reply, sudo, AllowanceResponse, BalanceResponse, ContractError, ExecuteMsg, InitialBalance,
+    InstantiateMsg, QueryMsg, TokenInfoResponse,
+};
+
+const OWNER: &str = "creator";
+
+fn token_contract() -> Box<dyn Contract<Empty>> {
+    let contract = ContractWrapper::new(execute, instantiate, query)
+        .with_sudo(sudo)
+        .with_reply(reply);
+    Box::new(contract)
+}
+
+struct Suite {
+    app: App,
+    token: Addr,
+}
+
+impl Suite {
+    /// Deploys "Cash Token" with the given initial balances, instantiated by `OWNER`
+    fn with_balances(balances: &[(&str, u128)]) -> Self {
+        let mut app = App::default();
+        let code_id = app.store_code(token_contract());
+        let msg = InstantiateMsg {
+            name: "Cash Token".to_string(),
+            symbol: "CASH".to_string(),
+            decimals: 9,
+            initial_balances: balances
+                .iter()
+                .map(|(address, amount)| InitialBalance {
+                    address: address.to_string(),
+                    amount: Uint128::from(*amount),
+                })
+                .collect(),
+            max_pause_duration: None,
+            wrapped_denom: None,
+            clawback_admin: None,
+            max_supply: None,
+            mint: None,
+            allow_zero_transfers: true,
+            drip: None,
+        };
+        let token = app
+            .instantiate_contract(code_id, Addr::unchecked(OWNER), &msg, &[], "cash", None)
+            .unwrap();
+        Suite { app, token }
+    }
+
+    fn execute(&mut self, sender: &str, msg: ExecuteMsg) -> Result<AppResponse, ContractError> {
+        self.app
+            .execute_contract(Addr::unchecked(sender), self.token.clone(), &msg, &[])
+            .map_err(|err| err.downcast().unwrap())
+    }
+
+    fn query<T: DeserializeOwned>(&self, msg: QueryMsg) -> T {
+        self.app
+            .wrap()
+            .query_wasm_smart(self.token.clone(), &msg)
+            .unwrap()
+    }
+
+    /// Executes a message of the `cw20` crate, as other cw20 contracts send them
+    fn execute_cw20(&mut self, sender: &str, msg: Cw20ExecuteMsg) -> AppResponse {
+        self.app
+            .execute_contract(Addr::unchecked(sender), self.token.clone(), &msg, &[])
+            .unwrap()
+    }
+
+    fn query_cw20<T: DeserializeOwned>(&self, msg: Cw20QueryMsg) -> T {
+        self.app
+            .wrap()
+            .query_wasm_smart(self.token.clone(), &msg)
+            .unwrap()
+    }
+
+    fn transfer(
+        &mut self,
+        sender: &str,
+        recipient: &str,
+        amount: u128,
+    ) -> Result<AppResponse, ContractError> {
+        let msg = ExecuteMsg::Transfer {
+            recipient: recipient.to_string(),
+            amount: Uint128::from(amount),
+            memo: None,
+            on_behalf_of: None,
+        };
+        self.execute(sender, msg)
+    }
+
+    fn balance(&self, address: &str) -> u128 {
+        let res: BalanceResponse = self.query(QueryMsg::Balance {
+            address: address.to_string(),
+        });
+        res.balance.u128()
+    }
+
+    fn allowance(&self, owner: &str, spender: &str) -> u128 {
+        let res: AllowanceResponse = self.query(QueryMsg::Allowance {
+            owner: owner.to_string(),
+            spender: spender.to_string(),
+        });
+        res.allowance.u128()
+    }
+
+    fn total_supply(&self) -> u128 {
+        let res: TokenInfoResponse = self.query(QueryMsg::TokenInfo {});
+        res.total_supply.u128()
+    }
+}
+
+#[test]
+fn transfer_moves_balances() {
+    let mut suite = Suite::with_balances(&[("addr0000", 1000)]);
+    suite.transfer("addr0000", "addr1111", 300).unwrap();
+    assert_eq!(suite.balance("addr0000"), 700);
+    assert_eq!(suite.balance("addr1111"), 300);
+    assert_eq!(suite.total_supply(), 1000);
+
+    match suite.transfer("addr1111", "addr0000", 301).unwrap_err() {
+        ContractError::InsufficientFunds { balance, required } => {
+            assert_eq!(balance, 300);
+            assert_eq!(required, 301);
+        }
+        e => panic!("unexpected error: {:?}", e),
+    }
+}
+
+#[test]
+fn approve_and_transfer_from() {
+    let mut suite = Suite::with_balances(&[("addr0000", 1000)]);
+    let msg = ExecuteMsg::Approve {
+        spender: "spender".to_string(),
+        amount: Uint128::from(250u128),
+        expected_current: None,
+        on_behalf_of: None,
+    };
+    suite.execute("addr0000", msg).unwrap();
+    assert_eq!(suite.allowance("addr0000", "spender"), 250);
+
+    let msg = ExecuteMsg::TransferFrom {
+        owner: "addr0000".to_string(),
+        recipient: "addr2222".to_string(),
+        amount: Uint128::from(200u128),
+        memo: None,
+    };
+    suite.execute("spender", msg.clone()).unwrap();
+    assert_eq!(suite.allowance("addr0000", "spender"), 50);
+    assert_eq!(suite.balance("addr0000"), 800);
+    assert_eq!(suite.balance("addr2222"), 200);
+
+    match suite.execute("spender", msg).unwrap_err() {
+        ContractError::InsufficientAllowance {
+            allowance,
+            required,
+        } => {
+            assert_eq!(allowance, 50);
+            assert_eq!(required, 200);
+        }
+        e => panic!("unexpected error: {:?}", e),
+    }
+}
+
+#[test]
+fn burn_reduces_total_supply() {
+    let mut suite = Suite::with_balances(&[("addr0000", 1000), ("addr1111", 500)]);
+    let msg = ExecuteMsg::Burn {
+        amount: Uint128::from(400u128),
+        memo: None,
+    };
+    suite.execute("addr0000", msg).unwrap();
+    assert_eq!(suite.balance("addr0000"), 600);
+    assert_eq!(suite.balance("addr1111"), 500);
+    assert_eq!(suite.total_supply(), 1100);
+}
+
+#[test]
+fn cw20_messages_and_responses() {
+    let mut suite = Suite::with_balances(&[("addr0000", 1000)]);
+    let expires = Expiration::AtHeight(1_000_000);
+    let msg = Cw20ExecuteMsg::IncreaseAllowance {
+        spender: "spender".to_string(),
+        amount: Uint128::from(300u128),
+        expires: Some(expires),
+    };
+    suite.execute_cw20("addr0000", msg);
+    let msg = Cw20ExecuteMsg::DecreaseAllowance {
+        spender: "spender".to_string(),
+        amount: Uint128::from(100u128),
+        expires: None,
+    };
+    suite.execute_cw20("addr0000", msg);
+    let res: cw20::AllowanceResponse = suite.query_cw20(Cw20QueryMsg::Allowance {
+        owner: "addr0000".to_string(),
+        spender: "spender".to_string(),
+    });
+    assert_eq!(
+        res,
+        cw20::AllowanceResponse {
+            allowance: Uint128::from(200u128),
+            expires,
+        }
+    );
+
+    let msg = Cw20ExecuteMsg::TransferFrom {
+        owner: "addr0000".to_string(),
+        recipient: "addr1111".to_string(),
+        amount: Uint128::from(150u128),
+    };
+    suite.execute_cw20("spender", msg);
+    let res: cw20::BalanceResponse = suite.query_cw20(Cw20QueryMsg::Balance {
+        address: "addr1111".to_string(),
+    });
+    assert_eq!(res.balance, Uint128::from(150u128));
+
+    let res: cw20::AllAllowancesResponse = suite.query_cw20(Cw20QueryMsg::AllAllowances {
+        owner: "addr0000".to_string(),
+        start_after: None,
+        limit: None,
+    });
+    assert_eq!(
+        res.allowances,
+        vec![cw20::AllowanceInfo {
+            spender: "spender".to_string(),
+            allowance: Uint128::from(50u128),
+            expires,
+        }]
+    );
+    let res: cw20::AllAccountsResponse = suite.query_cw20(Cw20QueryMsg::AllAccounts {
+        start_after: None,
+        limit: None,
+    });
+    assert_eq!(res.accounts, vec!["addr0000", "addr1111"]);
+
+    let res: cw20::TokenInfoResponse = suite.query_cw20(Cw20QueryMsg::TokenInfo {});
+    assert_eq!(res.symbol, "CASH");
+    assert_eq!(res.total_supply, Uint128::from(1000u128));
+    let res: Option<cw20::MinterResponse> = suite.query_cw20(Cw20QueryMsg::Minter {});
+    assert_eq!(res, None);
+
+    let msg = ExecuteMsg::UpdateMarketingAdmin {
+        admin: Some("marketing".to_string()),
+    };
+    suite.execute(OWNER, msg).unwrap();
+    let msg = Cw20ExecuteMsg::UpdateMarketing {
+        project: Some("https://cash.example".to_string()),
+        description: Some("Cash for everyone".to_string()),
+        marketing: None,
+    };
+    suite.execute_cw20("marketing", msg);
+    let msg =
+        Cw20ExecuteMsg::UploadLogo(cw20::Logo::Url("https://cash.example/logo.svg".to_string()));
+    suite.execute_cw20("marketing", msg);
+    let res: cw20::MarketingInfoResponse = suite.query_cw20(Cw20QueryMsg::MarketingInfo {});
+    assert_eq!(
+        res,
+        cw20::MarketingInfoResponse {
+            project: Some("https://cash.example".to_string()),
+            description: Some("Cash for everyone".to_string()),
+            logo: Some(cw20::LogoInfo::Url(
+                "https://cash.example/logo.svg".to_string()
+            )),
+            marketing: Some(Addr::unchecked("marketing")),
+        }
+    );
+}
@@ -0,0 +1,301 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+
+use crate::error::ContractError;
+use crate::state::{
+    next_schedule_id, read_all_schedule_ids, read_config, read_schedule, read_schedules,
+    store_config, store_schedule, vested_amount, Config, VestingSchedule,
+};
+
+use cosmwasm_std::{
+    from_binary, to_binary, Addr, Binary, CosmosMsg, Deps, DepsMut, Env, MessageInfo, Response,
+    StdError, StdResult, Uint128, WasmMsg,
+};
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use shard_token::vesting::{
+    ConfigResponse, Cw20HookMsg, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, Schedule,
+    ScheduleResponse, SchedulesResponse,
+};
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    store_config(
+        deps.storage,
+        &Config {
+            shard_token: deps.api.addr_canonicalize(&msg.shard_token)?,
+            admin: deps.api.addr_canonicalize(&msg.admin)?,
+        },
+    )?;
+
+    Ok(Response::default())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Receive(msg) => receive_cw20(deps, info, msg),
+        ExecuteMsg::Claim {} => claim(deps, env, info),
+        ExecuteMsg::Revoke { id } => revoke(deps, env, info, id),
+        ExecuteMsg::UpdateAdmin { admin } => update_admin(deps, info, admin),
+    }
+}
+
+pub fn receive_cw20(
+    deps: DepsMut,
+    info: MessageInfo,
+    cw20_msg: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    let config: Config = read_config(deps.storage)?;
+    // only the shard token contract can execute this message
+    if config.shard_token != deps.api.addr_canonicalize(info.sender.as_str())? {
+        return Err(ContractError::Unauthorized {});
+    }
+    // only the admin funds schedules, as only the admin can revoke them
+    if config.admin != deps.api.addr_canonicalize(&cw20_msg.sender)? {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    match from_binary(&cw20_msg.msg)? {
+        Cw20HookMsg::CreateSchedule {
+            beneficiary,
+            schedule,
+            revocable,
+        } => create_schedule(deps, cw20_msg.amount, beneficiary, schedule, revocable),
+    }
+}
+
+pub fn create_schedule(
+    deps: DepsMut,
+    amount: Uint128,
+    beneficiary: String,
+    schedule: Schedule,
+    revocable: bool,
+) -> Result<Response, ContractError> {
+    if amount.is_zero() {
+        return Err(ContractError::NoFunds {});
+    }
+    validate_schedule(&schedule, amount)?;
+
+    let id = next_schedule_id(deps.storage)?;
+    store_schedule(
+        deps.storage,
+        id,
+        &VestingSchedule {
+            beneficiary: deps.api.addr_canonicalize(&beneficiary)?,
+            schedule,
+            revocable,
+            total: amount,
+            claimed: Uint128::zero(),
+            revoked_at: None,
+        },
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        ("action", "create_schedule"),
+        ("id", &id.to_string()),
+        ("beneficiary", &beneficiary),
+        ("amount", &amount.to_string()),
+    ]))
+}
+
+fn validate_schedule(schedule: &Schedule, amount: Uint128) -> Result<(), ContractError> {
+    let valid = match schedule {
+        Schedule::Cliff { .. } => true,
+        Schedule::Linear {
+            start_time,
+            end_time,
+            cliff_time,
+        } => {
+            start_time < end_time
+                && cliff_time.is_none_or(|cliff| *start_time <= cliff && cliff <= *end_time)
+        }
+        Schedule::Custom { points } => {
+            points
+                .windows(2)
+                .all(|pair| pair[0].time < pair[1].time && pair[0].amount <= pair[1].amount)
+                && points.last().map(|point| point.amount) == Some(amount)
+        }
+    };
+    if !valid {
+        return Err(ContractError::InvalidSchedule {});
+    }
+    Ok(())
+}
+
+pub fn claim(deps: DepsMut, env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let beneficiary = deps.api.addr_canonicalize(info.sender.as_str())?;
+    let now = env.block.time.seconds();
+
+    let mut amount = Uint128::zero();
+    for id in read_all_schedule_ids(deps.storage, &beneficiary)? {
+        let mut schedule = load_schedule(deps.as_ref(), id)?;
+        let claimable = schedule.vested(now) - schedule.claimed;
+        if claimable.is_zero() {
+            continue;
+        }
+        schedule.claimed += claimable;
+        store_schedule(deps.storage, id, &schedule)?;
+        amount += claimable;
+    }
+    if amount.is_zero() {
+        return Err(ContractError::NothingToClaim {});
+    }
+
+    Ok(Response::new()
+        .add_message(send_tokens(deps.as_ref(), &info.sender, amount)?)
+        .add_attributes(vec![
+            ("action", "claim"),
+            ("beneficiary", info.sender.as_str()),
+            ("amount", &amount.to_string()),
+        ]))
+}
+
+pub fn revoke(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    let config: Config = read_config(deps.storage)?;
+    if config.admin != deps.api.addr_canonicalize(info.sender.as_str())? {
+        return Err(ContractError::Unauthorized {});
+    }
+    let mut schedule = load_schedule(deps.as_ref(), id)?;
+    if !schedule.revocable {
+        return Err(ContractError::NotRevocable {});
+    }
+    if schedule.revoked_at.is_some() {
+        return Err(ContractError::AlreadyRevoked {});
+    }
+
+    let now = env.block.time.seconds();
+    let vested = vested_amount(&schedule.schedule, schedule.total, now);
+    let unvested = schedule.total - vested;
+    schedule.total = vested;
+    schedule.revoked_at = Some(now);
+    store_schedule(deps.storage, id, &schedule)?;
+
+    let mut res = Response::new();
+    if !unvested.is_zero() {
+        res = res.add_message(send_tokens(deps.as_ref(), &info.sender, unvested)?);
+    }
+    Ok(res.add_attributes(vec![
+        ("action", "revoke"),
+        ("id", &id.to_string()),
+        ("unvested", &unvested.to_string()),
+    ]))
+}
+
+pub fn update_admin(
+    deps: DepsMut,
+    info: MessageInfo,
+    admin: String,
+) -> Result<Response, ContractError> {
+    let mut config: Config = read_config(deps.storage)?;
+    if config.admin != deps.api.addr_canonicalize(info.sender.as_str())? {
+        return Err(ContractError::Unauthorized {});
+    }
+    config.admin = deps.api.addr_canonicalize(&admin)?;
+    store_config(deps.storage, &config)?;
+
+    Ok(Response::new().add_attributes(vec![("action", "update_admin"), ("admin", &admin)]))
+}
+
+fn load_schedule(deps: Deps, id: u64) -> StdResult<VestingSchedule> {
+    match read_schedule(deps.storage, id)? {
+        Some(schedule) => Ok(schedule),
+        None => Err(StdError::not_found("Schedule")),
+    }
+}
+
+fn send_tokens(deps: Deps, recipient: &Addr, amount: Uint128) -> StdResult<CosmosMsg> {
+    let config: Config = read_config(deps.storage)?;
+    Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: deps.api.addr_humanize(&config.shard_token)?.to_string(),
+        msg: to_binary(&Cw20ExecuteMsg::Transfer {
+            recipient: recipient.to_string(),
+            amount,
+        })?,
+        funds: vec![],
+    }))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_binary(&query_config(deps)?),
+        QueryMsg::Schedule { id } => to_binary(&query_schedule(deps, env, id)?),
+        QueryMsg::Schedules {
+            beneficiary,
+            start_after,
+            limit,
+        } => to_binary(&query_schedules(
+            deps,
+            env,
+            beneficiary,
+            start_after,
+            limit,
+        )?),
+    }
+}
+
+pub fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
+    let config: Config = read_config(deps.storage)?;
+    Ok(ConfigResponse {
+        shard_token: deps.api.addr_humanize(&config.shard_token)?.to_string(),
+        admin: deps.api.addr_humanize(&config.admin)?.to_string(),
+    })
+}
+
+pub fn query_schedule(deps: Deps, env: Env, id: u64) -> StdResult<ScheduleResponse> {
+    let schedule = load_schedule(deps, id)?;
+    schedule_response(deps, &env, id, schedule)
+}
+
+pub fn query_schedules(
+    deps: Deps,
+    env: Env,
+    beneficiary: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<SchedulesResponse> {
+    let beneficiary = deps.api.addr_canonicalize(&beneficiary)?;
+    let schedules = read_schedules(deps.storage, &beneficiary, start_after, limit)?
+        .into_iter()
+        .map(|(id, schedule)| schedule_response(deps, &env, id, schedule))
+        .collect::<StdResult<Vec<ScheduleResponse>>>()?;
+    Ok(SchedulesResponse { schedules })
+}
+
+fn schedule_response(
+    deps: Deps,
+    env: &Env,
+    id: u64,
+    schedule: VestingSchedule,
+) -> StdResult<ScheduleResponse> {
+    Ok(ScheduleResponse {
+        id,
+        beneficiary: deps.api.addr_humanize(&schedule.beneficiary)?.to_string(),
+        vested: schedule.vested(env.block.time.seconds()),
+        schedule: schedule.schedule,
+        revocable: schedule.revocable,
+        total: schedule.total,
+        claimed: schedule.claimed,
+        revoked_at: schedule.revoked_at,
+    })
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    Ok(Response::default())
+}
@@ -0,0 +1,26 @@
+use cosmwasm_std::StdError;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("Vesting amount must be greater than zero")]
+    NoFunds {},
+
+    #[error("Invalid vesting schedule")]
+    InvalidSchedule {},
+
+    #[error("Schedule is not revocable")]
+    NotRevocable {},
+
+    #[error("Schedule was already revoked")]
+    AlreadyRevoked {},
+
+    #[error("Nothing to claim")]
+    NothingToClaim {},
+}
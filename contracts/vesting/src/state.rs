@@ -0,0 +1,167 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::{CanonicalAddr, Order, StdResult, Storage, Uint128};
+use cosmwasm_storage::{singleton, singleton_read, Bucket, ReadonlyBucket};
+use shard_token::vesting::Schedule;
+
+static KEY_CONFIG: &[u8] = b"config";
+static KEY_SCHEDULE_COUNT: &[u8] = b"schedule_count";
+
+static PREFIX_SCHEDULE: &[u8] = b"schedule";
+static PREFIX_SCHEDULES_BY_BENEFICIARY: &[u8] = b"schedules_by_beneficiary";
+
+// settings for pagination
+const MAX_LIMIT: u32 = 30;
+const DEFAULT_LIMIT: u32 = 10;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Config {
+    pub shard_token: CanonicalAddr,
+    pub admin: CanonicalAddr,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VestingSchedule {
+    pub beneficiary: CanonicalAddr,
+    pub schedule: Schedule,
+    pub revocable: bool,
+    pub total: Uint128,
+    pub claimed: Uint128,
+    pub revoked_at: Option<u64>,
+}
+
+impl VestingSchedule {
+    /// Amount vested at block time `now`. Revoked schedules are fully vested,
+    /// their total was cut down to what vested until the revocation.
+    pub fn vested(&self, now: u64) -> Uint128 {
+        if self.revoked_at.is_some() {
+            return self.total;
+        }
+        vested_amount(&self.schedule, self.total, now)
+    }
+}
+
+pub fn vested_amount(schedule: &Schedule, total: Uint128, now: u64) -> Uint128 {
+    match schedule {
+        Schedule::Cliff { time } => {
+            if now >= *time {
+                total
+            } else {
+                Uint128::zero()
+            }
+        }
+        Schedule::Linear {
+            start_time,
+            end_time,
+            cliff_time,
+        } => {
+            if now < cliff_time.unwrap_or(*start_time) || now <= *start_time {
+                Uint128::zero()
+            } else if now >= *end_time {
+                total
+            } else {
+                total.multiply_ratio(now - start_time, end_time - start_time)
+            }
+        }
+        Schedule::Custom { points } => match points.iter().position(|point| point.time > now) {
+            Some(0) => Uint128::zero(),
+            Some(next) => {
+                let (from, to) = (&points[next - 1], &points[next]);
+                from.amount
+                    + (to.amount - from.amount).multiply_ratio(now - from.time, to.time - from.time)
+            }
+            None => total,
+        },
+    }
+}
+
+pub fn store_config(storage: &mut dyn Storage, config: &Config) -> StdResult<()> {
+    singleton(storage, KEY_CONFIG).save(config)
+}
+
+pub fn read_config(storage: &dyn Storage) -> StdResult<Config> {
+    singleton_read(storage, KEY_CONFIG).load()
+}
+
+/// Id for the next schedule, ids start at 1
+pub fn next_schedule_id(storage: &mut dyn Storage) -> StdResult<u64> {
+    let id = singleton_read(storage, KEY_SCHEDULE_COUNT)
+        .may_load()?
+        .unwrap_or(0u64)
+        + 1;
+    singleton(storage, KEY_SCHEDULE_COUNT).save(&id)?;
+    Ok(id)
+}
+
+pub fn store_schedule(
+    storage: &mut dyn Storage,
+    id: u64,
+    schedule: &VestingSchedule,
+) -> StdResult<()> {
+    let mut schedule_bucket: Bucket<VestingSchedule> = Bucket::new(storage, PREFIX_SCHEDULE);
+    schedule_bucket.save(&id.to_be_bytes(), schedule)?;
+    let mut beneficiary_bucket: Bucket<bool> = Bucket::multilevel(
+        storage,
+        &[
+            PREFIX_SCHEDULES_BY_BENEFICIARY,
+            schedule.beneficiary.as_slice(),
+        ],
+    );
+    beneficiary_bucket.save(&id.to_be_bytes(), &true)
+}
+
+pub fn read_schedule(storage: &dyn Storage, id: u64) -> StdResult<Option<VestingSchedule>> {
+    let schedule_bucket: ReadonlyBucket<VestingSchedule> =
+        ReadonlyBucket::new(storage, PREFIX_SCHEDULE);
+    schedule_bucket.may_load(&id.to_be_bytes())
+}
+
+/// Schedules of `beneficiary` ordered by id
+pub fn read_schedules(
+    storage: &dyn Storage,
+    beneficiary: &CanonicalAddr,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<Vec<(u64, VestingSchedule)>> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let schedule_bucket: ReadonlyBucket<VestingSchedule> =
+        ReadonlyBucket::new(storage, PREFIX_SCHEDULE);
+    read_schedule_ids(storage, beneficiary, start_after, limit)?
+        .into_iter()
+        .map(|id| Ok((id, schedule_bucket.load(&id.to_be_bytes())?)))
+        .collect()
+}
+
+/// Ids of all schedules of `beneficiary`
+pub fn read_all_schedule_ids(
+    storage: &dyn Storage,
+    beneficiary: &CanonicalAddr,
+) -> StdResult<Vec<u64>> {
+    read_schedule_ids(storage, beneficiary, None, usize::MAX)
+}
+
+fn read_schedule_ids(
+    storage: &dyn Storage,
+    beneficiary: &CanonicalAddr,
+    start_after: Option<u64>,
+    limit: usize,
+) -> StdResult<Vec<u64>> {
+    let beneficiary_bucket: ReadonlyBucket<bool> = ReadonlyBucket::multilevel(
+        storage,
+        &[PREFIX_SCHEDULES_BY_BENEFICIARY, beneficiary.as_slice()],
+    );
+    // ids are stored big endian, so the first key after `start_after` is its successor
+    let start = start_after.map(|id| (id + 1).to_be_bytes().to_vec());
+
+    beneficiary_bucket
+        .range(start.as_deref(), None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (k, _) = item?;
+            let mut id = [0u8; 8];
+            id.copy_from_slice(&k);
+            Ok(u64::from_be_bytes(id))
+        })
+        .collect()
+}
@@ -0,0 +1,223 @@
+use crate::contract::{execute, instantiate, query};
+use crate::error::ContractError;
+use cosmwasm_std::testing::{
+    mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
+};
+use cosmwasm_std::{
+    from_binary, to_binary, CosmosMsg, DepsMut, Env, OwnedDeps, Response, SubMsg, Timestamp,
+    Uint128, WasmMsg,
+};
+use cw20::{Cw20ExecuteMsg, Cw20ReceiveMsg};
+use shard_token::vesting::{
+    CurvePoint, Cw20HookMsg, ExecuteMsg, InstantiateMsg, QueryMsg, Schedule, ScheduleResponse,
+    SchedulesResponse,
+};
+
+fn env_at(time: u64) -> Env {
+    let mut env = mock_env();
+    env.block.time = Timestamp::from_seconds(time);
+    env
+}
+
+fn setup() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+    let mut deps = mock_dependencies(&[]);
+    let msg = InstantiateMsg {
+        shard_token: "shard0000".to_string(),
+        admin: "admin0000".to_string(),
+    };
+    instantiate(deps.as_mut(), env_at(0), mock_info("addr0000", &[]), msg).unwrap();
+    deps
+}
+
+fn create(
+    deps: DepsMut,
+    sender: &str,
+    amount: u128,
+    schedule: Schedule,
+    revocable: bool,
+) -> Result<Response, ContractError> {
+    let msg = ExecuteMsg::Receive(Cw20ReceiveMsg {
+        sender: sender.to_string(),
+        amount: Uint128::from(amount),
+        msg: to_binary(&Cw20HookMsg::CreateSchedule {
+            beneficiary: "team0000".to_string(),
+            schedule,
+            revocable,
+        })
+        .unwrap(),
+    });
+    execute(deps, env_at(0), mock_info("shard0000", &[]), msg)
+}
+
+fn linear() -> Schedule {
+    Schedule::Linear {
+        start_time: 1000,
+        end_time: 2000,
+        cliff_time: Some(1250),
+    }
+}
+
+fn claim(deps: DepsMut, time: u64) -> Result<Response, ContractError> {
+    execute(
+        deps,
+        env_at(time),
+        mock_info("team0000", &[]),
+        ExecuteMsg::Claim {},
+    )
+}
+
+fn transfer_msg(recipient: &str, amount: u128) -> SubMsg {
+    SubMsg::new(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: "shard0000".to_string(),
+        msg: to_binary(&Cw20ExecuteMsg::Transfer {
+            recipient: recipient.to_string(),
+            amount: Uint128::from(amount),
+        })
+        .unwrap(),
+        funds: vec![],
+    }))
+}
+
+fn query_schedule(
+    deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>,
+    id: u64,
+    time: u64,
+) -> ScheduleResponse {
+    from_binary(&query(deps.as_ref(), env_at(time), QueryMsg::Schedule { id }).unwrap()).unwrap()
+}
+
+#[test]
+fn only_admin_funds_schedules() {
+    let mut deps = setup();
+    match create(deps.as_mut(), "other0000", 1000, linear(), true).unwrap_err() {
+        ContractError::Unauthorized {} => {}
+        e => panic!("unexpected error: {:?}", e),
+    }
+    let invalid = Schedule::Linear {
+        start_time: 1000,
+        end_time: 1000,
+        cliff_time: None,
+    };
+    match create(deps.as_mut(), "admin0000", 1000, invalid, true).unwrap_err() {
+        ContractError::InvalidSchedule {} => {}
+        e => panic!("unexpected error: {:?}", e),
+    }
+    create(deps.as_mut(), "admin0000", 1000, linear(), true).unwrap();
+    assert_eq!(query_schedule(&deps, 1, 0).total, Uint128::from(1000u128));
+}
+
+#[test]
+fn linear_schedule_vests_after_cliff() {
+    let mut deps = setup();
+    create(deps.as_mut(), "admin0000", 1000, linear(), false).unwrap();
+
+    assert_eq!(query_schedule(&deps, 1, 1249).vested, Uint128::zero());
+    match claim(deps.as_mut(), 1249).unwrap_err() {
+        ContractError::NothingToClaim {} => {}
+        e => panic!("unexpected error: {:?}", e),
+    }
+    let res = claim(deps.as_mut(), 1500).unwrap();
+    assert_eq!(res.messages, vec![transfer_msg("team0000", 500)]);
+    let res = claim(deps.as_mut(), 3000).unwrap();
+    assert_eq!(res.messages, vec![transfer_msg("team0000", 500)]);
+    assert_eq!(
+        query_schedule(&deps, 1, 3000).claimed,
+        Uint128::from(1000u128)
+    );
+}
+
+#[test]
+fn claim_pays_out_all_schedules() {
+    let mut deps = setup();
+    create(
+        deps.as_mut(),
+        "admin0000",
+        100,
+        Schedule::Cliff { time: 500 },
+        false,
+    )
+    .unwrap();
+    let curve = Schedule::Custom {
+        points: vec![
+            CurvePoint {
+                time: 100,
+                amount: Uint128::from(100u128),
+            },
+            CurvePoint {
+                time: 200,
+                amount: Uint128::from(300u128),
+            },
+        ],
+    };
+    create(deps.as_mut(), "admin0000", 300, curve, false).unwrap();
+
+    assert_eq!(query_schedule(&deps, 2, 99).vested, Uint128::zero());
+    assert_eq!(query_schedule(&deps, 2, 150).vested, Uint128::from(200u128));
+    let res = claim(deps.as_mut(), 500).unwrap();
+    assert_eq!(res.messages, vec![transfer_msg("team0000", 400)]);
+
+    let res = query(
+        deps.as_ref(),
+        env_at(500),
+        QueryMsg::Schedules {
+            beneficiary: "team0000".to_string(),
+            start_after: Some(1),
+            limit: None,
+        },
+    )
+    .unwrap();
+    let list: SchedulesResponse = from_binary(&res).unwrap();
+    let ids: Vec<u64> = list
+        .schedules
+        .into_iter()
+        .map(|schedule| schedule.id)
+        .collect();
+    assert_eq!(ids, vec![2]);
+}
+
+#[test]
+fn revoke_returns_unvested_to_admin() {
+    let mut deps = setup();
+    create(deps.as_mut(), "admin0000", 1000, linear(), true).unwrap();
+    create(deps.as_mut(), "admin0000", 1000, linear(), false).unwrap();
+
+    let revoke = |id| ExecuteMsg::Revoke { id };
+    match execute(
+        deps.as_mut(),
+        env_at(1500),
+        mock_info("team0000", &[]),
+        revoke(1),
+    )
+    .unwrap_err()
+    {
+        ContractError::Unauthorized {} => {}
+        e => panic!("unexpected error: {:?}", e),
+    }
+    match execute(
+        deps.as_mut(),
+        env_at(1500),
+        mock_info("admin0000", &[]),
+        revoke(2),
+    )
+    .unwrap_err()
+    {
+        ContractError::NotRevocable {} => {}
+        e => panic!("unexpected error: {:?}", e),
+    }
+
+    let res = execute(
+        deps.as_mut(),
+        env_at(1600),
+        mock_info("admin0000", &[]),
+        revoke(1),
+    )
+    .unwrap();
+    assert_eq!(res.messages, vec![transfer_msg("admin0000", 400)]);
+
+    // the vested part stays claimable, nothing vests any more
+    let schedule = query_schedule(&deps, 1, 3000);
+    assert_eq!(schedule.total, Uint128::from(600u128));
+    assert_eq!(schedule.revoked_at, Some(1600));
+    let res = claim(deps.as_mut(), 1700).unwrap();
+    assert_eq!(res.messages, vec![transfer_msg("team0000", 600 + 700)]);
+}
@@ -0,0 +1,123 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::{Binary, Uint128};
+use cw20::Cw20ReceiveMsg;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    /// The adapter must be allowed to mint the token
+    pub shard_token: String,
+    pub admin: String,
+    pub relayers: Vec<String>,
+    /// Attestations needed to mint an inbound transfer
+    pub threshold: u32,
+    pub inbound_limit: RateLimit,
+    pub outbound_limit: RateLimit,
+}
+
+/// At most `max_amount` tokens per window of `period` seconds
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RateLimit {
+    pub period: u64,
+    pub max_amount: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    Receive(Cw20ReceiveMsg),
+    /// Attest a transfer locked on `source_chain`. Relayers only, each
+    /// attesting a single recipient and amount per transfer. The tokens are
+    /// minted with the first recipient and amount to reach the threshold.
+    AttestInbound {
+        source_chain: String,
+        /// Unique per source chain, e.g. the hash or nonce of the lock
+        transfer_id: String,
+        recipient: String,
+        amount: Uint128,
+    },
+    /// Stop or resume both directions. Admin only.
+    SetPaused {
+        paused: bool,
+    },
+    /// Admin only
+    UpdateConfig {
+        admin: Option<String>,
+        relayers: Option<Vec<String>>,
+        threshold: Option<u32>,
+        inbound_limit: Option<RateLimit>,
+        outbound_limit: Option<RateLimit>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Cw20HookMsg {
+    /// Burn the sent tokens to be released to `destination_address` on
+    /// `destination_chain`. Relayers pick the transfer up from the events.
+    BridgeOut {
+        destination_chain: String,
+        destination_address: String,
+    },
+}
+
+/// We currently take no arguments for migrations
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MigrateMsg {}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    Config {},
+    InboundTransfer {
+        source_chain: String,
+        transfer_id: String,
+    },
+    /// Amounts bridged in the current rate limit windows
+    RateLimits {},
+}
+
+// We define a custom struct for each query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ConfigResponse {
+    pub shard_token: String,
+    pub admin: String,
+    pub relayers: Vec<String>,
+    pub threshold: u32,
+    pub inbound_limit: RateLimit,
+    pub outbound_limit: RateLimit,
+    pub paused: bool,
+}
+
+// We define a custom struct for each query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InboundTransferResponse {
+    /// Every recipient and amount attested so far
+    pub claims: Vec<InboundClaimResponse>,
+    pub minted: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InboundClaimResponse {
+    /// Sha256 of the canonical recipient followed by the big endian amount
+    pub hash: Binary,
+    pub recipient: String,
+    pub amount: Uint128,
+    pub attestations: Vec<String>,
+}
+
+// We define a custom struct for each query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RateLimitsResponse {
+    pub inbound: RateLimitUsage,
+    pub outbound: RateLimitUsage,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RateLimitUsage {
+    /// Block time in seconds the current window started at
+    pub window_start: u64,
+    pub used: Uint128,
+    pub remaining: Uint128,
+}
@@ -0,0 +1,112 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::Uint128;
+use cw20::Cw20ReceiveMsg;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    pub shard_token: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    Receive(Cw20ReceiveMsg),
+    /// Claim `amount` of a round for the signer. The leaf is the keccak256
+    /// hash of `"{index},{address},{amount}"`, pairs are hashed in ascending
+    /// order.
+    Claim {
+        round: u64,
+        index: u32,
+        amount: Uint128,
+        /// Hex encoded sibling hashes from the leaf up to the root
+        proof: Vec<String>,
+    },
+    /// Return what was not claimed of an expired round to its creator.
+    /// Creator only.
+    Clawback {
+        round: u64,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Cw20HookMsg {
+    /// Open a new round funded with the sent tokens, the cw20 sender being its
+    /// creator
+    CreateRound {
+        /// Hex encoded root of the claims tree
+        merkle_root: String,
+        /// Block time in seconds after which claims are closed and the creator
+        /// can claw back the rest. Never expires if unset.
+        expires_at: Option<u64>,
+    },
+}
+
+/// We currently take no arguments for migrations
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MigrateMsg {}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    Config {},
+    Round {
+        round: u64,
+    },
+    /// Rounds ordered by id
+    Rounds {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    IsClaimed {
+        round: u64,
+        index: u32,
+    },
+    /// Words of the claimed bitmap of a round, starting at `start_word`. Bit
+    /// `i` of word `w` is set once leaf `w * 128 + i` was claimed.
+    ClaimedBitmap {
+        round: u64,
+        start_word: u32,
+        limit: Option<u32>,
+    },
+}
+
+// We define a custom struct for each query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ConfigResponse {
+    pub shard_token: String,
+}
+
+// We define a custom struct for each query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RoundResponse {
+    pub round: u64,
+    pub creator: String,
+    pub merkle_root: String,
+    pub expires_at: Option<u64>,
+    /// Amount the round was funded with
+    pub total_amount: Uint128,
+    pub claimed_amount: Uint128,
+    pub clawed_back: bool,
+}
+
+// We define a custom struct for each query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct RoundsResponse {
+    pub rounds: Vec<RoundResponse>,
+}
+
+// We define a custom struct for each query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct IsClaimedResponse {
+    pub is_claimed: bool,
+}
+
+// We define a custom struct for each query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ClaimedBitmapResponse {
+    pub start_word: u32,
+    pub words: Vec<Uint128>,
+}
@@ -0,0 +1,126 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std_factory::{Binary, Coin, Uint128};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    /// Code id of the shard-token contract tokens are launched from
+    pub token_code_id: u64,
+    /// Native coins to attach to every CreateToken. Launches are free if unset.
+    pub creation_fee: Option<Coin>,
+    /// Receives the creation fees and can update the config. Defaults to the
+    /// instantiator.
+    pub admin: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    /// Launch a new token at the address predicted by `PredictAddress`. The
+    /// symbol must not have been launched before.
+    CreateToken {
+        name: String,
+        symbol: String,
+        decimals: u8,
+        initial_balances: Vec<InitialBalance>,
+        /// Cap on the total supply. Unbounded if unset.
+        max_supply: Option<Uint128>,
+        /// Account allowed to mint. Nobody can mint if unset.
+        minter: Option<String>,
+    },
+    /// Forward an owner-only message to a launched token. The factory owns the
+    /// tokens it launches, so this is how their creator manages them. Creator
+    /// of the token only.
+    ExecuteOnToken { symbol: String, msg: Binary },
+    /// Admin only
+    UpdateConfig {
+        token_code_id: u64,
+        creation_fee: Option<Coin>,
+        admin: String,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InitialBalance {
+    pub address: String,
+    pub amount: Uint128,
+}
+
+/// Subset of the shard-token instantiate message set by the factory, the
+/// remaining options keep their defaults
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TokenInstantiateMsg {
+    pub name: String,
+    pub symbol: String,
+    pub decimals: u8,
+    pub initial_balances: Vec<InitialBalance>,
+    pub max_supply: Option<Uint128>,
+    pub mint: Option<TokenMinterInfo>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TokenMinterInfo {
+    pub minter: String,
+}
+
+/// We currently take no arguments for migrations
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MigrateMsg {}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    Config {},
+    /// Launched token with this symbol
+    Token {
+        symbol: String,
+    },
+    /// Launched tokens ordered by symbol
+    ListTokens {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Tokens launched by `creator`, ordered by symbol
+    TokensByCreator {
+        creator: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Address a token with this symbol gets when launched with the current
+    /// token code
+    PredictAddress {
+        symbol: String,
+    },
+}
+
+// We define a custom struct for each query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ConfigResponse {
+    pub token_code_id: u64,
+    pub creation_fee: Option<Coin>,
+    pub admin: String,
+}
+
+// We define a custom struct for each query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TokenResponse {
+    pub symbol: String,
+    pub address: String,
+    pub creator: String,
+    pub code_id: u64,
+    /// Block time in seconds of the launch
+    pub created_at: u64,
+}
+
+// We define a custom struct for each query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TokensResponse {
+    pub tokens: Vec<TokenResponse>,
+}
+
+// We define a custom struct for each query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PredictAddressResponse {
+    pub address: String,
+}
@@ -0,0 +1,63 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::Uint128;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    pub shard_token: String,
+    pub admin: String,
+    /// Tokens dispensed per claim
+    pub amount: Uint128,
+    /// Seconds an address has to wait between claims
+    pub cooldown: u64,
+    /// Claims allowed across all addresses within one block
+    pub max_claims_per_block: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    /// Dispense `amount` tokens to the signer. The faucet is funded with plain
+    /// transfers to the contract.
+    Claim {},
+    /// Admin only
+    UpdateConfig {
+        admin: Option<String>,
+        amount: Option<Uint128>,
+        cooldown: Option<u64>,
+        max_claims_per_block: Option<u32>,
+    },
+}
+
+/// We currently take no arguments for migrations
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MigrateMsg {}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    Config {},
+    /// When `address` can claim next
+    NextClaimAt {
+        address: String,
+    },
+}
+
+// We define a custom struct for each query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ConfigResponse {
+    pub shard_token: String,
+    pub admin: String,
+    pub amount: Uint128,
+    pub cooldown: u64,
+    pub max_claims_per_block: u32,
+}
+
+// We define a custom struct for each query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct NextClaimAtResponse {
+    /// Block time in seconds, the current one if the address can claim now
+    pub next_claim_at: u64,
+    pub can_claim: bool,
+}
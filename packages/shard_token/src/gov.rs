@@ -0,0 +1,151 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::{CosmosMsg, Decimal, Uint128};
+use cw20::Cw20ReceiveMsg;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    pub shard_token: String,
+    pub quorum: Decimal,
+    pub threshold: Decimal,
+    pub voting_period: u64,
+    pub proposal_deposit: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    Receive(Cw20ReceiveMsg),
+    /// Vote with the signer's voting power at the proposal's snapshot. Votes
+    /// cannot be changed.
+    CastVote {
+        proposal_id: u64,
+        vote: VoteOption,
+    },
+    /// Run the messages of a passed proposal once voting ended. Anyone can
+    /// execute.
+    ExecuteProposal {
+        proposal_id: u64,
+    },
+    /// Settle a proposal that did not pass once voting ended. Anyone can close.
+    CloseProposal {
+        proposal_id: u64,
+    },
+    /// Only executable by the contract itself, through a proposal
+    UpdateConfig {
+        quorum: Decimal,
+        threshold: Decimal,
+        voting_period: u64,
+        proposal_deposit: Uint128,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Cw20HookMsg {
+    /// Open a proposal with the sent tokens as deposit, the cw20 sender being
+    /// the proposer. The deposit is returned once the proposal is settled if
+    /// it reached quorum, otherwise it stays with the contract.
+    CreateProposal {
+        title: String,
+        description: String,
+        msgs: Vec<CosmosMsg>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum VoteOption {
+    Yes,
+    No,
+    Abstain,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ProposalStatus {
+    /// Voting has not ended
+    Open,
+    /// Voting ended and the proposal can be executed
+    Passed,
+    /// Voting ended and the proposal can be closed
+    Rejected,
+    Executed,
+    Closed,
+}
+
+/// We currently take no arguments for migrations
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MigrateMsg {}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    Config {},
+    Proposal {
+        proposal_id: u64,
+    },
+    /// Proposals ordered by id
+    Proposals {
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Votes on a proposal ordered by voter
+    Votes {
+        proposal_id: u64,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+}
+
+// We define a custom struct for each query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ConfigResponse {
+    pub shard_token: String,
+    pub quorum: Decimal,
+    pub threshold: Decimal,
+    pub voting_period: u64,
+    pub proposal_deposit: Uint128,
+}
+
+// We define a custom struct for each query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ProposalResponse {
+    pub id: u64,
+    pub proposer: String,
+    pub title: String,
+    pub description: String,
+    pub msgs: Vec<CosmosMsg>,
+    pub deposit: Uint128,
+    /// Voting power is read at the end of this block
+    pub snapshot_height: u64,
+    /// Last block of the voting period
+    pub end_height: u64,
+    pub status: ProposalStatus,
+    pub yes_votes: Uint128,
+    pub no_votes: Uint128,
+    pub abstain_votes: Uint128,
+    /// Total supply at the snapshot, the base of the quorum
+    pub total_power: Uint128,
+}
+
+// We define a custom struct for each query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ProposalsResponse {
+    pub proposals: Vec<ProposalResponse>,
+}
+
+// We define a custom struct for each query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VoteResponse {
+    pub voter: String,
+    pub vote: VoteOption,
+    pub weight: Uint128,
+}
+
+// We define a custom struct for each query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct VotesResponse {
+    pub votes: Vec<VoteResponse>,
+}
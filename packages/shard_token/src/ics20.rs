@@ -0,0 +1,77 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::Uint128;
+use cw20::Cw20ReceiveMsg;
+
+/// Only ICS20 channels opened with this version are accepted
+pub const ICS20_VERSION: &str = "ics20-1";
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    pub shard_token: String,
+    /// Packet timeout in seconds when a transfer doesn't set one
+    pub default_timeout: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    /// Send tokens to another chain, with a `TransferMsg` as hook message
+    Receive(Cw20ReceiveMsg),
+}
+
+/// Hook message of the `Send` moving tokens over an ICS20 channel
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct TransferMsg {
+    /// Local channel to send the tokens over
+    pub channel: String,
+    /// Address on the remote chain to receive the tokens
+    pub remote_address: String,
+    /// Packet timeout in seconds, `default_timeout` if unset
+    pub timeout: Option<u64>,
+}
+
+/// We currently take no arguments for migrations
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MigrateMsg {}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    Config {},
+    ListChannels {},
+    Channel { id: String },
+}
+
+// We define a custom struct for each query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ConfigResponse {
+    pub shard_token: String,
+    pub default_timeout: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ChannelInfo {
+    pub id: String,
+    pub counterparty_port_id: String,
+    pub counterparty_channel_id: String,
+    pub connection_id: String,
+}
+
+// We define a custom struct for each query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ListChannelsResponse {
+    pub channels: Vec<ChannelInfo>,
+}
+
+// We define a custom struct for each query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ChannelResponse {
+    pub info: ChannelInfo,
+    /// Tokens escrowed here for transfers over the channel that were not
+    /// returned yet
+    pub outstanding: Uint128,
+    /// Tokens ever sent over the channel
+    pub total_sent: Uint128,
+}
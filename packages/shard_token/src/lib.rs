@@ -1,2 +1,26 @@
+#[cfg(feature = "contracts")]
 pub mod airdrop;
-pub mod staking;
\ No newline at end of file
+#[cfg(feature = "contracts")]
+pub mod bridge;
+#[cfg(feature = "contracts")]
+pub mod distributor;
+#[cfg(feature = "contracts")]
+pub mod escrow;
+#[cfg(feature = "factory")]
+pub mod factory;
+#[cfg(feature = "contracts")]
+pub mod faucet;
+#[cfg(feature = "contracts")]
+pub mod gov;
+#[cfg(feature = "contracts")]
+pub mod ics20;
+#[cfg(feature = "contracts")]
+pub mod lockdrop;
+#[cfg(feature = "contracts")]
+pub mod pair;
+#[cfg(feature = "contracts")]
+pub mod splitter;
+#[cfg(feature = "contracts")]
+pub mod staking;
+#[cfg(feature = "contracts")]
+pub mod vesting;
@@ -0,0 +1,102 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::{Decimal, Uint128};
+use cw20::Cw20ReceiveMsg;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    pub shard_token: String,
+    /// Tokens can be locked from `window_start` until before `window_end`, in
+    /// block time seconds
+    pub window_start: u64,
+    pub window_end: u64,
+    /// The lock durations users can choose from, each with the boost applied
+    /// to the locked amount when allocating rewards
+    pub lock_options: Vec<LockOption>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LockOption {
+    /// Seconds after the end of the window until the tokens unlock
+    pub duration: u64,
+    pub boost: Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    Receive(Cw20ReceiveMsg),
+    /// Withdraw the signer's position of `duration` once it unlocked
+    Unlock {
+        duration: u64,
+    },
+    /// Pay out the signer's reward allocation not yet claimed. Available once
+    /// the window ended.
+    ClaimRewards {},
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Cw20HookMsg {
+    /// Lock the sent tokens for `duration`, which must be one of the lock
+    /// options. Only during the window.
+    Lock { duration: u64 },
+    /// Add the sent tokens to the rewards shared among lockers
+    FundRewards {},
+}
+
+/// We currently take no arguments for migrations
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MigrateMsg {}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    Config {},
+    State {},
+    /// Positions and reward allocation of `address`
+    UserInfo {
+        address: String,
+    },
+}
+
+// We define a custom struct for each query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ConfigResponse {
+    pub shard_token: String,
+    pub window_start: u64,
+    pub window_end: u64,
+    pub lock_options: Vec<LockOption>,
+}
+
+// We define a custom struct for each query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct StateResponse {
+    pub total_locked: Uint128,
+    /// Sum of the locked amounts multiplied by their boost
+    pub total_weight: Uint128,
+    pub total_rewards: Uint128,
+}
+
+// We define a custom struct for each query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PositionResponse {
+    pub duration: u64,
+    pub amount: Uint128,
+    pub boost: Decimal,
+    pub unlock_time: u64,
+}
+
+// We define a custom struct for each query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct UserInfoResponse {
+    /// Positions not unlocked yet, ordered by duration
+    pub positions: Vec<PositionResponse>,
+    /// Weight of everything the user locked, unlocked positions included
+    pub weight: Uint128,
+    /// Share of the current rewards, final once the window ended and no more
+    /// rewards are funded
+    pub reward_allocation: Uint128,
+    pub rewards_claimed: Uint128,
+}
@@ -0,0 +1,81 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::Uint128;
+use cw20::Cw20ReceiveMsg;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    pub shard_token: String,
+    /// Native denom the token is paired with
+    pub native_denom: String,
+    /// Share of every swap's return left in the pool for liquidity providers,
+    /// in basis points
+    pub commission_bps: u16,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    /// Swap tokens for native coins
+    Receive(Cw20ReceiveMsg),
+    /// Deposit the attached native coins and `token_amount` tokens, pulled
+    /// with TransferFrom, for pool shares. Shares are issued for the tokens
+    /// actually received. Amounts beyond the pool ratio are donated to the pool.
+    ProvideLiquidity {
+        token_amount: Uint128,
+        /// Fail if fewer shares would be issued
+        min_shares: Option<Uint128>,
+    },
+    /// Burn `shares` of the signer for their part of both reserves
+    WithdrawLiquidity { shares: Uint128 },
+    /// Swap the attached native coins for tokens
+    Swap { min_return: Option<Uint128> },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Cw20HookMsg {
+    /// Swap the sent tokens for native coins, paid to the cw20 sender. Only
+    /// the tokens the pair's balance grew by beyond its reserve are swapped.
+    Swap { min_return: Option<Uint128> },
+}
+
+/// We currently take no arguments for migrations
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MigrateMsg {}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    Config {},
+    Pool {},
+    /// Pool shares of `address` and what they can currently be withdrawn for
+    Share {
+        address: String,
+    },
+}
+
+// We define a custom struct for each query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ConfigResponse {
+    pub shard_token: String,
+    pub native_denom: String,
+    pub commission_bps: u16,
+}
+
+// We define a custom struct for each query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PoolResponse {
+    pub token_reserve: Uint128,
+    pub native_reserve: Uint128,
+    pub total_shares: Uint128,
+}
+
+// We define a custom struct for each query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ShareResponse {
+    pub shares: Uint128,
+    pub token_amount: Uint128,
+    pub native_amount: Uint128,
+}
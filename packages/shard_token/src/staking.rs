@@ -15,10 +15,13 @@ pub struct InstantiateMsg {
 #[serde(rename_all = "snake_case")]
 pub enum ExecuteMsg {
     Receive(Cw20ReceiveMsg),
+    /// Also accepted as `unstake`
+    #[serde(alias = "unstake")]
     Unbond {
         amount: Uint128,
     },
-    /// Withdraw pending rewards
+    /// Withdraw pending rewards, also accepted as `claim`
+    #[serde(alias = "claim")]
     Withdraw {},
     /// Owner operation to stop distribution on current staking contract
     /// and send remaining tokens to the new contract
@@ -34,6 +37,9 @@ pub enum ExecuteMsg {
 #[serde(rename_all = "snake_case")]
 pub enum Cw20HookMsg {
     Bond {},
+    /// Gov operation adding a distribution of the sent SHARD tokens
+    /// between `start` and `end` to the schedule
+    FundRewards { start: u64, end: u64 },
 }
 
 /// migrate struct for distribution schedule
@@ -52,6 +58,13 @@ pub enum QueryMsg {
         staker: String,
         block_time: Option<u64>,
     },
+    Staked {
+        address: String,
+    },
+    /// Rewards of `address` claimable at the current block time
+    PendingRewards {
+        address: String,
+    },
 }
 
 // We define a custom struct for each query response
@@ -78,3 +91,15 @@ pub struct StakerInfoResponse {
     pub bond_amount: Uint128,
     pub pending_reward: Uint128,
 }
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct StakedResponse {
+    pub address: String,
+    pub staked: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingRewardsResponse {
+    pub address: String,
+    pub pending_rewards: Uint128,
+}
@@ -0,0 +1,113 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use cosmwasm_std::Uint128;
+use cw20::Cw20ReceiveMsg;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct InstantiateMsg {
+    pub shard_token: String,
+    /// Funds schedules and can revoke the revocable ones
+    pub admin: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ExecuteMsg {
+    Receive(Cw20ReceiveMsg),
+    /// Pay out everything vested and not yet claimed of the signer's schedules
+    Claim {},
+    /// End a revocable schedule. What vested so far stays claimable by the
+    /// beneficiary, the unvested rest is returned to the admin. Admin only.
+    Revoke {
+        id: u64,
+    },
+    /// Admin only
+    UpdateAdmin {
+        admin: String,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Cw20HookMsg {
+    /// Vest the sent tokens to `beneficiary`. The cw20 sender must be the admin.
+    CreateSchedule {
+        beneficiary: String,
+        schedule: Schedule,
+        revocable: bool,
+    },
+}
+
+/// How the total amount of a schedule vests over time, in block time seconds
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Schedule {
+    /// Everything vests at `time`
+    Cliff { time: u64 },
+    /// Vests linearly from `start_time` to `end_time`. Nothing vests before
+    /// `cliff_time` if set, what accrued until then vests at once.
+    Linear {
+        start_time: u64,
+        end_time: u64,
+        cliff_time: Option<u64>,
+    },
+    /// Vests linearly between the points, nothing before the first one. Times
+    /// must increase, amounts are cumulative and the last one is the total.
+    Custom { points: Vec<CurvePoint> },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CurvePoint {
+    pub time: u64,
+    /// Amount vested in total at `time`
+    pub amount: Uint128,
+}
+
+/// We currently take no arguments for migrations
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MigrateMsg {}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryMsg {
+    Config {},
+    Schedule {
+        id: u64,
+    },
+    /// Schedules of `beneficiary` ordered by id
+    Schedules {
+        beneficiary: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+}
+
+// We define a custom struct for each query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ConfigResponse {
+    pub shard_token: String,
+    pub admin: String,
+}
+
+// We define a custom struct for each query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ScheduleResponse {
+    pub id: u64,
+    pub beneficiary: String,
+    pub schedule: Schedule,
+    pub revocable: bool,
+    /// Amount funded, less what a revocation returned to the admin
+    pub total: Uint128,
+    /// Vested at the current block time
+    pub vested: Uint128,
+    pub claimed: Uint128,
+    /// Block time in seconds of the revocation, if revoked
+    pub revoked_at: Option<u64>,
+}
+
+// We define a custom struct for each query response
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SchedulesResponse {
+    pub schedules: Vec<ScheduleResponse>,
+}